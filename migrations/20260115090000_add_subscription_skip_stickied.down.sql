@@ -0,0 +1,2 @@
+-- Remove skip_stickied column from subscriptions table
+ALTER TABLE subscriptions DROP COLUMN skip_stickied;
@@ -0,0 +1,2 @@
+-- Per-subscription toggle for skipping stickied/pinned posts (on by default)
+ALTER TABLE subscriptions ADD COLUMN skip_stickied INTEGER NOT NULL DEFAULT 1;
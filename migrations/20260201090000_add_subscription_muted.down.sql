@@ -0,0 +1,2 @@
+-- Remove muted column from subscriptions table
+ALTER TABLE subscriptions DROP COLUMN muted;
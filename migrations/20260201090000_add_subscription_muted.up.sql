@@ -0,0 +1,2 @@
+-- Muted subscriptions keep their endpoint links but are not polled
+ALTER TABLE subscriptions ADD COLUMN muted INTEGER NOT NULL DEFAULT 0;
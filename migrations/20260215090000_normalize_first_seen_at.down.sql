@@ -0,0 +1,3 @@
+UPDATE notified_posts
+SET first_seen_at = datetime(first_seen_at)
+WHERE first_seen_at LIKE '%Z';
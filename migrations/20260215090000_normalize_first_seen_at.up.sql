@@ -0,0 +1,5 @@
+-- Store first_seen_at as ISO-8601 UTC with a Z suffix (e.g. 2026-02-15T09:00:00Z)
+-- instead of SQLite's space-separated CURRENT_TIMESTAMP format
+UPDATE notified_posts
+SET first_seen_at = strftime('%Y-%m-%dT%H:%M:%SZ', first_seen_at)
+WHERE first_seen_at IS NOT NULL AND first_seen_at NOT LIKE '%Z';
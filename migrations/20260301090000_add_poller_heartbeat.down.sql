@@ -0,0 +1 @@
+DROP TABLE poller_heartbeat;
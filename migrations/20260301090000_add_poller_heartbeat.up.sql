@@ -0,0 +1,6 @@
+-- Single-row heartbeat written by the poller after each successful cycle,
+-- so the TUI can tell whether a (possibly external) daemon is healthy
+CREATE TABLE poller_heartbeat (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_poll_at TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- Remove priority column from subscriptions table
+ALTER TABLE subscriptions DROP COLUMN priority;
@@ -0,0 +1,2 @@
+-- Priority subscriptions ping the endpoint's configured mention
+ALTER TABLE subscriptions ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Global key/value settings shared by the daemon, TUI and CLI
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
@@ -0,0 +1,12 @@
+-- One row per notification send attempt (after retries), for delivery reporting
+CREATE TABLE deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    delivered_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    endpoint_id INTEGER NOT NULL REFERENCES endpoints(id) ON DELETE CASCADE,
+    post_id TEXT NOT NULL,
+    status TEXT NOT NULL CHECK(status IN ('sent', 'failed')),
+    latency_ms INTEGER NOT NULL,
+    error TEXT
+);
+
+CREATE INDEX idx_deliveries_delivered_at ON deliveries(delivered_at);
@@ -0,0 +1,2 @@
+ALTER TABLE notified_posts DROP COLUMN url;
+ALTER TABLE notified_posts DROP COLUMN title;
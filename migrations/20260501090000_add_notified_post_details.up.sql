@@ -0,0 +1,3 @@
+-- Title and link of each notified post, so recent notifications can be re-sent
+ALTER TABLE notified_posts ADD COLUMN title TEXT;
+ALTER TABLE notified_posts ADD COLUMN url TEXT;
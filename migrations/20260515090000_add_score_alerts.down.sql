@@ -0,0 +1,2 @@
+DROP TABLE post_state;
+ALTER TABLE subscriptions DROP COLUMN score_thresholds;
@@ -0,0 +1,11 @@
+-- Opt-in score alerts: comma-separated score thresholds (NULL = first-seen notifications only)
+ALTER TABLE subscriptions ADD COLUMN score_thresholds TEXT;
+
+-- Highest score bucket reached by each tracked post, so a threshold alerts once
+CREATE TABLE post_state (
+    subreddit TEXT NOT NULL,
+    post_id TEXT NOT NULL,
+    score_bucket INTEGER NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (subreddit, post_id)
+);
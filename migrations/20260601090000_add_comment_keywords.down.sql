@@ -0,0 +1,2 @@
+DROP TABLE notified_comments;
+ALTER TABLE subscriptions DROP COLUMN comment_keywords;
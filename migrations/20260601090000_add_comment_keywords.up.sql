@@ -0,0 +1,11 @@
+-- Opt-in comment polling: comma-separated keywords (NULL = posts only)
+ALTER TABLE subscriptions ADD COLUMN comment_keywords TEXT;
+
+-- Comments already notified about, so each match is sent once
+CREATE TABLE notified_comments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    subreddit TEXT NOT NULL,
+    comment_id TEXT NOT NULL,
+    first_seen_at TEXT NOT NULL,
+    UNIQUE (subreddit, comment_id)
+);
@@ -0,0 +1,10 @@
+DROP TRIGGER bump_config_version_subscriptions_insert;
+DROP TRIGGER bump_config_version_subscriptions_update;
+DROP TRIGGER bump_config_version_subscriptions_delete;
+DROP TRIGGER bump_config_version_endpoints_insert;
+DROP TRIGGER bump_config_version_endpoints_update;
+DROP TRIGGER bump_config_version_endpoints_delete;
+DROP TRIGGER bump_config_version_links_insert;
+DROP TRIGGER bump_config_version_links_update;
+DROP TRIGGER bump_config_version_links_delete;
+DELETE FROM settings WHERE key = 'config_version';
@@ -0,0 +1,48 @@
+-- Version of the subscription/endpoint configuration, bumped by every change
+-- (TUI, CLI or hand-written SQL) so the poller can cache what it derives from it
+INSERT OR IGNORE INTO settings (key, value) VALUES ('config_version', '0');
+
+CREATE TRIGGER bump_config_version_subscriptions_insert AFTER INSERT ON subscriptions
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_subscriptions_update AFTER UPDATE ON subscriptions
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_subscriptions_delete AFTER DELETE ON subscriptions
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_endpoints_insert AFTER INSERT ON endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_endpoints_update AFTER UPDATE ON endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_endpoints_delete AFTER DELETE ON endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_links_insert AFTER INSERT ON subscription_endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_links_update AFTER UPDATE ON subscription_endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_links_delete AFTER DELETE ON subscription_endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
@@ -0,0 +1,6 @@
+-- Runtime facts written by the daemon for monitoring (last poll, last success);
+-- unlike `settings`, nothing here is configuration
+CREATE TABLE meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
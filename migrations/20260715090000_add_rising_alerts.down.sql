@@ -0,0 +1,2 @@
+DROP TABLE post_metrics;
+ALTER TABLE subscriptions DROP COLUMN rising_threshold;
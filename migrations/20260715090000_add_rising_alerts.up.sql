@@ -0,0 +1,13 @@
+-- Opt-in rising alerts: score growth in points per hour (NULL = off)
+ALTER TABLE subscriptions ADD COLUMN rising_threshold INTEGER;
+
+-- Latest score/comment sample of each young tracked post, for velocity between cycles
+CREATE TABLE post_metrics (
+    subreddit TEXT NOT NULL,
+    post_id TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    num_comments INTEGER NOT NULL,
+    sampled_at TEXT NOT NULL,
+    alerted INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (subreddit, post_id)
+);
@@ -0,0 +1 @@
+ALTER TABLE subscriptions DROP COLUMN comment_keyword_mode;
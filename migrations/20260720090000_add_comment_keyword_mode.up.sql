@@ -0,0 +1,3 @@
+-- How comment_keywords are matched: whole words (the previous behaviour), anywhere, or as one regex
+ALTER TABLE subscriptions ADD COLUMN comment_keyword_mode TEXT NOT NULL DEFAULT 'word'
+    CHECK (comment_keyword_mode IN ('substring', 'word', 'regex'));
@@ -0,0 +1,47 @@
+-- Rebuild endpoints without 'teams' (SQLite can't change a CHECK
+-- constraint in place); Teams endpoints and their links are deleted.
+-- Other links and deliveries are set aside and put back.
+CREATE TEMP TABLE saved_links AS
+SELECT * FROM subscription_endpoints
+WHERE endpoint_id IN (SELECT id FROM endpoints WHERE kind != 'teams');
+CREATE TEMP TABLE saved_deliveries AS
+SELECT * FROM deliveries
+WHERE endpoint_id IN (SELECT id FROM endpoints WHERE kind != 'teams');
+
+CREATE TABLE endpoints_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL CHECK(kind IN ('discord','pushover','mattermost')),
+    config_json TEXT NOT NULL,
+    active INTEGER NOT NULL DEFAULT 1,
+    note TEXT
+);
+INSERT INTO endpoints_new (id, kind, config_json, active, note)
+SELECT id, kind, config_json, active, note FROM endpoints WHERE kind != 'teams';
+-- Keep IDs of deleted endpoints from being handed out again
+UPDATE sqlite_sequence
+SET seq = (SELECT seq FROM sqlite_sequence WHERE name = 'endpoints')
+WHERE name = 'endpoints_new' AND EXISTS (SELECT 1 FROM sqlite_sequence WHERE name = 'endpoints');
+
+DROP TABLE endpoints;
+ALTER TABLE endpoints_new RENAME TO endpoints;
+
+INSERT INTO subscription_endpoints SELECT * FROM saved_links;
+INSERT INTO deliveries SELECT * FROM saved_deliveries;
+DROP TABLE saved_links;
+DROP TABLE saved_deliveries;
+
+-- Dropped with the old table
+CREATE TRIGGER bump_config_version_endpoints_insert AFTER INSERT ON endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_endpoints_update AFTER UPDATE ON endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
+
+CREATE TRIGGER bump_config_version_endpoints_delete AFTER DELETE ON endpoints
+BEGIN
+    UPDATE settings SET value = CAST(value AS INTEGER) + 1 WHERE key = 'config_version';
+END;
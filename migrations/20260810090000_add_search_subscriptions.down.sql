@@ -0,0 +1,2 @@
+ALTER TABLE subscriptions DROP COLUMN search_restrict_sr;
+ALTER TABLE subscriptions DROP COLUMN search_query;
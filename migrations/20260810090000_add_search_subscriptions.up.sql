@@ -0,0 +1,4 @@
+-- Search subscriptions poll /search.json?q=... instead of the subreddit's new posts (NULL = normal subscription).
+-- search_restrict_sr limits the search to the subscription's subreddit; searches across all of Reddit store 'all'.
+ALTER TABLE subscriptions ADD COLUMN search_query TEXT;
+ALTER TABLE subscriptions ADD COLUMN search_restrict_sr INTEGER NOT NULL DEFAULT 1;
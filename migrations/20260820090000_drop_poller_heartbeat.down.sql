@@ -0,0 +1,6 @@
+CREATE TABLE poller_heartbeat (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_poll_at TEXT NOT NULL
+);
+INSERT INTO poller_heartbeat (id, last_poll_at)
+SELECT 1, value FROM meta WHERE key = 'last_poll_at';
@@ -0,0 +1,3 @@
+-- The poller's last cycle is recorded in meta (last_poll_at), which the
+-- TUI, `status` and the healthcheck all read; the old heartbeat row is unused
+DROP TABLE poller_heartbeat;
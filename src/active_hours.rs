@@ -0,0 +1,163 @@
+//! Per-endpoint active hours
+//!
+//! An endpoint's config can hold `"active_hours": "09:00-17:00"` and a
+//! `"timezone"` (IANA name, default UTC). Outside that window the poller
+//! doesn't send to the endpoint - the post still reaches the subscription's
+//! other endpoints right away, but isn't queued for this one.
+
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+/// A daily time window, `start` inclusive and `end` exclusive
+///
+/// Windows with `end` before `start` run over midnight (e.g. `22:00-06:00`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HoursWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl HoursWindow {
+    /// Parse `HH:MM-HH:MM`
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid active hours '{}' (expected HH:MM-HH:MM, e.g. 09:00-17:00)", input.trim());
+        let (start, end) = input.trim().split_once('-').ok_or_else(invalid)?;
+        let time = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").map_err(|_| invalid());
+        let window = Self { start: time(start)?, end: time(end)? };
+        if window.start == window.end {
+            return Err(format!("active hours '{}' start and end at the same time", input.trim()));
+        }
+        Ok(window)
+    }
+}
+
+/// Whether `time` falls inside `window`
+pub fn is_within_hours(window: &HoursWindow, time: NaiveTime) -> bool {
+    if window.start < window.end {
+        window.start <= time && time < window.end
+    } else {
+        time >= window.start || time < window.end
+    }
+}
+
+/// When an endpoint receives notifications
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActiveHours {
+    pub window: HoursWindow,
+    pub timezone: Tz,
+}
+
+impl ActiveHours {
+    /// Whether `now` is inside the window, on the endpoint's local clock
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        is_within_hours(&self.window, now.with_timezone(&self.timezone).time())
+    }
+}
+
+/// The schedule keys of an endpoint config (every kind accepts them)
+#[derive(Deserialize)]
+struct ScheduleFields {
+    #[serde(default)]
+    active_hours: Option<String>,
+    #[serde(default)]
+    timezone: Option<String>,
+}
+
+/// Parse an IANA timezone name such as `Europe/Berlin`
+pub fn parse_timezone(input: &str) -> Result<Tz, String> {
+    input
+        .trim()
+        .parse::<Tz>()
+        .map_err(|_| format!("unknown timezone '{}' (expected an IANA name, e.g. America/New_York)", input.trim()))
+}
+
+/// The active hours in an endpoint's config_json, `None` if it has none (always active)
+///
+/// A timezone without active hours is rejected, since it would have no effect.
+pub fn endpoint_active_hours(config_json: &str) -> Result<Option<ActiveHours>, String> {
+    let fields: ScheduleFields = serde_json::from_str(config_json).map_err(|e| e.to_string())?;
+    let timezone = match fields.timezone.as_deref().filter(|tz| !tz.trim().is_empty()) {
+        Some(tz) => Some(parse_timezone(tz)?),
+        None => None,
+    };
+    match fields.active_hours.as_deref().filter(|h| !h.trim().is_empty()) {
+        Some(hours) => Ok(Some(ActiveHours {
+            window: HoursWindow::parse(hours)?,
+            timezone: timezone.unwrap_or(Tz::UTC),
+        })),
+        None if timezone.is_some() => Err("timezone is set without active_hours".to_string()),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_hours_window() {
+        let window = HoursWindow::parse(" 09:00 - 17:30 ").unwrap();
+        assert_eq!(window.start, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(window.end, NaiveTime::from_hms_opt(17, 30, 0).unwrap());
+        assert!(HoursWindow::parse("9-5").is_err());
+        assert!(HoursWindow::parse("09:00").is_err());
+        assert!(HoursWindow::parse("25:00-26:00").is_err());
+        assert!(HoursWindow::parse("09:00-09:00").is_err());
+    }
+
+    #[test]
+    fn test_is_within_hours_including_overnight() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let day = HoursWindow::parse("09:00-17:00").unwrap();
+        assert!(is_within_hours(&day, time(9, 0)));
+        assert!(is_within_hours(&day, time(16, 59)));
+        assert!(!is_within_hours(&day, time(17, 0)));
+        assert!(!is_within_hours(&day, time(8, 59)));
+
+        let night = HoursWindow::parse("22:00-06:00").unwrap();
+        assert!(is_within_hours(&night, time(23, 0)));
+        assert!(is_within_hours(&night, time(5, 59)));
+        assert!(!is_within_hours(&night, time(12, 0)));
+    }
+
+    #[test]
+    fn test_active_hours_follow_endpoint_timezone() {
+        let config = |tz: &str| format!(r#"{{"webhook_url":"https://x","active_hours":"09:00-17:00","timezone":"{}"}}"#, tz);
+        let new_york = endpoint_active_hours(&config("America/New_York")).unwrap().unwrap();
+        let berlin = endpoint_active_hours(&config("Europe/Berlin")).unwrap().unwrap();
+
+        // 14:00 UTC is 10:00 in New York and 16:00 in Berlin (summer time)
+        let afternoon = at("2026-07-01T14:00:00Z");
+        assert!(new_york.is_active(afternoon));
+        assert!(berlin.is_active(afternoon));
+
+        // 18:00 UTC is 14:00 in New York but 20:00 in Berlin
+        let evening = at("2026-07-01T18:00:00Z");
+        assert!(new_york.is_active(evening));
+        assert!(!berlin.is_active(evening));
+
+        // 08:00 UTC is 04:00 in New York but 10:00 in Berlin
+        let morning = at("2026-07-01T08:00:00Z");
+        assert!(!new_york.is_active(morning));
+        assert!(berlin.is_active(morning));
+    }
+
+    #[test]
+    fn test_endpoint_active_hours_from_config() {
+        assert_eq!(endpoint_active_hours(r#"{"webhook_url":"https://x"}"#).unwrap(), None);
+        let utc = endpoint_active_hours(r#"{"token":"t","user":"u","active_hours":"09:00-17:00"}"#).unwrap().unwrap();
+        assert_eq!(utc.timezone, Tz::UTC);
+        assert!(utc.is_active(at("2026-07-01T09:30:00Z")));
+
+        assert!(endpoint_active_hours(r#"{"active_hours":"09:00-17:00","timezone":"Mars/Olympus"}"#)
+            .unwrap_err()
+            .contains("Mars/Olympus"));
+        assert!(endpoint_active_hours(r#"{"timezone":"UTC"}"#).unwrap_err().contains("without active_hours"));
+        assert!(endpoint_active_hours(r#"{"active_hours":"whenever"}"#).is_err());
+    }
+}
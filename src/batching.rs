@@ -0,0 +1,429 @@
+//! Time-based notification batching
+//!
+//! With `NOTIFY_BATCH_WINDOW_SECS` set, each endpoint's notifications are
+//! collected instead of sent one by one. The window opens with the first
+//! post and, once it has passed, everything collected goes out as a single
+//! message, or several if it's too long for the endpoint. A batch reaching
+//! `NOTIFY_BATCH_MAX_POSTS` is sent straight away.
+//! The window doesn't depend on the poll cadence, so one batch can span many
+//! poll cycles.
+//!
+//! The poller only adds to the buffer; a background flusher task sends the
+//! batches that fall due. Priority posts skip the buffer, so their mention
+//! isn't delayed.
+
+use reqwest::Client;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::{debug, error, info, warn};
+
+use crate::models::database::{DeliveryStatus, EndpointRow};
+use crate::notifiers::{build_notifier, marked_title, send_raw_with_retry, Message, NotifierClientProfile, SendContext};
+use crate::services::DatabaseService;
+
+/// Default for `NOTIFY_BATCH_MAX_POSTS`
+pub const DEFAULT_BATCH_MAX_POSTS: u32 = 20;
+
+/// How long the flusher sleeps when no batch is open (it is woken by new posts anyway)
+const IDLE_FLUSH_CHECK: Duration = Duration::from_secs(3600);
+
+/// A notification waiting in a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchedPost {
+    /// The post (or comment) ID deliveries are recorded against
+    pub item_id: String,
+    pub subreddit: String,
+    pub title: String,
+    pub url: String,
+    pub nsfw: bool,
+}
+
+impl BatchedPost {
+    pub fn from_context(item_id: &str, ctx: &SendContext<'_>) -> Self {
+        Self {
+            item_id: item_id.to_string(),
+            subreddit: ctx.subreddit.to_string(),
+            title: ctx.title.to_string(),
+            url: ctx.url.to_string(),
+            nsfw: ctx.nsfw,
+        }
+    }
+}
+
+/// The posts collected for one endpoint, ready to send
+#[derive(Debug, Clone)]
+pub struct Batch {
+    pub endpoint: EndpointRow,
+    pub posts: Vec<BatchedPost>,
+}
+
+#[derive(Debug)]
+struct OpenBatch {
+    endpoint: EndpointRow,
+    opened_at: Instant,
+    posts: Vec<BatchedPost>,
+}
+
+/// Open batches keyed by endpoint ID
+///
+/// Takes the current time as an argument so the flush rules can be tested
+/// without waiting.
+#[derive(Debug)]
+pub struct BatchBuffer {
+    window: Duration,
+    max_posts: usize,
+    open: HashMap<i64, OpenBatch>,
+}
+
+impl BatchBuffer {
+    pub fn new(window: Duration, max_posts: u32) -> Self {
+        Self {
+            window,
+            max_posts: max_posts.max(1) as usize,
+            open: HashMap::new(),
+        }
+    }
+
+    /// Add a post to the endpoint's batch, opening one at `now` if needed
+    ///
+    /// Returns whether the batch is now full and should be sent without
+    /// waiting for the window.
+    pub fn push(&mut self, endpoint: &EndpointRow, post: BatchedPost, now: Instant) -> bool {
+        let batch = self.open.entry(endpoint.id).or_insert_with(|| OpenBatch {
+            endpoint: endpoint.clone(),
+            opened_at: now,
+            posts: Vec::new(),
+        });
+        // The latest config wins, e.g. after the endpoint was edited mid-window
+        batch.endpoint = endpoint.clone();
+        batch.posts.push(post);
+        batch.posts.len() >= self.max_posts
+    }
+
+    /// Remove and return the batches that are full or whose window has passed
+    pub fn take_due(&mut self, now: Instant) -> Vec<Batch> {
+        let due: Vec<i64> = self
+            .open
+            .iter()
+            .filter(|(_, b)| b.posts.len() >= self.max_posts || now.duration_since(b.opened_at) >= self.window)
+            .map(|(id, _)| *id)
+            .collect();
+        self.take(due)
+    }
+
+    /// Remove and return every open batch (e.g. on shutdown)
+    pub fn take_all(&mut self) -> Vec<Batch> {
+        let all: Vec<i64> = self.open.keys().copied().collect();
+        self.take(all)
+    }
+
+    fn take(&mut self, mut ids: Vec<i64>) -> Vec<Batch> {
+        ids.sort_unstable();
+        ids.into_iter()
+            .filter_map(|id| self.open.remove(&id))
+            .map(|b| Batch { endpoint: b.endpoint, posts: b.posts })
+            .collect()
+    }
+
+    /// When the earliest open batch's window ends
+    pub fn next_due(&self) -> Option<Instant> {
+        self.open.values().map(|b| b.opened_at + self.window).min()
+    }
+
+    /// Posts waiting across all batches
+    pub fn pending_posts(&self) -> usize {
+        self.open.values().map(|b| b.posts.len()).sum()
+    }
+}
+
+/// The batch buffer shared by the poller and the flusher task
+#[derive(Clone)]
+pub struct Batcher {
+    buffer: Arc<Mutex<BatchBuffer>>,
+    wake: Arc<Notify>,
+}
+
+impl fmt::Debug for Batcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Batcher").field("buffer", &self.buffer).finish()
+    }
+}
+
+impl Batcher {
+    pub fn new(window: Duration, max_posts: u32) -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(BatchBuffer::new(window, max_posts))),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Queue a notification for `endpoint`, waking the flusher if its batch is full
+    pub fn add(&self, endpoint: &EndpointRow, post: BatchedPost) {
+        let full = self.buffer.lock().unwrap().push(endpoint, post, Instant::now());
+        // Also wakes the flusher for a newly opened batch, so it knows the new deadline
+        self.wake.notify_one();
+        if full {
+            debug!("Batch for endpoint id {} is full, sending it now", endpoint.id);
+        }
+    }
+
+    /// Send batches as they fall due; runs until the task is dropped
+    pub async fn run_flusher<D: DatabaseService>(self, db: Arc<D>, client: Client, profile: NotifierClientProfile) {
+        loop {
+            let next_due = self.buffer.lock().unwrap().next_due();
+            let deadline = next_due.unwrap_or_else(|| Instant::now() + IDLE_FLUSH_CHECK);
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline.into()) => {}
+                _ = self.wake.notified() => {}
+            }
+            let due = self.buffer.lock().unwrap().take_due(Instant::now());
+            for batch in due {
+                flush_batch(db.as_ref(), &client, &profile, &batch).await;
+            }
+        }
+    }
+
+    /// Send every open batch now, whether or not its window has passed
+    ///
+    /// Returns the number of posts whose delivery failed.
+    pub async fn flush_all<D: DatabaseService>(&self, db: &D, client: &Client, profile: &NotifierClientProfile) -> usize {
+        let batches = self.buffer.lock().unwrap().take_all();
+        if !batches.is_empty() {
+            info!("Sending {} pending notification batch(es) before exiting", batches.len());
+        }
+        let mut failed = 0;
+        for batch in batches {
+            failed += flush_batch(db, client, profile, &batch).await;
+        }
+        failed
+    }
+
+    /// Posts waiting across all batches
+    pub fn pending_posts(&self) -> usize {
+        self.buffer.lock().unwrap().pending_posts()
+    }
+}
+
+/// The text of a batched notification
+pub fn format_batch(posts: &[BatchedPost], include_nsfw_marker: bool) -> String {
+    let mut text = batch_header(posts.len());
+    for post in posts {
+        text.push_str(&batch_entry(post, include_nsfw_marker));
+    }
+    text
+}
+
+fn batch_header(posts: usize) -> String {
+    format!("📬 {} new Reddit post(s)", posts)
+}
+
+fn batch_entry(post: &BatchedPost, include_nsfw_marker: bool) -> String {
+    let title = Message::new(&post.subreddit, &marked_title(&post.title, post.nsfw, include_nsfw_marker), &post.url).body;
+    format!("\n\nr/{}: {}\n{}", post.subreddit, title, post.url)
+}
+
+/// Split `posts` into runs whose `format_batch` text fits in `max_chars`
+///
+/// Keeps the posts in order. A post too long for a message on its own still
+/// gets one, shortened by the notifier like any other message.
+pub fn split_batch(posts: &[BatchedPost], include_nsfw_marker: bool, max_chars: usize) -> Vec<&[BatchedPost]> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut entries_len = 0;
+    for (i, post) in posts.iter().enumerate() {
+        let entry_len = batch_entry(post, include_nsfw_marker).chars().count();
+        let header_len = batch_header(i + 1 - start).chars().count();
+        if i > start && header_len + entries_len + entry_len > max_chars {
+            parts.push(&posts[start..i]);
+            start = i;
+            entries_len = 0;
+        }
+        entries_len += entry_len;
+    }
+    if start < posts.len() {
+        parts.push(&posts[start..]);
+    }
+    parts
+}
+
+/// Send one batch and record a delivery for each post in it
+///
+/// The posts go out in as few messages as fit the endpoint's message limit,
+/// and each post's delivery is recorded with the outcome of the message it
+/// was in. Returns the number of posts whose delivery failed.
+pub async fn flush_batch<D: DatabaseService>(db: &D, client: &Client, profile: &NotifierClientProfile, batch: &Batch) -> usize {
+    let ep = &batch.endpoint;
+    let notifier = match build_notifier(ep, client.clone(), profile.footers) {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            error!(endpoint_id = ep.id, error = %e, "Build notifier failed for endpoint id {}: {}", ep.id, e);
+            let error = format!("Failed to build notifier: {:#}", e);
+            record_deliveries(db, ep, &batch.posts, DeliveryStatus::Failed, 0, Some(&error)).await;
+            return batch.posts.len();
+        }
+    };
+
+    let mut failed = 0;
+    for posts in split_batch(&batch.posts, notifier.include_nsfw_marker(), ep.kind.max_message_len()) {
+        let started = Instant::now();
+        let text = format_batch(posts, notifier.include_nsfw_marker());
+        let result = send_raw_with_retry(notifier.as_ref(), &text, profile.max_attempts, profile.base_backoff).await;
+        let latency_ms = started.elapsed().as_millis() as i64;
+        match result {
+            Ok(()) => {
+                info!(endpoint_id = ep.id, posts = posts.len(), "Sent a batch of {} post(s) to endpoint id {}", posts.len(), ep.id);
+                record_deliveries(db, ep, posts, DeliveryStatus::Sent, latency_ms, None).await;
+            }
+            Err(e) => {
+                error!(
+                    endpoint_id = ep.id,
+                    kind = notifier.kind(),
+                    posts = posts.len(),
+                    error = %e,
+                    "Notify error ({} id={}): batch of {} post(s): {}",
+                    notifier.kind(), ep.id, posts.len(), e
+                );
+                record_deliveries(db, ep, posts, DeliveryStatus::Failed, latency_ms, Some(&e.to_string())).await;
+                failed += posts.len();
+            }
+        }
+    }
+    failed
+}
+
+async fn record_deliveries<D: DatabaseService>(
+    db: &D,
+    ep: &EndpointRow,
+    posts: &[BatchedPost],
+    status: DeliveryStatus,
+    latency_ms: i64,
+    error: Option<&str>,
+) {
+    for post in posts {
+        if let Err(e) = db.record_delivery(ep.id, &post.item_id, status, latency_ms, error).await {
+            warn!(endpoint_id = ep.id, item_id = %post.item_id, error = %e, "Failed to record delivery for endpoint id {}: {}", ep.id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::database::EndpointKind;
+
+    fn endpoint(id: i64) -> EndpointRow {
+        EndpointRow {
+            id,
+            kind: EndpointKind::Discord,
+            config_json: r#"{"webhook_url":"https://example.com/hook"}"#.to_string(),
+            active: true,
+            note: None,
+        }
+    }
+
+    fn post(id: &str) -> BatchedPost {
+        BatchedPost {
+            item_id: id.to_string(),
+            subreddit: "rust".to_string(),
+            title: format!("Post {}", id),
+            url: format!("https://redd.it/{}", id),
+            nsfw: false,
+        }
+    }
+
+    fn ids(batch: &Batch) -> Vec<&str> {
+        batch.posts.iter().map(|p| p.item_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_posts_accumulate_until_the_window_passes() {
+        let mut buffer = BatchBuffer::new(Duration::from_secs(600), 20);
+        let start = Instant::now();
+
+        // Posts from several poll cycles land in one batch per endpoint
+        assert!(!buffer.push(&endpoint(1), post("a"), start));
+        assert!(!buffer.push(&endpoint(2), post("b"), start + Duration::from_secs(60)));
+        assert!(!buffer.push(&endpoint(1), post("c"), start + Duration::from_secs(300)));
+        assert_eq!(buffer.pending_posts(), 3);
+        assert_eq!(buffer.next_due(), Some(start + Duration::from_secs(600)));
+
+        assert!(buffer.take_due(start + Duration::from_secs(599)).is_empty());
+
+        // Each endpoint's window runs from its first post
+        let due = buffer.take_due(start + Duration::from_secs(600));
+        assert_eq!(due.len(), 1);
+        assert_eq!((due[0].endpoint.id, ids(&due[0])), (1, vec!["a", "c"]));
+        assert_eq!(buffer.next_due(), Some(start + Duration::from_secs(660)));
+
+        let due = buffer.take_due(start + Duration::from_secs(660));
+        assert_eq!((due[0].endpoint.id, ids(&due[0])), (2, vec!["b"]));
+        assert_eq!(buffer.pending_posts(), 0);
+        assert_eq!(buffer.next_due(), None);
+
+        // The next post opens a fresh window
+        buffer.push(&endpoint(1), post("d"), start + Duration::from_secs(900));
+        assert!(buffer.take_due(start + Duration::from_secs(1200)).is_empty());
+        assert_eq!(ids(&buffer.take_due(start + Duration::from_secs(1500))[0]), vec!["d"]);
+    }
+
+    #[test]
+    fn test_full_batch_is_due_before_the_window() {
+        let mut buffer = BatchBuffer::new(Duration::from_secs(600), 3);
+        let start = Instant::now();
+
+        assert!(!buffer.push(&endpoint(1), post("a"), start));
+        assert!(!buffer.push(&endpoint(1), post("b"), start));
+        assert!(buffer.push(&endpoint(1), post("c"), start + Duration::from_secs(1)));
+        buffer.push(&endpoint(2), post("x"), start);
+
+        let due = buffer.take_due(start + Duration::from_secs(2));
+        assert_eq!(due.len(), 1);
+        assert_eq!(ids(&due[0]), vec!["a", "b", "c"]);
+
+        // Shutdown sends what's left regardless of the window
+        let rest = buffer.take_all();
+        assert_eq!((rest[0].endpoint.id, ids(&rest[0])), (2, vec!["x"]));
+        assert_eq!(buffer.pending_posts(), 0);
+    }
+
+    #[test]
+    fn test_format_batch_lists_every_post() {
+        let nsfw = BatchedPost { nsfw: true, title: "Tom &amp; Jerry".to_string(), ..post("b") };
+        let text = format_batch(&[post("a"), nsfw], true);
+        assert_eq!(
+            text,
+            "📬 2 new Reddit post(s)\n\nr/rust: Post a\nhttps://redd.it/a\n\nr/rust: 🔞 NSFW Tom & Jerry\nhttps://redd.it/b"
+        );
+        assert!(!format_batch(&[post("a")], false).contains("NSFW"));
+    }
+
+    #[test]
+    fn test_split_batch_fits_each_message() {
+        // Reddit allows titles up to 300 characters
+        let posts: Vec<BatchedPost> = (0..20)
+            .map(|i| BatchedPost { title: format!("{} {}", i, "x".repeat(296)), ..post(&i.to_string()) })
+            .collect();
+        let cap = EndpointKind::Discord.max_message_len();
+        assert!(format_batch(&posts, true).chars().count() > cap);
+
+        let parts = split_batch(&posts, true, cap);
+        assert!(parts.len() > 1);
+        for part in &parts {
+            assert!(format_batch(part, true).chars().count() <= cap);
+        }
+        // Every post is in exactly one message, in order
+        let sent: Vec<&str> = parts.iter().flat_map(|part| part.iter().map(|p| p.item_id.as_str())).collect();
+        let expected: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        assert_eq!(sent, expected);
+
+        // Short posts still share one message; an oversized one gets its own
+        assert_eq!(split_batch(&[post("a"), post("b")], true, cap).len(), 1);
+        let huge = BatchedPost { title: "y".repeat(cap), ..post("h") };
+        let posts = [post("a"), huge, post("b")];
+        let parts = split_batch(&posts, true, cap);
+        assert_eq!(parts.iter().map(|p| p.len()).collect::<Vec<_>>(), vec![1, 1, 1]);
+    }
+}
@@ -50,8 +50,28 @@ async fn check_database(db_path: &str) -> Result<(), String> {
             .fetch_one(&mut conn)
             .await;
 
-    match count {
-        Ok(_) => Ok(()),
-        Err(e) => Err(format!("Failed to query database: {}", e)),
+    if let Err(e) = count {
+        return Err(format!("Failed to query database: {}", e));
     }
+
+    // Optionally also require recent poller activity (the daemon's last_poll_at)
+    let Some(max_age) = std::env::var("HEALTHCHECK_MAX_POLL_AGE_SECS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+    else {
+        return Ok(());
+    };
+    let last_poll: Option<String> = sqlx::query_scalar("SELECT value FROM meta WHERE key = 'last_poll_at'")
+        .fetch_optional(&mut conn)
+        .await
+        .map_err(|e| format!("Failed to read last poll time: {}", e))?;
+    let last_poll = last_poll.ok_or("The poller has not completed a cycle yet")?;
+    let last_poll = chrono::DateTime::parse_from_rfc3339(&last_poll)
+        .map_err(|e| format!("Invalid last poll time '{}': {}", last_poll, e))?;
+
+    let age = chrono::Utc::now().signed_duration_since(last_poll).num_seconds();
+    if age > max_age {
+        return Err(format!("Last poll was {}s ago (limit {}s)", age, max_age));
+    }
+    Ok(())
 }
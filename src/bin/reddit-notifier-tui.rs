@@ -1,12 +1,16 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dotenvy::dotenv;
-use reddit_notifier::db_connection::{connect_with_retry, ConnectionConfig};
+use reddit_notifier::db_connection::{connect_with_retry, read_write_options, ConnectionConfig, PoolConfig};
+use reddit_notifier::http_client::{client_builder, HttpClientConfig};
+use reddit_notifier::logging::reloadable_filter;
+use reddit_notifier::migrations::run_migrations;
 use reddit_notifier::models::config::AppConfig;
+use reddit_notifier::notifiers::NotifierClientProfile;
 use reddit_notifier::services::SqliteDatabaseService;
-use reddit_notifier::tui::App;
-use sqlx::sqlite::SqliteConnectOptions;
-use std::str::FromStr;
+use reddit_notifier::tui::{autocomplete::SubredditAutocomplete, session::{database_file_path, default_state_path}, App};
+use std::path::PathBuf;
 use std::sync::Arc;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -14,34 +18,40 @@ async fn main() -> Result<()> {
     dotenv().ok();
 
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
+    let (filter, log_level) = reloadable_filter(
+        tracing_subscriber::EnvFilter::from_default_env()
+            .add_directive(tracing::Level::INFO.into()),
+    );
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
         .init();
 
     // Load configuration
     let cfg = AppConfig::from_env()?;
 
     // Connect to database with retry logic
-    let connect_options = SqliteConnectOptions::from_str(&cfg.database_url)?
-        .create_if_missing(true)
-        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-        .busy_timeout(std::time::Duration::from_secs(5));
+    let pool_config = PoolConfig::from_env();
+    let connect_options = read_write_options(&cfg.database_url, &pool_config)?;
 
-    // Configure pool for SQLite (low max_connections to reduce contention)
+    // The TUI needs fewer connections than the daemon
     let retry_config = ConnectionConfig::from_env();
     let pool = connect_with_retry(
         connect_options,
-        3, // max_connections (lower for TUI)
-        std::time::Duration::from_secs(300), // idle_timeout
+        pool_config.max_connections.min(3),
+        pool_config.idle_timeout,
         Some(retry_config),
     )
     .await?;
 
     // Run migrations
-    sqlx::migrate!().run(&pool).await?;
+    run_migrations(&pool).await?;
+
+    // Subreddit suggestions use the same proxy / CA settings as the daemon
+    let client = client_builder(&HttpClientConfig::from_app_config(&cfg))
+        .context("Failed to configure HTTP client")?
+        .user_agent(cfg.reddit_user_agent.clone())
+        .build()?;
 
     // Initialize terminal
     let mut terminal = ratatui::init();
@@ -49,7 +59,24 @@ async fn main() -> Result<()> {
 
     // Create database service and app
     let db = Arc::new(SqliteDatabaseService::new(pool));
-    let mut app = App::new(db)?;
+    let mut app = App::new(db)?
+        .with_subreddit_autocomplete(SubredditAutocomplete::new(client))
+        .with_log_level(log_level)
+        .with_notifier_profile(NotifierClientProfile::from_app_config(&cfg))
+        .with_compact_mode(cfg.tui_compact);
+    if let Some(database) = database_file_path(&cfg.database_url) {
+        app = app.with_backups(database);
+    }
+    if cfg.tui_restore_session {
+        let state_path = cfg
+            .tui_state_file
+            .as_ref()
+            .map(PathBuf::from)
+            .or_else(|| default_state_path(&cfg.database_url));
+        if let Some(path) = state_path {
+            app = app.with_session_file(path);
+        }
+    }
     let result = app.run(&mut terminal).await;
 
     // Restore terminal
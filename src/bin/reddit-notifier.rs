@@ -1,73 +1,136 @@
 use anyhow::{Context, Result};
+use chrono::{TimeDelta, Utc};
 use dotenvy::dotenv;
-use reqwest::Client;
-use sqlx::{sqlite::SqliteConnectOptions, Sqlite};
+use sqlx::{Sqlite, SqlitePool};
 use sqlx::migrate::MigrateDatabase;
-use std::str::FromStr;
+use std::collections::HashMap;
+use std::process::ExitCode;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use reddit_notifier::db_connection::{connect_with_retry, ConnectionConfig};
+use reddit_notifier::cli::{report_error, take_output_format, usage, DoctorFindings, OutputFormat, ValidationError};
+use reddit_notifier::keywords::{KeywordMode, KeywordSet};
+use reddit_notifier::db_connection::{connect_read_only, read_write_options, ConnectionConfig, PoolConfig, ReconnectConfig};
+use reddit_notifier::env_config::{ensure_env_config, EnvConfig};
+use reddit_notifier::explain::{match_report, parse_post_json};
+use reddit_notifier::import::{apply_config, parse_config, validate_config};
+use reddit_notifier::logging::reloadable_filter;
+use reddit_notifier::migrations::{migration_status, run_migrations};
 use reddit_notifier::models::config::AppConfig;
-use reddit_notifier::poller::poll_combined_subreddits_loop;
-use reddit_notifier::rate_limiter::RateLimiter;
+use reddit_notifier::notifiers::{build_client, build_notifier, resend::resend_recent, NotifierClientProfile};
+use reddit_notifier::poller::{
+    meta_time, poll_combined_subreddits_loop, request_sync, run_once_and_flush, workers_within_pool, PollerConfig, PollerHealth, PollerState, HEARTBEAT_STALE_AFTER,
+    META_LAST_POLL_AT, META_LAST_SUCCESS_AT,
+};
+use reddit_notifier::rate_limiter::{sync_burst, RateLimiter, SYNC_BOOST_WINDOW};
+use reddit_notifier::rising::{parse_rising_threshold, RISING_WINDOW_HOURS};
+use reddit_notifier::score_alerts::{format_score_thresholds, parse_score_thresholds};
 use reddit_notifier::services::{DatabaseService, SqliteDatabaseService};
 use reddit_notifier::shutdown::{race_with_shutdown, ShutdownRace};
+use reddit_notifier::subreddit_health::{check_subreddit, SubredditHealth};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     dotenv().ok();
+    let (filter, log_level) = reloadable_filter(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"))
+    );
     tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"))
-        )
+        .with(filter)
         .with(tracing_subscriber::fmt::layer())
         .init();
+    #[cfg(unix)]
+    tokio::spawn(cycle_log_level_on_sigusr1(log_level));
+    #[cfg(not(unix))]
+    drop(log_level);
 
-    let cfg = AppConfig::from_env()?;
+    // Errors go to stderr (as JSON with `--output json`) with an exit code per error kind
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let (output, result) = match take_output_format(&mut args) {
+        Ok(output) => (output, run(&args).await),
+        Err(e) => (OutputFormat::Text, Err(e)),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report_error(&e, output),
+    }
+}
 
-    // Create database file if it doesn't exist
-    if !Sqlite::database_exists(&cfg.database_url).await? {
-        Sqlite::create_database(&cfg.database_url).await?;
+/// Run a subcommand, or the poller daemon when none is given
+async fn run(args: &[String]) -> Result<()> {
+    let rest = args.get(1..).unwrap_or_default();
+    // `import --check` never opens the database, so it works without DATABASE_URL (e.g. in CI)
+    if args.first().map(String::as_str) == Some("import") {
+        return import(rest).await;
     }
 
-    let connect_options = SqliteConnectOptions::from_str(&cfg.database_url)?
-        .create_if_missing(true)
-        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-        .busy_timeout(std::time::Duration::from_secs(5));
+    let cfg = AppConfig::from_env()?;
+
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("migrate") => return migrate(&cfg, rest.first().map(String::as_str)).await,
+        Some("export") => return export(&cfg, rest.first().map(String::as_str)).await,
+        Some("doctor") => return doctor(&cfg, rest).await,
+        Some("stats") => return stats(&cfg, rest.first().map(String::as_str)).await,
+        Some("failures") => return failures(&cfg).await,
+        Some("status") => return status(&cfg).await,
+        Some("pause") => return set_notifications_enabled(&cfg, false).await,
+        Some("resume") => return set_notifications_enabled(&cfg, true).await,
+        Some("sync") => return sync(&cfg).await,
+        Some("poll-once") => return poll_once(&cfg).await,
+        Some("resend") => return resend(&cfg, rest).await,
+        Some("score-alerts") => return score_alerts(&cfg, rest).await,
+        Some("rising-alerts") => return rising_alerts(&cfg, rest).await,
+        Some("comment-keywords") => return comment_keywords(&cfg, rest).await,
+        Some("explain") => return explain(&cfg, rest).await,
+        Some(other) => {
+            return Err(usage(format!(
+                "unknown command '{}' (usage: reddit-notifier [--output json] [migrate [status] | export <file.csv> | doctor [--fix] [--check-subreddits [--mute-dead]] | stats [days] | failures | status | pause | resume | sync | poll-once | import <file.json> [--check] | resend <endpoint-id> [count] [--subreddit <name>] | score-alerts <subscription-id> <thresholds|off> | rising-alerts <subscription-id> <points-per-hour|off> | comment-keywords <subscription-id> <keywords|off> [--mode substring|word|regex] | explain <post.json|->])",
+                other
+            )))
+        }
+    }
 
-    // Configure pool for SQLite (low max_connections to reduce contention)
-    let retry_config = ConnectionConfig::from_env();
-    let pool = connect_with_retry(
-        connect_options,
-        5, // max_connections
-        std::time::Duration::from_secs(300), // idle_timeout
-        Some(retry_config),
-    )
-    .await
-    .with_context(|| format!("failed to connect to {}", cfg.database_url))?;
+    let pool = connect(&cfg).await?;
 
     // Apply migrations at startup
-    sqlx::migrate!()
-        .run(&pool)
-        .await
-        .context("Failed to run database migrations")?;
+    let status = run_migrations(&pool).await?;
+    match status.current() {
+        Some(current) => info!("Database schema at migration {}", current),
+        None => warn!("No database migrations are embedded in this build"),
+    }
+
+    // Create database service (the poller reopens the pool if the database becomes unreachable)
+    let db = Arc::new(SqliteDatabaseService::new(pool).with_reconnect(reconnect_config(&cfg)?));
 
-    // Create database service
-    let db = Arc::new(SqliteDatabaseService::new(pool));
+    // Zero-config setup: make sure the subscriptions/endpoint named in the environment exist
+    if let Some(env) = EnvConfig::from_env()? {
+        let created = ensure_env_config(db.as_ref(), &env).await?;
+        info!(
+            "Environment config: {} subreddit(s), created {} subscription(s), {} endpoint(s) and {} link(s)",
+            env.subreddits.len(),
+            created.subscriptions,
+            usize::from(created.endpoint),
+            created.links
+        );
+        if env.endpoint.is_none() && !env.subreddits.is_empty() {
+            warn!("REDDIT_SUBREDDITS is set without NOTIFY_ENDPOINT_KIND/NOTIFY_ENDPOINT_CONFIG - only endpoints linked from the TUI or an import are notified");
+        }
+    }
 
-    let client = Client::builder()
-        .user_agent(cfg.reddit_user_agent.clone())
-        .build()?;
+    // Shared by Reddit polling and webhook delivery (proxy / custom CA / timeout from the notifier profile)
+    let mut poller_config = PollerConfig::from_app_config(&cfg);
+    poller_config.batch_workers = workers_within_pool(cfg.poll_workers, PoolConfig::from_env().max_connections);
+    let client = build_client(&poller_config.notifier_profile).context("Failed to configure HTTP client")?;
 
     // Wait for subreddits to be configured
     // Check every 10 seconds until subscriptions exist in the database
     let subreddits = loop {
         let subs = db.unique_subreddits().await?;
-        if !subs.is_empty() {
+        if !subs.is_empty() || !db.search_subscriptions().await?.is_empty() {
             break subs;
         }
 
@@ -98,14 +161,21 @@ async fn main() -> Result<()> {
     );
 
     info!(
-        "Starting combined poller for {} subreddit(s) with rate limiting ({} req/min)",
+        "Starting combined poller for {} subreddit(s) with rate limiting ({} req/min, listing limit {})",
         subreddits.len(),
-        cfg.rate_limit_per_minute
+        cfg.rate_limit_per_minute,
+        cfg.listing_limit
     );
     info!("Reddit notifier is running. Press Ctrl+C to shutdown gracefully.");
 
+    // Kept to send the batches still open when the poller stops
+    let pending_batches = poller_config
+        .batcher
+        .clone()
+        .map(|batcher| (batcher, db.clone(), client.clone(), poller_config.notifier_profile.clone()));
+
     // Race the poller against the shutdown signal
-    match race_with_shutdown(poll_combined_subreddits_loop(db, client, subreddits, rate_limiter)).await? {
+    match race_with_shutdown(poll_combined_subreddits_loop(db, client, subreddits, rate_limiter, poller_config)).await? {
         ShutdownRace::Shutdown => {
             info!("Received shutdown signal, cleaning up...");
         }
@@ -122,6 +192,630 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Some((batcher, db, client, profile)) = pending_batches {
+        batcher.flush_all(db.as_ref(), &client, &profile).await;
+    }
+
     info!("Shutdown complete");
     Ok(())
 }
+
+/// Open the database read-write, creating the file if it doesn't exist
+async fn connect(cfg: &AppConfig) -> Result<SqlitePool> {
+    // Create database file if it doesn't exist
+    if !Sqlite::database_exists(&cfg.database_url).await? {
+        Sqlite::create_database(&cfg.database_url).await?;
+    }
+
+    reconnect_config(cfg)?
+        .connect()
+        .await
+        .with_context(|| format!("failed to connect to {}", cfg.database_url))
+}
+
+/// Read-write pool settings, kept by the daemon so the poller can reopen the pool
+fn reconnect_config(cfg: &AppConfig) -> Result<ReconnectConfig> {
+    // Pool size and timeouts come from DB_MAX_CONNECTIONS / DB_IDLE_TIMEOUT_SECS / DB_BUSY_TIMEOUT_MS
+    let pool_config = PoolConfig::from_env();
+    Ok(ReconnectConfig {
+        connect_options: read_write_options(&cfg.database_url, &pool_config)?,
+        max_connections: pool_config.max_connections,
+        idle_timeout: pool_config.idle_timeout,
+        retry_config: ConnectionConfig::from_env(),
+    })
+}
+
+/// `reddit-notifier migrate` applies pending migrations; `migrate status` only reports them
+async fn migrate(cfg: &AppConfig, subcommand: Option<&str>) -> Result<()> {
+    let status = match subcommand {
+        None => {
+            let pool = connect(cfg).await?;
+            run_migrations(&pool).await?
+        }
+        Some("status") => {
+            let pool = connect_read_only(&cfg.database_url, 1, Duration::from_secs(30), Some(ConnectionConfig::from_env()))
+                .await
+                .with_context(|| format!("failed to open {}", cfg.database_url))?;
+            migration_status(&pool).await?
+        }
+        Some(other) => return Err(usage(format!("unknown migrate command '{}' (usage: reddit-notifier migrate [status])", other))),
+    };
+
+    println!("{}", status);
+    Ok(())
+}
+
+/// `reddit-notifier export <file>` writes the notification history as CSV (`-` = stdout)
+async fn export(cfg: &AppConfig, path: Option<&str>) -> Result<()> {
+    let path = path.ok_or_else(|| usage("usage: reddit-notifier export <file.csv>"))?;
+    let pool = connect_read_only(&cfg.database_url, 1, Duration::from_secs(30), Some(ConnectionConfig::from_env()))
+        .await
+        .with_context(|| format!("failed to open {}", cfg.database_url))?;
+    let db = SqliteDatabaseService::new(pool);
+
+    let written = if path == "-" {
+        db.export_notified_posts_stream(&mut std::io::BufWriter::new(std::io::stdout())).await?
+    } else {
+        let file = std::fs::File::create(path).with_context(|| format!("failed to create {}", path))?;
+        let written = db.export_notified_posts_stream(&mut std::io::BufWriter::new(file)).await?;
+        info!("Exported {} notified post(s) to {}", written, path);
+        written
+    };
+    if written == 0 {
+        warn!("Notification history is empty");
+    }
+    Ok(())
+}
+
+/// `reddit-notifier stats [days]` reports delivery success rate and p95 latency per endpoint
+async fn stats(cfg: &AppConfig, days: Option<&str>) -> Result<()> {
+    let days: i64 = match days {
+        Some(d) => d.parse().ok().filter(|d| *d > 0).ok_or_else(|| usage("usage: reddit-notifier stats [days]"))?,
+        None => 7,
+    };
+    let pool = connect_read_only(&cfg.database_url, 1, Duration::from_secs(30), Some(ConnectionConfig::from_env()))
+        .await
+        .with_context(|| format!("failed to open {}", cfg.database_url))?;
+    let db = SqliteDatabaseService::new(pool);
+
+    let stats = db.delivery_stats(days).await?;
+    if stats.is_empty() {
+        println!("No deliveries in the last {} day(s)", days);
+        return Ok(());
+    }
+
+    let endpoints = db.list_endpoints().await?;
+    println!("Deliveries in the last {} day(s):", days);
+    println!("{:<24} {:>10} {:>8} {:>12}", "Endpoint", "Deliveries", "Success", "p95 latency");
+    for s in &stats {
+        let kind = endpoints
+            .iter()
+            .find(|e| e.id == s.endpoint_id)
+            .map_or("unknown", |e| e.kind.as_str());
+        let p95 = s.p95_latency_ms.map_or("-".to_string(), |ms| format!("{}ms", ms));
+        println!(
+            "{:<24} {:>10} {:>7.1}% {:>12}",
+            format!("{} (ID: {})", kind, s.endpoint_id),
+            s.total,
+            s.success_rate() * 100.0,
+            p95
+        );
+    }
+    Ok(())
+}
+
+/// `reddit-notifier failures` lists endpoints with failed deliveries in the last day
+///
+/// Shows failure counts for the last hour and day plus the most recent error,
+/// so a broken webhook or expired token can be spotted in one place.
+async fn failures(cfg: &AppConfig) -> Result<()> {
+    let pool = connect_read_only(&cfg.database_url, 1, Duration::from_secs(30), Some(ConnectionConfig::from_env()))
+        .await
+        .with_context(|| format!("failed to open {}", cfg.database_url))?;
+    let db = SqliteDatabaseService::new(pool);
+
+    let now = Utc::now();
+    let last_day = db.recent_delivery_failures(now - TimeDelta::days(1)).await?;
+    if last_day.is_empty() {
+        println!("No failed deliveries in the last day");
+        return Ok(());
+    }
+    let last_hour = db.recent_delivery_failures(now - TimeDelta::hours(1)).await?;
+
+    let endpoints = db.list_endpoints().await?;
+    println!("{:<24} {:>9} {:>8} {:>10}  Last error", "Endpoint", "Last hour", "Last day", "Last failed");
+    for f in &last_day {
+        let kind = endpoints
+            .iter()
+            .find(|e| e.id == f.endpoint_id)
+            .map_or("unknown", |e| e.kind.as_str());
+        let hour = last_hour
+            .iter()
+            .find(|h| h.endpoint_id == f.endpoint_id)
+            .map_or(0, |h| h.failures);
+        let ago = (now - f.last_failed_at).num_minutes();
+        println!(
+            "{:<24} {:>9} {:>8} {:>10}  {}",
+            format!("{} (ID: {})", kind, f.endpoint_id),
+            hour,
+            f.failures,
+            format!("{}m ago", ago),
+            f.last_error.as_deref().unwrap_or("-")
+        );
+    }
+    Ok(())
+}
+
+/// `reddit-notifier status` shows when the daemon last polled, and last polled without failures
+///
+/// Exits non-zero when the poller has never run or its last poll is stale,
+/// so it can back an external monitor.
+async fn status(cfg: &AppConfig) -> Result<()> {
+    let pool = connect_read_only(&cfg.database_url, 1, Duration::from_secs(30), Some(ConnectionConfig::from_env()))
+        .await
+        .with_context(|| format!("failed to open {}", cfg.database_url))?;
+    let db = SqliteDatabaseService::new(pool);
+
+    let last_poll = meta_time(&db, META_LAST_POLL_AT).await?;
+    let last_success = meta_time(&db, META_LAST_SUCCESS_AT).await?;
+    let describe = |at: Option<chrono::DateTime<Utc>>| {
+        at.map_or("never".to_string(), |at| format!("{} ({}m ago)", at.to_rfc3339(), (Utc::now() - at).num_minutes()))
+    };
+    println!("Last poll:     {}", describe(last_poll));
+    println!("Last success:  {}", describe(last_success));
+    println!(
+        "Notifications: {}",
+        if db.notifications_enabled().await? { "enabled" } else { "paused" }
+    );
+
+    let health = PollerHealth::classify(last_poll, Utc::now(), HEARTBEAT_STALE_AFTER);
+    println!("{}", health);
+    if !health.is_healthy() {
+        anyhow::bail!("the poller has not polled in the last {} minutes", HEARTBEAT_STALE_AFTER.as_secs() / 60);
+    }
+    Ok(())
+}
+
+/// `reddit-notifier pause` / `resume` flip the global notification switch
+///
+/// A running poller picks the change up at the start of its next cycle.
+async fn set_notifications_enabled(cfg: &AppConfig, enabled: bool) -> Result<()> {
+    let pool = connect(cfg).await?;
+    run_migrations(&pool).await?;
+    let db = SqliteDatabaseService::new(pool);
+
+    db.set_notifications_enabled(enabled).await?;
+    if enabled {
+        println!("Notifications resumed");
+    } else {
+        println!("Notifications paused - new posts are still recorded but not delivered");
+    }
+    Ok(())
+}
+
+/// `reddit-notifier sync` asks the running daemon for a one-time catch-up at a boosted rate
+async fn sync(cfg: &AppConfig) -> Result<()> {
+    let pool = connect(cfg).await?;
+    run_migrations(&pool).await?;
+    let db = SqliteDatabaseService::new(pool);
+
+    request_sync(&db).await?;
+    let burst = sync_burst(cfg.rate_limit_per_minute);
+    if burst == 0 {
+        println!("Sync requested - the rate limit is already at Reddit's maximum, so polling continues at the configured rate");
+    } else {
+        println!(
+            "Sync requested - the daemon allows {} extra request(s) over the {}s after its next poll cycle starts",
+            burst,
+            SYNC_BOOST_WINDOW.as_secs()
+        );
+    }
+    Ok(())
+}
+
+/// `reddit-notifier import <file.json>` creates the endpoints and subscriptions in a config file
+///
+/// With `--check` the file is only validated; every problem is listed and the
+/// database is not opened.
+async fn import(args: &[String]) -> Result<()> {
+    const USAGE: &str = "usage: reddit-notifier import <file.json> [--check]";
+    let check = args.iter().any(|a| a == "--check");
+    let mut paths = args.iter().filter(|a| *a != "--check");
+    let (Some(path), None) = (paths.next(), paths.next()) else {
+        return Err(usage(USAGE));
+    };
+
+    let json = std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?;
+    let doc = parse_config(&json)?;
+
+    if check {
+        let issues = validate_config(&doc);
+        for issue in &issues {
+            println!("{}", issue);
+        }
+        if !issues.is_empty() {
+            return Err(ValidationError(format!("{} has {} problem(s)", path, issues.len())).into());
+        }
+        println!(
+            "{} is valid: {} endpoint(s), {} subscription(s)",
+            path,
+            doc.endpoints.len(),
+            doc.subscriptions.len()
+        );
+        return Ok(());
+    }
+
+    let cfg = AppConfig::from_env()?;
+    let pool = connect(&cfg).await?;
+    run_migrations(&pool).await?;
+    let db = SqliteDatabaseService::new(pool);
+
+    let summary = apply_config(&db, &doc).await?;
+    println!(
+        "Imported {} endpoint(s), {} subscription(s) and {} link(s)",
+        summary.endpoints, summary.subscriptions, summary.links
+    );
+    Ok(())
+}
+
+/// `reddit-notifier explain <post.json>` shows which subscriptions would notify a post
+///
+/// Reads the post's JSON from a file (or stdin with `-`) and reports every
+/// check per subscription. Nothing is fetched or written.
+async fn explain(cfg: &AppConfig, args: &[String]) -> Result<()> {
+    const USAGE: &str = "usage: reddit-notifier explain <post.json|->";
+    let [path] = args else {
+        return Err(usage(USAGE));
+    };
+
+    let json = if path == "-" {
+        std::io::read_to_string(std::io::stdin()).context("failed to read stdin")?
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?
+    };
+    let post = parse_post_json(&json)?;
+
+    let pool = connect_read_only(&cfg.database_url, 1, Duration::from_secs(30), Some(ConnectionConfig::from_env()))
+        .await
+        .with_context(|| format!("failed to open {}", cfg.database_url))?;
+    let db = SqliteDatabaseService::new(pool);
+
+    let subscriptions = db.list_subscriptions().await?;
+    let mut endpoints = HashMap::new();
+    for sub in subscriptions.iter().filter(|s| s.subreddit.eq_ignore_ascii_case(&post.subreddit)) {
+        endpoints.insert(sub.id, db.get_subscription_endpoints(sub.id).await?);
+    }
+
+    println!("{}", match_report(&post, &subscriptions, &endpoints, Utc::now()));
+    Ok(())
+}
+
+/// `reddit-notifier poll-once` runs a single poll cycle and exits
+///
+/// Meant for cron or a smoke test after deploying: the exit status is non-zero
+/// when the cycle could not run or any fetch or notification failed.
+async fn poll_once(cfg: &AppConfig) -> Result<()> {
+    let pool = connect(cfg).await?;
+    run_migrations(&pool).await?;
+    let db = SqliteDatabaseService::new(pool);
+
+    let mut config = PollerConfig::from_app_config(cfg);
+    config.batch_workers = workers_within_pool(cfg.poll_workers, PoolConfig::from_env().max_connections);
+    let client = build_client(&config.notifier_profile).context("Failed to configure HTTP client")?;
+
+    let subreddits = db.unique_subreddits().await?;
+    if subreddits.is_empty() {
+        println!("No subscriptions configured - nothing to poll");
+        return Ok(());
+    }
+
+    let rate_limiter = RateLimiter::new(
+        cfg.rate_limit_per_minute,
+        Duration::from_secs(60) / cfg.rate_limit_per_minute,
+    );
+    let mut state = PollerState::new(&config);
+
+    // Also sends anything batched this cycle, as there's no flusher task to do it later
+    let summary = run_once_and_flush(&db, &client, &subreddits, &rate_limiter, &config, &mut state).await?;
+    println!("{}", summary);
+    if summary.failures > 0 {
+        anyhow::bail!("poll cycle finished with {} failure(s)", summary.failures);
+    }
+    Ok(())
+}
+
+/// `reddit-notifier resend <endpoint-id> [count] [--subreddit <name>]` re-sends recent notifications
+///
+/// Lets a new endpoint catch up on the last `count` (default 10) notified
+/// posts. Sends are spaced one per second and the notification history is
+/// not modified.
+async fn resend(cfg: &AppConfig, args: &[String]) -> Result<()> {
+    const USAGE: &str = "usage: reddit-notifier resend <endpoint-id> [count] [--subreddit <name>]";
+    let mut positional = Vec::new();
+    let mut subreddit = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--subreddit" {
+            subreddit = Some(args.next().ok_or_else(|| usage(USAGE))?.trim_start_matches("r/").to_string());
+        } else {
+            positional.push(arg);
+        }
+    }
+    let (endpoint_id, count): (i64, i64) = match positional.as_slice() {
+        [id] => (id.parse().map_err(|_| usage(USAGE))?, 10),
+        [id, count] => (
+            id.parse().map_err(|_| usage(USAGE))?,
+            count.parse().ok().filter(|c| *c > 0).ok_or_else(|| usage(USAGE))?,
+        ),
+        _ => return Err(usage(USAGE)),
+    };
+
+    let pool = connect(cfg).await?;
+    run_migrations(&pool).await?;
+    let db = SqliteDatabaseService::new(pool);
+    let endpoint = db
+        .get_endpoint(endpoint_id)
+        .await
+        .with_context(|| format!("no endpoint with ID {}", endpoint_id))?;
+
+    let profile = NotifierClientProfile::from_app_config(cfg);
+    let client = build_client(&profile).context("Failed to configure HTTP client")?;
+    let notifier = build_notifier(&endpoint, client, profile.footers)?;
+    let rate_limiter = RateLimiter::new(1, Duration::from_secs(1));
+
+    let summary = resend_recent(&db, notifier.as_ref(), count, subreddit.as_deref(), &rate_limiter).await?;
+    println!(
+        "Re-sent {} notification(s) to {} (ID: {})",
+        summary.sent,
+        endpoint.kind.as_str(),
+        endpoint.id
+    );
+    if summary.failed > 0 {
+        anyhow::bail!("{} notification(s) could not be re-sent", summary.failed);
+    }
+    Ok(())
+}
+
+/// `reddit-notifier score-alerts <subscription-id> <thresholds|off>` sets follow-up score thresholds
+async fn score_alerts(cfg: &AppConfig, args: &[String]) -> Result<()> {
+    const USAGE: &str = "usage: reddit-notifier score-alerts <subscription-id> <thresholds|off> (e.g. 100,1000)";
+    let [id, thresholds] = args else {
+        return Err(usage(USAGE));
+    };
+    let id: i64 = id.parse().map_err(|_| usage(USAGE))?;
+    let thresholds = if thresholds == "off" {
+        Vec::new()
+    } else {
+        let parsed = parse_score_thresholds(thresholds).map_err(|e| usage(format!("{} ({})", e, USAGE)))?;
+        if parsed.is_empty() {
+            return Err(usage(USAGE));
+        }
+        parsed
+    };
+
+    let pool = connect(cfg).await?;
+    run_migrations(&pool).await?;
+    let db = SqliteDatabaseService::new(pool);
+    db.set_subscription_score_thresholds(id, &thresholds)
+        .await
+        .with_context(|| format!("no subscription with ID {}", id))?;
+
+    if thresholds.is_empty() {
+        println!("Score alerts off for subscription {}", id);
+    } else {
+        println!("Score alerts for subscription {} at {} points", id, format_score_thresholds(&thresholds));
+    }
+    Ok(())
+}
+
+/// `reddit-notifier rising-alerts <subscription-id> <points-per-hour|off>` sets the rising threshold
+async fn rising_alerts(cfg: &AppConfig, args: &[String]) -> Result<()> {
+    const USAGE: &str = "usage: reddit-notifier rising-alerts <subscription-id> <points-per-hour|off> (e.g. 200)";
+    let [id, threshold] = args else {
+        return Err(usage(USAGE));
+    };
+    let id: i64 = id.parse().map_err(|_| usage(USAGE))?;
+    let threshold = if threshold == "off" {
+        None
+    } else {
+        Some(parse_rising_threshold(threshold).map_err(|e| usage(format!("{} ({})", e, USAGE)))?)
+    };
+
+    let pool = connect(cfg).await?;
+    run_migrations(&pool).await?;
+    let db = SqliteDatabaseService::new(pool);
+    db.set_subscription_rising_threshold(id, threshold)
+        .await
+        .with_context(|| format!("no subscription with ID {}", id))?;
+
+    match threshold {
+        Some(threshold) => println!(
+            "Rising alerts for subscription {} at {} points/h within {}h of posting",
+            id, threshold, RISING_WINDOW_HOURS
+        ),
+        None => println!("Rising alerts off for subscription {}", id),
+    }
+    Ok(())
+}
+
+/// `reddit-notifier comment-keywords <subscription-id> <keywords|off> [--mode substring|word|regex]` sets comment keyword alerts
+async fn comment_keywords(cfg: &AppConfig, args: &[String]) -> Result<()> {
+    const USAGE: &str = "usage: reddit-notifier comment-keywords <subscription-id> <keywords|off> [--mode substring|word|regex] (e.g. \"tokio,async runtime\")";
+    let mut positional = Vec::new();
+    let mut mode = KeywordMode::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--mode" {
+            mode = args.next().ok_or_else(|| usage(USAGE))?.parse().map_err(|e| usage(format!("{} ({})", e, USAGE)))?;
+        } else {
+            positional.push(arg);
+        }
+    }
+    let [id, keywords] = positional.as_slice() else {
+        return Err(usage(USAGE));
+    };
+    let id: i64 = id.parse().map_err(|_| usage(USAGE))?;
+    let keywords = if *keywords == "off" {
+        None
+    } else {
+        Some(KeywordSet::parse(mode, keywords).map_err(|e| usage(format!("{} ({})", e, USAGE)))?)
+    };
+
+    let pool = connect(cfg).await?;
+    run_migrations(&pool).await?;
+    let db = SqliteDatabaseService::new(pool);
+    db.set_subscription_comment_keywords(id, keywords.as_ref())
+        .await
+        .with_context(|| format!("no subscription with ID {}", id))?;
+
+    match keywords {
+        Some(set) => println!("Comment alerts for subscription {} on ({}): {}", id, set.mode, set.keywords.join(", ")),
+        None => println!("Comment alerts off for subscription {}", id),
+    }
+    Ok(())
+}
+
+/// Step the log level (info -> debug -> trace) each time the process receives SIGUSR1
+#[cfg(unix)]
+async fn cycle_log_level_on_sigusr1(log_level: reddit_notifier::logging::LogLevelHandle) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut signals = match signal(SignalKind::user_defined1()) {
+        Ok(signals) => signals,
+        Err(e) => {
+            warn!("Failed to listen for SIGUSR1, log level can't be changed at runtime: {}", e);
+            return;
+        }
+    };
+    while signals.recv().await.is_some() {
+        match log_level.cycle() {
+            Ok(level) => info!("Log level changed to {}", level),
+            Err(e) => warn!("Failed to change log level: {}", e),
+        }
+    }
+}
+
+/// `reddit-notifier doctor [--fix] [--check-subreddits [--mute-dead]]` reports configuration problems that silently stop notifications
+///
+/// With `--fix`, integrity problems (orphaned links, unparseable settings,
+/// broken endpoints) are repaired; subscriptions without an active endpoint
+/// still need one to be linked. `--check-subreddits` also asks Reddit about
+/// every polled subreddit, and `--mute-dead` mutes the private, banned and
+/// missing ones.
+async fn doctor(cfg: &AppConfig, args: &[String]) -> Result<()> {
+    let (mut fix, mut check_subreddits, mut mute_dead) = (false, false, false);
+    for arg in args {
+        match arg.as_str() {
+            "--fix" => fix = true,
+            "--check-subreddits" => check_subreddits = true,
+            "--mute-dead" => mute_dead = true,
+            _ => return Err(usage("usage: reddit-notifier doctor [--fix] [--check-subreddits [--mute-dead]]")),
+        }
+    }
+    if mute_dead && !check_subreddits {
+        return Err(usage("--mute-dead needs --check-subreddits"));
+    }
+
+    let db = if fix || mute_dead {
+        let pool = connect(cfg).await?;
+        run_migrations(&pool).await?;
+        SqliteDatabaseService::new(pool)
+    } else {
+        let pool = connect_read_only(&cfg.database_url, 1, Duration::from_secs(30), Some(ConnectionConfig::from_env()))
+            .await
+            .with_context(|| format!("failed to open {}", cfg.database_url))?;
+        SqliteDatabaseService::new(pool)
+    };
+
+    let integrity = if fix { db.fix_integrity_problems().await? } else { db.integrity_problems().await? };
+    if !integrity.orphaned_links.is_empty() {
+        println!("Subscription-endpoint links to a deleted subscription or endpoint:");
+        for (subscription_id, endpoint_id) in &integrity.orphaned_links {
+            println!("  subscription {} -> endpoint {}", subscription_id, endpoint_id);
+        }
+    }
+    if !integrity.invalid_subscriptions.is_empty() {
+        println!("Subscriptions with invalid settings:");
+        for problem in &integrity.invalid_subscriptions {
+            println!("  ID {}: {}: {}", problem.id, problem.field, problem.reason);
+        }
+    }
+    if !integrity.invalid_endpoints.is_empty() {
+        println!("Active endpoints with an invalid kind or config:");
+        for problem in &integrity.invalid_endpoints {
+            println!("  ID {}: {}: {}", problem.id, problem.field, problem.reason);
+        }
+    }
+    if fix && !integrity.is_empty() {
+        println!(
+            "Fixed: removed {} link(s), reset {} setting(s), deactivated {} endpoint(s)",
+            integrity.orphaned_links.len(),
+            integrity.invalid_subscriptions.len(),
+            integrity.invalid_endpoints.len()
+        );
+    }
+
+    // Checked after fixing, since deactivating a broken endpoint can leave a subscription without one
+    let orphaned = db.subscriptions_without_active_endpoints().await?;
+    if !orphaned.is_empty() {
+        println!("Subscriptions with no active endpoint (these are never polled):");
+        for sub in &orphaned {
+            println!("  r/{} (ID: {})", sub.subreddit, sub.id);
+        }
+    }
+
+    let dead = if check_subreddits { check_subreddit_health(cfg, &db, mute_dead).await? } else { 0 };
+    let findings = DoctorFindings {
+        integrity_problems: if fix { 0 } else { integrity.len() },
+        without_active_endpoint: orphaned.len(),
+        unmuted_dead: if mute_dead { 0 } else { dead },
+    };
+    if let Some(summary) = findings.summary() {
+        anyhow::bail!(summary);
+    }
+    if integrity.is_empty() && dead == 0 {
+        println!("No problems found");
+    }
+    Ok(())
+}
+
+/// Ask Reddit about every polled subreddit and print the ones that can't be polled
+///
+/// Requests go through the configured rate limit. Checks that fail (rate
+/// limited, server or network errors) are printed but never count as dead.
+/// Returns the number of dead subreddits, which were muted if `mute_dead`.
+async fn check_subreddit_health(cfg: &AppConfig, db: &SqliteDatabaseService, mute_dead: bool) -> Result<usize> {
+    let config = PollerConfig::from_app_config(cfg);
+    let client = build_client(&config.notifier_profile).context("Failed to configure HTTP client")?;
+    let rate_limiter = RateLimiter::new(
+        cfg.rate_limit_per_minute,
+        Duration::from_secs(60) / cfg.rate_limit_per_minute,
+    );
+
+    let subreddits = db.unique_subreddits().await?;
+    let mut dead = 0;
+    let mut unknown = Vec::new();
+    println!("Checking {} subreddit(s)...", subreddits.len());
+    for subreddit in &subreddits {
+        rate_limiter.acquire().await;
+        let health = check_subreddit(&client, &config.reddit_base, subreddit).await;
+        if health.is_dead() {
+            dead += 1;
+            if mute_dead {
+                let muted = db.mute_subreddit(subreddit).await?;
+                println!("  r/{}: {} - muted {} subscription(s)", subreddit, health, muted);
+            } else {
+                println!("  r/{}: {}", subreddit, health);
+            }
+        } else if !matches!(health, SubredditHealth::Ok) {
+            unknown.push((subreddit, health));
+        }
+    }
+    if !unknown.is_empty() {
+        println!("Subreddits that couldn't be checked (try again later):");
+        for (subreddit, health) in &unknown {
+            println!("  r/{}: {}", subreddit, health);
+        }
+    }
+    Ok(dead)
+}
@@ -0,0 +1,280 @@
+//! Exit codes and error output for the `reddit-notifier` command line
+//!
+//! Every subcommand returns `anyhow::Result`; `main` hands a failure to
+//! `report_error`, which prints it to stderr (as text or JSON) and picks the
+//! process exit code from the kind of error found in its chain.
+
+use std::fmt;
+use std::process::ExitCode;
+
+use crate::notifiers::HttpStatusError;
+use crate::services::error::DbError;
+
+/// Why a command failed, and the exit code it maps to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// Anything not covered below (e.g. `doctor` found problems, a poll cycle had failures)
+    Failure,
+    /// Unknown command or missing/malformed arguments
+    Usage,
+    /// Input was rejected (invalid config file, value rejected by the database)
+    Invalid,
+    /// The requested subscription/endpoint doesn't exist
+    NotFound,
+    /// The database couldn't be opened or queried
+    Database,
+    /// Reddit or a notification service couldn't be reached or refused the request
+    Network,
+}
+
+impl ExitStatus {
+    /// Process exit code (0 is reserved for success)
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Failure => 1,
+            Self::Usage => 2,
+            Self::Invalid => 3,
+            Self::NotFound => 4,
+            Self::Database => 5,
+            Self::Network => 6,
+        }
+    }
+
+    /// Short name used in JSON error output
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Failure => "failure",
+            Self::Usage => "usage",
+            Self::Invalid => "invalid",
+            Self::NotFound => "not_found",
+            Self::Database => "database",
+            Self::Network => "network",
+        }
+    }
+
+    /// Classify an error by the first recognised error type in its chain
+    pub fn of(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if cause.is::<UsageError>() {
+                return Self::Usage;
+            }
+            if cause.is::<ValidationError>() || cause.is::<serde_json::Error>() {
+                return Self::Invalid;
+            }
+            if let Some(db_err) = cause.downcast_ref::<DbError>() {
+                return match db_err {
+                    DbError::NotFound(_) => Self::NotFound,
+                    DbError::Conflict(_) | DbError::Invalid(_) => Self::Invalid,
+                    DbError::Connection(_) | DbError::Other(_) => Self::Database,
+                };
+            }
+            if let Some(sqlx_err) = cause.downcast_ref::<sqlx::Error>() {
+                return match sqlx_err {
+                    sqlx::Error::RowNotFound => Self::NotFound,
+                    _ => Self::Database,
+                };
+            }
+            if cause.is::<HttpStatusError>() || cause.is::<reqwest::Error>() {
+                return Self::Network;
+            }
+        }
+        Self::Failure
+    }
+}
+
+/// A command was called with the wrong arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+/// Build a `UsageError` as an `anyhow::Error`
+pub fn usage(message: impl Into<String>) -> anyhow::Error {
+    UsageError(message.into()).into()
+}
+
+/// Input was checked and rejected before anything was written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError(pub String);
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// What `reddit-notifier doctor` found that still needs attention
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DoctorFindings {
+    /// Integrity problems left unrepaired (none after `--fix`)
+    pub integrity_problems: usize,
+    /// Subscriptions with no active endpoint, which `--fix` can't repair
+    pub without_active_endpoint: usize,
+    /// Dead subreddits that were found but not muted
+    pub unmuted_dead: usize,
+}
+
+impl DoctorFindings {
+    /// Each kind of remaining problem with its own count and remedy, or `None` when there are none
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.integrity_problems > 0 {
+            parts.push(format!(
+                "{} integrity problem(s) found (run `reddit-notifier doctor --fix` to repair)",
+                self.integrity_problems
+            ));
+        }
+        if self.without_active_endpoint > 0 {
+            parts.push(format!("{} subscription(s) need an active endpoint", self.without_active_endpoint));
+        }
+        if self.unmuted_dead > 0 {
+            parts.push(format!(
+                "{} subreddit(s) can't be polled (run `reddit-notifier doctor --check-subreddits --mute-dead` to mute them)",
+                self.unmuted_dead
+            ));
+        }
+        (!parts.is_empty()).then(|| parts.join("; "))
+    }
+}
+
+/// How errors are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `error: <message>`
+    #[default]
+    Text,
+    /// One JSON object: `{"error": ..., "kind": ..., "exit_code": ...}`
+    Json,
+}
+
+/// Remove a global `--output <text|json>` option from `args` and return the format
+pub fn take_output_format(args: &mut Vec<String>) -> anyhow::Result<OutputFormat> {
+    let Some(pos) = args.iter().position(|a| a == "--output") else {
+        return Ok(OutputFormat::Text);
+    };
+    args.remove(pos);
+    if pos >= args.len() {
+        return Err(usage("--output needs a format (text or json)"));
+    }
+    match args.remove(pos).as_str() {
+        "text" => Ok(OutputFormat::Text),
+        "json" => Ok(OutputFormat::Json),
+        other => Err(usage(format!("unknown output format '{}' (expected text or json)", other))),
+    }
+}
+
+/// Render `err` for stderr in `format`
+pub fn format_error(err: &anyhow::Error, format: OutputFormat) -> String {
+    let status = ExitStatus::of(err);
+    match format {
+        OutputFormat::Text => format!("error: {:#}", err),
+        OutputFormat::Json => serde_json::json!({
+            "error": format!("{:#}", err),
+            "kind": status.as_str(),
+            "exit_code": status.code(),
+        })
+        .to_string(),
+    }
+}
+
+/// Print `err` to stderr and return the matching process exit code
+pub fn report_error(err: &anyhow::Error, format: OutputFormat) -> ExitCode {
+    eprintln!("{}", format_error(err, format));
+    ExitCode::from(ExitStatus::of(err).code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_status_from_error_variants() {
+        let cases = [
+            (usage("usage: reddit-notifier stats [days]"), ExitStatus::Usage, 2),
+            (ValidationError("config.json has 2 problem(s)".into()).into(), ExitStatus::Invalid, 3),
+            (DbError::NotFound("subscription 9".into()).into(), ExitStatus::NotFound, 4),
+            (DbError::Conflict("endpoint".into()).into(), ExitStatus::Invalid, 3),
+            (DbError::Connection("database is locked".into()).into(), ExitStatus::Database, 5),
+            (sqlx::Error::PoolTimedOut.into(), ExitStatus::Database, 5),
+            (
+                HttpStatusError {
+                    service: "discord",
+                    status: reqwest::StatusCode::BAD_GATEWAY,
+                    body: String::new(),
+                    retry_after: None,
+                }
+                .into(),
+                ExitStatus::Network,
+                6,
+            ),
+            (anyhow::anyhow!("2 subscription(s) need an active endpoint"), ExitStatus::Failure, 1),
+        ];
+        for (err, status, code) in cases {
+            assert_eq!(ExitStatus::of(&err), status, "{:#}", err);
+            assert_eq!(status.code(), code);
+        }
+    }
+
+    #[test]
+    fn test_doctor_summary_counts_each_problem_separately() {
+        assert_eq!(DoctorFindings::default().summary(), None);
+
+        let findings = DoctorFindings { integrity_problems: 2, without_active_endpoint: 1, unmuted_dead: 3 };
+        assert_eq!(
+            findings.summary().unwrap(),
+            "2 integrity problem(s) found (run `reddit-notifier doctor --fix` to repair); \
+             1 subscription(s) need an active endpoint; \
+             3 subreddit(s) can't be polled (run `reddit-notifier doctor --check-subreddits --mute-dead` to mute them)"
+        );
+
+        // Dead subreddits alone don't suggest --fix, which doesn't mute them
+        let findings = DoctorFindings { unmuted_dead: 1, ..DoctorFindings::default() };
+        assert!(!findings.summary().unwrap().contains("--fix"));
+    }
+
+    #[test]
+    fn test_exit_status_looks_through_context() {
+        let err = Err::<(), _>(anyhow::Error::from(DbError::NotFound("subscription 9".into())))
+            .context("no subscription with ID 9")
+            .unwrap_err();
+        assert_eq!(ExitStatus::of(&err), ExitStatus::NotFound);
+
+        let err = serde_json::from_str::<serde_json::Value>("{").context("failed to parse config.json").unwrap_err();
+        assert_eq!(ExitStatus::of(&err), ExitStatus::Invalid);
+    }
+
+    #[test]
+    fn test_take_output_format() {
+        let mut args: Vec<String> = ["stats", "--output", "json", "30"].map(String::from).to_vec();
+        assert_eq!(take_output_format(&mut args).unwrap(), OutputFormat::Json);
+        assert_eq!(args, ["stats", "30"]);
+
+        let mut args: Vec<String> = vec!["doctor".into()];
+        assert_eq!(take_output_format(&mut args).unwrap(), OutputFormat::Text);
+
+        let mut args: Vec<String> = ["doctor", "--output", "xml"].map(String::from).to_vec();
+        assert_eq!(ExitStatus::of(&take_output_format(&mut args).unwrap_err()), ExitStatus::Usage);
+        let mut args: Vec<String> = ["doctor", "--output"].map(String::from).to_vec();
+        assert!(take_output_format(&mut args).is_err());
+    }
+
+    #[test]
+    fn test_json_error_output() {
+        let err = anyhow::Error::from(DbError::NotFound("subscription 9".into())).context("no subscription with ID 9");
+        let json: serde_json::Value = serde_json::from_str(&format_error(&err, OutputFormat::Json)).unwrap();
+        assert_eq!(json["kind"], "not_found");
+        assert_eq!(json["exit_code"], 4);
+        assert_eq!(json["error"], "no subscription with ID 9: Not found: subscription 9");
+
+        assert_eq!(format_error(&usage("usage: x"), OutputFormat::Text), "error: usage: x");
+    }
+}
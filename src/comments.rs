@@ -0,0 +1,218 @@
+//! Keyword alerts for new comments
+//!
+//! Opt-in per subscription. Comment listings can't be combined like post
+//! listings, so each subreddit with keywords costs one extra rate-limited
+//! request per poll cycle. Matching comments are sent once (deduplicated via
+//! `notified_comments`) with a link to the comment.
+
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use chrono::{TimeDelta, Utc};
+use tracing::{debug, error, warn};
+
+use crate::keywords::{KeywordMatcher, KeywordSet};
+use crate::models::{database::EndpointRow, reddit_api::RedditComment};
+use crate::notifiers::SendContext;
+use crate::poller::{canonical_reddit_url, fetch_listing_items, notify_endpoints, CycleSummary, DeliveryGuard, PollerConfig, POST_WINDOW_HOURS};
+use crate::rate_limiter::RateLimiter;
+use crate::services::DatabaseService;
+
+/// Compile each subreddit's keyword sets, leaving out sets that are rejected
+///
+/// A stored regex can fail the limits (e.g. written before they were
+/// tightened); it is skipped with a warning so other subscriptions to the
+/// same subreddit keep working.
+pub fn compile_keywords(by_subreddit: HashMap<String, Vec<KeywordSet>>) -> HashMap<String, KeywordMatcher> {
+    by_subreddit
+        .into_iter()
+        .filter_map(|(subreddit, sets)| {
+            let mut matcher = KeywordMatcher::default();
+            for set in &sets {
+                if let Err(e) = matcher.add(set) {
+                    warn!("Skipping comment keywords for r/{}: {}", subreddit, e);
+                }
+            }
+            (!matcher.is_empty()).then_some((subreddit, matcher))
+        })
+        .collect()
+}
+
+/// Build the newest-comments URL for one subreddit
+///
+/// E.g. `https://www.reddit.com/r/rust/comments.json?limit=100`
+pub fn build_comments_url(reddit_base: &str, subreddit: &str, listing_limit: u32) -> String {
+    format!("{}/r/{}/comments.json?limit={}", reddit_base, subreddit, listing_limit)
+}
+
+/// Fetch new comments for every subreddit in `keywords` and notify on matches
+///
+/// Called once per poll cycle after the post batches. Each subreddit waits for
+/// `rate_limiter`; fetch, database and notification errors are logged and
+/// counted in `summary` without stopping the other subreddits.
+#[allow(clippy::too_many_arguments)]
+pub async fn poll_comments<D: DatabaseService>(
+    db: &D,
+    client: &Client,
+    rate_limiter: &RateLimiter,
+    config: &PollerConfig,
+    keywords: &HashMap<String, KeywordMatcher>,
+    mappings: &HashMap<String, Vec<EndpointRow>>,
+    delivery_guard: &mut DeliveryGuard,
+    summary: &mut CycleSummary,
+) {
+    let mut subreddits: Vec<&String> = keywords.keys().collect();
+    subreddits.sort_unstable();
+
+    for subreddit in subreddits {
+        let Some(endpoints) = mappings.get(subreddit) else {
+            debug!("No endpoints for r/{}, not polling its comments", subreddit);
+            continue;
+        };
+
+        rate_limiter.acquire().await;
+        let url = build_comments_url(&config.reddit_base, subreddit, config.listing_limit);
+        let comments: Vec<RedditComment> = match fetch_listing_items(client, &url).await {
+            Ok(comments) => comments,
+            Err(e) => {
+                warn!(url = %url, error = %e, "Reddit GET {} -> {}", url, e);
+                summary.record_failure();
+                continue;
+            }
+        };
+        debug!("Fetched {} comment(s) from r/{}", comments.len(), subreddit);
+
+        for comment in comments {
+            let age = Utc::now().signed_duration_since(comment.created_utc);
+            if age.abs() > TimeDelta::hours(POST_WINDOW_HOURS) {
+                continue;
+            }
+            let Some(keyword) = keywords[subreddit].find(&comment.body) else {
+                continue;
+            };
+
+            let is_new = match db.record_comment_if_new(&comment.subreddit, &comment.id).await {
+                Ok(new) => new,
+                Err(e) => {
+                    error!(
+                        comment_id = %comment.id,
+                        subreddit = %comment.subreddit,
+                        error = %e,
+                        "Failed to record comment {} for r/{}: {} - skipping it",
+                        comment.id, comment.subreddit, e
+                    );
+                    summary.record_failure();
+                    continue;
+                }
+            };
+            if !is_new || !config.notifications_enabled.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            let title = format!(
+                "💬 \"{}\" mentioned on: {}",
+                keyword,
+                comment.link_title.as_deref().unwrap_or("a post")
+            );
+            let url = canonical_reddit_url(&comment.permalink);
+            let unique_endpoints = delivery_guard.unclaimed(&comment.id, endpoints);
+            debug!(
+                comment_id = %comment.id,
+                subreddit = %comment.subreddit,
+                keyword = %keyword,
+                endpoints = unique_endpoints.len(),
+                "Comment {} in r/{} mentions \"{}\" -> notifying {} endpoint(s)",
+                comment.id,
+                comment.subreddit,
+                keyword,
+                unique_endpoints.len()
+            );
+
+            let ctx = SendContext {
+                subreddit: &comment.subreddit,
+                title: &title,
+                url: &url,
+                nsfw: comment.over_18,
+                priority: false,
+            };
+            notify_endpoints(db, client, &config.notifier_profile, config.batcher.as_ref(), &unique_endpoints, &comment.id, &ctx, summary).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::reddit_api::RedditListing;
+
+    #[test]
+    fn test_parse_comment_listing() {
+        let json = r#"{
+            "kind": "Listing",
+            "data": {
+                "children": [
+                    {
+                        "kind": "t1",
+                        "data": {
+                            "id": "kx1",
+                            "subreddit": "rust",
+                            "body": "Has anyone tried tokio-console?",
+                            "author": "ferris",
+                            "permalink": "/r/rust/comments/abc/title/kx1/",
+                            "link_title": "Weekly questions thread",
+                            "created_utc": 1700000000.0
+                        }
+                    },
+                    {
+                        "kind": "t1",
+                        "data": {
+                            "id": "kx2",
+                            "subreddit": "rust",
+                            "body": "[deleted]",
+                            "permalink": "/r/rust/comments/abc/title/kx2/",
+                            "over_18": true,
+                            "created_utc": 1700000001.5
+                        }
+                    }
+                ]
+            }
+        }"#;
+
+        let listing: RedditListing<RedditComment> = serde_json::from_str(json).unwrap();
+        let comments: Vec<RedditComment> = listing.data.children.into_iter().map(|c| c.data).collect();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].id, "kx1");
+        assert_eq!(comments[0].link_title.as_deref(), Some("Weekly questions thread"));
+        assert!(!comments[0].over_18);
+        assert_eq!(comments[0].created_utc.timestamp(), 1700000000);
+        assert_eq!(comments[1].link_title, None);
+        assert!(comments[1].over_18);
+    }
+
+    #[test]
+    fn test_compile_keywords_skips_rejected_sets() {
+        use crate::keywords::KeywordMode;
+
+        let by_subreddit = HashMap::from([
+            (
+                "rust".to_string(),
+                vec![
+                    KeywordSet::from_stored(KeywordMode::Regex, ".*"),
+                    KeywordSet::from_stored(KeywordMode::Word, "tokio"),
+                ],
+            ),
+            ("golang".to_string(), vec![KeywordSet::from_stored(KeywordMode::Regex, ".*")]),
+        ]);
+        let compiled = compile_keywords(by_subreddit);
+        assert_eq!(compiled.len(), 1);
+        assert_eq!(compiled["rust"].find("tokio 2.0").as_deref(), Some("tokio"));
+    }
+
+    #[test]
+    fn test_build_comments_url() {
+        assert_eq!(
+            build_comments_url("https://www.reddit.com", "rust", 50),
+            "https://www.reddit.com/r/rust/comments.json?limit=50"
+        );
+    }
+}
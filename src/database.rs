@@ -1,22 +1,35 @@
-use anyhow::Result;
-use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
-use std::collections::HashMap;
-
-use crate::models::database::{EndpointKind, EndpointRow, NotifiedPostRow, SubscriptionRow};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::TryStreamExt;
+use sqlx::{sqlite::SqliteRow, AssertSqlSafe, Connection, Row, SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::Path;
+
+use crate::keywords::{format_keywords, KeywordMatcher, KeywordMode, KeywordSet};
+use crate::models::database::{
+    DeliveryFailures, DeliveryStats, DeliveryStatus, LastDeliveryError, StoredPost, EndpointKind, EndpointRow, IntegrityProblem,
+    IntegrityReport, NotifiedPostRow, SubscriptionRow, MAX_ENDPOINT_CONFIG_LEN, MAX_ENDPOINT_NOTE_LEN, NOTIFIED_POSTS_CSV_HEADER,
+    NewEndpoint, NewSubscription,
+};
+use crate::rising::{MetricSample, PostMetrics};
+use crate::search::{SearchQuery, SearchSubscription, ALL_SUBREDDITS};
+use crate::services::error::DbError;
 
 pub async fn unique_subreddits(pool: &SqlitePool) -> Result<Vec<String>> {
     let rows = sqlx::query(
         r#"
-        SELECT DISTINCT s.subreddit
+        SELECT DISTINCT lower(s.subreddit) AS subreddit
         FROM subscriptions s
         JOIN subscription_endpoints se ON se.subscription_id = s.id
         JOIN endpoints e ON e.id = se.endpoint_id
-        WHERE e.active = 1
+        WHERE e.active = 1 AND s.muted = 0 AND s.search_query IS NULL
         "#,
     )
     .fetch_all(pool)
     .await?;
 
+    // Lowercased in SQL, so "Rust" and "rust" are polled once
     let subs = rows
         .into_iter()
         .filter_map(|r| r.try_get::<String, _>("subreddit").ok())
@@ -26,8 +39,9 @@ pub async fn unique_subreddits(pool: &SqlitePool) -> Result<Vec<String>> {
 
 /// Fetch all subreddit-to-endpoints mappings in a single query
 ///
-/// Returns a HashMap where keys are subreddit names and values are vectors of active
-/// endpoints subscribed to that subreddit.
+/// Returns a HashMap where keys are lowercased subreddit names and values are vectors
+/// of active endpoints subscribed to that subreddit. Reddit names are case-insensitive,
+/// so "Rust" and "rust" subscriptions share one entry.
 ///
 /// This function is used by the combined poller to determine which endpoints should
 /// receive notifications for posts from each subreddit.
@@ -46,7 +60,7 @@ pub async fn all_subreddit_endpoint_mappings(
         FROM endpoints e
         JOIN subscription_endpoints se ON se.endpoint_id = e.id
         JOIN subscriptions s ON s.id = se.subscription_id
-        WHERE e.active = 1
+        WHERE e.active = 1 AND s.muted = 0 AND s.search_query IS NULL
         ORDER BY s.subreddit
         "#,
     )
@@ -83,7 +97,7 @@ pub async fn all_subreddit_endpoint_mappings(
         };
 
         mappings
-            .entry(subreddit)
+            .entry(subreddit.to_lowercase())
             .or_default()
             .push(endpoint);
     }
@@ -91,318 +105,529 @@ pub async fn all_subreddit_endpoint_mappings(
     Ok(mappings)
 }
 
-/// Returns true if the (subreddit, post_id) was newly inserted.
-pub async fn record_if_new(pool: &SqlitePool, subreddit: &str, post_id: &str) -> Result<bool> {
-    let res = sqlx::query(
+/// Subreddits for which at least one subscription wants stickied posts
+///
+/// A stickied post is only processed when some subscription for its subreddit
+/// has `skip_stickied` turned off.
+pub async fn subreddits_allowing_stickied(pool: &SqlitePool) -> Result<HashSet<String>> {
+    let rows = sqlx::query(
         r#"
-        INSERT OR IGNORE INTO notified_posts (subreddit, post_id)
-        VALUES (?1, ?2)
+        SELECT DISTINCT subreddit
+        FROM subscriptions
+        WHERE skip_stickied = 0 AND muted = 0 AND search_query IS NULL
         "#,
     )
-    .bind(subreddit)
-    .bind(post_id)
-    .execute(pool)
+    .fetch_all(pool)
     .await?;
 
-    Ok(res.rows_affected() == 1)
+    let subs = rows
+        .into_iter()
+        .filter_map(|r| r.try_get::<String, _>("subreddit").ok())
+        .map(|s| s.to_lowercase())
+        .collect();
+    Ok(subs)
 }
 
-// =============================================================================
-// TUI Database Functions
-// =============================================================================
-
-// --- Subscriptions CRUD ---
-
-/// List all subscriptions with metadata
-pub async fn list_subscriptions(pool: &SqlitePool) -> Result<Vec<SubscriptionRow>> {
+/// Subreddits with at least one active priority subscription
+///
+/// Posts from these subreddits are sent with the endpoint's mention.
+pub async fn priority_subreddits(pool: &SqlitePool) -> Result<HashSet<String>> {
     let rows = sqlx::query(
         r#"
-        SELECT
-            s.id,
-            s.subreddit,
-            s.created_at,
-            COUNT(se.endpoint_id) as endpoint_count
-        FROM subscriptions s
-        LEFT JOIN subscription_endpoints se ON se.subscription_id = s.id
-        GROUP BY s.id, s.subreddit, s.created_at
-        ORDER BY s.created_at DESC
+        SELECT DISTINCT subreddit
+        FROM subscriptions
+        WHERE priority = 1 AND muted = 0 AND search_query IS NULL
         "#,
     )
-    .map(|row: SqliteRow| SubscriptionRow {
-        id: row.get::<i64, _>("id"),
-        subreddit: row.get::<String, _>("subreddit"),
-        created_at: row.get::<String, _>("created_at"),
-    })
     .fetch_all(pool)
     .await?;
 
-    Ok(rows)
+    let subs = rows
+        .into_iter()
+        .filter_map(|r| r.try_get::<String, _>("subreddit").ok())
+        .map(|s| s.to_lowercase())
+        .collect();
+    Ok(subs)
 }
 
-/// Create a new subscription
-pub async fn create_subscription(pool: &SqlitePool, subreddit: &str) -> Result<i64> {
-    let res = sqlx::query(
+/// Score thresholds of unmuted subscriptions with score alerts turned on
+///
+/// Keyed by lowercased subreddit; thresholds from several subscriptions to the
+/// same subreddit are merged.
+pub async fn score_thresholds_by_subreddit(pool: &SqlitePool) -> Result<HashMap<String, Vec<i64>>> {
+    let rows = sqlx::query(
         r#"
-        INSERT INTO subscriptions (subreddit)
-        VALUES (?1)
+        SELECT subreddit, score_thresholds
+        FROM subscriptions
+        WHERE score_thresholds IS NOT NULL AND muted = 0
         "#,
     )
-    .bind(subreddit)
-    .execute(pool)
+    .fetch_all(pool)
     .await?;
 
-    Ok(res.last_insert_rowid())
+    let mut thresholds: HashMap<String, Vec<i64>> = HashMap::new();
+    for row in rows {
+        let subreddit: String = row.get("subreddit");
+        let parsed = crate::score_alerts::parse_score_thresholds(&row.get::<String, _>("score_thresholds"))?;
+        let merged = thresholds.entry(subreddit.to_lowercase()).or_default();
+        merged.extend(parsed);
+        merged.sort_unstable();
+        merged.dedup();
+    }
+    Ok(thresholds)
 }
 
-/// Delete a subscription (cascade deletes links)
-pub async fn delete_subscription(pool: &SqlitePool, id: i64) -> Result<()> {
+/// Set a subscription's score alert thresholds (an empty list turns alerts off)
+pub async fn set_subscription_score_thresholds(pool: &SqlitePool, id: i64, thresholds: &[i64]) -> Result<()> {
+    let value = (!thresholds.is_empty()).then(|| crate::score_alerts::format_score_thresholds(thresholds));
     sqlx::query(
         r#"
-        DELETE FROM subscriptions WHERE id = ?1
+        UPDATE subscriptions
+        SET score_thresholds = ?2
+        WHERE id = ?1
+        RETURNING id
         "#,
     )
     .bind(id)
-    .execute(pool)
+    .bind(value)
+    .fetch_one(pool)
     .await?;
 
     Ok(())
 }
 
-/// Get all endpoints linked to a subscription
-pub async fn get_subscription_endpoints(pool: &SqlitePool, subscription_id: i64) -> Result<Vec<EndpointRow>> {
-    let rows = sqlx::query(
+/// Store the score bucket a post reached, keeping the highest one seen
+///
+/// Returns the previously stored bucket, or `None` if the post wasn't tracked yet.
+pub async fn record_score_bucket(pool: &SqlitePool, subreddit: &str, post_id: &str, bucket: i64) -> Result<Option<i64>> {
+    let previous = sqlx::query(
         r#"
-        SELECT e.id, e.kind, e.config_json, e.active, e.note
-        FROM endpoints e
-        JOIN subscription_endpoints se ON se.endpoint_id = e.id
-        WHERE se.subscription_id = ?1
-        ORDER BY e.id
+        SELECT score_bucket FROM post_state WHERE subreddit = ?1 AND post_id = ?2
         "#,
     )
-    .bind(subscription_id)
-    .fetch_all(pool)
+    .bind(subreddit)
+    .bind(post_id)
+    .map(|row: SqliteRow| row.get::<i64, _>("score_bucket"))
+    .fetch_optional(pool)
     .await?;
 
-    // Parse each row and skip any with invalid endpoint kinds
-    let mut endpoints = Vec::new();
-    for row in rows {
-        let id = row.get::<i64, _>("id");
-        let kind_str = row.get::<String, _>("kind");
-
-        // Try to parse the kind - if it fails, log a warning and skip this endpoint
-        let kind = match kind_str.parse::<EndpointKind>() {
-            Ok(k) => k,
-            Err(_) => {
-                tracing::warn!("Invalid endpoint kind '{}' for endpoint id {} - skipping", kind_str, id);
-                continue; // Skip this endpoint
-            }
-        };
-
-        endpoints.push(EndpointRow {
-            id,
-            kind,
-            config_json: row.get::<String, _>("config_json"),
-            active: row.get::<i64, _>("active") != 0,
-            note: row.get::<Option<String>, _>("note"),
-        });
-    }
+    sqlx::query(
+        r#"
+        INSERT INTO post_state (subreddit, post_id, score_bucket, updated_at)
+        VALUES (?1, ?2, ?3, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+        ON CONFLICT (subreddit, post_id) DO UPDATE
+        SET score_bucket = max(score_bucket, excluded.score_bucket), updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(subreddit)
+    .bind(post_id)
+    .bind(bucket)
+    .execute(pool)
+    .await?;
 
-    Ok(endpoints)
+    Ok(previous)
 }
 
-// --- Endpoints CRUD ---
-
-/// List all endpoints with type and active status
-pub async fn list_endpoints(pool: &SqlitePool) -> Result<Vec<EndpointRow>> {
+/// Rising thresholds of unmuted subscriptions with rising alerts turned on
+///
+/// Keyed by lowercased subreddit; with several subscriptions to the same
+/// subreddit the lowest threshold wins.
+pub async fn rising_thresholds_by_subreddit(pool: &SqlitePool) -> Result<HashMap<String, i64>> {
     let rows = sqlx::query(
         r#"
-        SELECT id, kind, config_json, active, note
-        FROM endpoints
-        ORDER BY id
+        SELECT subreddit, rising_threshold
+        FROM subscriptions
+        WHERE rising_threshold IS NOT NULL AND muted = 0
         "#,
     )
     .fetch_all(pool)
     .await?;
 
-    // Parse each row and skip any with invalid endpoint kinds
-    let mut endpoints = Vec::new();
+    let mut thresholds: HashMap<String, i64> = HashMap::new();
     for row in rows {
-        let id = row.get::<i64, _>("id");
-        let kind_str = row.get::<String, _>("kind");
-
-        // Try to parse the kind - if it fails, log a warning and skip this endpoint
-        let kind = match kind_str.parse::<EndpointKind>() {
-            Ok(k) => k,
-            Err(_) => {
-                tracing::warn!("Invalid endpoint kind '{}' for endpoint id {} - skipping", kind_str, id);
-                continue; // Skip this endpoint
-            }
-        };
-
-        endpoints.push(EndpointRow {
-            id,
-            kind,
-            config_json: row.get::<String, _>("config_json"),
-            active: row.get::<i64, _>("active") != 0,
-            note: row.get::<Option<String>, _>("note"),
-        });
+        let subreddit: String = row.get("subreddit");
+        let threshold: i64 = row.get("rising_threshold");
+        thresholds
+            .entry(subreddit.to_lowercase())
+            .and_modify(|t| *t = (*t).min(threshold))
+            .or_insert(threshold);
     }
-
-    Ok(endpoints)
+    Ok(thresholds)
 }
 
-/// Get a single endpoint by ID
-pub async fn get_endpoint(pool: &SqlitePool, id: i64) -> Result<EndpointRow> {
-    let row = sqlx::query(
+/// Set a subscription's rising threshold in points per hour (`None` turns rising alerts off)
+pub async fn set_subscription_rising_threshold(pool: &SqlitePool, id: i64, threshold: Option<i64>) -> Result<()> {
+    sqlx::query(
         r#"
-        SELECT id, kind, config_json, active, note
-        FROM endpoints
+        UPDATE subscriptions
+        SET rising_threshold = ?2
         WHERE id = ?1
+        RETURNING id
         "#,
     )
     .bind(id)
+    .bind(threshold)
     .fetch_one(pool)
     .await?;
 
-    // Extract the fields from the database row
-    let endpoint_id = row.get::<i64, _>("id");
-    let kind_str = row.get::<String, _>("kind");
-
-    // Try to parse the kind - return an error if it's invalid (for single item, we can't skip)
-    let kind = kind_str
-        .parse::<EndpointKind>()
-        .map_err(|_| anyhow::anyhow!("Invalid endpoint kind '{}' for endpoint id {}", kind_str, endpoint_id))?;
-
-    Ok(EndpointRow {
-        id: endpoint_id,
-        kind,
-        config_json: row.get::<String, _>("config_json"),
-        active: row.get::<i64, _>("active") != 0,
-        note: row.get::<Option<String>, _>("note"),
-    })
+    Ok(())
 }
 
-/// Create a new endpoint
-pub async fn create_endpoint(pool: &SqlitePool, kind: &str, config_json: &str, note: Option<&str>) -> Result<i64> {
-    let res = sqlx::query(
+/// The stored metrics sample of a post, if it is being tracked
+pub async fn post_metrics(pool: &SqlitePool, subreddit: &str, post_id: &str) -> Result<Option<PostMetrics>> {
+    let row = sqlx::query(
         r#"
-        INSERT INTO endpoints (kind, config_json, note)
-        VALUES (?1, ?2, ?3)
+        SELECT score, num_comments, sampled_at, alerted
+        FROM post_metrics
+        WHERE subreddit = ?1 AND post_id = ?2
         "#,
     )
-    .bind(kind)
-    .bind(config_json)
-    .bind(note)
-    .execute(pool)
+    .bind(subreddit)
+    .bind(post_id)
+    .fetch_optional(pool)
     .await?;
 
-    Ok(res.last_insert_rowid())
+    match row {
+        Some(row) => Ok(Some(PostMetrics {
+            sample: MetricSample {
+                score: row.get("score"),
+                num_comments: row.get("num_comments"),
+                sampled_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("sampled_at"))?.with_timezone(&Utc),
+            },
+            alerted: row.get("alerted"),
+        })),
+        None => Ok(None),
+    }
 }
 
-/// Update an endpoint's configuration and note
-pub async fn update_endpoint(pool: &SqlitePool, id: i64, config_json: &str, note: Option<&str>) -> Result<()> {
+/// Store a post's latest metrics sample, replacing the previous one
+pub async fn record_post_metrics(pool: &SqlitePool, subreddit: &str, post_id: &str, metrics: &PostMetrics) -> Result<()> {
     sqlx::query(
         r#"
-        UPDATE endpoints
-        SET config_json = ?1, note = ?2
-        WHERE id = ?3
+        INSERT INTO post_metrics (subreddit, post_id, score, num_comments, sampled_at, alerted)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT (subreddit, post_id) DO UPDATE
+        SET score = excluded.score, num_comments = excluded.num_comments,
+            sampled_at = excluded.sampled_at, alerted = excluded.alerted
         "#,
     )
-    .bind(config_json)
-    .bind(note)
-    .bind(id)
+    .bind(subreddit)
+    .bind(post_id)
+    .bind(metrics.sample.score)
+    .bind(metrics.sample.num_comments)
+    .bind(metrics.sample.sampled_at.to_rfc3339_opts(SecondsFormat::Secs, true))
+    .bind(metrics.alerted)
     .execute(pool)
     .await?;
 
     Ok(())
 }
 
-/// Delete an endpoint (cascade deletes links)
-pub async fn delete_endpoint(pool: &SqlitePool, id: i64) -> Result<()> {
-    sqlx::query(
+/// Delete metrics samples not updated in the last `hours`
+///
+/// # Returns
+/// Number of samples deleted
+pub async fn prune_post_metrics(pool: &SqlitePool, hours: i64) -> Result<u64> {
+    let result = sqlx::query(
         r#"
-        DELETE FROM endpoints WHERE id = ?1
+        DELETE FROM post_metrics
+        WHERE sampled_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || ?1 || ' hours')
         "#,
     )
-    .bind(id)
+    .bind(hours)
     .execute(pool)
     .await?;
 
-    Ok(())
+    Ok(result.rows_affected())
 }
 
-/// Toggle an endpoint's active status, returns new status
-pub async fn toggle_endpoint_active(pool: &SqlitePool, id: i64) -> Result<bool> {
-    // Atomically toggle using SQL (1 - active flips 0->1 and 1->0)
-    let row = sqlx::query(
+/// Unmuted search subscriptions, each with its active endpoints
+///
+/// Searches whose endpoints are all inactive are left out.
+pub async fn search_subscriptions(pool: &SqlitePool) -> Result<Vec<SearchSubscription>> {
+    let rows = sqlx::query(
         r#"
-        UPDATE endpoints
-        SET active = 1 - active
-        WHERE id = ?1
-        RETURNING active
+        SELECT
+            s.id AS subscription_id,
+            s.subreddit,
+            s.search_query,
+            s.search_restrict_sr,
+            s.skip_stickied,
+            s.priority,
+            e.id,
+            e.kind,
+            e.config_json,
+            e.active,
+            e.note
+        FROM subscriptions s
+        JOIN subscription_endpoints se ON se.subscription_id = s.id
+        JOIN endpoints e ON e.id = se.endpoint_id
+        WHERE s.search_query IS NOT NULL AND s.muted = 0 AND e.active = 1
+        ORDER BY s.id, e.id
         "#,
     )
-    .bind(id)
-    .map(|row: SqliteRow| row.get::<i64, _>("active") != 0)
-    .fetch_one(pool)
+    .fetch_all(pool)
     .await?;
 
-    Ok(row)
+    let mut searches: Vec<SearchSubscription> = Vec::new();
+    for row in rows {
+        let subscription_id: i64 = row.get("subscription_id");
+        if searches.last().is_none_or(|s| s.id != subscription_id) {
+            let Some(search) = search_from_row(&row) else { continue };
+            searches.push(SearchSubscription {
+                id: subscription_id,
+                subreddit: row.get("subreddit"),
+                search,
+                skip_stickied: row.get::<i64, _>("skip_stickied") != 0,
+                priority: row.get::<i64, _>("priority") != 0,
+                endpoints: Vec::new(),
+            });
+        }
+
+        let id: i64 = row.get("id");
+        let kind_str: String = row.get("kind");
+        let Ok(kind) = kind_str.parse::<EndpointKind>() else {
+            tracing::warn!("Invalid endpoint kind '{}' for endpoint id {} - skipping", kind_str, id);
+            continue;
+        };
+        if let Some(search) = searches.last_mut() {
+            search.endpoints.push(EndpointRow {
+                id,
+                kind,
+                config_json: row.get("config_json"),
+                active: row.get::<i64, _>("active") != 0,
+                note: row.get("note"),
+            });
+        }
+    }
+    Ok(searches)
 }
 
-// --- Junction Table Management ---
+/// Comment keywords of unmuted subscriptions with comment polling turned on
+///
+/// Keyed by lowercased subreddit, with one set per subscription to it.
+pub async fn comment_keywords_by_subreddit(pool: &SqlitePool) -> Result<HashMap<String, Vec<KeywordSet>>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT subreddit, comment_keywords, comment_keyword_mode
+        FROM subscriptions
+        WHERE comment_keywords IS NOT NULL AND muted = 0
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
 
-/// Link a subscription to an endpoint
-pub async fn link_subscription_endpoint(pool: &SqlitePool, subscription_id: i64, endpoint_id: i64) -> Result<()> {
+    let mut keywords: HashMap<String, Vec<KeywordSet>> = HashMap::new();
+    for row in rows {
+        let subreddit: String = row.get("subreddit");
+        let mode: KeywordMode = row.get::<String, _>("comment_keyword_mode").parse().map_err(|e: String| anyhow!(e))?;
+        let set = KeywordSet::from_stored(mode, &row.get::<String, _>("comment_keywords"));
+        keywords.entry(subreddit.to_lowercase()).or_default().push(set);
+    }
+    Ok(keywords)
+}
+
+/// Set a subscription's comment keywords and how they match (`None` turns comment polling off)
+pub async fn set_subscription_comment_keywords(pool: &SqlitePool, id: i64, keywords: Option<&KeywordSet>) -> Result<()> {
+    let value = keywords.map(|set| format_keywords(&set.keywords));
+    let mode = keywords.map(|set| set.mode).unwrap_or_default();
     sqlx::query(
         r#"
-        INSERT OR IGNORE INTO subscription_endpoints (subscription_id, endpoint_id)
-        VALUES (?1, ?2)
+        UPDATE subscriptions
+        SET comment_keywords = ?2, comment_keyword_mode = ?3
+        WHERE id = ?1
+        RETURNING id
         "#,
     )
-    .bind(subscription_id)
-    .bind(endpoint_id)
-    .execute(pool)
+    .bind(id)
+    .bind(value)
+    .bind(mode.as_str())
+    .fetch_one(pool)
     .await?;
 
     Ok(())
 }
 
-/// Unlink a subscription from an endpoint
-pub async fn unlink_subscription_endpoint(pool: &SqlitePool, subscription_id: i64, endpoint_id: i64) -> Result<()> {
-    sqlx::query(
+/// Returns true if the (subreddit, comment_id) was newly inserted.
+pub async fn record_comment_if_new(pool: &SqlitePool, subreddit: &str, comment_id: &str) -> Result<bool> {
+    let res = sqlx::query(
         r#"
-        DELETE FROM subscription_endpoints
-        WHERE subscription_id = ?1 AND endpoint_id = ?2
+        INSERT OR IGNORE INTO notified_comments (subreddit, comment_id, first_seen_at)
+        VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
         "#,
     )
-    .bind(subscription_id)
-    .bind(endpoint_id)
+    .bind(subreddit)
+    .bind(comment_id)
     .execute(pool)
     .await?;
 
-    Ok(())
+    Ok(res.rows_affected() == 1)
 }
 
-// --- Logs ---
+/// Returns true if the (subreddit, post_id) was newly inserted.
+///
+/// The title and URL are stored so the notification can be re-sent later.
+pub async fn record_if_new(pool: &SqlitePool, subreddit: &str, post_id: &str, title: &str, url: &str) -> Result<bool> {
+    let res = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO notified_posts (subreddit, post_id, first_seen_at, title, url)
+        VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), ?3, ?4)
+        "#,
+    )
+    .bind(subreddit)
+    .bind(post_id)
+    .bind(title)
+    .bind(url)
+    .execute(pool)
+    .await?;
 
-/// List notified posts with pagination
-pub async fn list_notified_posts(pool: &SqlitePool, limit: i64, offset: i64) -> Result<Vec<NotifiedPostRow>> {
+    Ok(res.rows_affected() == 1)
+}
+
+/// The `limit` most recently notified posts, oldest first, optionally for one subreddit
+///
+/// Posts recorded before titles and URLs were stored are skipped.
+pub async fn recent_stored_posts(pool: &SqlitePool, limit: i64, subreddit: Option<&str>) -> Result<Vec<StoredPost>> {
     let rows = sqlx::query(
         r#"
-        SELECT id, subreddit, post_id, first_seen_at
+        SELECT subreddit, post_id, title, url
         FROM notified_posts
-        ORDER BY first_seen_at DESC
-        LIMIT ?1 OFFSET ?2
+        WHERE title IS NOT NULL AND url IS NOT NULL
+          AND (?2 IS NULL OR subreddit = ?2 COLLATE NOCASE)
+        ORDER BY id DESC
+        LIMIT ?1
         "#,
     )
     .bind(limit)
-    .bind(offset)
-    .map(|row: SqliteRow| NotifiedPostRow {
+    .bind(subreddit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .rev()
+        .map(|row| StoredPost {
+            subreddit: row.get("subreddit"),
+            post_id: row.get("post_id"),
+            title: row.get("title"),
+            url: row.get("url"),
+        })
+        .collect())
+}
+
+/// Mute every subscription to `subreddit`, whatever its case
+///
+/// # Returns
+/// Number of subscriptions that were newly muted
+pub async fn mute_subreddit(pool: &SqlitePool, subreddit: &str) -> Result<u64> {
+    let res = sqlx::query(
+        r#"
+        UPDATE subscriptions SET muted = 1
+        WHERE lower(subreddit) = lower(?1) AND muted = 0
+        "#,
+    )
+    .bind(subreddit)
+    .execute(pool)
+    .await?;
+    Ok(res.rows_affected())
+}
+
+/// Read a `meta` value, e.g. the poller's `last_poll_at`
+pub async fn get_meta(pool: &SqlitePool, key: &str) -> Result<Option<String>> {
+    let value = sqlx::query_scalar("SELECT value FROM meta WHERE key = ?1")
+        .bind(key)
+        .fetch_optional(pool)
+        .await?;
+    Ok(value)
+}
+
+/// Insert or replace a `meta` value
+pub async fn set_meta(pool: &SqlitePool, key: &str, value: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO meta (key, value)
+        VALUES (?1, ?2)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value
+        "#,
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Whether notifications are delivered (the global pause switch)
+///
+/// Defaults to enabled until `set_notifications_enabled` has been called.
+pub async fn notifications_enabled(pool: &SqlitePool) -> Result<bool> {
+    let value: Option<String> = sqlx::query_scalar("SELECT value FROM settings WHERE key = 'notifications_enabled'")
+        .fetch_optional(pool)
+        .await?;
+    Ok(value.is_none_or(|v| v != "0"))
+}
+
+/// Pause (`false`) or resume (`true`) delivery of all notifications
+pub async fn set_notifications_enabled(pool: &SqlitePool, enabled: bool) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value)
+        VALUES ('notifications_enabled', ?)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value
+        "#,
+    )
+    .bind(if enabled { "1" } else { "0" })
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Version of the subscription/endpoint configuration
+///
+/// Triggers bump it on every insert, update or delete of subscriptions,
+/// endpoints or their links, whoever makes the change.
+pub async fn config_version(pool: &SqlitePool) -> Result<i64> {
+    let value: Option<String> = sqlx::query_scalar("SELECT value FROM settings WHERE key = 'config_version'")
+        .fetch_optional(pool)
+        .await?;
+    Ok(value.and_then(|v| v.parse().ok()).unwrap_or(0))
+}
+
+// =============================================================================
+// TUI Database Functions
+// =============================================================================
+
+// --- Subscriptions CRUD ---
+
+/// List all subscriptions with metadata
+pub async fn list_subscriptions(pool: &SqlitePool) -> Result<Vec<SubscriptionRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            s.id,
+            s.subreddit,
+            s.created_at,
+            s.skip_stickied,
+            s.muted,
+            s.priority,
+            s.search_query,
+            s.search_restrict_sr,
+            COUNT(se.endpoint_id) as endpoint_count
+        FROM subscriptions s
+        LEFT JOIN subscription_endpoints se ON se.subscription_id = s.id
+        GROUP BY s.id, s.subreddit, s.created_at, s.skip_stickied, s.muted, s.priority, s.search_query, s.search_restrict_sr
+        ORDER BY s.created_at DESC
+        "#,
+    )
+    .map(|row: SqliteRow| SubscriptionRow {
         id: row.get::<i64, _>("id"),
         subreddit: row.get::<String, _>("subreddit"),
-        post_id: row.get::<String, _>("post_id"),
-        first_seen_at: row.get::<String, _>("first_seen_at"),
+        created_at: row.get::<String, _>("created_at"),
+        skip_stickied: row.get::<i64, _>("skip_stickied") != 0,
+        muted: row.get::<i64, _>("muted") != 0,
+        priority: row.get::<i64, _>("priority") != 0,
+        search: search_from_row(&row),
     })
     .fetch_all(pool)
     .await?;
@@ -410,37 +635,76 @@ pub async fn list_notified_posts(pool: &SqlitePool, limit: i64, offset: i64) ->
     Ok(rows)
 }
 
-/// List notified posts filtered by subreddit with pagination
-pub async fn list_notified_posts_by_subreddit(pool: &SqlitePool, subreddit: &str, limit: i64, offset: i64) -> Result<Vec<NotifiedPostRow>> {
+/// When each subreddit's most recent notified post was first seen
+pub async fn last_notified_at_per_subreddit(pool: &SqlitePool) -> Result<HashMap<String, DateTime<Utc>>> {
     let rows = sqlx::query(
         r#"
-        SELECT id, subreddit, post_id, first_seen_at
+        SELECT subreddit, MAX(first_seen_at) AS last_seen
         FROM notified_posts
-        WHERE subreddit = ?1
-        ORDER BY first_seen_at DESC
-        LIMIT ?2 OFFSET ?3
+        GROUP BY subreddit
         "#,
     )
-    .bind(subreddit)
-    .bind(limit)
-    .bind(offset)
-    .map(|row: SqliteRow| NotifiedPostRow {
-        id: row.get::<i64, _>("id"),
-        subreddit: row.get::<String, _>("subreddit"),
-        post_id: row.get::<String, _>("post_id"),
-        first_seen_at: row.get::<String, _>("first_seen_at"),
-    })
     .fetch_all(pool)
     .await?;
 
-    Ok(rows)
+    rows.iter()
+        .map(|row| {
+            let last_seen: String = row.get("last_seen");
+            Ok((
+                row.get::<String, _>("subreddit"),
+                DateTime::parse_from_rfc3339(&last_seen)?.with_timezone(&Utc),
+            ))
+        })
+        .collect()
 }
 
-/// Delete a notified post by ID
-pub async fn delete_notified_post(pool: &SqlitePool, id: i64) -> Result<()> {
+/// Create a new subscription
+pub async fn create_subscription(pool: &SqlitePool, subreddit: &str) -> Result<i64> {
+    let res = sqlx::query(
+        r#"
+        INSERT INTO subscriptions (subreddit)
+        VALUES (?1)
+        "#,
+    )
+    .bind(subreddit)
+    .execute(pool)
+    .await?;
+
+    Ok(res.last_insert_rowid())
+}
+
+/// Create a subscription to a Reddit search
+///
+/// A search across all of Reddit (`restrict_sr` off) is stored under `ALL_SUBREDDITS`
+/// whatever `subreddit` says.
+pub async fn create_search_subscription(pool: &SqlitePool, subreddit: &str, search: &SearchQuery) -> Result<i64> {
+    let subreddit = if search.restrict_sr { subreddit } else { ALL_SUBREDDITS };
+    let res = sqlx::query(
+        r#"
+        INSERT INTO subscriptions (subreddit, search_query, search_restrict_sr)
+        VALUES (?1, ?2, ?3)
+        "#,
+    )
+    .bind(subreddit)
+    .bind(&search.query)
+    .bind(search.restrict_sr)
+    .execute(pool)
+    .await?;
+
+    Ok(res.last_insert_rowid())
+}
+
+/// The saved search of a subscription row selecting `search_query` and `search_restrict_sr`
+fn search_from_row(row: &SqliteRow) -> Option<SearchQuery> {
+    let query = row.get::<Option<String>, _>("search_query")?;
+    Some(SearchQuery { query, restrict_sr: row.get::<i64, _>("search_restrict_sr") != 0 })
+}
+
+/// Delete a subscription (cascade deletes links)
+pub async fn delete_subscription(pool: &SqlitePool, id: i64) -> Result<()> {
     sqlx::query(
         r#"
-        DELETE FROM notified_posts WHERE id = ?1
+        DELETE FROM subscriptions WHERE id = ?1
         "#,
     )
     .bind(id)
@@ -450,49 +714,51 @@ pub async fn delete_notified_post(pool: &SqlitePool, id: i64) -> Result<()> {
     Ok(())
 }
 
-/// Clean up old notified posts, deleting records older than the specified number of days
-///
-/// This prevents unbounded growth of the notified_posts table. Since the application
-/// only notifies on posts within 24 hours of the current time (see poller.rs), we can
-/// safely delete older records without risk of duplicate notifications.
-///
-/// # Arguments
-/// * `pool` - Database connection pool
-/// * `days_to_keep` - Number of days of history to keep (default: 7)
-///
-/// # Returns
-/// Number of records deleted
-pub async fn cleanup_old_posts(pool: &SqlitePool, days_to_keep: i64) -> Result<u64> {
-    let result = sqlx::query(
+/// Toggle whether a subscription skips stickied posts, returns the new setting
+pub async fn toggle_subscription_skip_stickied(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let row = sqlx::query(
         r#"
-        DELETE FROM notified_posts
-        WHERE first_seen_at < datetime('now', '-' || ?1 || ' days')
+        UPDATE subscriptions
+        SET skip_stickied = 1 - skip_stickied
+        WHERE id = ?1
+        RETURNING skip_stickied
         "#,
     )
-    .bind(days_to_keep)
-    .execute(pool)
+    .bind(id)
+    .map(|row: SqliteRow| row.get::<i64, _>("skip_stickied") != 0)
+    .fetch_one(pool)
     .await?;
 
-    Ok(result.rows_affected())
+    Ok(row)
 }
 
-/// Get statistics about notified posts per subreddit
+/// Unmuted subscriptions with no active linked endpoint
 ///
-/// Useful for monitoring database growth and cleanup effectiveness
-pub async fn get_post_statistics(pool: &SqlitePool) -> Result<Vec<(String, i64)>> {
+/// These are dropped by `unique_subreddits`, so they are never polled and
+/// never notify; listing them lets the user spot the misconfiguration.
+pub async fn subscriptions_without_active_endpoints(pool: &SqlitePool) -> Result<Vec<SubscriptionRow>> {
     let rows = sqlx::query(
         r#"
-        SELECT subreddit, COUNT(*) as count
-        FROM notified_posts
-        GROUP BY subreddit
-        ORDER BY count DESC
+        SELECT s.id, s.subreddit, s.created_at, s.skip_stickied, s.muted, s.priority, s.search_query, s.search_restrict_sr
+        FROM subscriptions s
+        WHERE s.muted = 0
+          AND NOT EXISTS (
+            SELECT 1
+            FROM subscription_endpoints se
+            JOIN endpoints e ON e.id = se.endpoint_id
+            WHERE se.subscription_id = s.id AND e.active = 1
+          )
+        ORDER BY s.subreddit
         "#,
     )
-    .map(|row: sqlx::sqlite::SqliteRow| {
-        (
-            row.get::<String, _>("subreddit"),
-            row.get::<i64, _>("count"),
-        )
+    .map(|row: SqliteRow| SubscriptionRow {
+        id: row.get::<i64, _>("id"),
+        subreddit: row.get::<String, _>("subreddit"),
+        created_at: row.get::<String, _>("created_at"),
+        skip_stickied: row.get::<i64, _>("skip_stickied") != 0,
+        muted: row.get::<i64, _>("muted") != 0,
+        priority: row.get::<i64, _>("priority") != 0,
+        search: search_from_row(&row),
     })
     .fetch_all(pool)
     .await?;
@@ -500,54 +766,1794 @@ pub async fn get_post_statistics(pool: &SqlitePool) -> Result<Vec<(String, i64)>
     Ok(rows)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[tokio::test]
-    async fn test_cleanup_old_posts() {
-        // Create an in-memory test database
-        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
-        sqlx::migrate!().run(&pool).await.unwrap();
+/// Find orphaned links, unparseable subscription settings and broken active endpoints
+pub async fn integrity_problems(pool: &SqlitePool) -> Result<IntegrityReport> {
+    let orphaned_links = sqlx::query(
+        r#"
+        SELECT se.subscription_id, se.endpoint_id
+        FROM subscription_endpoints se
+        LEFT JOIN subscriptions s ON s.id = se.subscription_id
+        LEFT JOIN endpoints e ON e.id = se.endpoint_id
+        WHERE s.id IS NULL OR e.id IS NULL
+        ORDER BY se.subscription_id, se.endpoint_id
+        "#,
+    )
+    .map(|row: SqliteRow| (row.get::<i64, _>("subscription_id"), row.get::<i64, _>("endpoint_id")))
+    .fetch_all(pool)
+    .await?;
 
-        // Insert test data with different ages
-        // Recent posts (within 7 days) - should NOT be deleted
-        for i in 1..=3 {
-            sqlx::query(
-                "INSERT INTO notified_posts (subreddit, post_id, first_seen_at) VALUES (?1, ?2, datetime('now', ?3))",
-            )
-            .bind("testA")
-            .bind(format!("recent_{}", i))
-            .bind(format!("-{} days", i))
-            .execute(&pool)
-            .await
-            .unwrap();
+    let mut invalid_subscriptions = Vec::new();
+    let rows = sqlx::query(
+        r#"
+        SELECT id, score_thresholds, rising_threshold, comment_keywords, comment_keyword_mode
+        FROM subscriptions
+        WHERE score_thresholds IS NOT NULL OR rising_threshold IS NOT NULL OR comment_keywords IS NOT NULL
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    for row in rows {
+        let id: i64 = row.get("id");
+        if let Some(thresholds) = row.get::<Option<String>, _>("score_thresholds") {
+            if let Err(e) = crate::score_alerts::parse_score_thresholds(&thresholds) {
+                invalid_subscriptions.push(IntegrityProblem { id, field: "score_thresholds", reason: e.to_string() });
+            }
         }
+        if let Some(threshold) = row.get::<Option<i64>, _>("rising_threshold") {
+            if threshold <= 0 {
+                let reason = format!("invalid rising threshold {} (expected a positive number)", threshold);
+                invalid_subscriptions.push(IntegrityProblem { id, field: "rising_threshold", reason });
+            }
+        }
+        if let Some(keywords) = row.get::<Option<String>, _>("comment_keywords") {
+            let parsed = row
+                .get::<String, _>("comment_keyword_mode")
+                .parse()
+                .and_then(|mode| KeywordMatcher::compile(&[KeywordSet::from_stored(mode, &keywords)]));
+            if let Err(reason) = parsed {
+                invalid_subscriptions.push(IntegrityProblem { id, field: "comment_keywords", reason });
+            }
+        }
+    }
 
-        // Old posts (older than 7 days) - should be deleted
-        for i in 1..=4 {
-            sqlx::query(
-                "INSERT INTO notified_posts (subreddit, post_id, first_seen_at) VALUES (?1, ?2, datetime('now', ?3))",
-            )
-            .bind("testB")
-            .bind(format!("old_{}", i))
-            .bind(format!("-{} days", 7 + i))
-            .execute(&pool)
-            .await
-            .unwrap();
+    let mut invalid_endpoints = Vec::new();
+    let rows = sqlx::query("SELECT id, kind, config_json FROM endpoints WHERE active = 1 ORDER BY id")
+        .fetch_all(pool)
+        .await?;
+    for row in rows {
+        let id: i64 = row.get("id");
+        let kind: String = row.get("kind");
+        match kind.parse::<EndpointKind>() {
+            Ok(kind) => {
+                if let Err(reason) = crate::models::validate_endpoint_config(&kind, &row.get::<String, _>("config_json")) {
+                    invalid_endpoints.push(IntegrityProblem { id, field: "config_json", reason });
+                }
+            }
+            Err(_) => {
+                let reason = format!("unknown endpoint kind '{}'", kind);
+                invalid_endpoints.push(IntegrityProblem { id, field: "kind", reason });
+            }
         }
+    }
 
-        // Clean up posts older than 7 days
-        let deleted = cleanup_old_posts(&pool, 7).await.unwrap();
+    Ok(IntegrityReport { orphaned_links, invalid_subscriptions, invalid_endpoints })
+}
 
-        // Should delete 4 old posts
-        assert_eq!(deleted, 4);
+/// Find integrity problems and repair them in one transaction
+///
+/// Orphaned links are deleted, unparseable subscription settings are reset
+/// (turning that alert off) and broken endpoints are deactivated, so they can
+/// be fixed in the TUI without the poller failing on them meanwhile.
+///
+/// # Returns
+/// The problems that were repaired
+pub async fn fix_integrity_problems(pool: &SqlitePool) -> Result<IntegrityReport> {
+    let report = integrity_problems(pool).await?;
+    let mut tx = pool.begin().await?;
+
+    for (subscription_id, endpoint_id) in &report.orphaned_links {
+        sqlx::query("DELETE FROM subscription_endpoints WHERE subscription_id = ?1 AND endpoint_id = ?2")
+            .bind(subscription_id)
+            .bind(endpoint_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for problem in &report.invalid_subscriptions {
+        let reset = match problem.field {
+            "score_thresholds" => "UPDATE subscriptions SET score_thresholds = NULL WHERE id = ?1",
+            "comment_keywords" => "UPDATE subscriptions SET comment_keywords = NULL WHERE id = ?1",
+            _ => "UPDATE subscriptions SET rising_threshold = NULL WHERE id = ?1",
+        };
+        sqlx::query(reset)
+            .bind(problem.id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for problem in &report.invalid_endpoints {
+        sqlx::query("UPDATE endpoints SET active = 0 WHERE id = ?1")
+            .bind(problem.id)
+            .execute(&mut *tx)
+            .await?;
+    }
 
-        // Verify 3 recent posts remain
-        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notified_posts")
-            .fetch_one(&pool)
-            .await
-            .unwrap();
-        assert_eq!(remaining, 3);
+    tx.commit().await?;
+    Ok(report)
+}
+
+/// Toggle whether a subscription is high priority, returns the new setting
+pub async fn toggle_subscription_priority(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let row = sqlx::query(
+        r#"
+        UPDATE subscriptions
+        SET priority = 1 - priority
+        WHERE id = ?1
+        RETURNING priority
+        "#,
+    )
+    .bind(id)
+    .map(|row: SqliteRow| row.get::<i64, _>("priority") != 0)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Toggle a subscription's muted status, returns the new status
+pub async fn toggle_subscription_muted(pool: &SqlitePool, id: i64) -> Result<bool> {
+    let row = sqlx::query(
+        r#"
+        UPDATE subscriptions
+        SET muted = 1 - muted
+        WHERE id = ?1
+        RETURNING muted
+        "#,
+    )
+    .bind(id)
+    .map(|row: SqliteRow| row.get::<i64, _>("muted") != 0)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Get all endpoints linked to a subscription
+pub async fn get_subscription_endpoints(pool: &SqlitePool, subscription_id: i64) -> Result<Vec<EndpointRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT e.id, e.kind, e.config_json, e.active, e.note
+        FROM endpoints e
+        JOIN subscription_endpoints se ON se.endpoint_id = e.id
+        WHERE se.subscription_id = ?1
+        ORDER BY e.id
+        "#,
+    )
+    .bind(subscription_id)
+    .fetch_all(pool)
+    .await?;
+
+    // Parse each row and skip any with invalid endpoint kinds
+    let mut endpoints = Vec::new();
+    for row in rows {
+        let id = row.get::<i64, _>("id");
+        let kind_str = row.get::<String, _>("kind");
+
+        // Try to parse the kind - if it fails, log a warning and skip this endpoint
+        let kind = match kind_str.parse::<EndpointKind>() {
+            Ok(k) => k,
+            Err(_) => {
+                tracing::warn!("Invalid endpoint kind '{}' for endpoint id {} - skipping", kind_str, id);
+                continue; // Skip this endpoint
+            }
+        };
+
+        endpoints.push(EndpointRow {
+            id,
+            kind,
+            config_json: row.get::<String, _>("config_json"),
+            active: row.get::<i64, _>("active") != 0,
+            note: row.get::<Option<String>, _>("note"),
+        });
+    }
+
+    Ok(endpoints)
+}
+
+// --- Endpoints CRUD ---
+
+/// List all endpoints with type and active status
+pub async fn list_endpoints(pool: &SqlitePool) -> Result<Vec<EndpointRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, kind, config_json, active, note
+        FROM endpoints
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Parse each row and skip any with invalid endpoint kinds
+    let mut endpoints = Vec::new();
+    for row in rows {
+        let id = row.get::<i64, _>("id");
+        let kind_str = row.get::<String, _>("kind");
+
+        // Try to parse the kind - if it fails, log a warning and skip this endpoint
+        let kind = match kind_str.parse::<EndpointKind>() {
+            Ok(k) => k,
+            Err(_) => {
+                tracing::warn!("Invalid endpoint kind '{}' for endpoint id {} - skipping", kind_str, id);
+                continue; // Skip this endpoint
+            }
+        };
+
+        endpoints.push(EndpointRow {
+            id,
+            kind,
+            config_json: row.get::<String, _>("config_json"),
+            active: row.get::<i64, _>("active") != 0,
+            note: row.get::<Option<String>, _>("note"),
+        });
+    }
+
+    Ok(endpoints)
+}
+
+/// Get a single endpoint by ID
+pub async fn get_endpoint(pool: &SqlitePool, id: i64) -> Result<EndpointRow> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, kind, config_json, active, note
+        FROM endpoints
+        WHERE id = ?1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+
+    // Extract the fields from the database row
+    let endpoint_id = row.get::<i64, _>("id");
+    let kind_str = row.get::<String, _>("kind");
+
+    // Try to parse the kind - return an error if it's invalid (for single item, we can't skip)
+    let kind = kind_str
+        .parse::<EndpointKind>()
+        .map_err(|_| anyhow::anyhow!("Invalid endpoint kind '{}' for endpoint id {}", kind_str, endpoint_id))?;
+
+    Ok(EndpointRow {
+        id: endpoint_id,
+        kind,
+        config_json: row.get::<String, _>("config_json"),
+        active: row.get::<i64, _>("active") != 0,
+        note: row.get::<Option<String>, _>("note"),
+    })
+}
+
+/// Create a new endpoint
+/// Check endpoint input against the size limits and normalize the note
+///
+/// Trailing whitespace is trimmed from the note and a blank note becomes `None`.
+/// Oversized values are rejected with `DbError::Invalid` so a pasted blob can't
+/// bloat the row or break the Endpoints table layout.
+pub fn check_endpoint_input<'a>(config_json: &str, note: Option<&'a str>) -> Result<Option<&'a str>> {
+    if config_json.len() > MAX_ENDPOINT_CONFIG_LEN {
+        return Err(DbError::Invalid(format!(
+            "config is {} bytes, the limit is {}",
+            config_json.len(),
+            MAX_ENDPOINT_CONFIG_LEN
+        ))
+        .into());
+    }
+
+    let note = note.map(str::trim_end).filter(|n| !n.is_empty());
+    if let Some(note) = note {
+        let chars = note.chars().count();
+        if chars > MAX_ENDPOINT_NOTE_LEN {
+            return Err(DbError::Invalid(format!(
+                "note is {} characters, the limit is {}",
+                chars, MAX_ENDPOINT_NOTE_LEN
+            ))
+            .into());
+        }
+    }
+
+    Ok(note)
+}
+
+pub async fn create_endpoint(pool: &SqlitePool, kind: &str, config_json: &str, note: Option<&str>) -> Result<i64> {
+    let note = check_endpoint_input(config_json, note)?;
+    let res = sqlx::query(
+        r#"
+        INSERT INTO endpoints (kind, config_json, note)
+        VALUES (?1, ?2, ?3)
+        "#,
+    )
+    .bind(kind)
+    .bind(config_json)
+    .bind(note)
+    .execute(pool)
+    .await?;
+
+    Ok(res.last_insert_rowid())
+}
+
+/// Update an endpoint's configuration and note
+pub async fn update_endpoint(pool: &SqlitePool, id: i64, config_json: &str, note: Option<&str>) -> Result<()> {
+    let note = check_endpoint_input(config_json, note)?;
+    sqlx::query(
+        r#"
+        UPDATE endpoints
+        SET config_json = ?1, note = ?2
+        WHERE id = ?3
+        "#,
+    )
+    .bind(config_json)
+    .bind(note)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Delete an endpoint (cascade deletes links)
+pub async fn delete_endpoint(pool: &SqlitePool, id: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM endpoints WHERE id = ?1
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Toggle an endpoint's active status, returns new status
+pub async fn toggle_endpoint_active(pool: &SqlitePool, id: i64) -> Result<bool> {
+    // Atomically toggle using SQL (1 - active flips 0->1 and 1->0)
+    let row = sqlx::query(
+        r#"
+        UPDATE endpoints
+        SET active = 1 - active
+        WHERE id = ?1
+        RETURNING active
+        "#,
+    )
+    .bind(id)
+    .map(|row: SqliteRow| row.get::<i64, _>("active") != 0)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row)
+}
+
+// --- Junction Table Management ---
+
+/// Link a subscription to an endpoint
+pub async fn link_subscription_endpoint(pool: &SqlitePool, subscription_id: i64, endpoint_id: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO subscription_endpoints (subscription_id, endpoint_id)
+        VALUES (?1, ?2)
+        "#,
+    )
+    .bind(subscription_id)
+    .bind(endpoint_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Unlink a subscription from an endpoint
+pub async fn unlink_subscription_endpoint(pool: &SqlitePool, subscription_id: i64, endpoint_id: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM subscription_endpoints
+        WHERE subscription_id = ?1 AND endpoint_id = ?2
+        "#,
+    )
+    .bind(subscription_id)
+    .bind(endpoint_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Create endpoints, subscriptions and their links in one transaction
+///
+/// `NewSubscription::endpoints` index into `endpoints`. Any failure rolls
+/// everything back, so an import is either complete or not there at all.
+pub async fn import_rows(pool: &SqlitePool, endpoints: &[NewEndpoint], subscriptions: &[NewSubscription]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let mut endpoint_ids = Vec::with_capacity(endpoints.len());
+    for endpoint in endpoints {
+        let note = check_endpoint_input(&endpoint.config_json, endpoint.note.as_deref())?;
+        let res = sqlx::query(
+            r#"
+            INSERT INTO endpoints (kind, config_json, note)
+            VALUES (?1, ?2, ?3)
+            "#,
+        )
+        .bind(&endpoint.kind)
+        .bind(&endpoint.config_json)
+        .bind(note)
+        .execute(&mut *tx)
+        .await?;
+        endpoint_ids.push(res.last_insert_rowid());
+    }
+
+    for subscription in subscriptions {
+        let res = sqlx::query(
+            r#"
+            INSERT INTO subscriptions (subreddit)
+            VALUES (?1)
+            "#,
+        )
+        .bind(&subscription.subreddit)
+        .execute(&mut *tx)
+        .await?;
+        let subscription_id = res.last_insert_rowid();
+
+        for &index in &subscription.endpoints {
+            let endpoint_id = *endpoint_ids
+                .get(index)
+                .ok_or_else(|| DbError::Invalid(format!("no endpoint #{} in this import", index)))?;
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO subscription_endpoints (subscription_id, endpoint_id)
+                VALUES (?1, ?2)
+                "#,
+            )
+            .bind(subscription_id)
+            .bind(endpoint_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Replace a subscription's endpoint links with exactly `endpoint_ids`
+///
+/// Runs in one transaction, so a failure leaves the previous links in place.
+pub async fn set_subscription_endpoints(pool: &SqlitePool, subscription_id: i64, endpoint_ids: &[i64]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM subscription_endpoints WHERE subscription_id = ?1
+        "#,
+    )
+    .bind(subscription_id)
+    .execute(&mut *tx)
+    .await?;
+
+    for endpoint_id in endpoint_ids {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO subscription_endpoints (subscription_id, endpoint_id)
+            VALUES (?1, ?2)
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(endpoint_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// --- Logs ---
+
+/// List notified posts with pagination
+pub async fn list_notified_posts(pool: &SqlitePool, limit: i64, offset: i64) -> Result<Vec<NotifiedPostRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, subreddit, post_id, first_seen_at, title, url
+        FROM notified_posts
+        ORDER BY first_seen_at DESC
+        LIMIT ?1 OFFSET ?2
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .map(|row: SqliteRow| NotifiedPostRow {
+        id: row.get::<i64, _>("id"),
+        subreddit: row.get::<String, _>("subreddit"),
+        post_id: row.get::<String, _>("post_id"),
+        first_seen_at: row.get::<String, _>("first_seen_at"),
+        title: row.get::<Option<String>, _>("title"),
+        url: row.get::<Option<String>, _>("url"),
+    })
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Write the whole notification history to `writer` as CSV, oldest first
+///
+/// Rows are streamed from the database and written one at a time, so memory
+/// use doesn't grow with the size of the table. Returns the number of rows written.
+pub async fn export_notified_posts_stream(pool: &SqlitePool, writer: &mut (dyn Write + Send)) -> Result<u64> {
+    writer.write_all(NOTIFIED_POSTS_CSV_HEADER.as_bytes())?;
+
+    let mut rows = sqlx::query(
+        r#"
+        SELECT id, subreddit, post_id, first_seen_at, title, url
+        FROM notified_posts
+        ORDER BY id
+        "#,
+    )
+    .map(|row: SqliteRow| NotifiedPostRow {
+        id: row.get::<i64, _>("id"),
+        subreddit: row.get::<String, _>("subreddit"),
+        post_id: row.get::<String, _>("post_id"),
+        first_seen_at: row.get::<String, _>("first_seen_at"),
+        title: row.get::<Option<String>, _>("title"),
+        url: row.get::<Option<String>, _>("url"),
+    })
+    .fetch(pool);
+
+    let mut written = 0;
+    while let Some(post) = rows.try_next().await? {
+        writer.write_all(post.to_csv_line().as_bytes())?;
+        written += 1;
+    }
+    writer.flush()?;
+
+    Ok(written)
+}
+
+/// List notified posts filtered by subreddit with pagination
+pub async fn list_notified_posts_by_subreddit(pool: &SqlitePool, subreddit: &str, limit: i64, offset: i64) -> Result<Vec<NotifiedPostRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, subreddit, post_id, first_seen_at, title, url
+        FROM notified_posts
+        WHERE subreddit = ?1
+        ORDER BY first_seen_at DESC
+        LIMIT ?2 OFFSET ?3
+        "#,
+    )
+    .bind(subreddit)
+    .bind(limit)
+    .bind(offset)
+    .map(|row: SqliteRow| NotifiedPostRow {
+        id: row.get::<i64, _>("id"),
+        subreddit: row.get::<String, _>("subreddit"),
+        post_id: row.get::<String, _>("post_id"),
+        first_seen_at: row.get::<String, _>("first_seen_at"),
+        title: row.get::<Option<String>, _>("title"),
+        url: row.get::<Option<String>, _>("url"),
+    })
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Delete a notified post by ID
+pub async fn delete_notified_post(pool: &SqlitePool, id: i64) -> Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM notified_posts WHERE id = ?1
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Delete every notified post recorded for `subreddit` (exact name, as listed in the logs)
+///
+/// Score alert state, rising metrics and notified comments for the subreddit are cleared too.
+/// Posts still inside the poller's 24h window can be notified again.
+///
+/// # Returns
+/// Number of notified posts deleted
+pub async fn delete_notified_posts_by_subreddit(pool: &SqlitePool, subreddit: &str) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query("DELETE FROM notified_posts WHERE subreddit = ?1")
+        .bind(subreddit)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM post_state WHERE subreddit = ?1")
+        .bind(subreddit)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM notified_comments WHERE subreddit = ?1")
+        .bind(subreddit)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM post_metrics WHERE subreddit = ?1")
+        .bind(subreddit)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Clean up old notified posts, deleting records older than the specified number of days
+///
+/// This prevents unbounded growth of the notified_posts table. Since the application
+/// only notifies on posts within 24 hours of the current time (see poller.rs), we can
+/// safely delete older records without risk of duplicate notifications.
+///
+/// # Arguments
+/// * `pool` - Database connection pool
+/// * `days_to_keep` - Number of days of history to keep (default: 7)
+///
+/// # Returns
+/// Number of records deleted
+pub async fn cleanup_old_posts(pool: &SqlitePool, days_to_keep: i64) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM notified_posts
+        WHERE first_seen_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || ?1 || ' days')
+        "#,
+    )
+    .bind(days_to_keep)
+    .execute(pool)
+    .await?;
+
+    // Score alert state for the same posts; not counted in the result
+    sqlx::query(
+        r#"
+        DELETE FROM post_state
+        WHERE updated_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || ?1 || ' days')
+        "#,
+    )
+    .bind(days_to_keep)
+    .execute(pool)
+    .await?;
+
+    // Notified comments follow the same window as posts; not counted either
+    sqlx::query(
+        r#"
+        DELETE FROM notified_comments
+        WHERE first_seen_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || ?1 || ' days')
+        "#,
+    )
+    .bind(days_to_keep)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Record the outcome of one notification delivery
+pub async fn record_delivery(
+    pool: &SqlitePool,
+    endpoint_id: i64,
+    post_id: &str,
+    status: DeliveryStatus,
+    latency_ms: i64,
+    error: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO deliveries (endpoint_id, post_id, status, latency_ms, error)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(endpoint_id)
+    .bind(post_id)
+    .bind(status.as_str())
+    .bind(latency_ms)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Per-endpoint delivery totals and p95 latency over the last `days` days
+///
+/// Endpoints without deliveries in the window are omitted. Ordered by endpoint ID.
+pub async fn delivery_stats(pool: &SqlitePool, days: i64) -> Result<Vec<DeliveryStats>> {
+    let rows = sqlx::query(
+        r#"
+        WITH ranked AS (
+            SELECT
+                endpoint_id,
+                status,
+                latency_ms,
+                ROW_NUMBER() OVER (PARTITION BY endpoint_id ORDER BY latency_ms) AS rn,
+                COUNT(*) OVER (PARTITION BY endpoint_id) AS n
+            FROM deliveries
+            WHERE delivered_at >= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || ?1 || ' days')
+        )
+        SELECT
+            endpoint_id,
+            COUNT(*) AS total,
+            SUM(CASE WHEN status = 'sent' THEN 1 ELSE 0 END) AS succeeded,
+            MIN(CASE WHEN rn * 100 >= n * 95 THEN latency_ms END) AS p95_latency_ms
+        FROM ranked
+        GROUP BY endpoint_id
+        ORDER BY endpoint_id
+        "#,
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| DeliveryStats {
+            endpoint_id: row.get("endpoint_id"),
+            total: row.get("total"),
+            succeeded: row.get("succeeded"),
+            p95_latency_ms: row.get("p95_latency_ms"),
+        })
+        .collect())
+}
+
+/// Endpoints with failed deliveries since `since`, most failures first
+///
+/// Each entry carries the failure count and the time and error text of the
+/// endpoint's most recent failure. Endpoints with no failures are omitted.
+pub async fn recent_delivery_failures(pool: &SqlitePool, since: DateTime<Utc>) -> Result<Vec<DeliveryFailures>> {
+    let rows = sqlx::query(
+        r#"
+        WITH failed AS (
+            SELECT
+                endpoint_id,
+                delivered_at,
+                error,
+                ROW_NUMBER() OVER (PARTITION BY endpoint_id ORDER BY id DESC) AS rn,
+                COUNT(*) OVER (PARTITION BY endpoint_id) AS failures
+            FROM deliveries
+            WHERE status = 'failed' AND delivered_at >= ?1
+        )
+        SELECT endpoint_id, failures, delivered_at, error
+        FROM failed
+        WHERE rn = 1
+        ORDER BY failures DESC, endpoint_id
+        "#,
+    )
+    .bind(since.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter()
+        .map(|row| {
+            let last_failed_at: String = row.get("delivered_at");
+            Ok(DeliveryFailures {
+                endpoint_id: row.get("endpoint_id"),
+                failures: row.get("failures"),
+                last_failed_at: DateTime::parse_from_rfc3339(&last_failed_at)?.with_timezone(&Utc),
+                last_error: row.get("error"),
+            })
+        })
+        .collect()
+}
+
+/// The most recent failed delivery to `endpoint_id`, with the post it was for
+///
+/// The post's title and link come from `notified_posts` when they were
+/// stored; comment alerts and older rows only have the ID.
+pub async fn last_delivery_error(pool: &SqlitePool, endpoint_id: i64) -> Result<Option<LastDeliveryError>> {
+    let row = sqlx::query(
+        r#"
+        SELECT
+            d.delivered_at,
+            d.post_id,
+            d.error,
+            p.subreddit,
+            p.title,
+            p.url,
+            EXISTS (
+                SELECT 1 FROM deliveries later
+                WHERE later.endpoint_id = d.endpoint_id AND later.status = 'sent' AND later.id > d.id
+            ) AS resolved
+        FROM deliveries d
+        LEFT JOIN notified_posts p ON p.id = (
+            SELECT MAX(id) FROM notified_posts
+            WHERE post_id = d.post_id AND title IS NOT NULL AND url IS NOT NULL
+        )
+        WHERE d.endpoint_id = ?1 AND d.status = 'failed'
+        ORDER BY d.id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(endpoint_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let failed_at: String = row.get("delivered_at");
+    let post_id: String = row.get("post_id");
+    let post = match (row.get::<Option<String>, _>("subreddit"), row.get::<Option<String>, _>("title"), row.get::<Option<String>, _>("url")) {
+        (Some(subreddit), Some(title), Some(url)) => Some(StoredPost { subreddit, post_id: post_id.clone(), title, url }),
+        _ => None,
+    };
+    Ok(Some(LastDeliveryError {
+        endpoint_id,
+        failed_at: DateTime::parse_from_rfc3339(&failed_at)?.with_timezone(&Utc),
+        error: row.get("error"),
+        post_id,
+        post,
+        resolved: row.get("resolved"),
+    }))
+}
+
+/// Delete delivery records older than `days_to_keep` days
+///
+/// # Returns
+/// Number of records deleted
+pub async fn cleanup_old_deliveries(pool: &SqlitePool, days_to_keep: i64) -> Result<u64> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM deliveries
+        WHERE delivered_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || ?1 || ' days')
+        "#,
+    )
+    .bind(days_to_keep)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Get statistics about notified posts per subreddit
+///
+/// Useful for monitoring database growth and cleanup effectiveness
+pub async fn get_post_statistics(pool: &SqlitePool) -> Result<Vec<(String, i64)>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT subreddit, COUNT(*) as count
+        FROM notified_posts
+        GROUP BY subreddit
+        ORDER BY count DESC
+        "#,
+    )
+    .map(|row: sqlx::sqlite::SqliteRow| {
+        (
+            row.get::<String, _>("subreddit"),
+            row.get::<i64, _>("count"),
+        )
+    })
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+// --- Backup and restore ---
+
+/// Tables `restore_from` copies, parents before the tables referencing them
+///
+/// `meta` describes the running daemon rather than the configuration, so it
+/// keeps its current values.
+const RESTORED_TABLES: &[&str] = &[
+    "subscriptions",
+    "endpoints",
+    "subscription_endpoints",
+    "notified_posts",
+    "deliveries",
+    "post_state",
+    "notified_comments",
+    "post_metrics",
+    "settings",
+];
+
+/// Write a consistent copy of the database to a new file at `path`
+///
+/// Uses `VACUUM INTO`, which reads through SQLite like any other query, so
+/// it's safe while the daemon is writing (unlike copying the file with its WAL).
+pub async fn backup_to(pool: &SqlitePool, path: &Path) -> Result<()> {
+    if path.exists() {
+        anyhow::bail!("{} already exists", path.display());
+    }
+    sqlx::query("VACUUM INTO ?1")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Replace the database's contents with those of a backup made by `backup_to`
+///
+/// The backup must be at the same schema version. Everything is copied in one
+/// transaction, so a failed restore leaves the database as it was. The config
+/// version is bumped so a running poller reloads its subscriptions.
+pub async fn restore_from(pool: &SqlitePool, path: &Path) -> Result<()> {
+    if !path.is_file() {
+        anyhow::bail!("{} does not exist", path.display());
+    }
+
+    let mut conn = pool.acquire().await?;
+    sqlx::query("ATTACH DATABASE ?1 AS backup")
+        .bind(path.to_string_lossy().into_owned())
+        .execute(&mut *conn)
+        .await?;
+    let result = copy_from_backup(&mut conn).await;
+    sqlx::query("DETACH DATABASE backup").execute(&mut *conn).await?;
+    result
+}
+
+/// Copy `RESTORED_TABLES` from the attached `backup` schema into `main`
+async fn copy_from_backup(conn: &mut SqliteConnection) -> Result<()> {
+    let ours: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM main._sqlx_migrations WHERE success = 1")
+        .fetch_one(&mut *conn)
+        .await?;
+    let theirs: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM backup._sqlx_migrations WHERE success = 1")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| anyhow::anyhow!("not a reddit-notifier database: {}", e))?;
+    if ours != theirs {
+        anyhow::bail!(
+            "backup is at schema version {}, the database at {}",
+            theirs.unwrap_or_default(),
+            ours.unwrap_or_default()
+        );
+    }
+
+    let mut tx = conn.begin().await?;
+    let config_version: Option<String> = sqlx::query_scalar("SELECT value FROM settings WHERE key = 'config_version'")
+        .fetch_optional(&mut *tx)
+        .await?;
+    for table in RESTORED_TABLES.iter().rev() {
+        sqlx::query(AssertSqlSafe(format!("DELETE FROM main.{}", table))).execute(&mut *tx).await?;
+    }
+    for table in RESTORED_TABLES {
+        sqlx::query(AssertSqlSafe(format!("INSERT INTO main.{0} SELECT * FROM backup.{0}", table)))
+            .execute(&mut *tx)
+            .await?;
+    }
+    let before = config_version.and_then(|v| v.parse::<i64>().ok()).unwrap_or(0);
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value) VALUES ('config_version', ?1 + 1)
+        ON CONFLICT (key) DO UPDATE SET value = max(CAST(value AS INTEGER), ?1) + 1
+        "#,
+    )
+    .bind(before)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_delete_notified_posts_by_subreddit() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        for (subreddit, post_id) in [("rust", "a"), ("rust", "b"), ("golang", "c")] {
+            assert!(record_if_new(&pool, subreddit, post_id, "title", "https://redd.it/x").await.unwrap());
+        }
+        record_score_bucket(&pool, "rust", "a", 100).await.unwrap();
+        record_comment_if_new(&pool, "rust", "k1").await.unwrap();
+
+        assert_eq!(delete_notified_posts_by_subreddit(&pool, "rust").await.unwrap(), 2);
+
+        // Only r/rust was cleared, including its score and comment state
+        let remaining = list_notified_posts(&pool, 10, 0).await.unwrap();
+        assert_eq!(remaining.iter().map(|p| p.post_id.as_str()).collect::<Vec<_>>(), vec!["c"]);
+        assert_eq!(record_score_bucket(&pool, "rust", "a", 100).await.unwrap(), None);
+        assert!(record_comment_if_new(&pool, "rust", "k1").await.unwrap());
+        assert!(record_if_new(&pool, "rust", "a", "title", "").await.unwrap());
+
+        assert_eq!(delete_notified_posts_by_subreddit(&pool, "python").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_posts() {
+        // Create an in-memory test database
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        // Insert test data with different ages
+        // Recent posts (within 7 days) - should NOT be deleted
+        for i in 1..=3 {
+            sqlx::query(
+                "INSERT INTO notified_posts (subreddit, post_id, first_seen_at) VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?3))",
+            )
+            .bind("testA")
+            .bind(format!("recent_{}", i))
+            .bind(format!("-{} days", i))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Old posts (older than 7 days) - should be deleted
+        for i in 1..=4 {
+            sqlx::query(
+                "INSERT INTO notified_posts (subreddit, post_id, first_seen_at) VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?3))",
+            )
+            .bind("testB")
+            .bind(format!("old_{}", i))
+            .bind(format!("-{} days", 7 + i))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Clean up posts older than 7 days
+        let deleted = cleanup_old_posts(&pool, 7).await.unwrap();
+
+        // Should delete 4 old posts
+        assert_eq!(deleted, 4);
+
+        // Verify 3 recent posts remain
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notified_posts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 3);
+    }
+
+    #[tokio::test]
+    async fn test_post_counts_and_last_notified_per_subreddit() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        for (subreddit, post_id, age) in [("rust", "a", "-3 days"), ("rust", "b", "-2 hours"), ("golang", "c", "-5 days")] {
+            sqlx::query(
+                "INSERT INTO notified_posts (subreddit, post_id, first_seen_at) VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?3))",
+            )
+            .bind(subreddit)
+            .bind(post_id)
+            .bind(age)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            get_post_statistics(&pool).await.unwrap(),
+            vec![("rust".to_string(), 2), ("golang".to_string(), 1)]
+        );
+
+        let last = last_notified_at_per_subreddit(&pool).await.unwrap();
+        assert_eq!(last.len(), 2);
+        assert_eq!((Utc::now() - last["rust"]).num_hours(), 2);
+        assert_eq!((Utc::now() - last["golang"]).num_days(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_recent_stored_posts_newest_n_oldest_first() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        // A post recorded before titles were stored can't be re-sent
+        sqlx::query("INSERT INTO notified_posts (subreddit, post_id) VALUES ('rust', 'legacy')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (subreddit, id) in [("rust", "a"), ("golang", "b"), ("Rust", "c")] {
+            record_if_new(&pool, subreddit, id, &format!("post {}", id), "https://redd.it/x").await.unwrap();
+        }
+
+        let ids = |posts: Vec<StoredPost>| posts.into_iter().map(|p| p.post_id).collect::<Vec<_>>();
+        assert_eq!(ids(recent_stored_posts(&pool, 2, None).await.unwrap()), vec!["b", "c"]);
+        assert_eq!(ids(recent_stored_posts(&pool, 10, Some("RUST")).await.unwrap()), vec!["a", "c"]);
+
+        let post = recent_stored_posts(&pool, 1, None).await.unwrap().remove(0);
+        assert_eq!((post.title.as_str(), post.url.as_str()), ("post c", "https://redd.it/x"));
+    }
+
+    #[tokio::test]
+    async fn test_first_seen_at_is_iso_utc() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let before = chrono::Utc::now() - chrono::TimeDelta::seconds(1);
+        assert!(record_if_new(&pool, "rust", "abc", "Hello", "https://redd.it/abc").await.unwrap());
+        let after = chrono::Utc::now() + chrono::TimeDelta::seconds(1);
+
+        let post = list_notified_posts(&pool, 10, 0).await.unwrap().remove(0);
+        assert!(post.first_seen_at.ends_with('Z'), "{}", post.first_seen_at);
+        let seen = chrono::DateTime::parse_from_rfc3339(&post.first_seen_at).unwrap();
+        assert_eq!(seen.offset().local_minus_utc(), 0);
+        assert!(seen >= before && seen <= after);
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_size_limits_enforced() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let big_config = format!(r#"{{"webhook_url":"{}"}}"#, "x".repeat(MAX_ENDPOINT_CONFIG_LEN));
+        let err = create_endpoint(&pool, "discord", &big_config, None).await.unwrap_err();
+        assert!(matches!(DbError::from_anyhow(&err), Some(DbError::Invalid(_))));
+
+        let long_note = "n".repeat(MAX_ENDPOINT_NOTE_LEN + 1);
+        let err = create_endpoint(&pool, "discord", "{}", Some(&long_note)).await.unwrap_err();
+        assert!(matches!(DbError::from_anyhow(&err), Some(DbError::Invalid(_))));
+
+        // At the limit (after trimming trailing whitespace) is fine
+        let note = format!("{}   \n", "n".repeat(MAX_ENDPOINT_NOTE_LEN));
+        let id = create_endpoint(&pool, "discord", "{}", Some(&note)).await.unwrap();
+        let ep = get_endpoint(&pool, id).await.unwrap();
+        assert_eq!(ep.note.as_deref(), Some("n".repeat(MAX_ENDPOINT_NOTE_LEN).as_str()));
+
+        let err = update_endpoint(&pool, id, &big_config, None).await.unwrap_err();
+        assert!(matches!(DbError::from_anyhow(&err), Some(DbError::Invalid(_))));
+
+        update_endpoint(&pool, id, "{}", Some("   ")).await.unwrap();
+        assert_eq!(get_endpoint(&pool, id).await.unwrap().note, None);
+    }
+
+    #[tokio::test]
+    async fn test_mute_subreddit() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        create_subscription(&pool, "gone").await.unwrap();
+        create_subscription(&pool, "rust").await.unwrap();
+
+        assert_eq!(mute_subreddit(&pool, "gone").await.unwrap(), 1);
+        assert_eq!(mute_subreddit(&pool, "gone").await.unwrap(), 0);
+
+        let subs = list_subscriptions(&pool).await.unwrap();
+        assert!(subs.iter().find(|s| s.subreddit == "gone").unwrap().muted);
+        assert!(!subs.iter().find(|s| s.subreddit == "rust").unwrap().muted);
+    }
+
+    #[tokio::test]
+    async fn test_mixed_case_subscriptions_polled_and_muted_together() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        let endpoint = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com"}"#, None)
+            .await
+            .unwrap();
+        for subreddit in ["Rust", "rust", "AskReddit"] {
+            let id = create_subscription(&pool, subreddit).await.unwrap();
+            link_subscription_endpoint(&pool, id, endpoint).await.unwrap();
+        }
+
+        let mut subreddits = unique_subreddits(&pool).await.unwrap();
+        subreddits.sort();
+        assert_eq!(subreddits, vec!["askreddit".to_string(), "rust".to_string()]);
+
+        // The poller mutes by the lowercased name it polls
+        assert_eq!(mute_subreddit(&pool, "askreddit").await.unwrap(), 1);
+        assert_eq!(mute_subreddit(&pool, "rust").await.unwrap(), 2);
+        assert!(list_subscriptions(&pool).await.unwrap().iter().all(|s| s.muted));
+        assert!(unique_subreddits(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_meta_upsert_and_read() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        assert_eq!(get_meta(&pool, "last_poll_at").await.unwrap(), None);
+
+        set_meta(&pool, "last_poll_at", "2026-07-01T09:00:00Z").await.unwrap();
+        set_meta(&pool, "last_poll_at", "2026-07-01T09:05:00Z").await.unwrap();
+        set_meta(&pool, "last_success_at", "2026-07-01T09:00:00Z").await.unwrap();
+
+        assert_eq!(get_meta(&pool, "last_poll_at").await.unwrap().as_deref(), Some("2026-07-01T09:05:00Z"));
+        assert_eq!(get_meta(&pool, "last_success_at").await.unwrap().as_deref(), Some("2026-07-01T09:00:00Z"));
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM meta").fetch_one(&pool).await.unwrap();
+        assert_eq!(rows, 2);
+    }
+
+    /// Insert a delivery `days_ago` days in the past
+    async fn seed_delivery(pool: &SqlitePool, endpoint_id: i64, status: &str, latency_ms: i64, days_ago: i64) {
+        sqlx::query(
+            r#"
+            INSERT INTO deliveries (delivered_at, endpoint_id, post_id, status, latency_ms)
+            VALUES (strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || ? || ' days'), ?, 'p', ?, ?)
+            "#,
+        )
+        .bind(days_ago)
+        .bind(endpoint_id)
+        .bind(status)
+        .bind(latency_ms)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    /// Insert a failed delivery `minutes_ago` minutes in the past
+    async fn seed_failure(pool: &SqlitePool, endpoint_id: i64, error: &str, minutes_ago: i64) {
+        sqlx::query(
+            r#"
+            INSERT INTO deliveries (delivered_at, endpoint_id, post_id, status, latency_ms, error)
+            VALUES (strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-' || ? || ' minutes'), ?, 'p', 'failed', 100, ?)
+            "#,
+        )
+        .bind(minutes_ago)
+        .bind(endpoint_id)
+        .bind(error)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recent_delivery_failures_grouped_and_windowed() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        let discord = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://discord.com/api/webhooks/1/a"}"#, None)
+            .await
+            .unwrap();
+        let pushover = create_endpoint(&pool, "pushover", r#"{"token":"t","user":"u"}"#, None).await.unwrap();
+
+        seed_failure(&pool, discord, "HTTP 404", 600).await;
+        seed_failure(&pool, discord, "HTTP 429", 30).await;
+        seed_failure(&pool, discord, "HTTP 500", 10).await;
+        seed_failure(&pool, pushover, "invalid token", 3000).await;
+        seed_delivery(&pool, pushover, "sent", 100, 0).await;
+
+        let day = recent_delivery_failures(&pool, Utc::now() - chrono::TimeDelta::days(1)).await.unwrap();
+        assert_eq!(day.len(), 1);
+        assert_eq!((day[0].endpoint_id, day[0].failures), (discord, 3));
+        assert_eq!(day[0].last_error.as_deref(), Some("HTTP 500"));
+        assert!((Utc::now() - day[0].last_failed_at).num_minutes() >= 9);
+
+        let hour = recent_delivery_failures(&pool, Utc::now() - chrono::TimeDelta::hours(1)).await.unwrap();
+        assert_eq!(hour[0].failures, 2);
+
+        // The older pushover failure shows up in a wider window, ranked below discord
+        let week = recent_delivery_failures(&pool, Utc::now() - chrono::TimeDelta::days(7)).await.unwrap();
+        let ids: Vec<i64> = week.iter().map(|f| f.endpoint_id).collect();
+        assert_eq!(ids, vec![discord, pushover]);
+        assert_eq!(week[1].last_error.as_deref(), Some("invalid token"));
+    }
+
+    #[tokio::test]
+    async fn test_last_delivery_error_with_post() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        let discord = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://discord.com/api/webhooks/1/a"}"#, None)
+            .await
+            .unwrap();
+        assert_eq!(last_delivery_error(&pool, discord).await.unwrap(), None);
+
+        record_if_new(&pool, "rust", "abc", "Rust 1.90", "https://www.reddit.com/r/rust/comments/abc/").await.unwrap();
+        record_delivery(&pool, discord, "old", DeliveryStatus::Failed, 100, Some("HTTP 404")).await.unwrap();
+        record_delivery(&pool, discord, "abc", DeliveryStatus::Failed, 100, Some("HTTP 500: upstream")).await.unwrap();
+
+        let last = last_delivery_error(&pool, discord).await.unwrap().unwrap();
+        assert_eq!(last.error.as_deref(), Some("HTTP 500: upstream"));
+        assert_eq!(last.post_id, "abc");
+        assert_eq!(last.post.as_ref().map(|p| p.title.as_str()), Some("Rust 1.90"));
+        assert!(!last.resolved);
+
+        // A later success marks it resolved; an unknown post has no details
+        record_delivery(&pool, discord, "def", DeliveryStatus::Sent, 100, None).await.unwrap();
+        assert!(last_delivery_error(&pool, discord).await.unwrap().unwrap().resolved);
+        record_delivery(&pool, discord, "ghi", DeliveryStatus::Failed, 100, Some("timeout")).await.unwrap();
+        let last = last_delivery_error(&pool, discord).await.unwrap().unwrap();
+        assert_eq!((last.post_id.as_str(), last.post, last.resolved), ("ghi", None, false));
+    }
+
+    #[tokio::test]
+    async fn test_delivery_stats_aggregates_per_endpoint() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        let discord = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://discord.com/api/webhooks/1/a"}"#, None)
+            .await
+            .unwrap();
+        let pushover = create_endpoint(&pool, "pushover", r#"{"token":"t","user":"u"}"#, None).await.unwrap();
+
+        // Discord: 20 deliveries at 10..=200ms, the two slowest failed
+        for i in 1..=20 {
+            let status = if i > 18 { "failed" } else { "sent" };
+            seed_delivery(&pool, discord, status, i * 10, 0).await;
+        }
+        // Pushover: one recent success, plus an old failure outside the window
+        record_delivery(&pool, pushover, "abc", DeliveryStatus::Sent, 300, None).await.unwrap();
+        seed_delivery(&pool, pushover, "failed", 5000, 10).await;
+
+        let stats = delivery_stats(&pool, 7).await.unwrap();
+        assert_eq!(
+            stats,
+            vec![
+                DeliveryStats { endpoint_id: discord, total: 20, succeeded: 18, p95_latency_ms: Some(190) },
+                DeliveryStats { endpoint_id: pushover, total: 1, succeeded: 1, p95_latency_ms: Some(300) },
+            ]
+        );
+        assert!((stats[0].success_rate() - 0.9).abs() < f64::EPSILON);
+
+        // A wider window includes the old failure
+        let stats = delivery_stats(&pool, 30).await.unwrap();
+        assert_eq!(stats[1].total, 2);
+        assert_eq!(stats[1].p95_latency_ms, Some(5000));
+
+        assert_eq!(cleanup_old_deliveries(&pool, 7).await.unwrap(), 1);
+        assert_eq!(delivery_stats(&pool, 30).await.unwrap()[1].total, 1);
+
+        // Deleting an endpoint removes its history
+        delete_endpoint(&pool, discord).await.unwrap();
+        assert_eq!(delivery_stats(&pool, 30).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_notifications_enabled_defaults_on_and_persists() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        assert!(notifications_enabled(&pool).await.unwrap());
+
+        set_notifications_enabled(&pool, false).await.unwrap();
+        assert!(!notifications_enabled(&pool).await.unwrap());
+
+        set_notifications_enabled(&pool, true).await.unwrap();
+        assert!(notifications_enabled(&pool).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_skip_stickied_defaults_on_and_toggles() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let rust = create_subscription(&pool, "rust").await.unwrap();
+        create_subscription(&pool, "golang").await.unwrap();
+
+        let subs = list_subscriptions(&pool).await.unwrap();
+        assert!(subs.iter().all(|s| s.skip_stickied));
+        assert!(subreddits_allowing_stickied(&pool).await.unwrap().is_empty());
+
+        assert!(!toggle_subscription_skip_stickied(&pool, rust).await.unwrap());
+        let allowed = subreddits_allowing_stickied(&pool).await.unwrap();
+        assert_eq!(allowed, HashSet::from(["rust".to_string()]));
+
+        assert!(toggle_subscription_skip_stickied(&pool, rust).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_export_streams_every_row() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        for i in 0..2500 {
+            record_if_new(&pool, if i % 2 == 0 { "rust" } else { "golang" }, &format!("post{}", i), "t", "u")
+                .await
+                .unwrap();
+        }
+
+        let mut out = Vec::new();
+        let written = export_notified_posts_stream(&pool, &mut out).await.unwrap();
+        assert_eq!(written, 2500);
+
+        let csv = String::from_utf8(out).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(NOTIFIED_POSTS_CSV_HEADER.trim_end()));
+        assert!(lines.next().unwrap().starts_with("1,rust,post0,"));
+        assert_eq!(lines.count(), 2499);
+    }
+
+    #[tokio::test]
+    async fn test_score_thresholds_and_post_state() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let rust = create_subscription(&pool, "Rust").await.unwrap();
+        let rust_again = create_subscription(&pool, "rust").await.unwrap();
+        create_subscription(&pool, "golang").await.unwrap();
+
+        assert!(score_thresholds_by_subreddit(&pool).await.unwrap().is_empty());
+        set_subscription_score_thresholds(&pool, rust, &[100, 1000]).await.unwrap();
+        set_subscription_score_thresholds(&pool, rust_again, &[500, 100]).await.unwrap();
+        let thresholds = score_thresholds_by_subreddit(&pool).await.unwrap();
+        assert_eq!(thresholds, HashMap::from([("rust".to_string(), vec![100, 500, 1000])]));
+        assert!(set_subscription_score_thresholds(&pool, 999, &[1]).await.is_err());
+
+        set_subscription_score_thresholds(&pool, rust, &[]).await.unwrap();
+        set_subscription_score_thresholds(&pool, rust_again, &[]).await.unwrap();
+        assert!(score_thresholds_by_subreddit(&pool).await.unwrap().is_empty());
+
+        // The stored bucket only ever rises
+        assert_eq!(record_score_bucket(&pool, "rust", "p1", 0).await.unwrap(), None);
+        assert_eq!(record_score_bucket(&pool, "rust", "p1", 2).await.unwrap(), Some(0));
+        assert_eq!(record_score_bucket(&pool, "rust", "p1", 1).await.unwrap(), Some(2));
+        assert_eq!(record_score_bucket(&pool, "rust", "p1", 1).await.unwrap(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_rising_thresholds_and_post_metrics() {
+        use chrono::{TimeDelta, Timelike};
+
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let rust = create_subscription(&pool, "Rust").await.unwrap();
+        let rust_again = create_subscription(&pool, "rust").await.unwrap();
+
+        assert!(rising_thresholds_by_subreddit(&pool).await.unwrap().is_empty());
+        set_subscription_rising_threshold(&pool, rust, Some(300)).await.unwrap();
+        set_subscription_rising_threshold(&pool, rust_again, Some(200)).await.unwrap();
+        let thresholds = rising_thresholds_by_subreddit(&pool).await.unwrap();
+        assert_eq!(thresholds, HashMap::from([("rust".to_string(), 200)]));
+        assert!(set_subscription_rising_threshold(&pool, 999, Some(1)).await.is_err());
+        set_subscription_rising_threshold(&pool, rust_again, None).await.unwrap();
+        assert_eq!(rising_thresholds_by_subreddit(&pool).await.unwrap()["rust"], 300);
+
+        // Only the latest sample is kept, and stale ones are pruned
+        assert_eq!(post_metrics(&pool, "rust", "p1").await.unwrap(), None);
+        let now = Utc::now().with_nanosecond(0).unwrap();
+        let sample = |score, sampled_at| PostMetrics {
+            sample: MetricSample { score, num_comments: 3, sampled_at },
+            alerted: false,
+        };
+        record_post_metrics(&pool, "rust", "p1", &sample(10, now)).await.unwrap();
+        let latest = PostMetrics { alerted: true, ..sample(50, now) };
+        record_post_metrics(&pool, "rust", "p1", &latest).await.unwrap();
+        assert_eq!(post_metrics(&pool, "rust", "p1").await.unwrap(), Some(latest));
+
+        record_post_metrics(&pool, "rust", "old", &sample(10, now - TimeDelta::hours(7))).await.unwrap();
+        assert_eq!(prune_post_metrics(&pool, 6).await.unwrap(), 1);
+        assert_eq!(post_metrics(&pool, "rust", "old").await.unwrap(), None);
+        assert!(post_metrics(&pool, "rust", "p1").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_priority_defaults_off_and_toggles() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let rust = create_subscription(&pool, "rust").await.unwrap();
+        create_subscription(&pool, "golang").await.unwrap();
+
+        assert!(list_subscriptions(&pool).await.unwrap().iter().all(|s| !s.priority));
+        assert!(priority_subreddits(&pool).await.unwrap().is_empty());
+
+        assert!(toggle_subscription_priority(&pool, rust).await.unwrap());
+        assert_eq!(priority_subreddits(&pool).await.unwrap(), HashSet::from(["rust".to_string()]));
+
+        // A muted priority subscription is not polled, so it can't raise the priority
+        toggle_subscription_muted(&pool, rust).await.unwrap();
+        assert!(priority_subreddits(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_muted_subscription_excluded_from_poller_queries() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let endpoint = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com"}"#, None)
+            .await
+            .unwrap();
+        let rust = create_subscription(&pool, "rust").await.unwrap();
+        let golang = create_subscription(&pool, "golang").await.unwrap();
+        link_subscription_endpoint(&pool, rust, endpoint).await.unwrap();
+        link_subscription_endpoint(&pool, golang, endpoint).await.unwrap();
+
+        assert!(toggle_subscription_muted(&pool, rust).await.unwrap());
+
+        assert_eq!(unique_subreddits(&pool).await.unwrap(), vec!["golang".to_string()]);
+        let mappings = all_subreddit_endpoint_mappings(&pool).await.unwrap();
+        assert!(!mappings.contains_key("rust"));
+        assert!(mappings.contains_key("golang"));
+
+        // Links survive muting and come back on unmute
+        assert_eq!(get_subscription_endpoints(&pool, rust).await.unwrap().len(), 1);
+        assert!(!toggle_subscription_muted(&pool, rust).await.unwrap());
+        assert!(all_subreddit_endpoint_mappings(&pool).await.unwrap().contains_key("rust"));
+    }
+
+    #[tokio::test]
+    async fn test_mappings_keyed_by_lowercase_subreddit() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let a = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com/a"}"#, None)
+            .await
+            .unwrap();
+        let b = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com/b"}"#, None)
+            .await
+            .unwrap();
+        let upper = create_subscription(&pool, "Rust").await.unwrap();
+        let lower = create_subscription(&pool, "rust").await.unwrap();
+        link_subscription_endpoint(&pool, upper, a).await.unwrap();
+        link_subscription_endpoint(&pool, lower, b).await.unwrap();
+
+        let mappings = all_subreddit_endpoint_mappings(&pool).await.unwrap();
+        assert_eq!(mappings.len(), 1);
+        assert_eq!(mappings["rust"].len(), 2);
+        assert!(subreddits_allowing_stickied(&pool).await.unwrap().iter().all(|s| s == "rust"));
+    }
+
+    #[tokio::test]
+    async fn test_integrity_problems_detected_and_fixed() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let endpoint = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com/a"}"#, None)
+            .await
+            .unwrap();
+        let rust = create_subscription(&pool, "rust").await.unwrap();
+        let golang = create_subscription(&pool, "golang").await.unwrap();
+        link_subscription_endpoint(&pool, rust, endpoint).await.unwrap();
+        link_subscription_endpoint(&pool, golang, endpoint).await.unwrap();
+        assert!(integrity_problems(&pool).await.unwrap().is_empty());
+
+        // Rows cascades and validation would have prevented
+        let mut conn = pool.acquire().await.unwrap();
+        sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await.unwrap();
+        sqlx::query("DELETE FROM subscriptions WHERE id = ?1").bind(golang).execute(&mut *conn).await.unwrap();
+        sqlx::query("INSERT INTO subscription_endpoints (subscription_id, endpoint_id) VALUES (?1, 999)")
+            .bind(rust)
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        sqlx::query("PRAGMA ignore_check_constraints = ON").execute(&mut *conn).await.unwrap();
+        let unknown: i64 = sqlx::query_scalar("INSERT INTO endpoints (kind, config_json, active) VALUES ('slack', '{}', 1) RETURNING id")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        sqlx::query("PRAGMA ignore_check_constraints = OFF").execute(&mut *conn).await.unwrap();
+        sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await.unwrap();
+        drop(conn);
+        sqlx::query(
+            "UPDATE subscriptions SET score_thresholds = 'lots', rising_threshold = 0, comment_keywords = '.*', comment_keyword_mode = 'regex' WHERE id = ?1",
+        )
+            .bind(rust)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let broken: i64 = sqlx::query_scalar("INSERT INTO endpoints (kind, config_json, active) VALUES ('discord', '{}', 1) RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let report = integrity_problems(&pool).await.unwrap();
+        assert_eq!(report.orphaned_links, vec![(rust, 999), (golang, endpoint)]);
+        let fields = |problems: &[IntegrityProblem]| problems.iter().map(|p| (p.id, p.field)).collect::<Vec<_>>();
+        assert_eq!(
+            fields(&report.invalid_subscriptions),
+            vec![
+                (rust, "score_thresholds"),
+                (rust, "rising_threshold"),
+                (rust, "comment_keywords")
+            ]
+        );
+        assert_eq!(fields(&report.invalid_endpoints), vec![(unknown, "kind"), (broken, "config_json")]);
+        assert_eq!(report.len(), 7);
+
+        // Fixing repairs everything it reported, and the good link survives
+        assert_eq!(fix_integrity_problems(&pool).await.unwrap(), report);
+        assert!(integrity_problems(&pool).await.unwrap().is_empty());
+        let links: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM subscription_endpoints").fetch_one(&pool).await.unwrap();
+        assert_eq!(links, 1);
+        assert!(rising_thresholds_by_subreddit(&pool).await.unwrap().is_empty());
+        assert!(comment_keywords_by_subreddit(&pool).await.unwrap().is_empty());
+        assert!(get_endpoint(&pool, broken).await.map(|ep| !ep.active).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_backup_is_readable_and_restores_row_counts() {
+        // A file database: `VACUUM INTO` from an in-memory one writes to memory too
+        let source = std::env::temp_dir().join(format!("reddit-notifier-backup-source-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&source);
+        let url = format!("sqlite://{}", source.display());
+        let options = crate::db_connection::read_write_options(&url, &Default::default()).unwrap();
+        let pool = SqlitePool::connect_with(options).await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let endpoint = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com/a"}"#, None)
+            .await
+            .unwrap();
+        for subreddit in ["rust", "golang"] {
+            let id = create_subscription(&pool, subreddit).await.unwrap();
+            link_subscription_endpoint(&pool, id, endpoint).await.unwrap();
+            record_if_new(&pool, subreddit, &format!("{}_1", subreddit), "Hello", "https://redd.it/x").await.unwrap();
+        }
+
+        async fn row_counts(pool: &SqlitePool) -> Vec<i64> {
+            let mut counts = Vec::new();
+            for table in RESTORED_TABLES {
+                let sql = format!("SELECT COUNT(*) FROM {}", table);
+                counts.push(sqlx::query_scalar(AssertSqlSafe(sql)).fetch_one(pool).await.unwrap());
+            }
+            counts
+        }
+        let original = row_counts(&pool).await;
+
+        let path = std::env::temp_dir().join(format!("reddit-notifier-backup-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        backup_to(&pool, &path).await.unwrap();
+        assert!(backup_to(&pool, &path).await.unwrap_err().to_string().contains("already exists"));
+
+        // The backup opens as a database of its own with the same rows
+        let copy = SqlitePool::connect(&format!("sqlite://{}", path.display())).await.unwrap();
+        assert_eq!(row_counts(&copy).await, original);
+        copy.close().await;
+
+        // Restoring undoes later changes and makes the poller reload
+        let version = config_version(&pool).await.unwrap();
+        let subs = list_subscriptions(&pool).await.unwrap();
+        delete_subscription(&pool, subs[0].id).await.unwrap();
+        assert_ne!(row_counts(&pool).await, original);
+        restore_from(&pool, &path).await.unwrap();
+        assert_eq!(row_counts(&pool).await, original);
+        assert!(config_version(&pool).await.unwrap() > version + 1);
+        std::fs::remove_file(&path).unwrap();
+
+        let not_a_db = std::env::temp_dir().join(format!("reddit-notifier-not-a-backup-{}.db", std::process::id()));
+        std::fs::write(&not_a_db, "hello").unwrap();
+        assert!(restore_from(&pool, &not_a_db).await.is_err());
+        std::fs::remove_file(&not_a_db).unwrap();
+        assert_eq!(row_counts(&pool).await, original);
+
+        pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", source.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_without_active_endpoints() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let active = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com/a"}"#, None)
+            .await
+            .unwrap();
+        let inactive = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com/b"}"#, None)
+            .await
+            .unwrap();
+        toggle_endpoint_active(&pool, inactive).await.unwrap();
+
+        let linked = create_subscription(&pool, "rust").await.unwrap();
+        let stale = create_subscription(&pool, "golang").await.unwrap();
+        create_subscription(&pool, "python").await.unwrap();
+        let muted = create_subscription(&pool, "haskell").await.unwrap();
+        link_subscription_endpoint(&pool, linked, active).await.unwrap();
+        link_subscription_endpoint(&pool, linked, inactive).await.unwrap();
+        link_subscription_endpoint(&pool, stale, inactive).await.unwrap();
+        toggle_subscription_muted(&pool, muted).await.unwrap();
+
+        let flagged: Vec<String> = subscriptions_without_active_endpoints(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.subreddit)
+            .collect();
+        assert_eq!(flagged, vec!["golang".to_string(), "python".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_search_subscriptions_are_kept_out_of_subreddit_polling() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let endpoint = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com/a"}"#, None)
+            .await
+            .unwrap();
+        let plain = create_subscription(&pool, "rust").await.unwrap();
+        let scoped = SearchQuery::parse("tokio console", true).unwrap();
+        let in_rust = create_search_subscription(&pool, "rust", &scoped).await.unwrap();
+        let anywhere = SearchQuery::parse("site:github.com", false).unwrap();
+        // Unrestricted searches ignore the subreddit given
+        let everywhere = create_search_subscription(&pool, "golang", &anywhere).await.unwrap();
+        let muted = create_search_subscription(&pool, "rust", &scoped).await.unwrap();
+        for id in [plain, in_rust, everywhere, muted] {
+            link_subscription_endpoint(&pool, id, endpoint).await.unwrap();
+        }
+        toggle_subscription_muted(&pool, muted).await.unwrap();
+
+        // Only the plain subscription is polled through /new
+        assert_eq!(unique_subreddits(&pool).await.unwrap(), vec!["rust".to_string()]);
+        let mappings = all_subreddit_endpoint_mappings(&pool).await.unwrap();
+        assert_eq!(mappings.keys().collect::<Vec<_>>(), vec!["rust"]);
+
+        let searches = search_subscriptions(&pool).await.unwrap();
+        assert_eq!(searches.iter().map(|s| s.id).collect::<Vec<_>>(), vec![in_rust, everywhere]);
+        assert_eq!(searches[0].search, scoped);
+        assert_eq!(searches[1].subreddit, ALL_SUBREDDITS);
+        assert_eq!(searches[1].endpoints.iter().map(|e| e.id).collect::<Vec<_>>(), vec![endpoint]);
+
+        let listed = list_subscriptions(&pool).await.unwrap();
+        assert_eq!(listed.iter().filter(|s| s.search.is_some()).count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_set_subscription_endpoints_replaces_links_atomically() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let mut endpoints = Vec::new();
+        for url in ["https://example.com/a", "https://example.com/b", "https://example.com/c"] {
+            let config = serde_json::json!({ "webhook_url": url }).to_string();
+            endpoints.push(create_endpoint(&pool, "discord", &config, None).await.unwrap());
+        }
+        let rust = create_subscription(&pool, "rust").await.unwrap();
+        link_subscription_endpoint(&pool, rust, endpoints[0]).await.unwrap();
+        link_subscription_endpoint(&pool, rust, endpoints[1]).await.unwrap();
+
+        let linked_ids = |pool: SqlitePool| async move {
+            let mut ids: Vec<i64> = get_subscription_endpoints(&pool, rust).await.unwrap().iter().map(|e| e.id).collect();
+            ids.sort_unstable();
+            ids
+        };
+
+        set_subscription_endpoints(&pool, rust, &[endpoints[1], endpoints[2]]).await.unwrap();
+        assert_eq!(linked_ids(pool.clone()).await, vec![endpoints[1], endpoints[2]]);
+
+        // An unknown endpoint fails the whole change
+        assert!(set_subscription_endpoints(&pool, rust, &[endpoints[0], 999]).await.is_err());
+        assert_eq!(linked_ids(pool.clone()).await, vec![endpoints[1], endpoints[2]]);
+
+        set_subscription_endpoints(&pool, rust, &[]).await.unwrap();
+        assert!(linked_ids(pool.clone()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_comment_keywords_and_dedup() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let rust = create_subscription(&pool, "Rust").await.unwrap();
+        let muted = create_subscription(&pool, "golang").await.unwrap();
+        assert!(comment_keywords_by_subreddit(&pool).await.unwrap().is_empty());
+
+        let words = KeywordSet::parse(KeywordMode::Word, "tokio, async runtime").unwrap();
+        let pattern = KeywordSet::parse(KeywordMode::Regex, r"\bv\d+\.\d+").unwrap();
+        let also_rust = create_subscription(&pool, "rust").await.unwrap();
+        set_subscription_comment_keywords(&pool, rust, Some(&words)).await.unwrap();
+        set_subscription_comment_keywords(&pool, also_rust, Some(&pattern)).await.unwrap();
+        set_subscription_comment_keywords(&pool, muted, Some(&KeywordSet::parse(KeywordMode::Word, "generics").unwrap()))
+            .await
+            .unwrap();
+        toggle_subscription_muted(&pool, muted).await.unwrap();
+        let keywords = comment_keywords_by_subreddit(&pool).await.unwrap();
+        assert_eq!(keywords, HashMap::from([("rust".to_string(), vec![words, pattern])]));
+
+        set_subscription_comment_keywords(&pool, rust, None).await.unwrap();
+        set_subscription_comment_keywords(&pool, also_rust, None).await.unwrap();
+        assert!(comment_keywords_by_subreddit(&pool).await.unwrap().is_empty());
+
+        assert!(record_comment_if_new(&pool, "rust", "kx1").await.unwrap());
+        assert!(!record_comment_if_new(&pool, "rust", "kx1").await.unwrap());
+        assert!(record_comment_if_new(&pool, "golang", "kx1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_config_version_bumped_by_config_changes() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let mut last = config_version(&pool).await.unwrap();
+        let mut assert_bumped = |version: i64| {
+            assert!(version > last, "version {} not above {}", version, last);
+            last = version;
+        };
+
+        let sub = create_subscription(&pool, "rust").await.unwrap();
+        assert_bumped(config_version(&pool).await.unwrap());
+        let ep = create_endpoint(&pool, "discord", r#"{"webhook_url":"https://example.com/a"}"#, None).await.unwrap();
+        assert_bumped(config_version(&pool).await.unwrap());
+        link_subscription_endpoint(&pool, sub, ep).await.unwrap();
+        assert_bumped(config_version(&pool).await.unwrap());
+        toggle_endpoint_active(&pool, ep).await.unwrap();
+        assert_bumped(config_version(&pool).await.unwrap());
+        toggle_subscription_muted(&pool, sub).await.unwrap();
+        assert_bumped(config_version(&pool).await.unwrap());
+        unlink_subscription_endpoint(&pool, sub, ep).await.unwrap();
+        assert_bumped(config_version(&pool).await.unwrap());
+        delete_subscription(&pool, sub).await.unwrap();
+        assert_bumped(config_version(&pool).await.unwrap());
+
+        // Recording posts doesn't touch the configuration
+        record_if_new(&pool, "rust", "p1", "t", "u").await.unwrap();
+        assert_eq!(config_version(&pool).await.unwrap(), last);
     }
 }
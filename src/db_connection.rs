@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
-use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
+use sqlx::{sqlite::{SqliteConnectOptions, SqliteJournalMode}, SqlitePool};
+use std::str::FromStr;
 use std::time::Duration;
 use tracing::{info, warn};
 
@@ -50,6 +51,76 @@ impl ConnectionConfig {
     }
 }
 
+/// Pool sizing and SQLite lock-wait settings for read-write connections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Maximum number of connections in the pool (default: 5)
+    pub max_connections: u32,
+    /// How long idle connections stay in the pool (default: 300s)
+    pub idle_timeout: Duration,
+    /// How long a statement waits on a locked database before failing (default: 5000ms)
+    pub busy_timeout: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 5,
+            idle_timeout: Duration::from_secs(300),
+            busy_timeout: Duration::from_millis(5000),
+        }
+    }
+}
+
+impl PoolConfig {
+    /// Load pool configuration from environment variables
+    pub fn from_env() -> Self {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    /// Load pool configuration using `var` to look up each setting
+    ///
+    /// Missing or unparsable values fall back to the defaults; a pool size
+    /// of 0 is treated as unset.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+
+        let max_connections = var("DB_MAX_CONNECTIONS")
+            .and_then(|s| s.parse().ok())
+            .filter(|&n: &u32| n > 0)
+            .unwrap_or(defaults.max_connections);
+
+        let idle_timeout = var("DB_IDLE_TIMEOUT_SECS")
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.idle_timeout);
+
+        let busy_timeout = var("DB_BUSY_TIMEOUT_MS")
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.busy_timeout);
+
+        Self {
+            max_connections,
+            idle_timeout,
+            busy_timeout,
+        }
+    }
+}
+
+/// Build read-write connection options with WAL journaling and `config`'s busy timeout
+///
+/// The database file is created if it doesn't exist.
+pub fn read_write_options(database_url: &str, config: &PoolConfig) -> Result<SqliteConnectOptions> {
+    let options = SqliteConnectOptions::from_str(database_url)
+        .with_context(|| format!("invalid database URL {}", database_url))?
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(config.busy_timeout);
+
+    Ok(options)
+}
+
 /// Connect to SQLite database with retry logic and exponential backoff
 ///
 /// This function attempts to connect to the database with configurable retry logic.
@@ -59,7 +130,8 @@ impl ConnectionConfig {
 /// - Temporary filesystem issues
 ///
 /// # Arguments
-/// * `connect_options` - SQLite connection options (must already have WAL, busy_timeout configured)
+/// * `connect_options` - SQLite connection options (must already have WAL, busy_timeout configured;
+///   see `read_write_options`)
 /// * `max_connections` - Maximum number of connections in the pool
 /// * `idle_timeout` - How long idle connections stay in the pool
 /// * `retry_config` - Optional retry configuration (uses defaults if None)
@@ -116,6 +188,61 @@ pub async fn connect_with_retry(
     }
 }
 
+/// Everything needed to open a read-write pool again, e.g. after the database became unreachable
+#[derive(Debug, Clone)]
+pub struct ReconnectConfig {
+    /// Options built by `read_write_options`
+    pub connect_options: SqliteConnectOptions,
+    pub max_connections: u32,
+    pub idle_timeout: Duration,
+    pub retry_config: ConnectionConfig,
+}
+
+impl ReconnectConfig {
+    /// Open a new pool with `connect_with_retry`
+    pub async fn connect(&self) -> Result<SqlitePool> {
+        connect_with_retry(
+            self.connect_options.clone(),
+            self.max_connections,
+            self.idle_timeout,
+            Some(self.retry_config.clone()),
+        )
+        .await
+    }
+}
+
+/// Build connection options for a read-only connection to an existing database
+///
+/// The connection is opened with `SQLITE_OPEN_READONLY` and `PRAGMA query_only`,
+/// and never sets `journal_mode`, so it can read alongside the daemon's WAL
+/// writer without taking write locks or touching the journal configuration.
+/// The database file must already exist.
+pub fn read_only_options(database_url: &str) -> Result<SqliteConnectOptions> {
+    let options = SqliteConnectOptions::from_str(database_url)
+        .with_context(|| format!("invalid database URL {}", database_url))?
+        .read_only(true)
+        .create_if_missing(false)
+        .pragma("query_only", "ON")
+        .busy_timeout(Duration::from_secs(5));
+
+    Ok(options)
+}
+
+/// Connect to an existing database in read-only mode, with retry logic
+///
+/// Intended for read commands (listing subscriptions, endpoints, history) that
+/// may run while the daemon is polling the same SQLite file. Writes through the
+/// returned pool fail with a read-only error.
+pub async fn connect_read_only(
+    database_url: &str,
+    max_connections: u32,
+    idle_timeout: Duration,
+    retry_config: Option<ConnectionConfig>,
+) -> Result<SqlitePool> {
+    let connect_options = read_only_options(database_url)?;
+    connect_with_retry(connect_options, max_connections, idle_timeout, retry_config).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,6 +255,62 @@ mod tests {
         assert_eq!(config.max_delay_ms, 5000);
     }
 
+    #[test]
+    fn test_pool_config_defaults_when_unset() {
+        let config = PoolConfig::from_vars(|_| None);
+        assert_eq!(config, PoolConfig::default());
+        assert_eq!(config.max_connections, 5);
+        assert_eq!(config.idle_timeout, Duration::from_secs(300));
+        assert_eq!(config.busy_timeout, Duration::from_millis(5000));
+    }
+
+    #[test]
+    fn test_pool_config_from_vars() {
+        let vars = std::collections::HashMap::from([
+            ("DB_MAX_CONNECTIONS", "10"),
+            ("DB_IDLE_TIMEOUT_SECS", "60"),
+            ("DB_BUSY_TIMEOUT_MS", "250"),
+        ]);
+        let config = PoolConfig::from_vars(|key| vars.get(key).map(|v| v.to_string()));
+        assert_eq!(config.max_connections, 10);
+        assert_eq!(config.idle_timeout, Duration::from_secs(60));
+        assert_eq!(config.busy_timeout, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_pool_config_ignores_invalid_values() {
+        let vars = std::collections::HashMap::from([
+            ("DB_MAX_CONNECTIONS", "0"),
+            ("DB_IDLE_TIMEOUT_SECS", "soon"),
+            ("DB_BUSY_TIMEOUT_MS", "-1"),
+        ]);
+        let config = PoolConfig::from_vars(|key| vars.get(key).map(|v| v.to_string()));
+        assert_eq!(config, PoolConfig::default());
+    }
+
+    #[tokio::test]
+    async fn test_read_write_options_apply_config() {
+        let path = std::env::temp_dir().join(format!("reddit-notifier-rw-{}.db", std::process::id()));
+        let url = format!("sqlite://{}", path.display());
+        let config = PoolConfig {
+            busy_timeout: Duration::from_millis(1234),
+            ..PoolConfig::default()
+        };
+
+        let options = read_write_options(&url, &config).unwrap();
+        let pool = connect_with_retry(options, 1, config.idle_timeout, None).await.unwrap();
+
+        let busy: i64 = sqlx::query_scalar("PRAGMA busy_timeout").fetch_one(&pool).await.unwrap();
+        assert_eq!(busy, 1234);
+        let journal: String = sqlx::query_scalar("PRAGMA journal_mode").fetch_one(&pool).await.unwrap();
+        assert_eq!(journal, "wal");
+
+        pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
     #[test]
     fn test_exponential_backoff_cap() {
         let config = ConnectionConfig {
@@ -154,4 +337,71 @@ mod tests {
         delay = (delay * 2).min(config.max_delay_ms); // stays at 1000
         assert_eq!(delay, 1000);
     }
+
+    #[tokio::test]
+    async fn test_read_only_pool_reads_alongside_wal_writer() {
+        let path = std::env::temp_dir().join(format!(
+            "reddit-notifier-ro-{}-{}.db",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let url = format!("sqlite://{}", path.display());
+
+        // Writer configured like the daemon
+        let writer_options = SqliteConnectOptions::from_str(&url)
+            .unwrap()
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal);
+        let writer = connect_with_retry(writer_options, 1, Duration::from_secs(60), None)
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&writer).await.unwrap();
+        sqlx::query("INSERT INTO subscriptions (subreddit) VALUES ('rust')")
+            .execute(&writer)
+            .await
+            .unwrap();
+
+        let reader = connect_read_only(&url, 1, Duration::from_secs(60), None)
+            .await
+            .unwrap();
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM subscriptions")
+            .fetch_one(&reader)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let write = sqlx::query("INSERT INTO subscriptions (subreddit) VALUES ('golang')")
+            .execute(&reader)
+            .await;
+        assert!(write.is_err());
+
+        // The writer is unaffected by the open reader
+        sqlx::query("INSERT INTO subscriptions (subreddit) VALUES ('golang')")
+            .execute(&writer)
+            .await
+            .unwrap();
+
+        reader.close().await;
+        writer.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_requires_existing_database() {
+        let url = "sqlite:///nonexistent-dir/reddit-notifier-missing.db";
+        let config = ConnectionConfig {
+            max_retries: 1,
+            initial_delay_ms: 1,
+            max_delay_ms: 1,
+        };
+        assert!(connect_read_only(url, 1, Duration::from_secs(1), Some(config))
+            .await
+            .is_err());
+    }
 }
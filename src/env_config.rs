@@ -0,0 +1,248 @@
+//! Subscriptions and an endpoint declared in environment variables
+//!
+//! The zero-config Docker setup: `REDDIT_SUBREDDITS=rust,programming` plus
+//! `NOTIFY_ENDPOINT_KIND`/`NOTIFY_ENDPOINT_CONFIG` are applied by the daemon
+//! at startup. Applying is additive and idempotent: anything already in the
+//! database (from the TUI, `import` or a previous start) is reused, never
+//! changed or removed.
+
+use anyhow::{bail, Context, Result};
+use std::str::FromStr;
+
+use crate::import::{is_valid_subreddit_name, MAX_SUBREDDIT_LEN};
+use crate::models::database::EndpointKind;
+use crate::models::notifiers::{upgrade_config_value, validate_endpoint_config};
+use crate::services::DatabaseService;
+
+/// An endpoint declared by `NOTIFY_ENDPOINT_KIND` and `NOTIFY_ENDPOINT_CONFIG`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvEndpoint {
+    pub kind: EndpointKind,
+    /// The endpoint's config, as it would appear in `config_json`
+    pub config: serde_json::Value,
+}
+
+/// Everything the environment asks to exist
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvConfig {
+    pub subreddits: Vec<String>,
+    pub endpoint: Option<EnvEndpoint>,
+}
+
+impl EnvConfig {
+    /// Read `REDDIT_SUBREDDITS`, `NOTIFY_ENDPOINT_KIND` and `NOTIFY_ENDPOINT_CONFIG`
+    ///
+    /// Returns `None` when none of them are set. Invalid values are an error,
+    /// so a typo stops startup instead of silently polling nothing.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name| std::env::var(name).ok().filter(|s: &String| !s.trim().is_empty());
+        let subreddits = var("REDDIT_SUBREDDITS");
+        let kind = var("NOTIFY_ENDPOINT_KIND");
+        let config = var("NOTIFY_ENDPOINT_CONFIG");
+        if subreddits.is_none() && kind.is_none() && config.is_none() {
+            return Ok(None);
+        }
+
+        let subreddits = parse_subreddit_list(subreddits.as_deref().unwrap_or_default())?;
+        let endpoint = match (kind, config) {
+            (None, None) => None,
+            (Some(kind), Some(config)) => Some(parse_endpoint(&kind, &config)?),
+            _ => bail!("NOTIFY_ENDPOINT_KIND and NOTIFY_ENDPOINT_CONFIG must be set together"),
+        };
+        Ok(Some(Self { subreddits, endpoint }))
+    }
+}
+
+/// Parse a comma-separated subreddit list ("rust, r/programming,Rust")
+///
+/// Names are trimmed, a leading `r/` or `/r/` is dropped, and repeats are
+/// removed ignoring case (the first spelling is kept).
+pub fn parse_subreddit_list(input: &str) -> Result<Vec<String>> {
+    let mut subreddits: Vec<String> = Vec::new();
+    for entry in input.split(',') {
+        let entry = entry.trim();
+        let name = entry.strip_prefix('/').unwrap_or(entry);
+        let name = name.strip_prefix("r/").or_else(|| name.strip_prefix("R/")).unwrap_or(name);
+        if name.is_empty() {
+            continue;
+        }
+        if !is_valid_subreddit_name(name) {
+            bail!(
+                "REDDIT_SUBREDDITS: '{}' is not a subreddit name (2-{} letters, digits or underscores)",
+                entry,
+                MAX_SUBREDDIT_LEN
+            );
+        }
+        if !subreddits.iter().any(|s| s.eq_ignore_ascii_case(name)) {
+            subreddits.push(name.to_string());
+        }
+    }
+    Ok(subreddits)
+}
+
+/// Check an endpoint kind and JSON config the same way the TUI and `import` do
+pub fn parse_endpoint(kind: &str, config: &str) -> Result<EnvEndpoint> {
+    let kind = EndpointKind::from_str(kind.trim())
+        .map_err(anyhow::Error::msg)
+        .context("NOTIFY_ENDPOINT_KIND")?;
+    let config: serde_json::Value = serde_json::from_str(config).context("NOTIFY_ENDPOINT_CONFIG is not valid JSON")?;
+    if !config.is_object() {
+        bail!("NOTIFY_ENDPOINT_CONFIG must be a JSON object");
+    }
+    validate_endpoint_config(&kind, &config.to_string())
+        .map_err(anyhow::Error::msg)
+        .context("NOTIFY_ENDPOINT_CONFIG")?;
+    Ok(EnvEndpoint { kind, config })
+}
+
+/// What `ensure_env_config` had to create
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EnsureSummary {
+    pub subscriptions: usize,
+    pub endpoint: bool,
+    pub links: usize,
+}
+
+/// Make sure every subscription and the endpoint in `env` exist, linked together
+///
+/// Subreddits match existing subscriptions ignoring case; the endpoint
+/// matches an existing one of the same kind whose config is identical once
+/// both are upgraded to the current shape (the TUI saves upgraded configs). Only
+/// missing rows and links are created, so running this on every start is safe.
+pub async fn ensure_env_config<D: DatabaseService>(db: &D, env: &EnvConfig) -> Result<EnsureSummary> {
+    let mut summary = EnsureSummary::default();
+
+    let endpoint_id = match &env.endpoint {
+        None => None,
+        Some(endpoint) => {
+            let wanted = upgrade_config_value(&endpoint.kind, endpoint.config.clone()).map_err(anyhow::Error::msg)?;
+            let existing = db.list_endpoints().await?.into_iter().find(|e| {
+                e.kind == endpoint.kind
+                    && serde_json::from_str::<serde_json::Value>(&e.config_json)
+                        .ok()
+                        .and_then(|c| upgrade_config_value(&e.kind, c).ok())
+                        .is_some_and(|c| c == wanted)
+            });
+            match existing {
+                Some(e) => Some(e.id),
+                None => {
+                    let id = db
+                        .create_endpoint(endpoint.kind.as_str(), &endpoint.config.to_string(), Some("from environment"))
+                        .await
+                        .context("failed to create the NOTIFY_ENDPOINT endpoint")?;
+                    summary.endpoint = true;
+                    Some(id)
+                }
+            }
+        }
+    };
+
+    let subscriptions = db.list_subscriptions().await?;
+    for subreddit in &env.subreddits {
+        let sub_id = match subscriptions.iter().find(|s| s.subreddit.eq_ignore_ascii_case(subreddit)) {
+            Some(s) => s.id,
+            None => {
+                summary.subscriptions += 1;
+                db.create_subscription(subreddit)
+                    .await
+                    .with_context(|| format!("failed to create subscription r/{}", subreddit))?
+            }
+        };
+
+        let Some(endpoint_id) = endpoint_id else { continue };
+        if !db.get_subscription_endpoints(sub_id).await?.iter().any(|e| e.id == endpoint_id) {
+            db.link_subscription_endpoint(sub_id, endpoint_id).await?;
+            summary.links += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mock_database::MockDatabaseService;
+
+    fn discord() -> EnvEndpoint {
+        parse_endpoint("discord", r#"{"webhook_url": "https://discord.com/api/webhooks/1/abc"}"#).unwrap()
+    }
+
+    #[test]
+    fn test_parse_subreddit_list() {
+        assert_eq!(
+            parse_subreddit_list(" rust, r/programming,/r/golang ,,Rust, R/Programming ").unwrap(),
+            vec!["rust", "programming", "golang"]
+        );
+        assert!(parse_subreddit_list("").unwrap().is_empty());
+        assert!(parse_subreddit_list("rust, not a sub").is_err());
+        assert!(parse_subreddit_list("r/").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_endpoint() {
+        assert_eq!(discord().kind, EndpointKind::Discord);
+        assert!(parse_endpoint("carrier-pigeon", "{}").is_err());
+        assert!(parse_endpoint("discord", "[]").is_err());
+        assert!(parse_endpoint("discord", r#"{"webhook_url": "http://insecure"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_is_idempotent() {
+        let db = MockDatabaseService::new();
+        let env = EnvConfig {
+            subreddits: vec!["rust".into(), "programming".into()],
+            endpoint: Some(discord()),
+        };
+
+        let first = ensure_env_config(&db, &env).await.unwrap();
+        assert_eq!(first, EnsureSummary { subscriptions: 2, endpoint: true, links: 2 });
+
+        let second = ensure_env_config(&db, &env).await.unwrap();
+        assert_eq!(second, EnsureSummary::default());
+        assert_eq!(db.list_subscriptions().await.unwrap().len(), 2);
+        assert_eq!(db.list_endpoints().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_matches_endpoint_saved_in_upgraded_form() {
+        let db = MockDatabaseService::new();
+        // What the TUI writes back after opening the env endpoint and saving it unchanged
+        let saved = r#"{"version": 2, "webhook_urls": ["https://discord.com/api/webhooks/1/abc"]}"#;
+        let id = db.create_endpoint("discord", saved, Some("from environment")).await.unwrap();
+        let env = EnvConfig { subreddits: vec!["rust".into()], endpoint: Some(discord()) };
+
+        let summary = ensure_env_config(&db, &env).await.unwrap();
+        assert_eq!(summary, EnsureSummary { subscriptions: 1, endpoint: false, links: 1 });
+        assert_eq!(db.list_endpoints().await.unwrap().iter().map(|e| e.id).collect::<Vec<_>>(), vec![id]);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_reuses_existing_rows() {
+        let db = MockDatabaseService::new();
+        let sub_id = db.create_subscription("Rust").await.unwrap();
+        let endpoint_id = db
+            .create_endpoint("discord", r#"{"webhook_url":"https://discord.com/api/webhooks/1/abc"}"#, None)
+            .await
+            .unwrap();
+
+        // Same subreddit in another case, same config with different formatting
+        let env = EnvConfig {
+            subreddits: vec!["rust".into()],
+            endpoint: Some(discord()),
+        };
+        let summary = ensure_env_config(&db, &env).await.unwrap();
+        assert_eq!(summary, EnsureSummary { subscriptions: 0, endpoint: false, links: 1 });
+
+        let linked = db.get_subscription_endpoints(sub_id).await.unwrap();
+        assert_eq!(linked.iter().map(|e| e.id).collect::<Vec<_>>(), vec![endpoint_id]);
+
+        // Without an endpoint only the subscriptions are ensured
+        let env = EnvConfig {
+            subreddits: vec!["golang".into()],
+            endpoint: None,
+        };
+        let summary = ensure_env_config(&db, &env).await.unwrap();
+        assert_eq!(summary, EnsureSummary { subscriptions: 1, endpoint: false, links: 0 });
+    }
+}
@@ -0,0 +1,297 @@
+//! Explain which subscriptions a post would notify, and why not
+//!
+//! A read-only mirror of the poller's per-post checks (post window, muted
+//! subscriptions, stickied posts, active endpoints), used by
+//! `reddit-notifier explain` to diagnose a subscription that stays quiet.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::models::database::{EndpointRow, SubscriptionRow};
+use crate::models::reddit_api::{RedditChild, RedditListing, RedditPost};
+use crate::poller::{describe_post_age, POST_WINDOW_HOURS};
+
+/// One check the poller applies, and whether the post passed it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    /// Why the check passed or failed
+    pub detail: String,
+}
+
+impl MatchCheck {
+    fn new(name: &'static str, passed: bool, detail: impl Into<String>) -> Self {
+        Self { name, passed, detail: detail.into() }
+    }
+}
+
+/// How one subscription to the post's subreddit would handle the post
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionMatch {
+    pub subscription_id: i64,
+    pub subreddit: String,
+    pub checks: Vec<MatchCheck>,
+    /// Active endpoints that would be notified if every check passes
+    pub endpoint_ids: Vec<i64>,
+}
+
+impl SubscriptionMatch {
+    /// Whether the post would be sent through this subscription
+    pub fn passes(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+}
+
+/// Result of `match_report`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchReport {
+    pub post_id: String,
+    pub subreddit: String,
+    /// Subscriptions to the post's subreddit (case-insensitive), by ID
+    pub subscriptions: Vec<SubscriptionMatch>,
+}
+
+impl MatchReport {
+    /// Whether any subscription would send the post
+    pub fn would_notify(&self) -> bool {
+        self.subscriptions.iter().any(SubscriptionMatch::passes)
+    }
+}
+
+impl fmt::Display for MatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Post {} in r/{}", self.post_id, self.subreddit)?;
+        if self.subscriptions.is_empty() {
+            return write!(f, "No subscription to r/{} - the post would not be fetched", self.subreddit);
+        }
+        for sub in &self.subscriptions {
+            let verdict = if sub.passes() { "would notify" } else { "would not notify" };
+            writeln!(f, "Subscription {} (r/{}): {}", sub.subscription_id, sub.subreddit, verdict)?;
+            for check in &sub.checks {
+                writeln!(f, "  [{}] {}: {}", if check.passed { "pass" } else { "FAIL" }, check.name, check.detail)?;
+            }
+        }
+        write!(f, "Overall: {}", if self.would_notify() { "would notify" } else { "would not notify" })
+    }
+}
+
+/// Check `post` against every subscription to its subreddit, as of `now`
+///
+/// `endpoints` holds each subscription's linked endpoints by subscription ID.
+/// Stickied posts follow the poller: they're sent to the whole subreddit if
+/// any unmuted subscription to it allows them.
+pub fn match_report(
+    post: &RedditPost,
+    subscriptions: &[SubscriptionRow],
+    endpoints: &HashMap<i64, Vec<EndpointRow>>,
+    now: DateTime<Utc>,
+) -> MatchReport {
+    let matching: Vec<&SubscriptionRow> = subscriptions
+        .iter()
+        .filter(|s| s.subreddit.eq_ignore_ascii_case(&post.subreddit))
+        .collect();
+    let sticky_allowed_by = matching.iter().find(|s| !s.muted && !s.skip_stickied).map(|s| s.id);
+
+    let age = now.signed_duration_since(post.created_utc);
+    let window = if age.abs() <= TimeDelta::hours(POST_WINDOW_HOURS) {
+        MatchCheck::new("window", true, format!("{}, inside the {}h window", describe_post_age(age), POST_WINDOW_HOURS))
+    } else {
+        MatchCheck::new("window", false, format!("{}, outside the {}h window", describe_post_age(age), POST_WINDOW_HOURS))
+    };
+
+    let sticky = match (post.stickied, sticky_allowed_by) {
+        (false, _) => MatchCheck::new("stickied", true, "not stickied"),
+        (true, Some(id)) => MatchCheck::new("stickied", true, format!("stickied, allowed by subscription {}", id)),
+        (true, None) => MatchCheck::new(
+            "stickied",
+            false,
+            format!("stickied, and every active subscription to r/{} skips stickied posts", post.subreddit),
+        ),
+    };
+
+    let subscriptions = matching
+        .into_iter()
+        .map(|sub| {
+            let muted = if sub.muted {
+                MatchCheck::new("muted", false, "subscription is muted")
+            } else {
+                MatchCheck::new("muted", true, "not muted")
+            };
+
+            let active: Vec<&EndpointRow> = endpoints
+                .get(&sub.id)
+                .map(|eps| eps.iter().filter(|e| e.active).collect())
+                .unwrap_or_default();
+            let endpoint_check = if active.is_empty() {
+                MatchCheck::new("endpoints", false, "no active endpoint linked")
+            } else {
+                let names: Vec<String> = active.iter().map(|e| format!("{} #{}", e.kind.as_str(), e.id)).collect();
+                MatchCheck::new("endpoints", true, names.join(", "))
+            };
+
+            SubscriptionMatch {
+                subscription_id: sub.id,
+                subreddit: sub.subreddit.clone(),
+                checks: vec![window.clone(), muted, sticky.clone(), endpoint_check],
+                endpoint_ids: active.iter().map(|e| e.id).collect(),
+            }
+        })
+        .collect();
+
+    MatchReport {
+        post_id: post.id.clone(),
+        subreddit: post.subreddit.clone(),
+        subscriptions,
+    }
+}
+
+/// Parse a post pasted from Reddit's JSON
+///
+/// Accepts a post's `data` object, a listing child (`{"kind": "t3", "data": ...}`),
+/// or a whole listing (`.../new.json` or a post page's `.json`), using its first post.
+pub fn parse_post_json(json: &str) -> Result<RedditPost> {
+    let value: serde_json::Value = serde_json::from_str(json).context("post is not valid JSON")?;
+
+    // A post page's .json is [post listing, comments listing]
+    let value = match value {
+        serde_json::Value::Array(mut items) if !items.is_empty() => items.swap_remove(0),
+        other => other,
+    };
+
+    if value.pointer("/data/children").is_some() {
+        let listing: RedditListing = serde_json::from_value(value).context("listing is not a post listing")?;
+        return listing
+            .data
+            .children
+            .into_iter()
+            .next()
+            .map(|c| c.data)
+            .ok_or_else(|| anyhow!("listing contains no posts"));
+    }
+    if value.get("data").is_some() {
+        let child: RedditChild<RedditPost> = serde_json::from_value(value).context("not a Reddit post")?;
+        return Ok(child.data);
+    }
+    serde_json::from_value(value).context("not a Reddit post")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::database::EndpointKind;
+
+    fn subscription(id: i64, subreddit: &str, skip_stickied: bool, muted: bool) -> SubscriptionRow {
+        SubscriptionRow {
+            id,
+            subreddit: subreddit.to_string(),
+            created_at: "2026-01-01 00:00:00".to_string(),
+            skip_stickied,
+            muted,
+            priority: false,
+            search: None,
+        }
+    }
+
+    fn endpoint(id: i64, active: bool) -> EndpointRow {
+        EndpointRow {
+            id,
+            kind: EndpointKind::Discord,
+            config_json: "{}".to_string(),
+            active,
+            note: None,
+        }
+    }
+
+    fn post(subreddit: &str, stickied: bool, age: TimeDelta, now: DateTime<Utc>) -> RedditPost {
+        RedditPost {
+            id: "abc".to_string(),
+            title: "Hello".to_string(),
+            subreddit: subreddit.to_string(),
+            permalink: None,
+            url: None,
+            stickied,
+            over_18: false,
+            score: 0,
+            num_comments: 0,
+            created_utc: now - age,
+        }
+    }
+
+    fn failed(report: &MatchReport, sub: usize) -> Vec<&'static str> {
+        report.subscriptions[sub].checks.iter().filter(|c| !c.passed).map(|c| c.name).collect()
+    }
+
+    #[test]
+    fn test_fresh_post_matches_subscription_with_active_endpoint() {
+        let now = Utc::now();
+        let subs = [subscription(1, "Rust", true, false), subscription(2, "golang", true, false)];
+        let endpoints = HashMap::from([(1, vec![endpoint(10, true), endpoint(11, false)])]);
+
+        let report = match_report(&post("rust", false, TimeDelta::minutes(5), now), &subs, &endpoints, now);
+        assert_eq!(report.subscriptions.len(), 1);
+        assert!(report.would_notify());
+        assert_eq!(report.subscriptions[0].endpoint_ids, vec![10]);
+        assert!(report.to_string().contains("Subscription 1 (r/Rust): would notify"));
+    }
+
+    #[test]
+    fn test_report_explains_failed_checks() {
+        let now = Utc::now();
+        let endpoints = HashMap::from([(1, vec![endpoint(10, true)]), (2, vec![endpoint(11, false)])]);
+        let subs = [subscription(1, "rust", true, true), subscription(2, "rust", true, false)];
+
+        // Too old, sub 1 muted, sub 2 has no active endpoint
+        let report = match_report(&post("rust", false, TimeDelta::hours(30), now), &subs, &endpoints, now);
+        assert!(!report.would_notify());
+        assert_eq!(failed(&report, 0), vec!["window", "muted"]);
+        assert_eq!(failed(&report, 1), vec!["window", "endpoints"]);
+        assert!(report.subscriptions[0].checks[0].detail.contains("30h 0m old"));
+
+        // No subscription at all
+        let report = match_report(&post("python", false, TimeDelta::zero(), now), &subs, &endpoints, now);
+        assert!(report.subscriptions.is_empty());
+        assert!(report.to_string().contains("No subscription to r/python"));
+    }
+
+    #[test]
+    fn test_stickied_posts_follow_the_subreddit() {
+        let now = Utc::now();
+        let endpoints = HashMap::from([(1, vec![endpoint(10, true)]), (2, vec![endpoint(11, true)])]);
+        let stickied = post("rust", true, TimeDelta::minutes(1), now);
+
+        let skipping = [subscription(1, "rust", true, false)];
+        let report = match_report(&stickied, &skipping, &endpoints, now);
+        assert_eq!(failed(&report, 0), vec!["stickied"]);
+
+        // Another subscription allowing stickies sends it to every endpoint of the subreddit
+        let mixed = [subscription(1, "rust", true, false), subscription(2, "rust", false, false)];
+        let report = match_report(&stickied, &mixed, &endpoints, now);
+        assert!(report.subscriptions.iter().all(SubscriptionMatch::passes));
+        assert_eq!(report.subscriptions[0].checks[2].detail, "stickied, allowed by subscription 2");
+
+        // ...unless that subscription is muted
+        let muted = [subscription(1, "rust", true, false), subscription(2, "rust", false, true)];
+        assert!(!match_report(&stickied, &muted, &endpoints, now).would_notify());
+    }
+
+    #[test]
+    fn test_parse_post_json_shapes() {
+        let data = r#"{"id": "p1", "title": "T", "subreddit": "rust", "created_utc": 1700000000.0}"#;
+        assert_eq!(parse_post_json(data).unwrap().id, "p1");
+
+        let child = format!(r#"{{"kind": "t3", "data": {}}}"#, data);
+        assert_eq!(parse_post_json(&child).unwrap().id, "p1");
+
+        let listing = format!(r#"{{"kind": "Listing", "data": {{"children": [{}]}}}}"#, child);
+        assert_eq!(parse_post_json(&listing).unwrap().id, "p1");
+
+        let post_page = format!(r#"[{}, {{"kind": "Listing", "data": {{"children": []}}}}]"#, listing);
+        assert_eq!(parse_post_json(&post_page).unwrap().subreddit, "rust");
+
+        assert!(parse_post_json(r#"{"data": {"children": []}}"#).is_err());
+        assert!(parse_post_json("not json").is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Shared construction of outbound HTTP clients
+//!
+//! Reddit polling and webhook delivery go through the same `reqwest::Client`,
+//! so proxy and certificate settings for locked-down networks are applied here.
+
+use anyhow::{Context, Result};
+use reqwest::{redirect, Certificate, ClientBuilder, Proxy};
+use std::path::PathBuf;
+
+use crate::models::config::AppConfig;
+
+/// Network settings applied to every outbound client
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpClientConfig {
+    /// Proxy for all requests (`http://`, `https://` or `socks5://`)
+    pub proxy: Option<String>,
+    /// PEM file with extra root certificates to trust (e.g. an internal CA)
+    pub ca_cert: Option<PathBuf>,
+}
+
+impl HttpClientConfig {
+    /// Build network settings from the application configuration
+    pub fn from_app_config(cfg: &AppConfig) -> Self {
+        Self {
+            proxy: cfg.https_proxy.clone(),
+            ca_cert: cfg.reddit_ca_cert.as_ref().map(PathBuf::from),
+        }
+    }
+}
+
+/// Redirects followed per request
+///
+/// Reddit redirects e.g. `reddit.com` to `www.reddit.com`; the poller checks
+/// where a listing request ended up (see `fetch_listing_items`).
+pub const MAX_REDIRECTS: usize = 10;
+
+/// Start a client builder with the proxy and extra CA certificates applied
+///
+/// Fails if the proxy URL is invalid or the CA file can't be read or parsed,
+/// so misconfiguration is reported at startup rather than on the first request.
+pub fn client_builder(cfg: &HttpClientConfig) -> Result<ClientBuilder> {
+    let mut builder = reqwest::Client::builder().redirect(redirect::Policy::limited(MAX_REDIRECTS));
+
+    if let Some(proxy) = &cfg.proxy {
+        let proxy = Proxy::all(proxy).with_context(|| format!("invalid HTTPS_PROXY url: {}", proxy))?;
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(path) = &cfg.ca_cert {
+        let pem = std::fs::read(path)
+            .with_context(|| format!("failed to read REDDIT_CA_CERT file {}", path.display()))?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("REDDIT_CA_CERT file {} is not a valid PEM certificate", path.display()))?;
+        if certs.is_empty() {
+            anyhow::bail!("REDDIT_CA_CERT file {} contains no certificates", path.display());
+        }
+        builder = builder.tls_certs_merge(certs);
+    }
+
+    Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_builds_client() {
+        let client = client_builder(&HttpClientConfig::default()).unwrap().build();
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_proxy_is_applied() {
+        for proxy in ["http://proxy.internal:3128", "socks5://127.0.0.1:1080"] {
+            let cfg = HttpClientConfig {
+                proxy: Some(proxy.to_string()),
+                ca_cert: None,
+            };
+            assert!(client_builder(&cfg).unwrap().build().is_ok(), "{}", proxy);
+        }
+    }
+
+    #[test]
+    fn test_missing_ca_file_is_descriptive() {
+        let cfg = HttpClientConfig {
+            proxy: None,
+            ca_cert: Some(PathBuf::from("/nonexistent/internal-ca.pem")),
+        };
+        let err = client_builder(&cfg).unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("failed to read REDDIT_CA_CERT file /nonexistent/internal-ca.pem"),
+            "{:#}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_ca_file_without_certificates_is_rejected() {
+        let path = std::env::temp_dir().join(format!("reddit-notifier-ca-{}.pem", std::process::id()));
+        std::fs::write(&path, "not a certificate").unwrap();
+        let cfg = HttpClientConfig {
+            proxy: None,
+            ca_cert: Some(path.clone()),
+        };
+        let result = client_builder(&cfg);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.unwrap_err().to_string().contains("REDDIT_CA_CERT file"));
+    }
+}
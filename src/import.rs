@@ -0,0 +1,326 @@
+//! Importing subscriptions and endpoints from a JSON config file
+//!
+//! `reddit-notifier import <file.json>` creates everything a config document
+//! describes; with `--check` it only reports problems. Either way the whole
+//! document is validated first and nothing is written unless it is clean.
+//! The rows are then written in one transaction, so neither a typo nor a
+//! database error halfway through can leave a half-applied config behind.
+//!
+//! ```json
+//! {
+//!   "endpoints": [
+//!     { "name": "alerts", "kind": "discord", "config": { "webhook_url": "env:DISCORD_WEBHOOK" } }
+//!   ],
+//!   "subscriptions": [
+//!     { "subreddit": "rust", "endpoints": ["alerts"] }
+//!   ]
+//! }
+//! ```
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::database::{EndpointKind, NewEndpoint, NewSubscription, MAX_ENDPOINT_CONFIG_LEN, MAX_ENDPOINT_NOTE_LEN};
+use crate::models::notifiers::validate_endpoint_config;
+use crate::services::DatabaseService;
+
+/// Longest subreddit name Reddit allows
+pub const MAX_SUBREDDIT_LEN: usize = 21;
+
+/// A config file: endpoints, and subscriptions linked to them by name
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigDocument {
+    #[serde(default)]
+    pub endpoints: Vec<EndpointEntry>,
+    #[serde(default)]
+    pub subscriptions: Vec<SubscriptionEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EndpointEntry {
+    /// Name used by subscriptions to refer to this endpoint (not stored)
+    pub name: String,
+    pub kind: String,
+    /// The endpoint's config, as it would appear in `config_json`
+    pub config: serde_json::Value,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubscriptionEntry {
+    pub subreddit: String,
+    /// Names of endpoints defined in the same document
+    #[serde(default)]
+    pub endpoints: Vec<String>,
+}
+
+/// One problem found in a config document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Where the problem is, e.g. `endpoints[1] (alerts).config`
+    pub location: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Parse a config document (unknown fields are rejected so typos aren't ignored)
+pub fn parse_config(json: &str) -> Result<ConfigDocument> {
+    serde_json::from_str(json).context("invalid config document")
+}
+
+/// True for a valid subreddit name: 2-21 letters, digits or underscores
+pub fn is_valid_subreddit_name(name: &str) -> bool {
+    (2..=MAX_SUBREDDIT_LEN).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Check every entry of `doc` and return all problems found (empty = valid)
+///
+/// Covers endpoint names, kinds and configs (against the same schema the
+/// TUI enforces), subreddit names, and that every link refers to an endpoint
+/// defined in the document.
+pub fn validate_config(doc: &ConfigDocument) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let mut names = HashSet::new();
+
+    for (i, endpoint) in doc.endpoints.iter().enumerate() {
+        let location = format!("endpoints[{}] ({})", i, endpoint.name);
+
+        if endpoint.name.trim().is_empty() {
+            issues.push(ConfigIssue::new(&location, "name is empty"));
+        } else if !names.insert(endpoint.name.as_str()) {
+            issues.push(ConfigIssue::new(&location, "name is used by another endpoint"));
+        }
+
+        if let Some(note) = &endpoint.note {
+            if note.chars().count() > MAX_ENDPOINT_NOTE_LEN {
+                issues.push(ConfigIssue::new(
+                    format!("{}.note", location),
+                    format!("longer than {} characters", MAX_ENDPOINT_NOTE_LEN),
+                ));
+            }
+        }
+
+        let kind = match EndpointKind::from_str(&endpoint.kind) {
+            Ok(kind) => kind,
+            Err(e) => {
+                issues.push(ConfigIssue::new(format!("{}.kind", location), e));
+                continue;
+            }
+        };
+        if !endpoint.config.is_object() {
+            issues.push(ConfigIssue::new(format!("{}.config", location), "must be an object"));
+            continue;
+        }
+        let config_json = endpoint.config.to_string();
+        if config_json.len() > MAX_ENDPOINT_CONFIG_LEN {
+            issues.push(ConfigIssue::new(
+                format!("{}.config", location),
+                format!("larger than {} bytes", MAX_ENDPOINT_CONFIG_LEN),
+            ));
+        } else if let Err(e) = validate_endpoint_config(&kind, &config_json) {
+            issues.push(ConfigIssue::new(format!("{}.config", location), e));
+        }
+    }
+
+    for (i, sub) in doc.subscriptions.iter().enumerate() {
+        let location = format!("subscriptions[{}] (r/{})", i, sub.subreddit);
+
+        if !is_valid_subreddit_name(&sub.subreddit) {
+            issues.push(ConfigIssue::new(
+                format!("{}.subreddit", location),
+                format!("must be 2-{} letters, digits or underscores", MAX_SUBREDDIT_LEN),
+            ));
+        }
+        for name in &sub.endpoints {
+            if !names.contains(name.as_str()) {
+                issues.push(ConfigIssue::new(
+                    format!("{}.endpoints", location),
+                    format!("no endpoint named '{}' in this file", name),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// What `apply_config` created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub endpoints: usize,
+    pub subscriptions: usize,
+    pub links: usize,
+}
+
+/// Create every endpoint, subscription and link in `doc`
+///
+/// Refuses to write anything if `validate_config` finds a problem; the
+/// error lists every issue. Everything is created in one transaction, so a
+/// failed import leaves the database as it was.
+pub async fn apply_config<D: DatabaseService>(db: &D, doc: &ConfigDocument) -> Result<ImportSummary> {
+    let issues = validate_config(doc);
+    if !issues.is_empty() {
+        let list: Vec<String> = issues.iter().map(|i| format!("  {}", i)).collect();
+        anyhow::bail!("config has {} problem(s), nothing was imported:\n{}", issues.len(), list.join("\n"));
+    }
+
+    let positions: HashMap<&str, usize> =
+        doc.endpoints.iter().enumerate().map(|(i, endpoint)| (endpoint.name.as_str(), i)).collect();
+    let endpoints: Vec<NewEndpoint> = doc
+        .endpoints
+        .iter()
+        .map(|endpoint| NewEndpoint {
+            kind: endpoint.kind.clone(),
+            config_json: endpoint.config.to_string(),
+            note: endpoint.note.clone(),
+        })
+        .collect();
+    let subscriptions: Vec<NewSubscription> = doc
+        .subscriptions
+        .iter()
+        .map(|sub| NewSubscription {
+            subreddit: sub.subreddit.clone(),
+            endpoints: sub.endpoints.iter().map(|name| positions[name.as_str()]).collect(),
+        })
+        .collect();
+
+    db.import_rows(&endpoints, &subscriptions)
+        .await
+        .context("import failed, nothing was imported")?;
+
+    Ok(ImportSummary {
+        endpoints: endpoints.len(),
+        subscriptions: subscriptions.len(),
+        links: subscriptions.iter().map(|sub| sub.endpoints.len()).sum(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mock_database::MockDatabaseService;
+
+    const VALID: &str = r#"{
+        "endpoints": [
+            {"name": "alerts", "kind": "discord", "config": {"webhook_url": "https://discord.com/api/webhooks/1/abc"}},
+            {"name": "phone", "kind": "pushover", "config": {"token": "t", "user": "u"}, "note": "on call"}
+        ],
+        "subscriptions": [
+            {"subreddit": "rust", "endpoints": ["alerts", "phone"]},
+            {"subreddit": "golang", "endpoints": ["alerts"]}
+        ]
+    }"#;
+
+    #[test]
+    fn test_valid_config_has_no_issues() {
+        assert!(validate_config(&parse_config(VALID).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_all_issues_reported_together() {
+        let doc = parse_config(
+            r#"{
+            "endpoints": [
+                {"name": "alerts", "kind": "discord", "config": {"webhook_url": "http://insecure"}},
+                {"name": "alerts", "kind": "pushover", "config": {"token": "", "user": "u"}},
+                {"name": "chat", "kind": "slack", "config": {}}
+            ],
+            "subscriptions": [
+                {"subreddit": "r/rust", "endpoints": ["alerts"]},
+                {"subreddit": "golang", "endpoints": ["missing"]}
+            ]
+        }"#,
+        )
+        .unwrap();
+
+        let issues: Vec<String> = validate_config(&doc).iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            issues,
+            vec![
+                "endpoints[0] (alerts).config: webhook_url must start with https://",
+                "endpoints[1] (alerts): name is used by another endpoint",
+                "endpoints[1] (alerts).config: token is empty",
+                "endpoints[2] (chat).kind: Unknown endpoint kind: slack",
+                "subscriptions[0] (r/r/rust).subreddit: must be 2-21 letters, digits or underscores",
+                "subscriptions[1] (r/golang).endpoints: no endpoint named 'missing' in this file",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_rejected() {
+        assert!(parse_config(r#"{"subscriptions": [{"subreddit": "rust", "endpoint": ["alerts"]}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_subreddit_name_rules() {
+        assert!(is_valid_subreddit_name("de"));
+        assert!(is_valid_subreddit_name("rust_gamedev"));
+        assert!(!is_valid_subreddit_name("r"));
+        assert!(!is_valid_subreddit_name("this_name_is_far_too_long"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_creates_everything() {
+        let db = MockDatabaseService::new();
+        let summary = apply_config(&db, &parse_config(VALID).unwrap()).await.unwrap();
+        assert_eq!(summary, ImportSummary { endpoints: 2, subscriptions: 2, links: 3 });
+
+        let rust = db.list_subscriptions().await.unwrap().into_iter().find(|s| s.subreddit == "rust").unwrap();
+        assert_eq!(db.get_subscription_endpoints(rust.id).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_apply_writes_nothing_when_invalid() {
+        let db = MockDatabaseService::new();
+        let mut doc = parse_config(VALID).unwrap();
+        doc.subscriptions[1].endpoints.push("missing".to_string());
+
+        let err = apply_config(&db, &doc).await.unwrap_err();
+        assert!(err.to_string().contains("nothing was imported"));
+        assert!(db.list_endpoints().await.unwrap().is_empty());
+        assert!(db.list_subscriptions().await.unwrap().is_empty());
+    }
+    #[tokio::test]
+    async fn test_apply_rolls_back_on_database_error() {
+        use crate::services::sqlite_database::SqliteDatabaseService;
+
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        // Fail the second subscription, after both endpoints and r/rust were written
+        sqlx::query(
+            "CREATE TRIGGER reject_golang BEFORE INSERT ON subscriptions WHEN NEW.subreddit = 'golang' \
+             BEGIN SELECT RAISE(ABORT, 'golang rejected'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let db = SqliteDatabaseService::new(pool);
+
+        let err = apply_config(&db, &parse_config(VALID).unwrap()).await.unwrap_err();
+        assert!(err.to_string().contains("nothing was imported"));
+        assert!(db.list_endpoints().await.unwrap().is_empty());
+        assert!(db.list_subscriptions().await.unwrap().is_empty());
+    }
+}
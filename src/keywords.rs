@@ -0,0 +1,277 @@
+//! Keyword matching modes
+//!
+//! `word` (the default) matches whole words or phrases, so "cat" doesn't
+//! match "category". `substring` matches anywhere in the text, and `regex`
+//! takes a single regular expression. All modes ignore case.
+//!
+//! Regexes come from users and run against every fetched comment, so they
+//! are compiled once per poll cycle with limits on length, nesting and
+//! compiled size. The regex engine itself runs in linear time, which rules
+//! out catastrophic backtracking; the limits keep compilation cheap.
+
+use regex::{Regex, RegexBuilder};
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest regex accepted, in bytes
+pub const MAX_PATTERN_LEN: usize = 256;
+
+/// Compiled program size limit for a regex, in bytes
+///
+/// Counted repetitions multiply: `(\w{50}){50}` is a short pattern but far
+/// over this limit.
+const REGEX_SIZE_LIMIT: usize = 256 * 1024;
+
+/// How deeply groups and repetitions may nest in a regex
+const REGEX_NEST_LIMIT: u32 = 20;
+
+/// How a subscription's keywords are matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeywordMode {
+    /// Anywhere in the text
+    Substring,
+    /// Whole words or phrases only
+    #[default]
+    Word,
+    /// One regular expression
+    Regex,
+}
+
+impl KeywordMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeywordMode::Substring => "substring",
+            KeywordMode::Word => "word",
+            KeywordMode::Regex => "regex",
+        }
+    }
+}
+
+impl fmt::Display for KeywordMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for KeywordMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "substring" => Ok(KeywordMode::Substring),
+            "word" => Ok(KeywordMode::Word),
+            "regex" => Ok(KeywordMode::Regex),
+            other => Err(format!("unknown keyword mode '{}' (expected substring, word or regex)", other)),
+        }
+    }
+}
+
+/// One subscription's keywords and how to match them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordSet {
+    pub mode: KeywordMode,
+    /// Lowercased keywords, or the single pattern in regex mode
+    pub keywords: Vec<String>,
+}
+
+impl KeywordSet {
+    /// Read keywords as stored: comma-separated, or one verbatim pattern in regex mode
+    pub fn from_stored(mode: KeywordMode, stored: &str) -> Self {
+        let keywords = match mode {
+            KeywordMode::Regex => vec![stored.trim().to_string()],
+            KeywordMode::Substring | KeywordMode::Word => parse_keywords(stored),
+        };
+        Self { mode, keywords }
+    }
+
+    /// Parse user input, rejecting an empty list or an unsafe regex
+    pub fn parse(mode: KeywordMode, input: &str) -> Result<Self, String> {
+        let set = Self::from_stored(mode, input);
+        if set.keywords.iter().all(|k| k.is_empty()) {
+            return Err("no keywords given".to_string());
+        }
+        KeywordMatcher::compile(std::slice::from_ref(&set))?;
+        Ok(set)
+    }
+}
+
+/// Parse a comma-separated keyword list ("rust, async runtime") into lowercased, unique keywords
+///
+/// An empty string yields an empty list.
+pub fn parse_keywords(input: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = input
+        .split(',')
+        .map(|k| k.trim().to_lowercase())
+        .filter(|k| !k.is_empty())
+        .collect();
+    keywords.sort_unstable();
+    keywords.dedup();
+    keywords
+}
+
+/// Format keywords the way `KeywordSet::from_stored` reads them
+pub fn format_keywords(keywords: &[String]) -> String {
+    keywords.join(",")
+}
+
+/// Compile a user-supplied regex within the size and complexity limits
+pub fn compile_regex(pattern: &str) -> Result<Regex, String> {
+    if pattern.len() > MAX_PATTERN_LEN {
+        return Err(format!("regex is {} bytes long (at most {})", pattern.len(), MAX_PATTERN_LEN));
+    }
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .nest_limit(REGEX_NEST_LIMIT)
+        .build()
+        .map_err(|e| format!("regex '{}' rejected: {}", pattern, e))?;
+    if regex.is_match("") {
+        return Err(format!("regex '{}' matches empty text, so it would match everything", pattern));
+    }
+    Ok(regex)
+}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Substring(String),
+    Word(String),
+    Regex(Regex),
+}
+
+/// Keyword sets compiled for matching
+#[derive(Debug, Clone, Default)]
+pub struct KeywordMatcher {
+    rules: Vec<Rule>,
+}
+
+impl KeywordMatcher {
+    /// Compile all of `sets`, failing on the first unsafe regex
+    pub fn compile(sets: &[KeywordSet]) -> Result<Self, String> {
+        let mut matcher = Self::default();
+        for set in sets {
+            matcher.add(set)?;
+        }
+        Ok(matcher)
+    }
+
+    /// Add one set's keywords, leaving the matcher unchanged if a regex is rejected
+    pub fn add(&mut self, set: &KeywordSet) -> Result<(), String> {
+        let rules = set
+            .keywords
+            .iter()
+            .map(|keyword| match set.mode {
+                KeywordMode::Substring => Ok(Rule::Substring(keyword.to_lowercase())),
+                KeywordMode::Word => Ok(Rule::Word(keyword.to_lowercase())),
+                KeywordMode::Regex => compile_regex(keyword).map(Rule::Regex),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.rules.extend(rules);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The first keyword found in `text`, or for a regex the text it matched
+    pub fn find(&self, text: &str) -> Option<String> {
+        let lowercase = text.to_lowercase();
+        self.rules.iter().find_map(|rule| match rule {
+            Rule::Substring(keyword) => lowercase.contains(keyword.as_str()).then(|| keyword.clone()),
+            Rule::Word(keyword) => contains_word(&lowercase, keyword).then(|| keyword.clone()),
+            Rule::Regex(regex) => regex.find(text).map(|m| m.as_str().to_string()),
+        })
+    }
+}
+
+/// Whether `word` appears in `haystack` with no letters or digits right before or after it
+fn contains_word(haystack: &str, word: &str) -> bool {
+    haystack.match_indices(word).any(|(i, _)| {
+        let before = haystack[..i].chars().next_back();
+        let after = haystack[i + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(mode: KeywordMode, input: &str) -> KeywordMatcher {
+        KeywordMatcher::compile(&[KeywordSet::parse(mode, input).unwrap()]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_keywords() {
+        assert_eq!(parse_keywords(" Tokio, async runtime,tokio,, "), vec!["async runtime", "tokio"]);
+        assert!(parse_keywords("").is_empty());
+        assert_eq!(format_keywords(&parse_keywords("b,a")), "a,b");
+    }
+
+    #[test]
+    fn test_parse_keyword_mode() {
+        assert_eq!("Regex".parse::<KeywordMode>().unwrap(), KeywordMode::Regex);
+        assert_eq!(" word ".parse::<KeywordMode>().unwrap(), KeywordMode::Word);
+        assert!("glob".parse::<KeywordMode>().is_err());
+        assert_eq!(KeywordMode::default(), KeywordMode::Word);
+    }
+
+    #[test]
+    fn test_word_mode_matches_whole_words() {
+        let keywords = matcher(KeywordMode::Word, "tokio, async runtime, c++, cat");
+
+        assert_eq!(keywords.find("Has anyone tried Tokio-console?").as_deref(), Some("tokio"));
+        assert_eq!(keywords.find("Which ASYNC RUNTIME do you use").as_deref(), Some("async runtime"));
+        assert_eq!(keywords.find("coming from C++.").as_deref(), Some("c++"));
+
+        // Whole words only
+        assert_eq!(keywords.find("tokiofied"), None);
+        assert_eq!(keywords.find("an async runtimes list"), None);
+        assert_eq!(keywords.find("wrong category"), None);
+        assert_eq!(keywords.find("nothing to see"), None);
+    }
+
+    #[test]
+    fn test_substring_mode_matches_anywhere() {
+        let keywords = matcher(KeywordMode::Substring, "cat");
+        assert_eq!(keywords.find("Wrong CATEGORY").as_deref(), Some("cat"));
+        assert_eq!(keywords.find("dog"), None);
+    }
+
+    #[test]
+    fn test_regex_mode_keeps_pattern_verbatim() {
+        // Neither split on the comma nor lowercased (\S is not \s)
+        let set = KeywordSet::parse(KeywordMode::Regex, r"\bv\d{1,3}\.\d+\S*").unwrap();
+        assert_eq!(set.keywords, vec![r"\bv\d{1,3}\.\d+\S*".to_string()]);
+
+        let keywords = KeywordMatcher::compile(&[set]).unwrap();
+        assert_eq!(keywords.find("Tokio V1.40-rc released").as_deref(), Some("V1.40-rc"));
+        assert_eq!(keywords.find("v one point forty"), None);
+    }
+
+    #[test]
+    fn test_unsafe_regexes_are_rejected() {
+        // Counted repetitions blow up the compiled size
+        let err = KeywordSet::parse(KeywordMode::Regex, r"(\w{50}){50}").unwrap_err();
+        assert!(err.contains("rejected"), "{}", err);
+
+        assert!(KeywordSet::parse(KeywordMode::Regex, &"a".repeat(MAX_PATTERN_LEN + 1)).unwrap_err().contains("at most"));
+        assert!(KeywordSet::parse(KeywordMode::Regex, &format!("{}a{}", "(".repeat(30), ")".repeat(30))).is_err());
+        assert!(KeywordSet::parse(KeywordMode::Regex, ".*").unwrap_err().contains("match everything"));
+        assert!(KeywordSet::parse(KeywordMode::Regex, "(unclosed").is_err());
+        assert!(KeywordSet::parse(KeywordMode::Word, " , ").is_err());
+    }
+
+    #[test]
+    fn test_matcher_combines_sets_and_skips_rejected_ones() {
+        let mut keywords = KeywordMatcher::default();
+        assert!(keywords.is_empty());
+        keywords.add(&KeywordSet::from_stored(KeywordMode::Word, "tokio")).unwrap();
+        assert!(keywords.add(&KeywordSet::from_stored(KeywordMode::Regex, r"(\w{50}){50}")).is_err());
+        keywords.add(&KeywordSet::from_stored(KeywordMode::Substring, "serde")).unwrap();
+
+        assert_eq!(keywords.find("using serde_json").as_deref(), Some("serde"));
+        assert_eq!(keywords.find("tokio").as_deref(), Some("tokio"));
+    }
+}
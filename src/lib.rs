@@ -1,9 +1,25 @@
+pub mod active_hours;
+pub mod batching;
+pub mod cli;
+pub mod comments;
 pub mod database;
 pub mod db_connection;
+pub mod env_config;
+pub mod explain;
+pub mod http_client;
+pub mod import;
+pub mod keywords;
+pub mod logging;
+pub mod migrations;
 pub mod models;
 pub mod notifiers;
 pub mod poller;
 pub mod rate_limiter;
+pub mod rising;
+pub mod score_alerts;
+pub mod search;
+pub mod secrets;
 pub mod services;
 pub mod shutdown;
+pub mod subreddit_health;
 pub mod tui;
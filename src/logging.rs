@@ -0,0 +1,89 @@
+//! Log verbosity that can be changed while the process runs
+//!
+//! Both binaries install their `EnvFilter` through a reload layer, so the TUI
+//! (`v` on the main menu) and the daemon (`SIGUSR1`) can step the level
+//! between info, debug and trace without a restart.
+
+use anyhow::Result;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Handle for reading and changing the level of a reloadable filter
+#[derive(Clone)]
+pub struct LogLevelHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    level: Arc<Mutex<LevelFilter>>,
+}
+
+impl std::fmt::Debug for LogLevelHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogLevelHandle").field("level", &self.current()).finish()
+    }
+}
+
+/// Wrap `filter` in a reload layer; add the layer directly to `tracing_subscriber::registry()`
+pub fn reloadable_filter(filter: EnvFilter) -> (reload::Layer<EnvFilter, Registry>, LogLevelHandle) {
+    let level = filter.max_level_hint().unwrap_or(LevelFilter::TRACE);
+    let (layer, handle) = reload::Layer::new(filter);
+    (layer, LogLevelHandle { handle, level: Arc::new(Mutex::new(level)) })
+}
+
+/// The level after `level` when cycling: info -> debug -> trace -> info
+pub fn next_level(level: LevelFilter) -> LevelFilter {
+    match level {
+        LevelFilter::DEBUG => LevelFilter::TRACE,
+        LevelFilter::TRACE => LevelFilter::INFO,
+        _ => LevelFilter::DEBUG,
+    }
+}
+
+impl LogLevelHandle {
+    /// The most verbose level currently logged
+    pub fn current(&self) -> LevelFilter {
+        *self.level.lock().unwrap()
+    }
+
+    /// Log everything at `level` and above (replaces any per-module `RUST_LOG` directives)
+    pub fn set(&self, level: LevelFilter) -> Result<()> {
+        self.handle.reload(EnvFilter::new(level.to_string()))?;
+        *self.level.lock().unwrap() = level;
+        Ok(())
+    }
+
+    /// Step to the next level (see `next_level`) and return it
+    pub fn cycle(&self) -> Result<LevelFilter> {
+        let level = next_level(self.current());
+        self.set(level)?;
+        Ok(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::Level;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_reload_changes_effective_filter() {
+        let (layer, handle) = reloadable_filter(EnvFilter::new("info"));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            assert_eq!(handle.current(), LevelFilter::INFO);
+            assert!(tracing::enabled!(Level::INFO));
+            assert!(!tracing::enabled!(Level::DEBUG));
+
+            assert_eq!(handle.cycle().unwrap(), LevelFilter::DEBUG);
+            assert!(tracing::enabled!(Level::DEBUG));
+            assert!(!tracing::enabled!(Level::TRACE));
+
+            assert_eq!(handle.cycle().unwrap(), LevelFilter::TRACE);
+            assert!(tracing::enabled!(Level::TRACE));
+
+            assert_eq!(handle.cycle().unwrap(), LevelFilter::INFO);
+            assert!(!tracing::enabled!(Level::DEBUG));
+        });
+    }
+}
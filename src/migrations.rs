@@ -0,0 +1,123 @@
+//! Database migrations and their applied/pending status
+//!
+//! The migrations in `migrations/` are embedded at compile time. Both binaries
+//! apply them at startup; `reddit-notifier migrate` runs them on demand and
+//! `reddit-notifier migrate status` reports them without changing anything.
+
+use anyhow::{Context, Result};
+use sqlx::migrate::Migrator;
+use sqlx::{Row, SqlitePool};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Migrations embedded from the `migrations/` directory
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// One migration, identified by its version (timestamp prefix) and description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+}
+
+impl fmt::Display for MigrationInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.version, self.description)
+    }
+}
+
+/// Which embedded migrations have been applied to a database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub applied: Vec<MigrationInfo>,
+    pub pending: Vec<MigrationInfo>,
+}
+
+impl MigrationStatus {
+    /// The most recent applied migration (None = empty database)
+    pub fn current(&self) -> Option<&MigrationInfo> {
+        self.applied.last()
+    }
+
+    /// True when every embedded migration has been applied
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl fmt::Display for MigrationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in &self.applied {
+            writeln!(f, "applied  {}", m)?;
+        }
+        for m in &self.pending {
+            writeln!(f, "pending  {}", m)?;
+        }
+        write!(f, "{} applied, {} pending", self.applied.len(), self.pending.len())
+    }
+}
+
+/// Report applied vs pending migrations without modifying the database
+///
+/// Works on a read-only connection; a database that has never been migrated
+/// reports every migration as pending.
+pub async fn migration_status(pool: &SqlitePool) -> Result<MigrationStatus> {
+    // MIGRATOR uses sqlx's default bookkeeping table
+    let has_table = sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'")
+        .fetch_optional(pool)
+        .await?
+        .is_some();
+
+    let applied_versions: HashSet<i64> = if has_table {
+        sqlx::query("SELECT version FROM _sqlx_migrations WHERE success = 1")
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| row.get::<i64, _>("version"))
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    let (applied, pending) = MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| MigrationInfo {
+            version: m.version,
+            description: m.description.to_string(),
+        })
+        .partition(|m| applied_versions.contains(&m.version));
+
+    Ok(MigrationStatus { applied, pending })
+}
+
+/// Apply all pending migrations and return the resulting status
+pub async fn run_migrations(pool: &SqlitePool) -> Result<MigrationStatus> {
+    MIGRATOR
+        .run(pool)
+        .await
+        .context("Failed to run database migrations")?;
+    migration_status(pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_status_reflects_applied_migrations() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        let before = migration_status(&pool).await.unwrap();
+        assert!(before.applied.is_empty());
+        assert!(!before.pending.is_empty());
+        assert_eq!(before.current(), None);
+
+        let after = run_migrations(&pool).await.unwrap();
+        assert!(after.is_up_to_date());
+        assert_eq!(after.applied, before.pending);
+        let latest = MIGRATOR.iter().map(|m| m.version).max().unwrap();
+        assert_eq!(after.current().unwrap().version, latest);
+        assert!(after.to_string().ends_with(&format!("{} applied, 0 pending", after.applied.len())));
+    }
+}
@@ -1,10 +1,81 @@
 use anyhow::{Context, Result};
 
+use crate::batching::DEFAULT_BATCH_MAX_POSTS;
+
+/// Maximum number of posts Reddit will return in a single listing request
+pub const MAX_LISTING_LIMIT: u32 = 100;
+
+/// Default cap on posts notified per subreddit in one poll cycle
+pub const DEFAULT_MAX_NOTIFICATIONS_PER_SUBREDDIT: u32 = 25;
+
+/// Largest allowed poll jitter, as a percentage of the batch spacing
+pub const MAX_POLL_JITTER_PCT: u32 = 50;
+
+/// Order in which the poller sends a batch's new-post notifications
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotificationOrder {
+    /// Oldest post first, so notifications read chronologically in a chat log
+    #[default]
+    OldestFirst,
+    /// As returned by Reddit (newest first for `/new`)
+    Listing,
+}
+
+impl NotificationOrder {
+    /// Parse a `NOTIFICATION_ORDER` value ("oldest" or "listing")
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "oldest" | "oldest_first" => Some(Self::OldestFirst),
+            "listing" => Some(Self::Listing),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub database_url: String,
     pub rate_limit_per_minute: u32,
     pub reddit_user_agent: String,
+    pub listing_limit: u32,
+    /// Random ±% applied to the spacing between batch requests (0 = disabled)
+    pub poll_jitter_pct: u32,
+    /// Seconds to sleep when there is nothing to poll
+    pub idle_sleep_secs: u64,
+    /// Fetched batches recorded concurrently in a poll cycle (1 = sequential)
+    pub poll_workers: usize,
+    /// Auto-mute a subreddit after this many failed poll cycles in a row (0 = off)
+    pub auto_mute_after: u32,
+    /// Days of delivery history kept for reporting (0 = keep forever)
+    pub delivery_retention_days: u32,
+    /// Most posts notified per subreddit in one poll cycle (0 = no cap)
+    pub max_notifications_per_subreddit_per_cycle: u32,
+    /// Order in which new posts are sent within a poll cycle
+    pub notification_order: NotificationOrder,
+    /// Record but don't send the posts found by the first poll cycle after startup
+    pub startup_suppress: bool,
+    /// Most posts notified, across all subreddits, in the first poll cycle after startup (0 = no cap)
+    pub startup_max_notifications: u32,
+    /// Collect each endpoint's notifications for this many seconds and send them as one message (0 = off)
+    pub notify_batch_window_secs: u64,
+    /// Send a batch as soon as it holds this many posts
+    pub notify_batch_max_posts: u32,
+    /// Timeout for each outbound request (notifications and Reddit polling), in seconds
+    pub notifier_timeout_secs: u64,
+    /// Attempts per notification before it counts as failed
+    pub notifier_max_attempts: u32,
+    /// Send endpoint footers (NOTIFICATION_FOOTERS=false drops every one)
+    pub notification_footers: bool,
+    /// Proxy for outbound requests (HTTPS_PROXY, http(s):// or socks5://)
+    pub https_proxy: Option<String>,
+    /// Extra root CA certificate (PEM) to trust for outbound requests
+    pub reddit_ca_cert: Option<String>,
+    /// Restore the TUI's last screen and selection on startup
+    pub tui_restore_session: bool,
+    /// Override for the TUI session file (defaults to next to the database)
+    pub tui_state_file: Option<String>,
+    /// Force the TUI's compact layout on or off (None = automatic on short terminals)
+    pub tui_compact: Option<bool>,
 }
 
 impl AppConfig {
@@ -44,10 +115,198 @@ impl AppConfig {
                 )
             });
 
+        // Number of posts requested per listing call (Reddit caps this at 100)
+        let listing_limit = std::env::var("REDDIT_LISTING_LIMIT")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .map(clamp_listing_limit)
+            .unwrap_or(MAX_LISTING_LIMIT);
+
+        // Random jitter on the spacing between polls, so requests don't land on a fixed beat
+        let poll_jitter_pct = std::env::var("REDDIT_POLL_JITTER_PCT")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .map(clamp_poll_jitter_pct)
+            .unwrap_or(0);
+
+        // Pause between checks while there is nothing to poll (avoids spinning when idle)
+        let idle_sleep_secs = std::env::var("REDDIT_IDLE_SLEEP_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(30);
+
+        // Batches recorded at once; capped by the database pool size when the poller starts
+        let poll_workers = std::env::var("POLL_WORKERS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(1);
+
+        // Mute subreddits that keep failing (banned, deleted, private); off by default
+        let auto_mute_after = std::env::var("REDDIT_AUTO_MUTE_AFTER")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(0);
+
+        // Delivery history for `reddit-notifier stats`; pruned by the poller
+        let delivery_retention_days = std::env::var("DELIVERY_RETENTION_DAYS")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(30);
+
+        // Blast-radius cap: a flood of posts (or an overly broad subscription) sends at most this many per cycle
+        let max_notifications_per_subreddit_per_cycle = std::env::var("MAX_NOTIFICATIONS_PER_SUBREDDIT_PER_CYCLE")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_MAX_NOTIFICATIONS_PER_SUBREDDIT);
+
+        // Send each cycle's posts oldest-first unless Reddit's own order is requested
+        let notification_order = match std::env::var("NOTIFICATION_ORDER") {
+            Ok(value) => NotificationOrder::parse(&value).unwrap_or_else(|| {
+                tracing::warn!("NOTIFICATION_ORDER is set to '{}', expected 'oldest' or 'listing'. Using oldest.", value);
+                NotificationOrder::OldestFirst
+            }),
+            Err(_) => NotificationOrder::default(),
+        };
+
+        // Startup grace: posts that piled up while the daemon was down don't all fire at once
+        let startup_suppress = std::env::var("STARTUP_SUPPRESS")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(false);
+        let startup_max_notifications = std::env::var("STARTUP_MAX_NOTIFICATIONS")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(0);
+
+        // Time-based batching: one message per endpoint per window, however often the poller runs
+        let notify_batch_window_secs = std::env::var("NOTIFY_BATCH_WINDOW_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+        let notify_batch_max_posts = std::env::var("NOTIFY_BATCH_MAX_POSTS")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_BATCH_MAX_POSTS);
+
+        // Shared by the daemon and the TUI's test sends, so tests behave like real deliveries
+        let notifier_timeout_secs = std::env::var("NOTIFIER_TIMEOUT_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(15);
+        let notifier_max_attempts = std::env::var("NOTIFIER_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(3);
+        let notification_footers = std::env::var("NOTIFICATION_FOOTERS")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(true);
+
+        // Corporate network support: outbound proxy and an internal CA
+        let https_proxy = std::env::var("HTTPS_PROXY")
+            .or_else(|_| std::env::var("https_proxy"))
+            .ok()
+            .filter(|s| !s.is_empty());
+        let reddit_ca_cert = std::env::var("REDDIT_CA_CERT").ok().filter(|s| !s.is_empty());
+
+        // TUI session restore (on by default; set TUI_RESTORE_SESSION=false for a fixed start)
+        let tui_restore_session = std::env::var("TUI_RESTORE_SESSION")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(true);
+
+        let tui_state_file = std::env::var("TUI_STATE_FILE").ok();
+
+        // Compact TUI layout: unset picks it by terminal height
+        let tui_compact = std::env::var("TUI_COMPACT").ok().and_then(|s| s.parse::<bool>().ok());
+
         Ok(Self {
             database_url,
             rate_limit_per_minute,
             reddit_user_agent,
+            listing_limit,
+            poll_jitter_pct,
+            idle_sleep_secs,
+            poll_workers,
+            auto_mute_after,
+            delivery_retention_days,
+            max_notifications_per_subreddit_per_cycle,
+            notification_order,
+            startup_suppress,
+            startup_max_notifications,
+            notify_batch_window_secs,
+            notify_batch_max_posts,
+            notifier_timeout_secs,
+            notifier_max_attempts,
+            notification_footers,
+            https_proxy,
+            reddit_ca_cert,
+            tui_restore_session,
+            tui_state_file,
+            tui_compact,
         })
     }
 }
+
+/// Clamp a requested listing limit to the range Reddit accepts (1..=100)
+pub fn clamp_listing_limit(requested: u32) -> u32 {
+    let clamped = requested.clamp(1, MAX_LISTING_LIMIT);
+    if clamped != requested {
+        tracing::warn!(
+            "REDDIT_LISTING_LIMIT is set to {}, which is outside the allowed range 1..={}. Using {}.",
+            requested,
+            MAX_LISTING_LIMIT,
+            clamped
+        );
+    }
+    clamped
+}
+
+/// Clamp a requested poll jitter percentage to 0..=50
+pub fn clamp_poll_jitter_pct(requested: u32) -> u32 {
+    let clamped = requested.min(MAX_POLL_JITTER_PCT);
+    if clamped != requested {
+        tracing::warn!(
+            "REDDIT_POLL_JITTER_PCT is set to {}, which exceeds the maximum of {}. Using {}.",
+            requested,
+            MAX_POLL_JITTER_PCT,
+            clamped
+        );
+    }
+    clamped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_notification_order() {
+        assert_eq!(NotificationOrder::parse("oldest"), Some(NotificationOrder::OldestFirst));
+        assert_eq!(NotificationOrder::parse(" Listing "), Some(NotificationOrder::Listing));
+        assert_eq!(NotificationOrder::parse("newest"), None);
+    }
+
+    #[test]
+    fn test_clamp_listing_limit_in_range() {
+        assert_eq!(clamp_listing_limit(1), 1);
+        assert_eq!(clamp_listing_limit(25), 25);
+        assert_eq!(clamp_listing_limit(100), 100);
+    }
+
+    #[test]
+    fn test_clamp_listing_limit_out_of_range() {
+        assert_eq!(clamp_listing_limit(0), 1);
+        assert_eq!(clamp_listing_limit(101), 100);
+        assert_eq!(clamp_listing_limit(u32::MAX), 100);
+    }
+
+    #[test]
+    fn test_clamp_poll_jitter_pct() {
+        assert_eq!(clamp_poll_jitter_pct(0), 0);
+        assert_eq!(clamp_poll_jitter_pct(20), 20);
+        assert_eq!(clamp_poll_jitter_pct(51), 50);
+    }
+}
@@ -1,18 +1,171 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::borrow::Cow;
 use std::str::FromStr;
 
+use crate::search::SearchQuery;
+
+/// Format of stored timestamps: ISO-8601 UTC with a `Z` suffix
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Largest endpoint `config_json` accepted, in bytes
+pub const MAX_ENDPOINT_CONFIG_LEN: usize = 4096;
+
+/// Longest endpoint note accepted, in characters
+pub const MAX_ENDPOINT_NOTE_LEN: usize = 200;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EndpointKind {
     Discord,
     Pushover,
+    Mattermost,
+    Teams,
+    /// Local file or Unix domain socket, for custom integrations
+    File,
 }
 
 impl EndpointKind {
+    /// Every supported endpoint kind, in display order
+    pub fn all() -> &'static [EndpointKind] {
+        &[Self::Discord, Self::Pushover, Self::Mattermost, Self::Teams, Self::File]
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Discord => "discord",
             Self::Pushover => "pushover",
+            Self::Mattermost => "mattermost",
+            Self::Teams => "teams",
+            Self::File => "file",
         }
     }
+
+    /// What this kind of endpoint supports
+    ///
+    /// Discord caps message content at 2000 characters, Pushover messages at
+    /// 1024 and Mattermost posts at 16383 (the server default); longer
+    /// messages are rejected with an opaque API error. Teams rejects payloads
+    /// over about 28 KB, so its text is kept well under that after card markup.
+    /// Files have no limit of their own; titles are capped like Mattermost's
+    /// so a line stays readable by line-oriented tools.
+    pub fn capabilities(&self) -> NotifierCapabilities {
+        match self {
+            Self::Discord => NotifierCapabilities {
+                display_name: "Discord",
+                embeds: true,
+                mentions: true,
+                html: false,
+                max_message_len: 2000,
+                required_fields: &["webhook_url"],
+                webhook_url_field: Some("webhook_url"),
+                field_help: &[
+                    ("webhook_url", "Server Settings → Integrations → Webhooks → New Webhook → Copy Webhook URL"),
+                    ("username", "Name shown as the sender (default: Reddit Notifier)"),
+                    ("mention", "@here, <@user_id> or <@&role_id>; IDs via Developer Mode → right-click → Copy ID"),
+                ],
+            },
+            Self::Pushover => NotifierCapabilities {
+                display_name: "Pushover",
+                embeds: false,
+                mentions: false,
+                html: false,
+                max_message_len: 1024,
+                required_fields: &["token", "user"],
+                webhook_url_field: None,
+                field_help: &[
+                    ("token", "Create an application at pushover.net/apps/build and copy its API token"),
+                    ("user", "Your user key, shown on the pushover.net dashboard after logging in"),
+                    ("device", "Device name to send to (empty = all of your devices)"),
+                ],
+            },
+            Self::Mattermost => NotifierCapabilities {
+                display_name: "Mattermost",
+                embeds: false,
+                mentions: false,
+                html: false,
+                max_message_len: 16383,
+                required_fields: &["webhook_url"],
+                webhook_url_field: Some("webhook_url"),
+                field_help: &[
+                    ("webhook_url", "Integrations → Incoming Webhooks → Add Incoming Webhook, then copy the URL"),
+                    ("channel", "Channel name (from its URL) to post to instead of the webhook's own"),
+                    ("username", "Needs \"Enable integrations to override usernames\" in System Console"),
+                ],
+            },
+            Self::Teams => NotifierCapabilities {
+                display_name: "Microsoft Teams",
+                embeds: true,
+                mentions: false,
+                html: false,
+                max_message_len: 4000,
+                required_fields: &["webhook_url"],
+                webhook_url_field: Some("webhook_url"),
+                field_help: &[
+                    ("webhook_url", "Channel ⋯ → Workflows → \"Post to a channel when a webhook request is received\""),
+                    ("card", "adaptive for Workflows webhooks, message_card for legacy Office 365 connectors"),
+                ],
+            },
+            Self::File => NotifierCapabilities {
+                display_name: "File / socket",
+                embeds: false,
+                mentions: false,
+                html: false,
+                max_message_len: 16383,
+                required_fields: &["path"],
+                webhook_url_field: None,
+                field_help: &[
+                    ("path", "Absolute path; appended to as a file, or written to if it is a Unix domain socket"),
+                    ("format", "json (default: one JSON object per line) or text (one line per post)"),
+                ],
+            },
+        }
+    }
+
+    /// Longest message text the service accepts, in characters
+    pub fn max_message_len(&self) -> usize {
+        self.capabilities().max_message_len
+    }
+}
+
+/// What an endpoint kind supports
+///
+/// UI and validation code consult this rather than matching on the kind, so
+/// kind-specific knowledge lives in `EndpointKind::capabilities`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NotifierCapabilities {
+    /// Name shown in the UI ("Discord")
+    pub display_name: &'static str,
+    /// Posts are sent as rich embeds (title, description and link) rather than plain text
+    pub embeds: bool,
+    /// Priority subscriptions can ping someone (the `mention` config key)
+    pub mentions: bool,
+    /// Message text is sent as HTML
+    pub html: bool,
+    /// Longest message text the service accepts, in characters
+    pub max_message_len: usize,
+    /// Config keys that must be present and non-empty
+    pub required_fields: &'static [&'static str],
+    /// Config key holding a webhook URL, which must use https://
+    pub webhook_url_field: Option<&'static str>,
+    /// Where to find or how to fill in this kind's own config keys, shown under form fields
+    pub field_help: &'static [(&'static str, &'static str)],
+}
+
+/// Help for the config keys every kind shares
+const COMMON_FIELD_HELP: &[(&str, &str)] = &[
+    ("footer", "Line added after the post title, up to 200 characters"),
+    ("active_hours", "Only send between these times, e.g. 09:00-17:00 or 22:00-06:00"),
+    ("timezone", "IANA name for the active hours, e.g. Europe/Berlin (default: UTC)"),
+];
+
+impl NotifierCapabilities {
+    /// Help text for config key `key`, if there is any
+    pub fn field_help(&self, key: &str) -> Option<&'static str> {
+        self.field_help
+            .iter()
+            .chain(COMMON_FIELD_HELP)
+            .find(|(k, _)| *k == key)
+            .map(|(_, help)| *help)
+    }
 }
 
 impl FromStr for EndpointKind {
@@ -22,6 +175,9 @@ impl FromStr for EndpointKind {
         match s {
             "discord" => Ok(Self::Discord),
             "pushover" => Ok(Self::Pushover),
+            "mattermost" => Ok(Self::Mattermost),
+            "teams" => Ok(Self::Teams),
+            "file" => Ok(Self::File),
             _ => Err(format!("Unknown endpoint kind: {}", s)),
         }
     }
@@ -36,11 +192,35 @@ pub struct EndpointRow {
     pub note: Option<String>,
 }
 
+/// An endpoint created by `DatabaseService::import_rows`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewEndpoint {
+    pub kind: String,
+    pub config_json: String,
+    pub note: Option<String>,
+}
+
+/// A subscription created by `DatabaseService::import_rows`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewSubscription {
+    pub subreddit: String,
+    /// Endpoints to link, as indexes into the endpoints imported with it
+    pub endpoints: Vec<usize>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SubscriptionRow {
     pub id: i64,
     pub subreddit: String,
     pub created_at: String,
+    /// Skip stickied/pinned posts (rules, megathreads) for this subscription
+    pub skip_stickied: bool,
+    /// Paused: excluded from polling, but endpoint links are kept
+    pub muted: bool,
+    /// Notifications for this subscription include the endpoint's mention
+    pub priority: bool,
+    /// Set for a search subscription, which polls this search instead of the subreddit's new posts
+    pub search: Option<SearchQuery>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,4 +229,205 @@ pub struct NotifiedPostRow {
     pub subreddit: String,
     pub post_id: String,
     pub first_seen_at: String,
+    /// Post title and link; None for posts recorded before these were stored
+    pub title: Option<String>,
+    pub url: Option<String>,
+}
+
+impl NotifiedPostRow {
+    /// Parse `first_seen_at` as UTC
+    ///
+    /// Accepts the stored ISO-8601 form and SQLite's legacy `YYYY-MM-DD HH:MM:SS`
+    /// (which is also UTC).
+    pub fn first_seen_utc(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.first_seen_at)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(&self.first_seen_at, "%Y-%m-%d %H:%M:%S")
+                    .ok()
+                    .map(|naive| naive.and_utc())
+            })
+    }
+
+    /// One CSV record (with trailing newline) in the `NOTIFIED_POSTS_CSV_HEADER` column order
+    pub fn to_csv_line(&self) -> String {
+        format!(
+            "{},{},{},{}\n",
+            self.id,
+            csv_field(&self.subreddit),
+            csv_field(&self.post_id),
+            csv_field(&self.first_seen_at)
+        )
+    }
+}
+
+/// Outcome of delivering one notification to one endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Sent,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Delivery totals for one endpoint over a reporting window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryStats {
+    pub endpoint_id: i64,
+    pub total: i64,
+    pub succeeded: i64,
+    /// 95th percentile send latency (nearest rank), including failed sends
+    pub p95_latency_ms: Option<i64>,
+}
+
+impl DeliveryStats {
+    /// Fraction of deliveries that succeeded (0.0 - 1.0)
+    pub fn success_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.succeeded as f64 / self.total as f64
+        }
+    }
+}
+
+/// A notified post with the title and link needed to send it again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredPost {
+    pub subreddit: String,
+    pub post_id: String,
+    pub title: String,
+    pub url: String,
+}
+
+/// Failed deliveries to one endpoint within a troubleshooting window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryFailures {
+    pub endpoint_id: i64,
+    /// Failed deliveries in the window
+    pub failures: i64,
+    pub last_failed_at: DateTime<Utc>,
+    /// Error text of the most recent failure
+    pub last_error: Option<String>,
+}
+
+/// The most recent failed delivery to an endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastDeliveryError {
+    pub endpoint_id: i64,
+    pub failed_at: DateTime<Utc>,
+    pub error: Option<String>,
+    /// Reddit ID of the post (or comment) being delivered
+    pub post_id: String,
+    /// Title and link of the post, when it was stored
+    pub post: Option<StoredPost>,
+    /// A later delivery to the endpoint succeeded
+    pub resolved: bool,
+}
+
+/// One inconsistent row found by the integrity check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityProblem {
+    /// Subscription or endpoint ID
+    pub id: i64,
+    /// Column holding the bad value
+    pub field: &'static str,
+    pub reason: String,
+}
+
+/// Rows the integrity check (`doctor`) found inconsistent
+///
+/// Cascading deletes should keep these lists empty; they catch manual edits
+/// and rows written by older or buggy versions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// (subscription_id, endpoint_id) links to a subscription or endpoint that no longer exists
+    pub orphaned_links: Vec<(i64, i64)>,
+    /// Subscriptions with an alert setting that can't be parsed
+    pub invalid_subscriptions: Vec<IntegrityProblem>,
+    /// Active endpoints with an unknown kind or invalid config
+    pub invalid_endpoints: Vec<IntegrityProblem>,
+}
+
+impl IntegrityReport {
+    /// Total number of problems found
+    pub fn len(&self) -> usize {
+        self.orphaned_links.len() + self.invalid_subscriptions.len() + self.invalid_endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Nearest-rank 95th percentile of `latencies` (sorted in place)
+pub fn p95(latencies: &mut [i64]) -> Option<i64> {
+    if latencies.is_empty() {
+        return None;
+    }
+    latencies.sort_unstable();
+    let rank = (latencies.len() * 95).div_ceil(100);
+    Some(latencies[rank - 1])
+}
+
+/// Header line of a notification history CSV export
+pub const NOTIFIED_POSTS_CSV_HEADER: &str = "id,subreddit,post_id,first_seen_at\n";
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_line_quotes_special_characters() {
+        let mut post = post_seen_at("2026-02-15T09:30:00Z");
+        assert_eq!(post.to_csv_line(), "1,rust,abc,2026-02-15T09:30:00Z\n");
+        post.post_id = "a,\"b\"".to_string();
+        assert_eq!(post.to_csv_line(), "1,rust,\"a,\"\"b\"\"\",2026-02-15T09:30:00Z\n");
+    }
+
+    fn post_seen_at(first_seen_at: &str) -> NotifiedPostRow {
+        NotifiedPostRow {
+            id: 1,
+            subreddit: "rust".to_string(),
+            post_id: "abc".to_string(),
+            first_seen_at: first_seen_at.to_string(),
+            title: None,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_first_seen_utc_parses_iso_and_legacy() {
+        let expected = DateTime::parse_from_rfc3339("2026-02-15T09:30:00Z").unwrap();
+        assert_eq!(post_seen_at("2026-02-15T09:30:00Z").first_seen_utc(), Some(expected.into()));
+        assert_eq!(post_seen_at("2026-02-15 09:30:00").first_seen_utc(), Some(expected.into()));
+        assert_eq!(post_seen_at("yesterday").first_seen_utc(), None);
+    }
+
+    #[test]
+    fn test_p95_nearest_rank() {
+        assert_eq!(p95(&mut []), None);
+        assert_eq!(p95(&mut [42]), Some(42));
+        let mut latencies: Vec<i64> = (1..=20).rev().collect();
+        assert_eq!(p95(&mut latencies), Some(19));
+        let mut latencies: Vec<i64> = (1..=100).collect();
+        assert_eq!(p95(&mut latencies), Some(95));
+    }
 }
@@ -6,5 +6,5 @@ pub mod reddit_api;
 // Re-export commonly used types at models root for convenience
 pub use config::AppConfig;
 pub use database::{EndpointKind, EndpointRow, NotifiedPostRow, SubscriptionRow};
-pub use notifiers::{DiscordConfig, PushoverConfig};
-pub use reddit_api::{RedditChild, RedditListing, RedditListingData, RedditPost};
+pub use notifiers::{validate_endpoint_config, DiscordConfig, DiscordWebhooks, PushoverConfig};
+pub use reddit_api::{RedditChild, RedditComment, RedditListing, RedditListingData, RedditPost};
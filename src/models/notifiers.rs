@@ -1,10 +1,90 @@
+use reqwest::header::{HeaderName, HeaderValue};
 use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::active_hours::endpoint_active_hours;
+use crate::models::database::EndpointKind;
+use crate::secrets::is_secret_ref;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct DiscordConfig {
-    pub webhook_url: String,
+    /// `webhook_url`, or `webhook_urls` to post each notification to several channels
+    #[serde(flatten)]
+    pub webhooks: DiscordWebhooks,
     #[serde(default)]
     pub username: Option<String>,
+    /// Ping sent with notifications from priority subscriptions (see `parse_mention`)
+    #[serde(default)]
+    pub mention: Option<String>,
+    /// Prefix the title of NSFW posts with a content warning
+    #[serde(default = "default_true")]
+    pub include_nsfw_marker: bool,
+    /// Text appended after the message body, e.g. "via reddit-notifier"
+    #[serde(default)]
+    pub footer: Option<String>,
+    /// Headers added to every request, e.g. auth for a reverse proxy in front of the service
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
+}
+
+/// The webhook(s) a Discord endpoint posts to
+///
+/// Configs hold either `"webhook_url": "..."` (one channel, the original
+/// format) or `"webhook_urls": [...]` (fan out to several), never both.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "WebhookFields")]
+pub enum DiscordWebhooks {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl DiscordWebhooks {
+    /// Every webhook URL, in config order
+    pub fn urls(&self) -> &[String] {
+        match self {
+            Self::One(url) => std::slice::from_ref(url),
+            Self::Many(urls) => urls,
+        }
+    }
+
+    /// Mutable access to the URLs (e.g. to redact them)
+    pub fn urls_mut(&mut self) -> &mut [String] {
+        match self {
+            Self::One(url) => std::slice::from_mut(url),
+            Self::Many(urls) => urls,
+        }
+    }
+
+    /// The config key the URLs are stored under
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::One(_) => "webhook_url",
+            Self::Many(_) => "webhook_urls",
+        }
+    }
+}
+
+/// Both webhook keys as they appear in config_json, before checking that exactly one is set
+#[derive(Deserialize)]
+struct WebhookFields {
+    #[serde(default)]
+    webhook_url: Option<String>,
+    #[serde(default)]
+    webhook_urls: Option<Vec<String>>,
+}
+
+impl TryFrom<WebhookFields> for DiscordWebhooks {
+    type Error = String;
+
+    fn try_from(fields: WebhookFields) -> Result<Self, Self::Error> {
+        match (fields.webhook_url, fields.webhook_urls) {
+            (Some(url), None) => Ok(Self::One(url)),
+            (None, Some(urls)) if urls.is_empty() => Err("webhook_urls is empty".to_string()),
+            (None, Some(urls)) => Ok(Self::Many(urls)),
+            (Some(_), Some(_)) => Err("set webhook_url or webhook_urls, not both".to_string()),
+            (None, None) => Err("missing field `webhook_url` (or `webhook_urls`)".to_string()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -13,4 +93,569 @@ pub struct PushoverConfig {
     pub user: String,
     #[serde(default)]
     pub device: Option<String>,
+    /// Prefix the title of NSFW posts with a content warning
+    #[serde(default = "default_true")]
+    pub include_nsfw_marker: bool,
+    /// Text appended after the message body, e.g. "via reddit-notifier"
+    #[serde(default)]
+    pub footer: Option<String>,
+    /// Headers added to every request, e.g. auth for a reverse proxy in front of the service
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MattermostConfig {
+    /// Incoming webhook, `https://<server>/hooks/<id>`
+    pub webhook_url: String,
+    /// Post to this channel instead of the webhook's default (channel name, e.g. `town-square`)
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Shown as the sender; needs "Enable integrations to override usernames" on the server
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Prefix the title of NSFW posts with a content warning
+    #[serde(default = "default_true")]
+    pub include_nsfw_marker: bool,
+    /// Text appended after the message body, e.g. "via reddit-notifier"
+    #[serde(default)]
+    pub footer: Option<String>,
+    /// Headers added to every request, e.g. auth for a reverse proxy in front of the service
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
+}
+
+/// Check that a Mattermost webhook URL looks like `https://<server>/hooks/<id>`
+///
+/// `file:` / `env:` references are resolved (and checked) at send time.
+pub fn check_mattermost_webhook_url(url: &str) -> Result<(), String> {
+    if is_secret_ref(url) {
+        return Ok(());
+    }
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("invalid webhook_url: {}", e))?;
+    let hook_id = parsed.path().trim_end_matches('/').rsplit_once("/hooks/").map(|(_, id)| id);
+    if parsed.scheme() != "https" || parsed.host_str().is_none() {
+        return Err("webhook_url must start with https://".to_string());
+    }
+    match hook_id {
+        Some(id) if !id.is_empty() && !id.contains('/') => Ok(()),
+        _ => Err("webhook_url must be a Mattermost incoming webhook (https://<server>/hooks/<id>)".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamsConfig {
+    /// Workflows ("Post to a channel when a webhook request is received") or legacy connector URL
+    pub webhook_url: String,
+    /// Card format; connectors created before Workflows also accept `message_card`
+    #[serde(default)]
+    pub card: TeamsCard,
+    /// Prefix the title of NSFW posts with a content warning
+    #[serde(default = "default_true")]
+    pub include_nsfw_marker: bool,
+    /// Text appended after the message body, e.g. "via reddit-notifier"
+    #[serde(default)]
+    pub footer: Option<String>,
+    /// Headers added to every request, e.g. auth for a reverse proxy in front of the service
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
+}
+
+/// The card a Teams endpoint posts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamsCard {
+    /// Adaptive Card, accepted by Workflows and connectors
+    #[default]
+    Adaptive,
+    /// Legacy Office 365 connector card
+    MessageCard,
+}
+
+/// Check that a Teams webhook URL is https with a host and a path
+///
+/// Workflow URLs live on Azure/Power Platform hosts and connector URLs on
+/// `*.webhook.office.com`, so only the shape is checked. `file:` / `env:`
+/// references are resolved (and checked) at send time.
+pub fn check_teams_webhook_url(url: &str) -> Result<(), String> {
+    if is_secret_ref(url) {
+        return Ok(());
+    }
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("invalid webhook_url: {}", e))?;
+    if parsed.scheme() != "https" || parsed.host_str().is_none() {
+        return Err("webhook_url must start with https://".to_string());
+    }
+    if parsed.path().trim_matches('/').is_empty() {
+        return Err("webhook_url has no path; copy the full URL from the Teams workflow or connector".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileConfig {
+    /// Absolute path of a file to append to, or of a Unix domain socket to write to
+    pub path: String,
+    /// How each notification is written
+    #[serde(default)]
+    pub format: FileFormat,
+    /// Prefix the title of NSFW posts with a content warning
+    #[serde(default = "default_true")]
+    pub include_nsfw_marker: bool,
+}
+
+/// How a file endpoint writes each notification: always one line per notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileFormat {
+    /// A JSON object per line (JSON Lines)
+    #[default]
+    Json,
+    /// `<time> r/<subreddit>: <title> <url>`
+    Text,
+}
+
+/// Check that a file endpoint's path is absolute
+///
+/// The daemon and the TUI may run from different directories (or containers),
+/// so a relative path would point at different files. `env:` references are
+/// resolved (and checked) at send time.
+pub fn check_file_path(path: &str) -> Result<(), String> {
+    if is_secret_ref(path) {
+        return Ok(());
+    }
+    if path.trim().is_empty() {
+        return Err("path is empty".to_string());
+    }
+    if !std::path::Path::new(path.trim()).is_absolute() {
+        return Err("path must be absolute, e.g. /var/log/reddit-notifier/posts.jsonl".to_string());
+    }
+    Ok(())
+}
+
+/// Longest endpoint footer accepted, in characters
+pub const MAX_FOOTER_LEN: usize = 200;
+
+fn check_footer(footer: Option<&str>) -> Result<(), String> {
+    match footer {
+        Some(footer) if footer.chars().count() > MAX_FOOTER_LEN => {
+            Err(format!("footer is longer than {} characters", MAX_FOOTER_LEN))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Headers that `extra_headers` can't override (set by the HTTP client itself)
+const RESERVED_HEADERS: &[&str] = &["host", "content-type", "content-length", "transfer-encoding"];
+
+/// Check that every extra header is a valid, non-reserved HTTP header
+///
+/// `file:` / `env:` values are only checked once resolved, at send time.
+pub fn check_extra_headers(headers: &HashMap<String, String>) -> Result<(), String> {
+    for (name, value) in headers {
+        let parsed = HeaderName::from_bytes(name.as_bytes()).map_err(|_| format!("invalid header name '{}'", name))?;
+        if RESERVED_HEADERS.contains(&parsed.as_str()) {
+            return Err(format!("header '{}' can't be overridden", name));
+        }
+        if !is_secret_ref(value) && HeaderValue::from_str(value).is_err() {
+            return Err(format!("invalid value for header '{}'", name));
+        }
+    }
+    Ok(())
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One allowed piece of a Discord mention
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MentionTarget {
+    /// `@here` - everyone currently online in the channel
+    Here,
+    /// `<@id>` or `<@!id>` - a single user
+    User(u64),
+    /// `<@&id>` - everyone with a role
+    Role(u64),
+}
+
+/// Parse a mention string against the allow-list
+///
+/// Only `@here`, user mentions and role mentions are accepted, separated by
+/// whitespace. Anything else - in particular `@everyone` - is rejected so a
+/// typo can't ping a whole server.
+pub fn parse_mention(mention: &str) -> Result<Vec<MentionTarget>, String> {
+    let targets = mention
+        .split_whitespace()
+        .map(|token| {
+            if token == "@here" {
+                return Ok(MentionTarget::Here);
+            }
+            let id = |rest: &str| rest.strip_suffix('>').and_then(|id| id.parse::<u64>().ok());
+            if let Some(role) = token.strip_prefix("<@&").and_then(id) {
+                return Ok(MentionTarget::Role(role));
+            }
+            if let Some(user) = token
+                .strip_prefix("<@!")
+                .or_else(|| token.strip_prefix("<@"))
+                .and_then(id)
+            {
+                return Ok(MentionTarget::User(user));
+            }
+            if token == "@everyone" {
+                return Err("@everyone is not allowed, use @here or a role".to_string());
+            }
+            Err(format!("unsupported mention '{}' (use @here, <@user_id> or <@&role_id>)", token))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if targets.is_empty() {
+        return Err("mention is empty".to_string());
+    }
+    Ok(targets)
+}
+
+/// Shape of the endpoint configs this build writes
+///
+/// Stored as `version` in config_json; configs without it are version 1.
+/// Version 2 stores Discord webhooks as a `webhook_urls` list.
+pub const ENDPOINT_CONFIG_VERSION: u64 = 2;
+
+/// Key holding the config version in config_json
+pub const CONFIG_VERSION_KEY: &str = "version";
+
+/// Bring a config object up to `ENDPOINT_CONFIG_VERSION`, one version at a time
+///
+/// Values that aren't objects are returned unchanged for the schema check
+/// to reject. A version newer than this build is an error rather than a
+/// guess at a shape it doesn't know.
+pub fn upgrade_config_value(kind: &EndpointKind, mut value: serde_json::Value) -> Result<serde_json::Value, String> {
+    let Some(obj) = value.as_object_mut() else {
+        return Ok(value);
+    };
+    let version = match obj.get(CONFIG_VERSION_KEY) {
+        None => 1,
+        Some(v) => v.as_u64().filter(|v| *v >= 1).ok_or_else(|| format!("invalid config version {}", v))?,
+    };
+    if version > ENDPOINT_CONFIG_VERSION {
+        return Err(format!(
+            "config version {} is newer than this build supports ({})",
+            version, ENDPOINT_CONFIG_VERSION
+        ));
+    }
+    if version < 2 {
+        upgrade_v1_to_v2(kind, obj);
+    }
+    obj.insert(CONFIG_VERSION_KEY.to_string(), serde_json::json!(ENDPOINT_CONFIG_VERSION));
+    Ok(value)
+}
+
+/// Version 2: a Discord `webhook_url` becomes a one-item `webhook_urls` list
+///
+/// Configs that already have both keys are left for validation to reject.
+fn upgrade_v1_to_v2(kind: &EndpointKind, obj: &mut serde_json::Map<String, serde_json::Value>) {
+    if *kind == EndpointKind::Discord && !obj.contains_key("webhook_urls") {
+        if let Some(url) = obj.get("webhook_url").filter(|url| url.is_string()).cloned() {
+            obj.remove("webhook_url");
+            obj.insert("webhook_urls".to_string(), serde_json::json!([url]));
+        }
+    }
+}
+
+/// A stored config_json upgraded to the current shape
+pub fn upgrade_endpoint_config(kind: &EndpointKind, config_json: &str) -> Result<String, String> {
+    let value: serde_json::Value = serde_json::from_str(config_json).map_err(|e| format!("invalid JSON: {}", e))?;
+    Ok(upgrade_config_value(kind, value)?.to_string())
+}
+
+/// Check that an endpoint's config_json matches the schema for its kind
+///
+/// Catches rows that would only fail at send time, e.g. a Discord endpoint
+/// without a `webhook_url` or with blank Pushover credentials. Configs are
+/// checked as written, so errors name the keys the user wrote; every older
+/// version still parses, and only an unsupported `version` is rejected here.
+pub fn validate_endpoint_config(kind: &EndpointKind, config_json: &str) -> Result<(), String> {
+    upgrade_endpoint_config(kind, config_json)?;
+    // The kind's schema, then the checks its capabilities call for
+    let (mention, footer, extra_headers, webhooks) = match kind {
+        EndpointKind::Discord => {
+            let cfg: DiscordConfig =
+                serde_json::from_str(config_json).map_err(|e| format!("invalid Discord config: {}", e))?;
+            (cfg.mention, cfg.footer, cfg.extra_headers, Some(cfg.webhooks))
+        }
+        EndpointKind::Pushover => {
+            let cfg: PushoverConfig =
+                serde_json::from_str(config_json).map_err(|e| format!("invalid Pushover config: {}", e))?;
+            (None, cfg.footer, cfg.extra_headers, None)
+        }
+        EndpointKind::Mattermost => {
+            let cfg: MattermostConfig =
+                serde_json::from_str(config_json).map_err(|e| format!("invalid Mattermost config: {}", e))?;
+            if cfg.webhook_url.trim().is_empty() {
+                return Err("webhook_url is empty".to_string());
+            }
+            check_mattermost_webhook_url(&cfg.webhook_url)?;
+            (None, cfg.footer, cfg.extra_headers, None)
+        }
+        EndpointKind::Teams => {
+            let cfg: TeamsConfig =
+                serde_json::from_str(config_json).map_err(|e| format!("invalid Teams config: {}", e))?;
+            if cfg.webhook_url.trim().is_empty() {
+                return Err("webhook_url is empty".to_string());
+            }
+            check_teams_webhook_url(&cfg.webhook_url)?;
+            (None, cfg.footer, cfg.extra_headers, None)
+        }
+        EndpointKind::File => {
+            let cfg: FileConfig =
+                serde_json::from_str(config_json).map_err(|e| format!("invalid file config: {}", e))?;
+            check_file_path(&cfg.path)?;
+            (None, None, HashMap::new(), None)
+        }
+    };
+    check_extra_headers(&extra_headers)?;
+    let value: serde_json::Value = serde_json::from_str(config_json).map_err(|e| e.to_string())?;
+    let capabilities = kind.capabilities();
+
+    for field in capabilities.required_fields {
+        // The webhook field may be given as a list instead; checked below
+        if Some(*field) == capabilities.webhook_url_field {
+            continue;
+        }
+        if value.get(field).and_then(|v| v.as_str()).is_none_or(|s| s.trim().is_empty()) {
+            return Err(format!("{} is empty", field));
+        }
+    }
+    if let Some(webhooks) = &webhooks {
+        for url in webhooks.urls() {
+            if url.trim().is_empty() {
+                return Err(format!("{} is empty", webhooks.key()));
+            }
+            // `file:` / `env:` references are resolved (and checked) at send time
+            if !url.starts_with("https://") && !is_secret_ref(url) {
+                return Err(format!("{} must start with https://", webhooks.key()));
+            }
+        }
+    }
+    if let (true, Some(mention)) = (capabilities.mentions, &mention) {
+        parse_mention(mention)?;
+    }
+    endpoint_active_hours(config_json)?;
+    check_footer(footer.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_capabilities() {
+        let discord = EndpointKind::Discord.capabilities();
+        assert_eq!(discord.display_name, "Discord");
+        assert!(discord.embeds && discord.mentions && !discord.html);
+        assert_eq!(discord.max_message_len, 2000);
+        assert_eq!(discord.required_fields, &["webhook_url"]);
+        assert_eq!(discord.webhook_url_field, Some("webhook_url"));
+
+        let pushover = EndpointKind::Pushover.capabilities();
+        assert_eq!(pushover.display_name, "Pushover");
+        assert!(!pushover.embeds && !pushover.mentions && !pushover.html);
+        assert_eq!(pushover.max_message_len, 1024);
+        assert_eq!(pushover.required_fields, &["token", "user"]);
+        assert_eq!(pushover.webhook_url_field, None);
+
+        let mattermost = EndpointKind::Mattermost.capabilities();
+        assert_eq!(mattermost.display_name, "Mattermost");
+        assert_eq!(mattermost.required_fields, &["webhook_url"]);
+        assert_eq!(mattermost.webhook_url_field, Some("webhook_url"));
+
+        let teams = EndpointKind::Teams.capabilities();
+        assert_eq!(teams.display_name, "Microsoft Teams");
+        assert_eq!(teams.required_fields, &["webhook_url"]);
+        assert_eq!(teams.webhook_url_field, Some("webhook_url"));
+    }
+
+    #[test]
+    fn test_valid_configs() {
+        assert!(validate_endpoint_config(
+            &EndpointKind::Discord,
+            r#"{"webhook_url":"https://discord.com/api/webhooks/1/abc","username":"bot"}"#
+        )
+        .is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::Pushover, r#"{"token":"t","user":"u"}"#).is_ok());
+    }
+
+    #[test]
+    fn test_mention_allow_list() {
+        assert_eq!(
+            parse_mention("@here <@123> <@!456> <@&789>").unwrap(),
+            vec![
+                MentionTarget::Here,
+                MentionTarget::User(123),
+                MentionTarget::User(456),
+                MentionTarget::Role(789),
+            ]
+        );
+        assert!(parse_mention("@everyone").unwrap_err().contains("not allowed"));
+        assert!(parse_mention("@here @everyone").is_err());
+        assert!(parse_mention("<@abc>").is_err());
+        assert!(parse_mention("<!here>").is_err());
+        assert!(parse_mention("  ").is_err());
+    }
+
+    #[test]
+    fn test_discord_config_rejects_disallowed_mention() {
+        let err = validate_endpoint_config(
+            &EndpointKind::Discord,
+            r#"{"webhook_url":"https://discord.com/api/webhooks/1/abc","mention":"@everyone"}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("@everyone"));
+    }
+
+    #[test]
+    fn test_nsfw_marker_defaults_on() {
+        let cfg: DiscordConfig = serde_json::from_str(r#"{"webhook_url":"https://x"}"#).unwrap();
+        assert!(cfg.include_nsfw_marker);
+        let cfg: PushoverConfig =
+            serde_json::from_str(r#"{"token":"t","user":"u","include_nsfw_marker":false}"#).unwrap();
+        assert!(!cfg.include_nsfw_marker);
+    }
+
+    #[test]
+    fn test_discord_invalid_configs() {
+        let err = validate_endpoint_config(&EndpointKind::Discord, r#"{"username":"bot"}"#).unwrap_err();
+        assert!(err.contains("webhook_url"));
+        assert!(validate_endpoint_config(&EndpointKind::Discord, r#"{"webhook_url":""}"#).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Discord, r#"{"webhook_url":"http://x"}"#).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Discord, r#"{"webhook_url":"file:/run/secrets/hook"}"#).is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::Discord, "not json").is_err());
+    }
+
+    #[test]
+    fn test_discord_webhook_urls() {
+        // The original single-URL format still loads
+        let cfg: DiscordConfig = serde_json::from_str(r#"{"webhook_url":"https://a"}"#).unwrap();
+        assert_eq!(cfg.webhooks, DiscordWebhooks::One("https://a".to_string()));
+        assert_eq!(cfg.webhooks.urls(), ["https://a"]);
+
+        let cfg: DiscordConfig =
+            serde_json::from_str(r#"{"webhook_urls":["https://a","https://b"],"username":"bot"}"#).unwrap();
+        assert_eq!(cfg.webhooks.urls(), ["https://a", "https://b"]);
+        assert_eq!(cfg.username.as_deref(), Some("bot"));
+
+        let both = r#"{"webhook_url":"https://a","webhook_urls":["https://b"]}"#;
+        assert!(validate_endpoint_config(&EndpointKind::Discord, both).unwrap_err().contains("not both"));
+        assert!(validate_endpoint_config(&EndpointKind::Discord, r#"{"webhook_urls":[]}"#).is_err());
+        let insecure = r#"{"webhook_urls":["https://discord.com/api/webhooks/1/abc","http://x"]}"#;
+        assert_eq!(
+            validate_endpoint_config(&EndpointKind::Discord, insecure).unwrap_err(),
+            "webhook_urls must start with https://"
+        );
+        let with_secret = r#"{"webhook_urls":["https://discord.com/api/webhooks/1/abc","env:SECOND_HOOK"]}"#;
+        assert!(validate_endpoint_config(&EndpointKind::Discord, with_secret).is_ok());
+    }
+
+    #[test]
+    fn test_pushover_invalid_configs() {
+        let err = validate_endpoint_config(&EndpointKind::Pushover, r#"{"token":"t"}"#).unwrap_err();
+        assert!(err.contains("user"));
+        assert!(validate_endpoint_config(&EndpointKind::Pushover, r#"{"token":" ","user":"u"}"#).is_err());
+        // A Discord config stored under the Pushover kind
+        assert!(validate_endpoint_config(&EndpointKind::Pushover, r#"{"webhook_url":"https://x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_mattermost_configs() {
+        let config = |url: &str| format!(r#"{{"webhook_url":"{}","channel":"alerts"}}"#, url);
+        assert!(validate_endpoint_config(&EndpointKind::Mattermost, &config("https://chat.example.com/hooks/abc123")).is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::Mattermost, &config("env:MATTERMOST_HOOK")).is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::Mattermost, &config("http://chat.example.com/hooks/abc123")).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Mattermost, &config("https://chat.example.com/api/v4/posts")).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Mattermost, &config("https://chat.example.com/hooks/")).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Mattermost, r#"{"channel":"alerts"}"#).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Mattermost, r#"{"webhook_url":" "}"#).is_err());
+    }
+
+    #[test]
+    fn test_teams_configs() {
+        let workflow = "https://prod-12.westus.logic.azure.com:443/workflows/abc/triggers/manual/paths/invoke?sig=xyz";
+        let cfg: TeamsConfig = serde_json::from_str(&format!(r#"{{"webhook_url":"{}"}}"#, workflow)).unwrap();
+        assert_eq!(cfg.card, TeamsCard::Adaptive);
+        let cfg: TeamsConfig =
+            serde_json::from_str(r#"{"webhook_url":"https://x.webhook.office.com/webhookb2/a","card":"message_card"}"#).unwrap();
+        assert_eq!(cfg.card, TeamsCard::MessageCard);
+
+        assert!(validate_endpoint_config(&EndpointKind::Teams, &format!(r#"{{"webhook_url":"{}"}}"#, workflow)).is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::Teams, r#"{"webhook_url":"env:TEAMS_HOOK"}"#).is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::Teams, r#"{"webhook_url":"http://x.webhook.office.com/a"}"#).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Teams, r#"{"webhook_url":"https://x.webhook.office.com/"}"#).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Teams, r#"{"webhook_url":""}"#).is_err());
+        let bad_card = r#"{"webhook_url":"https://x.webhook.office.com/a","card":"hero"}"#;
+        assert!(validate_endpoint_config(&EndpointKind::Teams, bad_card).unwrap_err().contains("invalid Teams config"));
+    }
+
+    #[test]
+    fn test_file_configs() {
+        let cfg: FileConfig = serde_json::from_str(r#"{"path":"/var/log/posts.jsonl"}"#).unwrap();
+        assert_eq!(cfg.format, FileFormat::Json);
+        let cfg: FileConfig = serde_json::from_str(r#"{"path":"/var/log/posts.log","format":"text"}"#).unwrap();
+        assert_eq!(cfg.format, FileFormat::Text);
+
+        assert!(validate_endpoint_config(&EndpointKind::File, r#"{"path":"/run/notify.sock"}"#).is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::File, r#"{"path":"env:NOTIFY_FILE"}"#).is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::File, r#"{"path":"posts.jsonl"}"#).unwrap_err().contains("absolute"));
+        assert!(validate_endpoint_config(&EndpointKind::File, r#"{"path":" "}"#).is_err());
+        let bad_format = r#"{"path":"/var/log/posts.csv","format":"csv"}"#;
+        assert!(validate_endpoint_config(&EndpointKind::File, bad_format).unwrap_err().contains("invalid file config"));
+    }
+
+    #[test]
+    fn test_upgrade_v1_discord_config() {
+        let v1 = serde_json::json!({"webhook_url": "https://discord.com/api/webhooks/1/abc", "username": "bot"});
+        let upgraded = upgrade_config_value(&EndpointKind::Discord, v1).unwrap();
+        assert_eq!(
+            upgraded,
+            serde_json::json!({
+                "webhook_urls": ["https://discord.com/api/webhooks/1/abc"],
+                "username": "bot",
+                "version": ENDPOINT_CONFIG_VERSION
+            })
+        );
+        let cfg: DiscordConfig = serde_json::from_value(upgraded.clone()).unwrap();
+        assert_eq!(cfg.webhooks.urls(), ["https://discord.com/api/webhooks/1/abc"]);
+
+        // Upgrading is idempotent, and other kinds keep their webhook_url
+        assert_eq!(upgrade_config_value(&EndpointKind::Discord, upgraded.clone()).unwrap(), upgraded);
+        let mattermost = upgrade_endpoint_config(&EndpointKind::Mattermost, r#"{"webhook_url":"https://chat/hooks/a"}"#).unwrap();
+        assert_eq!(mattermost, r#"{"version":2,"webhook_url":"https://chat/hooks/a"}"#);
+        // Both keys are left for validation to reject
+        let both = upgrade_endpoint_config(&EndpointKind::Discord, r#"{"webhook_url":"https://a","webhook_urls":["https://b"]}"#).unwrap();
+        assert!(both.contains("webhook_url\""));
+    }
+
+    #[test]
+    fn test_unsupported_config_versions_rejected() {
+        let future = r#"{"token":"t","user":"u","version":3}"#;
+        assert!(upgrade_endpoint_config(&EndpointKind::Pushover, future).unwrap_err().contains("newer than this build"));
+        assert!(validate_endpoint_config(&EndpointKind::Pushover, future).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Pushover, r#"{"token":"t","user":"u","version":"two"}"#).is_err());
+        assert!(validate_endpoint_config(&EndpointKind::Pushover, r#"{"token":"t","user":"u","version":2}"#).is_ok());
+    }
+
+    #[test]
+    fn test_extra_headers_validated() {
+        let config = |headers: &str| {
+            format!(r#"{{"webhook_url":"https://discord.com/api/webhooks/1/abc","extra_headers":{}}}"#, headers)
+        };
+        assert!(validate_endpoint_config(&EndpointKind::Discord, &config(r#"{"X-Proxy-Auth":"Bearer abc"}"#)).is_ok());
+        assert!(validate_endpoint_config(&EndpointKind::Discord, &config(r#"{"Authorization":"env:PROXY_TOKEN"}"#)).is_ok());
+
+        let err = |headers: &str| validate_endpoint_config(&EndpointKind::Discord, &config(headers)).unwrap_err();
+        assert_eq!(err(r#"{"Bad Header":"x"}"#), "invalid header name 'Bad Header'");
+        assert_eq!(err(r#"{"X-Token":"line\nbreak"}"#), "invalid value for header 'X-Token'");
+        assert_eq!(err(r#"{"Content-Type":"text/plain"}"#), "header 'Content-Type' can't be overridden");
+
+        let pushover = r#"{"token":"t","user":"u","extra_headers":{"Host":"evil"}}"#;
+        assert!(validate_endpoint_config(&EndpointKind::Pushover, pushover).is_err());
+    }
 }
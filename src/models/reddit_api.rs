@@ -3,19 +3,21 @@ use serde::Deserialize;
 use serde_with::{serde_as, TimestampSecondsWithFrac};
 
 /// Reddit API models
+///
+/// Listings hold posts by default; `RedditListing<RedditComment>` reads a comment listing.
 #[derive(Debug, Deserialize)]
-pub struct RedditListing {
-    pub data: RedditListingData,
+pub struct RedditListing<T = RedditPost> {
+    pub data: RedditListingData<T>,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct RedditListingData {
-    pub children: Vec<RedditChild>,
+pub struct RedditListingData<T = RedditPost> {
+    pub children: Vec<RedditChild<T>>,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct RedditChild {
-    pub data: RedditPost,
+pub struct RedditChild<T = RedditPost> {
+    pub data: T,
 }
 
 #[serde_as]
@@ -26,6 +28,38 @@ pub struct RedditPost {
     pub subreddit: String,
     pub permalink: Option<String>,
     pub url: Option<String>,
+    /// Pinned by the moderators (rules, megathreads)
+    #[serde(default)]
+    pub stickied: bool,
+    /// Marked NSFW by the author or moderators
+    #[serde(default)]
+    pub over_18: bool,
+    /// Upvotes minus downvotes at fetch time
+    #[serde(default)]
+    pub score: i64,
+    /// Comment count at fetch time
+    #[serde(default)]
+    pub num_comments: i64,
+    #[serde_as(as = "TimestampSecondsWithFrac<f64>")]
+    pub created_utc: DateTime<Utc>,
+}
+
+/// A comment from a subreddit's `/comments.json` listing
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct RedditComment {
+    pub id: String,
+    pub subreddit: String,
+    /// Comment text (markdown)
+    pub body: String,
+    /// Path of the comment, relative to the Reddit base URL
+    pub permalink: String,
+    /// Title of the post the comment was left on
+    #[serde(default)]
+    pub link_title: Option<String>,
+    /// The post the comment was left on is NSFW
+    #[serde(default)]
+    pub over_18: bool,
     #[serde_as(as = "TimestampSecondsWithFrac<f64>")]
     pub created_utc: DateTime<Utc>,
 }
@@ -0,0 +1,288 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashMap;
+
+use crate::models::{
+    database::{EndpointKind, EndpointRow},
+    notifiers::{upgrade_endpoint_config, DiscordConfig, FileConfig, MattermostConfig, PushoverConfig, TeamsConfig},
+};
+use crate::secrets::resolve_config_secrets;
+use super::{
+    discord, file, mattermost,
+    message::{SAMPLE_SUBREDDIT, SAMPLE_TITLE, SAMPLE_URL},
+    pushover, teams, Message,
+};
+
+/// Placeholder shown instead of secrets in redacted commands
+const REDACTED: &str = "<redacted>";
+
+/// Build a `curl` command that sends the sample notification to an endpoint
+///
+/// Useful for reproducing delivery problems outside the app. Secrets (the
+/// Discord and Mattermost webhook tokens, the whole path of a Teams webhook,
+/// Pushover token and user key) are replaced with a placeholder unless
+/// `include_secrets` is set. A Discord endpoint with several webhooks gets one
+/// command per webhook, separated by blank lines. File endpoints aren't sent
+/// over HTTP, so they get the `printf` command that writes the same line.
+pub fn to_curl(endpoint: &EndpointRow, include_secrets: bool) -> Result<String> {
+    let message = Message::sample();
+    let config_json = upgrade_endpoint_config(&endpoint.kind, &endpoint.config_json).map_err(anyhow::Error::msg)?;
+    // Only look up `file:` / `env:` secrets when they are going to be shown
+    let config_json = if include_secrets {
+        resolve_config_secrets(&config_json)?
+    } else {
+        config_json
+    };
+
+    match endpoint.kind {
+        EndpointKind::Discord => {
+            let mut cfg: DiscordConfig = serde_json::from_str(&config_json)?;
+            if !include_secrets {
+                for url in cfg.webhooks.urls_mut() {
+                    *url = redact_webhook_token(url);
+                }
+            }
+            let payload = discord::build_payload(&cfg, &message);
+            let commands: Vec<String> = cfg
+                .webhooks
+                .urls()
+                .iter()
+                .map(|url| {
+                    format!(
+                        "curl -X POST {} \\\n  -H 'Content-Type: application/json' \\\n{}  -d {}",
+                        shell_quote(url),
+                        header_args(&cfg.extra_headers, include_secrets),
+                        shell_quote(&payload.to_string())
+                    )
+                })
+                .collect();
+            Ok(commands.join("\n\n"))
+        }
+        EndpointKind::Pushover => {
+            let mut cfg: PushoverConfig = serde_json::from_str(&config_json)?;
+            if !include_secrets {
+                cfg.token = REDACTED.to_string();
+                cfg.user = REDACTED.to_string();
+            }
+            let fields: Vec<String> = pushover::build_form(&cfg, message)
+                .into_iter()
+                .map(|(key, value)| format!("  --form-string {}", shell_quote(&format!("{}={}", key, value))))
+                .collect();
+            Ok(format!(
+                "curl -X POST {} \\\n{}{}",
+                shell_quote(pushover::API_URL),
+                header_args(&cfg.extra_headers, include_secrets),
+                fields.join(" \\\n")
+            ))
+        }
+        EndpointKind::Mattermost => {
+            let mut cfg: MattermostConfig = serde_json::from_str(&config_json)?;
+            if !include_secrets {
+                cfg.webhook_url = redact_webhook_token(&cfg.webhook_url);
+            }
+            let payload = mattermost::build_payload(&cfg, &message);
+            Ok(format!(
+                "curl -X POST {} \\\n  -H 'Content-Type: application/json' \\\n{}  -d {}",
+                shell_quote(&cfg.webhook_url),
+                header_args(&cfg.extra_headers, include_secrets),
+                shell_quote(&payload.to_string())
+            ))
+        }
+        EndpointKind::Teams => {
+            let mut cfg: TeamsConfig = serde_json::from_str(&config_json)?;
+            if !include_secrets {
+                cfg.webhook_url = redact_url_path(&cfg.webhook_url);
+            }
+            let payload = teams::build_payload(&cfg, &message);
+            Ok(format!(
+                "curl -X POST {} \\\n  -H 'Content-Type: application/json' \\\n{}  -d {}",
+                shell_quote(&cfg.webhook_url),
+                header_args(&cfg.extra_headers, include_secrets),
+                shell_quote(&payload.to_string())
+            ))
+        }
+        // Not sent over HTTP: the equivalent shell command writes the same line
+        EndpointKind::File => {
+            let cfg: FileConfig = serde_json::from_str(&config_json)?;
+            let path = cfg.path.trim();
+            let line = file::post_line(cfg.format, SAMPLE_SUBREDDIT, SAMPLE_TITLE, SAMPLE_URL, false, Utc::now());
+            let line = shell_quote(line.trim_end());
+            if file::is_socket(std::path::Path::new(path)) {
+                Ok(format!("printf '%s\\n' {} | nc -U {}", line, shell_quote(path)))
+            } else {
+                Ok(format!("printf '%s\\n' {} >> {}", line, shell_quote(path)))
+            }
+        }
+    }
+}
+
+/// `-H` lines for an endpoint's extra headers, sorted by name
+///
+/// Values are redacted unless `include_secrets` is set, as they usually carry credentials.
+fn header_args(headers: &HashMap<String, String>, include_secrets: bool) -> String {
+    let mut names: Vec<&String> = headers.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let value = if include_secrets { headers[name].as_str() } else { REDACTED };
+            format!("  -H {} \\\n", shell_quote(&format!("{}: {}", name, value)))
+        })
+        .collect()
+}
+
+/// Replace the token (last path segment) of a Discord or Mattermost webhook URL
+fn redact_webhook_token(url: &str) -> String {
+    match url.trim_end_matches('/').rsplit_once('/') {
+        Some((base, _token)) => format!("{}/{}", base, REDACTED),
+        None => REDACTED.to_string(),
+    }
+}
+
+/// Keep only the scheme and host of a URL
+///
+/// Teams workflow URLs carry their signature in the query string and
+/// connector URLs spread IDs across the path, so all of it is hidden.
+fn redact_url_path(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => format!("{}/{}", parsed.origin().ascii_serialization(), REDACTED),
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+/// Quote a string for POSIX shells using single quotes
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(kind: EndpointKind, config_json: &str) -> EndpointRow {
+        EndpointRow {
+            id: 1,
+            kind,
+            config_json: config_json.to_string(),
+            active: true,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_discord_curl_redacted() {
+        let ep = endpoint(
+            EndpointKind::Discord,
+            r#"{"webhook_url":"https://discord.com/api/webhooks/123/secret-token"}"#,
+        );
+        let curl = to_curl(&ep, false).unwrap();
+
+        assert!(curl.starts_with("curl -X POST 'https://discord.com/api/webhooks/123/<redacted>'"));
+        assert!(!curl.contains("secret-token"));
+        assert!(curl.contains("-H 'Content-Type: application/json'"));
+        assert!(curl.contains(r#""title":"New Reddit Post Alert (rust)""#));
+        // The apostrophe in the sample title is escaped for the shell
+        assert!(curl.contains(r"what'\''s next"));
+    }
+
+    #[test]
+    fn test_discord_curl_with_secrets() {
+        let ep = endpoint(
+            EndpointKind::Discord,
+            r#"{"webhook_url":"https://discord.com/api/webhooks/123/secret-token","username":"bot"}"#,
+        );
+        let curl = to_curl(&ep, true).unwrap();
+
+        assert!(curl.contains("'https://discord.com/api/webhooks/123/secret-token'"));
+        assert!(curl.contains(r#""username":"bot""#));
+    }
+
+    #[test]
+    fn test_discord_curl_per_webhook() {
+        let ep = endpoint(
+            EndpointKind::Discord,
+            r#"{"webhook_urls":["https://discord.com/api/webhooks/1/first","https://discord.com/api/webhooks/2/second"]}"#,
+        );
+        let curl = to_curl(&ep, false).unwrap();
+        let commands: Vec<&str> = curl.split("\n\n").collect();
+        assert_eq!(commands.len(), 2);
+        assert!(commands[0].starts_with("curl -X POST 'https://discord.com/api/webhooks/1/<redacted>'"));
+        assert!(commands[1].starts_with("curl -X POST 'https://discord.com/api/webhooks/2/<redacted>'"));
+        assert!(!curl.contains("second"));
+    }
+
+    #[test]
+    fn test_pushover_curl() {
+        let ep = endpoint(
+            EndpointKind::Pushover,
+            r#"{"token":"app-token","user":"user-key","device":"phone"}"#,
+        );
+
+        let redacted = to_curl(&ep, false).unwrap();
+        assert!(redacted.starts_with("curl -X POST 'https://api.pushover.net/1/messages.json'"));
+        assert!(redacted.contains("--form-string 'token=<redacted>'"));
+        assert!(redacted.contains("--form-string 'user=<redacted>'"));
+        assert!(redacted.contains("--form-string 'device=phone'"));
+        assert!(!redacted.contains("app-token"));
+
+        let full = to_curl(&ep, true).unwrap();
+        assert!(full.contains("--form-string 'token=app-token'"));
+        assert!(full.contains("--form-string 'user=user-key'"));
+    }
+
+    #[test]
+    fn test_mattermost_curl() {
+        let ep = endpoint(
+            EndpointKind::Mattermost,
+            r#"{"webhook_url":"https://chat.example.com/hooks/secret-id","channel":"alerts"}"#,
+        );
+        let redacted = to_curl(&ep, false).unwrap();
+        assert!(redacted.starts_with("curl -X POST 'https://chat.example.com/hooks/<redacted>'"));
+        assert!(!redacted.contains("secret-id"));
+        assert!(redacted.contains(r#""channel":"alerts""#));
+        assert!(to_curl(&ep, true).unwrap().contains("'https://chat.example.com/hooks/secret-id'"));
+    }
+
+    #[test]
+    fn test_teams_curl() {
+        let ep = endpoint(
+            EndpointKind::Teams,
+            r#"{"webhook_url":"https://prod-1.westus.logic.azure.com:443/workflows/abc/triggers/manual/paths/invoke?sig=secret"}"#,
+        );
+        let redacted = to_curl(&ep, false).unwrap();
+        assert!(redacted.starts_with("curl -X POST 'https://prod-1.westus.logic.azure.com/<redacted>'"));
+        assert!(!redacted.contains("secret") && !redacted.contains("workflows"));
+        assert!(redacted.contains("application/vnd.microsoft.card.adaptive"));
+        assert!(to_curl(&ep, true).unwrap().contains("invoke?sig=secret'"));
+    }
+
+    #[test]
+    fn test_file_command() {
+        let ep = endpoint(EndpointKind::File, r#"{"path":"/var/log/reddit posts.log","format":"text"}"#);
+        let command = to_curl(&ep, false).unwrap();
+        assert!(command.starts_with("printf '%s\\n' '"), "{}", command);
+        assert!(command.contains(" r/rust: Announcing Rust 1.90.0 & what'\\''s next "));
+        assert!(command.ends_with(" >> '/var/log/reddit posts.log'"));
+    }
+
+    #[test]
+    fn test_extra_headers_in_curl() {
+        let ep = endpoint(
+            EndpointKind::Discord,
+            r#"{"webhook_url":"https://discord.com/api/webhooks/123/t","extra_headers":{"X-Tenant":"home","Authorization":"Bearer abc"}}"#,
+        );
+        let redacted = to_curl(&ep, false).unwrap();
+        assert!(redacted.contains("  -H 'Authorization: <redacted>' \\\n  -H 'X-Tenant: <redacted>' \\\n  -d "));
+        assert!(!redacted.contains("Bearer abc"));
+        assert!(to_curl(&ep, true).unwrap().contains("-H 'Authorization: Bearer abc'"));
+
+        let ep = endpoint(EndpointKind::Pushover, r#"{"token":"t","user":"u","extra_headers":{"X-Tenant":"home"}}"#);
+        assert!(to_curl(&ep, true).unwrap().contains("  -H 'X-Tenant: home' \\\n  --form-string 'token=t'"));
+    }
+
+    #[test]
+    fn test_invalid_config_is_an_error() {
+        let ep = endpoint(EndpointKind::Pushover, r#"{"token":"t"}"#);
+        assert!(to_curl(&ep, false).is_err());
+    }
+}
@@ -1,38 +1,323 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use reqwest::Client;
-use html_escape::decode_html_entities;
+use reqwest::{Client, Request};
 
-use crate::models::notifiers::DiscordConfig;
-use super::Notifier;
+use crate::models::database::EndpointKind;
+use crate::models::notifiers::{parse_mention, DiscordConfig, MentionTarget};
+use super::{extra_header_map, message::{append_footer, truncate_to_limit}, HttpStatusError, Message, Notifier};
+
+/// Username shown on webhook messages when none is configured
+pub const DEFAULT_USERNAME: &str = "Reddit Notifier";
 
 pub struct DiscordNotifier {
     pub client: Client,
     pub cfg: DiscordConfig,
 }
 
+/// Build the webhook JSON body for a message
+pub fn build_payload(cfg: &DiscordConfig, message: &Message) -> serde_json::Value {
+    serde_json::json!({
+        "username": cfg.username.as_deref().unwrap_or(DEFAULT_USERNAME),
+        "embeds": [{
+            "title": message.title,
+            "description": append_footer(&message.body, cfg.footer.as_deref(), EndpointKind::Discord.max_message_len(), "discord"),
+            "url": message.url,
+            "type": "rich"
+        }]
+    })
+}
+
+/// Build the webhook JSON body for a priority post, pinging the configured mention
+///
+/// Embeds never ping, so the mention goes in `content`. `allowed_mentions` is
+/// limited to exactly the parsed targets. An invalid mention (e.g. a config
+/// edited outside the app) is dropped rather than sent.
+pub fn build_priority_payload(cfg: &DiscordConfig, message: &Message) -> serde_json::Value {
+    let mut payload = build_payload(cfg, message);
+    let Some(mention) = &cfg.mention else {
+        return payload;
+    };
+
+    match parse_mention(mention) {
+        Ok(targets) => {
+            let mut parse = Vec::new();
+            let mut users = Vec::new();
+            let mut roles = Vec::new();
+            for target in targets {
+                match target {
+                    MentionTarget::Here => parse.push("everyone"),
+                    MentionTarget::User(id) => users.push(id.to_string()),
+                    MentionTarget::Role(id) => roles.push(id.to_string()),
+                }
+            }
+            parse.dedup();
+            payload["content"] = serde_json::json!(mention.split_whitespace().collect::<Vec<_>>().join(" "));
+            payload["allowed_mentions"] = serde_json::json!({
+                "parse": parse,
+                "users": users,
+                "roles": roles,
+            });
+        }
+        Err(e) => tracing::warn!("Ignoring Discord mention: {}", e),
+    }
+    payload
+}
+
+/// Build the webhook JSON body for a free-form message
+pub fn build_raw_payload(cfg: &DiscordConfig, message: &str) -> serde_json::Value {
+    serde_json::json!({
+        "username": cfg.username.as_deref().unwrap_or(DEFAULT_USERNAME),
+        "content": truncate_to_limit(message, EndpointKind::Discord.max_message_len(), "discord"),
+    })
+}
+
+impl DiscordNotifier {
+    /// Build the request to one webhook, with the endpoint's extra headers
+    pub fn build_request(&self, webhook_url: &str, payload: &serde_json::Value) -> Result<Request> {
+        Ok(self
+            .client
+            .post(webhook_url)
+            .headers(extra_header_map(&self.cfg.extra_headers)?)
+            .json(payload)
+            .build()?)
+    }
+
+    async fn post_to(&self, webhook_url: &str, payload: &serde_json::Value) -> Result<()> {
+        let res = self.client.execute(self.build_request(webhook_url, payload)?).await?;
+        if !res.status().is_success() {
+            return Err(HttpStatusError::from_response("discord webhook", res).await.into());
+        }
+        Ok(())
+    }
+
+    /// Post `payload` to every configured webhook, even if some of them fail
+    async fn post(&self, payload: &serde_json::Value) -> Result<()> {
+        let urls = self.cfg.webhooks.urls();
+        let mut failures = Vec::new();
+        for (i, url) in urls.iter().enumerate() {
+            if let Err(e) = self.post_to(url, payload).await {
+                failures.push((i, e));
+            }
+        }
+        fan_out_result(urls.len(), failures)
+    }
+}
+
+/// Combine the errors from posting to `total` webhooks (by webhook index)
+///
+/// With a single webhook its error is returned unchanged. When every webhook
+/// failed the first error is kept underneath, so `send_with_retry` still
+/// retries transient failures. A partial failure is not retryable: a retry
+/// would post again to the channels that already got the message.
+fn fan_out_result(total: usize, failures: Vec<(usize, anyhow::Error)>) -> Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<String> = failures.iter().map(|(i, e)| format!("webhook {}: {}", i + 1, e)).collect();
+    let failed = failures.len();
+    if failed < total {
+        return Err(anyhow!("{} of {} discord webhooks failed ({})", failed, total, details.join("; ")));
+    }
+
+    let first = failures.into_iter().next().map(|(_, e)| e).expect("at least one failure");
+    if total == 1 {
+        return Err(first);
+    }
+    Err(first.context(format!("all {} discord webhooks failed ({})", total, details.join("; "))))
+}
+
 #[async_trait]
 impl Notifier for DiscordNotifier {
     fn kind(&self) -> &'static str {
         "discord"
     }
 
+    fn include_nsfw_marker(&self) -> bool {
+        self.cfg.include_nsfw_marker
+    }
+
     async fn send(&self, subreddit: &str, title: &str, url: &str) -> Result<()> {
-        let payload = serde_json::json!({
-            "username": self.cfg.username.as_deref().unwrap_or("Reddit Notifier"),
-            "embeds": [{
-                "title": format!("New Reddit Post Alert ({})", subreddit),
-                "description": decode_html_entities(title),
-                "url": url,
-                "type": "rich"
-            }]
+        let message = Message::new(subreddit, title, url);
+        self.post(&build_payload(&self.cfg, &message)).await
+    }
+
+    async fn send_priority(&self, subreddit: &str, title: &str, url: &str) -> Result<()> {
+        let message = Message::new(subreddit, title, url);
+        self.post(&build_priority_payload(&self.cfg, &message)).await
+    }
+
+    async fn send_raw(&self, message: &str) -> Result<()> {
+        self.post(&build_raw_payload(&self.cfg, message)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::notifiers::DiscordWebhooks;
+    use crate::notifiers::{classify_error, RetryDecision};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_raw_payload_is_plain_content() {
+        let cfg = DiscordConfig {
+            webhooks: DiscordWebhooks::One("https://discord.com/api/webhooks/1/abc".to_string()),
+            username: Some("Announcer".to_string()),
+            mention: None,
+            include_nsfw_marker: true,
+            footer: None,
+            extra_headers: HashMap::new(),
+        };
+        let payload = build_raw_payload(&cfg, "Maintenance tonight");
+        assert_eq!(
+            payload,
+            serde_json::json!({"username": "Announcer", "content": "Maintenance tonight"})
+        );
+    }
+
+    #[test]
+    fn test_raw_payload_default_username() {
+        let cfg = DiscordConfig {
+            webhooks: DiscordWebhooks::One("https://discord.com/api/webhooks/1/abc".to_string()),
+            username: None,
+            mention: None,
+            include_nsfw_marker: true,
+            footer: None,
+            extra_headers: HashMap::new(),
+        };
+        assert_eq!(build_raw_payload(&cfg, "hi")["username"], DEFAULT_USERNAME);
+    }
+
+    #[test]
+    fn test_over_limit_text_is_truncated() {
+        let cfg = config_with_mention(None);
+        let cap = EndpointKind::Discord.max_message_len();
+        let long = "x".repeat(cap + 500);
+
+        let raw = build_raw_payload(&cfg, &long);
+        assert_eq!(raw["content"].as_str().unwrap().chars().count(), cap);
+
+        let message = Message::new("rust", &long, "https://example.com");
+        let payload = build_payload(&cfg, &message);
+        assert_eq!(payload["embeds"][0]["description"].as_str().unwrap().chars().count(), cap);
+    }
+
+    #[test]
+    fn test_footer_kept_when_body_is_truncated() {
+        let cfg = DiscordConfig {
+            footer: Some("via reddit-notifier".to_string()),
+            ..config_with_mention(None)
+        };
+        let cap = EndpointKind::Discord.max_message_len();
+        let message = Message::new("rust", &"x".repeat(cap + 500), "https://example.com");
+        let description = build_payload(&cfg, &message)["embeds"][0]["description"].as_str().unwrap().to_string();
+        assert_eq!(description.chars().count(), cap);
+        assert!(description.ends_with("…\n\nvia reddit-notifier"));
+
+        // Free-form messages are sent as written
+        assert_eq!(build_raw_payload(&cfg, "hi")["content"], "hi");
+    }
+
+    fn config_with_mention(mention: Option<&str>) -> DiscordConfig {
+        DiscordConfig {
+            webhooks: DiscordWebhooks::One("https://discord.com/api/webhooks/1/abc".to_string()),
+            username: None,
+            mention: mention.map(str::to_string),
+            include_nsfw_marker: true,
+            footer: None,
+            extra_headers: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_priority_payload_pings_only_allowed_targets() {
+        let message = Message::new("rust", "title", "https://example.com");
+        let payload = build_priority_payload(&config_with_mention(Some("@here  <@123> <@&456>")), &message);
+        assert_eq!(payload["content"], "@here <@123> <@&456>");
+        assert_eq!(
+            payload["allowed_mentions"],
+            serde_json::json!({"parse": ["everyone"], "users": ["123"], "roles": ["456"]})
+        );
+        assert_eq!(payload["embeds"][0]["description"], "title");
+    }
+
+    #[test]
+    fn test_priority_payload_drops_disallowed_or_missing_mention() {
+        let message = Message::new("rust", "title", "https://example.com");
+        for mention in [None, Some("@everyone")] {
+            let cfg = config_with_mention(mention);
+            assert_eq!(build_priority_payload(&cfg, &message), build_payload(&cfg, &message));
+        }
+    }
+
+    #[test]
+    fn test_extra_headers_attached_to_request() {
+        let notifier = DiscordNotifier {
+            client: Client::new(),
+            cfg: DiscordConfig {
+                extra_headers: HashMap::from([("X-Proxy-Auth".to_string(), "Bearer abc".to_string())]),
+                ..config_with_mention(None)
+            },
+        };
+        let message = Message::new("rust", "title", "https://example.com");
+        let url = "https://discord.com/api/webhooks/1/abc";
+        let request = notifier.build_request(url, &build_payload(&notifier.cfg, &message)).unwrap();
+        assert_eq!(request.url().as_str(), "https://discord.com/api/webhooks/1/abc");
+        assert_eq!(request.headers()["x-proxy-auth"], "Bearer abc");
+        assert_eq!(request.headers()["content-type"], "application/json");
+
+        // Without extra headers only the JSON content type is set
+        let plain = DiscordNotifier { client: Client::new(), cfg: config_with_mention(None) };
+        assert_eq!(plain.build_request(url, &serde_json::json!({})).unwrap().headers().len(), 1);
+    }
+
+    /// A webhook server answering `/ok/...` with 204 and anything else with the status in the path
+    fn spawn_webhook_stub() -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let status = path.split('/').nth(1).and_then(|s| s.parse::<u16>().ok()).unwrap_or(204);
+                let response = format!("HTTP/1.1 {} Stub\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+                let _ = stream.write_all(response.as_bytes());
+            }
         });
-        let res = self.client.post(&self.cfg.webhook_url).json(&payload).send().await?;
-        let status = res.status();
-        if !status.is_success() {
-            let body = res.text().await.unwrap_or_default();
-            anyhow::bail!("discord webhook non-success: {} body: {}", status, body);
+        format!("http://{}", addr)
+    }
+
+    fn fan_out_notifier(base: &str, paths: &[&str]) -> DiscordNotifier {
+        DiscordNotifier {
+            client: Client::new(),
+            cfg: DiscordConfig {
+                webhooks: DiscordWebhooks::Many(paths.iter().map(|p| format!("{}{}", base, p)).collect()),
+                ..config_with_mention(None)
+            },
         }
-        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_to_every_webhook() {
+        let base = spawn_webhook_stub();
+        let notifier = fan_out_notifier(&base, &["/ok/1", "/ok/2"]);
+        notifier.send("rust", "title", "https://example.com").await.unwrap();
+
+        // One bad channel doesn't stop the others, and the error names it
+        let notifier = fan_out_notifier(&base, &["/ok/1", "/404/2", "/ok/3"]);
+        let err = notifier.send("rust", "title", "https://example.com").await.unwrap_err();
+        assert!(err.to_string().starts_with("1 of 3 discord webhooks failed (webhook 2: "), "{}", err);
+        // ...and isn't retried, which would post twice to channels 1 and 3
+        assert_eq!(classify_error(&err), RetryDecision::Fail);
+
+        // When every channel fails transiently the send can be retried
+        let notifier = fan_out_notifier(&base, &["/503/1", "/503/2"]);
+        let err = notifier.send_raw("hello").await.unwrap_err();
+        assert!(err.to_string().starts_with("all 2 discord webhooks failed"), "{}", err);
+        assert_eq!(classify_error(&err), RetryDecision::Retry(None));
     }
 }
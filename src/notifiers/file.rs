@@ -0,0 +1,278 @@
+//! Notifications written to a local file or Unix domain socket
+//!
+//! For custom integrations: every notification is one line, a JSON object or
+//! plain text, so the output can be tailed or read by other local tools. A
+//! path that is a Unix domain socket gets a new connection per notification;
+//! anything else is appended to (and created if missing).
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use html_escape::decode_html_entities;
+use std::collections::HashMap;
+use std::fs::{File, Metadata, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use crate::models::database::{EndpointKind, TIMESTAMP_FORMAT};
+use crate::models::notifiers::{FileConfig, FileFormat};
+use super::{message::truncate_to_limit, Notifier};
+
+/// Longest a socket write may block before the notification counts as failed
+#[cfg(unix)]
+const SOCKET_WRITE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+pub struct FileNotifier {
+    pub cfg: FileConfig,
+}
+
+/// A post notification as one line, ending in a newline
+pub fn post_line(format: FileFormat, subreddit: &str, title: &str, url: &str, priority: bool, sent_at: DateTime<Utc>) -> String {
+    let title = decode_html_entities(title);
+    let title = truncate_to_limit(&title, EndpointKind::File.max_message_len(), "file");
+    let sent_at = sent_at.format(TIMESTAMP_FORMAT).to_string();
+    match format {
+        FileFormat::Json => {
+            let line = serde_json::json!({
+                "type": "post",
+                "sent_at": sent_at,
+                "subreddit": subreddit,
+                "title": title,
+                "url": url,
+                "priority": priority,
+            });
+            format!("{}\n", line)
+        }
+        FileFormat::Text => format!("{} r/{}: {} {}\n", sent_at, subreddit, single_line(&title), url),
+    }
+}
+
+/// A free-form message as one line, ending in a newline
+pub fn raw_line(format: FileFormat, message: &str, sent_at: DateTime<Utc>) -> String {
+    let message = truncate_to_limit(message, EndpointKind::File.max_message_len(), "file");
+    let sent_at = sent_at.format(TIMESTAMP_FORMAT).to_string();
+    match format {
+        FileFormat::Json => {
+            let line = serde_json::json!({ "type": "message", "sent_at": sent_at, "text": message });
+            format!("{}\n", line)
+        }
+        FileFormat::Text => format!("{} {}\n", sent_at, single_line(&message)),
+    }
+}
+
+/// Line breaks replaced with spaces, so text output stays one line per notification
+fn single_line(text: &str) -> String {
+    text.split(['\r', '\n']).filter(|part| !part.is_empty()).collect::<Vec<_>>().join(" ")
+}
+
+/// An appended-to file kept open between notifications
+struct OpenFile {
+    file: File,
+    /// Device and inode it was opened at (None where the platform can't tell)
+    id: Option<(u64, u64)>,
+}
+
+/// Files kept open, by configured path
+fn open_files() -> &'static Mutex<HashMap<PathBuf, OpenFile>> {
+    static OPEN_FILES: OnceLock<Mutex<HashMap<PathBuf, OpenFile>>> = OnceLock::new();
+    OPEN_FILES.get_or_init(Default::default)
+}
+
+#[cfg(unix)]
+fn file_id(meta: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Append `line` to the file at `path`
+///
+/// The file stays open for the next notification, unless the path now names
+/// another file (moved away by logrotate, or deleted): then the path is
+/// opened again, so writes never go to a rotated file.
+fn append_line(path: &Path, line: &str) -> Result<()> {
+    let mut files = open_files().lock().unwrap_or_else(PoisonError::into_inner);
+    let on_disk = std::fs::metadata(path).ok().and_then(|meta| file_id(&meta));
+    let cached = files.remove(path).filter(|open| on_disk.is_some() && open.id == on_disk);
+    let mut open = match cached {
+        Some(open) => open,
+        None => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("failed to open {}", path.display()))?;
+            let id = file.metadata().ok().and_then(|meta| file_id(&meta));
+            OpenFile { file, id }
+        }
+    };
+    // A failed write drops the handle, so the next notification reopens the path
+    open.file
+        .write_all(line.as_bytes())
+        .with_context(|| format!("failed to write to {}", path.display()))?;
+    files.insert(path.to_path_buf(), open);
+    Ok(())
+}
+
+/// Send `line` over a new connection to the Unix domain socket at `path`
+#[cfg(unix)]
+fn write_socket(path: &Path, line: &str) -> Result<()> {
+    let mut stream = std::os::unix::net::UnixStream::connect(path)
+        .with_context(|| format!("failed to connect to socket {}", path.display()))?;
+    stream.set_write_timeout(Some(SOCKET_WRITE_TIMEOUT))?;
+    stream
+        .write_all(line.as_bytes())
+        .with_context(|| format!("failed to write to socket {}", path.display()))
+}
+
+/// Whether `path` is a Unix domain socket (always false on other platforms)
+pub fn is_socket(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        std::fs::metadata(path).is_ok_and(|meta| meta.file_type().is_socket())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Write `line` to the socket or file at `path`
+fn write_line(path: &Path, line: &str) -> Result<()> {
+    #[cfg(unix)]
+    if is_socket(path) {
+        return write_socket(path, line);
+    }
+    append_line(path, line)
+}
+
+impl FileNotifier {
+    /// Write a line off the async runtime; file and socket I/O can block
+    async fn write(&self, line: String) -> Result<()> {
+        let path = PathBuf::from(self.cfg.path.trim());
+        tokio::task::spawn_blocking(move || write_line(&path, &line)).await?
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    fn kind(&self) -> &'static str {
+        "file"
+    }
+
+    fn include_nsfw_marker(&self) -> bool {
+        self.cfg.include_nsfw_marker
+    }
+
+    async fn send(&self, subreddit: &str, title: &str, url: &str) -> Result<()> {
+        self.write(post_line(self.cfg.format, subreddit, title, url, false, Utc::now())).await
+    }
+
+    async fn send_priority(&self, subreddit: &str, title: &str, url: &str) -> Result<()> {
+        self.write(post_line(self.cfg.format, subreddit, title, url, true, Utc::now())).await
+    }
+
+    async fn send_raw(&self, message: &str) -> Result<()> {
+        self.write(raw_line(self.cfg.format, message, Utc::now())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notifier(path: &Path, format: FileFormat) -> FileNotifier {
+        FileNotifier {
+            cfg: FileConfig { path: path.display().to_string(), format, include_nsfw_marker: true },
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("reddit-notifier-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_line_formats() {
+        let sent_at = DateTime::parse_from_rfc3339("2026-10-16T12:00:00Z").unwrap().with_timezone(&Utc);
+        let line = post_line(FileFormat::Json, "rust", "Tom &amp; Jerry", "https://example.com/a", true, sent_at);
+        assert!(line.ends_with('\n') && !line.trim_end().contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&line).unwrap(),
+            serde_json::json!({
+                "type": "post",
+                "sent_at": "2026-10-16T12:00:00Z",
+                "subreddit": "rust",
+                "title": "Tom & Jerry",
+                "url": "https://example.com/a",
+                "priority": true,
+            })
+        );
+
+        let line = post_line(FileFormat::Text, "rust", "Two\nlines", "https://example.com/a", false, sent_at);
+        assert_eq!(line, "2026-10-16T12:00:00Z r/rust: Two lines https://example.com/a\n");
+
+        assert_eq!(raw_line(FileFormat::Text, "Maintenance\ntonight", sent_at), "2026-10-16T12:00:00Z Maintenance tonight\n");
+        let raw: serde_json::Value = serde_json::from_str(&raw_line(FileFormat::Json, "hi", sent_at)).unwrap();
+        assert_eq!((raw["type"].as_str(), raw["text"].as_str()), (Some("message"), Some("hi")));
+    }
+
+    #[tokio::test]
+    async fn test_notifications_appended_in_chosen_format() {
+        let path = temp_path("posts.jsonl");
+        let json = notifier(&path, FileFormat::Json);
+        json.send("rust", "First", "https://example.com/1").await.unwrap();
+        json.send("golang", "Second", "https://example.com/2").await.unwrap();
+        let lines: Vec<serde_json::Value> =
+            std::fs::read_to_string(&path).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0]["subreddit"].as_str(), lines[1]["title"].as_str()), (Some("rust"), Some("Second")));
+
+        let path = temp_path("posts.log");
+        notifier(&path, FileFormat::Text).send("rust", "Hello", "https://example.com/1").await.unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.ends_with(" r/rust: Hello https://example.com/1\n"), "{}", text);
+    }
+
+    #[tokio::test]
+    async fn test_rotated_file_is_reopened() {
+        let path = temp_path("rotated.log");
+        let rotated = path.with_extension("log.1");
+        let file = notifier(&path, FileFormat::Text);
+
+        file.send("rust", "Before", "https://example.com/1").await.unwrap();
+        std::fs::rename(&path, &rotated).unwrap();
+        file.send("rust", "After", "https://example.com/2").await.unwrap();
+
+        assert!(std::fs::read_to_string(&rotated).unwrap().contains("Before"));
+        let current = std::fs::read_to_string(&path).unwrap();
+        assert!(current.contains("After") && !current.contains("Before"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_socket_receives_line() {
+        use std::io::Read;
+
+        let path = temp_path("notify.sock");
+        let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        notifier(&path, FileFormat::Json).send_raw("hello").await.unwrap();
+
+        // The connection is closed after the write, so reading to the end returns the one line
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut received = String::new();
+        stream.read_to_string(&mut received).unwrap();
+        let line: serde_json::Value = serde_json::from_str(&received).unwrap();
+        assert_eq!(line["text"], "hello");
+    }
+}
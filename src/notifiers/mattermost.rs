@@ -0,0 +1,147 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{Client, Request};
+
+use crate::models::database::EndpointKind;
+use crate::models::notifiers::MattermostConfig;
+use super::{extra_header_map, message::{append_footer, truncate_to_limit}, HttpStatusError, Message, Notifier};
+
+pub struct MattermostNotifier {
+    pub client: Client,
+    pub cfg: MattermostConfig,
+}
+
+/// Add the channel and username overrides; unset ones keep the webhook's defaults
+fn with_overrides(cfg: &MattermostConfig, mut payload: serde_json::Value) -> serde_json::Value {
+    if let Some(channel) = cfg.channel.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        payload["channel"] = serde_json::json!(channel);
+    }
+    if let Some(username) = cfg.username.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        payload["username"] = serde_json::json!(username);
+    }
+    payload
+}
+
+/// Build the webhook JSON body for a message
+///
+/// Mattermost renders `text` as Markdown: the title in bold, then the post
+/// title and its link (which the server previews).
+pub fn build_payload(cfg: &MattermostConfig, message: &Message) -> serde_json::Value {
+    let body = format!("**{}**\n{}\n{}", message.title, message.body, message.url);
+    let text = append_footer(&body, cfg.footer.as_deref(), EndpointKind::Mattermost.max_message_len(), "mattermost");
+    with_overrides(cfg, serde_json::json!({ "text": text }))
+}
+
+/// Build the webhook JSON body for a free-form message
+pub fn build_raw_payload(cfg: &MattermostConfig, message: &str) -> serde_json::Value {
+    let text = truncate_to_limit(message, EndpointKind::Mattermost.max_message_len(), "mattermost");
+    with_overrides(cfg, serde_json::json!({ "text": text }))
+}
+
+impl MattermostNotifier {
+    /// Build the webhook request, with the endpoint's extra headers
+    pub fn build_request(&self, payload: &serde_json::Value) -> Result<Request> {
+        Ok(self
+            .client
+            .post(&self.cfg.webhook_url)
+            .headers(extra_header_map(&self.cfg.extra_headers)?)
+            .json(payload)
+            .build()?)
+    }
+
+    async fn post(&self, payload: &serde_json::Value) -> Result<()> {
+        let res = self.client.execute(self.build_request(payload)?).await?;
+        if !res.status().is_success() {
+            return Err(HttpStatusError::from_response("mattermost webhook", res).await.into());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for MattermostNotifier {
+    fn kind(&self) -> &'static str {
+        "mattermost"
+    }
+
+    fn include_nsfw_marker(&self) -> bool {
+        self.cfg.include_nsfw_marker
+    }
+
+    async fn send(&self, subreddit: &str, title: &str, url: &str) -> Result<()> {
+        let message = Message::new(subreddit, title, url);
+        self.post(&build_payload(&self.cfg, &message)).await
+    }
+
+    async fn send_raw(&self, message: &str) -> Result<()> {
+        self.post(&build_raw_payload(&self.cfg, message)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(channel: Option<&str>, username: Option<&str>) -> MattermostConfig {
+        MattermostConfig {
+            webhook_url: "https://chat.example.com/hooks/abc123".to_string(),
+            channel: channel.map(str::to_string),
+            username: username.map(str::to_string),
+            include_nsfw_marker: true,
+            footer: None,
+            extra_headers: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_payload_shape() {
+        let message = Message::new("rust", "Tom &amp; Jerry", "https://www.reddit.com/r/rust/comments/abc/");
+        assert_eq!(
+            build_payload(&config(None, None), &message),
+            serde_json::json!({
+                "text": "**New Reddit Post Alert (rust)**\nTom & Jerry\nhttps://www.reddit.com/r/rust/comments/abc/"
+            })
+        );
+        assert_eq!(build_raw_payload(&config(None, None), "Maintenance tonight"), serde_json::json!({"text": "Maintenance tonight"}));
+    }
+
+    #[test]
+    fn test_channel_and_username_override() {
+        let cfg = config(Some("reddit-alerts"), Some("Reddit Notifier"));
+        let message = Message::new("rust", "title", "https://example.com");
+        let payload = build_payload(&cfg, &message);
+        assert_eq!(payload["channel"], "reddit-alerts");
+        assert_eq!(payload["username"], "Reddit Notifier");
+        assert_eq!(build_raw_payload(&cfg, "hi")["channel"], "reddit-alerts");
+
+        // Blank overrides are left out, so the webhook's defaults apply
+        let payload = build_raw_payload(&config(Some(" "), Some("")), "hi");
+        assert_eq!(payload, serde_json::json!({"text": "hi"}));
+    }
+
+    #[test]
+    fn test_footer_and_truncation() {
+        let cfg = MattermostConfig { footer: Some("via reddit-notifier".to_string()), ..config(None, None) };
+        let cap = EndpointKind::Mattermost.max_message_len();
+        let message = Message::new("rust", &"x".repeat(cap + 10), "https://example.com");
+        let text = build_payload(&cfg, &message)["text"].as_str().unwrap().to_string();
+        assert_eq!(text.chars().count(), cap);
+        assert!(text.ends_with("…\n\nvia reddit-notifier"));
+    }
+
+    #[test]
+    fn test_request_goes_to_webhook_with_extra_headers() {
+        let notifier = MattermostNotifier {
+            client: Client::new(),
+            cfg: MattermostConfig {
+                extra_headers: HashMap::from([("X-Proxy-Auth".to_string(), "Bearer abc".to_string())]),
+                ..config(None, None)
+            },
+        };
+        let request = notifier.build_request(&build_raw_payload(&notifier.cfg, "hi")).unwrap();
+        assert_eq!(request.url().as_str(), "https://chat.example.com/hooks/abc123");
+        assert_eq!(request.headers()["x-proxy-auth"], "Bearer abc");
+        assert_eq!(request.headers()["content-type"], "application/json");
+    }
+}
@@ -0,0 +1,149 @@
+use html_escape::decode_html_entities;
+use std::borrow::Cow;
+
+/// Subreddit used when previewing a notification
+pub const SAMPLE_SUBREDDIT: &str = "rust";
+
+/// Post title used when previewing a notification (HTML-escaped, as Reddit returns it)
+pub const SAMPLE_TITLE: &str = "Announcing Rust 1.90.0 &amp; what&#39;s next";
+
+/// Post URL used when previewing a notification
+pub const SAMPLE_URL: &str = "https://www.reddit.com/r/rust/comments/abc123/announcing_rust_1900/";
+
+/// Content warning prepended to the title of NSFW posts
+pub const NSFW_MARKER: &str = "🔞 NSFW";
+
+/// Post title with the NSFW marker prepended when the post is NSFW and the endpoint wants it
+pub fn marked_title(title: &str, nsfw: bool, include_marker: bool) -> Cow<'_, str> {
+    if nsfw && include_marker {
+        Cow::Owned(format!("{} {}", NSFW_MARKER, title))
+    } else {
+        Cow::Borrowed(title)
+    }
+}
+
+/// Cut `text` down to `max_chars` characters, ending with `…` when shortened
+///
+/// Logs a warning when truncation happens, naming the notifier `kind`.
+pub fn truncate_to_limit<'a>(text: &'a str, max_chars: usize, kind: &str) -> Cow<'a, str> {
+    let len = text.chars().count();
+    if len <= max_chars {
+        return Cow::Borrowed(text);
+    }
+    tracing::warn!(
+        "Truncating {} message from {} to {} characters to fit the service limit",
+        kind,
+        len,
+        max_chars
+    );
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    Cow::Owned(truncated)
+}
+
+/// Separator between the message body and an endpoint's footer
+pub const FOOTER_SEPARATOR: &str = "\n\n";
+
+/// `body` followed by `footer`, fitted into `max_chars`
+///
+/// The body is shortened first so the footer survives intact; a footer too
+/// long to fit even after an empty body is cut like any other text. A blank
+/// footer adds nothing.
+pub fn append_footer<'a>(body: &'a str, footer: Option<&str>, max_chars: usize, kind: &str) -> Cow<'a, str> {
+    let Some(footer) = footer.map(str::trim).filter(|f| !f.is_empty()) else {
+        return truncate_to_limit(body, max_chars, kind);
+    };
+
+    let reserved = FOOTER_SEPARATOR.chars().count() + footer.chars().count();
+    if reserved >= max_chars {
+        let full = format!("{}{}{}", body, FOOTER_SEPARATOR, footer);
+        return Cow::Owned(truncate_to_limit(&full, max_chars, kind).into_owned());
+    }
+    let body = truncate_to_limit(body, max_chars - reserved, kind);
+    Cow::Owned(format!("{}{}{}", body, FOOTER_SEPARATOR, footer))
+}
+
+/// The user-visible parts of a notification, shared by every notifier
+///
+/// Notifiers render through this so the TUI preview shows exactly what
+/// would be sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub title: String,
+    pub body: String,
+    pub url: String,
+}
+
+impl Message {
+    /// Render a notification for a post
+    pub fn new(subreddit: &str, title: &str, url: &str) -> Self {
+        Self {
+            title: format!("New Reddit Post Alert ({})", subreddit),
+            body: decode_html_entities(title).to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    /// Render the notification for the sample post
+    pub fn sample() -> Self {
+        Self::new(SAMPLE_SUBREDDIT, SAMPLE_TITLE, SAMPLE_URL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_decodes_title_entities() {
+        let msg = Message::new("rust", "Tom &amp; Jerry", "https://example.com");
+        assert_eq!(msg.title, "New Reddit Post Alert (rust)");
+        assert_eq!(msg.body, "Tom & Jerry");
+        assert_eq!(msg.url, "https://example.com");
+    }
+
+    #[test]
+    fn test_nsfw_marker_needs_post_and_endpoint_flag() {
+        assert_eq!(marked_title("Title", true, true), "🔞 NSFW Title");
+        assert_eq!(marked_title("Title", true, false), "Title");
+        assert_eq!(marked_title("Title", false, true), "Title");
+        assert_eq!(marked_title("Title", false, false), "Title");
+    }
+
+    #[test]
+    fn test_truncate_to_limit() {
+        assert_eq!(truncate_to_limit("short", 10, "test"), "short");
+        assert_eq!(truncate_to_limit("exactly10!", 10, "test"), "exactly10!");
+
+        let long = "é".repeat(30);
+        let cut = truncate_to_limit(&long, 10, "test");
+        assert_eq!(cut.chars().count(), 10);
+        assert!(cut.ends_with('…'));
+    }
+
+    #[test]
+    fn test_append_footer() {
+        assert_eq!(append_footer("Body", Some("via reddit-notifier"), 100, "test"), "Body\n\nvia reddit-notifier");
+        assert_eq!(append_footer("Body", None, 100, "test"), "Body");
+        assert_eq!(append_footer("Body", Some("  "), 100, "test"), "Body");
+    }
+
+    #[test]
+    fn test_footer_survives_body_truncation() {
+        let body = "x".repeat(50);
+        let text = append_footer(&body, Some("via bot"), 20, "test");
+        assert_eq!(text.chars().count(), 20);
+        assert_eq!(text, format!("{}…\n\nvia bot", "x".repeat(10)));
+
+        // A footer that can't fit at all is cut with the rest of the text
+        let text = append_footer("Body", Some(&"f".repeat(30)), 20, "test");
+        assert_eq!(text.chars().count(), 20);
+        assert!(text.starts_with("Body\n\nfff") && text.ends_with('…'));
+    }
+
+    #[test]
+    fn test_sample_message() {
+        let msg = Message::sample();
+        assert_eq!(msg.body, "Announcing Rust 1.90.0 & what's next");
+    }
+}
@@ -1,30 +1,627 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, StatusCode,
+};
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+use tracing::warn;
 
+use crate::http_client::{client_builder, HttpClientConfig};
 use crate::models::{
-    database::{EndpointKind, EndpointRow},
-    notifiers::{DiscordConfig, PushoverConfig},
+    config::AppConfig,
+    database::{EndpointKind, EndpointRow, NotifierCapabilities},
+    notifiers::{upgrade_endpoint_config, DiscordConfig, FileConfig, MattermostConfig, PushoverConfig, TeamsConfig},
 };
+use crate::secrets::resolve_config_secrets;
 
+pub mod curl;
 pub mod discord;
+pub mod file;
+pub mod mattermost;
+pub mod message;
 pub mod pushover;
+pub mod resend;
+pub mod teams;
+pub mod test_run;
+
+pub use message::{marked_title, Message};
+
+/// Default number of attempts made by `send_with_retry`
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default base delay for the exponential backoff in `send_with_retry`
+pub const DEFAULT_BASE_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Default per-request timeout for notification delivery
+pub const DEFAULT_NOTIFIER_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Upper bound on any single retry delay (including server-provided Retry-After)
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
 
 #[async_trait]
 pub trait Notifier: Send + Sync {
     fn kind(&self) -> &'static str;
+
+    /// What this notifier supports; by default, the capabilities of the kind named by `kind()`
+    fn capabilities(&self) -> NotifierCapabilities {
+        self.kind()
+            .parse::<EndpointKind>()
+            .map(|kind| kind.capabilities())
+            .unwrap_or_default()
+    }
     async fn send(&self, subreddit: &str, title: &str, url: &str) -> Result<()>;
+
+    /// Send a post from a priority subscription
+    ///
+    /// Notifiers that support pinging (Discord `mention`) override this; the
+    /// default sends a normal notification.
+    async fn send_priority(&self, subreddit: &str, title: &str, url: &str) -> Result<()> {
+        self.send(subreddit, title, url).await
+    }
+
+    /// Whether titles of NSFW posts get the content-warning marker on this endpoint
+    fn include_nsfw_marker(&self) -> bool {
+        true
+    }
+
+    /// Send free-form text instead of a post notification (admin announcements, format checks)
+    ///
+    /// The default forwards the text as the post title with no subreddit or URL;
+    /// notifiers override this to send a plain message without the post framing.
+    async fn send_raw(&self, message: &str) -> Result<()> {
+        self.send("", message, "").await
+    }
+}
+
+/// Error returned by notifiers when the remote service answers with a non-success status
+///
+/// Carrying the status (and any Retry-After hint) lets `send_with_retry` tell
+/// transient failures apart from permanent ones.
+#[derive(Debug)]
+pub struct HttpStatusError {
+    pub service: &'static str,
+    pub status: StatusCode,
+    pub body: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl HttpStatusError {
+    /// Build an error from a non-success response, consuming its body
+    pub async fn from_response(service: &'static str, res: reqwest::Response) -> Self {
+        let status = res.status();
+        let retry_after = parse_retry_after(res.headers());
+        let body = res.text().await.unwrap_or_default();
+        Self {
+            service,
+            status,
+            body,
+            retry_after,
+        }
+    }
+}
+
+impl fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} non-success: {} body: {}", self.service, self.status, self.body)
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
+/// Turn an endpoint's `extra_headers` into request headers
+///
+/// Invalid names or values are an error (the config may have been edited
+/// outside the app, or a `file:`/`env:` secret resolved to something invalid).
+pub(crate) fn extra_header_map(headers: &HashMap<String, String>) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| anyhow::anyhow!("invalid header name '{}'", name))?;
+        let value = HeaderValue::from_str(value).map_err(|_| anyhow::anyhow!("invalid value for header '{}'", name))?;
+        map.insert(name, value);
+    }
+    Ok(map)
 }
 
-pub fn build_notifier(row: &EndpointRow, client: Client) -> Result<Box<dyn Notifier>> {
+/// Parse a Retry-After header given in seconds (HTTP-date values are ignored)
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+/// Whether a failed send should be attempted again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Transient failure - retry, optionally after a server-provided delay
+    Retry(Option<Duration>),
+    /// Permanent failure - give up immediately
+    Fail,
+}
+
+/// Classify a send error as retryable or not
+///
+/// Timeouts, connection errors, 5xx responses and 429 (honoring Retry-After)
+/// are retried. Other 4xx responses and local errors (bad config, serialization)
+/// fail fast.
+pub fn classify_error(err: &anyhow::Error) -> RetryDecision {
+    if let Some(http) = err.downcast_ref::<HttpStatusError>() {
+        return classify_status(http.status, http.retry_after);
+    }
+
+    if let Some(req) = err.downcast_ref::<reqwest::Error>() {
+        if req.is_timeout() || req.is_connect() || req.is_request() {
+            return RetryDecision::Retry(None);
+        }
+        if let Some(status) = req.status() {
+            return classify_status(status, None);
+        }
+    }
+
+    RetryDecision::Fail
+}
+
+fn classify_status(status: StatusCode, retry_after: Option<Duration>) -> RetryDecision {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        RetryDecision::Retry(retry_after)
+    } else if status.is_server_error() {
+        RetryDecision::Retry(None)
+    } else {
+        RetryDecision::Fail
+    }
+}
+
+/// The message being delivered by `send_with_retry`
+#[derive(Debug, Clone, Copy)]
+pub struct SendContext<'a> {
+    pub subreddit: &'a str,
+    pub title: &'a str,
+    pub url: &'a str,
+    /// The post is marked NSFW (`over_18`)
+    pub nsfw: bool,
+    /// The post comes from a priority subscription (sent with the endpoint's mention)
+    pub priority: bool,
+}
+
+/// Send a notification, retrying transient failures with exponential backoff and jitter
+///
+/// # Arguments
+/// * `notifier` - The notifier to send through
+/// * `ctx` - The message to deliver
+/// * `max_attempts` - Total attempts including the first one (minimum 1)
+/// * `base_backoff` - Delay before the first retry; doubles on each further retry
+///
+/// # Returns
+/// The error from the last attempt if all attempts failed, or the first
+/// non-retryable error.
+pub async fn send_with_retry(
+    notifier: &dyn Notifier,
+    ctx: &SendContext<'_>,
+    max_attempts: u32,
+    base_backoff: Duration,
+) -> Result<()> {
+    let title = &marked_title(ctx.title, ctx.nsfw, notifier.include_nsfw_marker());
+    retry_send(notifier.kind(), max_attempts, base_backoff, || async move {
+        if ctx.priority {
+            notifier.send_priority(ctx.subreddit, title, ctx.url).await
+        } else {
+            notifier.send(ctx.subreddit, title, ctx.url).await
+        }
+    })
+    .await
+}
+
+/// Send free-form text with the same retry policy as `send_with_retry`
+pub async fn send_raw_with_retry(notifier: &dyn Notifier, message: &str, max_attempts: u32, base_backoff: Duration) -> Result<()> {
+    retry_send(notifier.kind(), max_attempts, base_backoff, || notifier.send_raw(message)).await
+}
+
+/// Run `send` until it succeeds, fails permanently or runs out of attempts
+async fn retry_send<F, Fut>(kind: &str, max_attempts: u32, base_backoff: Duration, mut send: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+
+        let err = match send().await {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+
+        let retry_after = match classify_error(&err) {
+            RetryDecision::Retry(retry_after) if attempt < max_attempts => retry_after,
+            _ => return Err(err),
+        };
+
+        let delay = retry_after
+            .unwrap_or_else(|| backoff_delay(base_backoff, attempt))
+            .min(MAX_BACKOFF);
+
+        warn!(
+            "{} send attempt {}/{} failed: {} - retrying in {}ms",
+            kind,
+            attempt,
+            max_attempts,
+            err,
+            delay.as_millis()
+        );
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Exponential backoff for the given (1-based) attempt, plus up to 50% jitter
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    let exp = base.saturating_mul(1u32 << (attempt - 1).min(16));
+    exp + jitter(exp / 2)
+}
+
+/// A pseudo-random duration in `0..=max`
+pub(crate) fn jitter(max: Duration) -> Duration {
+    let max_nanos = max.as_nanos() as u64;
+    if max_nanos == 0 {
+        return Duration::ZERO;
+    }
+    // RandomState is seeded randomly per instance, which is plenty for jitter
+    let random = RandomState::new().build_hasher().finish();
+    Duration::from_nanos(random % (max_nanos + 1))
+}
+
+/// How notifications are sent: client settings plus the retry policy
+///
+/// The daemon and every TUI send (test notifications, raw messages) use the
+/// same profile, so a test behaves like a real delivery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotifierClientProfile {
+    /// Timeout for each request, including connecting
+    pub timeout: Duration,
+    /// Total attempts per notification (see `send_with_retry`)
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub base_backoff: Duration,
+    pub user_agent: Option<String>,
+    /// Proxy and extra CA certificates
+    pub http: HttpClientConfig,
+    /// Send endpoint footers; off drops every endpoint's footer (`NOTIFICATION_FOOTERS=false`)
+    pub footers: bool,
+}
+
+impl Default for NotifierClientProfile {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_NOTIFIER_TIMEOUT,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            user_agent: None,
+            http: HttpClientConfig::default(),
+            footers: true,
+        }
+    }
+}
+
+impl NotifierClientProfile {
+    /// Build the profile from the application configuration
+    pub fn from_app_config(cfg: &AppConfig) -> Self {
+        Self {
+            timeout: Duration::from_secs(cfg.notifier_timeout_secs.max(1)),
+            max_attempts: cfg.notifier_max_attempts.max(1),
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            user_agent: Some(cfg.reddit_user_agent.clone()),
+            http: HttpClientConfig::from_app_config(cfg),
+            footers: cfg.notification_footers,
+        }
+    }
+}
+
+/// Build the HTTP client described by `profile`
+///
+/// Fails on an invalid proxy or CA file (see `client_builder`).
+pub fn build_client(profile: &NotifierClientProfile) -> Result<Client> {
+    let mut builder = client_builder(&profile.http)?.timeout(profile.timeout);
+    if let Some(user_agent) = &profile.user_agent {
+        builder = builder.user_agent(user_agent.clone());
+    }
+    Ok(builder.build()?)
+}
+
+/// Build the notifier for an endpoint; with `footers` off its footer is dropped
+pub fn build_notifier(row: &EndpointRow, client: Client, footers: bool) -> Result<Box<dyn Notifier>> {
+    // Older config shapes are read as the current one
+    let config_json = upgrade_endpoint_config(&row.kind, &row.config_json).map_err(anyhow::Error::msg)?;
+    // `file:` / `env:` references are resolved here so secrets never live in the database
+    let config_json = resolve_config_secrets(&config_json)?;
     match row.kind {
         EndpointKind::Discord => {
-            let cfg: DiscordConfig = serde_json::from_str(&row.config_json)?;
+            let mut cfg: DiscordConfig = serde_json::from_str(&config_json)?;
+            if !footers {
+                cfg.footer = None;
+            }
             Ok(Box::new(discord::DiscordNotifier { client, cfg }))
         }
         EndpointKind::Pushover => {
-            let cfg: PushoverConfig = serde_json::from_str(&row.config_json)?;
+            let mut cfg: PushoverConfig = serde_json::from_str(&config_json)?;
+            if !footers {
+                cfg.footer = None;
+            }
             Ok(Box::new(pushover::PushoverNotifier { client, cfg }))
         }
+        EndpointKind::Mattermost => {
+            let mut cfg: MattermostConfig = serde_json::from_str(&config_json)?;
+            if !footers {
+                cfg.footer = None;
+            }
+            Ok(Box::new(mattermost::MattermostNotifier { client, cfg }))
+        }
+        EndpointKind::Teams => {
+            let mut cfg: TeamsConfig = serde_json::from_str(&config_json)?;
+            if !footers {
+                cfg.footer = None;
+            }
+            Ok(Box::new(teams::TeamsNotifier { client, cfg }))
+        }
+        EndpointKind::File => {
+            let cfg: FileConfig = serde_json::from_str(&config_json)?;
+            Ok(Box::new(file::FileNotifier { cfg }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Notifier that fails with the given status a fixed number of times, then succeeds
+    struct FlakyNotifier {
+        failures: u32,
+        status: StatusCode,
+        calls: AtomicU32,
+    }
+
+    impl FlakyNotifier {
+        fn new(failures: u32, status: StatusCode) -> Self {
+            Self {
+                failures,
+                status,
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Notifier for FlakyNotifier {
+        fn kind(&self) -> &'static str {
+            "flaky"
+        }
+
+        async fn send(&self, _subreddit: &str, _title: &str, _url: &str) -> Result<()> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if call <= self.failures {
+                return Err(status_error(self.status, None).into());
+            }
+            Ok(())
+        }
+    }
+
+    fn status_error(status: StatusCode, retry_after: Option<Duration>) -> HttpStatusError {
+        HttpStatusError {
+            service: "test",
+            status,
+            body: String::new(),
+            retry_after,
+        }
+    }
+
+    fn ctx() -> SendContext<'static> {
+        SendContext {
+            subreddit: "test",
+            title: "title",
+            url: "https://reddit.com",
+            nsfw: false,
+            priority: false,
+        }
+    }
+
+    #[test]
+    fn test_classify_server_error_is_retryable() {
+        let err = anyhow::Error::from(status_error(StatusCode::BAD_GATEWAY, None));
+        assert_eq!(classify_error(&err), RetryDecision::Retry(None));
+    }
+
+    #[test]
+    fn test_classify_rate_limited_honors_retry_after() {
+        let err = anyhow::Error::from(status_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(Duration::from_secs(2)),
+        ));
+        assert_eq!(
+            classify_error(&err),
+            RetryDecision::Retry(Some(Duration::from_secs(2)))
+        );
+    }
+
+    #[test]
+    fn test_classify_client_error_fails_fast() {
+        for status in [StatusCode::BAD_REQUEST, StatusCode::UNAUTHORIZED, StatusCode::NOT_FOUND] {
+            let err = anyhow::Error::from(status_error(status, None));
+            assert_eq!(classify_error(&err), RetryDecision::Fail);
+        }
+    }
+
+    #[test]
+    fn test_classify_unknown_error_fails_fast() {
+        let err = anyhow::anyhow!("invalid config");
+        assert_eq!(classify_error(&err), RetryDecision::Fail);
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "3".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(3)));
+
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_jitter_within_bounds() {
+        let max = Duration::from_millis(10);
+        for _ in 0..100 {
+            assert!(jitter(max) <= max);
+        }
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+    }
+
+    /// Notifier that records the titles it was asked to send
+    struct TitleRecorder {
+        include_nsfw_marker: bool,
+        titles: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Notifier for TitleRecorder {
+        fn kind(&self) -> &'static str {
+            "recorder"
+        }
+
+        fn include_nsfw_marker(&self) -> bool {
+            self.include_nsfw_marker
+        }
+
+        async fn send(&self, _subreddit: &str, title: &str, _url: &str) -> Result<()> {
+            self.titles.lock().unwrap().push(title.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nsfw_marker_added_only_when_post_and_endpoint_agree() {
+        for (nsfw, include, expected) in [
+            (true, true, "🔞 NSFW title"),
+            (true, false, "title"),
+            (false, true, "title"),
+        ] {
+            let notifier = TitleRecorder {
+                include_nsfw_marker: include,
+                titles: std::sync::Mutex::new(Vec::new()),
+            };
+            let ctx = SendContext { nsfw, ..ctx() };
+            send_with_retry(&notifier, &ctx, 1, Duration::from_millis(1)).await.unwrap();
+            assert_eq!(notifier.titles.lock().unwrap().as_slice(), [expected]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_recovers_from_transient_errors() {
+        let notifier = FlakyNotifier::new(2, StatusCode::SERVICE_UNAVAILABLE);
+        let result = send_with_retry(&notifier, &ctx(), 3, Duration::from_millis(1)).await;
+        assert!(result.is_ok());
+        assert_eq!(notifier.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_gives_up_after_max_attempts() {
+        let notifier = FlakyNotifier::new(10, StatusCode::INTERNAL_SERVER_ERROR);
+        let result = send_with_retry(&notifier, &ctx(), 3, Duration::from_millis(1)).await;
+        assert!(result.is_err());
+        assert_eq!(notifier.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_fails_fast_on_client_error() {
+        let notifier = FlakyNotifier::new(10, StatusCode::FORBIDDEN);
+        let result = send_with_retry(&notifier, &ctx(), 3, Duration::from_millis(1)).await;
+        assert!(result.is_err());
+        assert_eq!(notifier.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_build_notifier_reports_unresolvable_secret() {
+        let row = EndpointRow {
+            id: 1,
+            kind: EndpointKind::Pushover,
+            config_json: r#"{"token":"env:REDDIT_NOTIFIER_SURELY_UNSET_VAR","user":"u"}"#.to_string(),
+            active: true,
+            note: None,
+        };
+        let err = build_notifier(&row, Client::new(), true).err().unwrap();
+        assert!(format!("{:#}", err).starts_with("cannot resolve token: environment variable"));
+    }
+
+    #[test]
+    fn test_build_notifier_reads_upgraded_config() {
+        let row = |config_json: &str| EndpointRow {
+            id: 1,
+            kind: EndpointKind::Discord,
+            config_json: config_json.to_string(),
+            active: true,
+            note: None,
+        };
+        // A version 1 config without `version` still builds
+        assert!(build_notifier(&row(r#"{"webhook_url":"https://discord.com/api/webhooks/1/abc"}"#), Client::new(), true).is_ok());
+        let future = row(r#"{"webhook_urls":["https://discord.com/api/webhooks/1/abc"],"version":99}"#);
+        let err = build_notifier(&future, Client::new(), true).err().unwrap();
+        assert!(err.to_string().contains("newer than this build"));
+    }
+
+    #[test]
+    fn test_notifiers_report_their_kinds_capabilities() {
+        for kind in EndpointKind::all() {
+            let config_json = match kind {
+                EndpointKind::Discord => r#"{"webhook_url":"https://discord.com/api/webhooks/1/abc"}"#,
+                EndpointKind::Pushover => r#"{"token":"t","user":"u"}"#,
+                EndpointKind::Mattermost => r#"{"webhook_url":"https://chat.example.com/hooks/abc"}"#,
+                EndpointKind::Teams => r#"{"webhook_url":"https://x.webhook.office.com/webhookb2/abc"}"#,
+                EndpointKind::File => r#"{"path":"/var/log/reddit-notifier/posts.jsonl"}"#,
+            };
+            let row = EndpointRow { id: 1, kind: kind.clone(), config_json: config_json.to_string(), active: true, note: None };
+            let notifier = build_notifier(&row, Client::new(), true).unwrap();
+            assert_eq!(notifier.capabilities(), kind.capabilities());
+        }
+        assert_eq!(FlakyNotifier::new(0, StatusCode::OK).capabilities(), NotifierCapabilities::default());
+    }
+
+    #[tokio::test]
+    async fn test_build_client_applies_timeout_and_user_agent() {
+        use std::io::Read;
+
+        // Accepts one request, reports its User-Agent, and never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = stream.read(&mut buf).unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+            let agent = request.lines().find_map(|l| l.strip_prefix("user-agent: ")).map(str::to_string);
+            tx.send(agent).unwrap();
+            std::thread::sleep(Duration::from_secs(2));
+        });
+
+        let profile = NotifierClientProfile {
+            timeout: Duration::from_millis(100),
+            user_agent: Some("profile-test/1.0".to_string()),
+            ..NotifierClientProfile::default()
+        };
+        let err = build_client(&profile).unwrap().get(&url).send().await.unwrap_err();
+        assert!(err.is_timeout(), "{}", err);
+        assert_eq!(rx.recv().unwrap().as_deref(), Some("profile-test/1.0"));
     }
 }
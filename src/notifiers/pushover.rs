@@ -1,43 +1,160 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::Client;
-use html_escape::decode_html_entities;
+use reqwest::{Client, Request};
 
+use crate::models::database::EndpointKind;
 use crate::models::notifiers::PushoverConfig;
-use super::Notifier;
+use super::{extra_header_map, message::{append_footer, truncate_to_limit}, HttpStatusError, Message, Notifier};
+
+/// Pushover message API endpoint
+pub const API_URL: &str = "https://api.pushover.net/1/messages.json";
 
 pub struct PushoverNotifier {
     pub client: Client,
     pub cfg: PushoverConfig,
 }
 
+/// Build the form fields posted to the Pushover API for a message
+pub fn build_form(cfg: &PushoverConfig, message: Message) -> Vec<(&'static str, String)> {
+    let mut form = vec![
+        ("token", cfg.token.clone()),
+        ("user", cfg.user.clone()),
+        ("title", message.title),
+        ("message", append_footer(&message.body, cfg.footer.as_deref(), EndpointKind::Pushover.max_message_len(), "pushover").into_owned()),
+        ("url", message.url),
+    ];
+    if let Some(device) = &cfg.device {
+        form.push(("device", device.clone()));
+    }
+    form
+}
+
+/// Build the form fields for a free-form message (Pushover uses the app name as title)
+pub fn build_raw_form(cfg: &PushoverConfig, message: &str) -> Vec<(&'static str, String)> {
+    let mut form = vec![
+        ("token", cfg.token.clone()),
+        ("user", cfg.user.clone()),
+        ("message", truncate_to_limit(message, EndpointKind::Pushover.max_message_len(), "pushover").into_owned()),
+    ];
+    if let Some(device) = &cfg.device {
+        form.push(("device", device.clone()));
+    }
+    form
+}
+
+impl PushoverNotifier {
+    /// Build the API request, with the endpoint's extra headers
+    pub fn build_request(&self, form: &[(&'static str, String)]) -> Result<Request> {
+        Ok(self
+            .client
+            .post(API_URL)
+            .headers(extra_header_map(&self.cfg.extra_headers)?)
+            .form(form)
+            .build()?)
+    }
+
+    async fn post(&self, form: &[(&'static str, String)]) -> Result<()> {
+        let res = self.client.execute(self.build_request(form)?).await?;
+        if !res.status().is_success() {
+            return Err(HttpStatusError::from_response("pushover", res).await.into());
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Notifier for PushoverNotifier {
     fn kind(&self) -> &'static str {
         "pushover"
     }
 
+    fn include_nsfw_marker(&self) -> bool {
+        self.cfg.include_nsfw_marker
+    }
+
     async fn send(&self, subreddit: &str, title: &str, url: &str) -> Result<()> {
-        let mut form = vec![
-            ("token", self.cfg.token.clone()),
-            ("user", self.cfg.user.clone()),
-            ("title", format!("New Reddit Post Alert ({})", subreddit).to_string()),
-            ("message", decode_html_entities(title).to_string()),
-            ("url", url.to_string()),
-        ];
-        if let Some(device) = &self.cfg.device {
-            form.push(("device", device.clone()));
-        }
-        let res = self.client
-            .post("https://api.pushover.net/1/messages.json")
-            .form(&form)
-            .send()
-            .await?;
-        let status = res.status();
-        if !status.is_success() {
-            let body = res.text().await.unwrap_or_default();
-            anyhow::bail!("pushover non-success: {} body: {}", status, body);
-        }
-        Ok(())
+        let message = Message::new(subreddit, title, url);
+        self.post(&build_form(&self.cfg, message)).await
+    }
+
+    async fn send_raw(&self, message: &str) -> Result<()> {
+        self.post(&build_raw_form(&self.cfg, message)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_raw_form_has_message_only() {
+        let cfg = PushoverConfig {
+            token: "app-token".to_string(),
+            user: "user-key".to_string(),
+            device: Some("phone".to_string()),
+            include_nsfw_marker: true,
+            footer: None,
+            extra_headers: HashMap::new(),
+        };
+        let form = build_raw_form(&cfg, "Maintenance tonight");
+        assert_eq!(
+            form,
+            vec![
+                ("token", "app-token".to_string()),
+                ("user", "user-key".to_string()),
+                ("message", "Maintenance tonight".to_string()),
+                ("device", "phone".to_string()),
+            ]
+        );
+        assert!(!form.iter().any(|(k, _)| *k == "title" || *k == "url"));
+    }
+
+    #[test]
+    fn test_over_limit_message_is_truncated() {
+        let cfg = PushoverConfig {
+            token: "app-token".to_string(),
+            user: "user-key".to_string(),
+            device: None,
+            include_nsfw_marker: true,
+            footer: None,
+            extra_headers: HashMap::new(),
+        };
+        let cap = EndpointKind::Pushover.max_message_len();
+        let long = "y".repeat(cap * 2);
+        let message_len = |form: Vec<(&'static str, String)>| {
+            form.into_iter().find(|(k, _)| *k == "message").unwrap().1.chars().count()
+        };
+
+        assert_eq!(message_len(build_raw_form(&cfg, &long)), cap);
+        assert_eq!(message_len(build_form(&cfg, Message::new("rust", &long, "https://example.com"))), cap);
+    }
+
+    #[test]
+    fn test_extra_headers_attached_to_request() {
+        let notifier = PushoverNotifier {
+            client: Client::new(),
+            cfg: PushoverConfig {
+                token: "app-token".to_string(),
+                user: "user-key".to_string(),
+                device: None,
+                include_nsfw_marker: true,
+                footer: None,
+                extra_headers: HashMap::from([
+                    ("X-Proxy-Auth".to_string(), "Bearer abc".to_string()),
+                    ("X-Tenant".to_string(), "home".to_string()),
+                ]),
+            },
+        };
+        let request = notifier.build_request(&build_raw_form(&notifier.cfg, "hi")).unwrap();
+        assert_eq!(request.url().as_str(), API_URL);
+        assert_eq!(request.headers()["x-proxy-auth"], "Bearer abc");
+        assert_eq!(request.headers()["x-tenant"], "home");
+
+        // A header made invalid outside the app (e.g. a resolved secret) fails the send
+        let mut bad = notifier.cfg.clone();
+        bad.extra_headers.insert("X-Token".to_string(), "line\nbreak".to_string());
+        let bad = PushoverNotifier { client: Client::new(), cfg: bad };
+        assert!(bad.build_request(&[]).is_err());
     }
 }
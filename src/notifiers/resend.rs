@@ -0,0 +1,106 @@
+//! Send recent notifications again to one endpoint
+//!
+//! Used to let a newly added endpoint catch up on what others already
+//! received. Posts are read from the notification history and delivered
+//! through the endpoint's notifier; the history itself (and so dedup) is
+//! left untouched.
+
+use anyhow::Result;
+use tracing::warn;
+
+use crate::rate_limiter::RateLimiter;
+use crate::services::DatabaseService;
+use super::{send_with_retry, Notifier, SendContext, DEFAULT_BASE_BACKOFF, DEFAULT_MAX_ATTEMPTS};
+
+/// What `resend_recent` did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResendSummary {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// Re-send the `count` most recent notified posts (oldest first) through `notifier`
+///
+/// `subreddit` limits the posts to one subreddit. Each send waits for
+/// `rate_limiter`, so a large catch-up doesn't trip the service's limits.
+/// A failed post is logged and counted; the rest are still sent.
+pub async fn resend_recent<D: DatabaseService>(
+    db: &D,
+    notifier: &dyn Notifier,
+    count: i64,
+    subreddit: Option<&str>,
+    rate_limiter: &RateLimiter,
+) -> Result<ResendSummary> {
+    let posts = db.recent_stored_posts(count, subreddit).await?;
+    let mut summary = ResendSummary::default();
+
+    for post in &posts {
+        rate_limiter.acquire().await;
+        let ctx = SendContext {
+            subreddit: &post.subreddit,
+            title: &post.title,
+            url: &post.url,
+            nsfw: false,
+            priority: false,
+        };
+        match send_with_retry(notifier, &ctx, DEFAULT_MAX_ATTEMPTS, DEFAULT_BASE_BACKOFF).await {
+            Ok(()) => summary.sent += 1,
+            Err(e) => {
+                warn!("Failed to re-send post {} from r/{}: {}", post.post_id, post.subreddit, e);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use crate::services::mock_database::MockDatabaseService;
+
+    /// Notifier that records every title it is asked to send
+    #[derive(Default)]
+    struct RecordingNotifier {
+        titles: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        fn kind(&self) -> &'static str {
+            "recording"
+        }
+
+        async fn send(&self, _subreddit: &str, title: &str, _url: &str) -> Result<()> {
+            self.titles.lock().unwrap().push(title.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resend_sends_each_selected_post_once() {
+        let db = MockDatabaseService::new();
+        for (subreddit, id) in [("rust", "a"), ("golang", "b"), ("rust", "c"), ("Rust", "d")] {
+            db.record_if_new(subreddit, id, &format!("post {}", id), "https://redd.it/x").await.unwrap();
+        }
+        let limiter = RateLimiter::new(10, Duration::from_millis(1));
+
+        let notifier = RecordingNotifier::default();
+        let summary = resend_recent(&db, &notifier, 3, None, &limiter).await.unwrap();
+        assert_eq!(summary, ResendSummary { sent: 3, failed: 0 });
+        assert_eq!(*notifier.titles.lock().unwrap(), vec!["post b", "post c", "post d"]);
+
+        let notifier = RecordingNotifier::default();
+        resend_recent(&db, &notifier, 10, Some("rust"), &limiter).await.unwrap();
+        assert_eq!(*notifier.titles.lock().unwrap(), vec!["post a", "post c", "post d"]);
+
+        // Dedup state is untouched: the posts are still known, nothing new was recorded
+        assert!(!db.record_if_new("rust", "a", "post a", "").await.unwrap());
+        assert_eq!(db.recent_stored_posts(10, None).await.unwrap().len(), 4);
+    }
+}
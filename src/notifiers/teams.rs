@@ -0,0 +1,287 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{Client, Request, StatusCode};
+use serde_json::json;
+
+use crate::models::database::EndpointKind;
+use crate::models::notifiers::{TeamsCard, TeamsConfig};
+use super::{extra_header_map, message::{truncate_to_limit, FOOTER_SEPARATOR}, HttpStatusError, Message, Notifier};
+
+/// Accent colour of legacy MessageCards (Reddit orange)
+const THEME_COLOR: &str = "FF4500";
+
+pub struct TeamsNotifier {
+    pub client: Client,
+    pub cfg: TeamsConfig,
+}
+
+/// Escape the Markdown Teams renders in card text, so a post title can't break the link around it
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '(' | ')' | '*' | '_' | '`' | '#' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The footer to show, if any, and the post title shortened to leave room for it
+fn fit_body<'a>(cfg: &'a TeamsConfig, message: &'a Message) -> (String, Option<&'a str>) {
+    let footer = cfg.footer.as_deref().map(str::trim).filter(|f| !f.is_empty());
+    let reserved = footer.map_or(0, |f| FOOTER_SEPARATOR.chars().count() + f.chars().count());
+    let max = EndpointKind::Teams.max_message_len().saturating_sub(reserved);
+    (truncate_to_limit(&message.body, max, "teams").into_owned(), footer)
+}
+
+/// Wrap Adaptive Card content in the message envelope Teams webhooks expect
+fn adaptive_message(body: Vec<serde_json::Value>, actions: Vec<serde_json::Value>) -> serde_json::Value {
+    let mut card = json!({
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "type": "AdaptiveCard",
+        "version": "1.4",
+        "body": body,
+    });
+    if !actions.is_empty() {
+        card["actions"] = json!(actions);
+    }
+    json!({
+        "type": "message",
+        "attachments": [{
+            "contentType": "application/vnd.microsoft.card.adaptive",
+            "contentUrl": null,
+            "content": card,
+        }],
+    })
+}
+
+/// An Adaptive Card message holding one block of text
+///
+/// Used for free-form messages and for testing a webhook.
+pub fn text_card(text: &str) -> serde_json::Value {
+    adaptive_message(vec![json!({"type": "TextBlock", "text": text, "wrap": true})], Vec::new())
+}
+
+/// Build the webhook JSON body for a message
+///
+/// The card shows the subreddit heading, the post title linked to the post,
+/// an "Open post" button and the footer. Teams rejects cards with empty
+/// text blocks, so a blank footer is left out rather than sent empty.
+pub fn build_payload(cfg: &TeamsConfig, message: &Message) -> serde_json::Value {
+    let (body, footer) = fit_body(cfg, message);
+    let link = format!("[{}]({})", escape_markdown(&body), message.url);
+    match cfg.card {
+        TeamsCard::Adaptive => {
+            let mut blocks = vec![
+                json!({"type": "TextBlock", "text": message.title, "weight": "Bolder", "size": "Medium", "wrap": true}),
+                json!({"type": "TextBlock", "text": link, "wrap": true}),
+            ];
+            if let Some(footer) = footer {
+                blocks.push(json!({"type": "TextBlock", "text": footer, "isSubtle": true, "size": "Small", "wrap": true}));
+            }
+            let actions = vec![json!({"type": "Action.OpenUrl", "title": "Open post", "url": message.url})];
+            adaptive_message(blocks, actions)
+        }
+        TeamsCard::MessageCard => {
+            let text = match footer {
+                Some(footer) => format!("{}{}{}", link, FOOTER_SEPARATOR, footer),
+                None => link,
+            };
+            json!({
+                "@type": "MessageCard",
+                "@context": "https://schema.org/extensions",
+                "summary": message.title,
+                "themeColor": THEME_COLOR,
+                "title": message.title,
+                "text": text,
+                "potentialAction": [{
+                    "@type": "OpenUri",
+                    "name": "Open post",
+                    "targets": [{"os": "default", "uri": message.url}],
+                }],
+            })
+        }
+    }
+}
+
+/// Build the webhook JSON body for a free-form message
+pub fn build_raw_payload(cfg: &TeamsConfig, message: &str) -> serde_json::Value {
+    let text = truncate_to_limit(message, EndpointKind::Teams.max_message_len(), "teams");
+    match cfg.card {
+        TeamsCard::Adaptive => text_card(&text),
+        TeamsCard::MessageCard => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": truncate_to_limit(message, 80, "teams summary"),
+            "themeColor": THEME_COLOR,
+            "text": text,
+        }),
+    }
+}
+
+/// The status a legacy connector reports inside a 200 response
+///
+/// Connectors answer 200 even when Teams refused the card, with a body like
+/// "Webhook message delivery failed with error: Microsoft Teams endpoint
+/// returned HTTP error 413 with ContextId ...". A plain "1" means delivered.
+pub fn connector_error_status(body: &str) -> Option<StatusCode> {
+    if !body.contains("delivery failed") && !body.contains("returned HTTP error") {
+        return None;
+    }
+    let status = body
+        .split("HTTP error ")
+        .nth(1)
+        .and_then(|rest| rest.get(..3))
+        .and_then(|code| code.parse::<u16>().ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .filter(|status| status.is_client_error() || status.is_server_error());
+    Some(status.unwrap_or(StatusCode::BAD_GATEWAY))
+}
+
+impl TeamsNotifier {
+    /// Build the webhook request, with the endpoint's extra headers
+    pub fn build_request(&self, payload: &serde_json::Value) -> Result<Request> {
+        Ok(self
+            .client
+            .post(&self.cfg.webhook_url)
+            .headers(extra_header_map(&self.cfg.extra_headers)?)
+            .json(payload)
+            .build()?)
+    }
+
+    async fn post(&self, payload: &serde_json::Value) -> Result<()> {
+        let res = self.client.execute(self.build_request(payload)?).await?;
+        if !res.status().is_success() {
+            return Err(HttpStatusError::from_response("teams webhook", res).await.into());
+        }
+        let body = res.text().await.unwrap_or_default();
+        if let Some(status) = connector_error_status(&body) {
+            return Err(HttpStatusError { service: "teams webhook", status, body, retry_after: None }.into());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for TeamsNotifier {
+    fn kind(&self) -> &'static str {
+        "teams"
+    }
+
+    fn include_nsfw_marker(&self) -> bool {
+        self.cfg.include_nsfw_marker
+    }
+
+    async fn send(&self, subreddit: &str, title: &str, url: &str) -> Result<()> {
+        let message = Message::new(subreddit, title, url);
+        self.post(&build_payload(&self.cfg, &message)).await
+    }
+
+    async fn send_raw(&self, message: &str) -> Result<()> {
+        self.post(&build_raw_payload(&self.cfg, message)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(card: TeamsCard) -> TeamsConfig {
+        TeamsConfig {
+            webhook_url: "https://prod-1.westus.logic.azure.com/workflows/abc/triggers/manual/paths/invoke?sig=xyz".to_string(),
+            card,
+            include_nsfw_marker: true,
+            footer: None,
+            extra_headers: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_adaptive_card_structure() {
+        let message = Message::new("rust", "Tom &amp; Jerry [meta]", "https://www.reddit.com/r/rust/comments/abc/");
+        let payload = build_payload(&config(TeamsCard::Adaptive), &message);
+
+        assert_eq!(payload["type"], "message");
+        let attachments = payload["attachments"].as_array().unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0]["contentType"], "application/vnd.microsoft.card.adaptive");
+
+        let card = &attachments[0]["content"];
+        assert_eq!(card["type"], "AdaptiveCard");
+        assert_eq!(card["version"], "1.4");
+        let body = card["body"].as_array().unwrap();
+        assert_eq!(body.len(), 2);
+        assert_eq!(body[0]["text"], "New Reddit Post Alert (rust)");
+        // The title links to the post, with its brackets escaped
+        assert_eq!(body[1]["text"], r"[Tom & Jerry \[meta\]](https://www.reddit.com/r/rust/comments/abc/)");
+        assert!(body.iter().all(|block| block["type"] == "TextBlock" && block["wrap"] == true));
+        assert_eq!(
+            card["actions"],
+            json!([{"type": "Action.OpenUrl", "title": "Open post", "url": "https://www.reddit.com/r/rust/comments/abc/"}])
+        );
+    }
+
+    #[test]
+    fn test_message_card_structure() {
+        let cfg = TeamsConfig { footer: Some("via reddit-notifier".to_string()), ..config(TeamsCard::MessageCard) };
+        let payload = build_payload(&cfg, &Message::new("rust", "title", "https://example.com"));
+        assert_eq!(payload["@type"], "MessageCard");
+        assert_eq!(payload["summary"], "New Reddit Post Alert (rust)");
+        assert_eq!(payload["text"], "[title](https://example.com)\n\nvia reddit-notifier");
+        assert_eq!(payload["potentialAction"][0]["targets"][0]["uri"], "https://example.com");
+
+        let raw = build_raw_payload(&cfg, "Maintenance tonight");
+        assert_eq!(raw["text"], "Maintenance tonight");
+        assert!(raw.get("potentialAction").is_none());
+    }
+
+    #[test]
+    fn test_footer_block_and_truncation() {
+        let cfg = TeamsConfig { footer: Some("via reddit-notifier".to_string()), ..config(TeamsCard::Adaptive) };
+        let cap = EndpointKind::Teams.max_message_len();
+        let payload = build_payload(&cfg, &Message::new("rust", &"x".repeat(cap + 10), "https://example.com"));
+        let body = payload["attachments"][0]["content"]["body"].as_array().unwrap();
+        assert_eq!(body.len(), 3);
+        assert_eq!(body[2]["text"], "via reddit-notifier");
+        assert_eq!(body[2]["isSubtle"], true);
+
+        let title = body[1]["text"].as_str().unwrap();
+        let title_len = title.trim_start_matches('[').split(']').next().unwrap().chars().count();
+        assert_eq!(title_len + FOOTER_SEPARATOR.chars().count() + "via reddit-notifier".len(), cap);
+
+        // A blank footer is left out instead of sending an empty block
+        let cfg = TeamsConfig { footer: Some("  ".to_string()), ..config(TeamsCard::Adaptive) };
+        let payload = build_payload(&cfg, &Message::new("rust", "title", "https://example.com"));
+        assert_eq!(payload["attachments"][0]["content"]["body"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_connector_errors_in_success_responses() {
+        assert_eq!(connector_error_status("1"), None);
+        assert_eq!(connector_error_status(""), None);
+        assert_eq!(
+            connector_error_status(
+                "Webhook message delivery failed with error: Microsoft Teams endpoint returned HTTP error 429 with ContextId abc"
+            ),
+            Some(StatusCode::TOO_MANY_REQUESTS)
+        );
+        assert_eq!(connector_error_status("Webhook message delivery failed with error: Bad payload"), Some(StatusCode::BAD_GATEWAY));
+    }
+
+    #[test]
+    fn test_request_goes_to_webhook_with_extra_headers() {
+        let notifier = TeamsNotifier {
+            client: Client::new(),
+            cfg: TeamsConfig {
+                extra_headers: HashMap::from([("X-Proxy-Auth".to_string(), "Bearer abc".to_string())]),
+                ..config(TeamsCard::Adaptive)
+            },
+        };
+        let request = notifier.build_request(&build_raw_payload(&notifier.cfg, "hi")).unwrap();
+        assert!(request.url().as_str().ends_with("/paths/invoke?sig=xyz"));
+        assert_eq!(request.headers()["x-proxy-auth"], "Bearer abc");
+        assert_eq!(request.headers()["content-type"], "application/json");
+    }
+}
@@ -0,0 +1,154 @@
+//! Send the canned test notification to several endpoints at once
+
+use anyhow::Result;
+use tokio::task::JoinSet;
+
+use crate::models::database::EndpointRow;
+use super::{send_with_retry, Notifier, NotifierClientProfile, SendContext};
+
+/// Subreddit used by test notifications
+pub const TEST_SUBREDDIT: &str = "test";
+
+/// Title used by test notifications
+pub const TEST_TITLE: &str = "Test notification from reddit-notifier TUI";
+
+/// URL used by test notifications
+pub const TEST_URL: &str = "https://reddit.com";
+
+/// Result of sending the test notification to one endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestOutcome {
+    pub endpoint: EndpointRow,
+    pub result: Result<(), String>,
+}
+
+impl TestOutcome {
+    /// One-line report, e.g. `✓ discord (ID: 1)` or `✗ pushover (ID: 2): <error>`
+    pub fn summary_line(&self) -> String {
+        let target = format!("{} (ID: {})", self.endpoint.kind.as_str(), self.endpoint.id);
+        match &self.result {
+            Ok(()) => format!("✓ {}", target),
+            Err(e) => format!("✗ {}: {}", target, e),
+        }
+    }
+}
+
+/// Send the test notification to every endpoint concurrently
+///
+/// `build` creates the notifier for an endpoint (normally `build_notifier`);
+/// sends are retried as `profile` says. Outcomes are returned in the same
+/// order as `endpoints`.
+pub async fn send_test_to_all<F>(endpoints: Vec<EndpointRow>, profile: &NotifierClientProfile, build: F) -> Vec<TestOutcome>
+where
+    F: Fn(&EndpointRow) -> Result<Box<dyn Notifier>>,
+{
+    let (max_attempts, base_backoff) = (profile.max_attempts, profile.base_backoff);
+    let mut results: Vec<Option<Result<(), String>>> = vec![None; endpoints.len()];
+    let mut tasks = JoinSet::new();
+
+    for (i, endpoint) in endpoints.iter().enumerate() {
+        match build(endpoint) {
+            Ok(notifier) => {
+                tasks.spawn(async move {
+                    let ctx = SendContext {
+                        subreddit: TEST_SUBREDDIT,
+                        title: TEST_TITLE,
+                        url: TEST_URL,
+                        nsfw: false,
+                        priority: false,
+                    };
+                    let result = send_with_retry(notifier.as_ref(), &ctx, max_attempts, base_backoff)
+                        .await
+                        .map_err(|e| e.to_string());
+                    (i, result)
+                });
+            }
+            Err(e) => results[i] = Some(Err(format!("Failed to build notifier: {}", e))),
+        }
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((i, result)) => results[i] = Some(result),
+            Err(e) => tracing::error!("Test notification task failed: {}", e),
+        }
+    }
+
+    endpoints
+        .into_iter()
+        .zip(results)
+        .map(|(endpoint, result)| TestOutcome {
+            endpoint,
+            result: result.unwrap_or_else(|| Err("Send task did not complete".to_string())),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+
+    use crate::services::mock_database::MockDatabaseService;
+    use crate::services::DatabaseService;
+
+    /// Notifier that records which endpoint it was built for
+    struct RecordingNotifier {
+        endpoint_id: i64,
+        sent: Arc<Mutex<Vec<i64>>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        fn kind(&self) -> &'static str {
+            "recording"
+        }
+
+        async fn send(&self, subreddit: &str, _title: &str, _url: &str) -> Result<()> {
+            assert_eq!(subreddit, TEST_SUBREDDIT);
+            self.sent.lock().unwrap().push(self.endpoint_id);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscription_linked_endpoints_all_tested() {
+        let db = MockDatabaseService::with_test_data();
+        // Subscription 2 (programming) is linked to both test endpoints
+        let endpoints: Vec<EndpointRow> = db
+            .get_subscription_endpoints(2)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.active)
+            .collect();
+        assert_eq!(endpoints.len(), 2);
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let outcomes = send_test_to_all(endpoints, &NotifierClientProfile::default(), |ep| {
+            Ok(Box::new(RecordingNotifier {
+                endpoint_id: ep.id,
+                sent: sent.clone(),
+            }) as Box<dyn Notifier>)
+        })
+        .await;
+
+        let mut tested = sent.lock().unwrap().clone();
+        tested.sort();
+        assert_eq!(tested, vec![1, 2]);
+        assert_eq!(outcomes.iter().map(|o| o.endpoint.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(outcomes.iter().all(|o| o.result.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn test_build_failure_reported_per_endpoint() {
+        let db = MockDatabaseService::with_test_data();
+        let endpoints = db.list_endpoints().await.unwrap();
+
+        let outcomes = send_test_to_all(endpoints, &NotifierClientProfile::default(), |_| anyhow::bail!("bad config")).await;
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].summary_line(), "✗ discord (ID: 1): Failed to build notifier: bad config");
+    }
+}
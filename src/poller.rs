@@ -1,204 +1,3188 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use reqwest::Client;
-use std::collections::HashSet;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tracing::{info, warn, error};
-use chrono::{Utc, TimeDelta};
+use std::time::{Duration, Instant};
+use futures_util::stream::{self, StreamExt};
+use tracing::{debug, info, warn, error};
+use chrono::{DateTime, Utc, TimeDelta};
 
-use crate::models::{database::EndpointRow, reddit_api::RedditListing};
-use crate::rate_limiter::RateLimiter;
-use crate::services::DatabaseService;
+use crate::active_hours::endpoint_active_hours;
+use crate::batching::{BatchedPost, Batcher};
+use crate::models::config::{AppConfig, NotificationOrder, DEFAULT_MAX_NOTIFICATIONS_PER_SUBREDDIT, MAX_LISTING_LIMIT};
+use crate::models::{database::{DeliveryStatus, EndpointRow, TIMESTAMP_FORMAT}, reddit_api::{RedditListing, RedditPost}};
+use crate::notifiers::{jitter, send_with_retry, NotifierClientProfile, SendContext};
+use crate::rate_limiter::{sync_burst, RateLimiter, SYNC_BOOST_WINDOW};
+use crate::rising::{is_rising, velocity, MetricSample, PostMetrics, Velocity, RISING_WINDOW_HOURS};
+use crate::score_alerts::{crossed_threshold, score_bucket};
+use crate::services::{error::DbError, DatabaseService};
 
-/// Combined subreddit poller - polls multiple subreddits in a single API call
+/// Tunable settings for the combined poller
+#[derive(Debug, Clone)]
+pub struct PollerConfig {
+    /// Number of posts requested per listing call (1..=100)
+    ///
+    /// For combined multi-subreddit URLs this limit is shared across every
+    /// subreddit in the batch, so a busy batch with a low limit can miss posts.
+    pub listing_limit: u32,
+    /// Nominal spacing between batch requests (the rate limiter's refill interval)
+    pub batch_spacing: Duration,
+    /// Random ±% applied to `batch_spacing` (0 = no jitter)
+    pub jitter_pct: u32,
+    /// Base URL for listing requests (overridden in tests)
+    pub reddit_base: String,
+    /// Mute a subreddit's subscriptions after this many failed cycles in a row (0 = off)
+    pub auto_mute_after: u32,
+    /// Pause between checks when there is nothing to poll, or after a database error
+    pub idle_sleep: Duration,
+    /// Global delivery switch, refreshed from the settings table every cycle
+    ///
+    /// While false, new posts are still recorded (so nothing is re-sent on
+    /// resume) but no notifier is invoked.
+    pub notifications_enabled: Arc<AtomicBool>,
+    /// Delete delivery history older than this many days (0 = keep forever)
+    pub delivery_retention_days: u32,
+    /// Most posts notified per subreddit in one cycle (0 = no cap)
+    ///
+    /// Posts over the cap are still recorded, so they don't fire next cycle.
+    pub max_notifications_per_subreddit_per_cycle: u32,
+    /// Order in which each batch's notifications are sent (posts are recorded in listing order)
+    pub notification_order: NotificationOrder,
+    /// Send nothing in the first poll cycle after startup; its posts are only recorded
+    pub startup_suppress: bool,
+    /// Most posts notified across all subreddits in the first poll cycle after startup (0 = no cap)
+    ///
+    /// Like the per-subreddit cap, posts over it are still recorded.
+    pub startup_max_notifications: u32,
+    /// Time-based batching of notifications (`None` = send each one right away)
+    ///
+    /// Shared with the flusher task spawned by `poll_combined_subreddits_loop`.
+    pub batcher: Option<Batcher>,
+    /// Client settings and retry policy for notifications (shared with the TUI's test sends)
+    pub notifier_profile: NotifierClientProfile,
+    /// Fetched batches recorded at once (1 = one after another)
+    ///
+    /// Each worker holds at most one database connection at a time, so this
+    /// should stay below the pool size (see `workers_within_pool`).
+    pub batch_workers: usize,
+    /// Extra requests granted for `SYNC_BOOST_WINDOW` when a manual sync is requested (0 = none)
+    pub sync_burst: u32,
+}
+
+/// Reddit's public base URL
+pub const REDDIT_BASE_URL: &str = "https://www.reddit.com";
+
+impl Default for PollerConfig {
+    fn default() -> Self {
+        Self {
+            listing_limit: MAX_LISTING_LIMIT,
+            batch_spacing: Duration::from_secs(15),
+            jitter_pct: 0,
+            reddit_base: REDDIT_BASE_URL.to_string(),
+            auto_mute_after: 0,
+            idle_sleep: Duration::from_secs(30),
+            notifications_enabled: Arc::new(AtomicBool::new(true)),
+            delivery_retention_days: 30,
+            max_notifications_per_subreddit_per_cycle: DEFAULT_MAX_NOTIFICATIONS_PER_SUBREDDIT,
+            notification_order: NotificationOrder::default(),
+            startup_suppress: false,
+            startup_max_notifications: 0,
+            batcher: None,
+            notifier_profile: NotifierClientProfile::default(),
+            batch_workers: 1,
+            sync_burst: 0,
+        }
+    }
+}
+
+impl PollerConfig {
+    /// Build poller settings from the application configuration
+    pub fn from_app_config(cfg: &AppConfig) -> Self {
+        Self {
+            listing_limit: cfg.listing_limit,
+            batch_spacing: Duration::from_secs(60) / cfg.rate_limit_per_minute.max(1),
+            jitter_pct: cfg.poll_jitter_pct,
+            reddit_base: REDDIT_BASE_URL.to_string(),
+            auto_mute_after: cfg.auto_mute_after,
+            idle_sleep: Duration::from_secs(cfg.idle_sleep_secs.max(1)),
+            notifications_enabled: Arc::new(AtomicBool::new(true)),
+            delivery_retention_days: cfg.delivery_retention_days,
+            max_notifications_per_subreddit_per_cycle: cfg.max_notifications_per_subreddit_per_cycle,
+            notification_order: cfg.notification_order,
+            startup_suppress: cfg.startup_suppress,
+            startup_max_notifications: cfg.startup_max_notifications,
+            batcher: (cfg.notify_batch_window_secs > 0)
+                .then(|| Batcher::new(Duration::from_secs(cfg.notify_batch_window_secs), cfg.notify_batch_max_posts)),
+            notifier_profile: NotifierClientProfile::from_app_config(cfg),
+            batch_workers: cfg.poll_workers,
+            sync_burst: sync_burst(cfg.rate_limit_per_minute),
+        }
+    }
+}
+
+/// Cap the batch worker count so the poller leaves a pool connection free
 ///
-/// This is more efficient than spawning one poller per subreddit. Reddit allows
-/// combining up to 100 subreddits in a single URL using the format:
-/// `/r/sub1+sub2+sub3/new.json`
+/// The batch flusher, heartbeat and settings reads share the poller's pool;
+/// with every connection held by a worker they would wait out the pool's
+/// acquire timeout instead.
+pub fn workers_within_pool(requested: usize, max_connections: u32) -> usize {
+    let limit = (max_connections as usize).saturating_sub(1).max(1);
+    if requested > limit {
+        warn!(
+            "POLL_WORKERS is set to {}, but DB_MAX_CONNECTIONS={} leaves room for {}. Using {}.",
+            requested, max_connections, limit, limit
+        );
+    }
+    requested.clamp(1, limit)
+}
+
+/// How often the poller prunes delivery history
+const DELIVERY_CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Sync the in-memory pause switch with the persisted setting
 ///
-/// Benefits:
-/// - Fewer API calls to Reddit (better for rate limiting)
-/// - More efficient resource usage
-/// - Easier to implement global rate limiting
+/// Logs when delivery is paused or resumed. A failed read keeps the current value.
+async fn refresh_notifications_enabled<D: DatabaseService>(db: &D, flag: &AtomicBool) {
+    match db.notifications_enabled().await {
+        Ok(enabled) => {
+            let was = flag.swap(enabled, Ordering::Relaxed);
+            if was && !enabled {
+                warn!("Notifications paused - new posts are recorded but not delivered");
+            } else if !was && enabled {
+                info!("Notifications resumed");
+            }
+        }
+        Err(e) => warn!("Failed to read notification pause setting: {} - keeping current value", e),
+    }
+}
+
+/// How long to sleep instead of polling, given the number of active subreddits
 ///
-/// # Arguments
-/// * `db` - Database service
-/// * `client` - HTTP client for making Reddit API calls
-/// * `subreddits` - List of subreddit names to poll (will be automatically batched)
-/// * `rate_limiter` - Rate limiter to respect Reddit's API limits
+/// Returns `None` when there is something to poll.
+pub fn idle_delay(active_subreddits: usize, config: &PollerConfig) -> Option<Duration> {
+    (active_subreddits == 0).then_some(config.idle_sleep)
+}
+
+/// Randomize `base` by up to ±`pct`% (uniformly, so the mean stays at `base`)
+pub fn jittered_delay(base: Duration, pct: u32) -> Duration {
+    let band = base * pct.min(100) / 100;
+    base - band + jitter(band * 2)
+}
+
+/// Tracks which (post, endpoint) pairs have already been notified in a poll cycle
 ///
-/// # Polling Behavior
-/// The poller runs continuously, making API calls as fast as the rate limiter allows.
-/// Configure the rate limiter (via REDDIT_RATE_LIMIT_PER_MINUTE) to control polling frequency.
-/// Default: 20 requests/minute. Reddit's limit is approximately 60 requests/minute.
-pub async fn poll_combined_subreddits_loop<D: DatabaseService>(
-    db: Arc<D>,
-    client: Client,
-    subreddits: Vec<String>,
-    rate_limiter: RateLimiter,
-) -> Result<()> {
-    if subreddits.is_empty() {
-        info!("No subreddits to poll");
-        return Ok(());
+/// An endpoint can be reachable through several subscriptions for the same
+/// subreddit (duplicate rows, overlapping multireddits), and the same post can
+/// show up in more than one batch. The guard makes sure each endpoint is sent a
+/// given post at most once per cycle, regardless of which subscription matched.
+#[derive(Debug, Default)]
+pub struct DeliveryGuard {
+    sent: HashSet<(String, i64)>,
+}
+
+impl DeliveryGuard {
+    /// Create an empty guard (one per poll cycle)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claim a (post, endpoint) pair
+    ///
+    /// Returns `true` the first time the pair is seen, `false` afterwards.
+    pub fn claim(&mut self, post_id: &str, endpoint_id: i64) -> bool {
+        self.sent.insert((post_id.to_string(), endpoint_id))
+    }
+
+    /// Filter endpoints down to those that haven't received this post yet
+    pub fn unclaimed<'a>(&mut self, post_id: &str, endpoints: &'a [EndpointRow]) -> Vec<&'a EndpointRow> {
+        endpoints
+            .iter()
+            .filter(|e| self.claim(post_id, e.id))
+            .collect()
+    }
+}
+
+/// A post that passed every check in a batch and is waiting to be sent
+struct PendingNotification<'a> {
+    post: RedditPost,
+    url: String,
+    title: String,
+    is_new: bool,
+    subreddit_key: String,
+    endpoints: &'a [EndpointRow],
+}
+
+/// Sort a batch's notifications into sending order
+///
+/// The sort is stable, so posts with equal timestamps keep the listing order.
+fn sort_for_dispatch(pending: &mut [PendingNotification<'_>], order: NotificationOrder) {
+    match order {
+        NotificationOrder::OldestFirst => pending.sort_by_key(|p| p.post.created_utc),
+        NotificationOrder::Listing => {}
+    }
+}
+
+/// Rebuild the database pool after this many cycles in a row fail with connection errors
+pub const RECONNECT_AFTER_FAILURES: u32 = 3;
+
+/// Whether `err` means the database couldn't be reached (as opposed to a bad query or missing row)
+pub fn is_connection_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(|cause| matches!(cause.downcast_ref::<DbError>(), Some(DbError::Connection(_))))
+}
+
+/// Caps how many posts each subreddit can notify about in one poll cycle
+///
+/// A safeguard against floods (Reddit returning a burst of posts, or an
+/// overly broad subscription), separate from per-endpoint rate limits. Posts
+/// over the cap are counted per subreddit and reported once at the end of
+/// the cycle.
+#[derive(Debug, Default)]
+pub struct NotificationCap {
+    limit: u32,
+    notified: HashMap<String, u32>,
+    suppressed: BTreeMap<String, u32>,
+}
+
+impl NotificationCap {
+    /// Create a cap allowing `limit` posts per subreddit (0 = unlimited), one per poll cycle
+    pub fn new(limit: u32) -> Self {
+        Self { limit, ..Self::default() }
+    }
+
+    /// Count a post about to be notified for `subreddit`
+    ///
+    /// Returns `false` (and counts the post as suppressed) once the subreddit
+    /// has reached the cap.
+    pub fn allow(&mut self, subreddit: &str) -> bool {
+        let notified = self.notified.entry(subreddit.to_string()).or_default();
+        if self.limit == 0 || *notified < self.limit {
+            *notified += 1;
+            return true;
+        }
+        *self.suppressed.entry(subreddit.to_string()).or_default() += 1;
+        false
+    }
+
+    /// One warning per subreddit that went over the cap, in subreddit order
+    pub fn suppression_warnings(&self) -> Vec<String> {
+        self.suppressed
+            .iter()
+            .map(|(subreddit, count)| {
+                format!(
+                    "Suppressed {} further post(s) from r/{} this cycle (limit: {} per subreddit)",
+                    count, subreddit, self.limit
+                )
+            })
+            .collect()
+    }
+}
+
+/// Limits the first poll cycle after startup
+///
+/// After downtime every post from the last 24 hours is new, so the first cycle
+/// can flood the endpoints. Unlike `NotificationCap` the limit is shared by all
+/// subreddits. Posts over it are recorded like any other, so they never fire.
+#[derive(Debug, Default)]
+pub struct StartupThrottle {
+    /// Notifications left this cycle (None = unlimited)
+    remaining: Option<u32>,
+    suppressed: u32,
+}
+
+impl StartupThrottle {
+    /// The throttle for one cycle; unlimited unless it's the startup cycle and a limit is configured
+    pub fn for_cycle(config: &PollerConfig, startup: bool) -> Self {
+        let remaining = match (startup, config.startup_suppress, config.startup_max_notifications) {
+            (false, _, _) | (true, false, 0) => None,
+            (true, true, _) => Some(0),
+            (true, false, max) => Some(max),
+        };
+        Self { remaining, suppressed: 0 }
+    }
+
+    /// Count a post about to be notified; `false` once the startup limit is used up
+    pub fn allow(&mut self) -> bool {
+        match &mut self.remaining {
+            None => true,
+            Some(0) => {
+                self.suppressed += 1;
+                false
+            }
+            Some(remaining) => {
+                *remaining -= 1;
+                true
+            }
+        }
+    }
+
+    /// Posts held back this cycle
+    pub fn suppressed(&self) -> u32 {
+        self.suppressed
+    }
+}
+
+/// Posts created further than this from the local clock are skipped
+pub const POST_WINDOW_HOURS: i64 = 24;
+
+/// Host clock differences from Reddit's beyond this many seconds are reported
+pub const CLOCK_SKEW_WARN_SECS: i64 = 300;
+
+/// How far the local clock is ahead of a server's HTTP `Date` header (negative = behind)
+///
+/// Returns `None` if the header isn't a valid HTTP date.
+pub fn clock_skew(local_now: DateTime<Utc>, date_header: &str) -> Option<TimeDelta> {
+    let server_now = DateTime::parse_from_rfc2822(date_header).ok()?.with_timezone(&Utc);
+    Some(local_now - server_now)
+}
+
+/// Whether a clock difference is large enough to break the post window
+///
+/// HTTP dates have one-second resolution and responses take time to arrive,
+/// so only differences over `CLOCK_SKEW_WARN_SECS` count.
+pub fn is_implausible_skew(skew: TimeDelta) -> bool {
+    skew.num_seconds().abs() > CLOCK_SKEW_WARN_SECS
+}
+
+/// Describe a post's age for logs, e.g. `26h 5m old` or `3h 0m in the future`
+pub fn describe_post_age(age: TimeDelta) -> String {
+    let minutes = age.num_minutes().abs();
+    let amount = format!("{}h {}m", minutes / 60, minutes % 60);
+    if age < TimeDelta::zero() {
+        format!("{} in the future", amount)
+    } else {
+        format!("{} old", amount)
+    }
+}
+
+/// Compare the host clock with the `Date` header of a Reddit response
+///
+/// A wrong host clock makes every new post fall outside the post window, so
+/// notifications silently stop. This runs once at startup and warns when the
+/// clocks disagree; failures to reach Reddit are only logged at debug.
+pub async fn check_clock_skew(client: &Client, reddit_base: &str) -> Option<TimeDelta> {
+    let resp = match client.head(reddit_base).send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            debug!("Clock check skipped, Reddit unreachable: {}", e);
+            return None;
+        }
+    };
+    let date = resp.headers().get(reqwest::header::DATE)?.to_str().ok()?;
+    let skew = clock_skew(Utc::now(), date)?;
+    if is_implausible_skew(skew) {
+        warn!(
+            "Host clock is {} Reddit's by {}s - posts may be skipped as outside the {}h window; check NTP",
+            if skew > TimeDelta::zero() { "ahead of" } else { "behind" },
+            skew.num_seconds().abs(),
+            POST_WINDOW_HOURS
+        );
+    } else {
+        debug!("Host clock within {}s of Reddit's", skew.num_seconds().abs());
+    }
+    Some(skew)
+}
+
+/// Whether a post should be dropped because it is stickied
+///
+/// Stickied posts (rules, megathreads) are skipped unless at least one
+/// subscription for the post's subreddit has opted in to them. `allow_stickied`
+/// holds lowercased names, as returned by the database.
+pub fn is_skipped_sticky(post: &RedditPost, allow_stickied: &HashSet<String>) -> bool {
+    post.stickied && !allow_stickied.contains(&post.subreddit.to_lowercase())
+}
+
+/// Why a fetched post wasn't notified
+///
+/// Each skip is logged at `debug` with the post ID, subreddit and reason, and
+/// counted in `CycleSummary::skipped`, so `RUST_LOG=debug` answers "why didn't
+/// I get notified?".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SkipReason {
+    /// Stickied, and no subscription to the subreddit allows stickied posts
+    Stickied,
+    /// Created outside the ±24h window
+    OutsideWindow,
+    /// Notified (or recorded) in an earlier cycle
+    AlreadySeen,
+    /// Notifications are paused; the post is recorded but not sent
+    Paused,
+    /// No active endpoint is linked to the subreddit
+    NoEndpoints,
+}
+
+impl SkipReason {
+    /// Short name used in logs and the cycle summary
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stickied => "stickied",
+            Self::OutsideWindow => "outside window",
+            Self::AlreadySeen => "already seen",
+            Self::Paused => "paused",
+            Self::NoEndpoints => "no endpoints",
+        }
+    }
+}
+
+/// Counters accumulated over one full poll cycle
+///
+/// The poller logs a single summary line per cycle at `info` level so that
+/// `RUST_LOG=info` gives a readable heartbeat; per-post details are at `debug`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CycleSummary {
+    /// Batches successfully fetched and parsed
+    pub batches_polled: usize,
+    /// Posts returned by Reddit across all batches
+    pub posts_fetched: usize,
+    /// Posts inside the ±24h window
+    pub posts_in_window: usize,
+    /// Posts not seen before this cycle
+    pub new_posts: usize,
+    /// Notifications delivered successfully
+    pub notifications_sent: usize,
+    /// Failed fetches, database errors and failed notifications
+    pub failures: usize,
+    /// Posts recorded but not notified because their subreddit hit the per-cycle cap
+    pub posts_suppressed: usize,
+    /// Posts not notified, by reason
+    pub skipped: BTreeMap<SkipReason, usize>,
+    /// Deliveries not made because the endpoint was outside its active hours
+    pub deliveries_off_hours: usize,
+    /// Deliveries queued for a later batched message
+    pub deliveries_batched: usize,
+    /// Wall-clock time of the cycle
+    pub duration: Duration,
+}
+
+impl CycleSummary {
+    /// Create an empty summary (one per poll cycle)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a batch that was fetched and parsed successfully
+    pub fn record_batch(&mut self, posts: usize) {
+        self.batches_polled += 1;
+        self.posts_fetched += posts;
+    }
+
+    /// Record a post that falls inside the ±24h window
+    pub fn record_in_window(&mut self) {
+        self.posts_in_window += 1;
+    }
+
+    /// Record a post that hadn't been seen before
+    pub fn record_new_post(&mut self) {
+        self.new_posts += 1;
+    }
+
+    /// Record a delivered notification
+    pub fn record_sent(&mut self) {
+        self.notifications_sent += 1;
+    }
+
+    /// Record a post held back by the per-subreddit cap
+    pub fn record_suppressed(&mut self) {
+        self.posts_suppressed += 1;
+    }
+
+    /// Record a delivery skipped because the endpoint was outside its active hours
+    pub fn record_off_hours(&mut self) {
+        self.deliveries_off_hours += 1;
+    }
+
+    /// Record a delivery queued for a batched message
+    pub fn record_batched(&mut self) {
+        self.deliveries_batched += 1;
+    }
+
+    /// Record a post that won't be notified, and log why at `debug`
+    pub fn record_skip(&mut self, reason: SkipReason, post_id: &str, subreddit: &str, detail: &str) {
+        *self.skipped.entry(reason).or_default() += 1;
+        debug!(post_id, subreddit, reason = reason.as_str(), "Skipping post {} from r/{} - {}", post_id, subreddit, detail);
+    }
+
+    /// Record a failure (fetch, parse, database or notification)
+    pub fn record_failure(&mut self) {
+        self.failures += 1;
+    }
+
+    /// Add the counts from `other` (e.g. one batch's) to this summary; the duration is left as is
+    pub fn absorb(&mut self, other: &CycleSummary) {
+        self.batches_polled += other.batches_polled;
+        self.posts_fetched += other.posts_fetched;
+        self.posts_in_window += other.posts_in_window;
+        self.new_posts += other.new_posts;
+        self.notifications_sent += other.notifications_sent;
+        self.failures += other.failures;
+        self.posts_suppressed += other.posts_suppressed;
+        for (reason, count) in &other.skipped {
+            *self.skipped.entry(*reason).or_default() += count;
+        }
+        self.deliveries_off_hours += other.deliveries_off_hours;
+        self.deliveries_batched += other.deliveries_batched;
+    }
+
+    /// Stamp the cycle duration, measured from `started`
+    pub fn finish(&mut self, started: Instant) {
+        self.duration = started.elapsed();
+    }
+}
+
+impl fmt::Display for CycleSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Poll cycle complete: {} batch(es), {} post(s) fetched, {} in window, {} new, {} notification(s) sent, {} failure(s) in {:.2}s",
+            self.batches_polled,
+            self.posts_fetched,
+            self.posts_in_window,
+            self.new_posts,
+            self.notifications_sent,
+            self.failures,
+            self.duration.as_secs_f64()
+        )?;
+
+        let mut skipped: Vec<String> = self
+            .skipped
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(reason, count)| format!("{} {}", count, reason.as_str()))
+            .collect();
+        if self.posts_suppressed > 0 {
+            skipped.push(format!("{} over cap", self.posts_suppressed));
+        }
+        if self.deliveries_off_hours > 0 {
+            skipped.push(format!("{} delivery(s) outside active hours", self.deliveries_off_hours));
+        }
+        if !skipped.is_empty() {
+            write!(f, "; skipped: {}", skipped.join(", "))?;
+        }
+        if self.deliveries_batched > 0 {
+            write!(f, "; {} delivery(s) batched", self.deliveries_batched)?;
+        }
+        Ok(())
     }
+}
+
+/// `meta` key: when the poller last finished a cycle, with or without failures
+pub const META_LAST_POLL_AT: &str = "last_poll_at";
 
-    // Reddit allows up to 100 subreddits in a multi-subreddit URL
-    const MAX_SUBREDDITS_PER_BATCH: usize = 100;
+/// `meta` key: when the poller last finished a cycle that fetched posts without any failure
+pub const META_LAST_SUCCESS_AT: &str = "last_success_at";
 
-    // Split subreddits into batches if there are more than 100
-    let batches: Vec<Vec<String>> = subreddits
-        .chunks(MAX_SUBREDDITS_PER_BATCH)
-        .map(|chunk| chunk.to_vec())
-        .collect();
+/// `meta` key: when a manual sync was last requested (`reddit-notifier sync`, or `s` on the TUI main menu)
+pub const META_SYNC_REQUESTED_AT: &str = "sync_requested_at";
 
+/// Ask the running poller for a one-time catch-up at a boosted rate
+///
+/// The poller picks the request up at the start of its next cycle.
+pub async fn request_sync<D: DatabaseService>(db: &D) -> Result<()> {
+    db.set_meta(META_SYNC_REQUESTED_AT, &Utc::now().format(TIMESTAMP_FORMAT).to_string()).await
+}
+
+/// Boost the rate limiter if a sync was requested since the last one handled
+///
+/// Requests made before the poller started are ignored.
+async fn handle_sync_request<D: DatabaseService>(
+    db: &D,
+    rate_limiter: &RateLimiter,
+    config: &PollerConfig,
+    state: &mut PollerState,
+) {
+    let requested = match meta_time(db, META_SYNC_REQUESTED_AT).await {
+        Ok(Some(requested)) if requested > state.sync_handled => requested,
+        Ok(_) => return,
+        Err(e) => {
+            warn!("Failed to read sync request: {}", e);
+            return;
+        }
+    };
+    state.sync_handled = requested;
+    if config.sync_burst == 0 {
+        info!("Sync requested, but the rate limit is already at Reddit's maximum - polling at the configured rate");
+        return;
+    }
+    rate_limiter.boost(config.sync_burst, SYNC_BOOST_WINDOW).await;
     info!(
-        target: "reddit_notifier",
-        "Spawned combined poller for {} subreddit(s) across {} batch(es)",
-        subreddits.len(),
-        batches.len()
+        burst = config.sync_burst,
+        "Sync requested - allowing {} extra request(s) for the next {}s",
+        config.sync_burst,
+        SYNC_BOOST_WINDOW.as_secs()
     );
+}
 
-    let reddit_base = "https://www.reddit.com";
+/// Read a timestamp the poller wrote to `meta` (`None` if it never did)
+pub async fn meta_time<D: DatabaseService>(db: &D, key: &str) -> Result<Option<DateTime<Utc>>> {
+    match db.get_meta(key).await? {
+        Some(ts) => Ok(Some(
+            DateTime::parse_from_rfc3339(&ts)
+                .with_context(|| format!("meta value {} is not a timestamp: {}", key, ts))?
+                .with_timezone(&Utc),
+        )),
+        None => Ok(None),
+    }
+}
 
-    loop {
-        // Fetch the subreddit-to-endpoints mapping once per poll cycle
-        // This is more efficient than querying for each post
-        let mappings = match db.all_subreddit_endpoint_mappings().await {
-            Ok(m) => m,
-            Err(e) => {
-                error!("Failed to fetch subreddit-endpoint mappings: {} - will retry", e);
-                continue;
-            }
+/// How long after the last successful poll the poller is considered stale
+pub const HEARTBEAT_STALE_AFTER: Duration = Duration::from_secs(300);
+
+/// Poller health as seen from its heartbeat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollerHealth {
+    /// Polled successfully within the staleness window
+    Healthy { since_last_poll: Duration },
+    /// Has polled before, but not recently
+    Stale { since_last_poll: Duration },
+    /// No heartbeat recorded (poller never ran, or it couldn't be read)
+    Unknown,
+}
+
+impl PollerHealth {
+    /// Classify a heartbeat timestamp relative to `now`
+    pub fn classify(last_poll: Option<DateTime<Utc>>, now: DateTime<Utc>, stale_after: Duration) -> Self {
+        let Some(last_poll) = last_poll else {
+            return Self::Unknown;
         };
+        // Clock skew between processes can put the heartbeat slightly in the future
+        let since_last_poll = (now - last_poll).to_std().unwrap_or(Duration::ZERO);
+        if since_last_poll <= stale_after {
+            Self::Healthy { since_last_poll }
+        } else {
+            Self::Stale { since_last_poll }
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy { .. })
+    }
+}
+
+impl fmt::Display for PollerHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Healthy { since_last_poll } => {
+                write!(f, "Poller: healthy (last poll {} ago)", format_age(*since_last_poll))
+            }
+            Self::Stale { since_last_poll } => {
+                write!(f, "Poller: stale (last poll {} ago)", format_age(*since_last_poll))
+            }
+            Self::Unknown => write!(f, "Poller: unknown (no heartbeat)"),
+        }
+    }
+}
 
-        // Poll each batch
-        for batch in &batches {
-            // Wait for rate limiter before making the API call
-            rate_limiter.acquire().await;
+/// Compact age, e.g. `12s`, `4m`, `3h`, `2d` (callers add " ago" where it reads better)
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
 
-            // Build the combined subreddit URL (e.g., /r/sub1+sub2+sub3/new.json)
-            let combined_subreddit = batch.join("+");
-            let json_url = format!("{}/r/{}/new.json?limit=100", reddit_base, combined_subreddit);
+/// Why Reddit refused a listing with 403 Forbidden (from the `reason` field of the body)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForbiddenReason {
+    /// Private subreddit the account isn't an approved member of
+    Private,
+    /// Quarantined subreddit that requires an opt-in
+    Quarantined,
+    /// The subreddit (or the account) is banned
+    Banned,
+    /// Any other or missing reason
+    Other(String),
+}
 
-            match client.get(&json_url).send().await {
-                Ok(resp) => {
-                    if !resp.status().is_success() {
-                        warn!("Reddit GET {} -> {}", json_url, resp.status());
-                        continue;
-                    }
+impl ForbiddenReason {
+    /// Parse the reason from a 403 response body
+    pub fn from_body(body: &str) -> Self {
+        let reason = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| v.get("reason").and_then(|r| r.as_str()).map(str::to_string))
+            .unwrap_or_default();
+        match reason.as_str() {
+            "private" => Self::Private,
+            "quarantined" => Self::Quarantined,
+            "banned" => Self::Banned,
+            "" => Self::Other("no reason given".to_string()),
+            _ => Self::Other(reason),
+        }
+    }
+}
 
-                    let listing: RedditListing = match resp.json().await {
-                        Ok(v) => v,
-                        Err(e) => {
-                            warn!("Failed to parse Reddit JSON for combined URL: {}", e);
-                            continue;
-                        }
-                    };
-
-                    info!(
-                        "Fetched {} posts from {} subreddit(s)",
-                        listing.data.children.len(),
-                        batch.len()
-                    );
-
-                    // Process each post
-                    for child in listing.data.children {
-                        let post = child.data;
-
-                        // The post.subreddit field tells us which subreddit this post came from
-                        // This is crucial for the combined poller approach
-                        let subreddit = &post.subreddit;
-
-                        // Check if post is within ±24 hours
-                        // This was added because Reddit's API would randomly return old posts
-                        let now = Utc::now();
-                        let time_diff = now.signed_duration_since(post.created_utc);
-                        let is_within_24h = time_diff.abs() <= TimeDelta::hours(24);
-                        if !is_within_24h {
-                            info!(
-                                "Skipping post {} from r/{} - outside 24h window (posted: {})",
-                                post.id, subreddit, post.created_utc
-                            );
-                            continue;
-                        }
-
-                        // Check if we've already notified about this post
-                        let is_new = match db.record_if_new(subreddit, &post.id).await {
-                            Ok(new) => new,
-                            Err(e) => {
-                                error!(
-                                    "Failed to record post {} for r/{}: {} - skipping this post",
-                                    post.id, subreddit, e
-                                );
-                                continue;
-                            }
-                        };
-                        if !is_new {
-                            continue; // Already seen this post
-                        }
-
-                        // Get endpoints for this specific subreddit from our mapping
-                        let endpoints = match mappings.get(subreddit) {
-                            Some(eps) => eps,
-                            None => {
-                                // No endpoints subscribed to this subreddit
-                                // This can happen if mappings changed between poll cycles
-                                info!("No endpoints for r/{}, skipping post {}", subreddit, post.id);
-                                continue;
-                            }
-                        };
-
-                        // Deduplicate endpoints (same endpoint might be subscribed multiple times)
-                        let mut unique_endpoint_ids = HashSet::new();
-                        let unique_endpoints: Vec<&EndpointRow> = endpoints
-                            .iter()
-                            .filter(|e| unique_endpoint_ids.insert(e.id))
-                            .collect();
-
-                        // Build the post URL
-                        let url = post
-                            .permalink
-                            .as_ref()
-                            .map(|p| format!("{}{}", reddit_base, p))
-                            .or(post.url.clone())
-                            .unwrap_or_else(|| {
-                                format!("{}/r/{}/comments/{}", reddit_base, subreddit, post.id)
-                            });
-
-                        info!(
-                            "New post in r/{}: {} -> notifying {} endpoint(s)",
-                            subreddit,
-                            post.title,
-                            unique_endpoints.len()
-                        );
+impl fmt::Display for ForbiddenReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Private => write!(f, "private subreddit (not an approved member)"),
+            Self::Quarantined => write!(f, "quarantined subreddit (opt-in required)"),
+            Self::Banned => write!(f, "banned"),
+            Self::Other(reason) => write!(f, "forbidden ({})", reason),
+        }
+    }
+}
+
+/// Failure fetching a listing
+#[derive(Debug)]
+pub enum FetchError {
+    /// 403 Forbidden, with Reddit's reason
+    Forbidden(ForbiddenReason),
+    /// Any other non-success status
+    Status(reqwest::StatusCode),
+    /// Transport error (connect, timeout, ...)
+    Http(reqwest::Error),
+    /// The body wasn't a listing
+    Parse(reqwest::Error),
+    /// Redirected away from the listing (e.g. to a search for a subreddit that doesn't exist)
+    Redirected(String),
+}
+
+impl FetchError {
+    /// Whether the error is about the subreddits requested (private, banned,
+    /// deleted, ...) rather than Reddit rate limiting, being down or unreachable
+    pub fn is_subreddit_error(&self) -> bool {
+        match self {
+            Self::Forbidden(_) | Self::Redirected(_) => true,
+            Self::Status(status) => status.is_client_error() && *status != reqwest::StatusCode::TOO_MANY_REQUESTS,
+            Self::Http(_) | Self::Parse(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Forbidden(reason) => write!(f, "403 Forbidden: {}", reason),
+            Self::Status(status) => write!(f, "{}", status),
+            Self::Http(e) => write!(f, "HTTP error: {}", e),
+            Self::Parse(e) => write!(f, "failed to parse listing: {}", e),
+            Self::Redirected(url) => write!(f, "redirected to {}", url),
+        }
+    }
+}
+
+/// GET a listing URL and return its posts
+pub async fn fetch_listing(client: &Client, url: &str) -> Result<Vec<RedditPost>, FetchError> {
+    fetch_listing_items(client, url).await
+}
+
+/// GET a listing URL and return its items (posts, comments, ...)
+///
+/// Redirects to another host (`reddit.com` -> `www.reddit.com`) are followed;
+/// one that lands on a different path is an error rather than a listing of
+/// something else.
+pub async fn fetch_listing_items<T: DeserializeOwned>(client: &Client, url: &str) -> Result<Vec<T>, FetchError> {
+    let resp = client.get(url).send().await.map_err(FetchError::Http)?;
+    let requested_path = reqwest::Url::parse(url).map(|u| u.path().to_string()).unwrap_or_default();
+    if resp.url().path() != requested_path {
+        return Err(FetchError::Redirected(resp.url().to_string()));
+    }
+    let status = resp.status();
+    if status == reqwest::StatusCode::FORBIDDEN {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(FetchError::Forbidden(ForbiddenReason::from_body(&body)));
+    }
+    if !status.is_success() {
+        return Err(FetchError::Status(status));
+    }
+
+    let listing: RedditListing<T> = resp.json().await.map_err(FetchError::Parse)?;
+    Ok(listing.data.children.into_iter().map(|c| c.data).collect())
+}
+
+/// Posts fetched for a batch, plus the subreddits that failed
+#[derive(Debug, Default)]
+pub struct BatchFetch {
+    pub posts: Vec<RedditPost>,
+    /// Subreddits Reddit answered with an error about the subreddit itself
+    pub failed: Vec<String>,
+    /// Subreddits not checked this cycle because of an error that isn't theirs
+    /// (429, 5xx, transport errors); they neither fail nor reset a streak
+    pub unchecked: Vec<String>,
+}
+
+/// Fetch the newest posts for a batch of subreddits
+///
+/// The combined URL is tried first (the caller has already acquired a rate
+/// limiter token for it). If Reddit answers the combined request with an error
+/// about a subreddit - e.g. one private, banned or deleted subreddit in the
+/// batch - each subreddit is retried on its own so only the failing ones are
+/// skipped. Rate limiting and server errors aren't retried per subreddit, as
+/// that would only send Reddit more requests; the whole batch waits for the
+/// next cycle instead.
+pub async fn fetch_batch_posts(
+    client: &Client,
+    rate_limiter: &RateLimiter,
+    reddit_base: &str,
+    batch: &[String],
+    listing_limit: u32,
+    summary: &mut CycleSummary,
+) -> BatchFetch {
+    // Build the combined subreddit URL (e.g., /r/sub1+sub2+sub3/new.json)
+    let json_url = build_listing_url(reddit_base, batch, listing_limit);
+
+    let mut fetch = BatchFetch::default();
 
-                        // Send notifications to all endpoints
-                        for ep in unique_endpoints {
-                            let client_clone = client.clone();
-                            match crate::notifiers::build_notifier(ep, client_clone) {
-                                Ok(notifier) => {
-                                    if let Err(e) =
-                                        notifier.send(subreddit, &post.title, &url).await
-                                    {
-                                        error!(
-                                            "Notify error ({} id={}): {}",
-                                            notifier.kind(),
-                                            ep.id,
-                                            e
-                                        );
-                                    }
-                                }
-                                Err(e) => {
-                                    error!("Build notifier failed for endpoint id {}: {}", ep.id, e);
-                                }
-                            }
-                        }
+    match fetch_listing(client, &json_url).await {
+        Ok(posts) => {
+            debug!(posts = posts.len(), subreddits = batch.len(), "Fetched {} posts from {} subreddit(s)", posts.len(), batch.len());
+            summary.record_batch(posts.len());
+            fetch.posts = posts;
+        }
+        Err(e) if e.is_subreddit_error() && batch.len() > 1 => {
+            warn!(
+                "Combined listing for {} subreddit(s) failed ({}) - retrying each subreddit individually",
+                batch.len(),
+                e
+            );
+            for (i, subreddit) in batch.iter().enumerate() {
+                rate_limiter.acquire().await;
+                let url = build_listing_url(reddit_base, std::slice::from_ref(subreddit), listing_limit);
+                match fetch_listing(client, &url).await {
+                    Ok(sub_posts) => {
+                        summary.record_batch(sub_posts.len());
+                        fetch.posts.extend(sub_posts);
+                    }
+                    Err(e) if e.is_subreddit_error() => {
+                        warn!(subreddit = %subreddit, error = %e, "Skipping r/{} this cycle: {}", subreddit, e);
+                        summary.record_failure();
+                        fetch.failed.push(subreddit.clone());
+                    }
+                    Err(e) => {
+                        warn!(url = %url, error = %e, "Reddit GET {} -> {} - leaving the rest of the batch for the next cycle", url, e);
+                        summary.record_failure();
+                        fetch.unchecked.extend(batch[i..].iter().cloned());
+                        break;
                     }
                 }
-                Err(e) => {
-                    warn!("HTTP error fetching combined URL {}: {}", json_url, e);
+            }
+        }
+        Err(e) => {
+            warn!(url = %json_url, error = %e, "Reddit GET {} -> {}", json_url, e);
+            summary.record_failure();
+            if e.is_subreddit_error() {
+                fetch.failed.extend(batch.iter().cloned());
+            } else {
+                fetch.unchecked.extend(batch.iter().cloned());
+            }
+        }
+    }
+
+    fetch
+}
+
+/// Counts consecutive failed cycles per subreddit, for auto-muting
+#[derive(Debug, Default)]
+pub struct FailureTracker {
+    /// Failed cycles in a row before a subreddit is reported (0 = never)
+    threshold: u32,
+    streaks: HashMap<String, u32>,
+}
+
+impl FailureTracker {
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            streaks: HashMap::new(),
+        }
+    }
+
+    /// Record one cycle's outcome for `batch`
+    ///
+    /// Returns the subreddits whose failure streak just reached the threshold.
+    pub fn record(&mut self, batch: &[String], failed: &[String]) -> Vec<String> {
+        let mut reached = Vec::new();
+        for subreddit in batch {
+            if failed.contains(subreddit) {
+                let streak = self.streaks.entry(subreddit.clone()).or_insert(0);
+                *streak += 1;
+                if self.threshold > 0 && *streak == self.threshold {
+                    reached.push(subreddit.clone());
                 }
+            } else {
+                self.streaks.remove(subreddit);
             }
         }
-        // Loop continues immediately - rate limiter controls polling frequency
+        reached
+    }
+}
+
+/// Build the combined listing URL for a batch of subreddits
+///
+/// E.g. `https://www.reddit.com/r/sub1+sub2/new.json?limit=100`
+pub fn build_listing_url(reddit_base: &str, batch: &[String], listing_limit: u32) -> String {
+    format!(
+        "{}/r/{}/new.json?limit={}",
+        reddit_base,
+        batch.join("+"),
+        listing_limit
+    )
+}
+
+/// Rewrite a link to Reddit onto the canonical `REDDIT_BASE_URL` host
+///
+/// Relative paths (permalinks) are joined to it and `reddit.com`,
+/// `old.reddit.com`, `np.reddit.com`, ... links are moved to it, so links look
+/// the same whichever host the listing was fetched from. Other links are
+/// returned unchanged.
+pub fn canonical_reddit_url(link: &str) -> String {
+    if link.starts_with('/') {
+        return format!("{}{}", REDDIT_BASE_URL, link);
+    }
+    match reqwest::Url::parse(link) {
+        Ok(url) if url.host_str().is_some_and(|h| h == "reddit.com" || h.ends_with(".reddit.com")) => {
+            let query = url.query().map(|q| format!("?{}", q)).unwrap_or_default();
+            format!("{}{}{}", REDDIT_BASE_URL, url.path(), query)
+        }
+        _ => link.to_string(),
+    }
+}
+
+/// Link to a post for notifications, on the canonical host
+///
+/// The permalink, else the post's own link (for link posts), else one built
+/// from the subreddit and ID.
+pub fn resolve_post_url(post: &RedditPost) -> String {
+    post.permalink
+        .as_deref()
+        .or(post.url.as_deref())
+        .map(canonical_reddit_url)
+        .unwrap_or_else(|| format!("{}/r/{}/comments/{}", REDDIT_BASE_URL, post.subreddit, post.id))
+}
+
+/// Reddit allows up to 100 subreddits in a multi-subreddit URL
+const MAX_SUBREDDITS_PER_BATCH: usize = 100;
+
+/// Active endpoints per lowercased subreddit
+pub type EndpointMappings = HashMap<String, Vec<EndpointRow>>;
+
+/// State the poller carries from one cycle to the next
+#[derive(Debug)]
+pub struct PollerState {
+    /// When the last listing request was made (for jittered spacing)
+    last_request: Option<Instant>,
+    failure_tracker: FailureTracker,
+    /// Subreddits muted by this poller; skipped until it restarts with a fresh list
+    auto_muted: HashSet<String>,
+    last_delivery_cleanup: Option<Instant>,
+    /// Subreddit-to-endpoints mapping and the config version it was read at
+    mappings_cache: Option<(i64, Arc<EndpointMappings>)>,
+    /// Cycles in a row that failed because the database was unreachable
+    db_connection_failures: u32,
+    /// No cycle has fetched a listing yet, so the startup limits still apply
+    startup: bool,
+    /// Search subscriptions found by the last cycle; they keep polling going with no active subreddits
+    active_searches: usize,
+    /// Latest manual sync request acted on (starts at the poller's start time)
+    sync_handled: DateTime<Utc>,
+}
+
+impl PollerState {
+    pub fn new(config: &PollerConfig) -> Self {
+        Self {
+            last_request: None,
+            failure_tracker: FailureTracker::new(config.auto_mute_after),
+            auto_muted: HashSet::new(),
+            last_delivery_cleanup: None,
+            mappings_cache: None,
+            db_connection_failures: 0,
+            startup: true,
+            active_searches: 0,
+            sync_handled: Utc::now(),
+        }
+    }
+
+    /// Track a failed cycle, reconnecting to the database if it keeps being unreachable
+    ///
+    /// After `RECONNECT_AFTER_FAILURES` connection errors in a row the pool is
+    /// rebuilt (with the startup retry/backoff), so a database file that went
+    /// away briefly doesn't leave the poller failing forever.
+    pub async fn record_cycle_error<D: DatabaseService>(&mut self, db: &D, err: &anyhow::Error) {
+        if !is_connection_error(err) {
+            self.db_connection_failures = 0;
+            return;
+        }
+        self.db_connection_failures += 1;
+        if self.db_connection_failures < RECONNECT_AFTER_FAILURES {
+            return;
+        }
+
+        warn!("Database unreachable for {} cycle(s) in a row - reconnecting", self.db_connection_failures);
+        self.db_connection_failures = 0;
+        match db.reconnect().await {
+            Ok(true) => info!("Reconnected to the database"),
+            Ok(false) => debug!("Database service can't reconnect; retrying on the existing pool"),
+            Err(e) => error!("Failed to reconnect to the database: {:#}", e),
+        }
+    }
+
+    /// Note a cycle that completed, clearing the connection failure count
+    pub fn record_cycle_success(&mut self) {
+        self.db_connection_failures = 0;
+    }
+
+    /// The subreddit-to-endpoints mapping, re-queried only when the config version changed
+    ///
+    /// If the version can't be read the mapping is queried every time, as before caching.
+    pub async fn endpoint_mappings<D: DatabaseService>(&mut self, db: &D) -> Result<Arc<EndpointMappings>> {
+        let version = match db.config_version().await {
+            Ok(version) => Some(version),
+            Err(e) => {
+                warn!("Failed to read config version: {} - reloading endpoint mappings", e);
+                None
+            }
+        };
+        if let (Some(version), Some((cached_version, mappings))) = (version, &self.mappings_cache) {
+            if version == *cached_version {
+                return Ok(mappings.clone());
+            }
+        }
+
+        let mappings = Arc::new(db.all_subreddit_endpoint_mappings().await?);
+        self.mappings_cache = version.map(|v| (v, mappings.clone()));
+        Ok(mappings)
+    }
+
+    /// Number of `subreddits` that have not been auto-muted
+    pub fn active_count(&self, subreddits: &[String]) -> usize {
+        subreddits.iter().filter(|s| !self.auto_muted.contains(*s)).count()
+    }
+}
+
+/// Whether `ep` takes notifications at `now` (see `active_hours`)
+///
+/// An endpoint whose active hours can't be parsed is treated as always active,
+/// so a bad edit doesn't silently drop its notifications.
+pub fn endpoint_is_active(ep: &EndpointRow, now: DateTime<Utc>) -> bool {
+    match endpoint_active_hours(&ep.config_json) {
+        Ok(hours) => hours.is_none_or(|hours| hours.is_active(now)),
+        Err(e) => {
+            warn!("Ignoring active hours of endpoint id {}: {}", ep.id, e);
+            true
+        }
+    }
+}
+
+/// Send `ctx` to each endpoint, recording every delivery against `item_id`
+///
+/// Endpoints outside their active hours are skipped (nothing is queued for
+/// them). With a `batcher`, non-priority notifications are queued for the
+/// endpoint's next batch instead; the delivery is recorded when it's sent.
+/// Failures are logged and counted in `summary`; the remaining endpoints are
+/// still tried.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn notify_endpoints<D: DatabaseService>(
+    db: &D,
+    client: &Client,
+    profile: &NotifierClientProfile,
+    batcher: Option<&Batcher>,
+    endpoints: &[&EndpointRow],
+    item_id: &str,
+    ctx: &SendContext<'_>,
+    summary: &mut CycleSummary,
+) {
+    for ep in endpoints {
+        if !endpoint_is_active(ep, Utc::now()) {
+            debug!(endpoint_id = ep.id, item_id, "Endpoint id {} is outside its active hours, not sending {}", ep.id, item_id);
+            summary.record_off_hours();
+            continue;
+        }
+        if let Some(batcher) = batcher.filter(|_| !ctx.priority) {
+            batcher.add(ep, BatchedPost::from_context(item_id, ctx));
+            summary.record_batched();
+            continue;
+        }
+
+        let started = Instant::now();
+        let result = match crate::notifiers::build_notifier(ep, client.clone(), profile.footers) {
+            Ok(notifier) => {
+                match send_with_retry(notifier.as_ref(), ctx, profile.max_attempts, profile.base_backoff).await {
+                    Ok(()) => {
+                        summary.record_sent();
+                        Ok(())
+                    }
+                    Err(e) => {
+                        error!(
+                            endpoint_id = ep.id,
+                            kind = notifier.kind(),
+                            item_id,
+                            subreddit = ctx.subreddit,
+                            error = %e,
+                            "Notify error ({} id={}): {}",
+                            notifier.kind(),
+                            ep.id,
+                            e
+                        );
+                        summary.record_failure();
+                        Err(e.to_string())
+                    }
+                }
+            }
+            Err(e) => {
+                error!(endpoint_id = ep.id, item_id, error = %e, "Build notifier failed for endpoint id {}: {}", ep.id, e);
+                summary.record_failure();
+                Err(format!("Failed to build notifier: {:#}", e))
+            }
+        };
+
+        let latency_ms = started.elapsed().as_millis() as i64;
+        let (status, error) = match &result {
+            Ok(()) => (DeliveryStatus::Sent, None),
+            Err(e) => (DeliveryStatus::Failed, Some(e.as_str())),
+        };
+        if let Err(e) = db.record_delivery(ep.id, item_id, status, latency_ms, error).await {
+            warn!(endpoint_id = ep.id, item_id, error = %e, "Failed to record delivery for endpoint id {}: {}", ep.id, e);
+        }
+    }
+}
+
+/// Record the score bucket `post` reached; returns the threshold it newly crossed, if any
+async fn score_crossing<D: DatabaseService>(db: &D, post: &RedditPost, thresholds: &[i64]) -> Option<i64> {
+    let bucket = score_bucket(thresholds, post.score);
+    match db.record_score_bucket(&post.subreddit, &post.id, bucket).await {
+        Ok(previous) => crossed_threshold(thresholds, previous, bucket),
+        Err(e) => {
+            warn!("Failed to record score of post {} from r/{}: {}", post.id, post.subreddit, e);
+            None
+        }
+    }
+}
+
+/// Sample a young post's metrics and check whether it's rising
+///
+/// The stored sample is only replaced once it's `MIN_SAMPLE_GAP_MINUTES` old,
+/// and a post is reported as rising once. Returns the velocity that crossed
+/// `threshold`, if any.
+async fn rising_check<D: DatabaseService>(
+    db: &D,
+    post: &RedditPost,
+    threshold: i64,
+    post_age: TimeDelta,
+    now: DateTime<Utc>,
+) -> Option<Velocity> {
+    let current = MetricSample { score: post.score, num_comments: post.num_comments, sampled_at: now };
+    let previous = match db.post_metrics(&post.subreddit, &post.id).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            warn!("Failed to read metrics of post {} from r/{}: {}", post.id, post.subreddit, e);
+            return None;
+        }
+    };
+
+    let (growth, alerted) = match previous {
+        Some(previous) if previous.alerted => return None,
+        Some(previous) => match velocity(&previous.sample, &current) {
+            Some(v) => (Some(v), is_rising(&v, threshold, post_age)),
+            None => return None,
+        },
+        None => (None, false),
+    };
+    let metrics = PostMetrics { sample: current, alerted };
+    if let Err(e) = db.record_post_metrics(&post.subreddit, &post.id, &metrics).await {
+        warn!("Failed to record metrics of post {} from r/{}: {}", post.id, post.subreddit, e);
+        return None;
+    }
+    growth.filter(|_| alerted)
+}
+
+/// Per-cycle lookups the record step reads for every post
+struct RecordLookups<'a> {
+    mappings: &'a EndpointMappings,
+    allow_stickied: &'a HashSet<String>,
+    score_thresholds: &'a HashMap<String, Vec<i64>>,
+    rising_thresholds: &'a HashMap<String, i64>,
+}
+
+/// A batch's posts after recording: the ones to notify about, and the counts from recording them
+struct RecordedBatch<'a> {
+    pending: Vec<PendingNotification<'a>>,
+    summary: CycleSummary,
+}
+
+/// Record a fetched batch's posts and pick the ones to notify about
+///
+/// Several batches can be recorded at once (`PollerConfig::batch_workers`),
+/// so this only reads shared state. Its counts go in the batch's own summary,
+/// which the caller merges into the cycle's.
+async fn record_batch_posts<'a, D: DatabaseService>(
+    db: &D,
+    posts: Vec<RedditPost>,
+    lookups: &RecordLookups<'a>,
+    config: &PollerConfig,
+) -> RecordedBatch<'a> {
+    let mut summary = CycleSummary::new();
+    let mut pending = Vec::new();
+    for post in posts {
+
+        // The post.subreddit field tells us which subreddit this post came from
+        // This is crucial for the combined poller approach
+        let subreddit = &post.subreddit;
+        // Subreddit names are case-insensitive and the lookup sets are keyed lowercase,
+        // so a post from r/rust still matches a subscription typed as "Rust"
+        let subreddit_key = subreddit.to_lowercase();
+
+        if is_skipped_sticky(&post, lookups.allow_stickied) {
+            summary.record_skip(SkipReason::Stickied, &post.id, subreddit, "stickied");
+            continue;
+        }
+
+        // Check if post is within ±24 hours
+        // This was added because Reddit's API would randomly return old posts
+        let now = Utc::now();
+        let time_diff = now.signed_duration_since(post.created_utc);
+        let is_within_window = time_diff.abs() <= TimeDelta::hours(POST_WINDOW_HOURS);
+        if !is_within_window {
+            if time_diff < TimeDelta::zero() {
+                // Reddit doesn't return posts from the future; the host clock is behind
+                warn!(
+                    "Skipping post {} from r/{} - {} (posted: {}); is the host clock wrong?",
+                    post.id, subreddit, describe_post_age(time_diff), post.created_utc
+                );
+            }
+            let detail = format!(
+                "outside {}h window, {} (posted: {})",
+                POST_WINDOW_HOURS, describe_post_age(time_diff), post.created_utc
+            );
+            summary.record_skip(SkipReason::OutsideWindow, &post.id, subreddit, &detail);
+            continue;
+        }
+        summary.record_in_window();
+
+        let url = resolve_post_url(&post);
+
+        // Check if we've already notified about this post
+        let is_new = match db.record_if_new(subreddit, &post.id, &post.title, &url).await {
+            Ok(new) => new,
+            Err(e) => {
+                error!(
+                    post_id = %post.id,
+                    subreddit = %subreddit,
+                    error = %e,
+                    "Failed to record post {} for r/{}: {} - skipping this post",
+                    post.id, subreddit, e
+                );
+                summary.record_failure();
+                continue;
+            }
+        };
+
+        // Opted-in subreddits also track the post's score, for one follow-up per threshold
+        let crossed = match lookups.score_thresholds.get(&subreddit_key) {
+            Some(thresholds) => score_crossing(db, &post, thresholds).await,
+            None => None,
+        };
+        // Opted-in subreddits also sample young posts, for one follow-up when growing fast
+        let rising = match lookups.rising_thresholds.get(&subreddit_key) {
+            Some(&threshold) if time_diff <= TimeDelta::hours(RISING_WINDOW_HOURS) => {
+                rising_check(db, &post, threshold, time_diff, now).await
+            }
+            _ => None,
+        };
+        let title = if is_new {
+            summary.record_new_post();
+            post.title.clone()
+        } else if let Some(threshold) = crossed {
+            info!(post_id = %post.id, subreddit = %subreddit, threshold, "Post {} from r/{} reached {} points", post.id, subreddit, threshold);
+            format!("📈 {}+ points: {}", threshold, post.title)
+        } else if let Some(v) = rising {
+            info!(
+                post_id = %post.id,
+                subreddit = %subreddit,
+                points_per_hour = v.points_per_hour,
+                "Post {} from r/{} is rising at {:.0} points/h, {:.0} comments/h",
+                post.id, subreddit, v.points_per_hour, v.comments_per_hour
+            );
+            format!("🚀 Rising, +{:.0} points/h: {}", v.points_per_hour, post.title)
+        } else {
+            summary.record_skip(SkipReason::AlreadySeen, &post.id, subreddit, "already seen");
+            continue;
+        };
+
+        // Paused: the post stays recorded so it isn't sent after resuming
+        if !config.notifications_enabled.load(Ordering::Relaxed) {
+            summary.record_skip(SkipReason::Paused, &post.id, subreddit, "notifications paused");
+            continue;
+        }
+
+        // Get endpoints for this specific subreddit from our mapping
+        let endpoints = match lookups.mappings.get(&subreddit_key) {
+            Some(eps) => eps,
+            None => {
+                // No endpoints subscribed to this subreddit
+                // This can happen if mappings changed between poll cycles
+                summary.record_skip(SkipReason::NoEndpoints, &post.id, subreddit, "no endpoints");
+                continue;
+            }
+        };
+
+        pending.push(PendingNotification { post, url, title, is_new, subreddit_key, endpoints });
+    }
+    RecordedBatch { pending, summary }
+}
+
+/// Fetch one batch's posts and auto-mute subreddits that keep failing
+async fn fetch_next_batch<D: DatabaseService>(
+    db: &D,
+    client: &Client,
+    rate_limiter: &RateLimiter,
+    config: &PollerConfig,
+    state: &mut PollerState,
+    batch: &[String],
+    summary: &mut CycleSummary,
+) -> Vec<RedditPost> {
+    // Nudge the spacing off a fixed beat; the rate limiter still caps the average rate
+    if config.jitter_pct > 0 && !rate_limiter.is_boosted().await {
+        if let Some(last) = state.last_request {
+            let target = last + jittered_delay(config.batch_spacing, config.jitter_pct);
+            tokio::time::sleep_until(target.into()).await;
+        }
+    }
+
+    // Wait for rate limiter before making the API call
+    rate_limiter.acquire().await;
+    state.last_request = Some(Instant::now());
+
+    let BatchFetch { posts, failed, unchecked } =
+        fetch_batch_posts(client, rate_limiter, &config.reddit_base, batch, config.listing_limit, summary).await;
+
+    let checked: Vec<String> = batch.iter().filter(|s| !unchecked.contains(s)).cloned().collect();
+    for subreddit in state.failure_tracker.record(&checked, &failed) {
+        match db.mute_subreddit(&subreddit).await {
+            Ok(muted) => {
+                warn!(
+                    subreddit = %subreddit,
+                    muted,
+                    "r/{} failed {} poll cycle(s) in a row - muted {} subscription(s)",
+                    subreddit, config.auto_mute_after, muted
+                );
+                state.auto_muted.insert(subreddit);
+            }
+            Err(e) => error!(subreddit = %subreddit, error = %e, "Failed to auto-mute r/{}: {}", subreddit, e),
+        }
+    }
+
+    posts
+}
+
+/// Run a single poll cycle: fetch every batch once, notify, record
+///
+/// Returns the cycle's counters. Fails without polling if the subscription
+/// settings can't be read from the database; per-post and per-notification
+/// errors are logged and counted in `CycleSummary::failures` instead.
+pub async fn run_once<D: DatabaseService>(
+    db: &D,
+    client: &Client,
+    subreddits: &[String],
+    rate_limiter: &RateLimiter,
+    config: &PollerConfig,
+    state: &mut PollerState,
+) -> Result<CycleSummary> {
+    if config.delivery_retention_days > 0
+        && state.last_delivery_cleanup.is_none_or(|t| t.elapsed() >= DELIVERY_CLEANUP_INTERVAL)
+    {
+        match db.cleanup_old_deliveries(config.delivery_retention_days.into()).await {
+            Ok(0) => {}
+            Ok(deleted) => info!("Pruned {} delivery record(s) older than {} day(s)", deleted, config.delivery_retention_days),
+            Err(e) => warn!("Failed to prune delivery history: {}", e),
+        }
+        state.last_delivery_cleanup = Some(Instant::now());
+    }
+
+    handle_sync_request(db, rate_limiter, config, state).await;
+
+    // Fetch the subreddit-to-endpoints mapping once per poll cycle (reused while the config is unchanged)
+    // This is more efficient than querying for each post
+    let mappings = state
+        .endpoint_mappings(db)
+        .await
+        .context("Failed to fetch subreddit-endpoint mappings")?;
+
+    // Subreddits where some subscription wants stickied posts
+    let allow_stickied = db
+        .subreddits_allowing_stickied()
+        .await
+        .context("Failed to fetch stickied-post settings")?;
+
+    // Subreddits whose notifications include the endpoint's mention
+    let priority = db.priority_subreddits().await.unwrap_or_else(|e| {
+        warn!("Failed to fetch priority subscriptions: {} - sending without mentions", e);
+        HashSet::new()
+    });
+
+    // Subreddits with score alerts turned on, and their thresholds
+    let score_thresholds = db.score_thresholds_by_subreddit().await.unwrap_or_else(|e| {
+        warn!("Failed to fetch score alert thresholds: {} - sending first-seen notifications only", e);
+        HashMap::new()
+    });
+
+    // Subreddits with rising alerts turned on; samples of posts past the window are dropped
+    let rising_thresholds = db.rising_thresholds_by_subreddit().await.unwrap_or_else(|e| {
+        warn!("Failed to fetch rising thresholds: {} - not tracking post velocity this cycle", e);
+        HashMap::new()
+    });
+    if !rising_thresholds.is_empty() {
+        if let Err(e) = db.prune_post_metrics(RISING_WINDOW_HOURS).await {
+            warn!("Failed to prune post metrics: {}", e);
+        }
+    }
+
+    // Subreddits with comment keyword alerts turned on, compiled once for the cycle
+    let comment_keywords = match db.comment_keywords_by_subreddit().await {
+        Ok(keywords) => crate::comments::compile_keywords(keywords),
+        Err(e) => {
+            warn!("Failed to fetch comment keywords: {} - not polling comments this cycle", e);
+            HashMap::new()
+        }
+    };
+
+    // Saved searches, polled one request each after the batches
+    let searches = db.search_subscriptions().await.unwrap_or_else(|e| {
+        warn!("Failed to fetch search subscriptions: {} - not polling searches this cycle", e);
+        Vec::new()
+    });
+    state.active_searches = searches.len();
+
+    refresh_notifications_enabled(db, &config.notifications_enabled).await;
+
+    // Each endpoint receives a given post at most once per cycle
+    let mut delivery_guard = DeliveryGuard::new();
+    let mut notification_cap = NotificationCap::new(config.max_notifications_per_subreddit_per_cycle);
+    let mut startup_throttle = StartupThrottle::for_cycle(config, state.startup);
+    let cycle_started = Instant::now();
+    let mut summary = CycleSummary::new();
+
+    let lookups = RecordLookups {
+        mappings: &mappings,
+        allow_stickied: &allow_stickied,
+        score_thresholds: &score_thresholds,
+        rising_thresholds: &rising_thresholds,
+    };
+
+    // Batches are fetched one at a time, paced by the rate limiter. Up to
+    // `batch_workers` fetched batches are recorded at once while the next one
+    // is fetched; notifications are still sent one batch at a time, in order.
+    let mut fetch_summary = CycleSummary::new();
+    {
+        let batches = subreddits.chunks(MAX_SUBREDDITS_PER_BATCH);
+        let fetches = stream::unfold((batches, &mut *state, &mut fetch_summary), |(mut batches, state, fetch_summary)| async move {
+            let batch = loop {
+                let batch: Vec<String> = batches.next()?.iter().filter(|s| !state.auto_muted.contains(*s)).cloned().collect();
+                if !batch.is_empty() {
+                    break batch;
+                }
+            };
+            let posts = fetch_next_batch(db, client, rate_limiter, config, state, &batch, fetch_summary).await;
+            Some((posts, (batches, state, fetch_summary)))
+        });
+        let mut recorded =
+            std::pin::pin!(fetches.map(|posts| record_batch_posts(db, posts, &lookups, config)).buffered(config.batch_workers.max(1)));
+
+        while let Some(RecordedBatch { mut pending, summary: batch_summary }) = recorded.next().await {
+            summary.absorb(&batch_summary);
+
+            // Everything is recorded; only the sending follows the configured order
+            sort_for_dispatch(&mut pending, config.notification_order);
+            for PendingNotification { post, url, title, is_new, subreddit_key, endpoints } in pending {
+                let subreddit = &post.subreddit;
+
+                // Already recorded above, so a suppressed post won't fire next cycle either
+                if !startup_throttle.allow() {
+                    debug!(post_id = %post.id, subreddit = %subreddit, "Startup limit reached, not delivering post {} from r/{}", post.id, subreddit);
+                    summary.record_suppressed();
+                    continue;
+                }
+                if !notification_cap.allow(&subreddit_key) {
+                    debug!(post_id = %post.id, subreddit = %subreddit, "Notification cap reached for r/{}, not delivering post {}", subreddit, post.id);
+                    summary.record_suppressed();
+                    continue;
+                }
+
+                // Deduplicate endpoints (same endpoint might be subscribed multiple times)
+                let unique_endpoints = delivery_guard.unclaimed(&post.id, endpoints);
+
+                debug!(
+                    post_id = %post.id,
+                    subreddit = %subreddit,
+                    endpoints = unique_endpoints.len(),
+                    "Post in r/{}: {} -> notifying {} endpoint(s)",
+                    subreddit,
+                    title,
+                    unique_endpoints.len()
+                );
+
+                let ctx = SendContext {
+                    subreddit,
+                    title: &title,
+                    url: &url,
+                    nsfw: post.over_18,
+                    priority: is_new && priority.contains(&subreddit_key),
+                };
+                notify_endpoints(db, client, &config.notifier_profile, config.batcher.as_ref(), &unique_endpoints, &post.id, &ctx, &mut summary).await;
+            }
+        }
+    }
+    summary.absorb(&fetch_summary);
+
+    for warning in notification_cap.suppression_warnings() {
+        warn!("{}", warning);
+    }
+    if startup_throttle.suppressed() > 0 {
+        warn!(
+            "Held back {} post(s) found by the first poll cycle after startup (STARTUP_SUPPRESS / STARTUP_MAX_NOTIFICATIONS)",
+            startup_throttle.suppressed()
+        );
+    }
+    // A cycle that couldn't fetch anything (e.g. Reddit unreachable) leaves the backlog for the next one
+    if summary.batches_polled > 0 {
+        state.startup = false;
+    }
+
+    // Search listings can't be combined either
+    if !searches.is_empty() {
+        crate::search::poll_searches(db, client, rate_limiter, config, &searches, &mut delivery_guard, &mut summary).await;
+    }
+
+    // Comment listings can't be combined, so these are fetched one subreddit at a time
+    if !comment_keywords.is_empty() {
+        crate::comments::poll_comments(
+            db,
+            client,
+            rate_limiter,
+            config,
+            &comment_keywords,
+            &mappings,
+            &mut delivery_guard,
+            &mut summary,
+        )
+        .await;
+    }
+
+    summary.finish(cycle_started);
+    info!(
+        target: "reddit_notifier",
+        batches = summary.batches_polled,
+        new_posts = summary.new_posts,
+        sent = summary.notifications_sent,
+        failures = summary.failures,
+        duration_ms = summary.duration.as_millis() as u64,
+        "{}",
+        summary
+    );
+
+    // Activity timestamps for the TUI's poller health, `reddit-notifier status` and the healthcheck
+    let now = Utc::now().format(TIMESTAMP_FORMAT).to_string();
+    let mut keys = vec![META_LAST_POLL_AT];
+    if summary.batches_polled > 0 && summary.failures == 0 {
+        keys.push(META_LAST_SUCCESS_AT);
+    }
+    for key in keys {
+        if let Err(e) = db.set_meta(key, &now).await {
+            warn!("Failed to record {}: {}", key, e);
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Run a single poll cycle and send whatever it batched, for `poll-once`
+///
+/// With batching on, `run_once` only queues non-priority notifications (the
+/// posts are recorded as notified right away). A one-shot run has no flusher
+/// task, so the queued batches are sent before returning instead of being
+/// lost when the process exits. Batch deliveries that fail count towards the
+/// summary's failures.
+pub async fn run_once_and_flush<D: DatabaseService>(
+    db: &D,
+    client: &Client,
+    subreddits: &[String],
+    rate_limiter: &RateLimiter,
+    config: &PollerConfig,
+    state: &mut PollerState,
+) -> Result<CycleSummary> {
+    let mut summary = run_once(db, client, subreddits, rate_limiter, config, state).await?;
+    if let Some(batcher) = &config.batcher {
+        summary.failures += batcher.flush_all(db, client, &config.notifier_profile).await;
+    }
+    Ok(summary)
+}
+
+/// Combined subreddit poller - polls multiple subreddits in a single API call
+///
+/// This is more efficient than spawning one poller per subreddit. Reddit allows
+/// combining up to 100 subreddits in a single URL using the format:
+/// `/r/sub1+sub2+sub3/new.json`
+///
+/// Benefits:
+/// - Fewer API calls to Reddit (better for rate limiting)
+/// - More efficient resource usage
+/// - Easier to implement global rate limiting
+///
+/// # Arguments
+/// * `db` - Database service
+/// * `client` - HTTP client for making Reddit API calls
+/// * `subreddits` - List of subreddit names to poll (will be automatically batched)
+/// * `rate_limiter` - Rate limiter to respect Reddit's API limits
+/// * `config` - Poller settings (listing limit, ...)
+///
+/// # Polling Behavior
+/// The poller runs `run_once` continuously, making API calls as fast as the rate limiter allows.
+/// Configure the rate limiter (via REDDIT_RATE_LIMIT_PER_MINUTE) to control polling frequency.
+/// Default: 20 requests/minute. Reddit's limit is approximately 60 requests/minute.
+pub async fn poll_combined_subreddits_loop<D: DatabaseService + 'static>(
+    db: Arc<D>,
+    client: Client,
+    subreddits: Vec<String>,
+    rate_limiter: RateLimiter,
+    config: PollerConfig,
+) -> Result<()> {
+    let mut state = PollerState::new(&config);
+    state.active_searches = db.search_subscriptions().await.map(|s| s.len()).unwrap_or_default();
+    if subreddits.is_empty() && state.active_searches == 0 {
+        info!("No subreddits to poll");
+        return Ok(());
+    }
+
+    let batches: Vec<&[String]> = subreddits.chunks(MAX_SUBREDDITS_PER_BATCH).collect();
+
+    info!(
+        target: "reddit_notifier",
+        "Spawned combined poller for {} subreddit(s) across {} batch(es)",
+        subreddits.len(),
+        batches.len()
+    );
+
+    // The listing limit is shared by every subreddit in a combined URL, so a
+    // large batch with a low limit may only see a handful of posts per sub
+    for batch in &batches {
+        if batch.len() as u32 > config.listing_limit {
+            warn!(
+                "Batch of {} subreddit(s) shares a listing limit of {} post(s) per poll - busy subreddits may be missed (consider raising REDDIT_LISTING_LIMIT)",
+                batch.len(),
+                config.listing_limit
+            );
+        }
+    }
+
+    // Batched notifications go out from their own task, on the batching window's schedule
+    if let Some(batcher) = &config.batcher {
+        tokio::spawn(batcher.clone().run_flusher(db.clone(), client.clone(), config.notifier_profile.clone()));
+    }
+
+    rate_limiter.acquire().await;
+    check_clock_skew(&client, &config.reddit_base).await;
+
+    let mut idle_logged = false;
+
+    loop {
+        // Nothing left to poll (e.g. every subreddit auto-muted): sleep instead of spinning
+        if let Some(delay) = idle_delay(state.active_count(&subreddits) + state.active_searches, &config) {
+            if !idle_logged {
+                info!("No active subreddits to poll - polling is idle, checking again every {}s", delay.as_secs());
+                idle_logged = true;
+            }
+            tokio::time::sleep(delay).await;
+            continue;
+        }
+        idle_logged = false;
+
+        match run_once(db.as_ref(), &client, &subreddits, &rate_limiter, &config, &mut state).await {
+            Ok(_) => state.record_cycle_success(),
+            Err(e) => {
+                error!("{:#} - will retry in {}s", e, config.idle_sleep.as_secs());
+                state.record_cycle_error(db.as_ref(), &e).await;
+                tokio::time::sleep(config.idle_sleep).await;
+            }
+        }
+        // Loop continues immediately - rate limiter controls polling frequency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keywords::{KeywordMode, KeywordSet};
+    use crate::services::mock_database::MockDatabaseService;
+
+    #[test]
+    fn test_clock_skew_from_date_header() {
+        let local = DateTime::parse_from_rfc3339("2026-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let skew = clock_skew(local, "Fri, 01 May 2026 11:58:30 GMT").unwrap();
+        assert_eq!(skew.num_seconds(), 90);
+        assert!(!is_implausible_skew(skew));
+
+        let behind = clock_skew(local, "Fri, 01 May 2026 13:00:00 GMT").unwrap();
+        assert_eq!(behind.num_seconds(), -3600);
+        assert!(is_implausible_skew(behind));
+
+        assert!(!is_implausible_skew(TimeDelta::seconds(CLOCK_SKEW_WARN_SECS)));
+        assert!(is_implausible_skew(TimeDelta::seconds(-CLOCK_SKEW_WARN_SECS - 1)));
+        assert!(clock_skew(local, "yesterday").is_none());
+    }
+
+    #[test]
+    fn test_describe_post_age() {
+        assert_eq!(describe_post_age(TimeDelta::minutes(26 * 60 + 5)), "26h 5m old");
+        assert_eq!(describe_post_age(TimeDelta::hours(-3)), "3h 0m in the future");
+    }
+
+    #[test]
+    fn test_build_listing_url_single_subreddit() {
+        let batch = vec!["rust".to_string()];
+        assert_eq!(
+            build_listing_url("https://www.reddit.com", &batch, 100),
+            "https://www.reddit.com/r/rust/new.json?limit=100"
+        );
+    }
+
+    #[test]
+    fn test_build_listing_url_combined_batch() {
+        let batch = vec!["rust".to_string(), "programming".to_string()];
+        assert_eq!(
+            build_listing_url("https://www.reddit.com", &batch, 25),
+            "https://www.reddit.com/r/rust+programming/new.json?limit=25"
+        );
+    }
+
+    #[test]
+    fn test_post_urls_use_canonical_host() {
+        let mut p = post("abc", "rust", false);
+        assert_eq!(resolve_post_url(&p), "https://www.reddit.com/r/rust/comments/abc");
+
+        p.url = Some("https://old.reddit.com/r/rust/comments/abc/hello/".to_string());
+        assert_eq!(resolve_post_url(&p), "https://www.reddit.com/r/rust/comments/abc/hello/");
+
+        p.permalink = Some("/r/rust/comments/abc/hello/".to_string());
+        assert_eq!(resolve_post_url(&p), "https://www.reddit.com/r/rust/comments/abc/hello/");
+
+        // Link posts keep their external link
+        p.permalink = None;
+        p.url = Some("https://blog.rust-lang.org/2026/01/01/news.html".to_string());
+        assert_eq!(resolve_post_url(&p), "https://blog.rust-lang.org/2026/01/01/news.html");
+    }
+
+    #[test]
+    fn test_canonical_reddit_url_host_variations() {
+        for link in [
+            "/r/rust/comments/abc/hello/?context=3",
+            "https://old.reddit.com/r/rust/comments/abc/hello/?context=3",
+            "http://reddit.com/r/rust/comments/abc/hello/?context=3",
+            "https://np.reddit.com:443/r/rust/comments/abc/hello/?context=3",
+        ] {
+            assert_eq!(canonical_reddit_url(link), "https://www.reddit.com/r/rust/comments/abc/hello/?context=3", "{}", link);
+        }
+        assert_eq!(canonical_reddit_url("https://notreddit.com/r/rust"), "https://notreddit.com/r/rust");
+    }
+
+    #[tokio::test]
+    async fn test_listing_redirected_to_another_path_is_an_error() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/gone/") {
+                (302, "/subreddits/search.json?q=gone".to_string())
+            } else if path.starts_with("/r/moved/") {
+                (301, "/r/rust/new.json?limit=100".to_string())
+            } else if path.starts_with("/r/rust/new.json") {
+                (200, listing_json("rust"))
+            } else {
+                (200, serde_json::json!({ "data": { "children": [] } }).to_string())
+            }
+        });
+
+        let err = fetch_listing(&test_client(), &format!("{}/r/gone/new.json?limit=100", base)).await.unwrap_err();
+        assert!(matches!(err, FetchError::Redirected(ref url) if url.ends_with("/subreddits/search.json?q=gone")), "{}", err);
+        assert!(fetch_listing(&test_client(), &format!("{}/r/moved/new.json", base)).await.is_err());
+        let posts = fetch_listing(&test_client(), &format!("{}/r/rust/new.json?limit=100", base)).await.unwrap();
+        assert_eq!(posts.len(), 1);
+    }
+
+    #[test]
+    fn test_delivery_guard_claims_once() {
+        let mut guard = DeliveryGuard::new();
+        assert!(guard.claim("abc", 1));
+        assert!(!guard.claim("abc", 1));
+        assert!(guard.claim("abc", 2));
+        assert!(guard.claim("def", 1));
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_subscriptions_notify_endpoint_once() {
+        let db = MockDatabaseService::new();
+        let endpoint_id = db
+            .create_endpoint("discord", r#"{"webhook_url":"https://discord.com/api/webhooks/x"}"#, None)
+            .await
+            .unwrap();
+        let other_endpoint_id = db
+            .create_endpoint("pushover", r#"{"token":"t","user":"u"}"#, None)
+            .await
+            .unwrap();
+
+        // Two subscriptions for the same subreddit, both linked to the same endpoint
+        let sub_a = db.create_subscription("rust").await.unwrap();
+        let sub_b = db.create_subscription("rust").await.unwrap();
+        db.link_subscription_endpoint(sub_a, endpoint_id).await.unwrap();
+        db.link_subscription_endpoint(sub_b, endpoint_id).await.unwrap();
+        db.link_subscription_endpoint(sub_b, other_endpoint_id).await.unwrap();
+
+        let mappings = db.all_subreddit_endpoint_mappings().await.unwrap();
+        let endpoints = mappings.get("rust").unwrap();
+        assert_eq!(endpoints.len(), 3);
+
+        let mut guard = DeliveryGuard::new();
+        let targets: Vec<i64> = guard.unclaimed("post1", endpoints).iter().map(|e| e.id).collect();
+        assert_eq!(targets, vec![endpoint_id, other_endpoint_id]);
+
+        // The same post seen again in the cycle (e.g. via another batch) sends nothing
+        assert!(guard.unclaimed("post1", endpoints).is_empty());
+
+        // A different post still goes to every endpoint once
+        assert_eq!(guard.unclaimed("post2", endpoints).len(), 2);
+    }
+
+    #[test]
+    fn test_poller_config_default_limit() {
+        assert_eq!(PollerConfig::default().listing_limit, 100);
+    }
+
+    #[test]
+    fn test_poller_health_classification() {
+        let now = Utc::now();
+        let stale_after = Duration::from_secs(300);
+
+        assert_eq!(PollerHealth::classify(None, now, stale_after), PollerHealth::Unknown);
+
+        let recent = PollerHealth::classify(Some(now - TimeDelta::seconds(12)), now, stale_after);
+        assert_eq!(recent, PollerHealth::Healthy { since_last_poll: Duration::from_secs(12) });
+        assert_eq!(recent.to_string(), "Poller: healthy (last poll 12s ago)");
+
+        let boundary = PollerHealth::classify(Some(now - TimeDelta::seconds(300)), now, stale_after);
+        assert!(boundary.is_healthy());
+
+        let old = PollerHealth::classify(Some(now - TimeDelta::minutes(10)), now, stale_after);
+        assert_eq!(old, PollerHealth::Stale { since_last_poll: Duration::from_secs(600) });
+        assert_eq!(old.to_string(), "Poller: stale (last poll 10m ago)");
+
+        // A heartbeat slightly ahead of our clock still counts as fresh
+        let skewed = PollerHealth::classify(Some(now + TimeDelta::seconds(2)), now, stale_after);
+        assert_eq!(skewed, PollerHealth::Healthy { since_last_poll: Duration::ZERO });
+    }
+
+    #[test]
+    fn test_idle_delay_only_when_nothing_to_poll() {
+        let config = PollerConfig {
+            idle_sleep: Duration::from_secs(45),
+            ..PollerConfig::default()
+        };
+        assert_eq!(idle_delay(0, &config), Some(Duration::from_secs(45)));
+        assert_eq!(idle_delay(3, &config), None);
+    }
+
+    #[test]
+    fn test_jittered_delay_stays_within_band() {
+        let base = Duration::from_secs(15);
+        let (low, high) = (Duration::from_secs(12), Duration::from_secs(18));
+        for _ in 0..500 {
+            let delay = jittered_delay(base, 20);
+            assert!(delay >= low && delay <= high, "{:?} outside 12s..=18s", delay);
+        }
+        assert_eq!(jittered_delay(base, 0), base);
+    }
+
+    #[test]
+    fn test_cycle_summary_aggregates_counters() {
+        let mut summary = CycleSummary::new();
+        summary.record_batch(25);
+        summary.record_batch(10);
+        summary.record_in_window();
+        summary.record_in_window();
+        summary.record_new_post();
+        summary.record_sent();
+        summary.record_sent();
+        summary.record_failure();
+
+        assert_eq!(
+            summary,
+            CycleSummary {
+                batches_polled: 2,
+                posts_fetched: 35,
+                posts_in_window: 2,
+                new_posts: 1,
+                notifications_sent: 2,
+                failures: 1,
+                posts_suppressed: 0,
+                skipped: BTreeMap::new(),
+                deliveries_off_hours: 0,
+                deliveries_batched: 0,
+                duration: Duration::ZERO,
+            }
+        );
+
+        summary.finish(Instant::now());
+        let line = summary.to_string();
+        assert!(line.starts_with("Poll cycle complete: 2 batch(es), 35 post(s) fetched, 2 in window, 1 new, 2 notification(s) sent, 1 failure(s)"));
+    }
+
+    fn post(id: &str, subreddit: &str, stickied: bool) -> RedditPost {
+        RedditPost {
+            id: id.to_string(),
+            title: format!("Post {}", id),
+            subreddit: subreddit.to_string(),
+            permalink: None,
+            url: None,
+            stickied,
+            over_18: false,
+            score: 0,
+            num_comments: 0,
+            created_utc: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stickied_posts_skipped_unless_subscription_allows() {
+        let db = MockDatabaseService::with_test_data();
+        let posts = [
+            post("a", "rust", true),
+            post("b", "rust", false),
+            post("c", "programming", true),
+        ];
+
+        // Default: every subscription skips stickied posts
+        let allow = db.subreddits_allowing_stickied().await.unwrap();
+        let kept: Vec<&str> = posts
+            .iter()
+            .filter(|p| !is_skipped_sticky(p, &allow))
+            .map(|p| p.id.as_str())
+            .collect();
+        assert_eq!(kept, vec!["b"]);
+
+        // Turning the setting off for r/rust lets its stickied post through
+        assert!(!db.toggle_subscription_skip_stickied(1).await.unwrap());
+        let allow = db.subreddits_allowing_stickied().await.unwrap();
+        let kept: Vec<&str> = posts
+            .iter()
+            .filter(|p| !is_skipped_sticky(p, &allow))
+            .map(|p| p.id.as_str())
+            .collect();
+        assert_eq!(kept, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_stickied_defaults_to_false_when_missing() {
+        let json = r#"{"id":"x","title":"t","subreddit":"rust","created_utc":1700000000.0}"#;
+        let post: RedditPost = serde_json::from_str(json).unwrap();
+        assert!(!post.stickied);
+        assert!(!post.over_18);
+    }
+
+    /// Serve canned responses by request path from a local port
+    fn spawn_reddit_stub(respond: fn(&str) -> (u16, String)) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let (status, body) = respond(&path);
+                // A redirect's body is its target
+                let (location, body) = match status {
+                    300..=399 => (format!("Location: {}\r\n", body), String::new()),
+                    _ => (String::new(), body),
+                };
+                let response = format!(
+                    "HTTP/1.1 {} Stub\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    location,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    /// A fresh post from `subreddit` with id `{subreddit}_1`
+    fn post_json(subreddit: &str) -> serde_json::Value {
+        serde_json::json!({
+            "data": {
+                "id": format!("{}_1", subreddit),
+                "title": "Hello",
+                "subreddit": subreddit,
+                "created_utc": Utc::now().timestamp() as f64,
+            }
+        })
+    }
+
+    /// A listing containing one fresh post from `subreddit`
+    fn listing_json(subreddit: &str) -> String {
+        serde_json::json!({ "data": { "children": [post_json(subreddit)] } }).to_string()
+    }
+
+    fn test_client() -> Client {
+        Client::builder().no_proxy().build().unwrap()
+    }
+
+    /// A `discord` endpoint posting to the stub's `/hook`
+    async fn create_discord_endpoint(db: &MockDatabaseService, base: &str) -> i64 {
+        let config_json = serde_json::json!({ "webhook_url": format!("{}/hook", base) }).to_string();
+        db.create_endpoint("discord", &config_json, None).await.unwrap()
+    }
+
+    /// A subscription to `subreddit` linked to a new stub `discord` endpoint, as (subscription, endpoint) IDs
+    async fn setup_discord_subscription(db: &MockDatabaseService, base: &str, subreddit: &str) -> (i64, i64) {
+        let ep_id = create_discord_endpoint(db, base).await;
+        let sub_id = db.create_subscription(subreddit).await.unwrap();
+        db.link_subscription_endpoint(sub_id, ep_id).await.unwrap();
+        (sub_id, ep_id)
+    }
+
+    /// Poller settings for the stub at `base`, without waiting between batches
+    fn test_poller_config(base: &str) -> PollerConfig {
+        PollerConfig {
+            reddit_base: base.to_string(),
+            batch_spacing: Duration::from_millis(1),
+            ..PollerConfig::default()
+        }
+    }
+
+    fn test_rate_limiter() -> RateLimiter {
+        RateLimiter::new(10, Duration::from_millis(50))
+    }
+
+    /// Fields of each event logged while installed, by message
+    #[derive(Clone, Default)]
+    struct CapturedEvents(Arc<std::sync::Mutex<Vec<HashMap<String, String>>>>);
+
+    impl CapturedEvents {
+        fn find(&self, message_prefix: &str) -> Option<HashMap<String, String>> {
+            let events = self.0.lock().unwrap();
+            events.iter().find(|e| e.get("message").is_some_and(|m| m.starts_with(message_prefix))).cloned()
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for CapturedEvents {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            struct Fields(HashMap<String, String>);
+            impl tracing::field::Visit for Fields {
+                fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+                    self.0.insert(field.name().to_string(), value.to_string());
+                }
+                fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+                    self.0.insert(field.name().to_string(), format!("{:?}", value));
+                }
+            }
+            let mut fields = Fields(HashMap::new());
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+    }
+
+    #[test]
+    fn test_forbidden_reason_from_body() {
+        assert_eq!(ForbiddenReason::from_body(r#"{"reason": "private", "error": 403}"#), ForbiddenReason::Private);
+        assert_eq!(ForbiddenReason::from_body(r#"{"reason": "quarantined"}"#), ForbiddenReason::Quarantined);
+        assert_eq!(ForbiddenReason::from_body(r#"{"reason": "banned"}"#), ForbiddenReason::Banned);
+        assert_eq!(
+            ForbiddenReason::from_body("<html>blocked</html>"),
+            ForbiddenReason::Other("no reason given".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_sub_in_batch_does_not_drop_others() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust+secret+golang/") || path.starts_with("/r/secret/") {
+                (403, r#"{"reason": "private", "message": "Forbidden", "error": 403}"#.to_string())
+            } else if path.starts_with("/r/rust/") {
+                (200, listing_json("rust"))
+            } else if path.starts_with("/r/golang/") {
+                (200, listing_json("golang"))
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+        let batch = vec!["rust".to_string(), "secret".to_string(), "golang".to_string()];
+        let limiter = RateLimiter::new(10, Duration::from_millis(1));
+        let mut summary = CycleSummary::new();
+
+        let fetch = fetch_batch_posts(&test_client(), &limiter, &base, &batch, 100, &mut summary).await;
+
+        let subs: Vec<&str> = fetch.posts.iter().map(|p| p.subreddit.as_str()).collect();
+        assert_eq!(subs, vec!["rust", "golang"]);
+        assert_eq!(fetch.failed, vec!["secret".to_string()]);
+        assert_eq!(summary.failures, 1);
+        assert_eq!(summary.batches_polled, 2);
+    }
+
+    #[tokio::test]
+    async fn test_missing_sub_in_batch_falls_back_per_subreddit() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                (200, listing_json("rust"))
+            } else if path.starts_with("/r/golang/") {
+                (200, listing_json("golang"))
+            } else {
+                // The combined URL and r/gone both 404
+                (404, r#"{"message": "Not Found", "error": 404}"#.to_string())
+            }
+        });
+        let batch = vec!["rust".to_string(), "gone".to_string(), "golang".to_string()];
+        let limiter = RateLimiter::new(10, Duration::from_millis(1));
+        let mut summary = CycleSummary::new();
+
+        let fetch = fetch_batch_posts(&test_client(), &limiter, &base, &batch, 100, &mut summary).await;
+
+        let subs: Vec<&str> = fetch.posts.iter().map(|p| p.subreddit.as_str()).collect();
+        assert_eq!(subs, vec!["rust", "golang"]);
+        assert_eq!(fetch.failed, vec!["gone".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_batch_is_not_retried_or_muted() {
+        use std::sync::atomic::AtomicUsize;
+        static REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+        let base = spawn_reddit_stub(|_| {
+            REQUESTS.fetch_add(1, Ordering::SeqCst);
+            (503, "<html>Service Unavailable</html>".to_string())
+        });
+        let db = MockDatabaseService::new();
+        for sub in ["rust", "golang"] {
+            setup_discord_subscription(&db, &base, sub).await;
+        }
+        let config = PollerConfig { auto_mute_after: 2, ..test_poller_config(&base) };
+        let subreddits = vec!["rust".to_string(), "golang".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        // An outage lasting longer than the auto-mute threshold
+        for cycle in 1..=3 {
+            let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+            assert_eq!(summary.failures, 1);
+            // One combined request per cycle, without per-subreddit retries
+            assert_eq!(REQUESTS.load(Ordering::SeqCst), cycle);
+        }
+        assert_eq!(state.active_count(&subreddits), 2);
+        assert!(db.list_subscriptions().await.unwrap().iter().all(|s| !s.muted));
+
+        let mut summary = CycleSummary::new();
+        let fetch = fetch_batch_posts(&test_client(), &rate_limiter, &base, &subreddits, 100, &mut summary).await;
+        assert!(fetch.failed.is_empty());
+        assert_eq!(fetch.unchecked, subreddits);
+    }
+
+    #[tokio::test]
+    async fn test_paused_notifications_record_without_sending() {
+        use std::sync::atomic::AtomicUsize;
+        static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                (200, listing_json("rust"))
+            } else if path.starts_with("/hook") {
+                HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        setup_discord_subscription(&db, &base, "rust").await;
+        db.set_notifications_enabled(false).await.unwrap();
+
+        let config = test_poller_config(&base);
+        let mut state = PollerState::new(&config);
+        let summary = run_once(&db, &test_client(), &["rust".to_string()], &test_rate_limiter(), &config, &mut state).await.unwrap();
+
+        assert!(!config.notifications_enabled.load(Ordering::Relaxed));
+        assert_eq!((summary.new_posts, summary.notifications_sent), (1, 0));
+        assert!(meta_time(&db, META_LAST_POLL_AT).await.unwrap().is_some());
+        // Already recorded, so it won't be delivered after resuming either
+        assert!(!db.record_if_new("rust", "rust_1", "Hello", "").await.unwrap());
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_connection_errors_trigger_reconnect() {
+        let db = MockDatabaseService::new();
+        let mut state = PollerState::new(&PollerConfig::default());
+        let lost: anyhow::Error = anyhow::Error::from(DbError::Connection("disk I/O error".into()))
+            .context("Failed to fetch subreddit-endpoint mappings");
+
+        // Other errors don't count towards a reconnect
+        state.record_cycle_error(&db, &anyhow::anyhow!("bad row")).await;
+        for _ in 1..RECONNECT_AFTER_FAILURES {
+            state.record_cycle_error(&db, &lost).await;
+        }
+        assert_eq!(db.reconnects(), 0);
+        state.record_cycle_error(&db, &lost).await;
+        assert_eq!(db.reconnects(), 1);
+
+        // A successful cycle resets the count
+        state.record_cycle_error(&db, &lost).await;
+        state.record_cycle_success();
+        for _ in 1..RECONNECT_AFTER_FAILURES {
+            state.record_cycle_error(&db, &lost).await;
+        }
+        assert_eq!(db.reconnects(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_poller_recovers_after_reconnect() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                (200, listing_json("rust"))
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        setup_discord_subscription(&db, &base, "rust").await;
+        db.lose_connection();
+
+        let config = test_poller_config(&base);
+        let subreddits = vec!["rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        // Failed cycles lead to one reconnect, as in the poll loop
+        for _ in 0..RECONNECT_AFTER_FAILURES {
+            let err = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap_err();
+            state.record_cycle_error(&db, &err).await;
+        }
+        assert_eq!(db.reconnects(), 1);
+
+        // After which polling resumes
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent), (1, 1));
+        assert!(meta_time(&db, META_LAST_POLL_AT).await.unwrap().is_some());
+        assert!(!db.record_if_new("rust", "rust_1", "Hello", "").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_deliveries_recorded_after_send() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/golang/") {
+                (200, listing_json("golang"))
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let (_, ep_id) = setup_discord_subscription(&db, &base, "golang").await;
+
+        let config = test_poller_config(&base);
+        let mut state = PollerState::new(&config);
+        run_once(&db, &test_client(), &["golang".to_string()], &test_rate_limiter(), &config, &mut state).await.unwrap();
+
+        let stats = db.delivery_stats(1).await.unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!((stats[0].endpoint_id, stats[0].total, stats[0].succeeded), (ep_id, 1, 1));
+        assert!(stats[0].p95_latency_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_run_once_polls_and_notifies_once() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust+golang/") {
+                let children = [post_json("rust"), post_json("golang")];
+                (200, serde_json::json!({ "data": { "children": children } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let ep_id = create_discord_endpoint(&db, &base).await;
+        for sub in ["rust", "golang"] {
+            let sub_id = db.create_subscription(sub).await.unwrap();
+            db.link_subscription_endpoint(sub_id, ep_id).await.unwrap();
+        }
+
+        let config = test_poller_config(&base);
+        let subreddits = vec!["rust".to_string(), "golang".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(
+            (summary.batches_polled, summary.posts_fetched, summary.new_posts, summary.notifications_sent, summary.failures),
+            (1, 2, 2, 2, 0)
+        );
+        assert!(meta_time(&db, META_LAST_POLL_AT).await.unwrap().is_some());
+
+        // The same posts on the next cycle are already recorded
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_run_once_records_batches_on_worker_pool() {
+        // One fresh post per subreddit in the requested batch
+        let base = spawn_reddit_stub(|path| {
+            let Some(names) = path.strip_prefix("/r/").and_then(|p| p.split_once("/new.json")) else {
+                return (404, "{}".to_string());
+            };
+            let children: Vec<_> = names.0.split('+').map(post_json).collect();
+            (200, serde_json::json!({ "data": { "children": children } }).to_string())
+        });
+
+        let db = MockDatabaseService::new();
+        db.set_record_delay(Duration::from_millis(2));
+        let config = PollerConfig { batch_workers: 2, ..test_poller_config(&base) };
+        // Three batches: 100 + 100 + 50
+        let subreddits: Vec<String> = (0..250).map(|i| format!("s{:03}", i)).collect();
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.batches_polled, summary.posts_fetched, summary.new_posts), (3, 250, 250));
+        assert_eq!(summary.skipped.get(&SkipReason::NoEndpoints), Some(&250));
+        assert_eq!(db.list_notified_posts(1000, 0).await.unwrap().len(), 250);
+        // Batches overlapped, but never more than the configured workers
+        assert_eq!(db.peak_concurrent_records(), 2);
+    }
+
+    #[test]
+    fn test_workers_within_pool() {
+        assert_eq!(workers_within_pool(1, 5), 1);
+        assert_eq!(workers_within_pool(4, 5), 4);
+        assert_eq!(workers_within_pool(8, 5), 4);
+        // A single-connection pool still gets one worker
+        assert_eq!(workers_within_pool(3, 1), 1);
+        assert_eq!(workers_within_pool(0, 5), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sync_request_boosts_rate_limiter_once() {
+        let db = MockDatabaseService::new();
+        let config = PollerConfig { sync_burst: 5, ..PollerConfig::default() };
+        let rate_limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let mut state = PollerState::new(&config);
+
+        // A request left over from before the poller started is ignored
+        request_sync(&db).await.unwrap();
+        state.sync_handled = Utc::now() + TimeDelta::minutes(1);
+        run_once(&db, &test_client(), &[], &rate_limiter, &config, &mut state).await.unwrap();
+        assert!(!rate_limiter.is_boosted().await);
+
+        state.sync_handled = Utc::now() - TimeDelta::minutes(1);
+        run_once(&db, &test_client(), &[], &rate_limiter, &config, &mut state).await.unwrap();
+        assert!(rate_limiter.is_boosted().await);
+        // The initial token plus the burst
+        for _ in 0..6 {
+            assert!(rate_limiter.try_acquire().await);
+        }
+        assert!(!rate_limiter.try_acquire().await);
+
+        // The same request isn't granted twice
+        run_once(&db, &test_client(), &[], &rate_limiter, &config, &mut state).await.unwrap();
+        assert!(!rate_limiter.try_acquire().await);
+    }
+
+    #[tokio::test]
+    async fn test_run_once_counts_skip_reasons() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust+golang/") {
+                let post = |id: &str, subreddit: &str, age_hours: i64, stickied: bool| {
+                    serde_json::json!({ "data": {
+                        "id": id,
+                        "title": "Hello",
+                        "subreddit": subreddit,
+                        "stickied": stickied,
+                        "created_utc": (Utc::now() - TimeDelta::hours(age_hours)).timestamp() as f64,
+                    }})
+                };
+                let children = [
+                    post("fresh", "rust", 0, false),
+                    post("old", "rust", 48, false),
+                    post("rules", "rust", 0, true),
+                    post("unlinked", "golang", 0, false),
+                ];
+                (200, serde_json::json!({ "data": { "children": children } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        // r/golang has a subscription but no endpoint
+        let db = MockDatabaseService::new();
+        let ep_id = create_discord_endpoint(&db, &base).await;
+        let rust_id = db.create_subscription("rust").await.unwrap();
+        db.link_subscription_endpoint(rust_id, ep_id).await.unwrap();
+        db.create_subscription("golang").await.unwrap();
+
+        let config = test_poller_config(&base);
+        let subreddits = vec!["rust".to_string(), "golang".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(summary.notifications_sent, 1);
+        assert_eq!(
+            summary.skipped,
+            BTreeMap::from([(SkipReason::Stickied, 1), (SkipReason::OutsideWindow, 1), (SkipReason::NoEndpoints, 1)])
+        );
+        assert!(summary.to_string().ends_with("; skipped: 1 stickied, 1 outside window, 1 no endpoints"));
+
+        // Next cycle the recorded posts are already seen, and pausing doesn't change that
+        config.notifications_enabled.store(false, Ordering::Relaxed);
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(summary.skipped.get(&SkipReason::AlreadySeen), Some(&2));
+        assert_eq!(summary.skipped.get(&SkipReason::Paused), None);
+    }
+
+    #[tokio::test]
+    async fn test_poller_and_test_sends_share_the_notifier_profile() {
+        use crate::notifiers::{build_client, build_notifier, test_run::send_test_to_all};
+        use std::sync::atomic::AtomicUsize;
+        static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/hook") {
+                HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+            }
+            (500, "{}".to_string())
+        });
+
+        let db = MockDatabaseService::new();
+        create_discord_endpoint(&db, &base).await;
+        let endpoint = db.list_endpoints().await.unwrap().remove(0);
+
+        let profile = NotifierClientProfile {
+            max_attempts: 2,
+            base_backoff: Duration::from_millis(1),
+            ..NotifierClientProfile::default()
+        };
+
+        // A delivery from the poller
+        let ctx = SendContext {
+            subreddit: "rust",
+            title: "Hello",
+            url: "https://redd.it/x",
+            nsfw: false,
+            priority: false,
+        };
+        let mut summary = CycleSummary::new();
+        let client = build_client(&profile).unwrap();
+        notify_endpoints(&db, &client, &profile, None, &[&endpoint], "p1", &ctx, &mut summary).await;
+        assert_eq!((HOOK_CALLS.load(Ordering::SeqCst), summary.failures), (2, 1));
+
+        // The TUI's test send with the same profile makes the same attempts
+        let outcomes = send_test_to_all(vec![endpoint], &profile, |ep| build_notifier(ep, build_client(&profile)?, profile.footers)).await;
+        assert!(outcomes[0].result.is_err());
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_outside_active_hours_are_skipped() {
+        use crate::notifiers::build_client;
+        use std::sync::atomic::AtomicUsize;
+        static WORK_CALLS: AtomicUsize = AtomicUsize::new(0);
+        static OTHER_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/work") {
+                WORK_CALLS.fetch_add(1, Ordering::SeqCst);
+            } else {
+                OTHER_CALLS.fetch_add(1, Ordering::SeqCst);
+            }
+            (204, String::new())
+        });
+
+        // Windows around the current local time in two different timezones
+        let window = |tz: chrono_tz::Tz, from_hours: i64, to_hours: i64| {
+            let local = Utc::now().with_timezone(&tz);
+            let hhmm = |h: i64| (local + TimeDelta::hours(h)).format("%H:%M").to_string();
+            format!("{}-{}", hhmm(from_hours), hhmm(to_hours))
+        };
+        let db = MockDatabaseService::new();
+        let work = serde_json::json!({
+            "webhook_url": format!("{}/work", base),
+            "active_hours": window(chrono_tz::Asia::Tokyo, 1, 2),
+            "timezone": "Asia/Tokyo",
+        });
+        let on_call = serde_json::json!({
+            "webhook_url": format!("{}/on-call", base),
+            "active_hours": window(chrono_tz::America::New_York, -1, 1),
+            "timezone": "America/New_York",
+        });
+        let always = serde_json::json!({ "webhook_url": format!("{}/always", base) });
+        for config in [work, on_call, always] {
+            db.create_endpoint("discord", &config.to_string(), None).await.unwrap();
+        }
+        let endpoints = db.list_endpoints().await.unwrap();
+        assert!(!endpoint_is_active(&endpoints[0], Utc::now()));
+        assert!(endpoint_is_active(&endpoints[0], Utc::now() + TimeDelta::minutes(90)));
+
+        let ctx = SendContext {
+            subreddit: "rust",
+            title: "Hello",
+            url: "https://redd.it/x",
+            nsfw: false,
+            priority: false,
+        };
+        let profile = NotifierClientProfile::default();
+        let mut summary = CycleSummary::new();
+        let client = build_client(&profile).unwrap();
+        let refs: Vec<&EndpointRow> = endpoints.iter().collect();
+        notify_endpoints(&db, &client, &profile, None, &refs, "p1", &ctx, &mut summary).await;
+
+        // The work channel is held back; the others get the post right away
+        assert_eq!((WORK_CALLS.load(Ordering::SeqCst), OTHER_CALLS.load(Ordering::SeqCst)), (0, 2));
+        assert_eq!((summary.notifications_sent, summary.deliveries_off_hours), (2, 1));
+        assert!(summary.to_string().ends_with("; skipped: 1 delivery(s) outside active hours"));
+    }
+
+    #[tokio::test]
+    async fn test_run_once_records_poll_and_success_times() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                (200, listing_json("rust"))
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        // The hook 404s, so the first cycle has a failed notification
+        setup_discord_subscription(&db, &base, "rust").await;
+
+        let config = test_poller_config(&base);
+        let subreddits = vec!["rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert!(summary.failures > 0);
+        let last_poll = meta_time(&db, META_LAST_POLL_AT).await.unwrap().unwrap();
+        assert!((Utc::now() - last_poll).num_seconds().abs() <= 2);
+        assert_eq!(meta_time(&db, META_LAST_SUCCESS_AT).await.unwrap(), None);
+
+        // Nothing new to send on the next cycle, so it succeeds
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(summary.failures, 0);
+        assert!(meta_time(&db, META_LAST_SUCCESS_AT).await.unwrap().is_some());
+    }
+
+    #[test]
+    fn test_notification_cap_counts_per_subreddit() {
+        let mut cap = NotificationCap::new(2);
+        assert!(cap.allow("rust") && cap.allow("rust"));
+        assert!(!cap.allow("rust"));
+        assert!(cap.allow("golang"));
+        assert!(!cap.allow("rust"));
+        assert_eq!(
+            cap.suppression_warnings(),
+            vec!["Suppressed 2 further post(s) from r/rust this cycle (limit: 2 per subreddit)"]
+        );
+
+        let mut unlimited = NotificationCap::new(0);
+        assert!((0..500).all(|_| unlimited.allow("rust")));
+        assert!(unlimited.suppression_warnings().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_once_caps_notifications_per_subreddit() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                // A flood: a full listing of fresh posts
+                let children: Vec<serde_json::Value> = (0..100)
+                    .map(|i| {
+                        let mut post = post_json("rust");
+                        post["data"]["id"] = format!("flood_{}", i).into();
+                        post
+                    })
+                    .collect();
+                (200, serde_json::json!({ "data": { "children": children } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        setup_discord_subscription(&db, &base, "rust").await;
+
+        let config = PollerConfig { max_notifications_per_subreddit_per_cycle: 5, ..test_poller_config(&base) };
+        let subreddits = vec!["rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(
+            (summary.new_posts, summary.notifications_sent, summary.posts_suppressed, summary.failures),
+            (100, 5, 95, 0)
+        );
+
+        // Suppressed posts were recorded, so they don't fire on the next cycle
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent, summary.posts_suppressed), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_startup_throttle_only_limits_the_startup_cycle() {
+        let config = PollerConfig { startup_max_notifications: 2, ..PollerConfig::default() };
+        let mut throttle = StartupThrottle::for_cycle(&config, true);
+        assert!(throttle.allow() && throttle.allow());
+        assert!(!throttle.allow() && !throttle.allow());
+        assert_eq!(throttle.suppressed(), 2);
+
+        let mut later = StartupThrottle::for_cycle(&config, false);
+        assert!((0..100).all(|_| later.allow()));
+
+        // Suppressing wins over a cap; neither set means no limit
+        let config = PollerConfig { startup_suppress: true, ..config };
+        assert!(!StartupThrottle::for_cycle(&config, true).allow());
+        assert!((0..100).all(|_| StartupThrottle::for_cycle(&PollerConfig::default(), true).allow()));
+    }
+
+    #[tokio::test]
+    async fn test_notify_events_carry_structured_fields() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let base = spawn_reddit_stub(|_| (500, "{}".to_string()));
+        let db = MockDatabaseService::new();
+        let ep_id = create_discord_endpoint(&db, &base).await;
+        let endpoint = db.list_endpoints().await.unwrap().remove(0);
+        let profile = NotifierClientProfile { max_attempts: 1, ..NotifierClientProfile::default() };
+        let ctx = SendContext { subreddit: "rust", title: "Hello", url: "https://redd.it/x", nsfw: false, priority: false };
+
+        let events = CapturedEvents::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(events.clone()));
+        let mut summary = CycleSummary::new();
+        notify_endpoints(&db, &test_client(), &profile, None, &[&endpoint], "abc123", &ctx, &mut summary).await;
+        summary.record_skip(SkipReason::Stickied, "def456", "golang", "stickied");
+
+        let failed = events.find("Notify error").expect("notify error logged");
+        assert_eq!(failed["endpoint_id"], ep_id.to_string());
+        assert_eq!(failed["kind"], "discord");
+        assert_eq!(failed["item_id"], "abc123");
+        assert_eq!(failed["subreddit"], "rust");
+        assert!(failed["error"].contains("500"), "{:?}", failed);
+
+        let skipped = events.find("Skipping post").expect("skip logged");
+        assert_eq!((skipped["post_id"].as_str(), skipped["reason"].as_str()), ("def456", "stickied"));
+    }
+
+    #[tokio::test]
+    async fn test_batched_notifications_wait_for_the_flush() {
+        use std::sync::atomic::AtomicUsize;
+        static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+        static LISTINGS: AtomicUsize = AtomicUsize::new(0);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/new.json") {
+                // A new post every cycle
+                let mut post = post_json("rust");
+                post["data"]["id"] = format!("rust_{}", LISTINGS.fetch_add(1, Ordering::SeqCst)).into();
+                (200, serde_json::json!({ "data": { "children": [post] } }).to_string())
+            } else if path.starts_with("/hook") {
+                HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let (_, ep_id) = setup_discord_subscription(&db, &base, "rust").await;
+
+        let batcher = Batcher::new(Duration::from_secs(600), 20);
+        let config = PollerConfig { batcher: Some(batcher.clone()), ..test_poller_config(&base) };
+        let subreddits = vec!["rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        // Two cycles queue two posts without sending anything
+        for _ in 0..2 {
+            let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+            assert_eq!((summary.new_posts, summary.notifications_sent, summary.deliveries_batched), (1, 0, 1));
+        }
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 0);
+
+        // One message for both, with a delivery recorded per post
+        batcher.flush_all(&db, &test_client(), &config.notifier_profile).await;
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 1);
+        let stats = db.delivery_stats(1).await.unwrap();
+        assert_eq!((stats[0].endpoint_id, stats[0].total, stats[0].succeeded), (ep_id, 2, 2));
+    }
+
+    #[tokio::test]
+    async fn test_long_batch_is_split_across_messages() {
+        use crate::batching::{flush_batch, Batch};
+        use std::sync::atomic::AtomicUsize;
+        static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/hook") {
+                HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+        let db = MockDatabaseService::new();
+        let ep_id = create_discord_endpoint(&db, &base).await;
+        let posts = (0..20)
+            .map(|i| BatchedPost {
+                item_id: format!("p{}", i),
+                subreddit: "rust".to_string(),
+                title: "A very long title ".repeat(16),
+                url: format!("https://redd.it/p{}", i),
+                nsfw: false,
+            })
+            .collect();
+        let batch = Batch { endpoint: db.list_endpoints().await.unwrap().remove(0), posts };
+
+        let failed = flush_batch(&db, &test_client(), &NotifierClientProfile::default(), &batch).await;
+
+        // Several messages under Discord's limit, with every post delivered once
+        assert_eq!(failed, 0);
+        assert!(HOOK_CALLS.load(Ordering::SeqCst) > 1);
+        let stats = db.delivery_stats(1).await.unwrap();
+        assert_eq!((stats[0].endpoint_id, stats[0].total, stats[0].succeeded), (ep_id, 20, 20));
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_sends_batched_notifications() {
+        use std::sync::atomic::AtomicUsize;
+        static HOOK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust+golang/") {
+                let children = [post_json("rust"), post_json("golang")];
+                (200, serde_json::json!({ "data": { "children": children } }).to_string())
+            } else if path.starts_with("/hook") {
+                HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let ep_id = create_discord_endpoint(&db, &base).await;
+        for sub in ["rust", "golang"] {
+            let sub_id = db.create_subscription(sub).await.unwrap();
+            db.link_subscription_endpoint(sub_id, ep_id).await.unwrap();
+        }
+
+        // A window far longer than the run, as with NOTIFY_BATCH_WINDOW_SECS set
+        let config = PollerConfig { batcher: Some(Batcher::new(Duration::from_secs(600), 20)), ..test_poller_config(&base) };
+        let subreddits = vec!["rust".to_string(), "golang".to_string()];
+        let mut state = PollerState::new(&config);
+
+        let summary = run_once_and_flush(&db, &test_client(), &subreddits, &test_rate_limiter(), &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.deliveries_batched, summary.failures), (2, 2, 0));
+
+        // Both posts went out in one message before returning
+        assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 1);
+        let stats = db.delivery_stats(1).await.unwrap();
+        assert_eq!((stats[0].endpoint_id, stats[0].total, stats[0].succeeded), (ep_id, 2, 2));
+        assert_eq!(config.batcher.as_ref().unwrap().pending_posts(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_subscription_polls_search_listing() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/new.json") {
+                (200, listing_json("rust"))
+            } else if path.starts_with("/search.json?q=tokio&sort=new&limit=") {
+                // Finds the post already sent for r/rust, and one from elsewhere
+                let children = vec![post_json("rust"), post_json("golang")];
+                (200, serde_json::json!({ "data": { "children": children } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let (_, ep_id) = setup_discord_subscription(&db, &base, "rust").await;
+        let search = crate::search::SearchQuery::parse("tokio", false).unwrap();
+        let search_id = db.create_search_subscription("", &search).await.unwrap();
+        db.link_subscription_endpoint(search_id, ep_id).await.unwrap();
+
+        let config = test_poller_config(&base);
+        let subreddits = db.unique_subreddits().await.unwrap();
+        assert_eq!(subreddits, vec!["rust".to_string()]);
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.batches_polled, summary.new_posts, summary.notifications_sent, summary.failures), (2, 2, 2, 0));
+        assert_eq!(state.active_searches, 1);
+        let mut recorded: Vec<_> = db.list_notified_posts(10, 0).await.unwrap().into_iter().map(|p| p.post_id).collect();
+        recorded.sort();
+        assert_eq!(recorded, vec!["golang_1".to_string(), "rust_1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_first_cycle_respects_startup_cap() {
+        use std::sync::atomic::AtomicUsize;
+        static LISTINGS: AtomicUsize = AtomicUsize::new(0);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/golang+rust/") {
+                // Ten fresh posts per subreddit, new ones every cycle
+                let cycle = LISTINGS.fetch_add(1, Ordering::SeqCst);
+                let children: Vec<serde_json::Value> = ["rust", "golang"]
+                    .iter()
+                    .flat_map(|sub| {
+                        (0..10).map(move |i| {
+                            let mut post = post_json(sub);
+                            post["data"]["id"] = format!("{}_{}_{}", sub, cycle, i).into();
+                            post
+                        })
+                    })
+                    .collect();
+                (200, serde_json::json!({ "data": { "children": children } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let ep_id = create_discord_endpoint(&db, &base).await;
+        for subreddit in ["golang", "rust"] {
+            let sub_id = db.create_subscription(subreddit).await.unwrap();
+            db.link_subscription_endpoint(sub_id, ep_id).await.unwrap();
+        }
+
+        let config = PollerConfig { startup_max_notifications: 3, ..test_poller_config(&base) };
+        let subreddits = vec!["golang".to_string(), "rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        // The cap is shared by both subreddits
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(
+            (summary.new_posts, summary.notifications_sent, summary.posts_suppressed, summary.failures),
+            (20, 3, 17, 0)
+        );
+
+        // Later cycles are only subject to the per-subreddit cap
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent, summary.posts_suppressed), (20, 20, 0));
+    }
+
+    #[tokio::test]
+    async fn test_run_once_sends_oldest_first() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                // Newest first, as /new returns them, with one post out of place
+                let now = Utc::now().timestamp();
+                let children: Vec<serde_json::Value> = [("b", 120), ("c", 60), ("a", 180), ("d", 0)]
+                    .iter()
+                    .map(|(id, age)| {
+                        let mut post = post_json("rust");
+                        post["data"]["id"] = (*id).into();
+                        post["data"]["created_utc"] = ((now - age) as f64).into();
+                        post
+                    })
+                    .collect();
+                (200, serde_json::json!({ "data": { "children": children } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let subreddits = vec!["rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        for (order, expected) in [
+            (NotificationOrder::OldestFirst, ["a", "b", "c", "d"]),
+            (NotificationOrder::Listing, ["b", "c", "a", "d"]),
+        ] {
+            let db = MockDatabaseService::new();
+            setup_discord_subscription(&db, &base, "rust").await;
+
+            let config = PollerConfig { notification_order: order, ..test_poller_config(&base) };
+            let mut state = PollerState::new(&config);
+            run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+
+            assert_eq!(db.delivered_post_ids(), expected, "{:?}", order);
+            // Recording still follows the listing
+            let recorded: Vec<String> = db.recent_stored_posts(10, None).await.unwrap().into_iter().map(|p| p.post_id).collect();
+            assert_eq!(recorded, ["b", "c", "a", "d"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscription_matches_post_subreddit_case_insensitively() {
+        let base = spawn_reddit_stub(|path| {
+            // Polled by its lowercased name; Reddit answers with the canonical case
+            if path.starts_with("/r/rust/") {
+                (200, listing_json("Rust"))
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        setup_discord_subscription(&db, &base, "rUst").await;
+
+        let config = test_poller_config(&base);
+        let subreddits = db.unique_subreddits().await.unwrap();
+        assert_eq!(subreddits, vec!["rust".to_string()]);
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_mixed_case_subscription_is_auto_muted() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                (200, listing_json("rust"))
+            } else {
+                // The combined URL and r/askreddit
+                (404, r#"{"message": "Not Found", "error": 404}"#.to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        for subreddit in ["AskReddit", "askreddit", "Rust"] {
+            setup_discord_subscription(&db, &base, subreddit).await;
+        }
+        let subreddits = db.unique_subreddits().await.unwrap();
+        assert_eq!(subreddits, vec!["askreddit".to_string(), "rust".to_string()]);
+
+        let config = PollerConfig { auto_mute_after: 1, ..test_poller_config(&base) };
+        let mut state = PollerState::new(&config);
+        run_once(&db, &test_client(), &subreddits, &test_rate_limiter(), &config, &mut state).await.unwrap();
+
+        // Both spellings of r/AskReddit are muted, r/Rust keeps polling
+        let muted: Vec<(String, bool)> = db.list_subscriptions().await.unwrap().into_iter().map(|s| (s.subreddit, s.muted)).collect();
+        assert_eq!(
+            muted,
+            vec![("AskReddit".to_string(), true), ("askreddit".to_string(), true), ("Rust".to_string(), false)]
+        );
+        assert_eq!(db.unique_subreddits().await.unwrap(), vec!["rust".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_score_alert_follow_up_after_threshold_crossed() {
+        use std::sync::atomic::AtomicI64;
+        static SCORE: AtomicI64 = AtomicI64::new(40);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                let mut post = post_json("rust");
+                post["data"]["score"] = SCORE.load(Ordering::SeqCst).into();
+                (200, serde_json::json!({ "data": { "children": [post] } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let (sub_id, _) = setup_discord_subscription(&db, &base, "rust").await;
+        db.set_subscription_score_thresholds(sub_id, &[100, 1000]).await.unwrap();
+
+        let config = test_poller_config(&base);
+        let subreddits = vec!["rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        // Cycle 1: first seen below every threshold, the usual notification only
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent), (1, 1));
+
+        // Cycle 2: crossed 100 points, one follow-up
+        SCORE.store(150, Ordering::SeqCst);
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent), (0, 1));
+
+        // Cycle 3: still above 100, nothing new to report
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent), (0, 0));
+
+        // Turning alerts off restores first-seen-only behaviour
+        db.set_subscription_score_thresholds(sub_id, &[]).await.unwrap();
+        SCORE.store(5000, Ordering::SeqCst);
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(summary.notifications_sent, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rising_alert_once_when_velocity_exceeds_threshold() {
+        use std::sync::atomic::AtomicI64;
+        static SCORE: AtomicI64 = AtomicI64::new(40);
+
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/") {
+                let mut post = post_json("rust");
+                post["data"]["score"] = SCORE.load(Ordering::SeqCst).into();
+                (200, serde_json::json!({ "data": { "children": [post] } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let (sub_id, _) = setup_discord_subscription(&db, &base, "rust").await;
+        db.set_subscription_rising_threshold(sub_id, Some(200)).await.unwrap();
+
+        let config = test_poller_config(&base);
+        let subreddits = vec!["rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        // Cycle 1: first seen, the usual notification and a first sample
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent), (1, 1));
+
+        // Cycle 2 right after: too soon to compare samples
+        SCORE.store(400, Ordering::SeqCst);
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(summary.notifications_sent, 0);
+
+        // Half an hour later: +160 points is 320 points/h, one follow-up
+        let mut metrics = db.post_metrics("rust", "rust_1").await.unwrap().unwrap();
+        assert_eq!(metrics.sample.score, 40);
+        metrics.sample.sampled_at -= TimeDelta::minutes(30);
+        db.record_post_metrics("rust", "rust_1", &metrics).await.unwrap();
+        SCORE.store(200, Ordering::SeqCst);
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.new_posts, summary.notifications_sent), (0, 1));
+        assert!(db.post_metrics("rust", "rust_1").await.unwrap().unwrap().alerted);
+
+        // Still growing, but a post is reported as rising only once
+        let mut metrics = db.post_metrics("rust", "rust_1").await.unwrap().unwrap();
+        metrics.sample.sampled_at -= TimeDelta::minutes(30);
+        db.record_post_metrics("rust", "rust_1", &metrics).await.unwrap();
+        SCORE.store(2000, Ordering::SeqCst);
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(summary.notifications_sent, 0);
+    }
+
+    #[tokio::test]
+    async fn test_comment_keywords_notify_once_per_matching_comment() {
+        let base = spawn_reddit_stub(|path| {
+            if path.starts_with("/r/rust/new.json") {
+                (200, serde_json::json!({ "data": { "children": [] } }).to_string())
+            } else if path.starts_with("/r/rust/comments.json") {
+                let comment = |id: &str, body: &str| {
+                    serde_json::json!({ "data": {
+                        "id": id,
+                        "subreddit": "rust",
+                        "body": body,
+                        "permalink": format!("/r/rust/comments/abc/t/{}/", id),
+                        "created_utc": Utc::now().timestamp() as f64,
+                    }})
+                };
+                let children = [comment("c1", "Tokio is great"), comment("c2", "unrelated")];
+                (200, serde_json::json!({ "data": { "children": children } }).to_string())
+            } else if path.starts_with("/hook") {
+                (204, String::new())
+            } else {
+                (404, "{}".to_string())
+            }
+        });
+
+        let db = MockDatabaseService::new();
+        let (sub_id, _) = setup_discord_subscription(&db, &base, "rust").await;
+
+        let config = test_poller_config(&base);
+        let subreddits = vec!["rust".to_string()];
+        let rate_limiter = test_rate_limiter();
+        let mut state = PollerState::new(&config);
+
+        // Off by default: comments aren't fetched
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(summary.notifications_sent, 0);
+
+        let keywords = KeywordSet::parse(KeywordMode::Word, "tokio").unwrap();
+        db.set_subscription_comment_keywords(sub_id, Some(&keywords)).await.unwrap();
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!((summary.notifications_sent, summary.failures), (1, 0));
+
+        let summary = run_once(&db, &test_client(), &subreddits, &rate_limiter, &config, &mut state).await.unwrap();
+        assert_eq!(summary.notifications_sent, 0);
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_mappings_cached_until_config_changes() {
+        let db = MockDatabaseService::new();
+        let ep_id = db.create_endpoint("discord", r#"{"webhook_url":"https://example.com/hook"}"#, None).await.unwrap();
+        let rust = db.create_subscription("rust").await.unwrap();
+        db.link_subscription_endpoint(rust, ep_id).await.unwrap();
+        let mut state = PollerState::new(&PollerConfig::default());
+
+        let first = state.endpoint_mappings(&db).await.unwrap();
+        let second = state.endpoint_mappings(&db).await.unwrap();
+        assert_eq!(db.mapping_queries(), 1);
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Linking another subscription bumps the version; the next read sees it
+        let golang = db.create_subscription("golang").await.unwrap();
+        db.link_subscription_endpoint(golang, ep_id).await.unwrap();
+        let third = state.endpoint_mappings(&db).await.unwrap();
+        assert_eq!(db.mapping_queries(), 2);
+        assert!(third.contains_key("golang"));
+
+        state.endpoint_mappings(&db).await.unwrap();
+        assert_eq!(db.mapping_queries(), 2);
+    }
+
+    #[test]
+    fn test_failure_tracker_reports_streak_once() {
+        let batch = vec!["rust".to_string(), "gone".to_string()];
+        let gone = vec!["gone".to_string()];
+        let mut tracker = FailureTracker::new(3);
+
+        assert!(tracker.record(&batch, &gone).is_empty());
+        assert!(tracker.record(&batch, &gone).is_empty());
+        assert_eq!(tracker.record(&batch, &gone), gone);
+        assert!(tracker.record(&batch, &gone).is_empty());
+
+        // A cycle the subreddit wasn't checked in (e.g. Reddit was down) doesn't reset it
+        let mut tracker = FailureTracker::new(2);
+        tracker.record(&batch, &gone);
+        tracker.record(&batch[..1], &[]);
+        assert_eq!(tracker.record(&batch, &gone), gone);
+
+        // A successful cycle resets the streak
+        tracker.record(&batch, &[]);
+        assert!(tracker.record(&batch, &gone).is_empty());
+
+        // Threshold 0 disables reporting
+        let mut off = FailureTracker::new(0);
+        for _ in 0..5 {
+            assert!(off.record(&batch, &gone).is_empty());
+        }
     }
 }
@@ -2,6 +2,20 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+/// Requests per minute Reddit allows a client; a boost never takes the poller past this
+pub const REDDIT_MAX_REQUESTS_PER_MINUTE: u32 = 60;
+
+/// How long a manual sync's boost lasts
+pub const SYNC_BOOST_WINDOW: Duration = Duration::from_secs(60);
+
+/// Extra requests a manual sync may make within `SYNC_BOOST_WINDOW`
+///
+/// The configured rate keeps refilling during the boost, so only the
+/// difference to Reddit's limit is granted on top.
+pub fn sync_burst(requests_per_minute: u32) -> u32 {
+    REDDIT_MAX_REQUESTS_PER_MINUTE.saturating_sub(requests_per_minute)
+}
+
 /// A simple token bucket rate limiter
 ///
 /// This rate limiter allows a certain number of operations per time window.
@@ -17,6 +31,8 @@ pub struct RateLimiter {
 struct RateLimiterState {
     tokens: u32,
     last_refill: Instant,
+    /// Temporary raise of the bucket size, and when it ends
+    boost: Option<(u32, Instant)>,
 }
 
 impl RateLimiter {
@@ -43,6 +59,7 @@ impl RateLimiter {
                 // After the first request, proper rate limiting kicks in.
                 tokens: 1,
                 last_refill: Instant::now(),
+                boost: None,
             })),
             max_tokens,
             refill_rate,
@@ -55,29 +72,64 @@ impl RateLimiter {
     /// Once a token is available, it's consumed and the function returns.
     pub async fn acquire(&self) {
         loop {
-            let mut state = self.state.lock().await;
-
-            // Refill tokens based on elapsed time
-            let now = Instant::now();
-            let elapsed = now.duration_since(state.last_refill);
-            let tokens_to_add = (elapsed.as_millis() / self.refill_rate.as_millis()) as u32;
-
-            if tokens_to_add > 0 {
-                state.tokens = (state.tokens + tokens_to_add).min(self.max_tokens);
-                state.last_refill = now;
-            }
-
-            // If we have tokens, consume one and return
-            if state.tokens > 0 {
-                state.tokens -= 1;
+            if self.try_acquire().await {
                 return;
             }
 
-            // No tokens available, release the lock and wait before trying again
-            drop(state);
+            // No tokens available, wait before trying again
             tokio::time::sleep(self.refill_rate / 2).await;
         }
     }
+
+    /// Consume a token if one is available, without waiting
+    ///
+    /// Returns false when the bucket is empty. Useful for debouncing: calls
+    /// made faster than the refill rate are simply skipped.
+    pub async fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().await;
+
+        // An expired boost takes its unused tokens with it
+        let now = Instant::now();
+        if state.boost.is_some_and(|(_, until)| now >= until) {
+            state.boost = None;
+            state.tokens = state.tokens.min(self.max_tokens);
+        }
+        let max_tokens = self.max_tokens + state.boost.map_or(0, |(extra, _)| extra);
+
+        // Refill tokens based on elapsed time
+        let elapsed = now.duration_since(state.last_refill);
+        let tokens_to_add = (elapsed.as_millis() / self.refill_rate.as_millis()) as u32;
+
+        if tokens_to_add > 0 {
+            state.tokens = (state.tokens + tokens_to_add).min(max_tokens);
+            state.last_refill = now;
+        }
+
+        // If we have tokens, consume one
+        if state.tokens > 0 {
+            state.tokens -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Grant `extra_tokens` right away, on top of the bucket size, for `duration`
+    ///
+    /// Used for a one-time catch-up (`reddit-notifier sync`). Tokens still
+    /// unused when the boost ends are dropped and the configured rate applies
+    /// again. A new boost replaces one that is still running.
+    pub async fn boost(&self, extra_tokens: u32, duration: Duration) {
+        let mut state = self.state.lock().await;
+        let base = state.tokens.min(self.max_tokens);
+        state.tokens = base + extra_tokens;
+        state.boost = Some((extra_tokens, Instant::now() + duration));
+    }
+
+    /// Whether a boost is running
+    pub async fn is_boosted(&self) -> bool {
+        self.state.lock().await.boost.is_some_and(|(_, until)| Instant::now() < until)
+    }
 }
 
 #[cfg(test)]
@@ -121,6 +173,44 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_try_acquire_skips_instead_of_waiting() {
+        let limiter = RateLimiter::new(1, Duration::from_millis(100));
+
+        assert!(limiter.try_acquire().await);
+        assert!(!limiter.try_acquire().await);
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(limiter.try_acquire().await);
+    }
+
+    #[tokio::test]
+    async fn test_boost_grants_extra_tokens_then_reverts() {
+        let limiter = RateLimiter::new(1, Duration::from_millis(100));
+        assert!(limiter.try_acquire().await);
+        assert!(!limiter.try_acquire().await);
+
+        limiter.boost(3, Duration::from_millis(200)).await;
+        assert!(limiter.is_boosted().await);
+        for _ in 0..3 {
+            assert!(limiter.try_acquire().await);
+        }
+        assert!(!limiter.try_acquire().await);
+
+        // Once the boost ends the bucket holds at most the configured one token again
+        tokio::time::sleep(Duration::from_millis(350)).await;
+        assert!(!limiter.is_boosted().await);
+        assert!(limiter.try_acquire().await);
+        assert!(!limiter.try_acquire().await);
+    }
+
+    #[test]
+    fn test_sync_burst_stays_within_reddit_limit() {
+        assert_eq!(sync_burst(4), 56);
+        assert_eq!(sync_burst(45), 15);
+        assert_eq!(sync_burst(90), 0);
+    }
+
     #[tokio::test]
     async fn test_rate_limiter_refills_over_time() {
         let limiter = RateLimiter::new(5, Duration::from_millis(100));
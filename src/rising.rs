@@ -0,0 +1,123 @@
+//! Follow-up notifications for posts that are growing fast ("rising")
+//!
+//! Opt-in per subscription. A listing only shows a post's current score, so
+//! the poller samples the score and comment count of each young post in the
+//! `post_metrics` table and compares every cycle with the stored sample. A post
+//! still within its first `RISING_WINDOW_HOURS` whose score grows by at least
+//! the subscription's threshold (points per hour) gets one follow-up.
+//!
+//! Only the latest sample per post is kept, and samples of posts past the
+//! window are pruned every cycle, so the table stays small.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// How long after posting a post can still be reported as rising
+pub const RISING_WINDOW_HOURS: i64 = 6;
+
+/// Minimum time between the two samples a velocity is computed from
+///
+/// Poll cycles can be a minute apart, where a handful of votes would look
+/// like a huge hourly rate; a newer sample doesn't replace one younger than this.
+pub const MIN_SAMPLE_GAP_MINUTES: i64 = 10;
+
+/// A post's score and comment count at one point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricSample {
+    pub score: i64,
+    pub num_comments: i64,
+    pub sampled_at: DateTime<Utc>,
+}
+
+/// The stored sample of a tracked post
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PostMetrics {
+    pub sample: MetricSample,
+    /// Whether the post was already reported as rising
+    pub alerted: bool,
+}
+
+/// Growth between two samples, per hour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Velocity {
+    pub points_per_hour: f64,
+    pub comments_per_hour: f64,
+}
+
+/// Parse a rising threshold in points per hour
+pub fn parse_rising_threshold(input: &str) -> Result<i64> {
+    match input.trim().parse::<i64>() {
+        Ok(t) if t > 0 => Ok(t),
+        _ => bail!("invalid rising threshold '{}' (expected a positive whole number of points per hour)", input.trim()),
+    }
+}
+
+/// Growth per hour from `previous` to `current`
+///
+/// `None` unless `current` was sampled at least `MIN_SAMPLE_GAP_MINUTES` later.
+pub fn velocity(previous: &MetricSample, current: &MetricSample) -> Option<Velocity> {
+    let elapsed = current.sampled_at.signed_duration_since(previous.sampled_at);
+    if elapsed < TimeDelta::minutes(MIN_SAMPLE_GAP_MINUTES) {
+        return None;
+    }
+    let hours = elapsed.num_milliseconds() as f64 / 3_600_000.0;
+    Some(Velocity {
+        points_per_hour: (current.score - previous.score) as f64 / hours,
+        comments_per_hour: (current.num_comments - previous.num_comments) as f64 / hours,
+    })
+}
+
+/// Whether a post of age `post_age` growing at `velocity` counts as rising
+pub fn is_rising(velocity: &Velocity, threshold: i64, post_age: TimeDelta) -> bool {
+    post_age <= TimeDelta::hours(RISING_WINDOW_HOURS) && velocity.points_per_hour >= threshold as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(score: i64, num_comments: i64, sampled_at: DateTime<Utc>) -> MetricSample {
+        MetricSample { score, num_comments, sampled_at }
+    }
+
+    #[test]
+    fn test_parse_rising_threshold() {
+        assert_eq!(parse_rising_threshold(" 200 ").unwrap(), 200);
+        assert!(parse_rising_threshold("0").is_err());
+        assert!(parse_rising_threshold("fast").is_err());
+    }
+
+    #[test]
+    fn test_velocity_between_two_samples() {
+        let start = Utc::now();
+        let previous = sample(10, 2, start);
+
+        // +50 points and +5 comments in 30 minutes
+        let current = sample(60, 7, start + TimeDelta::minutes(30));
+        let v = velocity(&previous, &current).unwrap();
+        assert_eq!(v.points_per_hour, 100.0);
+        assert_eq!(v.comments_per_hour, 10.0);
+
+        // Losing votes is negative growth
+        let v = velocity(&previous, &sample(0, 2, start + TimeDelta::hours(2))).unwrap();
+        assert_eq!(v.points_per_hour, -5.0);
+        assert_eq!(v.comments_per_hour, 0.0);
+    }
+
+    #[test]
+    fn test_velocity_needs_samples_far_enough_apart() {
+        let start = Utc::now();
+        let previous = sample(10, 2, start);
+        assert_eq!(velocity(&previous, &sample(20, 2, start + TimeDelta::minutes(1))), None);
+        assert_eq!(velocity(&previous, &sample(20, 2, start - TimeDelta::minutes(30))), None);
+        assert!(velocity(&previous, &sample(20, 2, start + TimeDelta::minutes(MIN_SAMPLE_GAP_MINUTES))).is_some());
+    }
+
+    #[test]
+    fn test_is_rising_only_within_window() {
+        let fast = Velocity { points_per_hour: 250.0, comments_per_hour: 40.0 };
+        assert!(is_rising(&fast, 200, TimeDelta::hours(1)));
+        assert!(!is_rising(&fast, 300, TimeDelta::hours(1)));
+        assert!(!is_rising(&fast, 200, TimeDelta::hours(RISING_WINDOW_HOURS + 1)));
+    }
+}
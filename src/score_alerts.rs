@@ -0,0 +1,80 @@
+//! Follow-up notifications when a post's score crosses configured thresholds
+//!
+//! Opt-in per subscription. The poller normally notifies once, when a post is
+//! first seen; with thresholds set it also remembers the highest "bucket" each
+//! post reached (how many thresholds its score meets) and sends one follow-up
+//! per bucket the post climbs into.
+
+use anyhow::{bail, Result};
+
+/// Parse a comma-separated threshold list ("100, 1000") into sorted, unique scores
+///
+/// An empty string yields an empty list (alerts off).
+pub fn parse_score_thresholds(input: &str) -> Result<Vec<i64>> {
+    let mut thresholds = Vec::new();
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.parse::<i64>() {
+            Ok(t) if t > 0 => thresholds.push(t),
+            _ => bail!("invalid score threshold '{}' (expected a positive whole number)", part),
+        }
+    }
+    thresholds.sort_unstable();
+    thresholds.dedup();
+    Ok(thresholds)
+}
+
+/// Format thresholds the way `parse_score_thresholds` reads them
+pub fn format_score_thresholds(thresholds: &[i64]) -> String {
+    thresholds.iter().map(i64::to_string).collect::<Vec<_>>().join(",")
+}
+
+/// Number of (sorted) `thresholds` that `score` has reached
+pub fn score_bucket(thresholds: &[i64], score: i64) -> i64 {
+    thresholds.iter().take_while(|t| score >= **t).count() as i64
+}
+
+/// The highest threshold newly reached when a post moves from `previous` to `bucket`
+///
+/// `None` when the post didn't climb (or was never tracked before, so there is
+/// nothing to follow up on).
+pub fn crossed_threshold(thresholds: &[i64], previous: Option<i64>, bucket: i64) -> Option<i64> {
+    match previous {
+        Some(previous) if bucket > previous => thresholds.get(bucket as usize - 1).copied(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_score_thresholds() {
+        assert_eq!(parse_score_thresholds("1000, 100,100").unwrap(), vec![100, 1000]);
+        assert!(parse_score_thresholds("").unwrap().is_empty());
+        assert!(parse_score_thresholds("100,lots").is_err());
+        assert!(parse_score_thresholds("0").is_err());
+        assert_eq!(format_score_thresholds(&[100, 1000]), "100,1000");
+    }
+
+    #[test]
+    fn test_threshold_crossed_between_two_cycles() {
+        let thresholds = [100, 1000];
+
+        // Cycle 1: first seen at 40 points, no follow-up
+        let first = score_bucket(&thresholds, 40);
+        assert_eq!(first, 0);
+        assert_eq!(crossed_threshold(&thresholds, None, first), None);
+
+        // Cycle 2: 150 points crosses 100
+        let second = score_bucket(&thresholds, 150);
+        assert_eq!(crossed_threshold(&thresholds, Some(first), second), Some(100));
+
+        // Staying in (or dropping below) the bucket doesn't alert again
+        assert_eq!(crossed_threshold(&thresholds, Some(second), score_bucket(&thresholds, 180)), None);
+        assert_eq!(crossed_threshold(&thresholds, Some(second), score_bucket(&thresholds, 90)), None);
+
+        // Jumping several buckets at once reports the highest one
+        assert_eq!(crossed_threshold(&thresholds, Some(0), score_bucket(&thresholds, 5000)), Some(1000));
+    }
+}
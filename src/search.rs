@@ -0,0 +1,248 @@
+//! Subscriptions to a Reddit search instead of a whole subreddit
+//!
+//! A search subscription watches `/search.json?q=...&sort=new`, either within
+//! its subreddit (`restrict_sr`) or across all of Reddit. Search listings
+//! can't be combined like `/new` listings, so each one costs one extra
+//! rate-limited request per poll cycle. Matches are deduplicated through
+//! `notified_posts` like any other post, so a post already sent for a normal
+//! subscription isn't sent again.
+
+use anyhow::Result;
+use chrono::{TimeDelta, Utc};
+use reqwest::{Client, Url};
+use std::fmt;
+use std::sync::atomic::Ordering;
+use tracing::{debug, error, warn};
+
+use crate::models::database::EndpointRow;
+use crate::notifiers::SendContext;
+use crate::poller::{
+    fetch_listing, notify_endpoints, resolve_post_url, CycleSummary, DeliveryGuard, PollerConfig, SkipReason,
+    POST_WINDOW_HOURS,
+};
+use crate::rate_limiter::RateLimiter;
+use crate::services::DatabaseService;
+
+/// Subreddit stored for searches across all of Reddit
+pub const ALL_SUBREDDITS: &str = "all";
+
+/// Longest search query accepted, in characters (Reddit's own limit is 512)
+pub const MAX_QUERY_LEN: usize = 512;
+
+/// A saved search: the query, and whether it's limited to the subscription's subreddit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub query: String,
+    pub restrict_sr: bool,
+}
+
+impl SearchQuery {
+    /// Trim and check a query typed by the user
+    pub fn parse(query: &str, restrict_sr: bool) -> Result<Self, String> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err("search query is empty".to_string());
+        }
+        if query.chars().count() > MAX_QUERY_LEN {
+            return Err(format!("search query is longer than {} characters", MAX_QUERY_LEN));
+        }
+        Ok(Self { query: query.to_string(), restrict_sr })
+    }
+}
+
+/// An unmuted search subscription and its active endpoints
+#[derive(Debug, Clone)]
+pub struct SearchSubscription {
+    pub id: i64,
+    /// The subreddit searched, or `ALL_SUBREDDITS`
+    pub subreddit: String,
+    pub search: SearchQuery,
+    pub skip_stickied: bool,
+    pub priority: bool,
+    pub endpoints: Vec<EndpointRow>,
+}
+
+impl fmt::Display for SearchSubscription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.search.restrict_sr {
+            write!(f, "search \"{}\" in r/{}", self.search.query, self.subreddit)
+        } else {
+            write!(f, "search \"{}\" on all of Reddit", self.search.query)
+        }
+    }
+}
+
+/// Build the newest-first search URL for a subscription
+///
+/// E.g. `https://www.reddit.com/r/rust/search.json?q=site%3Agithub.com&sort=new&restrict_sr=1&limit=100`,
+/// or `https://www.reddit.com/search.json?q=...&sort=new&limit=100` across all of Reddit.
+pub fn build_search_url(reddit_base: &str, subreddit: &str, search: &SearchQuery, listing_limit: u32) -> Result<Url> {
+    let mut url = if search.restrict_sr {
+        Url::parse(&format!("{}/r/{}/search.json", reddit_base, subreddit))?
+    } else {
+        Url::parse(&format!("{}/search.json", reddit_base))?
+    };
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("q", &search.query).append_pair("sort", "new");
+        if search.restrict_sr {
+            query.append_pair("restrict_sr", "1");
+        }
+        query.append_pair("limit", &listing_limit.to_string());
+    }
+    Ok(url)
+}
+
+/// Fetch each search's newest results and notify on posts not seen before
+///
+/// Called once per poll cycle after the post batches. Each search waits for
+/// `rate_limiter`; fetch, database and notification errors are logged and
+/// counted in `summary` without stopping the other searches.
+pub async fn poll_searches<D: DatabaseService>(
+    db: &D,
+    client: &Client,
+    rate_limiter: &RateLimiter,
+    config: &PollerConfig,
+    searches: &[SearchSubscription],
+    delivery_guard: &mut DeliveryGuard,
+    summary: &mut CycleSummary,
+) {
+    for search in searches {
+        if search.endpoints.is_empty() {
+            debug!("No endpoints for {}, not polling it", search);
+            continue;
+        }
+        let url = match build_search_url(&config.reddit_base, &search.subreddit, &search.search, config.listing_limit) {
+            Ok(url) => url,
+            Err(e) => {
+                warn!("Skipping {} (subscription {}): {}", search, search.id, e);
+                summary.record_failure();
+                continue;
+            }
+        };
+
+        rate_limiter.acquire().await;
+        let posts = match fetch_listing(client, url.as_str()).await {
+            Ok(posts) => posts,
+            Err(e) => {
+                warn!(url = %url, error = %e, "Reddit GET {} -> {}", url, e);
+                summary.record_failure();
+                continue;
+            }
+        };
+        debug!("Fetched {} result(s) for {}", posts.len(), search);
+        summary.record_batch(posts.len());
+
+        for post in posts {
+            let subreddit = &post.subreddit;
+            if search.skip_stickied && post.stickied {
+                summary.record_skip(SkipReason::Stickied, &post.id, subreddit, "stickied");
+                continue;
+            }
+            let age = Utc::now().signed_duration_since(post.created_utc);
+            if age.abs() > TimeDelta::hours(POST_WINDOW_HOURS) {
+                summary.record_skip(SkipReason::OutsideWindow, &post.id, subreddit, "outside window");
+                continue;
+            }
+            summary.record_in_window();
+
+            let url = resolve_post_url(&post);
+            let is_new = match db.record_if_new(subreddit, &post.id, &post.title, &url).await {
+                Ok(new) => new,
+                Err(e) => {
+                    error!(
+                        post_id = %post.id,
+                        subreddit = %subreddit,
+                        error = %e,
+                        "Failed to record post {} for r/{}: {} - skipping this post",
+                        post.id, subreddit, e
+                    );
+                    summary.record_failure();
+                    continue;
+                }
+            };
+            if !is_new {
+                summary.record_skip(SkipReason::AlreadySeen, &post.id, subreddit, "already seen");
+                continue;
+            }
+            summary.record_new_post();
+            if !config.notifications_enabled.load(Ordering::Relaxed) {
+                summary.record_skip(SkipReason::Paused, &post.id, subreddit, "notifications paused");
+                continue;
+            }
+
+            let unique_endpoints = delivery_guard.unclaimed(&post.id, &search.endpoints);
+            debug!(
+                post_id = %post.id,
+                subreddit = %subreddit,
+                subscription_id = search.id,
+                endpoints = unique_endpoints.len(),
+                "Post {} in r/{} matches {} -> notifying {} endpoint(s)",
+                post.id, subreddit, search, unique_endpoints.len()
+            );
+            let ctx = SendContext {
+                subreddit,
+                title: &post.title,
+                url: &url,
+                nsfw: post.over_18,
+                priority: search.priority,
+            };
+            notify_endpoints(db, client, &config.notifier_profile, config.batcher.as_ref(), &unique_endpoints, &post.id, &ctx, summary).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::reddit_api::RedditListing;
+
+    #[test]
+    fn test_search_url_within_subreddit() {
+        let search = SearchQuery::parse(" site:github.com subreddit:rust ", true).unwrap();
+        let url = build_search_url("https://www.reddit.com", "rust", &search, 100).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://www.reddit.com/r/rust/search.json?q=site%3Agithub.com+subreddit%3Arust&sort=new&restrict_sr=1&limit=100"
+        );
+    }
+
+    #[test]
+    fn test_search_url_across_reddit() {
+        let search = SearchQuery::parse("\"tokio console\" & more", false).unwrap();
+        let url = build_search_url("https://www.reddit.com", ALL_SUBREDDITS, &search, 25).unwrap();
+        assert_eq!(url.path(), "/search.json");
+        let pairs: Vec<(String, String)> = url.query_pairs().map(|(k, v)| (k.into_owned(), v.into_owned())).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("q".to_string(), "\"tokio console\" & more".to_string()),
+                ("sort".to_string(), "new".to_string()),
+                ("limit".to_string(), "25".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_and_overlong_queries() {
+        assert!(SearchQuery::parse("  ", true).is_err());
+        assert!(SearchQuery::parse(&"x".repeat(MAX_QUERY_LEN + 1), false).unwrap_err().contains("longer"));
+    }
+
+    #[test]
+    fn test_search_listing_parses_as_posts() {
+        // Search results use the same listing shape as /new, from any subreddit
+        let body = r#"{"kind": "Listing", "data": {"after": null, "children": [
+            {"kind": "t3", "data": {"id": "abc", "title": "tokio-console 0.2", "subreddit": "rust",
+             "permalink": "/r/rust/comments/abc/tokio_console_02/", "url": "https://github.com/tokio-rs/console",
+             "created_utc": 1760000000.0, "score": 12}},
+            {"kind": "t3", "data": {"id": "def", "title": "Console tooling", "subreddit": "programming",
+             "permalink": "/r/programming/comments/def/console_tooling/", "created_utc": 1760000100.0}}
+        ]}}"#;
+        let listing: RedditListing = serde_json::from_str(body).unwrap();
+        let posts: Vec<_> = listing.data.children.into_iter().map(|c| c.data).collect();
+        assert_eq!(posts.iter().map(|p| p.subreddit.as_str()).collect::<Vec<_>>(), vec!["rust", "programming"]);
+        assert_eq!(resolve_post_url(&posts[0]), "https://www.reddit.com/r/rust/comments/abc/tokio_console_02/");
+        assert!(!posts[1].stickied && posts[1].url.is_none());
+    }
+}
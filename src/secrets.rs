@@ -0,0 +1,162 @@
+//! Indirect secrets in endpoint configs
+//!
+//! A string value in an endpoint's `config_json` may name where the secret
+//! lives instead of holding it: `file:/run/secrets/discord_webhook` reads the
+//! file (trailing newline trimmed) and `env:PUSHOVER_TOKEN` reads an
+//! environment variable. References are resolved each time a notifier is
+//! built, so mounted Docker/Kubernetes secrets can be rotated without editing
+//! the database.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+/// Prefix for a value read from a file
+pub const FILE_PREFIX: &str = "file:";
+
+/// Prefix for a value read from an environment variable
+pub const ENV_PREFIX: &str = "env:";
+
+/// True when `value` is a `file:` or `env:` reference rather than a literal
+pub fn is_secret_ref(value: &str) -> bool {
+    value.starts_with(FILE_PREFIX) || value.starts_with(ENV_PREFIX)
+}
+
+/// Resolve a single value; literals are returned unchanged
+///
+/// Errors name the file or variable but never include the secret itself.
+pub fn resolve_secret(value: &str) -> Result<String> {
+    if let Some(path) = value.strip_prefix(FILE_PREFIX) {
+        let contents = std::fs::read_to_string(path).with_context(|| format!("failed to read secret file {}", path))?;
+        let secret = contents.trim_end_matches(['\r', '\n']);
+        if secret.is_empty() {
+            bail!("secret file {} is empty", path);
+        }
+        return Ok(secret.to_string());
+    }
+
+    if let Some(name) = value.strip_prefix(ENV_PREFIX) {
+        let secret = std::env::var(name).ok().with_context(|| format!("environment variable {} is not set", name))?;
+        if secret.is_empty() {
+            bail!("environment variable {} is empty", name);
+        }
+        return Ok(secret);
+    }
+
+    Ok(value.to_string())
+}
+
+/// Resolve every string field of an endpoint config, including nested objects
+/// like `extra_headers` and lists like `webhook_urls`
+///
+/// Returns the config JSON with references replaced by their values; the
+/// error says which field could not be resolved (e.g. `extra_headers.Authorization`
+/// or `webhook_urls[1]`).
+pub fn resolve_config_secrets(config_json: &str) -> Result<String> {
+    let mut config: Value = serde_json::from_str(config_json)?;
+    let Some(fields) = config.as_object_mut() else {
+        return Ok(config_json.to_string());
+    };
+
+    let mut changed = false;
+    for (key, value) in fields.iter_mut() {
+        match value {
+            Value::String(s) if is_secret_ref(s) => {
+                *s = resolve_secret(s).with_context(|| format!("cannot resolve {}", key))?;
+                changed = true;
+            }
+            Value::Object(nested) => {
+                for (name, value) in nested.iter_mut() {
+                    if let Value::String(s) = value {
+                        if is_secret_ref(s) {
+                            *s = resolve_secret(s).with_context(|| format!("cannot resolve {}.{}", key, name))?;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for (i, value) in items.iter_mut().enumerate() {
+                    if let Value::String(s) = value {
+                        if is_secret_ref(s) {
+                            *s = resolve_secret(s).with_context(|| format!("cannot resolve {}[{}]", key, i))?;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !changed {
+        return Ok(config_json.to_string());
+    }
+    Ok(serde_json::to_string(&config)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_secret(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("reddit-notifier-secret-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_literal_values_unchanged() {
+        assert_eq!(resolve_secret("https://discord.com/api/webhooks/1/abc").unwrap(), "https://discord.com/api/webhooks/1/abc");
+        let json = r#"{"token":"t","user":"u"}"#;
+        assert_eq!(resolve_config_secrets(json).unwrap(), json);
+    }
+
+    #[test]
+    fn test_file_reference_trims_trailing_newline() {
+        let path = temp_secret("token", "s3cret\n");
+        assert_eq!(resolve_secret(&format!("file:{}", path.display())).unwrap(), "s3cret");
+
+        let json = format!(r#"{{"token":"file:{}","user":"u"}}"#, path.display());
+        let resolved: Value = serde_json::from_str(&resolve_config_secrets(&json).unwrap()).unwrap();
+        assert_eq!(resolved["token"], "s3cret");
+        assert_eq!(resolved["user"], "u");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_env_reference() {
+        // PATH is set in every test environment
+        let path = std::env::var("PATH").unwrap();
+        assert_eq!(resolve_secret("env:PATH").unwrap(), path);
+
+        // Nested values such as extra headers are resolved too
+        let resolved: Value = serde_json::from_str(&resolve_config_secrets(r#"{"extra_headers":{"X-Path":"env:PATH"}}"#).unwrap()).unwrap();
+        assert_eq!(resolved["extra_headers"]["X-Path"], path.as_str());
+
+        // ...and so are lists such as several Discord webhooks
+        let resolved: Value = serde_json::from_str(&resolve_config_secrets(r#"{"webhook_urls":["https://a","env:PATH"]}"#).unwrap()).unwrap();
+        assert_eq!(resolved["webhook_urls"], serde_json::json!(["https://a", path]));
+    }
+
+    #[test]
+    fn test_missing_sources_are_errors() {
+        let err = resolve_secret("file:/nonexistent/reddit-notifier-secret").unwrap_err();
+        assert!(err.to_string().contains("failed to read secret file /nonexistent/reddit-notifier-secret"));
+
+        let err = resolve_secret("env:REDDIT_NOTIFIER_SURELY_UNSET_VAR").unwrap_err();
+        assert_eq!(err.to_string(), "environment variable REDDIT_NOTIFIER_SURELY_UNSET_VAR is not set");
+
+        let empty = temp_secret("empty", "\n");
+        assert!(resolve_secret(&format!("file:{}", empty.display())).unwrap_err().to_string().contains("is empty"));
+        std::fs::remove_file(empty).unwrap();
+
+        let err = resolve_config_secrets(r#"{"webhook_url":"env:REDDIT_NOTIFIER_SURELY_UNSET_VAR"}"#).unwrap_err();
+        assert_eq!(format!("{:#}", err), "cannot resolve webhook_url: environment variable REDDIT_NOTIFIER_SURELY_UNSET_VAR is not set");
+
+        let err = resolve_config_secrets(r#"{"extra_headers":{"X-Auth":"env:REDDIT_NOTIFIER_SURELY_UNSET_VAR"}}"#).unwrap_err();
+        assert!(format!("{:#}", err).starts_with("cannot resolve extra_headers.X-Auth: "));
+
+        let err = resolve_config_secrets(r#"{"webhook_urls":["https://a","env:REDDIT_NOTIFIER_SURELY_UNSET_VAR"]}"#).unwrap_err();
+        assert!(format!("{:#}", err).starts_with("cannot resolve webhook_urls[1]: "));
+    }
+}
@@ -1,8 +1,16 @@
 use async_trait::async_trait;
 use anyhow::Result;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
-use crate::models::database::{EndpointRow, NotifiedPostRow, SubscriptionRow};
+use crate::keywords::KeywordSet;
+use crate::models::database::{
+    DeliveryFailures, DeliveryStats, DeliveryStatus, EndpointRow, IntegrityReport, LastDeliveryError, NewEndpoint, NewSubscription,
+    NotifiedPostRow, StoredPost, SubscriptionRow,
+};
+use crate::rising::PostMetrics;
+use crate::search::{SearchQuery, SearchSubscription};
 
 /// DatabaseService trait defines all database operations needed by the TUI and poller.
 ///
@@ -25,12 +33,47 @@ pub trait DatabaseService: Send + Sync {
     /// The ID of the newly created subscription
     async fn create_subscription(&self, subreddit: &str) -> Result<i64>;
 
+    /// Create a subscription to a Reddit search, within `subreddit` or (unrestricted) all of Reddit
+    ///
+    /// # Returns
+    /// The ID of the newly created subscription
+    async fn create_search_subscription(&self, subreddit: &str, search: &SearchQuery) -> Result<i64>;
+
     /// Delete a subscription by ID (cascade deletes junction table links)
     async fn delete_subscription(&self, id: i64) -> Result<()>;
 
     /// Get all endpoints linked to a specific subscription
     async fn get_subscription_endpoints(&self, subscription_id: i64) -> Result<Vec<EndpointRow>>;
 
+    /// Toggle whether a subscription skips stickied posts
+    ///
+    /// # Returns
+    /// The new setting (true = stickied posts are skipped)
+    async fn toggle_subscription_skip_stickied(&self, id: i64) -> Result<bool>;
+
+    /// Toggle whether a subscription is high priority
+    ///
+    /// # Returns
+    /// The new setting (true = notifications include the endpoint's mention)
+    async fn toggle_subscription_priority(&self, id: i64) -> Result<bool>;
+
+    /// Set a subscription's score alert thresholds (empty = first-seen notifications only)
+    async fn set_subscription_score_thresholds(&self, id: i64, thresholds: &[i64]) -> Result<()>;
+
+    /// Set a subscription's rising threshold in points per hour (`None` = rising alerts off)
+    async fn set_subscription_rising_threshold(&self, id: i64, threshold: Option<i64>) -> Result<()>;
+
+    /// Set the keywords a subscription watches for in new comments, and how they match (`None` = posts only)
+    async fn set_subscription_comment_keywords(&self, id: i64, keywords: Option<&KeywordSet>) -> Result<()>;
+
+    /// Toggle a subscription's muted status
+    ///
+    /// Muted subscriptions keep their endpoint links but are skipped by the poller.
+    ///
+    /// # Returns
+    /// The new muted status (true = muted)
+    async fn toggle_subscription_muted(&self, id: i64) -> Result<bool>;
+
     // ========================================================================
     // Endpoint Operations
     // ========================================================================
@@ -88,6 +131,14 @@ pub trait DatabaseService: Send + Sync {
         endpoint_id: i64,
     ) -> Result<()>;
 
+    /// Replace a subscription's endpoint links with exactly `endpoint_ids` (one transaction)
+    async fn set_subscription_endpoints(&self, subscription_id: i64, endpoint_ids: &[i64]) -> Result<()>;
+
+    /// Create endpoints, subscriptions and their links, all or nothing (one transaction)
+    ///
+    /// `NewSubscription::endpoints` index into `endpoints`.
+    async fn import_rows(&self, endpoints: &[NewEndpoint], subscriptions: &[NewSubscription]) -> Result<()>;
+
     // ========================================================================
     // Notified Posts Operations
     // ========================================================================
@@ -106,29 +157,186 @@ pub trait DatabaseService: Send + Sync {
     /// Delete a notified post by ID
     async fn delete_notified_post(&self, id: i64) -> Result<()>;
 
+    /// Delete every notified post recorded for `subreddit` (exact name, as listed in the logs)
+    ///
+    /// Score alert state and notified comments for the subreddit are cleared
+    /// too. Posts still inside the poller's 24h window can be notified again.
+    ///
+    /// # Returns
+    /// Number of notified posts deleted
+    async fn delete_notified_posts_by_subreddit(&self, subreddit: &str) -> Result<u64>;
+
     /// Clean up old notified posts, deleting records older than the specified number of days
     ///
     /// # Returns
     /// Number of records deleted
     async fn cleanup_old_posts(&self, days_to_keep: i64) -> Result<u64>;
 
+    /// Number of notified posts per subreddit, most first
+    async fn get_post_statistics(&self) -> Result<Vec<(String, i64)>>;
+
+    /// When each subreddit's most recent notified post was first seen
+    async fn last_notified_at_per_subreddit(&self) -> Result<HashMap<String, DateTime<Utc>>>;
+
+    // ========================================================================
+    // Delivery History
+    // ========================================================================
+
+    /// Record the outcome of sending one post to one endpoint
+    async fn record_delivery(
+        &self,
+        endpoint_id: i64,
+        post_id: &str,
+        status: DeliveryStatus,
+        latency_ms: i64,
+        error: Option<&str>,
+    ) -> Result<()>;
+
+    /// Success counts and p95 latency per endpoint over the last `days` days
+    async fn delivery_stats(&self, days: i64) -> Result<Vec<DeliveryStats>>;
+
+    /// Endpoints with failed deliveries since `since`, most failures first
+    async fn recent_delivery_failures(&self, since: DateTime<Utc>) -> Result<Vec<DeliveryFailures>>;
+
+    /// The most recent failed delivery to an endpoint, with the post it was for
+    ///
+    /// # Returns
+    /// None if no failed delivery to the endpoint is recorded
+    async fn last_delivery_error(&self, endpoint_id: i64) -> Result<Option<LastDeliveryError>>;
+
+    /// Delete delivery records older than `days_to_keep` days
+    ///
+    /// # Returns
+    /// Number of records deleted
+    async fn cleanup_old_deliveries(&self, days_to_keep: i64) -> Result<u64>;
+
+    /// Write the whole notification history to `writer` as CSV
+    ///
+    /// The SQLite implementation streams rows instead of loading them all.
+    ///
+    /// # Returns
+    /// Number of rows written (excluding the header)
+    async fn export_notified_posts_stream(&self, writer: &mut (dyn std::io::Write + Send)) -> Result<u64>;
+
     // ========================================================================
     // Poller-Specific Operations
     // ========================================================================
 
-    /// Get list of unique subreddits that have active endpoints
+    /// Get list of unique subreddits that have active endpoints (muted and search subscriptions excluded)
     async fn unique_subreddits(&self) -> Result<Vec<String>>;
 
+    /// Get unmuted subscriptions that have no active linked endpoint (never polled)
+    async fn subscriptions_without_active_endpoints(&self) -> Result<Vec<SubscriptionRow>>;
+
+    /// Find orphaned subscription-endpoint links, unparseable subscription settings
+    /// and active endpoints with an unknown kind or invalid config
+    async fn integrity_problems(&self) -> Result<IntegrityReport>;
+
+    /// Repair what `integrity_problems` finds: delete orphaned links, reset bad
+    /// settings and deactivate broken endpoints
+    ///
+    /// # Returns
+    /// The problems that were repaired
+    async fn fix_integrity_problems(&self) -> Result<IntegrityReport>;
+
     /// Fetch all subreddit-to-endpoints mappings in a single query
     ///
-    /// Returns a HashMap where keys are subreddit names and values are vectors
-    /// of active endpoints subscribed to that subreddit.
+    /// Returns a HashMap where keys are lowercased subreddit names and values are vectors
+    /// of active endpoints subscribed to that subreddit. Muted subscriptions are excluded.
     async fn all_subreddit_endpoint_mappings(&self)
         -> Result<HashMap<String, Vec<EndpointRow>>>;
 
-    /// Record a post as notified if it's new
+    /// Get subreddits (lowercased) for which at least one subscription wants stickied posts
+    async fn subreddits_allowing_stickied(&self) -> Result<HashSet<String>>;
+
+    /// Get subreddits (lowercased) with at least one active priority subscription
+    async fn priority_subreddits(&self) -> Result<HashSet<String>>;
+
+    /// Score alert thresholds of unmuted subscriptions, keyed by lowercased subreddit
+    async fn score_thresholds_by_subreddit(&self) -> Result<HashMap<String, Vec<i64>>>;
+
+    /// Store the score bucket a post reached (keeping the highest seen)
+    ///
+    /// # Returns
+    /// The previously stored bucket, or `None` if the post wasn't tracked yet
+    async fn record_score_bucket(&self, subreddit: &str, post_id: &str, bucket: i64) -> Result<Option<i64>>;
+
+    /// Rising thresholds of unmuted subscriptions, keyed by lowercased subreddit (lowest wins)
+    async fn rising_thresholds_by_subreddit(&self) -> Result<HashMap<String, i64>>;
+
+    /// The stored metrics sample of a post, if it is being tracked
+    async fn post_metrics(&self, subreddit: &str, post_id: &str) -> Result<Option<PostMetrics>>;
+
+    /// Store a post's latest metrics sample, replacing the previous one
+    async fn record_post_metrics(&self, subreddit: &str, post_id: &str, metrics: &PostMetrics) -> Result<()>;
+
+    /// Delete metrics samples not updated in the last `hours`
+    ///
+    /// # Returns
+    /// Number of samples deleted
+    async fn prune_post_metrics(&self, hours: i64) -> Result<u64>;
+
+    /// Comment keywords of unmuted subscriptions, keyed by lowercased subreddit
+    async fn comment_keywords_by_subreddit(&self) -> Result<HashMap<String, Vec<KeywordSet>>>;
+
+    /// Unmuted search subscriptions with at least one active endpoint, and those endpoints
+    async fn search_subscriptions(&self) -> Result<Vec<SearchSubscription>>;
+
+    /// Record a comment as notified if it's new
+    ///
+    /// # Returns
+    /// `true` if the comment was newly inserted, `false` if it already existed
+    async fn record_comment_if_new(&self, subreddit: &str, comment_id: &str) -> Result<bool>;
+
+    /// Record a post as notified if it's new, keeping its title and URL for re-sends
     ///
     /// # Returns
     /// `true` if the post was newly inserted, `false` if it already existed
-    async fn record_if_new(&self, subreddit: &str, post_id: &str) -> Result<bool>;
+    async fn record_if_new(&self, subreddit: &str, post_id: &str, title: &str, url: &str) -> Result<bool>;
+
+    /// The `limit` most recently notified posts with stored details, oldest first
+    ///
+    /// `subreddit` (case-insensitive) restricts the posts to one subreddit.
+    async fn recent_stored_posts(&self, limit: i64, subreddit: Option<&str>) -> Result<Vec<StoredPost>>;
+
+    /// Mute every subscription to `subreddit` (used to auto-mute persistently failing subreddits)
+    ///
+    /// # Returns
+    /// Number of subscriptions that were newly muted
+    async fn mute_subreddit(&self, subreddit: &str) -> Result<u64>;
+
+    /// Read a runtime `meta` value (see `poller::META_LAST_POLL_AT`), or `None` if never written
+    async fn get_meta(&self, key: &str) -> Result<Option<String>>;
+
+    /// Insert or replace a runtime `meta` value
+    async fn set_meta(&self, key: &str, value: &str) -> Result<()>;
+
+    /// Whether notifications are delivered (false = paused globally)
+    async fn notifications_enabled(&self) -> Result<bool>;
+
+    /// Pause or resume delivery of all notifications; posts are still recorded while paused
+    async fn set_notifications_enabled(&self, enabled: bool) -> Result<()>;
+
+    /// Version of the subscription/endpoint configuration, bumped by every change to it
+    ///
+    /// Lets the poller reuse data derived from the configuration until it changes.
+    async fn config_version(&self) -> Result<i64>;
+
+    /// Replace the connection pool with a freshly opened one
+    ///
+    /// Used by the poller when the database keeps failing with connection
+    /// errors (e.g. a network filesystem dropped out).
+    ///
+    /// # Returns
+    /// `false` if this service has no way to reconnect
+    async fn reconnect(&self) -> Result<bool>;
+
+    /// Write a consistent copy of the database to `path`, which must not exist yet
+    async fn backup_to(&self, path: &Path) -> Result<()>;
+
+    /// Replace all data with the contents of the backup at `path`
+    ///
+    /// Fails, leaving the data untouched, if the backup's schema version
+    /// differs from the database's.
+    async fn restore_from(&self, path: &Path) -> Result<()>;
 }
@@ -0,0 +1,175 @@
+//! Typed errors for the database layer
+//!
+//! `DatabaseService` methods keep returning `anyhow::Result`, but failures are
+//! wrapped in a `DbError` so callers can `downcast_ref::<DbError>()` and react to
+//! the kind of failure (e.g. show "already exists" instead of a raw SQLite string).
+
+use std::fmt;
+
+/// Category of a database failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbError {
+    /// The requested row does not exist
+    NotFound(String),
+    /// A unique/primary key constraint was violated
+    Conflict(String),
+    /// Input was rejected before reaching the database (e.g. too large)
+    Invalid(String),
+    /// The database could not be reached (pool closed/timed out, file locked, I/O)
+    Connection(String),
+    /// Any other failure
+    Other(String),
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(msg) => write!(f, "Not found: {}", msg),
+            Self::Conflict(msg) => write!(f, "Already exists: {}", msg),
+            Self::Invalid(msg) => write!(f, "Invalid: {}", msg),
+            Self::Connection(msg) => write!(f, "Database unavailable: {}", msg),
+            Self::Other(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+// SQLite primary result codes that indicate the database itself is unreachable
+const SQLITE_BUSY: &str = "5";
+const SQLITE_LOCKED: &str = "6";
+const SQLITE_IOERR: &str = "10";
+const SQLITE_CANTOPEN: &str = "14";
+
+impl From<sqlx::Error> for DbError {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            sqlx::Error::RowNotFound => Self::NotFound(err.to_string()),
+            sqlx::Error::Database(db_err) => {
+                if db_err.is_unique_violation() {
+                    return Self::Conflict(db_err.message().to_string());
+                }
+                // Extended result codes keep the primary code in the low byte
+                let primary_code = db_err
+                    .code()
+                    .and_then(|c| c.parse::<i32>().ok())
+                    .map(|c| (c & 0xff).to_string());
+                match primary_code.as_deref() {
+                    Some(SQLITE_BUSY) | Some(SQLITE_LOCKED) | Some(SQLITE_IOERR)
+                    | Some(SQLITE_CANTOPEN) => Self::Connection(db_err.message().to_string()),
+                    _ => Self::Other(db_err.message().to_string()),
+                }
+            }
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed => Self::Connection(err.to_string()),
+            _ => Self::Other(err.to_string()),
+        }
+    }
+}
+
+impl DbError {
+    /// Convert an `anyhow::Error` carrying a `sqlx::Error` into one carrying a `DbError`
+    ///
+    /// Errors that don't originate from sqlx are returned unchanged.
+    pub fn wrap(err: anyhow::Error) -> anyhow::Error {
+        match err.downcast::<sqlx::Error>() {
+            Ok(sqlx_err) => DbError::from(sqlx_err).into(),
+            Err(other) => other,
+        }
+    }
+
+    /// Find the `DbError` inside an `anyhow::Error`, if any
+    pub fn from_anyhow(err: &anyhow::Error) -> Option<&DbError> {
+        err.downcast_ref::<DbError>()
+    }
+}
+
+/// Render a user-facing description of a database error
+///
+/// Falls back to the error's own message when it isn't a `DbError`.
+pub fn describe_error(err: &anyhow::Error) -> String {
+    match DbError::from_anyhow(err) {
+        Some(DbError::NotFound(_)) => "the item no longer exists".to_string(),
+        Some(DbError::Conflict(_)) => "it already exists".to_string(),
+        Some(DbError::Invalid(msg)) => msg.clone(),
+        Some(DbError::Connection(_)) => {
+            "the database is unavailable (is it locked by another process?)".to_string()
+        }
+        Some(DbError::Other(msg)) => msg.clone(),
+        None => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    #[tokio::test]
+    async fn test_unique_violation_maps_to_conflict() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let insert = "INSERT INTO notified_posts (subreddit, post_id) VALUES ('rust', 'abc')";
+        sqlx::query(insert).execute(&pool).await.unwrap();
+        let err = sqlx::query(insert).execute(&pool).await.unwrap_err();
+
+        assert!(matches!(DbError::from(err), DbError::Conflict(_)));
+    }
+
+    #[test]
+    fn test_row_not_found_maps_to_not_found() {
+        assert!(matches!(
+            DbError::from(sqlx::Error::RowNotFound),
+            DbError::NotFound(_)
+        ));
+    }
+
+    #[test]
+    fn test_pool_errors_map_to_connection() {
+        assert!(matches!(
+            DbError::from(sqlx::Error::PoolTimedOut),
+            DbError::Connection(_)
+        ));
+        assert!(matches!(
+            DbError::from(sqlx::Error::PoolClosed),
+            DbError::Connection(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mock_missing_endpoint_maps_to_not_found() {
+        use crate::services::mock_database::MockDatabaseService;
+        use crate::services::DatabaseService;
+
+        let db = MockDatabaseService::new();
+        let err = db.get_endpoint(42).await.unwrap_err();
+        assert!(matches!(DbError::from_anyhow(&err), Some(DbError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mock_oversized_note_is_invalid() {
+        use crate::models::database::MAX_ENDPOINT_NOTE_LEN;
+        use crate::services::mock_database::MockDatabaseService;
+        use crate::services::DatabaseService;
+
+        let db = MockDatabaseService::new();
+        let note = "n".repeat(MAX_ENDPOINT_NOTE_LEN + 1);
+        let err = db.create_endpoint("discord", "{}", Some(&note)).await.unwrap_err();
+        assert!(matches!(DbError::from_anyhow(&err), Some(DbError::Invalid(_))));
+        assert_eq!(describe_error(&err), "note is 201 characters, the limit is 200");
+    }
+
+    #[test]
+    fn test_wrap_converts_sqlx_errors() {
+        let err = DbError::wrap(anyhow::Error::from(sqlx::Error::RowNotFound));
+        assert!(matches!(DbError::from_anyhow(&err), Some(DbError::NotFound(_))));
+
+        let err = DbError::wrap(anyhow::anyhow!("plain error"));
+        assert!(DbError::from_anyhow(&err).is_none());
+        assert_eq!(describe_error(&err), "plain error");
+    }
+}
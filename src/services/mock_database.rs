@@ -1,10 +1,41 @@
 use async_trait::async_trait;
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use crate::models::database::{EndpointKind, EndpointRow, NotifiedPostRow, SubscriptionRow};
+use crate::keywords::{KeywordMatcher, KeywordSet};
+use crate::models::database::{
+    p95, DeliveryFailures, DeliveryStats, DeliveryStatus, EndpointKind, EndpointRow, IntegrityProblem, IntegrityReport,
+    LastDeliveryError, NewEndpoint, NewSubscription, NotifiedPostRow, StoredPost, SubscriptionRow, NOTIFIED_POSTS_CSV_HEADER,
+    TIMESTAMP_FORMAT,
+};
+use crate::rising::PostMetrics;
+use crate::search::{SearchQuery, SearchSubscription, ALL_SUBREDDITS};
 use crate::services::database::DatabaseService;
+use crate::services::error::DbError;
+
+/// Build the same `DbError::NotFound` the SQLite service returns for a missing endpoint
+fn endpoint_not_found(id: i64) -> anyhow::Error {
+    DbError::NotFound(format!("endpoint {}", id)).into()
+}
+
+/// Build the same `DbError::NotFound` the SQLite service returns for a missing subscription
+fn subscription_not_found(id: i64) -> anyhow::Error {
+    DbError::NotFound(format!("subscription {}", id)).into()
+}
+
+/// The stored title and URL of a notified post, if it has them
+fn stored_post(post: &NotifiedPostRow) -> Option<StoredPost> {
+    Some(StoredPost {
+        subreddit: post.subreddit.clone(),
+        post_id: post.post_id.clone(),
+        title: post.title.clone()?,
+        url: post.url.clone()?,
+    })
+}
 
 /// Mock implementation of DatabaseService for testing
 ///
@@ -17,6 +48,48 @@ pub struct MockDatabaseService {
     posts: Arc<Mutex<Vec<NotifiedPostRow>>>,
     links: Arc<Mutex<Vec<(i64, i64)>>>, // (subscription_id, endpoint_id)
     next_id: Arc<Mutex<i64>>,
+    meta: Arc<Mutex<HashMap<String, String>>>,
+    notifications_paused: Arc<Mutex<bool>>,
+    deliveries: Arc<Mutex<Vec<MockDelivery>>>,
+    /// Score alert thresholds, by subscription ID
+    score_thresholds: Arc<Mutex<HashMap<i64, Vec<i64>>>>,
+    /// Highest score bucket per (subreddit, post_id)
+    post_state: Arc<Mutex<HashMap<(String, String), i64>>>,
+    /// Rising thresholds, by subscription ID
+    rising_thresholds: Arc<Mutex<HashMap<i64, i64>>>,
+    /// Latest metrics sample per (subreddit, post_id)
+    post_metrics: Arc<Mutex<HashMap<(String, String), PostMetrics>>>,
+    /// Comment keywords, by subscription ID
+    comment_keywords: Arc<Mutex<HashMap<i64, KeywordSet>>>,
+    /// (subreddit, comment_id) of comments already notified about
+    notified_comments: Arc<Mutex<HashSet<(String, String)>>>,
+    /// Bumped by every subscription, endpoint or link change (see `config_version`)
+    config_version: Arc<Mutex<i64>>,
+    /// Calls to `all_subreddit_endpoint_mappings`
+    mapping_queries: Arc<Mutex<usize>>,
+    /// Calls that changed subscription-endpoint links (link, unlink, set)
+    link_writes: Arc<Mutex<usize>>,
+    /// While set, the mapping query fails as if the database were unreachable (until `reconnect`)
+    connection_lost: Arc<Mutex<bool>>,
+    /// Calls to `reconnect`
+    reconnects: Arc<Mutex<usize>>,
+    /// Paths passed to `backup_to`; no file is written
+    backups: Arc<Mutex<Vec<PathBuf>>>,
+    /// How long each `record_if_new` call takes (zero = instant)
+    record_delay: Arc<Mutex<Duration>>,
+    /// `record_if_new` calls in progress, and the most seen at once
+    records_in_flight: Arc<Mutex<(usize, usize)>>,
+}
+
+/// A recorded delivery (the mock keeps only what the aggregates need)
+#[derive(Debug, Clone)]
+struct MockDelivery {
+    delivered_at: DateTime<Utc>,
+    endpoint_id: i64,
+    post_id: String,
+    status: DeliveryStatus,
+    latency_ms: i64,
+    error: Option<String>,
 }
 
 impl MockDatabaseService {
@@ -28,6 +101,23 @@ impl MockDatabaseService {
             posts: Arc::new(Mutex::new(Vec::new())),
             links: Arc::new(Mutex::new(Vec::new())),
             next_id: Arc::new(Mutex::new(1)),
+            meta: Arc::new(Mutex::new(HashMap::new())),
+            notifications_paused: Arc::new(Mutex::new(false)),
+            deliveries: Arc::new(Mutex::new(Vec::new())),
+            score_thresholds: Arc::new(Mutex::new(HashMap::new())),
+            post_state: Arc::new(Mutex::new(HashMap::new())),
+            rising_thresholds: Arc::new(Mutex::new(HashMap::new())),
+            post_metrics: Arc::new(Mutex::new(HashMap::new())),
+            comment_keywords: Arc::new(Mutex::new(HashMap::new())),
+            notified_comments: Arc::new(Mutex::new(HashSet::new())),
+            config_version: Arc::new(Mutex::new(0)),
+            mapping_queries: Arc::new(Mutex::new(0)),
+            link_writes: Arc::new(Mutex::new(0)),
+            connection_lost: Arc::new(Mutex::new(false)),
+            reconnects: Arc::new(Mutex::new(0)),
+            backups: Arc::new(Mutex::new(Vec::new())),
+            record_delay: Arc::new(Mutex::new(Duration::ZERO)),
+            records_in_flight: Arc::new(Mutex::new((0, 0))),
         }
     }
 
@@ -41,11 +131,19 @@ impl MockDatabaseService {
             id: 1,
             subreddit: "rust".to_string(),
             created_at: "2024-01-01 00:00:00".to_string(),
+            skip_stickied: true,
+            muted: false,
+            priority: false,
+            search: None,
         });
         subscriptions.push(SubscriptionRow {
             id: 2,
             subreddit: "programming".to_string(),
             created_at: "2024-01-02 00:00:00".to_string(),
+            skip_stickied: true,
+            muted: false,
+            priority: false,
+            search: None,
         });
         drop(subscriptions);
 
@@ -80,6 +178,50 @@ impl MockDatabaseService {
         service
     }
 
+    /// Number of calls that changed subscription-endpoint links so far
+    pub fn link_writes(&self) -> usize {
+        *self.link_writes.lock().unwrap()
+    }
+
+    /// Number of times the subreddit-to-endpoints mapping was queried so far
+    pub fn mapping_queries(&self) -> usize {
+        *self.mapping_queries.lock().unwrap()
+    }
+
+    /// Post IDs of every recorded delivery, in the order they were recorded
+    pub fn delivered_post_ids(&self) -> Vec<String> {
+        self.deliveries.lock().unwrap().iter().map(|d| d.post_id.clone()).collect()
+    }
+
+    /// Make the mapping query fail with connection errors until `reconnect` is called
+    pub fn lose_connection(&self) {
+        *self.connection_lost.lock().unwrap() = true;
+    }
+
+    /// Number of times `reconnect` was called so far
+    pub fn reconnects(&self) -> usize {
+        *self.reconnects.lock().unwrap()
+    }
+
+    /// Paths passed to `backup_to` so far
+    pub fn backups(&self) -> Vec<PathBuf> {
+        self.backups.lock().unwrap().clone()
+    }
+
+    /// Make every `record_if_new` call take `delay`, so overlapping calls can be observed
+    pub fn set_record_delay(&self, delay: Duration) {
+        *self.record_delay.lock().unwrap() = delay;
+    }
+
+    /// Most `record_if_new` calls that were in progress at the same time
+    pub fn peak_concurrent_records(&self) -> usize {
+        self.records_in_flight.lock().unwrap().1
+    }
+
+    fn bump_config_version(&self) {
+        *self.config_version.lock().unwrap() += 1;
+    }
+
     fn get_next_id(&self) -> i64 {
         let mut next_id = self.next_id.lock().unwrap();
         let id = *next_id;
@@ -100,17 +242,32 @@ impl DatabaseService for MockDatabaseService {
     }
 
     async fn create_subscription(&self, subreddit: &str) -> Result<i64> {
+        self.bump_config_version();
         let id = self.get_next_id();
         let mut subscriptions = self.subscriptions.lock().unwrap();
         subscriptions.push(SubscriptionRow {
             id,
             subreddit: subreddit.to_string(),
             created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            skip_stickied: true,
+            muted: false,
+            priority: false,
+            search: None,
         });
         Ok(id)
     }
 
+    async fn create_search_subscription(&self, subreddit: &str, search: &SearchQuery) -> Result<i64> {
+        let id = self.create_subscription(if search.restrict_sr { subreddit } else { ALL_SUBREDDITS }).await?;
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if let Some(sub) = subscriptions.iter_mut().find(|s| s.id == id) {
+            sub.search = Some(search.clone());
+        }
+        Ok(id)
+    }
+
     async fn delete_subscription(&self, id: i64) -> Result<()> {
+        self.bump_config_version();
         let mut subscriptions = self.subscriptions.lock().unwrap();
         subscriptions.retain(|s| s.id != id);
 
@@ -121,6 +278,82 @@ impl DatabaseService for MockDatabaseService {
         Ok(())
     }
 
+    async fn toggle_subscription_skip_stickied(&self, id: i64) -> Result<bool> {
+        self.bump_config_version();
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let subscription = subscriptions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| subscription_not_found(id))?;
+
+        subscription.skip_stickied = !subscription.skip_stickied;
+        Ok(subscription.skip_stickied)
+    }
+
+    async fn toggle_subscription_priority(&self, id: i64) -> Result<bool> {
+        self.bump_config_version();
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let subscription = subscriptions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| subscription_not_found(id))?;
+
+        subscription.priority = !subscription.priority;
+        Ok(subscription.priority)
+    }
+
+    async fn set_subscription_score_thresholds(&self, id: i64, thresholds: &[i64]) -> Result<()> {
+        self.bump_config_version();
+        if !self.subscriptions.lock().unwrap().iter().any(|s| s.id == id) {
+            return Err(subscription_not_found(id));
+        }
+        let mut score_thresholds = self.score_thresholds.lock().unwrap();
+        if thresholds.is_empty() {
+            score_thresholds.remove(&id);
+        } else {
+            score_thresholds.insert(id, thresholds.to_vec());
+        }
+        Ok(())
+    }
+
+    async fn set_subscription_rising_threshold(&self, id: i64, threshold: Option<i64>) -> Result<()> {
+        self.bump_config_version();
+        if !self.subscriptions.lock().unwrap().iter().any(|s| s.id == id) {
+            return Err(subscription_not_found(id));
+        }
+        let mut rising_thresholds = self.rising_thresholds.lock().unwrap();
+        match threshold {
+            Some(threshold) => rising_thresholds.insert(id, threshold),
+            None => rising_thresholds.remove(&id),
+        };
+        Ok(())
+    }
+
+    async fn set_subscription_comment_keywords(&self, id: i64, keywords: Option<&KeywordSet>) -> Result<()> {
+        self.bump_config_version();
+        if !self.subscriptions.lock().unwrap().iter().any(|s| s.id == id) {
+            return Err(subscription_not_found(id));
+        }
+        let mut comment_keywords = self.comment_keywords.lock().unwrap();
+        match keywords {
+            Some(set) => comment_keywords.insert(id, set.clone()),
+            None => comment_keywords.remove(&id),
+        };
+        Ok(())
+    }
+
+    async fn toggle_subscription_muted(&self, id: i64) -> Result<bool> {
+        self.bump_config_version();
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let subscription = subscriptions
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or_else(|| subscription_not_found(id))?;
+
+        subscription.muted = !subscription.muted;
+        Ok(subscription.muted)
+    }
+
     async fn get_subscription_endpoints(&self, subscription_id: i64) -> Result<Vec<EndpointRow>> {
         let links = self.links.lock().unwrap();
         let endpoints = self.endpoints.lock().unwrap();
@@ -155,7 +388,7 @@ impl DatabaseService for MockDatabaseService {
             .iter()
             .find(|e| e.id == id)
             .cloned()
-            .ok_or_else(|| anyhow!("Endpoint not found: {}", id))
+            .ok_or_else(|| endpoint_not_found(id))
     }
 
     async fn create_endpoint(
@@ -164,6 +397,8 @@ impl DatabaseService for MockDatabaseService {
         config_json: &str,
         note: Option<&str>,
     ) -> Result<i64> {
+        self.bump_config_version();
+        let note = crate::database::check_endpoint_input(config_json, note)?;
         let id = self.get_next_id();
         let parsed_kind = kind
             .parse::<EndpointKind>()
@@ -185,11 +420,13 @@ impl DatabaseService for MockDatabaseService {
         config_json: &str,
         note: Option<&str>,
     ) -> Result<()> {
+        self.bump_config_version();
+        let note = crate::database::check_endpoint_input(config_json, note)?;
         let mut endpoints = self.endpoints.lock().unwrap();
         let endpoint = endpoints
             .iter_mut()
             .find(|e| e.id == id)
-            .ok_or_else(|| anyhow!("Endpoint not found: {}", id))?;
+            .ok_or_else(|| endpoint_not_found(id))?;
 
         endpoint.config_json = config_json.to_string();
         endpoint.note = note.map(|s| s.to_string());
@@ -197,6 +434,7 @@ impl DatabaseService for MockDatabaseService {
     }
 
     async fn delete_endpoint(&self, id: i64) -> Result<()> {
+        self.bump_config_version();
         let mut endpoints = self.endpoints.lock().unwrap();
         endpoints.retain(|e| e.id != id);
 
@@ -204,15 +442,18 @@ impl DatabaseService for MockDatabaseService {
         let mut links = self.links.lock().unwrap();
         links.retain(|(_, end_id)| *end_id != id);
 
+        self.deliveries.lock().unwrap().retain(|d| d.endpoint_id != id);
+
         Ok(())
     }
 
     async fn toggle_endpoint_active(&self, id: i64) -> Result<bool> {
+        self.bump_config_version();
         let mut endpoints = self.endpoints.lock().unwrap();
         let endpoint = endpoints
             .iter_mut()
             .find(|e| e.id == id)
-            .ok_or_else(|| anyhow!("Endpoint not found: {}", id))?;
+            .ok_or_else(|| endpoint_not_found(id))?;
 
         endpoint.active = !endpoint.active;
         Ok(endpoint.active)
@@ -227,6 +468,8 @@ impl DatabaseService for MockDatabaseService {
         subscription_id: i64,
         endpoint_id: i64,
     ) -> Result<()> {
+        self.bump_config_version();
+        *self.link_writes.lock().unwrap() += 1;
         let mut links = self.links.lock().unwrap();
         if !links.contains(&(subscription_id, endpoint_id)) {
             links.push((subscription_id, endpoint_id));
@@ -239,6 +482,8 @@ impl DatabaseService for MockDatabaseService {
         subscription_id: i64,
         endpoint_id: i64,
     ) -> Result<()> {
+        self.bump_config_version();
+        *self.link_writes.lock().unwrap() += 1;
         let mut links = self.links.lock().unwrap();
         links.retain(|(sub_id, end_id)| {
             *sub_id != subscription_id || *end_id != endpoint_id
@@ -246,6 +491,42 @@ impl DatabaseService for MockDatabaseService {
         Ok(())
     }
 
+    async fn set_subscription_endpoints(&self, subscription_id: i64, endpoint_ids: &[i64]) -> Result<()> {
+        self.bump_config_version();
+        *self.link_writes.lock().unwrap() += 1;
+        let mut links = self.links.lock().unwrap();
+        links.retain(|(sub_id, _)| *sub_id != subscription_id);
+        for endpoint_id in endpoint_ids {
+            if !links.contains(&(subscription_id, *endpoint_id)) {
+                links.push((subscription_id, *endpoint_id));
+            }
+        }
+        Ok(())
+    }
+
+    async fn import_rows(&self, endpoints: &[NewEndpoint], subscriptions: &[NewSubscription]) -> Result<()> {
+        // Check everything up front so a failure leaves nothing behind, like the SQLite transaction
+        for endpoint in endpoints {
+            crate::database::check_endpoint_input(&endpoint.config_json, endpoint.note.as_deref())?;
+            endpoint.kind.parse::<EndpointKind>().map_err(|e| anyhow!("Invalid endpoint kind: {}", e))?;
+        }
+        if let Some(index) = subscriptions.iter().flat_map(|s| &s.endpoints).find(|&&i| i >= endpoints.len()) {
+            return Err(DbError::Invalid(format!("no endpoint #{} in this import", index)).into());
+        }
+
+        let mut endpoint_ids = Vec::with_capacity(endpoints.len());
+        for endpoint in endpoints {
+            endpoint_ids.push(self.create_endpoint(&endpoint.kind, &endpoint.config_json, endpoint.note.as_deref()).await?);
+        }
+        for subscription in subscriptions {
+            let subscription_id = self.create_subscription(&subscription.subreddit).await?;
+            for &index in &subscription.endpoints {
+                self.link_subscription_endpoint(subscription_id, endpoint_ids[index]).await?;
+            }
+        }
+        Ok(())
+    }
+
     // ========================================================================
     // Notified Posts Operations
     // ========================================================================
@@ -281,11 +562,145 @@ impl DatabaseService for MockDatabaseService {
         Ok(())
     }
 
+    async fn delete_notified_posts_by_subreddit(&self, subreddit: &str) -> Result<u64> {
+        let mut posts = self.posts.lock().unwrap();
+        let before = posts.len();
+        posts.retain(|p| p.subreddit != subreddit);
+        self.post_state.lock().unwrap().retain(|(sub, _), _| sub != subreddit);
+        self.notified_comments.lock().unwrap().retain(|(sub, _)| sub != subreddit);
+        self.post_metrics.lock().unwrap().retain(|(sub, _), _| sub != subreddit);
+        Ok((before - posts.len()) as u64)
+    }
+
     async fn cleanup_old_posts(&self, _days_to_keep: i64) -> Result<u64> {
         // In mock, we don't have real timestamps, so just return 0
         Ok(0)
     }
 
+    async fn get_post_statistics(&self) -> Result<Vec<(String, i64)>> {
+        let mut counts: HashMap<String, i64> = HashMap::new();
+        for post in self.posts.lock().unwrap().iter() {
+            *counts.entry(post.subreddit.clone()).or_default() += 1;
+        }
+        let mut stats: Vec<(String, i64)> = counts.into_iter().collect();
+        stats.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(stats)
+    }
+
+    async fn last_notified_at_per_subreddit(&self) -> Result<HashMap<String, DateTime<Utc>>> {
+        let mut last: HashMap<String, DateTime<Utc>> = HashMap::new();
+        for post in self.posts.lock().unwrap().iter() {
+            if let Some(seen) = post.first_seen_utc() {
+                let entry = last.entry(post.subreddit.clone()).or_insert(seen);
+                *entry = (*entry).max(seen);
+            }
+        }
+        Ok(last)
+    }
+
+    async fn record_delivery(
+        &self,
+        endpoint_id: i64,
+        post_id: &str,
+        status: DeliveryStatus,
+        latency_ms: i64,
+        error: Option<&str>,
+    ) -> Result<()> {
+        self.deliveries.lock().unwrap().push(MockDelivery {
+            delivered_at: Utc::now(),
+            endpoint_id,
+            post_id: post_id.to_string(),
+            status,
+            latency_ms,
+            error: error.map(str::to_string),
+        });
+        Ok(())
+    }
+
+    async fn delivery_stats(&self, days: i64) -> Result<Vec<DeliveryStats>> {
+        let since = Utc::now() - chrono::TimeDelta::days(days);
+        let mut by_endpoint: std::collections::BTreeMap<i64, (i64, Vec<i64>)> = Default::default();
+        for d in self.deliveries.lock().unwrap().iter().filter(|d| d.delivered_at >= since) {
+            let (succeeded, latencies) = by_endpoint.entry(d.endpoint_id).or_default();
+            if d.status == DeliveryStatus::Sent {
+                *succeeded += 1;
+            }
+            latencies.push(d.latency_ms);
+        }
+
+        Ok(by_endpoint
+            .into_iter()
+            .map(|(endpoint_id, (succeeded, mut latencies))| DeliveryStats {
+                endpoint_id,
+                total: latencies.len() as i64,
+                succeeded,
+                p95_latency_ms: p95(&mut latencies),
+            })
+            .collect())
+    }
+
+    async fn recent_delivery_failures(&self, since: DateTime<Utc>) -> Result<Vec<DeliveryFailures>> {
+        let mut by_endpoint: std::collections::BTreeMap<i64, DeliveryFailures> = Default::default();
+        let deliveries = self.deliveries.lock().unwrap();
+        for d in deliveries
+            .iter()
+            .filter(|d| d.status == DeliveryStatus::Failed && d.delivered_at >= since)
+        {
+            let entry = by_endpoint.entry(d.endpoint_id).or_insert_with(|| DeliveryFailures {
+                endpoint_id: d.endpoint_id,
+                failures: 0,
+                last_failed_at: d.delivered_at,
+                last_error: None,
+            });
+            entry.failures += 1;
+            entry.last_failed_at = d.delivered_at;
+            entry.last_error = d.error.clone();
+        }
+
+        let mut failures: Vec<DeliveryFailures> = by_endpoint.into_values().collect();
+        failures.sort_by_key(|f| std::cmp::Reverse(f.failures));
+        Ok(failures)
+    }
+
+    async fn last_delivery_error(&self, endpoint_id: i64) -> Result<Option<LastDeliveryError>> {
+        let deliveries = self.deliveries.lock().unwrap();
+        let to_endpoint: Vec<&MockDelivery> = deliveries.iter().filter(|d| d.endpoint_id == endpoint_id).collect();
+        let Some(last) = to_endpoint.iter().rposition(|d| d.status == DeliveryStatus::Failed) else {
+            return Ok(None);
+        };
+        let failed = to_endpoint[last];
+
+        let posts = self.posts.lock().unwrap();
+        let post = posts.iter().rev().filter(|p| p.post_id == failed.post_id).find_map(stored_post);
+        Ok(Some(LastDeliveryError {
+            endpoint_id,
+            failed_at: failed.delivered_at,
+            error: failed.error.clone(),
+            post_id: failed.post_id.clone(),
+            post,
+            resolved: to_endpoint[last + 1..].iter().any(|d| d.status == DeliveryStatus::Sent),
+        }))
+    }
+
+    async fn cleanup_old_deliveries(&self, days_to_keep: i64) -> Result<u64> {
+        let cutoff = Utc::now() - chrono::TimeDelta::days(days_to_keep);
+        let mut deliveries = self.deliveries.lock().unwrap();
+        let before = deliveries.len();
+        deliveries.retain(|d| d.delivered_at >= cutoff);
+        Ok((before - deliveries.len()) as u64)
+    }
+
+    async fn export_notified_posts_stream(&self, writer: &mut (dyn std::io::Write + Send)) -> Result<u64> {
+        let mut posts = self.posts.lock().unwrap().clone();
+        posts.sort_by_key(|p| p.id);
+
+        writer.write_all(NOTIFIED_POSTS_CSV_HEADER.as_bytes())?;
+        for post in &posts {
+            writer.write_all(post.to_csv_line().as_bytes())?;
+        }
+        Ok(posts.len() as u64)
+    }
+
     // ========================================================================
     // Poller-Specific Operations
     // ========================================================================
@@ -306,11 +721,11 @@ impl DatabaseService for MockDatabaseService {
             .map(|(sub_id, _)| *sub_id)
             .collect();
 
-        // Get unique subreddit names
+        // Get unique subreddit names, lowercased like SQLite's lower()
         let mut subreddits: Vec<String> = subscriptions
             .iter()
-            .filter(|s| sub_ids_with_active.contains(&s.id))
-            .map(|s| s.subreddit.clone())
+            .filter(|s| !s.muted && s.search.is_none() && sub_ids_with_active.contains(&s.id))
+            .map(|s| s.subreddit.to_ascii_lowercase())
             .collect();
 
         subreddits.sort();
@@ -318,9 +733,98 @@ impl DatabaseService for MockDatabaseService {
         Ok(subreddits)
     }
 
+    async fn subscriptions_without_active_endpoints(&self) -> Result<Vec<SubscriptionRow>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let links = self.links.lock().unwrap();
+        let endpoints = self.endpoints.lock().unwrap();
+
+        let has_active = |sub_id: i64| {
+            links
+                .iter()
+                .any(|(s, e)| *s == sub_id && endpoints.iter().any(|ep| ep.id == *e && ep.active))
+        };
+        let mut flagged: Vec<SubscriptionRow> = subscriptions
+            .iter()
+            .filter(|s| !s.muted && !has_active(s.id))
+            .cloned()
+            .collect();
+        flagged.sort_by(|a, b| a.subreddit.cmp(&b.subreddit));
+        Ok(flagged)
+    }
+
+    async fn integrity_problems(&self) -> Result<IntegrityReport> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let endpoints = self.endpoints.lock().unwrap();
+
+        let mut orphaned_links: Vec<(i64, i64)> = self
+            .links
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(s, e)| !subscriptions.iter().any(|sub| sub.id == *s) || !endpoints.iter().any(|ep| ep.id == *e))
+            .copied()
+            .collect();
+        orphaned_links.sort_unstable();
+
+        // Score thresholds and kinds are stored parsed here, so only these can be bad
+        let mut invalid_subscriptions: Vec<IntegrityProblem> = self
+            .rising_thresholds
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, threshold)| **threshold <= 0)
+            .map(|(id, threshold)| IntegrityProblem {
+                id: *id,
+                field: "rising_threshold",
+                reason: format!("invalid rising threshold {} (expected a positive number)", threshold),
+            })
+            .collect();
+        invalid_subscriptions.extend(self.comment_keywords.lock().unwrap().iter().filter_map(|(id, set)| {
+            let reason = KeywordMatcher::compile(std::slice::from_ref(set)).err()?;
+            Some(IntegrityProblem { id: *id, field: "comment_keywords", reason })
+        }));
+        invalid_subscriptions.sort_by_key(|p| p.id);
+
+        let invalid_endpoints = endpoints
+            .iter()
+            .filter(|ep| ep.active)
+            .filter_map(|ep| {
+                let reason = crate::models::validate_endpoint_config(&ep.kind, &ep.config_json).err()?;
+                Some(IntegrityProblem { id: ep.id, field: "config_json", reason })
+            })
+            .collect();
+
+        Ok(IntegrityReport { orphaned_links, invalid_subscriptions, invalid_endpoints })
+    }
+
+    async fn fix_integrity_problems(&self) -> Result<IntegrityReport> {
+        let report = self.integrity_problems().await?;
+        self.links.lock().unwrap().retain(|link| !report.orphaned_links.contains(link));
+        let mut rising_thresholds = self.rising_thresholds.lock().unwrap();
+        let mut comment_keywords = self.comment_keywords.lock().unwrap();
+        for problem in &report.invalid_subscriptions {
+            if problem.field == "comment_keywords" {
+                comment_keywords.remove(&problem.id);
+            } else {
+                rising_thresholds.remove(&problem.id);
+            }
+        }
+        let mut endpoints = self.endpoints.lock().unwrap();
+        for ep in endpoints.iter_mut() {
+            if report.invalid_endpoints.iter().any(|p| p.id == ep.id) {
+                ep.active = false;
+            }
+        }
+        Ok(report)
+    }
+
     async fn all_subreddit_endpoint_mappings(
         &self,
     ) -> Result<HashMap<String, Vec<EndpointRow>>> {
+        *self.mapping_queries.lock().unwrap() += 1;
+        if *self.connection_lost.lock().unwrap() {
+            return Err(DbError::Connection("unable to open database file".to_string()).into());
+        }
         let subscriptions = self.subscriptions.lock().unwrap();
         let links = self.links.lock().unwrap();
         let endpoints = self.endpoints.lock().unwrap();
@@ -329,11 +833,11 @@ impl DatabaseService for MockDatabaseService {
 
         for (sub_id, end_id) in links.iter() {
             // Find the subscription
-            if let Some(sub) = subscriptions.iter().find(|s| s.id == *sub_id) {
+            if let Some(sub) = subscriptions.iter().find(|s| s.id == *sub_id && !s.muted && s.search.is_none()) {
                 // Find the endpoint
                 if let Some(endpoint) = endpoints.iter().find(|e| e.id == *end_id && e.active) {
                     mappings
-                        .entry(sub.subreddit.clone())
+                        .entry(sub.subreddit.to_lowercase())
                         .or_default()
                         .push(endpoint.clone());
                 }
@@ -343,7 +847,135 @@ impl DatabaseService for MockDatabaseService {
         Ok(mappings)
     }
 
-    async fn record_if_new(&self, subreddit: &str, post_id: &str) -> Result<bool> {
+    async fn subreddits_allowing_stickied(&self) -> Result<HashSet<String>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        Ok(subscriptions
+            .iter()
+            .filter(|s| !s.skip_stickied && !s.muted && s.search.is_none())
+            .map(|s| s.subreddit.to_lowercase())
+            .collect())
+    }
+
+    async fn priority_subreddits(&self) -> Result<HashSet<String>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        Ok(subscriptions
+            .iter()
+            .filter(|s| s.priority && !s.muted && s.search.is_none())
+            .map(|s| s.subreddit.to_lowercase())
+            .collect())
+    }
+
+    async fn score_thresholds_by_subreddit(&self) -> Result<HashMap<String, Vec<i64>>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let score_thresholds = self.score_thresholds.lock().unwrap();
+        let mut merged: HashMap<String, Vec<i64>> = HashMap::new();
+        for s in subscriptions.iter().filter(|s| !s.muted) {
+            if let Some(thresholds) = score_thresholds.get(&s.id) {
+                let entry = merged.entry(s.subreddit.to_lowercase()).or_default();
+                entry.extend(thresholds);
+                entry.sort_unstable();
+                entry.dedup();
+            }
+        }
+        Ok(merged)
+    }
+
+    async fn record_score_bucket(&self, subreddit: &str, post_id: &str, bucket: i64) -> Result<Option<i64>> {
+        let mut post_state = self.post_state.lock().unwrap();
+        let key = (subreddit.to_string(), post_id.to_string());
+        let previous = post_state.get(&key).copied();
+        post_state.insert(key, previous.map_or(bucket, |p| p.max(bucket)));
+        Ok(previous)
+    }
+
+    async fn rising_thresholds_by_subreddit(&self) -> Result<HashMap<String, i64>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let rising_thresholds = self.rising_thresholds.lock().unwrap();
+        let mut merged: HashMap<String, i64> = HashMap::new();
+        for s in subscriptions.iter().filter(|s| !s.muted) {
+            if let Some(&threshold) = rising_thresholds.get(&s.id) {
+                merged
+                    .entry(s.subreddit.to_lowercase())
+                    .and_modify(|t| *t = (*t).min(threshold))
+                    .or_insert(threshold);
+            }
+        }
+        Ok(merged)
+    }
+
+    async fn post_metrics(&self, subreddit: &str, post_id: &str) -> Result<Option<PostMetrics>> {
+        let key = (subreddit.to_string(), post_id.to_string());
+        Ok(self.post_metrics.lock().unwrap().get(&key).copied())
+    }
+
+    async fn record_post_metrics(&self, subreddit: &str, post_id: &str, metrics: &PostMetrics) -> Result<()> {
+        let key = (subreddit.to_string(), post_id.to_string());
+        self.post_metrics.lock().unwrap().insert(key, *metrics);
+        Ok(())
+    }
+
+    async fn prune_post_metrics(&self, hours: i64) -> Result<u64> {
+        let cutoff = Utc::now() - chrono::TimeDelta::hours(hours);
+        let mut post_metrics = self.post_metrics.lock().unwrap();
+        let before = post_metrics.len();
+        post_metrics.retain(|_, m| m.sample.sampled_at >= cutoff);
+        Ok((before - post_metrics.len()) as u64)
+    }
+
+    async fn comment_keywords_by_subreddit(&self) -> Result<HashMap<String, Vec<KeywordSet>>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let comment_keywords = self.comment_keywords.lock().unwrap();
+        let mut by_subreddit: HashMap<String, Vec<KeywordSet>> = HashMap::new();
+        for s in subscriptions.iter().filter(|s| !s.muted) {
+            if let Some(set) = comment_keywords.get(&s.id) {
+                by_subreddit.entry(s.subreddit.to_lowercase()).or_default().push(set.clone());
+            }
+        }
+        Ok(by_subreddit)
+    }
+
+    async fn search_subscriptions(&self) -> Result<Vec<SearchSubscription>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let links = self.links.lock().unwrap();
+        let endpoints = self.endpoints.lock().unwrap();
+        Ok(subscriptions
+            .iter()
+            .filter(|s| !s.muted)
+            .filter_map(|s| {
+                let search = s.search.clone()?;
+                let linked: Vec<EndpointRow> = endpoints
+                    .iter()
+                    .filter(|e| e.active && links.contains(&(s.id, e.id)))
+                    .cloned()
+                    .collect();
+                (!linked.is_empty()).then(|| SearchSubscription {
+                    id: s.id,
+                    subreddit: s.subreddit.clone(),
+                    search,
+                    skip_stickied: s.skip_stickied,
+                    priority: s.priority,
+                    endpoints: linked,
+                })
+            })
+            .collect())
+    }
+
+    async fn record_comment_if_new(&self, subreddit: &str, comment_id: &str) -> Result<bool> {
+        let mut notified = self.notified_comments.lock().unwrap();
+        Ok(notified.insert((subreddit.to_string(), comment_id.to_string())))
+    }
+
+    async fn record_if_new(&self, subreddit: &str, post_id: &str, title: &str, url: &str) -> Result<bool> {
+        let delay = *self.record_delay.lock().unwrap();
+        if !delay.is_zero() {
+            {
+                let mut in_flight = self.records_in_flight.lock().unwrap();
+                in_flight.0 += 1;
+                in_flight.1 = in_flight.1.max(in_flight.0);
+            }
+            tokio::time::sleep(delay).await;
+            self.records_in_flight.lock().unwrap().0 -= 1;
+        }
         let mut posts = self.posts.lock().unwrap();
 
         // Check if already exists
@@ -357,9 +989,81 @@ impl DatabaseService for MockDatabaseService {
             id,
             subreddit: subreddit.to_string(),
             post_id: post_id.to_string(),
-            first_seen_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            first_seen_at: chrono::Utc::now().format(TIMESTAMP_FORMAT).to_string(),
+            title: Some(title.to_string()),
+            url: Some(url.to_string()),
         });
 
         Ok(true)
     }
+
+    async fn recent_stored_posts(&self, limit: i64, subreddit: Option<&str>) -> Result<Vec<StoredPost>> {
+        let posts = self.posts.lock().unwrap();
+        let mut recent: Vec<StoredPost> = posts
+            .iter()
+            .rev()
+            .filter(|p| subreddit.is_none_or(|s| p.subreddit.eq_ignore_ascii_case(s)))
+            .filter_map(stored_post)
+            .take(limit.max(0) as usize)
+            .collect();
+        recent.reverse();
+        Ok(recent)
+    }
+
+    async fn mute_subreddit(&self, subreddit: &str) -> Result<u64> {
+        self.bump_config_version();
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let mut muted = 0;
+        for sub in subscriptions.iter_mut().filter(|s| s.subreddit.eq_ignore_ascii_case(subreddit) && !s.muted) {
+            sub.muted = true;
+            muted += 1;
+        }
+        Ok(muted)
+    }
+
+    async fn get_meta(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.meta.lock().unwrap().get(key).cloned())
+    }
+
+    async fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.meta.lock().unwrap().insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    async fn notifications_enabled(&self) -> Result<bool> {
+        Ok(!*self.notifications_paused.lock().unwrap())
+    }
+
+    async fn set_notifications_enabled(&self, enabled: bool) -> Result<()> {
+        *self.notifications_paused.lock().unwrap() = !enabled;
+        Ok(())
+    }
+
+    async fn config_version(&self) -> Result<i64> {
+        Ok(*self.config_version.lock().unwrap())
+    }
+
+    async fn reconnect(&self) -> Result<bool> {
+        *self.reconnects.lock().unwrap() += 1;
+        *self.connection_lost.lock().unwrap() = false;
+        Ok(true)
+    }
+
+    async fn backup_to(&self, path: &Path) -> Result<()> {
+        let mut backups = self.backups.lock().unwrap();
+        if backups.iter().any(|p| p == path) {
+            return Err(anyhow!("backup file {} already exists", path.display()));
+        }
+        backups.push(path.to_path_buf());
+        Ok(())
+    }
+
+    async fn restore_from(&self, path: &Path) -> Result<()> {
+        // The mock keeps no snapshots, so a restore only checks the backup was taken
+        if !self.backups.lock().unwrap().iter().any(|p| p == path) {
+            return Err(anyhow!("backup file {} does not exist", path.display()));
+        }
+        self.bump_config_version();
+        Ok(())
+    }
 }
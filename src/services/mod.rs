@@ -1,7 +1,9 @@
 pub mod database;
+pub mod error;
 pub mod sqlite_database;
 #[cfg(test)]
 pub mod mock_database;
 
 pub use database::DatabaseService;
+pub use error::{describe_error, DbError};
 pub use sqlite_database::SqliteDatabaseService;
@@ -1,23 +1,51 @@
 use async_trait::async_trait;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{PoisonError, RwLock};
 
-use crate::models::database::{EndpointRow, NotifiedPostRow, SubscriptionRow};
+use crate::db_connection::ReconnectConfig;
+use crate::keywords::KeywordSet;
+use crate::models::database::{
+    DeliveryFailures, DeliveryStats, DeliveryStatus, EndpointRow, IntegrityReport, LastDeliveryError, NewEndpoint, NewSubscription,
+    NotifiedPostRow, StoredPost, SubscriptionRow,
+};
+use crate::rising::PostMetrics;
+use crate::search::{SearchQuery, SearchSubscription};
 use crate::services::database::DatabaseService;
+use crate::services::error::DbError;
 
 /// Production implementation of DatabaseService that uses SQLite
 ///
 /// This implementation wraps the existing database:: functions and provides
-/// them through the DatabaseService trait interface.
+/// them through the DatabaseService trait interface. sqlx errors are converted
+/// into `DbError` so callers can tell failure kinds apart.
 pub struct SqliteDatabaseService {
-    pool: SqlitePool,
+    pool: RwLock<SqlitePool>,
+    /// How to open a replacement pool; `None` disables `reconnect`
+    reconnect: Option<ReconnectConfig>,
 }
 
 impl SqliteDatabaseService {
     /// Create a new SqliteDatabaseService with the given connection pool
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool: RwLock::new(pool),
+            reconnect: None,
+        }
+    }
+
+    /// Allow `reconnect` to replace the pool with one opened from `config`
+    pub fn with_reconnect(mut self, config: ReconnectConfig) -> Self {
+        self.reconnect = Some(config);
+        self
+    }
+
+    /// The current pool (a cheap handle; a reconnect swaps it for later calls)
+    fn pool(&self) -> SqlitePool {
+        self.pool.read().unwrap_or_else(PoisonError::into_inner).clone()
     }
 }
 
@@ -28,19 +56,69 @@ impl DatabaseService for SqliteDatabaseService {
     // ========================================================================
 
     async fn list_subscriptions(&self) -> Result<Vec<SubscriptionRow>> {
-        crate::database::list_subscriptions(&self.pool).await
+        crate::database::list_subscriptions(&self.pool())
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn create_subscription(&self, subreddit: &str) -> Result<i64> {
-        crate::database::create_subscription(&self.pool, subreddit).await
+        crate::database::create_subscription(&self.pool(), subreddit)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn create_search_subscription(&self, subreddit: &str, search: &SearchQuery) -> Result<i64> {
+        crate::database::create_search_subscription(&self.pool(), subreddit, search)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn delete_subscription(&self, id: i64) -> Result<()> {
-        crate::database::delete_subscription(&self.pool, id).await
+        crate::database::delete_subscription(&self.pool(), id)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn set_subscription_score_thresholds(&self, id: i64, thresholds: &[i64]) -> Result<()> {
+        crate::database::set_subscription_score_thresholds(&self.pool(), id, thresholds)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn set_subscription_rising_threshold(&self, id: i64, threshold: Option<i64>) -> Result<()> {
+        crate::database::set_subscription_rising_threshold(&self.pool(), id, threshold)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn set_subscription_comment_keywords(&self, id: i64, keywords: Option<&KeywordSet>) -> Result<()> {
+        crate::database::set_subscription_comment_keywords(&self.pool(), id, keywords)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn toggle_subscription_muted(&self, id: i64) -> Result<bool> {
+        crate::database::toggle_subscription_muted(&self.pool(), id)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn get_subscription_endpoints(&self, subscription_id: i64) -> Result<Vec<EndpointRow>> {
-        crate::database::get_subscription_endpoints(&self.pool, subscription_id).await
+        crate::database::get_subscription_endpoints(&self.pool(), subscription_id)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn toggle_subscription_skip_stickied(&self, id: i64) -> Result<bool> {
+        crate::database::toggle_subscription_skip_stickied(&self.pool(), id)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn toggle_subscription_priority(&self, id: i64) -> Result<bool> {
+        crate::database::toggle_subscription_priority(&self.pool(), id)
+            .await
+            .map_err(DbError::wrap)
     }
 
     // ========================================================================
@@ -48,11 +126,15 @@ impl DatabaseService for SqliteDatabaseService {
     // ========================================================================
 
     async fn list_endpoints(&self) -> Result<Vec<EndpointRow>> {
-        crate::database::list_endpoints(&self.pool).await
+        crate::database::list_endpoints(&self.pool())
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn get_endpoint(&self, id: i64) -> Result<EndpointRow> {
-        crate::database::get_endpoint(&self.pool, id).await
+        crate::database::get_endpoint(&self.pool(), id)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn create_endpoint(
@@ -61,7 +143,9 @@ impl DatabaseService for SqliteDatabaseService {
         config_json: &str,
         note: Option<&str>,
     ) -> Result<i64> {
-        crate::database::create_endpoint(&self.pool, kind, config_json, note).await
+        crate::database::create_endpoint(&self.pool(), kind, config_json, note)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn update_endpoint(
@@ -70,15 +154,21 @@ impl DatabaseService for SqliteDatabaseService {
         config_json: &str,
         note: Option<&str>,
     ) -> Result<()> {
-        crate::database::update_endpoint(&self.pool, id, config_json, note).await
+        crate::database::update_endpoint(&self.pool(), id, config_json, note)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn delete_endpoint(&self, id: i64) -> Result<()> {
-        crate::database::delete_endpoint(&self.pool, id).await
+        crate::database::delete_endpoint(&self.pool(), id)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn toggle_endpoint_active(&self, id: i64) -> Result<bool> {
-        crate::database::toggle_endpoint_active(&self.pool, id).await
+        crate::database::toggle_endpoint_active(&self.pool(), id)
+            .await
+            .map_err(DbError::wrap)
     }
 
     // ========================================================================
@@ -90,7 +180,9 @@ impl DatabaseService for SqliteDatabaseService {
         subscription_id: i64,
         endpoint_id: i64,
     ) -> Result<()> {
-        crate::database::link_subscription_endpoint(&self.pool, subscription_id, endpoint_id).await
+        crate::database::link_subscription_endpoint(&self.pool(), subscription_id, endpoint_id)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn unlink_subscription_endpoint(
@@ -98,8 +190,21 @@ impl DatabaseService for SqliteDatabaseService {
         subscription_id: i64,
         endpoint_id: i64,
     ) -> Result<()> {
-        crate::database::unlink_subscription_endpoint(&self.pool, subscription_id, endpoint_id)
+        crate::database::unlink_subscription_endpoint(&self.pool(), subscription_id, endpoint_id)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn set_subscription_endpoints(&self, subscription_id: i64, endpoint_ids: &[i64]) -> Result<()> {
+        crate::database::set_subscription_endpoints(&self.pool(), subscription_id, endpoint_ids)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn import_rows(&self, endpoints: &[NewEndpoint], subscriptions: &[NewSubscription]) -> Result<()> {
+        crate::database::import_rows(&self.pool(), endpoints, subscriptions)
             .await
+            .map_err(DbError::wrap)
     }
 
     // ========================================================================
@@ -107,7 +212,9 @@ impl DatabaseService for SqliteDatabaseService {
     // ========================================================================
 
     async fn list_notified_posts(&self, limit: i64, offset: i64) -> Result<Vec<NotifiedPostRow>> {
-        crate::database::list_notified_posts(&self.pool, limit, offset).await
+        crate::database::list_notified_posts(&self.pool(), limit, offset)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn list_notified_posts_by_subreddit(
@@ -116,16 +223,82 @@ impl DatabaseService for SqliteDatabaseService {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<NotifiedPostRow>> {
-        crate::database::list_notified_posts_by_subreddit(&self.pool, subreddit, limit, offset)
+        crate::database::list_notified_posts_by_subreddit(&self.pool(), subreddit, limit, offset)
             .await
+            .map_err(DbError::wrap)
     }
 
     async fn delete_notified_post(&self, id: i64) -> Result<()> {
-        crate::database::delete_notified_post(&self.pool, id).await
+        crate::database::delete_notified_post(&self.pool(), id)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn delete_notified_posts_by_subreddit(&self, subreddit: &str) -> Result<u64> {
+        crate::database::delete_notified_posts_by_subreddit(&self.pool(), subreddit)
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn cleanup_old_posts(&self, days_to_keep: i64) -> Result<u64> {
-        crate::database::cleanup_old_posts(&self.pool, days_to_keep).await
+        crate::database::cleanup_old_posts(&self.pool(), days_to_keep)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn get_post_statistics(&self) -> Result<Vec<(String, i64)>> {
+        crate::database::get_post_statistics(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn last_notified_at_per_subreddit(&self) -> Result<HashMap<String, DateTime<Utc>>> {
+        crate::database::last_notified_at_per_subreddit(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn record_delivery(
+        &self,
+        endpoint_id: i64,
+        post_id: &str,
+        status: DeliveryStatus,
+        latency_ms: i64,
+        error: Option<&str>,
+    ) -> Result<()> {
+        crate::database::record_delivery(&self.pool(), endpoint_id, post_id, status, latency_ms, error)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn delivery_stats(&self, days: i64) -> Result<Vec<DeliveryStats>> {
+        crate::database::delivery_stats(&self.pool(), days)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn recent_delivery_failures(&self, since: DateTime<Utc>) -> Result<Vec<DeliveryFailures>> {
+        crate::database::recent_delivery_failures(&self.pool(), since)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn last_delivery_error(&self, endpoint_id: i64) -> Result<Option<LastDeliveryError>> {
+        crate::database::last_delivery_error(&self.pool(), endpoint_id)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn cleanup_old_deliveries(&self, days_to_keep: i64) -> Result<u64> {
+        crate::database::cleanup_old_deliveries(&self.pool(), days_to_keep)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn export_notified_posts_stream(&self, writer: &mut (dyn std::io::Write + Send)) -> Result<u64> {
+        crate::database::export_notified_posts_stream(&self.pool(), writer)
+            .await
+            .map_err(DbError::wrap)
     }
 
     // ========================================================================
@@ -133,16 +306,166 @@ impl DatabaseService for SqliteDatabaseService {
     // ========================================================================
 
     async fn unique_subreddits(&self) -> Result<Vec<String>> {
-        crate::database::unique_subreddits(&self.pool).await
+        crate::database::unique_subreddits(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn subscriptions_without_active_endpoints(&self) -> Result<Vec<SubscriptionRow>> {
+        crate::database::subscriptions_without_active_endpoints(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn integrity_problems(&self) -> Result<IntegrityReport> {
+        crate::database::integrity_problems(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn fix_integrity_problems(&self) -> Result<IntegrityReport> {
+        crate::database::fix_integrity_problems(&self.pool())
+            .await
+            .map_err(DbError::wrap)
     }
 
     async fn all_subreddit_endpoint_mappings(
         &self,
     ) -> Result<HashMap<String, Vec<EndpointRow>>> {
-        crate::database::all_subreddit_endpoint_mappings(&self.pool).await
+        crate::database::all_subreddit_endpoint_mappings(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn subreddits_allowing_stickied(&self) -> Result<HashSet<String>> {
+        crate::database::subreddits_allowing_stickied(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn priority_subreddits(&self) -> Result<HashSet<String>> {
+        crate::database::priority_subreddits(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn score_thresholds_by_subreddit(&self) -> Result<HashMap<String, Vec<i64>>> {
+        crate::database::score_thresholds_by_subreddit(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn record_score_bucket(&self, subreddit: &str, post_id: &str, bucket: i64) -> Result<Option<i64>> {
+        crate::database::record_score_bucket(&self.pool(), subreddit, post_id, bucket)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn rising_thresholds_by_subreddit(&self) -> Result<HashMap<String, i64>> {
+        crate::database::rising_thresholds_by_subreddit(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn post_metrics(&self, subreddit: &str, post_id: &str) -> Result<Option<PostMetrics>> {
+        crate::database::post_metrics(&self.pool(), subreddit, post_id)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn record_post_metrics(&self, subreddit: &str, post_id: &str, metrics: &PostMetrics) -> Result<()> {
+        crate::database::record_post_metrics(&self.pool(), subreddit, post_id, metrics)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn prune_post_metrics(&self, hours: i64) -> Result<u64> {
+        crate::database::prune_post_metrics(&self.pool(), hours)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn comment_keywords_by_subreddit(&self) -> Result<HashMap<String, Vec<KeywordSet>>> {
+        crate::database::comment_keywords_by_subreddit(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn search_subscriptions(&self) -> Result<Vec<SearchSubscription>> {
+        crate::database::search_subscriptions(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn record_comment_if_new(&self, subreddit: &str, comment_id: &str) -> Result<bool> {
+        crate::database::record_comment_if_new(&self.pool(), subreddit, comment_id)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn record_if_new(&self, subreddit: &str, post_id: &str, title: &str, url: &str) -> Result<bool> {
+        crate::database::record_if_new(&self.pool(), subreddit, post_id, title, url)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn recent_stored_posts(&self, limit: i64, subreddit: Option<&str>) -> Result<Vec<StoredPost>> {
+        crate::database::recent_stored_posts(&self.pool(), limit, subreddit)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn mute_subreddit(&self, subreddit: &str) -> Result<u64> {
+        crate::database::mute_subreddit(&self.pool(), subreddit)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn get_meta(&self, key: &str) -> Result<Option<String>> {
+        crate::database::get_meta(&self.pool(), key)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        crate::database::set_meta(&self.pool(), key, value)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn notifications_enabled(&self) -> Result<bool> {
+        crate::database::notifications_enabled(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn set_notifications_enabled(&self, enabled: bool) -> Result<()> {
+        crate::database::set_notifications_enabled(&self.pool(), enabled)
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn config_version(&self) -> Result<i64> {
+        crate::database::config_version(&self.pool())
+            .await
+            .map_err(DbError::wrap)
+    }
+
+    async fn reconnect(&self) -> Result<bool> {
+        let Some(config) = &self.reconnect else {
+            return Ok(false);
+        };
+        let pool = config.connect().await?;
+        // Calls still holding the old pool finish on it; it closes once they're done
+        *self.pool.write().unwrap_or_else(PoisonError::into_inner) = pool;
+        Ok(true)
+    }
+
+    async fn backup_to(&self, path: &Path) -> Result<()> {
+        crate::database::backup_to(&self.pool(), path).await.map_err(DbError::wrap)
     }
 
-    async fn record_if_new(&self, subreddit: &str, post_id: &str) -> Result<bool> {
-        crate::database::record_if_new(&self.pool, subreddit, post_id).await
+    async fn restore_from(&self, path: &Path) -> Result<()> {
+        crate::database::restore_from(&self.pool(), path).await.map_err(DbError::wrap)
     }
 }
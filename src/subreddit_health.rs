@@ -0,0 +1,146 @@
+//! Subreddit health checks for `doctor --check-subreddits`
+//!
+//! A subscription to a subreddit that went private, was banned or never
+//! existed fails every poll without ever notifying. Each subreddit's
+//! `/r/{name}/about.json` tells which of these it is: Reddit answers 403 with
+//! a reason for private and quarantined subreddits, 404 with `"reason":
+//! "banned"` for banned ones, and redirects unknown names to a search.
+
+use reqwest::{Client, StatusCode};
+use std::fmt;
+
+use crate::poller::ForbiddenReason;
+
+/// What a subreddit's about page says about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubredditHealth {
+    Ok,
+    /// Private subreddit the account isn't an approved member of
+    Private,
+    /// Quarantined subreddit that requires an opt-in
+    Quarantined,
+    Banned,
+    /// No such subreddit (404, or redirected to a search)
+    NotFound,
+    /// The check itself failed (rate limited, server or transport error); says nothing about the subreddit
+    Unknown(String),
+}
+
+impl SubredditHealth {
+    /// Whether polling the subreddit can never produce a notification
+    pub fn is_dead(&self) -> bool {
+        matches!(self, Self::Private | Self::Quarantined | Self::Banned | Self::NotFound)
+    }
+}
+
+impl fmt::Display for SubredditHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Private => write!(f, "private"),
+            Self::Quarantined => write!(f, "quarantined"),
+            Self::Banned => write!(f, "banned"),
+            Self::NotFound => write!(f, "not found"),
+            Self::Unknown(reason) => write!(f, "check failed ({})", reason),
+        }
+    }
+}
+
+/// Classify an about.json response
+///
+/// `redirected` is whether the response came from another path than the one
+/// requested, which is how Reddit answers for a name that doesn't exist.
+pub fn classify_about(status: StatusCode, redirected: bool, body: &str) -> SubredditHealth {
+    if redirected {
+        return SubredditHealth::NotFound;
+    }
+    let json = serde_json::from_str::<serde_json::Value>(body).ok();
+    match status {
+        StatusCode::OK => match json.as_ref().and_then(|v| v.get("kind")).and_then(|k| k.as_str()) {
+            Some("t5") => SubredditHealth::Ok,
+            // Search results in place of the subreddit
+            Some("Listing") => SubredditHealth::NotFound,
+            _ => SubredditHealth::Unknown("unexpected response body".to_string()),
+        },
+        StatusCode::FORBIDDEN => match ForbiddenReason::from_body(body) {
+            ForbiddenReason::Private => SubredditHealth::Private,
+            ForbiddenReason::Quarantined => SubredditHealth::Quarantined,
+            ForbiddenReason::Banned => SubredditHealth::Banned,
+            other => SubredditHealth::Unknown(format!("403 Forbidden: {}", other)),
+        },
+        StatusCode::NOT_FOUND => {
+            let reason = json.as_ref().and_then(|v| v.get("reason")).and_then(|r| r.as_str());
+            if reason == Some("banned") {
+                SubredditHealth::Banned
+            } else {
+                SubredditHealth::NotFound
+            }
+        }
+        status => SubredditHealth::Unknown(status.to_string()),
+    }
+}
+
+/// GET a subreddit's about page and classify the response
+///
+/// The caller is responsible for rate limiting.
+pub async fn check_subreddit(client: &Client, reddit_base: &str, subreddit: &str) -> SubredditHealth {
+    let url = format!("{}/r/{}/about.json", reddit_base, subreddit);
+    let resp = match client.get(&url).send().await {
+        Ok(resp) => resp,
+        Err(e) => return SubredditHealth::Unknown(format!("HTTP error: {}", e)),
+    };
+    let requested_path = reqwest::Url::parse(&url).map(|u| u.path().to_string()).unwrap_or_default();
+    let redirected = !resp.url().path().eq_ignore_ascii_case(&requested_path);
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    classify_about(status, redirected, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_live_subreddit() {
+        let body = r#"{"kind": "t5", "data": {"display_name": "rust", "subreddit_type": "public"}}"#;
+        assert_eq!(classify_about(StatusCode::OK, false, body), SubredditHealth::Ok);
+        assert!(!SubredditHealth::Ok.is_dead());
+    }
+
+    #[test]
+    fn test_classify_forbidden_reasons() {
+        let private = r#"{"reason": "private", "message": "Forbidden", "error": 403}"#;
+        assert_eq!(classify_about(StatusCode::FORBIDDEN, false, private), SubredditHealth::Private);
+        assert_eq!(classify_about(StatusCode::FORBIDDEN, false, r#"{"reason": "quarantined"}"#), SubredditHealth::Quarantined);
+        assert_eq!(classify_about(StatusCode::FORBIDDEN, false, r#"{"reason": "banned"}"#), SubredditHealth::Banned);
+
+        // A 403 without a reason (e.g. a blocked client) isn't blamed on the subreddit
+        let blocked = classify_about(StatusCode::FORBIDDEN, false, "<html>blocked</html>");
+        assert!(matches!(blocked, SubredditHealth::Unknown(_)));
+        assert!(!blocked.is_dead());
+    }
+
+    #[test]
+    fn test_classify_banned_and_missing() {
+        let banned = r#"{"reason": "banned", "message": "Not Found", "error": 404}"#;
+        assert_eq!(classify_about(StatusCode::NOT_FOUND, false, banned), SubredditHealth::Banned);
+        let missing = r#"{"message": "Not Found", "error": 404}"#;
+        assert_eq!(classify_about(StatusCode::NOT_FOUND, false, missing), SubredditHealth::NotFound);
+
+        // Unknown names redirect to a subreddit search
+        assert_eq!(classify_about(StatusCode::OK, true, "{}"), SubredditHealth::NotFound);
+        let search = r#"{"kind": "Listing", "data": {"children": []}}"#;
+        assert_eq!(classify_about(StatusCode::OK, false, search), SubredditHealth::NotFound);
+        assert!(SubredditHealth::NotFound.is_dead() && SubredditHealth::Banned.is_dead());
+    }
+
+    #[test]
+    fn test_transient_failures_are_not_dead() {
+        for status in [StatusCode::TOO_MANY_REQUESTS, StatusCode::INTERNAL_SERVER_ERROR, StatusCode::SERVICE_UNAVAILABLE] {
+            let health = classify_about(status, false, "");
+            assert!(matches!(health, SubredditHealth::Unknown(_)), "{}", status);
+            assert!(!health.is_dead());
+        }
+        assert!(matches!(classify_about(StatusCode::OK, false, "<html>"), SubredditHealth::Unknown(_)));
+    }
+}
@@ -1,14 +1,21 @@
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
-use ratatui::DefaultTerminal;
+use ratatui::{layout::Rect, DefaultTerminal};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use crate::logging::LogLevelHandle;
+use crate::notifiers::NotifierClientProfile;
+use crate::poller::{meta_time, PollerHealth, HEARTBEAT_STALE_AFTER, META_LAST_POLL_AT};
 use crate::services::DatabaseService;
+use super::autocomplete::SubredditAutocomplete;
 use super::screen_trait::{Screen as ScreenTrait, ScreenId, ScreenTransition};
 use super::screens;
+use super::session::SessionState;
 use super::state::MessageDisplay;
 use super::state_machine::ScreenStateMachine;
+use super::widgets::common;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Screen {
@@ -26,6 +33,18 @@ pub struct AppContext<D: DatabaseService> {
     pub should_quit: bool,
     pub messages: MessageDisplay,
     pub state_machine: ScreenStateMachine,
+    /// Poller status derived from the daemon's heartbeat, refreshed on a timer
+    pub poller_health: PollerHealth,
+    /// Global notification switch (false = paused), refreshed with the poller health
+    pub notifications_enabled: bool,
+    /// Runtime log verbosity (None = the level is fixed, e.g. in tests)
+    pub log_level: Option<LogLevelHandle>,
+    /// Client settings and retry policy for test sends, the same the daemon delivers with
+    pub notifier_profile: NotifierClientProfile,
+    /// Compact layout setting (None = automatic below `common::COMPACT_HEIGHT` rows)
+    pub compact: Option<bool>,
+    /// Database file that backups are taken of (None = backup/restore unavailable, e.g. in-memory)
+    pub backup_base: Option<PathBuf>,
 }
 
 /// Container for all screen states
@@ -40,8 +59,15 @@ pub struct ScreenStates {
 pub struct App<D: DatabaseService> {
     pub context: AppContext<D>,
     pub states: ScreenStates,
+    /// Where the last screen/selection is saved on exit (None = always start at the main menu)
+    session_path: Option<PathBuf>,
+    /// When the poller heartbeat was last read (None = not yet)
+    last_health_check: Option<Instant>,
 }
 
+/// How often the TUI re-reads the poller heartbeat
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
 // Provide convenient access to context fields (backward compatibility)
 impl<D: DatabaseService> App<D> {
     pub fn db(&self) -> &Arc<D> {
@@ -62,6 +88,12 @@ impl<D: DatabaseService> App<D> {
                 should_quit: false,
                 messages: MessageDisplay::new(),
                 state_machine: ScreenStateMachine::new(),
+                poller_health: PollerHealth::Unknown,
+                notifications_enabled: true,
+                log_level: None,
+                notifier_profile: NotifierClientProfile::default(),
+                compact: None,
+                backup_base: None,
             },
             states: ScreenStates {
                 main_menu_state: screens::MainMenuState::new(),
@@ -70,17 +102,64 @@ impl<D: DatabaseService> App<D> {
                 test_notification_state: screens::TestNotificationState::new(),
                 logs_state: screens::LogsState::new(),
             },
+            session_path: None,
+            last_health_check: None,
         })
     }
 
+    /// Suggest subreddit names from Reddit while creating subscriptions
+    pub fn with_subreddit_autocomplete(mut self, autocomplete: SubredditAutocomplete) -> Self {
+        self.states.subscriptions_state.autocomplete = Some(autocomplete);
+        self
+    }
+
+    /// Send test notifications with the daemon's client settings and retry policy
+    pub fn with_notifier_profile(mut self, profile: NotifierClientProfile) -> Self {
+        self.context.notifier_profile = profile;
+        self
+    }
+
+    /// Force the compact layout on or off instead of choosing it from the terminal height
+    pub fn with_compact_mode(mut self, compact: Option<bool>) -> Self {
+        self.context.compact = compact;
+        self
+    }
+
+    /// Whether screens drawn in `area` should use the compact layout
+    pub fn is_compact(&self, area: Rect) -> bool {
+        common::is_compact(area, self.context.compact)
+    }
+
+    /// Let `v` on the main menu change the log level through `handle`
+    pub fn with_log_level(mut self, handle: LogLevelHandle) -> Self {
+        self.context.log_level = Some(handle);
+        self
+    }
+
+    /// Enable backup and restore from the main menu, with backups kept next to `database`
+    pub fn with_backups(mut self, database: PathBuf) -> Self {
+        self.context.backup_base = Some(database);
+        self
+    }
+
+    /// Restore the last-open screen and selections from `path`, and save them back on exit
+    pub fn with_session_file(mut self, path: PathBuf) -> Self {
+        if let Some(session) = SessionState::load(&path) {
+            session.apply(&mut self);
+        }
+        self.session_path = Some(path);
+        self
+    }
+
     pub async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
-        let mut last_screen_id = self.context.state_machine.current();
+        // None forces on_enter for the first screen (which may be a restored one)
+        let mut last_screen_id = None;
 
         while !self.context.should_quit {
             let current_screen_id = self.context.state_machine.current();
 
             // Call on_enter when entering a new screen using the trait
-            if current_screen_id != last_screen_id {
+            if Some(current_screen_id) != last_screen_id {
                 let context = &mut self.context;
                 let states = &mut self.states;
 
@@ -101,11 +180,24 @@ impl<D: DatabaseService> App<D> {
                         states.logs_state.on_enter(context).await?;
                     }
                 }
-                last_screen_id = current_screen_id;
+                last_screen_id = Some(current_screen_id);
             }
 
+            if self.last_health_check.is_none_or(|t| t.elapsed() >= HEALTH_CHECK_INTERVAL) {
+                self.refresh_poller_health().await;
+            }
+
+            self.tick().await?;
+
+            // Drop timed messages whose TTL has elapsed
+            self.context.messages.clear_expired();
+
             // Render the current screen using the trait
             terminal.draw(|frame| {
+                if super::ui::render_size_warning(frame) {
+                    return;
+                }
+
                 match self.context.state_machine.current() {
                     ScreenId::MainMenu => {
                         self.states.main_menu_state.render(frame, self);
@@ -136,9 +228,44 @@ impl<D: DatabaseService> App<D> {
             }
         }
 
+        if let Some(path) = &self.session_path {
+            if let Err(e) = SessionState::capture(self).save(path) {
+                tracing::warn!("{:#}", e);
+            }
+        }
+
         Ok(())
     }
 
+    /// Re-read the poller heartbeat (`meta.last_poll_at`) and the pause switch from the database
+    ///
+    /// A failed read shows as unknown (or unpaused) rather than interrupting the TUI.
+    pub(crate) async fn refresh_poller_health(&mut self) {
+        let last_poll = meta_time(self.context.db.as_ref(), META_LAST_POLL_AT).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to read poller heartbeat: {}", e);
+            None
+        });
+        self.context.poller_health = PollerHealth::classify(last_poll, chrono::Utc::now(), HEARTBEAT_STALE_AFTER);
+        self.context.notifications_enabled = self.context.db.notifications_enabled().await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to read notification pause setting: {}", e);
+            true
+        });
+        self.last_health_check = Some(Instant::now());
+    }
+
+    /// Give the current screen its per-frame `on_tick` (background task results, spinners)
+    pub async fn tick(&mut self) -> Result<()> {
+        let context = &mut self.context;
+        let states = &mut self.states;
+        match context.state_machine.current() {
+            ScreenId::MainMenu => states.main_menu_state.on_tick(context).await,
+            ScreenId::Subscriptions => states.subscriptions_state.on_tick(context).await,
+            ScreenId::Endpoints => states.endpoints_state.on_tick(context).await,
+            ScreenId::TestNotification => states.test_notification_state.on_tick(context).await,
+            ScreenId::Logs => states.logs_state.on_tick(context).await,
+        }
+    }
+
     /// Handle key input for the current screen
     ///
     /// Now that we've split App into context and states, we can call the trait methods directly!
@@ -172,7 +299,7 @@ impl<D: DatabaseService> App<D> {
 
     /// Sync the old current_screen enum with the state machine
     /// (for backward compatibility with existing screen code)
-    fn sync_current_screen(&mut self) {
+    pub(crate) fn sync_current_screen(&mut self) {
         self.context.current_screen = match self.context.state_machine.current() {
             ScreenId::MainMenu => Screen::MainMenu,
             ScreenId::Subscriptions => Screen::Subscriptions,
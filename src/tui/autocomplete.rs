@@ -0,0 +1,182 @@
+//! Subreddit name suggestions from Reddit's autocomplete API
+//!
+//! Used while creating a subscription. The screen runs lookups in the
+//! background once typing pauses for `TYPING_PAUSE`, so only the latest query
+//! is sent; lookups also wait their turn on a `RateLimiter`. Any network or
+//! parse failure just leaves the user with free-text entry.
+
+use anyhow::{Context, Result};
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::poller::REDDIT_BASE_URL;
+use crate::rate_limiter::RateLimiter;
+
+/// Fewer characters than this are not worth a lookup
+pub const MIN_QUERY_LEN: usize = 2;
+
+/// Maximum number of suggestions requested
+pub const SUGGESTION_LIMIT: u32 = 10;
+
+/// Minimum spacing between lookups while typing
+const LOOKUP_SPACING: Duration = Duration::from_millis(750);
+
+/// How long typing must pause before the latest query is looked up
+pub const TYPING_PAUSE: Duration = Duration::from_millis(250);
+
+/// Give up on a lookup after this long so typing never stalls
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Deserialize)]
+struct AutocompleteListing {
+    data: AutocompleteData,
+}
+
+#[derive(Debug, Deserialize)]
+struct AutocompleteData {
+    children: Vec<AutocompleteChild>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AutocompleteChild {
+    kind: String,
+    data: AutocompleteSubreddit,
+}
+
+#[derive(Debug, Deserialize)]
+struct AutocompleteSubreddit {
+    display_name: String,
+}
+
+/// Extract subreddit names from a `/api/subreddit_autocomplete_v2` response
+///
+/// Only subreddits (`t5`) are kept; user profiles are skipped.
+pub fn parse_autocomplete_response(body: &str) -> Result<Vec<String>> {
+    let listing: AutocompleteListing =
+        serde_json::from_str(body).context("unexpected autocomplete response")?;
+    Ok(listing
+        .data
+        .children
+        .into_iter()
+        .filter(|child| child.kind == "t5")
+        .map(|child| child.data.display_name)
+        .collect())
+}
+
+/// True when `query` is worth sending (long enough, valid subreddit characters)
+pub fn is_lookup_query(query: &str) -> bool {
+    query.len() >= MIN_QUERY_LEN && query.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Fetches subreddit suggestions, rate limited
+#[derive(Clone)]
+pub struct SubredditAutocomplete {
+    client: Client,
+    base_url: String,
+    rate_limiter: RateLimiter,
+}
+
+impl SubredditAutocomplete {
+    /// Create a client for Reddit's public API
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            base_url: REDDIT_BASE_URL.to_string(),
+            rate_limiter: RateLimiter::new(1, LOOKUP_SPACING),
+        }
+    }
+
+    /// Send lookups to a different base URL (used in tests)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Build the lookup URL for `query`
+    pub fn lookup_url(&self, query: &str) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}/api/subreddit_autocomplete_v2.json", self.base_url))?;
+        url.query_pairs_mut()
+            .append_pair("query", query)
+            .append_pair("include_profiles", "false")
+            .append_pair("include_over_18", "true")
+            .append_pair("limit", &SUGGESTION_LIMIT.to_string());
+        Ok(url)
+    }
+
+    /// Look up subreddits matching `query`
+    ///
+    /// Waits until the previous lookup is far enough behind. Returns
+    /// `Ok(None)` when the query is too short or invalid to send.
+    pub async fn suggest(&self, query: &str) -> Result<Option<Vec<String>>> {
+        if !is_lookup_query(query) {
+            return Ok(None);
+        }
+        self.rate_limiter.acquire().await;
+
+        let body = self
+            .client
+            .get(self.lookup_url(query)?)
+            .timeout(LOOKUP_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        parse_autocomplete_response(&body).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_autocomplete_response() {
+        let body = r#"{
+            "kind": "Listing",
+            "data": {
+                "children": [
+                    {"kind": "t5", "data": {"display_name": "rust", "subscribers": 300000}},
+                    {"kind": "t2", "data": {"display_name": "u_rustacean"}},
+                    {"kind": "t5", "data": {"display_name": "rust_gamedev", "over18": false}}
+                ]
+            }
+        }"#;
+        assert_eq!(parse_autocomplete_response(body).unwrap(), vec!["rust", "rust_gamedev"]);
+    }
+
+    #[test]
+    fn test_parse_empty_and_invalid_responses() {
+        assert!(parse_autocomplete_response(r#"{"data": {"children": []}}"#).unwrap().is_empty());
+        assert!(parse_autocomplete_response("<html>rate limited</html>").is_err());
+    }
+
+    #[test]
+    fn test_lookup_query_filter() {
+        assert!(!is_lookup_query("r"));
+        assert!(is_lookup_query("ru"));
+        assert!(is_lookup_query("rust_gamedev"));
+        assert!(!is_lookup_query("r/rust"));
+    }
+
+    #[test]
+    fn test_lookup_url() {
+        let ac = SubredditAutocomplete::new(Client::new()).with_base_url("http://127.0.0.1:9");
+        assert_eq!(
+            ac.lookup_url("rust").unwrap().as_str(),
+            "http://127.0.0.1:9/api/subreddit_autocomplete_v2.json?query=rust&include_profiles=false&include_over_18=true&limit=10"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_is_an_error() {
+        // Nothing listens on the discard port
+        let ac = SubredditAutocomplete::new(Client::builder().no_proxy().build().unwrap())
+            .with_base_url("http://127.0.0.1:9");
+        assert!(ac.suggest("rust").await.is_err());
+        // The next lookup waits for its slot and is sent rather than skipped
+        assert!(ac.suggest("rusty").await.is_err());
+        assert!(ac.suggest("r/rust").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,77 @@
+//! Backup files kept next to the database
+//!
+//! Backups are named `<database>.<YYYYmmdd-HHMMSS>.bak`, so the newest one
+//! sorts last and the restore action can pick it without asking.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::path::{Path, PathBuf};
+
+/// Suffix of every backup file name
+const BACKUP_EXTENSION: &str = ".bak";
+
+/// Timestamp between the database name and the extension
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Where a backup of `database` taken at `now` is written
+pub fn backup_path(database: &Path, now: DateTime<Utc>) -> PathBuf {
+    let mut name = database.as_os_str().to_os_string();
+    name.push(format!(".{}{}", now.format(STAMP_FORMAT), BACKUP_EXTENSION));
+    PathBuf::from(name)
+}
+
+/// The most recent backup of `database`, or `None` if there is none
+pub fn latest_backup(database: &Path) -> Option<PathBuf> {
+    let file_name = database.file_name()?.to_str()?;
+    let prefix = format!("{}.", file_name);
+    let dir = match database.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry.file_name().to_str().is_some_and(|name| {
+                name.strip_prefix(&prefix)
+                    .and_then(|rest| rest.strip_suffix(BACKUP_EXTENSION))
+                    .is_some_and(|stamp| NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).is_ok())
+            })
+        })
+        .map(|entry| entry.path())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_path_is_timestamped_next_to_database() {
+        let now = DateTime::parse_from_rfc3339("2026-07-01T14:05:09Z").unwrap().with_timezone(&Utc);
+        assert_eq!(
+            backup_path(Path::new("data/notifier.db"), now),
+            PathBuf::from("data/notifier.db.20260701-140509.bak")
+        );
+    }
+
+    #[test]
+    fn test_latest_backup_picks_newest_matching_file() {
+        let dir = std::env::temp_dir().join(format!("reddit-notifier-backups-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let database = dir.join("notifier.db");
+        assert_eq!(latest_backup(&database), None);
+
+        for name in [
+            "notifier.db.20260101-000000.bak",
+            "notifier.db.20260301-120000.bak",
+            "notifier.db.tui-state.json",
+            "other.db.20270101-000000.bak",
+            "notifier.db.latest.bak",
+        ] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        assert_eq!(latest_backup(&database), Some(dir.join("notifier.db.20260301-120000.bak")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
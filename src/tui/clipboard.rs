@@ -0,0 +1,49 @@
+//! Copying text to the clipboard from the TUI
+//!
+//! Uses the OSC 52 escape sequence, which the terminal emulator handles
+//! rather than the host, so it also works over SSH and inside containers.
+//! Terminals don't answer it, so a copy can't be confirmed; only the cases
+//! known not to work are reported as unavailable.
+
+use anyhow::{bail, Result};
+use crossterm::{clipboard::CopyToClipboard, execute};
+use std::io::{stdout, IsTerminal};
+
+/// Why the clipboard can't be reached, or None if a copy will be attempted
+///
+/// Output that isn't a terminal can't carry the escape sequence, and the
+/// Linux console and `TERM=dumb` ignore it.
+pub fn unavailable_reason(is_terminal: bool, term: Option<&str>) -> Option<&'static str> {
+    if !is_terminal {
+        return Some("output is not a terminal");
+    }
+    match term {
+        Some("dumb") => Some("TERM=dumb has no clipboard support"),
+        Some("linux") => Some("the Linux console has no clipboard support"),
+        _ => None,
+    }
+}
+
+/// Ask the terminal to put `text` on the system clipboard
+pub fn copy(text: &str) -> Result<()> {
+    let term = std::env::var("TERM").ok();
+    if let Some(reason) = unavailable_reason(stdout().is_terminal(), term.as_deref()) {
+        bail!("Clipboard not available: {}", reason);
+    }
+    execute!(stdout(), CopyToClipboard::to_clipboard_from(text))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unavailable_reasons() {
+        assert_eq!(unavailable_reason(true, Some("xterm-256color")), None);
+        assert_eq!(unavailable_reason(true, None), None);
+        assert!(unavailable_reason(false, Some("xterm-256color")).is_some());
+        assert!(unavailable_reason(true, Some("dumb")).is_some());
+        assert!(unavailable_reason(true, Some("linux")).is_some());
+    }
+}
@@ -0,0 +1,82 @@
+//! Modifier-aware key matching
+//!
+//! crossterm reports Ctrl+A as `Char('a')` with the CONTROL modifier, so a
+//! handler that matches on `key.code` alone treats it exactly like a plain
+//! `a`. Handlers match on [`shortcut_code`] instead, which hides Ctrl/Alt
+//! character combos from single-letter shortcuts and text entry; combos are
+//! bound explicitly with [`is_ctrl`] / [`is_alt`].
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// True when Ctrl or Alt is held, but not both
+///
+/// Shift is part of the character (`A` vs `a`). Ctrl+Alt together is how
+/// AltGr is reported on some platforms, so those characters count as typed.
+pub fn has_command_modifier(key: &KeyEvent) -> bool {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+    ctrl != alt
+}
+
+/// The key code to match single-key shortcuts and text entry against
+///
+/// Ctrl/Alt character combos map to `KeyCode::Null` so they never trigger a
+/// plain-letter binding; all other keys are returned unchanged.
+pub fn shortcut_code(key: &KeyEvent) -> KeyCode {
+    match key.code {
+        KeyCode::Char(_) if has_command_modifier(key) => KeyCode::Null,
+        code => code,
+    }
+}
+
+/// True for Ctrl+`c` (case-insensitive, Alt not held)
+pub fn is_ctrl(key: &KeyEvent, c: char) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL)
+        && !key.modifiers.contains(KeyModifiers::ALT)
+        && matches!(key.code, KeyCode::Char(k) if k.eq_ignore_ascii_case(&c))
+}
+
+/// True for Alt+`c` (case-insensitive, Ctrl not held)
+pub fn is_alt(key: &KeyEvent, c: char) -> bool {
+    key.modifiers.contains(KeyModifiers::ALT)
+        && !key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char(k) if k.eq_ignore_ascii_case(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_and_shifted_letters_pass_through() {
+        let a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        let shift_a = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(shortcut_code(&a), KeyCode::Char('a'));
+        assert_eq!(shortcut_code(&shift_a), KeyCode::Char('A'));
+        assert!(!is_ctrl(&a, 'a'));
+    }
+
+    #[test]
+    fn test_ctrl_and_alt_letters_are_distinct() {
+        let ctrl_a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL);
+        let alt_a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::ALT);
+        assert_eq!(shortcut_code(&ctrl_a), KeyCode::Null);
+        assert_eq!(shortcut_code(&alt_a), KeyCode::Null);
+        assert!(is_ctrl(&ctrl_a, 'a') && !is_alt(&ctrl_a, 'a'));
+        assert!(is_alt(&alt_a, 'a') && !is_ctrl(&alt_a, 'a'));
+        assert!(is_ctrl(&KeyEvent::new(KeyCode::Char('A'), KeyModifiers::CONTROL | KeyModifiers::SHIFT), 'a'));
+    }
+
+    #[test]
+    fn test_non_character_keys_keep_modifiers_out_of_the_way() {
+        let ctrl_up = KeyEvent::new(KeyCode::Up, KeyModifiers::CONTROL);
+        assert_eq!(shortcut_code(&ctrl_up), KeyCode::Up);
+    }
+
+    #[test]
+    fn test_altgr_characters_are_typed() {
+        let altgr_at = KeyEvent::new(KeyCode::Char('@'), KeyModifiers::CONTROL | KeyModifiers::ALT);
+        assert_eq!(shortcut_code(&altgr_at), KeyCode::Char('@'));
+        assert!(!is_ctrl(&altgr_at, '@'));
+    }
+}
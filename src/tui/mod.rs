@@ -1,6 +1,11 @@
 pub mod app;
+pub mod autocomplete;
+pub mod backup;
+pub mod clipboard;
+pub mod keys;
 pub mod screen_trait;
 pub mod screens;
+pub mod session;
 pub mod state;
 pub mod state_machine;
 pub mod ui;
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use anyhow::Result;
 use crossterm::event::KeyEvent;
 use ratatui::Frame;
+use serde::{Deserialize, Serialize};
 
 use crate::services::DatabaseService;
 use crate::tui::app::{App, AppContext};
@@ -38,6 +39,14 @@ pub trait Screen<D: DatabaseService>: Send {
         Ok(())
     }
 
+    /// Called once per event-loop iteration while this screen is current
+    ///
+    /// Use this to collect the result of a `BackgroundTask` without blocking input.
+    async fn on_tick(&mut self, context: &mut AppContext<D>) -> Result<()> {
+        let _ = context;
+        Ok(())
+    }
+
     /// Get the screen identifier
     fn id(&self) -> ScreenId;
 }
@@ -65,7 +74,7 @@ pub enum ScreenTransition {
 ///
 /// This enum provides a type-safe way to reference screens throughout
 /// the application.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ScreenId {
     MainMenu,
     Subscriptions,
@@ -5,17 +5,24 @@ use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Style},
     text::Line,
-    widgets::{Block, Borders, Paragraph, Row},
+    widgets::{Block, Borders, Clear, Paragraph, Row},
     Frame,
 };
 
-use crate::models::database::EndpointRow;
-use crate::services::DatabaseService;
+use crate::models::database::{EndpointKind, EndpointRow, LastDeliveryError};
+use crate::models::notifiers::validate_endpoint_config;
+use crate::notifiers::{self, curl::to_curl, NotifierClientProfile};
+use crate::services::{describe_error, DatabaseService};
 use crate::tui::app::{App, Screen};
 use crate::tui::screen_trait::{Screen as ScreenTrait, ScreenId, ScreenTransition};
-use crate::tui::state::Navigable;
+use crate::tui::state::{clamp_selection, reselect_by_id, BackgroundTask, Navigable, PAGE_SIZE, TOAST_TTL};
+use crate::tui::validation::ValidationResult;
 use crate::tui::widgets::common;
-use crate::tui::widgets::{ColumnDef, ConfigAction, ConfigBuilder, ModalDialog, SelectableTable};
+use crate::tui::widgets::{ColumnDef, ConfigAction, ConfigBuilder, DialogType, ModalDialog, SelectableTable, TextInput};
+use crate::tui::keys;
+
+/// Longest raw message accepted (Pushover's message limit; Discord allows 2000)
+const RAW_MESSAGE_MAX_LEN: usize = 1024;
 
 #[derive(Debug, Clone)]
 pub enum EndpointsMode {
@@ -32,12 +39,32 @@ pub enum EndpointsMode {
         endpoint_id: i64,
         endpoint_desc: String,
     },
+    ShowingCurl {
+        command: String,
+        include_secrets: bool,
+        scroll: u16,
+    },
+    SendingRaw {
+        endpoint: EndpointRow,
+        input: TextInput,
+    },
+    ShowingLastError {
+        failure: LastDeliveryError,
+        scroll: u16,
+    },
 }
 
 pub struct EndpointsState {
     pub endpoints: Vec<EndpointRow>,
+    /// Index into the filtered (visible) endpoints
     pub selected: usize,
     pub mode: EndpointsMode,
+    /// Only show endpoints of this kind (None = all kinds)
+    pub kind_filter: Option<EndpointKind>,
+    /// Webhook check (Ctrl+T) for the form being created or edited
+    pub pending_validation: Option<BackgroundTask<ValidationResult>>,
+    /// Raw message being sent; finishes with the message to show
+    pub pending_send: Option<BackgroundTask<Result<String, String>>>,
 }
 
 impl Default for EndpointsState {
@@ -52,13 +79,43 @@ impl EndpointsState {
             endpoints: Vec::new(),
             selected: 0,
             mode: EndpointsMode::List,
+            kind_filter: None,
+            pending_validation: None,
+            pending_send: None,
         }
     }
+
+    /// Endpoints matching the current kind filter
+    pub fn visible_endpoints(&self) -> Vec<&EndpointRow> {
+        self.endpoints
+            .iter()
+            .filter(|e| self.kind_filter.as_ref().is_none_or(|kind| &e.kind == kind))
+            .collect()
+    }
+
+    /// The endpoint under the cursor in the filtered view
+    pub fn selected_endpoint(&self) -> Option<&EndpointRow> {
+        self.visible_endpoints().get(self.selected).copied()
+    }
+
+    /// Cycle the kind filter: All -> each kind in `EndpointKind::all()` -> All
+    pub fn cycle_kind_filter(&mut self) {
+        let kinds = EndpointKind::all();
+        self.kind_filter = match &self.kind_filter {
+            None => kinds.first().cloned(),
+            Some(current) => kinds
+                .iter()
+                .position(|k| k == current)
+                .and_then(|i| kinds.get(i + 1))
+                .cloned(),
+        };
+        self.selected = 0;
+    }
 }
 
 impl Navigable for EndpointsState {
     fn len(&self) -> usize {
-        self.endpoints.len()
+        self.visible_endpoints().len()
     }
 
     fn selected(&self) -> usize {
@@ -71,13 +128,10 @@ impl Navigable for EndpointsState {
 }
 
 pub async fn load_endpoints<D: DatabaseService>(state: &mut EndpointsState, context: &mut crate::tui::app::AppContext<D>) -> Result<()> {
-    let endpoints = context.db.list_endpoints().await?;
-    state.endpoints = endpoints;
-    if state.selected >= state.endpoints.len()
-        && !state.endpoints.is_empty()
-    {
-        state.selected = state.endpoints.len() - 1;
-    }
+    let previous_id = state.selected_endpoint().map(|e| e.id);
+    state.endpoints = context.db.list_endpoints().await?;
+    let visible = state.visible_endpoints();
+    state.selected = reselect_by_id(&visible, previous_id).unwrap_or_else(|| clamp_selection(state.selected, visible.len()));
     Ok(())
 }
 
@@ -101,6 +155,35 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
             let dialog = ModalDialog::confirm(prompt);
             dialog.render(frame, area);
         }
+        EndpointsMode::ShowingCurl {
+            command,
+            include_secrets,
+            scroll,
+        } => {
+            render_list(frame, app, area);
+            let title = if *include_secrets {
+                "curl (includes secrets!)"
+            } else {
+                "curl (secrets redacted - Shift+C to include)"
+            };
+            let mut content: Vec<Line<'static>> = command.lines().map(|l| Line::from(l.to_string())).collect();
+            content.push(Line::from(""));
+            content.push(Line::from("[↑/↓] Scroll  [any other key] Close").alignment(Alignment::Center));
+            let dialog = ModalDialog::new(DialogType::Info, title, "")
+                .with_content(content)
+                .with_width_percent(90)
+                .with_height_percent(60)
+                .with_scroll(*scroll);
+            dialog.render(frame, area);
+        }
+        EndpointsMode::SendingRaw { endpoint, input } => {
+            render_list(frame, app, area);
+            render_sending_raw(frame, area, endpoint, input);
+        }
+        EndpointsMode::ShowingLastError { failure, scroll } => {
+            render_list(frame, app, area);
+            last_error_dialog(failure, *scroll).render(frame, area);
+        }
     }
 
     // Show error/success messages using centralized display
@@ -108,38 +191,41 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
 }
 
 fn render_list<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: Rect) {
-    let chunks = Layout::vertical([
-        Constraint::Length(3),
-        Constraint::Min(0),
-        Constraint::Length(3),
-    ])
-    .split(area);
+    let compact = app.is_compact(area);
+    let chunks = common::render_screen_layout(area, compact);
 
-    // Title
-    let title = Paragraph::new("Manage Endpoints")
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Cyan)),
-        );
-    frame.render_widget(title, chunks[0]);
+    // Title (with the active kind filter, if any)
+    let title_text = match &app.states.endpoints_state.kind_filter {
+        Some(kind) => format!("Manage Endpoints (Filter: {})", kind.as_str()),
+        None => "Manage Endpoints".to_string(),
+    };
+    common::render_title(frame, chunks[0], &title_text);
 
     // Table using SelectableTable
     let columns = vec![
         ColumnDef::new("", Constraint::Length(2)),            // Selection marker
         ColumnDef::new("ID", Constraint::Length(5)),
-        ColumnDef::new("Type", Constraint::Length(10)),
+        ColumnDef::new("Type", Constraint::Length(11)),
         ColumnDef::new("Active", Constraint::Length(8)),
         ColumnDef::new("Note", Constraint::Percentage(20)),
         ColumnDef::new("Config", Constraint::Percentage(55)),
     ];
 
-    let mut table = SelectableTable::new(
-        app.states.endpoints_state.endpoints.clone(),
-        columns,
-    )
-    .with_empty_message("No endpoints yet. Press 'n' to create one.");
+    let visible: Vec<EndpointRow> = app
+        .states
+        .endpoints_state
+        .visible_endpoints()
+        .into_iter()
+        .cloned()
+        .collect();
+    let filtered_out = app.states.endpoints_state.kind_filter.is_some()
+        && !app.states.endpoints_state.endpoints.is_empty();
+    let empty_message = if filtered_out {
+        "No endpoints of this type. Press 'f' to change the filter."
+    } else {
+        "No endpoints yet. Press 'n' to create one."
+    };
+    let mut table = SelectableTable::new(visible, columns).with_empty_message(empty_message);
 
     // Sync the selection with the app state
     table.selected = app.states.endpoints_state.selected;
@@ -148,13 +234,17 @@ fn render_list<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: Rect)
         let (prefix, style) = common::selection_style(is_selected);
 
         let active = if endpoint.active { "[x]" } else { "[ ]" };
-        let kind_str = endpoint.kind.as_str();
+        // Flag configs that don't match their kind's schema (they'd fail at send time)
+        let kind_str = match validate_endpoint_config(&endpoint.kind, &endpoint.config_json) {
+            Ok(()) => endpoint.kind.as_str().to_string(),
+            Err(_) => format!("⚠ {}", endpoint.kind.as_str()),
+        };
         let note_display = endpoint.note.as_deref().unwrap_or("");
 
         Row::new(vec![
             prefix.to_string(),
             endpoint.id.to_string(),
-            kind_str.to_string(),
+            kind_str,
             active.to_string(),
             note_display.to_string(),
             endpoint.config_json.clone(),
@@ -162,42 +252,92 @@ fn render_list<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: Rect)
         .style(style)
     });
 
-    // Help text
+    // Help text (replaced by a spinner while a raw message is sent)
+    if let Some(task) = &app.states.endpoints_state.pending_send {
+        task.spinner().render(frame, chunks[2], task.label());
+        return;
+    }
     let help = Paragraph::new(Line::from(vec![
-        "[↑/↓] Navigate  ".into(),
+        "[↑/↓/PgUp/PgDn] Navigate  ".into(),
         "[n] New  ".into(),
         "[e] Edit  ".into(),
+        "[y] Clone  ".into(),
         "[d] Delete  ".into(),
         "[Space] Toggle  ".into(),
+        "[f] Filter  ".into(),
+        "[c] curl  ".into(),
+        "[r] Raw msg  ".into(),
+        "[l] Last error  ".into(),
         "[Enter] View  ".into(),
         "[Esc] Back".into(),
     ]))
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    .block(common::chrome_block(compact));
     frame.render_widget(help, chunks[2]);
 }
 
-fn render_viewing<D: DatabaseService>(frame: &mut Frame, _app: &App<D>, area: Rect, endpoint: &EndpointRow) {
+/// Scrollable dialog with an endpoint's last failed delivery
+fn last_error_dialog(failure: &LastDeliveryError, scroll: u16) -> ModalDialog {
+    let mut content = vec![Line::from(format!("Failed at: {}", failure.failed_at.format("%Y-%m-%d %H:%M:%S UTC")))];
+    match &failure.post {
+        Some(post) => {
+            content.push(Line::from(format!("Post: r/{} - {}", post.subreddit, post.title)));
+            content.push(Line::from(format!("Link: {}", post.url)));
+        }
+        None => content.push(Line::from(format!("Post ID: {}", failure.post_id))),
+    }
+    if failure.resolved {
+        content.push(Line::from("A later delivery succeeded."));
+    }
+    content.push(Line::from(""));
+    content.extend(
+        failure
+            .error
+            .as_deref()
+            .unwrap_or("(no error text recorded)")
+            .lines()
+            .map(|l| Line::from(l.to_string())),
+    );
+    content.push(Line::from(""));
+    content.push(Line::from("[↑/↓] Scroll  [any other key] Close").alignment(Alignment::Center));
+    ModalDialog::new(DialogType::Error, format!("Last error (endpoint {})", failure.endpoint_id), "")
+        .with_content(content)
+        .with_width_percent(80)
+        .with_height_percent(60)
+        .with_scroll(scroll)
+}
+
+fn render_sending_raw(frame: &mut Frame, area: Rect, endpoint: &EndpointRow, input: &TextInput) {
+    let popup = common::centered_rect(70, 30, area);
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Send raw message to {} (ID: {})", endpoint.kind.as_str(), endpoint.id))
+        .style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
     let chunks = Layout::vertical([
-        Constraint::Length(3),
+        Constraint::Length(3), // Input
         Constraint::Min(0),
-        Constraint::Length(3),
+        Constraint::Length(1), // Help
     ])
-    .split(area);
+    .split(inner);
+
+    input.render(frame, chunks[0]);
+
+    let help = Paragraph::new("[Enter] Send  [Esc] Cancel").alignment(Alignment::Center);
+    frame.render_widget(help, chunks[2]);
+}
+
+fn render_viewing<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: Rect, endpoint: &EndpointRow) {
+    let chunks = common::render_screen_layout(area, app.is_compact(area));
 
     let kind_str = endpoint.kind.as_str();
     let active_str = if endpoint.active { "Active" } else { "Inactive" };
-    let title = Paragraph::new(format!(
-        "{} Endpoint (ID: {}) - {}",
-        kind_str, endpoint.id, active_str
-    ))
-    .alignment(Alignment::Center)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::Cyan)),
-    );
-    frame.render_widget(title, chunks[0]);
+    let title = format!("{} Endpoint (ID: {}) - {}", kind_str, endpoint.id, active_str);
+    common::render_title(frame, chunks[0], &title);
 
     // Pretty print JSON
     let pretty_json = if let Ok(value) = serde_json::from_str::<serde_json::Value>(&endpoint.config_json) {
@@ -206,19 +346,30 @@ fn render_viewing<D: DatabaseService>(frame: &mut Frame, _app: &App<D>, area: Re
         endpoint.config_json.clone()
     };
 
+    let config_title = match validate_endpoint_config(&endpoint.kind, &endpoint.config_json) {
+        Ok(()) => "Configuration JSON".to_string(),
+        Err(problem) => format!("Configuration JSON (⚠ {} - press 'e' in the list to repair)", problem),
+    };
     let config = Paragraph::new(pretty_json)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Configuration JSON"),
+                .title(config_title),
         )
         .style(Style::default().fg(Color::Green));
     frame.render_widget(config, chunks[1]);
 
-    let help = Paragraph::new("[Esc] Back")
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(help, chunks[2]);
+    common::render_help(frame, chunks[2], &[("Esc", "Back")]);
+}
+
+/// A form prefilled from `endpoint`'s config, with `note`
+///
+/// Malformed configs open a prefilled form for repair instead of failing.
+fn prefilled_builder(endpoint: &EndpointRow, note: Option<String>) -> Result<ConfigBuilder> {
+    match validate_endpoint_config(&endpoint.kind, &endpoint.config_json) {
+        Ok(()) => ConfigBuilder::from_existing(endpoint.kind.clone(), &endpoint.config_json, note),
+        Err(problem) => Ok(ConfigBuilder::for_repair(endpoint.kind.clone(), &endpoint.config_json, note, &problem)),
+    }
 }
 
 async fn handle_list_mode<D: DatabaseService>(
@@ -226,15 +377,22 @@ async fn handle_list_mode<D: DatabaseService>(
     context: &mut crate::tui::app::AppContext<D>,
     key: KeyEvent,
 ) -> Result<()> {
-    match key.code {
-        KeyCode::Up => state.previous(),
-        KeyCode::Down => state.next(),
-        KeyCode::Char('n') => {
+    // Endpoint under the cursor in the filtered view (cloned so state can be mutated)
+    let current = state.selected_endpoint().cloned();
+
+    match (keys::shortcut_code(&key), current) {
+        (KeyCode::Up, _) => state.previous(),
+        (KeyCode::Down, _) => state.next(),
+        (KeyCode::PageUp, _) => state.prev_page(PAGE_SIZE),
+        (KeyCode::PageDown, _) => state.next_page(PAGE_SIZE),
+        (KeyCode::Home, _) => state.first(),
+        (KeyCode::End, _) => state.last(),
+        (KeyCode::Char('n'), _) => {
             state.mode = EndpointsMode::Creating(ConfigBuilder::new());
         }
-        KeyCode::Char('e') if !state.endpoints.is_empty() => {
-            let endpoint = state.endpoints[state.selected].clone();
-            match ConfigBuilder::from_existing(endpoint.kind.clone(), &endpoint.config_json, endpoint.note.clone()) {
+        (KeyCode::Char('f'), _) => state.cycle_kind_filter(),
+        (KeyCode::Char('e'), Some(endpoint)) => {
+            match prefilled_builder(&endpoint, endpoint.note.clone()) {
                 Ok(builder) => {
                     state.mode = EndpointsMode::Editing {
                         endpoint_id: endpoint.id,
@@ -246,31 +404,73 @@ async fn handle_list_mode<D: DatabaseService>(
                 }
             }
         }
-        KeyCode::Char('d') if !state.endpoints.is_empty() => {
-            let endpoint = state.endpoints[state.selected].clone();
+        (KeyCode::Char('y'), Some(endpoint)) => {
+            // Clone: the same form, but saving creates a new endpoint
+            let note = match endpoint.note.as_deref() {
+                Some(note) => format!("{} (copy)", note),
+                None => "(copy)".to_string(),
+            };
+            match prefilled_builder(&endpoint, Some(note)) {
+                Ok(builder) => state.mode = EndpointsMode::Creating(builder),
+                Err(e) => {
+                    context.messages.set_error(format!("Failed to load config: {}", e));
+                }
+            }
+        }
+        (KeyCode::Char('d'), Some(endpoint)) => {
             let kind_str = endpoint.kind.as_str();
             state.mode = EndpointsMode::ConfirmDelete {
                 endpoint_id: endpoint.id,
                 endpoint_desc: format!("{} (ID: {})", kind_str, endpoint.id),
             };
         }
-        KeyCode::Char(' ') if !state.endpoints.is_empty() => {
-            let endpoint_id = state.endpoints[state.selected].id;
-            match context.db.toggle_endpoint_active(endpoint_id).await {
+        (KeyCode::Char(' '), Some(endpoint)) => {
+            match context.db.toggle_endpoint_active(endpoint.id).await {
                 Ok(_new_status) => {
                     load_endpoints(state, context).await?;
                     // Silently update the list - no success message needed
                 }
                 Err(e) => {
-                    context.messages.set_error(format!("Failed to toggle: {}", e));
+                    context.messages.set_error(format!("Failed to toggle: {}", describe_error(&e)));
                 }
             }
         }
-        KeyCode::Enter if !state.endpoints.is_empty() => {
-            let endpoint = state.endpoints[state.selected].clone();
+        (KeyCode::Char(c @ ('c' | 'C')), Some(endpoint)) => {
+            // Shift+C includes secrets in the generated command
+            let include_secrets = c == 'C';
+            match to_curl(&endpoint, include_secrets) {
+                Ok(command) => {
+                    state.mode = EndpointsMode::ShowingCurl {
+                        command,
+                        include_secrets,
+                        scroll: 0,
+                    };
+                }
+                Err(e) => {
+                    context.messages.set_error(format!("Failed to build curl command: {}", e));
+                }
+            }
+        }
+        (KeyCode::Char('r'), Some(endpoint)) if state.pending_send.is_none() => {
+            let mut input = TextInput::new()
+                .with_placeholder("Message to send")
+                .with_max_length(RAW_MESSAGE_MAX_LEN);
+            input.set_focused(true);
+            state.mode = EndpointsMode::SendingRaw { endpoint, input };
+        }
+        (KeyCode::Char('l'), Some(endpoint)) => match context.db.last_delivery_error(endpoint.id).await {
+            Ok(Some(failure)) => state.mode = EndpointsMode::ShowingLastError { failure, scroll: 0 },
+            Ok(None) => context
+                .messages
+                .set_success_timed(format!("No failed deliveries recorded for endpoint {}", endpoint.id), TOAST_TTL),
+            Err(e) => {
+                context.messages.set_error(format!("Failed to load last error: {}", describe_error(&e)));
+            }
+        },
+        (KeyCode::Enter, Some(endpoint)) => {
             state.mode = EndpointsMode::Viewing { endpoint };
         }
-        KeyCode::Esc => {
+        (KeyCode::Esc, _) => {
             context.current_screen = Screen::MainMenu;
         }
         _ => {}
@@ -298,7 +498,7 @@ async fn handle_creating_mode<D: DatabaseService>(
                             state.mode = EndpointsMode::List;
                         }
                         Err(e) => {
-                            context.messages.set_error(format!("Failed to create endpoint: {}", e));
+                            context.messages.set_error(format!("Failed to create endpoint: {}", describe_error(&e)));
                             state.mode = EndpointsMode::List;
                         }
                     }
@@ -313,8 +513,7 @@ async fn handle_creating_mode<D: DatabaseService>(
             state.mode = EndpointsMode::List;
         }
         Some(ConfigAction::TestWebhook) => {
-            // Trigger webhook validation
-            new_builder.validate_webhook().await.ok();
+            start_webhook_validation(state, &mut new_builder);
             state.mode = EndpointsMode::Creating(new_builder);
         }
         None => {
@@ -345,7 +544,7 @@ async fn handle_editing_mode<D: DatabaseService>(
                             state.mode = EndpointsMode::List;
                         }
                         Err(e) => {
-                            context.messages.set_error(format!("Failed to update endpoint: {}", e));
+                            context.messages.set_error(format!("Failed to update endpoint: {}", describe_error(&e)));
                             state.mode = EndpointsMode::List;
                         }
                     }
@@ -360,8 +559,7 @@ async fn handle_editing_mode<D: DatabaseService>(
             state.mode = EndpointsMode::List;
         }
         Some(ConfigAction::TestWebhook) => {
-            // Trigger webhook validation
-            new_builder.validate_webhook().await.ok();
+            start_webhook_validation(state, &mut new_builder);
             state.mode = EndpointsMode::Editing {
                 endpoint_id,
                 builder: new_builder,
@@ -378,6 +576,13 @@ async fn handle_editing_mode<D: DatabaseService>(
     Ok(())
 }
 
+/// Start the form's webhook check in the background; `on_tick` shows the result
+fn start_webhook_validation(state: &mut EndpointsState, builder: &mut ConfigBuilder) {
+    if state.pending_validation.is_none() {
+        state.pending_validation = Some(BackgroundTask::spawn("Testing webhook...", builder.start_webhook_validation()));
+    }
+}
+
 async fn handle_viewing_mode<D: DatabaseService>(
     state: &mut EndpointsState,
     _context: &mut crate::tui::app::AppContext<D>,
@@ -387,6 +592,64 @@ async fn handle_viewing_mode<D: DatabaseService>(
     Ok(())
 }
 
+/// Scroll the curl or last-error dialog; any other key closes it
+fn handle_scrolling_dialog_mode(state: &mut EndpointsState, key: KeyEvent) {
+    if let EndpointsMode::ShowingCurl { scroll, .. } | EndpointsMode::ShowingLastError { scroll, .. } = &mut state.mode {
+        match keys::shortcut_code(&key) {
+            KeyCode::Up => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down => *scroll = scroll.saturating_add(1),
+            _ => state.mode = EndpointsMode::List,
+        }
+    }
+}
+
+async fn handle_sending_raw_mode<D: DatabaseService>(
+    state: &mut EndpointsState,
+    context: &mut crate::tui::app::AppContext<D>,
+    key: KeyEvent,
+    endpoint: &EndpointRow,
+    input: &TextInput,
+) -> Result<()> {
+    let mut new_input = input.clone();
+
+    match keys::shortcut_code(&key) {
+        KeyCode::Enter => {
+            let message = new_input.value().trim().to_string();
+            if message.is_empty() {
+                context.messages.set_error("Message cannot be empty".to_string());
+            } else {
+                // Sent in the background; `on_tick` shows the outcome
+                let profile = context.notifier_profile.clone();
+                let endpoint = endpoint.clone();
+                state.pending_send = Some(BackgroundTask::spawn("Sending message...", async move {
+                    match send_raw_message(&profile, &endpoint, &message).await {
+                        Ok(()) => Ok(format!("Sent message to {} endpoint (ID: {})", endpoint.kind.as_str(), endpoint.id)),
+                        Err(e) => Err(format!("Send failed: {}", e)),
+                    }
+                }));
+            }
+            state.mode = EndpointsMode::List;
+        }
+        KeyCode::Esc => {
+            state.mode = EndpointsMode::List;
+        }
+        _ => {
+            new_input.handle_key(key);
+            state.mode = EndpointsMode::SendingRaw {
+                endpoint: endpoint.clone(),
+                input: new_input,
+            };
+        }
+    }
+    Ok(())
+}
+
+async fn send_raw_message(profile: &NotifierClientProfile, endpoint: &EndpointRow, message: &str) -> Result<()> {
+    let client = notifiers::build_client(profile)?;
+    let notifier = notifiers::build_notifier(endpoint, client, profile.footers)?;
+    notifier.send_raw(message).await
+}
+
 async fn handle_confirm_delete_mode<D: DatabaseService>(
     state: &mut EndpointsState,
     context: &mut crate::tui::app::AppContext<D>,
@@ -394,7 +657,7 @@ async fn handle_confirm_delete_mode<D: DatabaseService>(
     endpoint_id: i64,
     _endpoint_desc: &str,
 ) -> Result<()> {
-    match key.code {
+    match keys::shortcut_code(&key) {
         KeyCode::Char('y') | KeyCode::Char('Y') => {
             match context.db.delete_endpoint(endpoint_id).await {
                 Ok(_) => {
@@ -402,7 +665,7 @@ async fn handle_confirm_delete_mode<D: DatabaseService>(
                     state.mode = EndpointsMode::List;
                 }
                 Err(e) => {
-                    context.messages.set_error(format!("Failed to delete: {}", e));
+                    context.messages.set_error(format!("Failed to delete: {}", describe_error(&e)));
                     state.mode = EndpointsMode::List;
                 }
             }
@@ -442,6 +705,17 @@ impl<D: DatabaseService> ScreenTrait<D> for EndpointsState {
                 endpoint_id,
                 endpoint_desc,
             } => handle_confirm_delete_mode(self, context, key, *endpoint_id, endpoint_desc).await?,
+            EndpointsMode::ShowingCurl { .. } | EndpointsMode::ShowingLastError { .. } => {
+                handle_scrolling_dialog_mode(self, key)
+            }
+            EndpointsMode::SendingRaw { endpoint, input } => {
+                handle_sending_raw_mode(self, context, key, endpoint, input).await?
+            }
+        }
+
+        // A webhook check only applies to the form it was started from
+        if !matches!(self.mode, EndpointsMode::Creating(_) | EndpointsMode::Editing { .. }) {
+            self.pending_validation = None;
         }
 
         // Check if screen changed
@@ -463,6 +737,25 @@ impl<D: DatabaseService> ScreenTrait<D> for EndpointsState {
         super::endpoints::load_endpoints(self, context).await
     }
 
+    async fn on_tick(&mut self, context: &mut crate::tui::app::AppContext<D>) -> Result<()> {
+        if let Some(result) = self.pending_validation.as_mut().and_then(|task| task.poll()) {
+            self.pending_validation = None;
+            let result = result.unwrap_or_else(|e| Err(describe_error(&e)));
+            if let EndpointsMode::Creating(builder) | EndpointsMode::Editing { builder, .. } = &mut self.mode {
+                builder.finish_webhook_validation(&result);
+            }
+        }
+        if let Some(result) = self.pending_send.as_mut().and_then(|task| task.poll()) {
+            self.pending_send = None;
+            match result {
+                Ok(Ok(message)) => context.messages.set_success_timed(message, TOAST_TTL),
+                Ok(Err(message)) => context.messages.set_error(message),
+                Err(e) => context.messages.set_error(describe_error(&e)),
+            }
+        }
+        Ok(())
+    }
+
     fn id(&self) -> ScreenId {
         ScreenId::Endpoints
     }
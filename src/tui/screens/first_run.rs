@@ -0,0 +1,311 @@
+//! First-run setup wizard
+//!
+//! Offered on the main menu when the database has no endpoints and no
+//! subscriptions. It walks through creating an endpoint, sending it a test
+//! notification and subscribing it to a subreddit. Esc skips the rest of the
+//! wizard at any step; anything already saved is kept.
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::import::is_valid_subreddit_name;
+use crate::models::database::EndpointRow;
+use crate::notifiers::{self, test_run::{send_test_to_all, TestOutcome}};
+use crate::services::{describe_error, DatabaseService};
+use crate::tui::app::AppContext;
+use crate::tui::keys;
+use crate::tui::state::BackgroundTask;
+use crate::tui::validation::ValidationResult;
+use crate::tui::widgets::{common, text_input, ConfigAction, ConfigBuilder, TextInput};
+
+/// Where the wizard is
+#[derive(Debug, Clone)]
+pub enum WizardStep {
+    /// Asking whether to run the wizard at all
+    Offer,
+    /// Filling in the first endpoint
+    Endpoint(ConfigBuilder),
+    /// Endpoint saved; optionally sending it a test notification
+    Test {
+        endpoint: EndpointRow,
+        /// Result of the last test, if one was sent
+        outcome: Option<TestOutcome>,
+    },
+    /// Naming the subreddit to subscribe the endpoint to
+    Subscription { endpoint: EndpointRow, input: TextInput },
+    /// Everything saved; any key closes the wizard
+    Done { endpoint: EndpointRow, subreddit: String },
+}
+
+pub struct FirstRunWizard {
+    pub step: WizardStep,
+    /// Test notification being sent from the `Test` step
+    pub pending_test: Option<BackgroundTask<Vec<TestOutcome>>>,
+    /// Webhook check (Ctrl+T) for the endpoint form
+    pub pending_validation: Option<BackgroundTask<ValidationResult>>,
+}
+
+impl Default for FirstRunWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether the database is empty enough to offer the wizard
+///
+/// Only offered with no endpoints and no subscriptions at all; a failed
+/// lookup counts as not empty, so a broken database doesn't open the wizard.
+pub async fn should_offer<D: DatabaseService>(db: &D) -> bool {
+    let no_endpoints = db.list_endpoints().await.is_ok_and(|endpoints| endpoints.is_empty());
+    no_endpoints && db.list_subscriptions().await.is_ok_and(|subscriptions| subscriptions.is_empty())
+}
+
+impl FirstRunWizard {
+    pub fn new() -> Self {
+        Self { step: WizardStep::Offer, pending_test: None, pending_validation: None }
+    }
+
+    /// Handle a key; returns false once the wizard is finished or skipped
+    pub async fn handle_key<D: DatabaseService>(&mut self, context: &mut AppContext<D>, key: KeyEvent) -> Result<bool> {
+        let step = std::mem::replace(&mut self.step, WizardStep::Offer);
+        self.step = match step {
+            WizardStep::Offer => match keys::shortcut_code(&key) {
+                KeyCode::Char('y') | KeyCode::Enter => WizardStep::Endpoint(ConfigBuilder::new()),
+                KeyCode::Char('n') | KeyCode::Esc => return Ok(false),
+                _ => WizardStep::Offer,
+            },
+            WizardStep::Endpoint(builder) => match self.handle_endpoint_key(context, key, builder).await? {
+                Some(step) => step,
+                None => return Ok(false),
+            },
+            WizardStep::Test { endpoint, outcome } => match keys::shortcut_code(&key) {
+                KeyCode::Char('t') if !self.is_testing() => {
+                    self.start_test(context, &endpoint)?;
+                    WizardStep::Test { endpoint, outcome: None }
+                }
+                KeyCode::Enter if !self.is_testing() => WizardStep::Subscription { endpoint, input: subreddit_input() },
+                KeyCode::Esc => {
+                    self.pending_test = None;
+                    return Ok(false);
+                }
+                _ => WizardStep::Test { endpoint, outcome },
+            },
+            WizardStep::Subscription { endpoint, mut input } => match keys::shortcut_code(&key) {
+                KeyCode::Enter => subscribe(context, endpoint, input).await,
+                KeyCode::Esc => return Ok(false),
+                _ => {
+                    input.handle_key(key);
+                    WizardStep::Subscription { endpoint, input }
+                }
+            },
+            WizardStep::Done { .. } => return Ok(false),
+        };
+        if !matches!(self.step, WizardStep::Endpoint(_)) {
+            self.pending_validation = None;
+        }
+        Ok(true)
+    }
+
+    /// Returns the next step, or None when the endpoint form was cancelled
+    async fn handle_endpoint_key<D: DatabaseService>(
+        &mut self,
+        context: &mut AppContext<D>,
+        key: KeyEvent,
+        mut builder: ConfigBuilder,
+    ) -> Result<Option<WizardStep>> {
+        let action = match builder.handle_input(key) {
+            Ok(action) => action,
+            Err(e) => {
+                context.messages.set_error(format!("Validation error: {}", e));
+                return Ok(Some(WizardStep::Endpoint(builder)));
+            }
+        };
+        match action {
+            Some(ConfigAction::Save) => {
+                let json = match builder.build_json() {
+                    Ok(json) => json,
+                    Err(e) => {
+                        context.messages.set_error(format!("Validation error: {}", e));
+                        return Ok(Some(WizardStep::Endpoint(builder)));
+                    }
+                };
+                let created = context.db.create_endpoint(builder.endpoint_type.as_str(), &json, builder.get_note()).await;
+                match created {
+                    Ok(id) => {
+                        let endpoint = context.db.get_endpoint(id).await?;
+                        Ok(Some(WizardStep::Test { endpoint, outcome: None }))
+                    }
+                    Err(e) => {
+                        context.messages.set_error(format!("Failed to create endpoint: {}", describe_error(&e)));
+                        Ok(Some(WizardStep::Endpoint(builder)))
+                    }
+                }
+            }
+            Some(ConfigAction::Cancel) => Ok(None),
+            Some(ConfigAction::TestWebhook) => {
+                if self.pending_validation.is_none() {
+                    let check = builder.start_webhook_validation();
+                    self.pending_validation = Some(BackgroundTask::spawn("Testing webhook...", check));
+                }
+                Ok(Some(WizardStep::Endpoint(builder)))
+            }
+            None => Ok(Some(WizardStep::Endpoint(builder))),
+        }
+    }
+
+    fn is_testing(&self) -> bool {
+        self.pending_test.as_ref().is_some_and(|task| task.is_busy())
+    }
+
+    /// Send the test notification to the new endpoint in the background
+    fn start_test<D: DatabaseService>(&mut self, context: &AppContext<D>, endpoint: &EndpointRow) -> Result<()> {
+        let profile = context.notifier_profile.clone();
+        let client = notifiers::build_client(&profile)?;
+        let endpoints = vec![endpoint.clone()];
+        self.pending_test = Some(BackgroundTask::spawn("Sending test notification...", async move {
+            send_test_to_all(endpoints, &profile, |ep| notifiers::build_notifier(ep, client.clone(), profile.footers)).await
+        }));
+        Ok(())
+    }
+
+    /// Collect a finished webhook check or test notification
+    pub fn on_tick<D: DatabaseService>(&mut self, context: &mut AppContext<D>) {
+        if let Some(result) = self.pending_validation.as_mut().and_then(|task| task.poll()) {
+            self.pending_validation = None;
+            if let WizardStep::Endpoint(builder) = &mut self.step {
+                builder.finish_webhook_validation(&result.unwrap_or_else(|e| Err(describe_error(&e))));
+            }
+        }
+        let Some(result) = self.pending_test.as_mut().and_then(|task| task.poll()) else {
+            return;
+        };
+        self.pending_test = None;
+        match result {
+            Ok(outcomes) => {
+                if let WizardStep::Test { outcome, .. } = &mut self.step {
+                    *outcome = outcomes.into_iter().next();
+                }
+            }
+            Err(e) => context.messages.set_error(describe_error(&e)),
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, compact: bool) {
+        let chunks = common::render_screen_layout(area, compact);
+        common::render_title(frame, chunks[0], "Welcome to Reddit Notifier");
+
+        let (heading, mut lines, help): (&str, Vec<Line>, Vec<(&str, &str)>) = match &self.step {
+            WizardStep::Offer => (
+                "Getting started",
+                vec![
+                    Line::from("There are no endpoints or subscriptions yet."),
+                    Line::from(""),
+                    Line::from("This short setup will:"),
+                    Line::from("  1. add an endpoint to send notifications to"),
+                    Line::from("  2. send it a test notification"),
+                    Line::from("  3. subscribe it to a subreddit"),
+                ],
+                vec![("y/Enter", "Start setup"), ("n/Esc", "Skip")],
+            ),
+            WizardStep::Endpoint(_) => (
+                "Step 1 of 3: Add an endpoint",
+                vec![Line::from("Fill in the endpoint form.")],
+                vec![("Esc", "Skip setup")],
+            ),
+            WizardStep::Test { endpoint, outcome } => {
+                let mut lines = vec![
+                    Line::from(format!("Saved {} endpoint (ID: {}).", endpoint.kind.as_str(), endpoint.id)),
+                    Line::from(""),
+                ];
+                match outcome {
+                    Some(outcome) => {
+                        let color = if outcome.result.is_ok() { Color::Green } else { Color::Red };
+                        lines.push(Line::styled(outcome.summary_line(), Style::default().fg(color)));
+                    }
+                    None if self.pending_test.is_none() => {
+                        lines.push(Line::from("Send a test notification to check that it arrives."));
+                    }
+                    None => {}
+                }
+                (
+                    "Step 2 of 3: Test the endpoint",
+                    lines,
+                    vec![("t", "Send test"), ("Enter", "Continue"), ("Esc", "Skip setup")],
+                )
+            }
+            WizardStep::Subscription { .. } => (
+                "Step 3 of 3: Subscribe to a subreddit",
+                vec![Line::from("Subreddit to notify this endpoint about (without r/):")],
+                vec![("Enter", "Subscribe"), ("Esc", "Skip setup")],
+            ),
+            WizardStep::Done { endpoint, subreddit } => (
+                "All set",
+                vec![
+                    Line::from(format!(
+                        "New posts in r/{} will be sent to the {} endpoint.",
+                        subreddit,
+                        endpoint.kind.as_str()
+                    )),
+                    Line::from(""),
+                    Line::from("Add more from Manage Subscriptions and Manage Endpoints."),
+                ],
+                vec![("any key", "Close")],
+            ),
+        };
+        if let WizardStep::Subscription { .. } = &self.step {
+            lines.push(Line::from(""));
+        }
+
+        let block = Block::default().borders(Borders::ALL).title(format!(" {} ", heading));
+        let inner = block.inner(chunks[1]);
+        frame.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), chunks[1]);
+        if let WizardStep::Subscription { input, .. } = &self.step {
+            let input_area = Rect { y: inner.y + 2, height: 3.min(inner.height.saturating_sub(2)), ..inner };
+            input.render(frame, input_area);
+        }
+        if let Some(task) = &self.pending_test {
+            let spinner_area = Rect { y: inner.y + 2, height: 1.min(inner.height.saturating_sub(2)), ..inner };
+            task.spinner().render(frame, spinner_area, task.label());
+        }
+        common::render_help(frame, chunks[2], &help);
+
+        if let WizardStep::Endpoint(builder) = &self.step {
+            builder.render(frame, area);
+        }
+    }
+}
+
+fn subreddit_input() -> TextInput {
+    let mut input = TextInput::new()
+        .with_placeholder("Enter subreddit name")
+        .with_validator(text_input::subreddit_validator);
+    input.set_focused(true);
+    input
+}
+
+/// Create the subscription and link it to the wizard's endpoint
+async fn subscribe<D: DatabaseService>(context: &mut AppContext<D>, endpoint: EndpointRow, input: TextInput) -> WizardStep {
+    let subreddit = input.value().trim().to_string();
+    if !is_valid_subreddit_name(&subreddit) {
+        context.messages.set_error(format!("'{}' is not a valid subreddit name", subreddit));
+        return WizardStep::Subscription { endpoint, input };
+    }
+    let linked = match context.db.create_subscription(&subreddit).await {
+        Ok(id) => context.db.link_subscription_endpoint(id, endpoint.id).await,
+        Err(e) => Err(e),
+    };
+    match linked {
+        Ok(()) => WizardStep::Done { endpoint, subreddit },
+        Err(e) => {
+            context.messages.set_error(format!("Failed to create subscription: {}", describe_error(&e)));
+            WizardStep::Subscription { endpoint, input }
+        }
+    }
+}
@@ -9,14 +9,43 @@ use ratatui::{
     Frame,
 };
 
+use html_escape::decode_html_entities;
+
 use crate::models::database::NotifiedPostRow;
+use crate::poller::REDDIT_BASE_URL;
 use crate::services::DatabaseService;
 use crate::tui::app::{App, Screen};
+use crate::tui::clipboard;
 use crate::tui::screen_trait::{Screen as ScreenTrait, ScreenId, ScreenTransition};
-use crate::tui::widgets::{common, ColumnDef, SelectableTable};
+use crate::tui::state::{clamp_selection, reselect_by_id, TOAST_TTL};
+use crate::tui::widgets::{common, ColumnDef, DialogType, ModalDialog, SelectableTable, TypedConfirm, TypedConfirmAction};
+use crate::tui::keys;
 
 const PAGE_SIZE: i64 = 50;
 
+/// Display form of a post's first-seen time, e.g. `2026-02-15 09:30:00 UTC`
+pub fn format_first_seen(post: &NotifiedPostRow) -> String {
+    post.first_seen_utc()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| post.first_seen_at.clone())
+}
+
+/// Text for sharing a logged post: its title and subreddit, then its link
+///
+/// Posts logged before titles and URLs were stored fall back to the post ID
+/// and its Reddit permalink.
+pub fn share_text(post: &NotifiedPostRow) -> String {
+    let title = match &post.title {
+        Some(title) => decode_html_entities(title).into_owned(),
+        None => format!("Post {}", post.post_id),
+    };
+    let url = match &post.url {
+        Some(url) => url.clone(),
+        None => format!("{}/r/{}/comments/{}/", REDDIT_BASE_URL, post.subreddit, post.post_id),
+    };
+    format!("{} (r/{})\n{}", title, post.subreddit, url)
+}
+
 pub struct LogsState {
     pub posts: Vec<NotifiedPostRow>,
     pub current_page: i64,
@@ -30,6 +59,12 @@ pub struct LogsState {
     pub truncate_mode: bool,
     pub truncate_days_input: String,
     pub truncate_result: Option<String>, // Result message after truncate
+    /// Typed confirmation shown before truncating (Some = waiting for CONFIRM)
+    pub truncate_confirm: Option<TypedConfirm>,
+    /// Typed confirmation shown before clearing the filtered subreddit's history
+    pub clear_confirm: Option<TypedConfirm>,
+    /// Share text of the selected post, shown in a dialog to copy
+    pub share: Option<String>,
 }
 
 impl Default for LogsState {
@@ -53,6 +88,9 @@ impl LogsState {
             truncate_mode: false,
             truncate_days_input: "7".to_string(), // Default to 7 days
             truncate_result: None,
+            truncate_confirm: None,
+            clear_confirm: None,
+            share: None,
         }
     }
 
@@ -124,7 +162,10 @@ pub async fn load_logs<D: DatabaseService>(
         ((state.current_page + 2) * PAGE_SIZE) as usize
     };
 
+    let previous_id = state.posts.get(state.selected_post).map(|p| p.id);
     state.posts = posts;
+    state.selected_post = reselect_by_id(&state.posts, previous_id)
+        .unwrap_or_else(|| clamp_selection(state.selected_post, state.posts.len()));
     Ok(())
 }
 
@@ -141,31 +182,35 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
             render_confirm_delete(frame, area, post_id);
         }
 
+        if let Some(confirm) = &app.states.logs_state.clear_confirm {
+            confirm.render(frame, area);
+        }
+
+        if let Some(text) = &app.states.logs_state.share {
+            render_share(frame, area, text);
+        }
+
         // Show truncate dialog if needed
         if app.states.logs_state.truncate_mode {
             render_truncate_dialog(frame, app, area);
+            if let Some(confirm) = &app.states.logs_state.truncate_confirm {
+                confirm.render(frame, area);
+            }
         }
     }
 }
 
 fn render_list_mode<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: Rect) {
+    let compact = app.is_compact(area);
     let chunks = Layout::vertical([
-        Constraint::Length(3),
-        Constraint::Length(3),
+        Constraint::Length(common::title_height(compact)),
+        Constraint::Length(common::title_height(compact)), // Filter
         Constraint::Min(0),
-        Constraint::Length(3),
+        Constraint::Length(common::help_height(compact, 1)),
     ])
     .split(area);
 
-    // Title
-    let title = Paragraph::new("Notification History")
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Cyan)),
-        );
-    frame.render_widget(title, chunks[0]);
+    common::render_title(frame, chunks[0], "Notification History");
 
     // Filter display
     let filter_text = if let Some(ref sub) = app.states.logs_state.filter_subreddit {
@@ -175,7 +220,7 @@ fn render_list_mode<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: R
     };
     let filter = Paragraph::new(filter_text)
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(common::chrome_block(compact));
     frame.render_widget(filter, chunks[1]);
 
     // Table using SelectableTable
@@ -203,13 +248,7 @@ fn render_list_mode<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: R
     table.render(frame, chunks[2], |post, _i, is_selected| {
         let (prefix, style) = common::selection_style(is_selected);
 
-        // Format timestamp to be more readable
-        let timestamp_short = post
-            .first_seen_at
-            .split('.')
-            .next()
-            .unwrap_or(&post.first_seen_at)
-            .replace('T', " ");
+        let timestamp_short = format_first_seen(post);
 
         Row::new(vec![
             prefix.to_string(),
@@ -221,16 +260,21 @@ fn render_list_mode<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: R
     });
 
     // Help text
-    let help = Paragraph::new(Line::from(vec![
+    let mut help_spans = vec![
         "[↑/↓] Navigate  ".into(),
         "[←/→] Page  ".into(),
+        "[s] Share  ".into(),
         "[d] Delete  ".into(),
         "[t] Truncate  ".into(),
         "[f] Filter  ".into(),
-        "[Esc] Back".into(),
-    ]))
+    ];
+    if app.states.logs_state.filter_subreddit.is_some() {
+        help_spans.push("[c] Clear subreddit  ".into());
+    }
+    help_spans.push("[Esc] Back".into());
+    let help = Paragraph::new(Line::from(help_spans))
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    .block(common::chrome_block(compact));
     frame.render_widget(help, chunks[3]);
 }
 
@@ -284,6 +328,16 @@ fn render_truncate_dialog<D: DatabaseService>(frame: &mut Frame, app: &App<D>, a
     frame.render_widget(popup, popup_area);
 }
 
+fn render_share(frame: &mut Frame, area: Rect, text: &str) {
+    let mut content: Vec<Line<'static>> = text.lines().map(|l| Line::from(l.to_string())).collect();
+    content.push(Line::from(""));
+    content.push(Line::from("[c] Copy to clipboard  [any other key] Close").alignment(Alignment::Center));
+    ModalDialog::new(DialogType::Info, "Share Post", "")
+        .with_content(content)
+        .with_width_percent(80)
+        .render(frame, area);
+}
+
 fn render_confirm_delete(frame: &mut Frame, area: Rect, post_id: i64) {
     let popup_area = common::centered_rect(50, 30, area);
     let text = format!("Delete log entry #{}?", post_id);
@@ -355,7 +409,7 @@ async fn handle_list_mode<D: DatabaseService>(
     context: &mut crate::tui::app::AppContext<D>,
     key: KeyEvent,
 ) -> Result<()> {
-    match key.code {
+    match keys::shortcut_code(&key) {
         KeyCode::Up => {
             state.prev_post();
         }
@@ -372,6 +426,9 @@ async fn handle_list_mode<D: DatabaseService>(
             state.selected_post = 0;
             load_logs(state, context).await?;
         }
+        KeyCode::Char('s') if !state.posts.is_empty() => {
+            state.share = Some(share_text(&state.posts[state.selected_post]));
+        }
         KeyCode::Char('d') if !state.posts.is_empty() => {
             let post_id = state.posts[state.selected_post].id;
             state.confirm_delete = Some(post_id);
@@ -383,6 +440,26 @@ async fn handle_list_mode<D: DatabaseService>(
             state.truncate_mode = true;
             state.truncate_result = None;
         }
+        KeyCode::Char('c') => {
+            // Only for a single subreddit; `t` covers the whole history
+            let Some(subreddit) = state.filter_subreddit.clone() else {
+                return Ok(());
+            };
+            let count = context
+                .db
+                .get_post_statistics()
+                .await?
+                .into_iter()
+                .find_map(|(sub, count)| (sub == subreddit).then_some(count))
+                .unwrap_or(0);
+            state.clear_confirm = Some(TypedConfirm::new(
+                "Clear Subreddit History",
+                format!(
+                    "Delete all {} logged post(s) from r/{}? Posts from the last 24h may notify again.",
+                    count, subreddit
+                ),
+            ));
+        }
         KeyCode::Esc => {
             context.current_screen = Screen::MainMenu;
         }
@@ -391,6 +468,24 @@ async fn handle_list_mode<D: DatabaseService>(
     Ok(())
 }
 
+/// `c` copies the share text; any other key closes the dialog
+fn handle_share_mode<D: DatabaseService>(state: &mut LogsState, context: &mut crate::tui::app::AppContext<D>, key: KeyEvent) {
+    let Some(text) = state.share.take() else {
+        return;
+    };
+    if keys::shortcut_code(&key) != KeyCode::Char('c') {
+        return;
+    }
+    match clipboard::copy(&text) {
+        Ok(()) => context.messages.set_success_timed("Copied to clipboard".to_string(), TOAST_TTL),
+        Err(e) => {
+            // Keep the dialog open so the text can still be selected by hand
+            context.messages.set_error(format!("{} - select the text to copy it", e));
+            state.share = Some(text);
+        }
+    }
+}
+
 async fn handle_truncate_mode<D: DatabaseService>(
     state: &mut LogsState,
     context: &mut crate::tui::app::AppContext<D>,
@@ -405,7 +500,24 @@ async fn handle_truncate_mode<D: DatabaseService>(
         return Ok(());
     }
 
-    match key.code {
+    if let Some(confirm) = &mut state.truncate_confirm {
+        match confirm.handle_key(key) {
+            Some(TypedConfirmAction::Confirmed) => {
+                state.truncate_confirm = None;
+                // Only reachable after the days input parsed as a positive number
+                let days = state.truncate_days_input.parse::<i64>().unwrap_or_default();
+                state.truncate_result = Some(match context.db.cleanup_old_posts(days).await {
+                    Ok(deleted) => format!("Deleted {} post(s) older than {} day(s)", deleted, days),
+                    Err(e) => format!("Error: {}", e),
+                });
+            }
+            Some(TypedConfirmAction::Cancelled) => state.truncate_confirm = None,
+            None => {}
+        }
+        return Ok(());
+    }
+
+    match keys::shortcut_code(&key) {
         KeyCode::Char(c) if c.is_ascii_digit() && state.truncate_days_input.len() < 3 => {
             // Allow max 3 digits (up to 999 days)
             state.truncate_days_input.push(c);
@@ -414,24 +526,16 @@ async fn handle_truncate_mode<D: DatabaseService>(
             state.truncate_days_input.pop();
         }
         KeyCode::Enter => {
-            // Parse and execute truncate
-            if let Ok(days) = state.truncate_days_input.parse::<i64>() {
-                if days > 0 {
-                    match context.db.cleanup_old_posts(days).await {
-                        Ok(deleted) => {
-                            let msg = format!("Deleted {} post(s) older than {} day(s)", deleted, days);
-                            state.truncate_result = Some(msg);
-                        }
-                        Err(e) => {
-                            let msg = format!("Error: {}", e);
-                            state.truncate_result = Some(msg);
-                        }
-                    }
-                } else {
-                    state.truncate_result = Some("Days must be greater than 0".to_string());
+            // Parse, then ask for typed confirmation before deleting
+            match state.truncate_days_input.parse::<i64>() {
+                Ok(days) if days > 0 => {
+                    state.truncate_confirm = Some(TypedConfirm::new(
+                        "Confirm Truncate",
+                        format!("Permanently delete all posts older than {} day(s)?", days),
+                    ));
                 }
-            } else {
-                state.truncate_result = Some("Invalid number".to_string());
+                Ok(_) => state.truncate_result = Some("Days must be greater than 0".to_string()),
+                Err(_) => state.truncate_result = Some("Invalid number".to_string()),
             }
         }
         KeyCode::Esc => {
@@ -443,12 +547,42 @@ async fn handle_truncate_mode<D: DatabaseService>(
     Ok(())
 }
 
+async fn handle_clear_confirm_mode<D: DatabaseService>(
+    state: &mut LogsState,
+    context: &mut crate::tui::app::AppContext<D>,
+    key: KeyEvent,
+) -> Result<()> {
+    let Some(confirm) = &mut state.clear_confirm else {
+        return Ok(());
+    };
+    match confirm.handle_key(key) {
+        Some(TypedConfirmAction::Confirmed) => {
+            state.clear_confirm = None;
+            let Some(subreddit) = state.filter_subreddit.clone() else {
+                return Ok(());
+            };
+            match context.db.delete_notified_posts_by_subreddit(&subreddit).await {
+                Ok(deleted) => context
+                    .messages
+                    .set_success(format!("Deleted {} post(s) from r/{}", deleted, subreddit)),
+                Err(e) => context.messages.set_error(format!("Failed to clear r/{}: {}", subreddit, e)),
+            }
+            state.current_page = 0;
+            state.selected_post = 0;
+            load_logs(state, context).await?;
+        }
+        Some(TypedConfirmAction::Cancelled) => state.clear_confirm = None,
+        None => {}
+    }
+    Ok(())
+}
+
 async fn handle_filter_mode<D: DatabaseService>(
     state: &mut LogsState,
     context: &mut crate::tui::app::AppContext<D>,
     key: KeyEvent,
 ) -> Result<()> {
-    match key.code {
+    match keys::shortcut_code(&key) {
         KeyCode::Up => {
             state.prev_filter();
         }
@@ -482,7 +616,7 @@ async fn handle_confirm_delete_mode<D: DatabaseService>(
     context: &mut crate::tui::app::AppContext<D>,
     key: KeyEvent,
 ) -> Result<()> {
-    match key.code {
+    match keys::shortcut_code(&key) {
         KeyCode::Char('y') | KeyCode::Char('Y') => {
             if let Some(post_id) = state.confirm_delete {
                 context.db.delete_notified_post(post_id).await?;
@@ -510,6 +644,10 @@ impl<D: DatabaseService> ScreenTrait<D> for LogsState {
 
         if self.truncate_mode {
             handle_truncate_mode(self, context, key).await?;
+        } else if self.share.is_some() {
+            handle_share_mode(self, context, key);
+        } else if self.clear_confirm.is_some() {
+            handle_clear_confirm_mode(self, context, key).await?;
         } else if self.confirm_delete.is_some() {
             handle_confirm_delete_mode(self, context, key).await?;
         } else if self.filter_mode {
@@ -2,19 +2,36 @@ use anyhow::Result;
 use async_trait::async_trait;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
+    style::{Color, Style},
+    text::Line,
     widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
 
-use crate::services::DatabaseService;
-use crate::tui::app::App;
+use std::path::PathBuf;
+
+use crate::poller::request_sync;
+use crate::services::{describe_error, DatabaseService};
+use crate::tui::app::{App, AppContext};
+use crate::tui::backup;
+use crate::tui::screens::first_run::{self, FirstRunWizard};
 use crate::tui::screen_trait::{Screen as ScreenTrait, ScreenId, ScreenTransition};
-use crate::tui::state::Navigable;
-use crate::tui::widgets::common;
+use crate::tui::state::{Navigable, TOAST_TTL};
+use crate::tui::widgets::{common, TypedConfirm, TypedConfirmAction};
+use crate::tui::keys;
+
+/// Word typed to confirm restoring a backup
+const RESTORE_WORD: &str = "RESTORE";
 
 pub struct MainMenuState {
     selected: usize,
     items: Vec<&'static str>,
+    /// Backup waiting for typed confirmation before it replaces all data
+    pub restore_confirm: Option<(PathBuf, TypedConfirm)>,
+    /// Setup wizard, open when the database was empty on the first visit
+    pub first_run: Option<FirstRunWizard>,
+    /// Whether the database has been checked for the wizard this session
+    first_run_checked: bool,
 }
 
 impl Default for MainMenuState {
@@ -34,6 +51,9 @@ impl MainMenuState {
                 "View Logs",
                 "Quit",
             ],
+            restore_confirm: None,
+            first_run: None,
+            first_run_checked: false,
         }
     }
 }
@@ -55,8 +75,13 @@ impl Navigable for MainMenuState {
 pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
     let area = frame.area();
 
+    if let Some(wizard) = &app.states.main_menu_state.first_run {
+        wizard.render(frame, area, app.is_compact(area));
+        return;
+    }
+
     // Create standard 3-section layout using common component
-    let chunks = common::render_screen_layout(area);
+    let chunks = common::render_screen_layout(area, app.is_compact(area));
 
     // Render title using common component
     common::render_title(frame, chunks[0], "Reddit Notifier TUI");
@@ -75,7 +100,21 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
         })
         .collect();
 
-    let list = List::new(items).block(Block::default().borders(Borders::ALL));
+    // Poller status from the daemon's heartbeat
+    let health = app.context.poller_health;
+    let health_color = if health.is_healthy() { Color::Green } else { Color::Red };
+    let health_line = Line::styled(format!(" {} ", health), Style::default().fg(health_color));
+
+    let mut block = Block::default().borders(Borders::ALL).title_bottom(health_line);
+    if !app.context.notifications_enabled {
+        let paused = Line::styled(" Notifications PAUSED ", Style::default().fg(Color::Yellow)).right_aligned();
+        block = block.title_bottom(paused);
+    }
+    if let Some(log_level) = &app.context.log_level {
+        let level = Line::styled(format!(" Log level: {} ", log_level.current()), Style::default().fg(Color::DarkGray));
+        block = block.title_top(level.right_aligned());
+    }
+    let list = List::new(items).block(block);
 
     let mut list_state = ListState::default();
     list_state.select(Some(app.states.main_menu_state.selected));
@@ -83,11 +122,74 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
     frame.render_stateful_widget(list, chunks[1], &mut list_state);
 
     // Render help text using common component
-    common::render_help(
-        frame,
-        chunks[2],
-        &[("↑/↓", "Navigate"), ("Enter", "Select"), ("q", "Quit")],
-    );
+    let mut help = vec![("↑/↓", "Navigate"), ("Enter", "Select"), ("p", "Pause/Resume notifications"), ("s", "Sync now")];
+    if app.context.log_level.is_some() {
+        help.push(("v", "Log level"));
+    }
+    if app.context.backup_base.is_some() {
+        help.push(("b", "Backup"));
+        help.push(("r", "Restore"));
+    }
+    help.push(("q", "Quit"));
+    common::render_help(frame, chunks[2], &help);
+
+    if let Some((_, confirm)) = &app.states.main_menu_state.restore_confirm {
+        confirm.render(frame, area);
+    }
+}
+
+/// Copy the database to a new timestamped file next to it
+async fn backup_database<D: DatabaseService>(context: &mut AppContext<D>) {
+    let Some(database) = &context.backup_base else {
+        return;
+    };
+    let path = backup::backup_path(database, chrono::Utc::now());
+    match context.db.backup_to(&path).await {
+        Ok(()) => context.messages.set_success(format!("Backed up to {}", path.display())),
+        Err(e) => context.messages.set_error(format!("Backup failed: {}", describe_error(&e))),
+    }
+}
+
+/// Ask for confirmation before restoring the newest backup
+fn start_restore<D: DatabaseService>(state: &mut MainMenuState, context: &mut AppContext<D>) {
+    let Some(database) = &context.backup_base else {
+        return;
+    };
+    let Some(path) = backup::latest_backup(database) else {
+        context.messages.set_error(format!("No backup of {} found (press b to create one)", database.display()));
+        return;
+    };
+    let confirm = TypedConfirm::new(
+        "Restore Backup",
+        format!(
+            "Replace all subscriptions, endpoints and history with {}? Changes since then are lost.",
+            path.display()
+        ),
+    )
+    .with_word(RESTORE_WORD);
+    state.restore_confirm = Some((path, confirm));
+}
+
+async fn handle_restore_confirm<D: DatabaseService>(
+    state: &mut MainMenuState,
+    context: &mut AppContext<D>,
+    key: KeyEvent,
+) {
+    let Some((path, confirm)) = &mut state.restore_confirm else {
+        return;
+    };
+    match confirm.handle_key(key) {
+        Some(TypedConfirmAction::Confirmed) => {
+            let path = path.clone();
+            state.restore_confirm = None;
+            match context.db.restore_from(&path).await {
+                Ok(()) => context.messages.set_success(format!("Restored {}", path.display())),
+                Err(e) => context.messages.set_error(format!("Restore failed: {}", describe_error(&e))),
+            }
+        }
+        Some(TypedConfirmAction::Cancelled) => state.restore_confirm = None,
+        None => {}
+    }
 }
 
 #[async_trait]
@@ -96,8 +198,24 @@ impl<D: DatabaseService> ScreenTrait<D> for MainMenuState {
         super::main_menu::render(frame, app)
     }
 
-    async fn handle_key(&mut self, _context: &mut crate::tui::app::AppContext<D>, key: KeyEvent) -> Result<ScreenTransition> {
-        match key.code {
+    async fn handle_key(&mut self, context: &mut AppContext<D>, key: KeyEvent) -> Result<ScreenTransition> {
+        if let Some(wizard) = &mut self.first_run {
+            if context.messages.has_message() {
+                context.messages.clear();
+                return Ok(ScreenTransition::Stay);
+            }
+            if !wizard.handle_key(context, key).await? {
+                self.first_run = None;
+            }
+            return Ok(ScreenTransition::Stay);
+        }
+
+        if self.restore_confirm.is_some() {
+            handle_restore_confirm(self, context, key).await;
+            return Ok(ScreenTransition::Stay);
+        }
+
+        match keys::shortcut_code(&key) {
             KeyCode::Up => self.previous(),
             KeyCode::Down => self.next(),
             KeyCode::Enter => {
@@ -110,6 +228,40 @@ impl<D: DatabaseService> ScreenTrait<D> for MainMenuState {
                     _ => {}
                 }
             }
+            KeyCode::Char('p') => {
+                let enabled = !context.notifications_enabled;
+                match context.db.set_notifications_enabled(enabled).await {
+                    Ok(()) => {
+                        context.notifications_enabled = enabled;
+                        let msg = if enabled {
+                            "Notifications resumed"
+                        } else {
+                            "Notifications paused - new posts are recorded but not delivered"
+                        };
+                        context.messages.set_success_timed(msg.to_string(), TOAST_TTL);
+                    }
+                    Err(e) => {
+                        context.messages.set_error(format!("Failed to toggle notifications: {}", describe_error(&e)));
+                    }
+                }
+            }
+            KeyCode::Char('s') => match request_sync(context.db.as_ref()).await {
+                Ok(()) => context.messages.set_success_timed(
+                    "Sync requested - the poller catches up at a boosted rate from its next cycle".to_string(),
+                    TOAST_TTL,
+                ),
+                Err(e) => context.messages.set_error(format!("Failed to request sync: {}", describe_error(&e))),
+            },
+            KeyCode::Char('v') => {
+                if let Some(log_level) = &context.log_level {
+                    match log_level.cycle() {
+                        Ok(level) => context.messages.set_success_timed(format!("Log level: {}", level), TOAST_TTL),
+                        Err(e) => context.messages.set_error(format!("Failed to change log level: {}", e)),
+                    }
+                }
+            }
+            KeyCode::Char('b') => backup_database(context).await,
+            KeyCode::Char('r') => start_restore(self, context),
             KeyCode::Char('q') => return Ok(ScreenTransition::Quit),
             _ => {}
         }
@@ -117,6 +269,23 @@ impl<D: DatabaseService> ScreenTrait<D> for MainMenuState {
         Ok(ScreenTransition::Stay)
     }
 
+    async fn on_enter(&mut self, context: &mut AppContext<D>) -> Result<()> {
+        if !self.first_run_checked {
+            self.first_run_checked = true;
+            if first_run::should_offer(context.db.as_ref()).await {
+                self.first_run = Some(FirstRunWizard::new());
+            }
+        }
+        Ok(())
+    }
+
+    async fn on_tick(&mut self, context: &mut AppContext<D>) -> Result<()> {
+        if let Some(wizard) = &mut self.first_run {
+            wizard.on_tick(context);
+        }
+        Ok(())
+    }
+
     fn id(&self) -> ScreenId {
         ScreenId::MainMenu
     }
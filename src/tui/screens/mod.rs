@@ -1,4 +1,5 @@
 pub mod endpoints;
+pub mod first_run;
 pub mod logs;
 pub mod main_menu;
 pub mod subscriptions;
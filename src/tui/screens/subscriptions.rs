@@ -1,6 +1,8 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use crossterm::event::{KeyCode, KeyEvent};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Style},
@@ -10,30 +12,63 @@ use ratatui::{
 };
 
 use crate::models::database::{EndpointRow, SubscriptionRow};
-use crate::services::DatabaseService;
+use crate::notifiers::{self, test_run::{send_test_to_all, TestOutcome}};
+use crate::poller::format_age;
+use crate::search::SearchQuery;
+use crate::services::{describe_error, DatabaseService};
 use crate::tui::app::{App, Screen};
+use crate::tui::autocomplete::{is_lookup_query, SubredditAutocomplete, TYPING_PAUSE};
 use crate::tui::screen_trait::{Screen as ScreenTrait, ScreenId, ScreenTransition};
-use crate::tui::state::Navigable;
-use crate::tui::widgets::{common, text_input, CheckboxList, ColumnDef, ModalDialog, SelectableTable, TextInput};
+use crate::tui::state::{clamp_selection, reselect_by_id, BackgroundTask, Navigable, PAGE_SIZE};
+use crate::tui::widgets::{common, text_input, CheckboxList, ColumnDef, DialogType, Dropdown, ModalDialog, SelectableTable, TextInput};
+use crate::tui::keys;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SubscriptionsMode {
     List,
     Creating(TextInput), // Input widget
+    /// New search subscription; an empty subreddit searches all of Reddit
+    CreatingSearch {
+        query: TextInput,
+        subreddit: TextInput,
+        editing_subreddit: bool,
+    },
     ManagingEndpoints {
         subscription_id: i64,
         checkbox_list: CheckboxList<EndpointRow>,
+        /// Endpoint IDs linked when the screen was opened, to detect changes on save
+        linked_ids: Vec<i64>,
     },
     ConfirmDelete {
         subscription_id: i64,
         subreddit_name: String,
     },
+    TestResults {
+        subreddit_name: String,
+        outcomes: Vec<TestOutcome>,
+    },
 }
 
 pub struct SubscriptionsState {
     pub subscriptions: Vec<SubscriptionRow>,
     pub selected: usize,
     pub mode: SubscriptionsMode,
+    /// IDs of unmuted subscriptions with no active endpoint (never polled)
+    pub without_active_endpoints: HashSet<i64>,
+    /// Subreddit name lookups while creating (None = free text only)
+    pub autocomplete: Option<SubredditAutocomplete>,
+    /// Suggestions for the name being typed
+    pub suggestions: Option<Dropdown>,
+    /// The last lookup failed; the user is on free text until one succeeds
+    pub suggestions_unavailable: bool,
+    /// Lookup for the latest typed name; replaced on every keystroke
+    pub pending_lookup: Option<BackgroundTask<Result<Option<Vec<String>>>>>,
+    /// Test notifications in flight: subreddit name and per-endpoint outcomes
+    pub pending_test: Option<BackgroundTask<(String, Vec<TestOutcome>)>>,
+    /// Notified posts per subreddit, keyed by lowercased name
+    pub notified_counts: HashMap<String, i64>,
+    /// When each subreddit last produced a notification, keyed by lowercased name
+    pub last_notified: HashMap<String, DateTime<Utc>>,
 }
 
 impl Default for SubscriptionsState {
@@ -48,6 +83,14 @@ impl SubscriptionsState {
             subscriptions: Vec::new(),
             selected: 0,
             mode: SubscriptionsMode::List,
+            without_active_endpoints: HashSet::new(),
+            autocomplete: None,
+            suggestions: None,
+            suggestions_unavailable: false,
+            pending_lookup: None,
+            pending_test: None,
+            notified_counts: HashMap::new(),
+            last_notified: HashMap::new(),
         }
     }
 }
@@ -70,13 +113,29 @@ pub async fn load_subscriptions<D: DatabaseService>(
     state: &mut SubscriptionsState,
     context: &mut crate::tui::app::AppContext<D>,
 ) -> Result<()> {
-    let subs = context.db.list_subscriptions().await?;
-    state.subscriptions = subs;
-    if state.selected >= state.subscriptions.len()
-        && !state.subscriptions.is_empty()
-    {
-        state.selected = state.subscriptions.len() - 1;
+    let previous_id = state.subscriptions.get(state.selected).map(|s| s.id);
+    state.subscriptions = context.db.list_subscriptions().await?;
+    state.without_active_endpoints = context
+        .db
+        .subscriptions_without_active_endpoints()
+        .await?
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+
+    // Reddit's casing of a name may differ from what was typed when subscribing
+    state.notified_counts = HashMap::new();
+    for (subreddit, count) in context.db.get_post_statistics().await? {
+        *state.notified_counts.entry(subreddit.to_lowercase()).or_default() += count;
     }
+    state.last_notified = HashMap::new();
+    for (subreddit, at) in context.db.last_notified_at_per_subreddit().await? {
+        let last = state.last_notified.entry(subreddit.to_lowercase()).or_insert(at);
+        *last = (*last).max(at);
+    }
+
+    state.selected = reselect_by_id(&state.subscriptions, previous_id)
+        .unwrap_or_else(|| clamp_selection(state.selected, state.subscriptions.len()));
     Ok(())
 }
 
@@ -86,6 +145,7 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
     match &app.states.subscriptions_state.mode {
         SubscriptionsMode::List => render_list(frame, app, area),
         SubscriptionsMode::Creating(input) => render_creating(frame, app, area, input),
+        SubscriptionsMode::CreatingSearch { query, subreddit, .. } => render_creating_search(frame, app, area, query, subreddit),
         SubscriptionsMode::ManagingEndpoints { checkbox_list, .. } => {
             render_managing_endpoints(frame, app, area, checkbox_list)
         }
@@ -95,6 +155,10 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
             let dialog = ModalDialog::confirm(prompt);
             dialog.render(frame, area);
         }
+        SubscriptionsMode::TestResults { subreddit_name, outcomes } => {
+            render_list(frame, app, area);
+            render_test_results(frame, area, subreddit_name, outcomes);
+        }
     }
 
     // Show error/success messages using centralized display
@@ -102,30 +166,30 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
 }
 
 fn render_list<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: Rect) {
+    let compact = app.is_compact(area);
     let chunks = Layout::vertical([
-        Constraint::Length(3),
+        Constraint::Length(common::title_height(compact)),
         Constraint::Min(0),
-        Constraint::Length(3),
+        Constraint::Length(common::help_height(compact, 2)), // Two-line help
     ])
     .split(area);
 
-    // Title
-    let title = Paragraph::new("Manage Subscriptions")
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Cyan)),
-        );
-    frame.render_widget(title, chunks[0]);
+    common::render_title(frame, chunks[0], "Manage Subscriptions");
 
     // Table using SelectableTable
     let columns = vec![
         ColumnDef::new("", Constraint::Length(2)),           // Selection marker
         ColumnDef::new("ID", Constraint::Length(5)),
-        ColumnDef::new("Subreddit", Constraint::Percentage(60)),
+        ColumnDef::new("Subreddit", Constraint::Percentage(50)),
+        ColumnDef::new("Status", Constraint::Length(15)),
+        ColumnDef::new("Stickied", Constraint::Length(9)),
+        ColumnDef::new("Priority", Constraint::Length(9)),
+        ColumnDef::new("Notified", Constraint::Length(9)),
+        ColumnDef::new("Last", Constraint::Length(10)),
         ColumnDef::new("Created", Constraint::Percentage(40)),
     ];
+    let state = &app.states.subscriptions_state;
+    let now = Utc::now();
 
     let mut table = SelectableTable::new(
         app.states.subscriptions_state.subscriptions.clone(),
@@ -143,46 +207,98 @@ fn render_list<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: Rect)
             .split(' ')
             .next()
             .unwrap_or(&sub.created_at);
+        let key = sub.subreddit.to_lowercase();
+        let notified = state.notified_counts.get(&key).copied().unwrap_or(0);
+        let last = state.last_notified.get(&key).map_or("never".to_string(), |at| {
+            // A timestamp slightly in the future (clock skew) reads as 0s
+            format!("{} ago", format_age((now - *at).to_std().unwrap_or_default()))
+        });
         Row::new(vec![
             prefix.to_string(),
             sub.id.to_string(),
-            sub.subreddit.clone(),
+            subscription_label(sub),
+            subscription_status(sub, &app.states.subscriptions_state.without_active_endpoints).to_string(),
+            if sub.skip_stickied { "skip" } else { "include" }.to_string(),
+            if sub.priority { "high" } else { "normal" }.to_string(),
+            notified.to_string(),
+            last,
             created_short.to_string(),
         ])
         .style(style)
     });
 
-    // Help text
-    let help = Paragraph::new(Line::from(vec![
-        "[↑/↓] Navigate  ".into(),
-        "[n] New  ".into(),
-        "[d] Delete  ".into(),
-        "[Enter] Manage Endpoints  ".into(),
-        "[Esc] Back".into(),
-    ]))
+    // Help text (replaced by a spinner while test notifications are sent)
+    if let Some(task) = &app.states.subscriptions_state.pending_test {
+        task.spinner().render(frame, chunks[2], task.label());
+        return;
+    }
+    let help = Paragraph::new(vec![
+        Line::from(vec![
+            "[↑/↓/PgUp/PgDn] Navigate  ".into(),
+            "[Enter] Manage Endpoints  ".into(),
+            "[Esc] Back".into(),
+        ]),
+        Line::from(vec![
+            "[n] New  ".into(),
+            "[f] New search  ".into(),
+            "[d] Delete  ".into(),
+            "[m] Mute  ".into(),
+            "[s] Stickied  ".into(),
+            "[p] Priority  ".into(),
+            "[t] Test".into(),
+        ]),
+    ])
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    .block(common::chrome_block(compact));
     frame.render_widget(help, chunks[2]);
 }
 
-fn render_creating<D: DatabaseService>(frame: &mut Frame, _app: &App<D>, area: Rect, input: &TextInput) {
+/// Subreddit column text; searches show their query
+fn subscription_label(sub: &SubscriptionRow) -> String {
+    match &sub.search {
+        Some(search) if search.restrict_sr => format!("🔍 \"{}\" in r/{}", search.query, sub.subreddit),
+        Some(search) => format!("🔍 \"{}\" (all of Reddit)", search.query),
+        None => sub.subreddit.clone(),
+    }
+}
+
+/// Status column text; flags subscriptions that can't notify anyone
+fn subscription_status(sub: &SubscriptionRow, without_active_endpoints: &HashSet<i64>) -> &'static str {
+    if sub.muted {
+        "muted"
+    } else if without_active_endpoints.contains(&sub.id) {
+        "⚠ no endpoints"
+    } else {
+        "active"
+    }
+}
+
+fn render_test_results(frame: &mut Frame, area: Rect, subreddit_name: &str, outcomes: &[TestOutcome]) {
+    let all_ok = outcomes.iter().all(|o| o.result.is_ok());
+    let dialog_type = if all_ok { DialogType::Success } else { DialogType::Error };
+
+    let mut content: Vec<Line<'static>> = outcomes.iter().map(|o| Line::from(o.summary_line())).collect();
+    content.push(Line::from(""));
+    content.push(Line::from("[any key] Close").alignment(Alignment::Center));
+
+    let dialog = ModalDialog::new(dialog_type, format!("Test results for r/{}", subreddit_name), "")
+        .with_content(content)
+        .with_width_percent(80);
+    dialog.render(frame, area);
+}
+
+fn render_creating<D: DatabaseService>(frame: &mut Frame, app: &App<D>, area: Rect, input: &TextInput) {
+    let compact = app.is_compact(area);
     let chunks = Layout::vertical([
-        Constraint::Length(3),
+        Constraint::Length(common::title_height(compact)),
         Constraint::Length(1), // Label
         Constraint::Length(3), // Input
         Constraint::Min(0),
-        Constraint::Length(3), // Help
+        Constraint::Length(common::help_height(compact, 1)), // Help
     ])
     .split(area);
 
-    let title = Paragraph::new("Create New Subscription")
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Cyan)),
-        );
-    frame.render_widget(title, chunks[0]);
+    common::render_title(frame, chunks[0], "Create New Subscription");
 
     // Label
     let label = Paragraph::new("Subreddit name (alphanumeric + underscores only):")
@@ -192,13 +308,70 @@ fn render_creating<D: DatabaseService>(frame: &mut Frame, _app: &App<D>, area: R
     // TextInput widget
     input.render(frame, chunks[2]);
 
+    // Suggestions below the input, or a note that lookups are failing
+    let state = &app.states.subscriptions_state;
+    let mut help_spans = Vec::new();
+    match &state.suggestions {
+        Some(suggestions) if Navigable::len(suggestions) > 0 => {
+            suggestions.render_inline(frame, chunks[3]);
+            help_spans.push("[↑/↓] Choose  ".into());
+            help_spans.push("[Tab] Use suggestion  ".into());
+        }
+        _ if state.suggestions_unavailable => {
+            let note = Paragraph::new("Suggestions unavailable - type the full subreddit name")
+                .style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(note, chunks[3]);
+        }
+        _ => {}
+    }
+    help_spans.push("[Enter] Save  ".into());
+    help_spans.push("[Esc] Cancel".into());
+
+    let help = Paragraph::new(Line::from(help_spans))
+    .alignment(Alignment::Center)
+    .block(common::chrome_block(compact));
+    frame.render_widget(help, chunks[4]);
+}
+
+fn render_creating_search<D: DatabaseService>(
+    frame: &mut Frame,
+    app: &App<D>,
+    area: Rect,
+    query: &TextInput,
+    subreddit: &TextInput,
+) {
+    let compact = app.is_compact(area);
+    let chunks = Layout::vertical([
+        Constraint::Length(common::title_height(compact)),
+        Constraint::Length(1), // Query label
+        Constraint::Length(3), // Query input
+        Constraint::Length(1), // Subreddit label
+        Constraint::Length(3), // Subreddit input
+        Constraint::Min(0),
+        Constraint::Length(common::help_height(compact, 1)), // Help
+    ])
+    .split(area);
+
+    common::render_title(frame, chunks[0], "Create New Search");
+
+    let label = Paragraph::new("Search query (Reddit search syntax, e.g. site:github.com tokio):")
+        .style(Style::default().fg(Color::Yellow));
+    frame.render_widget(label, chunks[1]);
+    query.render(frame, chunks[2]);
+
+    let label = Paragraph::new("Only in subreddit (leave empty to search all of Reddit):")
+        .style(Style::default().fg(Color::Yellow));
+    frame.render_widget(label, chunks[3]);
+    subreddit.render(frame, chunks[4]);
+
     let help = Paragraph::new(Line::from(vec![
+        "[Tab] Next field  ".into(),
         "[Enter] Save  ".into(),
         "[Esc] Cancel".into(),
     ]))
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(help, chunks[4]);
+    .block(common::chrome_block(compact));
+    frame.render_widget(help, chunks[6]);
 }
 
 fn render_managing_endpoints<D: DatabaseService>(
@@ -207,22 +380,11 @@ fn render_managing_endpoints<D: DatabaseService>(
     area: Rect,
     checkbox_list: &CheckboxList<EndpointRow>,
 ) {
-    let chunks = Layout::vertical([
-        Constraint::Length(3),
-        Constraint::Min(0),
-        Constraint::Length(3),
-    ])
-    .split(area);
+    let compact = app.is_compact(area);
+    let chunks = common::render_screen_layout(area, compact);
 
     let selected_sub = &app.states.subscriptions_state.subscriptions[app.states.subscriptions_state.selected];
-    let title = Paragraph::new(format!("Link Endpoints to '{}'", selected_sub.subreddit))
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Cyan)),
-        );
-    frame.render_widget(title, chunks[0]);
+    common::render_title(frame, chunks[0], &format!("Link Endpoints to '{}'", selected_sub.subreddit));
 
     if checkbox_list.is_empty() {
         let empty = Paragraph::new("No endpoints available. Create one first in Manage Endpoints.")
@@ -252,7 +414,7 @@ fn render_managing_endpoints<D: DatabaseService>(
         "[Esc] Cancel".into(),
     ]))
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    .block(common::chrome_block(compact));
     frame.render_widget(help, chunks[2]);
 }
 
@@ -261,23 +423,74 @@ async fn handle_list_mode<D: DatabaseService>(
     context: &mut crate::tui::app::AppContext<D>,
     key: KeyEvent,
 ) -> Result<()> {
-    match key.code {
+    match keys::shortcut_code(&key) {
         KeyCode::Up => state.previous(),
         KeyCode::Down => state.next(),
+        KeyCode::PageUp => state.prev_page(PAGE_SIZE),
+        KeyCode::PageDown => state.next_page(PAGE_SIZE),
+        KeyCode::Home => state.first(),
+        KeyCode::End => state.last(),
         KeyCode::Char('n') => {
             let mut input = TextInput::new()
                 .with_placeholder("Enter subreddit name")
                 .with_validator(text_input::subreddit_validator);
             input.set_focused(true);
+            state.suggestions = None;
+            state.suggestions_unavailable = false;
             state.mode = SubscriptionsMode::Creating(input);
         }
+        KeyCode::Char('f') => {
+            let mut query = TextInput::new().with_placeholder("Enter search query");
+            query.set_focused(true);
+            let subreddit = TextInput::new()
+                .with_placeholder("All of Reddit")
+                .with_validator(text_input::subreddit_validator);
+            state.mode = SubscriptionsMode::CreatingSearch { query, subreddit, editing_subreddit: false };
+        }
         KeyCode::Char('d') if !state.subscriptions.is_empty() => {
             let sub = &state.subscriptions[state.selected];
             state.mode = SubscriptionsMode::ConfirmDelete {
                 subscription_id: sub.id,
-                subreddit_name: sub.subreddit.clone(),
+                subreddit_name: subscription_label(sub),
             };
         }
+        KeyCode::Char('m') if !state.subscriptions.is_empty() => {
+            let subscription_id = state.subscriptions[state.selected].id;
+            match context.db.toggle_subscription_muted(subscription_id).await {
+                Ok(_muted) => {
+                    load_subscriptions(state, context).await?;
+                }
+                Err(e) => {
+                    context.messages.set_error(format!("Failed to toggle mute: {}", describe_error(&e)));
+                }
+            }
+        }
+        KeyCode::Char('s') if !state.subscriptions.is_empty() => {
+            let subscription_id = state.subscriptions[state.selected].id;
+            match context.db.toggle_subscription_skip_stickied(subscription_id).await {
+                Ok(_skip) => {
+                    load_subscriptions(state, context).await?;
+                }
+                Err(e) => {
+                    context.messages.set_error(format!("Failed to toggle: {}", describe_error(&e)));
+                }
+            }
+        }
+        KeyCode::Char('p') if !state.subscriptions.is_empty() => {
+            let subscription_id = state.subscriptions[state.selected].id;
+            match context.db.toggle_subscription_priority(subscription_id).await {
+                Ok(_priority) => {
+                    load_subscriptions(state, context).await?;
+                }
+                Err(e) => {
+                    context.messages.set_error(format!("Failed to toggle priority: {}", describe_error(&e)));
+                }
+            }
+        }
+        KeyCode::Char('t') if !state.subscriptions.is_empty() && state.pending_test.is_none() => {
+            let sub = state.subscriptions[state.selected].clone();
+            test_linked_endpoints(state, context, &sub).await?;
+        }
         KeyCode::Enter if !state.subscriptions.is_empty() => {
             let sub = &state.subscriptions[state.selected];
             let all_endpoints = context.db.list_endpoints().await?;
@@ -297,6 +510,7 @@ async fn handle_list_mode<D: DatabaseService>(
             state.mode = SubscriptionsMode::ManagingEndpoints {
                 subscription_id: sub.id,
                 checkbox_list,
+                linked_ids,
             };
         }
         KeyCode::Esc => {
@@ -307,6 +521,42 @@ async fn handle_list_mode<D: DatabaseService>(
     Ok(())
 }
 
+/// Start sending the test notification to every active endpoint linked to `sub`
+///
+/// The sends run in the background; `on_tick` shows the results when they finish.
+async fn test_linked_endpoints<D: DatabaseService>(
+    state: &mut SubscriptionsState,
+    context: &mut crate::tui::app::AppContext<D>,
+    sub: &SubscriptionRow,
+) -> Result<()> {
+    let endpoints: Vec<EndpointRow> = match context.db.get_subscription_endpoints(sub.id).await {
+        Ok(linked) => linked.into_iter().filter(|e| e.active).collect(),
+        Err(e) => {
+            context.messages.set_error(format!("Failed to load endpoints: {}", describe_error(&e)));
+            return Ok(());
+        }
+    };
+
+    if endpoints.is_empty() {
+        context
+            .messages
+            .set_error(format!("No active endpoints linked to r/{}", sub.subreddit));
+        return Ok(());
+    }
+
+    let profile = context.notifier_profile.clone();
+    let client = notifiers::build_client(&profile)?;
+    let subreddit_name = sub.subreddit.clone();
+    state.pending_test = Some(BackgroundTask::spawn(
+        format!("Sending test notifications for r/{}...", sub.subreddit),
+        async move {
+            let outcomes = send_test_to_all(endpoints, &profile, |ep| notifiers::build_notifier(ep, client.clone(), profile.footers)).await;
+            (subreddit_name, outcomes)
+        },
+    ));
+    Ok(())
+}
+
 async fn handle_creating_mode<D: DatabaseService>(
     state: &mut SubscriptionsState,
     context: &mut crate::tui::app::AppContext<D>,
@@ -315,8 +565,27 @@ async fn handle_creating_mode<D: DatabaseService>(
 ) -> Result<()> {
     let mut new_input = input.clone();
 
-    match key.code {
+    match keys::shortcut_code(&key) {
+        KeyCode::Up => {
+            if let Some(suggestions) = &mut state.suggestions {
+                suggestions.previous();
+            }
+        }
+        KeyCode::Down => {
+            if let Some(suggestions) = &mut state.suggestions {
+                suggestions.next();
+            }
+        }
+        KeyCode::Tab => {
+            state.pending_lookup = None;
+            if let Some(name) = state.suggestions.take().and_then(|s| s.selected_option().cloned()) {
+                new_input = new_input.with_value(name);
+            }
+            state.mode = SubscriptionsMode::Creating(new_input);
+        }
         KeyCode::Enter => {
+            state.suggestions = None;
+            state.pending_lookup = None;
             if new_input.value().trim().is_empty() {
                 context.messages.set_error("Subreddit name cannot be empty".to_string());
                 state.mode = SubscriptionsMode::List;
@@ -327,30 +596,127 @@ async fn handle_creating_mode<D: DatabaseService>(
                         state.mode = SubscriptionsMode::List;
                     }
                     Err(e) => {
-                        context.messages.set_error(format!("Failed to create subscription: {}", e));
+                        context.messages.set_error(format!("Failed to create subscription: {}", describe_error(&e)));
                         state.mode = SubscriptionsMode::List;
                     }
                 }
             }
         }
         KeyCode::Esc => {
+            state.suggestions = None;
+            state.pending_lookup = None;
             state.mode = SubscriptionsMode::List;
         }
         _ => {
             // Let TextInput handle the key
             new_input.handle_key(key);
+            if new_input.value() != input.value() {
+                update_suggestions(state, new_input.value());
+            }
             state.mode = SubscriptionsMode::Creating(new_input);
         }
     }
     Ok(())
 }
 
+async fn handle_creating_search_mode<D: DatabaseService>(
+    state: &mut SubscriptionsState,
+    context: &mut crate::tui::app::AppContext<D>,
+    key: KeyEvent,
+    mut query: TextInput,
+    mut subreddit: TextInput,
+    editing_subreddit: bool,
+) -> Result<()> {
+    match keys::shortcut_code(&key) {
+        KeyCode::Tab | KeyCode::BackTab => {
+            query.set_focused(editing_subreddit);
+            subreddit.set_focused(!editing_subreddit);
+            state.mode = SubscriptionsMode::CreatingSearch { query, subreddit, editing_subreddit: !editing_subreddit };
+        }
+        KeyCode::Enter => {
+            let name = subreddit.value().trim();
+            let search = match SearchQuery::parse(query.value(), !name.is_empty()) {
+                Ok(search) => search,
+                Err(e) => {
+                    context.messages.set_error(format!("Invalid search: {}", e));
+                    state.mode = SubscriptionsMode::CreatingSearch { query, subreddit, editing_subreddit };
+                    return Ok(());
+                }
+            };
+            if let Err(e) = context.db.create_search_subscription(name, &search).await {
+                context.messages.set_error(format!("Failed to create search: {}", describe_error(&e)));
+            } else {
+                load_subscriptions(state, context).await?;
+            }
+            state.mode = SubscriptionsMode::List;
+        }
+        KeyCode::Esc => state.mode = SubscriptionsMode::List,
+        _ => {
+            if editing_subreddit {
+                subreddit.handle_key(key);
+            } else {
+                query.handle_key(key);
+            }
+            state.mode = SubscriptionsMode::CreatingSearch { query, subreddit, editing_subreddit };
+        }
+    }
+    Ok(())
+}
+
+/// Refresh the suggestion list after the typed name changed
+///
+/// Existing suggestions are narrowed locally straight away. The lookup runs in
+/// the background after `TYPING_PAUSE`; each keystroke replaces the previous
+/// one, so only the latest query is sent. `on_tick` collects the result.
+fn update_suggestions(state: &mut SubscriptionsState, query: &str) {
+    state.pending_lookup = None;
+    if !is_lookup_query(query) {
+        state.suggestions = None;
+        return;
+    }
+    if let Some(suggestions) = &mut state.suggestions {
+        suggestions.filter = query.to_string();
+        suggestions.selected = 0;
+    }
+
+    let Some(autocomplete) = state.autocomplete.clone() else {
+        return;
+    };
+    let query = query.to_string();
+    state.pending_lookup = Some(BackgroundTask::spawn("Looking up subreddits...", async move {
+        tokio::time::sleep(TYPING_PAUSE).await;
+        autocomplete.suggest(&query).await
+    }));
+}
+
+/// Show the result of a finished lookup; failures fall back to free text
+fn apply_lookup(state: &mut SubscriptionsState, result: Result<Option<Vec<String>>>) {
+    let SubscriptionsMode::Creating(input) = &state.mode else {
+        return;
+    };
+    match result {
+        Ok(Some(names)) => {
+            let mut suggestions = Dropdown::new(names, "Suggestions");
+            suggestions.filter = input.value().to_string();
+            state.suggestions = Some(suggestions);
+            state.suggestions_unavailable = false;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::debug!("Subreddit autocomplete failed: {:#}", e);
+            state.suggestions = None;
+            state.suggestions_unavailable = true;
+        }
+    }
+}
+
 async fn handle_managing_endpoints_mode<D: DatabaseService>(
     state: &mut SubscriptionsState,
     context: &mut crate::tui::app::AppContext<D>,
     key: KeyEvent,
     subscription_id: i64,
     checkbox_list: &CheckboxList<EndpointRow>,
+    linked_ids: &[i64],
 ) -> Result<()> {
     let mut new_list = checkbox_list.clone();
 
@@ -359,39 +725,26 @@ async fn handle_managing_endpoints_mode<D: DatabaseService>(
         state.mode = SubscriptionsMode::ManagingEndpoints {
             subscription_id,
             checkbox_list: new_list,
+            linked_ids: linked_ids.to_vec(),
         };
         return Ok(());
     }
 
     // Handle other keys
-    match key.code {
+    match keys::shortcut_code(&key) {
         KeyCode::Enter => {
-            // Save changes
-            let original_linked = context.db.get_subscription_endpoints(subscription_id)
-                .await?
-                .iter()
-                .map(|e| e.id)
-                .collect::<Vec<_>>();
-
-            // Get IDs of checked endpoints
+            // Save changes: diff against the links loaded on entry, then write once
             let new_linked: Vec<i64> = new_list
                 .get_checked_items()
                 .iter()
                 .map(|endpoint| endpoint.id)
                 .collect();
 
-            // Unlink removed endpoints
-            for id in original_linked.iter() {
-                if !new_linked.contains(id) {
-                    context.db.unlink_subscription_endpoint(subscription_id, *id)
-                        .await?;
-                }
-            }
-
-            // Link new endpoints
-            for id in new_linked.iter() {
-                if !original_linked.contains(id) {
-                    context.db.link_subscription_endpoint(subscription_id, *id).await?;
+            let changed = new_linked.len() != linked_ids.len()
+                || new_linked.iter().any(|id| !linked_ids.contains(id));
+            if changed {
+                if let Err(e) = context.db.set_subscription_endpoints(subscription_id, &new_linked).await {
+                    context.messages.set_error(format!("Failed to save endpoints: {}", describe_error(&e)));
                 }
             }
 
@@ -412,7 +765,7 @@ async fn handle_confirm_delete_mode<D: DatabaseService>(
     subscription_id: i64,
     _subreddit_name: &str,
 ) -> Result<()> {
-    match key.code {
+    match keys::shortcut_code(&key) {
         KeyCode::Char('y') | KeyCode::Char('Y') => {
             match context.db.delete_subscription(subscription_id).await {
                 Ok(_) => {
@@ -420,7 +773,7 @@ async fn handle_confirm_delete_mode<D: DatabaseService>(
                     state.mode = SubscriptionsMode::List;
                 }
                 Err(e) => {
-                    context.messages.set_error(format!("Failed to delete: {}", e));
+                    context.messages.set_error(format!("Failed to delete: {}", describe_error(&e)));
                     state.mode = SubscriptionsMode::List;
                 }
             }
@@ -451,9 +804,13 @@ impl<D: DatabaseService> ScreenTrait<D> for SubscriptionsState {
         match &self.mode.clone() {
             SubscriptionsMode::List => handle_list_mode(self, context, key).await?,
             SubscriptionsMode::Creating(input) => handle_creating_mode(self, context, key, input).await?,
+            SubscriptionsMode::CreatingSearch { query, subreddit, editing_subreddit } => {
+                handle_creating_search_mode(self, context, key, query.clone(), subreddit.clone(), *editing_subreddit).await?
+            }
             SubscriptionsMode::ManagingEndpoints {
                 subscription_id,
                 checkbox_list,
+                linked_ids,
             } => {
                 handle_managing_endpoints_mode(
                     self,
@@ -461,6 +818,7 @@ impl<D: DatabaseService> ScreenTrait<D> for SubscriptionsState {
                     key,
                     *subscription_id,
                     checkbox_list,
+                    linked_ids,
                 )
                 .await?
             }
@@ -468,6 +826,7 @@ impl<D: DatabaseService> ScreenTrait<D> for SubscriptionsState {
                 subscription_id,
                 subreddit_name,
             } => handle_confirm_delete_mode(self, context, key, *subscription_id, subreddit_name).await?,
+            SubscriptionsMode::TestResults { .. } => self.mode = SubscriptionsMode::List,
         }
 
         // Check if screen changed
@@ -489,6 +848,25 @@ impl<D: DatabaseService> ScreenTrait<D> for SubscriptionsState {
         super::subscriptions::load_subscriptions(self, context).await
     }
 
+    async fn on_tick(&mut self, context: &mut crate::tui::app::AppContext<D>) -> Result<()> {
+        if let Some(result) = self.pending_lookup.as_mut().and_then(|task| task.poll()) {
+            self.pending_lookup = None;
+            // A panicked lookup counts as a failed one
+            apply_lookup(self, result.and_then(|lookup| lookup));
+        }
+        let Some(result) = self.pending_test.as_mut().and_then(|task| task.poll()) else {
+            return Ok(());
+        };
+        self.pending_test = None;
+        match result {
+            Ok((subreddit_name, outcomes)) => {
+                self.mode = SubscriptionsMode::TestResults { subreddit_name, outcomes };
+            }
+            Err(e) => context.messages.set_error(describe_error(&e)),
+        }
+        Ok(())
+    }
+
     fn id(&self) -> ScreenId {
         ScreenId::Subscriptions
     }
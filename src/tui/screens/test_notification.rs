@@ -11,11 +11,12 @@ use ratatui::{
 
 use crate::models::database::EndpointRow;
 use crate::notifiers;
-use crate::services::DatabaseService;
+use crate::services::{describe_error, DatabaseService};
 use crate::tui::app::App;
 use crate::tui::screen_trait::{Screen as ScreenTrait, ScreenId, ScreenTransition};
-use crate::tui::state::Navigable;
+use crate::tui::state::{clamp_selection, reselect_by_id, BackgroundTask, Navigable};
 use crate::tui::widgets::common;
+use crate::tui::keys;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum TestStatus {
@@ -26,9 +27,14 @@ pub enum TestStatus {
 }
 
 pub struct TestNotificationState {
+    /// Every endpoint; inactive ones are listed but only sent to when `include_inactive` is on
     pub endpoints: Vec<EndpointRow>,
     pub selected: usize,
     pub status: TestStatus,
+    /// Allow a one-off test of an inactive endpoint (toggled with `i`)
+    pub include_inactive: bool,
+    /// Test notification being sent; finishes with the status to show
+    pub pending_test: Option<BackgroundTask<TestStatus>>,
 }
 
 impl Default for TestNotificationState {
@@ -43,8 +49,19 @@ impl TestNotificationState {
             endpoints: Vec::new(),
             selected: 0,
             status: TestStatus::Ready,
+            include_inactive: false,
+            pending_test: None,
         }
     }
+
+    /// The endpoint under the cursor, if a test may be sent to it
+    ///
+    /// Inactive endpoints are refused unless `include_inactive` is on.
+    pub fn sendable_endpoint(&self) -> Option<&EndpointRow> {
+        self.endpoints
+            .get(self.selected)
+            .filter(|e| e.active || self.include_inactive)
+    }
 }
 
 impl Navigable for TestNotificationState {
@@ -65,46 +82,31 @@ pub async fn load_endpoints<D: DatabaseService>(
     state: &mut TestNotificationState,
     context: &mut crate::tui::app::AppContext<D>,
 ) -> Result<()> {
-    let all_endpoints = context.db.list_endpoints().await?;
-    // Filter to only active endpoints
-    let active_endpoints: Vec<EndpointRow> = all_endpoints
-        .into_iter()
-        .filter(|e| e.active)
-        .collect();
-    state.endpoints = active_endpoints;
-    if state.selected >= state.endpoints.len()
-        && !state.endpoints.is_empty()
-    {
-        state.selected = state.endpoints.len() - 1;
-    }
+    let previous_id = state.endpoints.get(state.selected).map(|e| e.id);
+    state.endpoints = context.db.list_endpoints().await?;
+    state.selected = reselect_by_id(&state.endpoints, previous_id)
+        .unwrap_or_else(|| clamp_selection(state.selected, state.endpoints.len()));
     Ok(())
 }
 
 pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
     let area = frame.area();
+    let compact = app.is_compact(area);
 
     let chunks = Layout::vertical([
-        Constraint::Length(3),
+        Constraint::Length(common::title_height(compact)),
         Constraint::Length(10),
         Constraint::Length(8),
         Constraint::Min(0),
-        Constraint::Length(3),
+        Constraint::Length(common::help_height(compact, 1)),
     ])
     .split(area);
 
-    // Title
-    let title = Paragraph::new("Test Notification")
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Cyan)),
-        );
-    frame.render_widget(title, chunks[0]);
+    common::render_title(frame, chunks[0], "Test Notification");
 
     // Endpoint list
     if app.states.test_notification_state.endpoints.is_empty() {
-        let empty = Paragraph::new("No active endpoints available. Create and activate an endpoint first.")
+        let empty = Paragraph::new("No endpoints available. Create an endpoint first.")
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL).title("Select Endpoint"));
         frame.render_widget(empty, chunks[1]);
@@ -117,8 +119,11 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
             .enumerate()
             .map(|(i, endpoint)| {
                 let is_selected = i == app.states.test_notification_state.selected;
-                let (prefix, style) = common::selection_style(is_selected);
+                let (prefix, mut style) = common::selection_style(is_selected);
                 let kind_str = endpoint.kind.as_str();
+                if !endpoint.active {
+                    style = style.fg(Color::DarkGray);
+                }
 
                 // Format: "prefix number. kind (ID: id) - note"
                 let display = if let Some(note) = &endpoint.note {
@@ -150,6 +155,7 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
                     )
                 };
 
+                let display = if endpoint.active { display } else { format!("{} (inactive)", display) };
                 ListItem::new(display).style(style)
             })
             .collect();
@@ -173,73 +179,77 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
     .block(Block::default().borders(Borders::ALL).title("Message"));
     frame.render_widget(test_message, chunks[2]);
 
-    // Status
-    let (status_text, status_color) = match &app.states.test_notification_state.status {
-        TestStatus::Ready => ("Status: Ready to send test notification".to_string(), Color::White),
-        TestStatus::Sending => ("Status: Sending...".to_string(), Color::Yellow),
-        TestStatus::Success(msg) => (format!("Status: ✓ {}", msg), Color::Green),
-        TestStatus::Error(msg) => (format!("Status: ✗ {}", msg), Color::Red),
-    };
+    // Status (a spinner while the test is being sent)
+    if let Some(task) = &app.states.test_notification_state.pending_test {
+        let block = Block::default().borders(Borders::ALL);
+        let inner = block.inner(chunks[3]);
+        frame.render_widget(block, chunks[3]);
+        task.spinner().render(frame, inner, task.label());
+    } else {
+        let (status_text, status_color) = match &app.states.test_notification_state.status {
+            TestStatus::Ready => ("Status: Ready to send test notification".to_string(), Color::White),
+            TestStatus::Sending => ("Status: Sending...".to_string(), Color::Yellow),
+            TestStatus::Success(msg) => (format!("Status: ✓ {}", msg), Color::Green),
+            TestStatus::Error(msg) => (format!("Status: ✗ {}", msg), Color::Red),
+        };
 
-    let status = Paragraph::new(status_text)
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL))
-        .style(Style::default().fg(status_color));
-    frame.render_widget(status, chunks[3]);
+        let status = Paragraph::new(status_text)
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL))
+            .style(Style::default().fg(status_color));
+        frame.render_widget(status, chunks[3]);
+    }
 
     // Help text
+    let include_inactive = if app.states.test_notification_state.include_inactive { "on" } else { "off" };
     let help = Paragraph::new(Line::from(vec![
         "[↑/↓] Navigate  ".into(),
         "[Enter] Send Test  ".into(),
+        format!("[i] Test inactive: {}  ", include_inactive).into(),
         "[Esc] Back".into(),
     ]))
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    .block(common::chrome_block(compact));
     frame.render_widget(help, chunks[4]);
 }
 
-async fn send_test_notification<D: DatabaseService>(
+/// Start sending a test notification to the selected endpoint in the background
+fn send_test_notification<D: DatabaseService>(
     state: &mut TestNotificationState,
-    _context: &mut crate::tui::app::AppContext<D>,
+    context: &crate::tui::app::AppContext<D>,
 ) -> Result<()> {
+    let Some(endpoint) = state.sendable_endpoint().cloned() else {
+        state.status = TestStatus::Error(
+            "Endpoint is inactive - activate it on the Endpoints screen, or press [i] to test it anyway".to_string(),
+        );
+        return Ok(());
+    };
     state.status = TestStatus::Sending;
 
-    let endpoint = state.endpoints[state.selected].clone();
-
-    // Create HTTP client
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()?;
+    // Same client settings and retry policy as the daemon's deliveries
+    let profile = context.notifier_profile.clone();
+    let client = notifiers::build_client(&profile)?;
 
-    // Build notifier
-    let notifier = match notifiers::build_notifier(&endpoint, client) {
-        Ok(n) => n,
-        Err(e) => {
-            state.status =
-                TestStatus::Error(format!("Failed to build notifier: {}", e));
-            return Ok(());
-        }
-    };
+    state.pending_test = Some(BackgroundTask::spawn("Sending test notification...", async move {
+        // Build notifier
+        let notifier = match notifiers::build_notifier(&endpoint, client, profile.footers) {
+            Ok(n) => n,
+            Err(e) => return TestStatus::Error(format!("Failed to build notifier: {}", e)),
+        };
 
-    // Send test notification
-    match notifier
-        .send(
-            "test",
-            "Test notification from reddit-notifier TUI",
-            "https://reddit.com",
-        )
-        .await
-    {
-        Ok(_) => {
-            let kind_str = notifier.kind();
-            state.status =
-                TestStatus::Success(format!("Successfully sent test to {} endpoint!", kind_str));
-        }
-        Err(e) => {
-            state.status = TestStatus::Error(format!("Send failed: {}", e));
+        // Send test notification (retrying transient failures like the poller does)
+        let ctx = notifiers::SendContext {
+            subreddit: notifiers::test_run::TEST_SUBREDDIT,
+            title: notifiers::test_run::TEST_TITLE,
+            url: notifiers::test_run::TEST_URL,
+            nsfw: false,
+            priority: false,
+        };
+        match notifiers::send_with_retry(notifier.as_ref(), &ctx, profile.max_attempts, profile.base_backoff).await {
+            Ok(_) => TestStatus::Success(format!("Successfully sent test to {} endpoint!", notifier.kind())),
+            Err(e) => TestStatus::Error(format!("Send failed: {}", e)),
         }
-    }
-
+    }));
     Ok(())
 }
 
@@ -250,13 +260,21 @@ impl<D: DatabaseService> ScreenTrait<D> for TestNotificationState {
     }
 
     async fn handle_key(&mut self, context: &mut crate::tui::app::AppContext<D>, key: KeyEvent) -> Result<ScreenTransition> {
-        match key.code {
+        match keys::shortcut_code(&key) {
             KeyCode::Up => self.previous(),
             KeyCode::Down => self.next(),
-            KeyCode::Enter if !self.endpoints.is_empty() => {
-                send_test_notification(self, context).await?;
+            KeyCode::Enter if !self.endpoints.is_empty() && self.pending_test.is_none() => {
+                send_test_notification(self, context)?;
+            }
+            KeyCode::Char('i') if self.pending_test.is_none() => {
+                self.include_inactive = !self.include_inactive;
+                self.status = TestStatus::Ready;
             }
             KeyCode::Esc => {
+                // Leaving abandons a send still in flight
+                if self.pending_test.take().is_some() {
+                    self.status = TestStatus::Ready;
+                }
                 return Ok(ScreenTransition::GoTo(ScreenId::MainMenu));
             }
             _ => {}
@@ -269,6 +287,14 @@ impl<D: DatabaseService> ScreenTrait<D> for TestNotificationState {
         super::test_notification::load_endpoints(self, context).await
     }
 
+    async fn on_tick(&mut self, _context: &mut crate::tui::app::AppContext<D>) -> Result<()> {
+        if let Some(result) = self.pending_test.as_mut().and_then(|task| task.poll()) {
+            self.pending_test = None;
+            self.status = result.unwrap_or_else(|e| TestStatus::Error(describe_error(&e)));
+        }
+        Ok(())
+    }
+
     fn id(&self) -> ScreenId {
         ScreenId::TestNotification
     }
@@ -0,0 +1,178 @@
+//! Persisted TUI session (last screen and list selections)
+//!
+//! The session is written to a small JSON file when the TUI exits and read
+//! back on startup, so the user resumes where they left off.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::services::DatabaseService;
+use crate::tui::app::App;
+use crate::tui::screen_trait::ScreenId;
+use crate::tui::state::Navigable;
+
+/// Snapshot of the screen and per-screen selections
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionState {
+    pub screen: ScreenId,
+    pub main_menu: usize,
+    pub subscriptions: usize,
+    pub endpoints: usize,
+    pub test_notification: usize,
+    pub logs: usize,
+}
+
+impl SessionState {
+    /// Capture the current screen and selections from the app
+    pub fn capture<D: DatabaseService>(app: &App<D>) -> Self {
+        Self {
+            screen: app.context.state_machine.current(),
+            main_menu: app.states.main_menu_state.selected(),
+            subscriptions: app.states.subscriptions_state.selected(),
+            endpoints: app.states.endpoints_state.selected(),
+            test_notification: app.states.test_notification_state.selected(),
+            logs: app.states.logs_state.selected_post,
+        }
+    }
+
+    /// Restore the screen and selections into the app
+    ///
+    /// The restored screen is pushed on top of the main menu, so Esc still
+    /// leads back to it. Data-backed selections are clamped by each screen's
+    /// loader once its rows are fetched.
+    pub fn apply<D: DatabaseService>(&self, app: &mut App<D>) {
+        if self.main_menu < app.states.main_menu_state.len() {
+            app.states.main_menu_state.set_selected(self.main_menu);
+        }
+        app.states.subscriptions_state.set_selected(self.subscriptions);
+        app.states.endpoints_state.set_selected(self.endpoints);
+        app.states.test_notification_state.set_selected(self.test_notification);
+        app.states.logs_state.selected_post = self.logs;
+
+        if self.screen != ScreenId::MainMenu {
+            app.context.state_machine.go_to(self.screen);
+            app.sync_current_screen();
+        }
+    }
+
+    /// Write the session to `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("failed to write TUI state to {}", path.display()))
+    }
+
+    /// Read a session from `path`
+    ///
+    /// Returns `None` if the file is missing or unreadable, so a corrupt state
+    /// file never prevents the TUI from starting.
+    pub fn load(path: &Path) -> Option<Self> {
+        let json = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&json) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid TUI state file {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+}
+
+/// Default location of the session file, next to the SQLite database
+///
+/// E.g. `sqlite://data/notifier.db` -> `data/notifier.db.tui-state.json`.
+/// Returns `None` for in-memory databases.
+pub fn default_state_path(database_url: &str) -> Option<PathBuf> {
+    database_file_path(database_url).map(|path| {
+        let mut name = path.into_os_string();
+        name.push(".tui-state.json");
+        PathBuf::from(name)
+    })
+}
+
+/// The file behind a `sqlite:` database URL, or `None` for an in-memory database
+pub fn database_file_path(database_url: &str) -> Option<PathBuf> {
+    let path = database_url
+        .trim_start_matches("sqlite://")
+        .trim_start_matches("sqlite:");
+    let path = path.split('?').next().unwrap_or(path);
+
+    if path.is_empty() || path.contains(":memory:") {
+        return None;
+    }
+
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::mock_database::MockDatabaseService;
+    use crate::tui::app::Screen;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "reddit-notifier-tui-state-{}.json",
+            std::process::id()
+        ));
+        let state = SessionState {
+            screen: ScreenId::Endpoints,
+            main_menu: 1,
+            subscriptions: 3,
+            endpoints: 2,
+            test_notification: 0,
+            logs: 4,
+        };
+
+        state.save(&path).unwrap();
+        let loaded = SessionState::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, state);
+    }
+
+    #[test]
+    fn test_load_missing_or_invalid_file() {
+        let path = std::env::temp_dir().join(format!(
+            "reddit-notifier-tui-state-invalid-{}.json",
+            std::process::id()
+        ));
+        assert!(SessionState::load(&path).is_none());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(SessionState::load(&path).is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_capture_and_apply_restore_screen_and_selection() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db.clone()).unwrap();
+        app.goto_screen(Screen::Subscriptions);
+        app.states.subscriptions_state.set_selected(1);
+        let saved = SessionState::capture(&app);
+
+        let mut restored = App::new(db).unwrap();
+        saved.apply(&mut restored);
+
+        assert_eq!(restored.context.state_machine.current(), ScreenId::Subscriptions);
+        assert_eq!(restored.context.state_machine.history(), &[ScreenId::MainMenu]);
+        assert_eq!(restored.states.subscriptions_state.selected(), 1);
+    }
+
+    #[test]
+    fn test_default_state_path() {
+        assert_eq!(
+            default_state_path("sqlite://data/notifier.db"),
+            Some(PathBuf::from("data/notifier.db.tui-state.json"))
+        );
+        assert_eq!(
+            default_state_path("sqlite:notifier.db?mode=rwc"),
+            Some(PathBuf::from("notifier.db.tui-state.json"))
+        );
+        assert_eq!(default_state_path("sqlite::memory:"), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Long-running work that must not freeze the event loop
+//!
+//! Awaiting a slow operation inside `handle_key` blocks drawing and input
+//! until it finishes. Instead a screen spawns the work as a `BackgroundTask`,
+//! shows its spinner while busy, and collects the result from `on_tick`.
+
+use anyhow::{anyhow, Result};
+use futures_util::FutureExt;
+use std::future::Future;
+use tokio::task::JoinHandle;
+
+use crate::tui::widgets::Spinner;
+
+/// A spawned task plus the spinner shown while it runs
+#[derive(Debug)]
+pub struct BackgroundTask<T> {
+    label: String,
+    spinner: Spinner,
+    handle: Option<JoinHandle<T>>,
+}
+
+impl<T: Send + 'static> BackgroundTask<T> {
+    /// Start `future` on the runtime; `label` describes it to the user
+    pub fn spawn<F>(label: impl Into<String>, future: F) -> Self
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        Self {
+            label: label.into(),
+            spinner: Spinner::new(),
+            handle: Some(tokio::spawn(future)),
+        }
+    }
+}
+
+impl<T> BackgroundTask<T> {
+    /// What the task is doing ("Sending test notifications...")
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn spinner(&self) -> &Spinner {
+        &self.spinner
+    }
+
+    /// True until the result has been taken
+    pub fn is_busy(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// Advance the spinner and take the result if the task has finished
+    ///
+    /// Returns `Some` exactly once; a task that panicked yields an error.
+    pub fn poll(&mut self) -> Option<Result<T>> {
+        self.spinner.tick();
+        let handle = self.handle.as_mut()?;
+        let finished = handle.now_or_never()?;
+        self.handle = None;
+        Some(finished.map_err(|e| anyhow!("{} failed: {}", self.label.trim_end_matches('.'), e)))
+    }
+}
+
+impl<T> Drop for BackgroundTask<T> {
+    fn drop(&mut self) {
+        // Leaving the screen abandons the work rather than leaking it
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_busy_until_result_taken() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let mut task = BackgroundTask::spawn("Working...", async move {
+            rx.await.unwrap();
+            42
+        });
+        assert!(task.is_busy());
+        assert!(task.poll().is_none());
+        assert_eq!(task.spinner().symbol(), crate::tui::widgets::spinner::FRAMES[1]);
+
+        tx.send(()).unwrap();
+        let result = loop {
+            if let Some(result) = task.poll() {
+                break result;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        };
+        assert_eq!(result.unwrap(), 42);
+        assert!(!task.is_busy());
+        assert!(task.poll().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_panicking_task_is_an_error() {
+        let mut task = BackgroundTask::<()>::spawn("Exploding...", async { panic!("boom") });
+        let result = loop {
+            if let Some(result) = task.poll() {
+                break result;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        };
+        assert!(result.unwrap_err().to_string().starts_with("Exploding failed"));
+    }
+}
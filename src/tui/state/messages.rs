@@ -3,14 +3,21 @@
 //! This module provides a unified way to display error and success messages
 //! across all screens, eliminating duplicate message handling code.
 
+use std::time::{Duration, Instant};
+
 use ratatui::{layout::Rect, Frame};
 
 use crate::tui::widgets::ModalDialog;
 
+/// How long transient confirmations ("Sync requested", "Copied to clipboard") stay up
+pub const TOAST_TTL: Duration = Duration::from_secs(3);
+
 /// Manages error and success messages for the TUI
 ///
 /// Provides centralized state management for displaying temporary messages
 /// to the user. Only one message (error or success) can be displayed at a time.
+/// Success messages may optionally carry a TTL, after which they dismiss
+/// themselves; errors always stay until acknowledged with a key press.
 ///
 /// # Example
 /// ```
@@ -30,6 +37,8 @@ use crate::tui::widgets::ModalDialog;
 pub struct MessageDisplay {
     error: Option<String>,
     success: Option<String>,
+    /// When a timed success message stops being displayed
+    expires_at: Option<Instant>,
 }
 
 impl MessageDisplay {
@@ -42,35 +51,70 @@ impl MessageDisplay {
     pub fn set_error(&mut self, msg: String) {
         self.error = Some(msg);
         self.success = None;
+        self.expires_at = None;
     }
 
     /// Set a success message, clearing any existing error message
     pub fn set_success(&mut self, msg: String) {
         self.success = Some(msg);
         self.error = None;
+        self.expires_at = None;
+    }
+
+    /// Set a success message that auto-dismisses after `ttl`
+    ///
+    /// Clears any existing error message. The message is still cleared by a
+    /// key press like any other, but it also disappears on its own once the
+    /// TTL has elapsed.
+    pub fn set_success_timed(&mut self, msg: String, ttl: Duration) {
+        self.success = Some(msg);
+        self.error = None;
+        self.expires_at = Some(Instant::now() + ttl);
     }
 
     /// Clear all messages
     pub fn clear(&mut self) {
         self.error = None;
         self.success = None;
+        self.expires_at = None;
+    }
+
+    /// Check if the current message is a timed message whose TTL has elapsed
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Drop the current message if it has expired
+    ///
+    /// Called once per frame by the event loop.
+    pub fn clear_expired(&mut self) {
+        if self.is_expired() {
+            self.clear();
+        }
     }
 
     /// Check if any message is currently set
+    ///
+    /// Expired timed messages are not counted, so they never swallow a key press.
     pub fn has_message(&self) -> bool {
-        self.error.is_some() || self.success.is_some()
+        (self.error.is_some() || self.success.is_some()) && !self.is_expired()
     }
 
     /// Render the current message as a popup
     ///
     /// If an error message is set, it will be displayed in red with the title "Error".
     /// If a success message is set, it will be displayed in green with the title "Success".
-    /// If no message is set, nothing is rendered.
+    /// If no message is set, or a timed message has expired, nothing is rendered.
     ///
     /// # Arguments
     /// * `frame` - The frame to render to
     /// * `area` - The full screen area (popup will be centered)
     pub fn render(&self, frame: &mut Frame, area: Rect) {
+        if self.is_expired() {
+            return;
+        }
+
         if let Some(msg) = &self.error {
             let dialog = ModalDialog::error(msg.clone());
             dialog.render(frame, area);
@@ -139,4 +183,38 @@ mod tests {
         assert_eq!(msg.error, None);
         assert_eq!(msg.success, None);
     }
+
+    #[test]
+    fn test_timed_success_expires_after_ttl() {
+        let mut msg = MessageDisplay::default();
+        msg.set_success_timed("Saved".to_string(), Duration::from_millis(20));
+        assert!(msg.has_message());
+        assert!(!msg.is_expired());
+
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(msg.is_expired());
+        assert!(!msg.has_message());
+
+        msg.clear_expired();
+        assert_eq!(msg.success, None);
+        assert_eq!(msg.expires_at, None);
+    }
+
+    #[test]
+    fn test_untimed_messages_never_expire() {
+        let mut msg = MessageDisplay::default();
+        msg.set_error("Error".to_string());
+        assert!(!msg.is_expired());
+        msg.clear_expired();
+        assert_eq!(msg.error, Some("Error".to_string()));
+    }
+
+    #[test]
+    fn test_set_error_clears_timed_success_deadline() {
+        let mut msg = MessageDisplay::default();
+        msg.set_success_timed("Saved".to_string(), Duration::ZERO);
+        msg.set_error("Error".to_string());
+        assert_eq!(msg.expires_at, None);
+        assert!(msg.has_message());
+    }
 }
@@ -1,5 +1,7 @@
+pub mod busy;
 pub mod messages;
 pub mod navigation;
 
-pub use messages::MessageDisplay;
-pub use navigation::Navigable;
+pub use busy::BackgroundTask;
+pub use messages::{MessageDisplay, TOAST_TTL};
+pub use navigation::{clamp_selection, reselect_by_id, HasId, Navigable, PAGE_SIZE};
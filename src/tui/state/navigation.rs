@@ -3,9 +3,15 @@
 //! Provides a common interface for navigating through selectable lists
 //! in the TUI, eliminating duplicate next/previous logic across screens.
 
+use crate::models::database::{EndpointRow, NotifiedPostRow, SubscriptionRow};
+
+/// Number of rows moved by PageUp/PageDown in list screens
+pub const PAGE_SIZE: usize = 10;
+
 /// Trait for types that support list navigation
 ///
-/// Provides default implementations for next/previous navigation with wrapping.
+/// Provides default implementations for next/previous navigation with wrapping,
+/// plus page-jump and first/last movement that stop at the list boundaries.
 /// Types implementing this trait only need to provide the core data accessors.
 ///
 /// # Example
@@ -73,6 +79,21 @@ pub trait Navigable {
         }
     }
 
+    /// Move selection down by `n` items, stopping at the last item
+    fn next_page(&mut self, n: usize) {
+        if !self.is_empty() {
+            let target = self.selected().saturating_add(n).min(self.len() - 1);
+            self.set_selected(target);
+        }
+    }
+
+    /// Move selection up by `n` items, stopping at the first item
+    fn prev_page(&mut self, n: usize) {
+        if !self.is_empty() {
+            self.set_selected(self.selected().saturating_sub(n));
+        }
+    }
+
     /// Move selection to the first item
     fn first(&mut self) {
         if !self.is_empty() {
@@ -89,6 +110,50 @@ pub trait Navigable {
     }
 }
 
+/// Rows with a stable identity (their database id) across reloads
+pub trait HasId {
+    fn id(&self) -> i64;
+}
+
+impl HasId for EndpointRow {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+impl HasId for SubscriptionRow {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+impl HasId for NotifiedPostRow {
+    fn id(&self) -> i64 {
+        self.id
+    }
+}
+
+impl<T: HasId> HasId for &T {
+    fn id(&self) -> i64 {
+        (*self).id()
+    }
+}
+
+/// Index of the row with `previous_id` in freshly reloaded `items`
+///
+/// Lets the cursor follow the same logical row when rows were added, removed
+/// or reordered. Returns `None` when there was no previous row or it is gone;
+/// callers then fall back to the old index via `clamp_selection`.
+pub fn reselect_by_id<T: HasId>(items: &[T], previous_id: Option<i64>) -> Option<usize> {
+    let previous_id = previous_id?;
+    items.iter().position(|item| item.id() == previous_id)
+}
+
+/// Keep `selected` inside a list of `len` rows (0 for an empty list)
+pub fn clamp_selection(selected: usize, len: usize) -> usize {
+    selected.min(len.saturating_sub(1))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,4 +254,89 @@ mod tests {
         nav.previous();
         assert_eq!(nav.selected(), 0); // Wraps to same item
     }
+
+    #[test]
+    fn test_next_page_stops_at_last() {
+        let mut nav = TestNav {
+            items: (0..25).map(|i| i.to_string()).collect(),
+            selected: 0,
+        };
+        nav.next_page(10);
+        assert_eq!(nav.selected(), 10);
+        nav.next_page(10);
+        assert_eq!(nav.selected(), 20);
+        nav.next_page(10);
+        assert_eq!(nav.selected(), 24); // Clamped, no wrap
+        nav.next_page(10);
+        assert_eq!(nav.selected(), 24);
+    }
+
+    #[test]
+    fn test_prev_page_stops_at_first() {
+        let mut nav = TestNav {
+            items: (0..25).map(|i| i.to_string()).collect(),
+            selected: 24,
+        };
+        nav.prev_page(10);
+        assert_eq!(nav.selected(), 14);
+        nav.prev_page(10);
+        assert_eq!(nav.selected(), 4);
+        nav.prev_page(10);
+        assert_eq!(nav.selected(), 0); // Clamped, no wrap
+    }
+
+    #[test]
+    fn test_page_jump_on_empty_and_single_item() {
+        let mut empty = TestNav {
+            items: vec![],
+            selected: 0,
+        };
+        empty.next_page(10); // Should not panic
+        empty.prev_page(10); // Should not panic
+        assert_eq!(empty.selected(), 0);
+
+        let mut single = TestNav {
+            items: vec!["only".to_string()],
+            selected: 0,
+        };
+        single.next_page(10);
+        assert_eq!(single.selected(), 0);
+        single.prev_page(10);
+        assert_eq!(single.selected(), 0);
+    }
+
+    fn sub(id: i64, name: &str) -> SubscriptionRow {
+        SubscriptionRow {
+            id,
+            subreddit: name.to_string(),
+            created_at: "2024-01-01 00:00:00".to_string(),
+            skip_stickied: false,
+            muted: false,
+            priority: false,
+            search: None,
+        }
+    }
+
+    #[test]
+    fn test_reselect_follows_id_after_reorder() {
+        let before = [sub(1, "rust"), sub(2, "golang"), sub(3, "python")];
+        let previous_id = Some(before[1].id);
+
+        // golang moved to the end and a new row appeared at the top
+        let after = [sub(4, "zig"), sub(1, "rust"), sub(3, "python"), sub(2, "golang")];
+        assert_eq!(reselect_by_id(&after, previous_id), Some(3));
+
+        let refs: Vec<&SubscriptionRow> = after.iter().collect();
+        assert_eq!(reselect_by_id(&refs, Some(4)), Some(0));
+    }
+
+    #[test]
+    fn test_reselect_missing_id_falls_back_to_clamped_index() {
+        let after = [sub(1, "rust"), sub(3, "python")];
+        assert_eq!(reselect_by_id(&after, Some(2)), None);
+        assert_eq!(reselect_by_id(&after, None), None);
+        assert_eq!(clamp_selection(2, after.len()), 1);
+        assert_eq!(clamp_selection(0, after.len()), 0);
+        assert_eq!(clamp_selection(5, 0), 0);
+    }
 }
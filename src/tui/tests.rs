@@ -6,11 +6,16 @@
 #[cfg(test)]
 mod navigation_tests {
     use crate::services::mock_database::MockDatabaseService;
+    use crate::models::database::{EndpointKind, EndpointRow};
+    use crate::services::DatabaseService;
     use crate::tui::app::{App, Screen};
     use crate::tui::screens::{
-        endpoints::EndpointsMode, subscriptions::SubscriptionsMode,
+        endpoints::EndpointsMode, first_run::WizardStep, subscriptions::SubscriptionsMode,
     };
+    use crate::tui::autocomplete::SubredditAutocomplete;
     use crate::tui::state::Navigable;
+    use crate::tui::screen_trait::Screen as ScreenTrait;
+    use crate::tui::widgets::Dropdown;
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
     use std::sync::Arc;
 
@@ -180,6 +185,444 @@ mod navigation_tests {
         assert!(!app.context.messages.has_message());
     }
 
+    #[tokio::test]
+    async fn test_subscriptions_t_without_active_endpoints_shows_error() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        // r/rust is only linked to endpoint 1
+        db.toggle_endpoint_active(1).await.unwrap();
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.states
+            .subscriptions_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        app.handle_key(key(KeyCode::Char('t'))).await.unwrap();
+
+        assert_eq!(app.states.subscriptions_state.mode, SubscriptionsMode::List);
+        assert!(app.context.messages.has_message());
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_s_toggles_skip_stickied() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.states
+            .subscriptions_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+        assert!(app.states.subscriptions_state.subscriptions[0].skip_stickied);
+
+        app.handle_key(key(KeyCode::Char('s')))
+            .await
+            .expect("Failed to handle key");
+
+        assert!(!app.states.subscriptions_state.subscriptions[0].skip_stickied);
+        assert!(app.states.subscriptions_state.subscriptions[1].skip_stickied);
+    }
+
+    #[tokio::test]
+    async fn test_logs_truncate_requires_typed_confirmation() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Logs);
+        app.states.logs_state.on_enter(&mut app.context).await.unwrap();
+
+        app.handle_key(key(KeyCode::Char('t'))).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.states.logs_state.truncate_confirm.is_some());
+
+        // Wrong case does not run the truncate
+        for c in "confirm".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.states.logs_state.truncate_confirm.is_some());
+        assert_eq!(app.states.logs_state.truncate_result, None);
+
+        // Esc cancels the confirmation only
+        app.handle_key(key(KeyCode::Esc)).await.unwrap();
+        assert!(app.states.logs_state.truncate_confirm.is_none());
+        assert!(app.states.logs_state.truncate_mode);
+        assert_eq!(app.states.logs_state.truncate_result, None);
+
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        for c in "CONFIRM".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.states.logs_state.truncate_confirm.is_none());
+        assert_eq!(
+            app.states.logs_state.truncate_result.as_deref(),
+            Some("Deleted 0 post(s) older than 7 day(s)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_logs_clear_subreddit_history() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        for (subreddit, post_id) in [("rust", "a"), ("rust", "b"), ("programming", "c")] {
+            db.record_if_new(subreddit, post_id, "title", "").await.unwrap();
+        }
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Logs);
+        app.states.logs_state.on_enter(&mut app.context).await.unwrap();
+
+        // Needs a subreddit filter
+        app.handle_key(key(KeyCode::Char('c'))).await.unwrap();
+        assert!(app.states.logs_state.clear_confirm.is_none());
+
+        // Filter to r/rust (first subscription)
+        app.handle_key(key(KeyCode::Char('f'))).await.unwrap();
+        app.handle_key(key(KeyCode::Down)).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.states.logs_state.filter_subreddit.as_deref(), Some("rust"));
+
+        app.handle_key(key(KeyCode::Char('c'))).await.unwrap();
+        let prompt = &app.states.logs_state.clear_confirm.as_ref().unwrap().prompt;
+        assert!(prompt.starts_with("Delete all 2 logged post(s) from r/rust?"), "{}", prompt);
+
+        for c in "CONFIRM".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.states.logs_state.clear_confirm.is_none());
+        assert!(app.states.logs_state.posts.is_empty());
+
+        // Other subreddits keep their history
+        let remaining = db.list_notified_posts(10, 0).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].subreddit, "programming");
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_flag_missing_active_endpoints() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.states.subscriptions_state.on_enter(&mut app.context).await.unwrap();
+        assert!(db.subscriptions_without_active_endpoints().await.unwrap().is_empty());
+        assert!(app.states.subscriptions_state.without_active_endpoints.is_empty());
+
+        // r/rust is only linked to the Discord endpoint; r/programming also has Pushover
+        db.toggle_endpoint_active(1).await.unwrap();
+        app.states.subscriptions_state.on_enter(&mut app.context).await.unwrap();
+
+        let flagged = db.subscriptions_without_active_endpoints().await.unwrap();
+        assert_eq!(flagged.iter().map(|s| s.subreddit.as_str()).collect::<Vec<_>>(), vec!["rust"]);
+        assert_eq!(
+            app.states.subscriptions_state.without_active_endpoints,
+            std::collections::HashSet::from([1])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_load_notification_counts() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        db.record_if_new("rust", "a", "A", "https://redd.it/a").await.unwrap();
+        db.record_if_new("Rust", "b", "B", "https://redd.it/b").await.unwrap();
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.states.subscriptions_state.on_enter(&mut app.context).await.unwrap();
+
+        let state = &app.states.subscriptions_state;
+        assert_eq!(state.notified_counts.get("rust"), Some(&2));
+        assert!(!state.notified_counts.contains_key("programming"));
+        let last = state.last_notified["rust"];
+        assert!((chrono::Utc::now() - last).num_seconds() <= 2);
+        assert!(!state.last_notified.contains_key("programming"));
+    }
+
+    #[tokio::test]
+    async fn test_manage_endpoints_save_writes_links_once() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.states
+            .subscriptions_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+        // "programming" is linked to both endpoints
+        app.states.subscriptions_state.selected = 1;
+        let sub_id = app.states.subscriptions_state.subscriptions[1].id;
+
+        // Opening and saving without changes writes nothing
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(db.link_writes(), 0);
+
+        // Uncheck the first endpoint and save: one write with the final set
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(matches!(
+            app.states.subscriptions_state.mode,
+            SubscriptionsMode::ManagingEndpoints { .. }
+        ));
+        app.handle_key(key(KeyCode::Char(' '))).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+
+        assert_eq!(app.states.subscriptions_state.mode, SubscriptionsMode::List);
+        assert_eq!(db.link_writes(), 1);
+        let linked: Vec<i64> = db.get_subscription_endpoints(sub_id).await.unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(linked, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_p_toggles_priority() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.states
+            .subscriptions_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+        assert!(!app.states.subscriptions_state.subscriptions[0].priority);
+
+        app.handle_key(key(KeyCode::Char('p')))
+            .await
+            .expect("Failed to handle key");
+
+        assert!(app.states.subscriptions_state.subscriptions[0].priority);
+        let priority = db.priority_subreddits().await.unwrap();
+        assert!(priority.contains(&app.states.subscriptions_state.subscriptions[0].subreddit));
+        assert_eq!(priority.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_home_end_and_paging() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.states
+            .subscriptions_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        app.handle_key(key(KeyCode::End)).await.unwrap();
+        assert_eq!(app.states.subscriptions_state.selected(), 1);
+
+        app.handle_key(key(KeyCode::Home)).await.unwrap();
+        assert_eq!(app.states.subscriptions_state.selected(), 0);
+
+        app.handle_key(key(KeyCode::PageDown)).await.unwrap();
+        assert_eq!(app.states.subscriptions_state.selected(), 1);
+
+        app.handle_key(key(KeyCode::PageUp)).await.unwrap();
+        assert_eq!(app.states.subscriptions_state.selected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reload_keeps_cursor_on_same_endpoint() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Endpoints);
+        app.states.endpoints_state.on_enter(&mut app.context).await.unwrap();
+
+        app.handle_key(key(KeyCode::End)).await.unwrap();
+        let selected_id = app.states.endpoints_state.selected_endpoint().unwrap().id;
+        assert_eq!(app.states.endpoints_state.selected(), 1);
+
+        // The row above it disappears, so its index shifts up on reload
+        db.delete_endpoint(1).await.unwrap();
+        app.states.endpoints_state.on_enter(&mut app.context).await.unwrap();
+        assert_eq!(app.states.endpoints_state.selected(), 0);
+        assert_eq!(app.states.endpoints_state.selected_endpoint().unwrap().id, selected_id);
+
+        // Removing the selected row itself falls back to the nearest index
+        db.delete_endpoint(selected_id).await.unwrap();
+        db.create_endpoint("discord", r#"{"webhook_url":"https://discord.com/api/webhooks/9/x"}"#, None).await.unwrap();
+        app.states.endpoints_state.on_enter(&mut app.context).await.unwrap();
+        assert_eq!(app.states.endpoints_state.selected(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_m_mutes_and_excludes_from_mappings() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.states
+            .subscriptions_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+        assert!(db.all_subreddit_endpoint_mappings().await.unwrap().contains_key("rust"));
+
+        app.handle_key(key(KeyCode::Char('m')))
+            .await
+            .expect("Failed to handle key");
+
+        assert!(app.states.subscriptions_state.subscriptions[0].muted);
+        assert!(!db.all_subreddit_endpoint_mappings().await.unwrap().contains_key("rust"));
+        assert!(!db.unique_subreddits().await.unwrap().contains(&"rust".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_kind_filter_shows_only_matching() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Endpoints);
+        app.states
+            .endpoints_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+        assert_eq!(app.states.endpoints_state.len(), 2);
+
+        // All -> Discord
+        app.handle_key(key(KeyCode::Char('f'))).await.unwrap();
+        let visible = app.states.endpoints_state.visible_endpoints();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].kind, EndpointKind::Discord);
+
+        // Discord -> Pushover; actions target the filtered row
+        app.handle_key(key(KeyCode::Char('f'))).await.unwrap();
+        assert_eq!(app.states.endpoints_state.len(), 1);
+        let pushover_id = app.states.endpoints_state.selected_endpoint().unwrap().id;
+        app.handle_key(key(KeyCode::Char('e'))).await.unwrap();
+        match &app.states.endpoints_state.mode {
+            EndpointsMode::Editing { endpoint_id, .. } => assert_eq!(*endpoint_id, pushover_id),
+            other => panic!("Expected Editing mode, got {:?}", other),
+        }
+        app.handle_key(key(KeyCode::Esc)).await.unwrap();
+
+        // Pushover -> Mattermost -> Teams -> File (none in the test data) -> All
+        app.handle_key(key(KeyCode::Char('f'))).await.unwrap();
+        assert_eq!(app.states.endpoints_state.kind_filter, Some(EndpointKind::Mattermost));
+        assert_eq!(app.states.endpoints_state.len(), 0);
+        app.handle_key(key(KeyCode::Char('f'))).await.unwrap();
+        assert_eq!(app.states.endpoints_state.kind_filter, Some(EndpointKind::Teams));
+        app.handle_key(key(KeyCode::Char('f'))).await.unwrap();
+        assert_eq!(app.states.endpoints_state.kind_filter, Some(EndpointKind::File));
+        app.handle_key(key(KeyCode::Char('f'))).await.unwrap();
+        assert_eq!(app.states.endpoints_state.kind_filter, None);
+        assert_eq!(app.states.endpoints_state.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_edit_malformed_config_opens_repair_form() {
+        let db = Arc::new(MockDatabaseService::new());
+        db.create_endpoint("discord", r#"{"username":"bot"}"#, None).await.unwrap();
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Endpoints);
+        app.states
+            .endpoints_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        app.handle_key(key(KeyCode::Char('e'))).await.unwrap();
+
+        assert!(!app.context.messages.has_message());
+        match &app.states.endpoints_state.mode {
+            EndpointsMode::Editing { builder, .. } => {
+                assert!(builder.fields[0].value.is_empty());
+                assert_eq!(builder.fields[1].value, "bot");
+                assert!(builder.webhook_validation.message().unwrap().starts_with("Needs repair"));
+            }
+            other => panic!("Expected Editing mode, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_c_shows_redacted_curl() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Endpoints);
+        app.states
+            .endpoints_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        app.handle_key(key(KeyCode::Char('c'))).await.unwrap();
+        match &app.states.endpoints_state.mode {
+            EndpointsMode::ShowingCurl { command, include_secrets, .. } => {
+                assert!(!include_secrets);
+                assert!(command.contains("<redacted>"));
+            }
+            other => panic!("Expected ShowingCurl mode, got {:?}", other),
+        }
+
+        // Scrolling keeps the dialog open, any other key closes it
+        app.handle_key(key(KeyCode::Down)).await.unwrap();
+        assert!(matches!(
+            app.states.endpoints_state.mode,
+            EndpointsMode::ShowingCurl { scroll: 1, .. }
+        ));
+        app.handle_key(key(KeyCode::Esc)).await.unwrap();
+        assert!(matches!(app.states.endpoints_state.mode, EndpointsMode::List));
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_y_clones_into_new_endpoint() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Endpoints);
+        app.states
+            .endpoints_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        app.handle_key(key(KeyCode::Char('y'))).await.unwrap();
+        match &app.states.endpoints_state.mode {
+            EndpointsMode::Creating(builder) => assert_eq!(builder.note, "Test Discord endpoint (copy)"),
+            other => panic!("Expected Creating mode, got {:?}", other),
+        }
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(matches!(app.states.endpoints_state.mode, EndpointsMode::List));
+
+        let endpoints = db.list_endpoints().await.unwrap();
+        assert_eq!(endpoints.len(), 3);
+        let (original, copy) = (&endpoints[0], endpoints.iter().find(|e| e.id == 3).unwrap());
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.kind, original.kind);
+        let config = |e: &EndpointRow| serde_json::from_str::<serde_json::Value>(&e.config_json).unwrap();
+        // The copy is saved in the current config shape
+        assert_eq!(config(copy)["webhook_urls"][0], config(original)["webhook_url"]);
+        assert_eq!(config(copy)["version"], 2);
+        assert_eq!(copy.note.as_deref(), Some("Test Discord endpoint (copy)"));
+        // The original is untouched
+        assert_eq!(original.note.as_deref(), Some("Test Discord endpoint"));
+    }
+
+    #[tokio::test]
+    async fn test_endpoints_r_opens_raw_message_input() {
+        let db = Arc::new(MockDatabaseService::with_test_data());
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Endpoints);
+        app.states
+            .endpoints_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        app.handle_key(key(KeyCode::Char('r'))).await.unwrap();
+        app.handle_key(key(KeyCode::Char('h'))).await.unwrap();
+        app.handle_key(key(KeyCode::Char('i'))).await.unwrap();
+        match &app.states.endpoints_state.mode {
+            EndpointsMode::SendingRaw { endpoint, input } => {
+                assert_eq!(endpoint.id, 1);
+                assert_eq!(input.value(), "hi");
+            }
+            other => panic!("Expected SendingRaw mode, got {:?}", other),
+        }
+
+        app.handle_key(key(KeyCode::Esc)).await.unwrap();
+        assert!(matches!(app.states.endpoints_state.mode, EndpointsMode::List));
+
+        // An empty message is rejected without sending
+        app.handle_key(key(KeyCode::Char('r'))).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(matches!(app.states.endpoints_state.mode, EndpointsMode::List));
+        assert!(app.context.messages.has_message());
+    }
+
     #[tokio::test]
     async fn test_subscriptions_mode_defaults_to_list() {
         let db = create_test_db();
@@ -212,6 +655,113 @@ mod navigation_tests {
         assert_eq!(app.states.subscriptions_state.mode, SubscriptionsMode::List);
     }
 
+    #[tokio::test]
+    async fn test_subscriptions_create_search() {
+        let db = create_test_db();
+        let mut app = App::new(db.clone()).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+
+        // An empty query is refused and keeps the form open
+        app.handle_key(key(KeyCode::Char('f'))).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.context.messages.has_message());
+        app.handle_key(key(KeyCode::Esc)).await.unwrap();
+        assert!(matches!(app.states.subscriptions_state.mode, SubscriptionsMode::CreatingSearch { .. }));
+
+        for c in "tokio console".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+        // Tab moves to the subreddit, which only takes valid name characters
+        app.handle_key(key(KeyCode::Tab)).await.unwrap();
+        for c in "r ust".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+        match &app.states.subscriptions_state.mode {
+            SubscriptionsMode::CreatingSearch { query, subreddit, editing_subreddit } => {
+                assert_eq!((query.value(), subreddit.value(), *editing_subreddit), ("tokio console", "rust", true));
+            }
+            other => panic!("Expected CreatingSearch mode, got {:?}", other),
+        }
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.states.subscriptions_state.mode, SubscriptionsMode::List);
+
+        let created = db.list_subscriptions().await.unwrap().into_iter().find(|s| s.search.is_some()).unwrap();
+        assert_eq!(created.subreddit, "rust");
+        assert_eq!(created.search.as_ref().map(|s| (s.query.as_str(), s.restrict_sr)), Some(("tokio console", true)));
+        assert!(app.states.subscriptions_state.subscriptions.iter().any(|s| s.id == created.id));
+        // Not polled through /new
+        assert!(db.unique_subreddits().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_main_menu_backup_then_typed_restore() {
+        let dir = std::env::temp_dir().join(format!("reddit-notifier-tui-backup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = create_test_db();
+        let mut app = App::new(db.clone()).expect("Failed to create app").with_backups(dir.join("notifier.db"));
+
+        // Nothing to restore yet
+        app.handle_key(key(KeyCode::Char('r'))).await.unwrap();
+        assert!(app.states.main_menu_state.restore_confirm.is_none());
+        assert!(app.context.messages.has_message());
+
+        app.handle_key(key(KeyCode::Char('b'))).await.unwrap();
+        let backups = db.backups();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with(&dir));
+        // The mock writes no file; create it so the restore can find it
+        std::fs::write(&backups[0], b"").unwrap();
+
+        // A wrong word keeps the dialog open, Esc closes it
+        app.handle_key(key(KeyCode::Char('r'))).await.unwrap();
+        app.handle_key(key(KeyCode::Char('x'))).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.states.main_menu_state.restore_confirm.is_some());
+        app.handle_key(key(KeyCode::Esc)).await.unwrap();
+        assert!(app.states.main_menu_state.restore_confirm.is_none());
+        assert_eq!(app.context.current_screen, Screen::MainMenu);
+
+        let version = db.config_version().await.unwrap();
+        app.handle_key(key(KeyCode::Char('r'))).await.unwrap();
+        for c in "RESTORE".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.states.main_menu_state.restore_confirm.is_none());
+        assert_eq!(db.config_version().await.unwrap(), version + 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ctrl_letters_do_not_trigger_plain_shortcuts() {
+        let db = create_test_db();
+        let mut app = App::new(db).expect("Failed to create app");
+
+        // Ctrl+Q / Ctrl+P are not the main menu's q / p
+        app.handle_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL)).await.unwrap();
+        assert!(app.context.notifications_enabled);
+        app.handle_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL)).await.unwrap();
+        assert!(!app.context.should_quit);
+
+        // Ctrl+N doesn't open the create form, n does
+        app.goto_screen(Screen::Subscriptions);
+        app.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)).await.unwrap();
+        assert!(matches!(app.states.subscriptions_state.mode, SubscriptionsMode::List));
+        app.handle_key(key(KeyCode::Char('n'))).await.unwrap();
+
+        // Ctrl+A and Alt+A are not typed; a and Shift+A are
+        app.handle_key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL)).await.unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::ALT)).await.unwrap();
+        app.handle_key(key(KeyCode::Char('a'))).await.unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT)).await.unwrap();
+        if let SubscriptionsMode::Creating(input) = &app.states.subscriptions_state.mode {
+            assert_eq!(input.value(), "aA");
+        } else {
+            panic!("Expected Creating mode");
+        }
+    }
+
     #[tokio::test]
     async fn test_subscriptions_creating_accepts_valid_chars() {
         let db = create_test_db();
@@ -256,6 +806,124 @@ mod navigation_tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_subscription_suggestion_fills_input() {
+        let db = create_test_db();
+        let mut app = App::new(db).expect("Failed to create app");
+        app.goto_screen(Screen::Subscriptions);
+        app.handle_key(key(KeyCode::Char('n'))).await.unwrap();
+        app.handle_key(key(KeyCode::Char('r'))).await.unwrap();
+        app.handle_key(key(KeyCode::Char('u'))).await.unwrap();
+
+        // No autocomplete configured: free text only
+        assert!(app.states.subscriptions_state.suggestions.is_none());
+
+        app.states.subscriptions_state.suggestions = Some(Dropdown::new(
+            vec!["rust".to_string(), "rust_gamedev".to_string(), "running".to_string()],
+            "Suggestions",
+        ));
+        // Typing narrows existing suggestions locally
+        app.handle_key(key(KeyCode::Char('s'))).await.unwrap();
+        assert_eq!(Navigable::len(app.states.subscriptions_state.suggestions.as_ref().unwrap()), 2);
+
+        app.handle_key(key(KeyCode::Down)).await.unwrap();
+        app.handle_key(key(KeyCode::Tab)).await.unwrap();
+        if let SubscriptionsMode::Creating(input) = &app.states.subscriptions_state.mode {
+            assert_eq!(input.value(), "rust_gamedev");
+        } else {
+            panic!("Expected Creating mode");
+        }
+        assert!(app.states.subscriptions_state.suggestions.is_none());
+    }
+
+    /// Tick until the subscription name lookup has finished
+    async fn wait_for_lookup(app: &mut App<MockDatabaseService>) {
+        for _ in 0..400 {
+            app.tick().await.unwrap();
+            if app.states.subscriptions_state.pending_lookup.is_none() {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        panic!("lookup did not finish");
+    }
+
+    /// Autocomplete server that suggests the queried name plus `{query}_extra`
+    fn spawn_autocomplete_stub() -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let query = request
+                    .split(['?', '&', ' '])
+                    .find_map(|pair| pair.strip_prefix("query="))
+                    .unwrap_or("")
+                    .to_string();
+                let body = format!(
+                    r#"{{"data": {{"children": [{{"kind": "t5", "data": {{"display_name": "{0}"}}}}, {{"kind": "t5", "data": {{"display_name": "{0}_extra"}}}}]}}}}"#,
+                    query
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_subscription_autocomplete_looks_up_latest_query() {
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let autocomplete = SubredditAutocomplete::new(client).with_base_url(spawn_autocomplete_stub());
+        let mut app = App::new(create_test_db())
+            .expect("Failed to create app")
+            .with_subreddit_autocomplete(autocomplete);
+        app.goto_screen(Screen::Subscriptions);
+        app.handle_key(key(KeyCode::Char('n'))).await.unwrap();
+        for c in "rust".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+
+        // Typing faster than the pause sends only the last query, not the first
+        wait_for_lookup(&mut app).await;
+        let suggestions = app.states.subscriptions_state.suggestions.as_ref().unwrap();
+        assert_eq!(suggestions.options, vec!["rust", "rust_extra"]);
+    }
+
+    #[tokio::test]
+    async fn test_subscription_autocomplete_falls_back_to_free_text() {
+        // Nothing listens on the discard port, so the lookup fails
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let autocomplete = SubredditAutocomplete::new(client).with_base_url("http://127.0.0.1:9");
+        let mut app = App::new(create_test_db())
+            .expect("Failed to create app")
+            .with_subreddit_autocomplete(autocomplete);
+        app.goto_screen(Screen::Subscriptions);
+        app.handle_key(key(KeyCode::Char('n'))).await.unwrap();
+        app.handle_key(key(KeyCode::Char('r'))).await.unwrap();
+        app.handle_key(key(KeyCode::Char('u'))).await.unwrap();
+
+        // The lookup runs in the background; the key handler returns at once
+        assert!(app.states.subscriptions_state.pending_lookup.is_some());
+        wait_for_lookup(&mut app).await;
+        assert!(app.states.subscriptions_state.suggestions_unavailable);
+        assert!(app.states.subscriptions_state.suggestions.is_none());
+
+        app.handle_key(key(KeyCode::Char('s'))).await.unwrap();
+        app.handle_key(key(KeyCode::Char('t'))).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        let subs = app.db().list_subscriptions().await.unwrap();
+        assert_eq!(subs.iter().map(|s| s.subreddit.as_str()).collect::<Vec<_>>(), vec!["rust"]);
+    }
+
     #[tokio::test]
     async fn test_endpoints_mode_defaults_to_list() {
         let db = create_test_db();
@@ -454,4 +1122,615 @@ mod navigation_tests {
         assert_eq!(app.states.endpoints_state.selected(), 0);
         assert_eq!(app.states.test_notification_state.selected(), 0);
     }
+
+    #[tokio::test]
+    async fn test_first_run_wizard_only_offered_on_empty_database() {
+        let mut app = App::new(Arc::new(MockDatabaseService::with_test_data())).unwrap();
+        app.states.main_menu_state.on_enter(&mut app.context).await.unwrap();
+        assert!(app.states.main_menu_state.first_run.is_none());
+
+        let mut app = App::new(create_test_db()).unwrap();
+        app.states.main_menu_state.on_enter(&mut app.context).await.unwrap();
+        assert!(matches!(
+            app.states.main_menu_state.first_run.as_ref().map(|w| &w.step),
+            Some(WizardStep::Offer)
+        ));
+
+        // Skipping closes it for the rest of the session, even though the database is still empty
+        app.handle_key(key(KeyCode::Char('n'))).await.unwrap();
+        assert!(app.states.main_menu_state.first_run.is_none());
+        app.states.main_menu_state.on_enter(&mut app.context).await.unwrap();
+        assert!(app.states.main_menu_state.first_run.is_none());
+        assert_eq!(app.context.current_screen, Screen::MainMenu);
+    }
+
+    #[tokio::test]
+    async fn test_first_run_wizard_creates_linked_subscription() {
+        let db = create_test_db();
+        let mut app = App::new(db.clone()).unwrap();
+        app.states.main_menu_state.on_enter(&mut app.context).await.unwrap();
+        let step = |app: &App<MockDatabaseService>| app.states.main_menu_state.first_run.as_ref().unwrap().step.clone();
+
+        app.handle_key(key(KeyCode::Char('y'))).await.unwrap();
+        assert!(matches!(step(&app), WizardStep::Endpoint(_)));
+
+        // Discord is the default type; the form then starts on the webhook URL
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        for c in "https://discord.com/api/webhooks/1/abc".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        let endpoint = match step(&app) {
+            WizardStep::Test { endpoint, outcome: None } => endpoint,
+            other => panic!("Expected Test step, got {:?}", other),
+        };
+        assert_eq!(db.list_endpoints().await.unwrap(), vec![endpoint.clone()]);
+
+        // Continue without testing; an invalid name keeps the step open
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        app.handle_key(key(KeyCode::Char('r'))).await.unwrap();
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(app.context.messages.has_message());
+        app.handle_key(key(KeyCode::Esc)).await.unwrap();
+        assert!(matches!(step(&app), WizardStep::Subscription { .. }));
+
+        for c in "ust".chars() {
+            app.handle_key(key(KeyCode::Char(c))).await.unwrap();
+        }
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert!(matches!(step(&app), WizardStep::Done { ref subreddit, .. } if subreddit == "rust"));
+
+        let subscriptions = db.list_subscriptions().await.unwrap();
+        assert_eq!(subscriptions.len(), 1);
+        assert_eq!(subscriptions[0].subreddit, "rust");
+        assert_eq!(db.get_subscription_endpoints(subscriptions[0].id).await.unwrap(), vec![endpoint]);
+
+        app.handle_key(key(KeyCode::Char('x'))).await.unwrap();
+        assert!(app.states.main_menu_state.first_run.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_first_run_wizard_cancelled_endpoint_form_skips_setup() {
+        let db = create_test_db();
+        let mut app = App::new(db.clone()).unwrap();
+        app.states.main_menu_state.on_enter(&mut app.context).await.unwrap();
+
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        app.handle_key(key(KeyCode::Esc)).await.unwrap();
+        assert!(app.states.main_menu_state.first_run.is_none());
+        assert!(db.list_endpoints().await.unwrap().is_empty());
+
+        // The main menu works normally afterwards
+        app.handle_key(key(KeyCode::Enter)).await.unwrap();
+        assert_eq!(app.context.current_screen, Screen::Subscriptions);
+    }
+}
+
+/// Rendering tests for TUI screens
+///
+/// Each screen is drawn into a fixed-size `TestBackend` buffer and key strings
+/// (titles, help bars, table contents, empty states) are asserted, so layout
+/// regressions are caught without a real terminal.
+#[cfg(test)]
+mod render_tests {
+    use crate::models::database::TIMESTAMP_FORMAT;
+    use crate::poller::META_LAST_POLL_AT;
+    use crate::services::mock_database::MockDatabaseService;
+    use crate::services::DatabaseService;
+    use crate::tui::app::{App, Screen};
+    use crate::tui::screen_trait::Screen as ScreenTrait;
+    use crate::tui::screens::{
+        endpoints::EndpointsMode, subscriptions::SubscriptionsMode, test_notification::TestStatus,
+    };
+    use crate::tui::widgets::config_builder::WebhookValidationState;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::{backend::TestBackend, Terminal};
+    use std::sync::Arc;
+
+    const WIDTH: u16 = 120;
+    const HEIGHT: u16 = 40;
+
+    /// Helper to create an app backed by the pre-populated mock database
+    fn create_test_app() -> App<MockDatabaseService> {
+        App::new(Arc::new(MockDatabaseService::with_test_data())).expect("Failed to create app")
+    }
+
+    /// Draw the app's current screen and return the buffer as one string per row
+    fn render_app(app: &App<MockDatabaseService>) -> Vec<String> {
+        render_app_sized(app, WIDTH, HEIGHT)
+    }
+
+    /// Draw the app's current screen into a terminal of the given size
+    fn render_app_sized(app: &App<MockDatabaseService>, width: u16, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| {
+                crate::tui::ui::render(frame, app);
+                app.context.messages.render(frame, frame.area());
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol().to_string())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    fn assert_contains(rows: &[String], needle: &str) {
+        assert!(
+            rows.iter().any(|row| row.contains(needle)),
+            "Expected rendered screen to contain {:?}:\n{}",
+            needle,
+            rows.join("\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_main_menu() {
+        let app = create_test_app();
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "Reddit Notifier TUI");
+        assert_contains(&rows, "> Manage Subscriptions");
+        assert_contains(&rows, "Manage Endpoints");
+        assert_contains(&rows, "View Logs");
+        assert_contains(&rows, "[Enter] Select");
+    }
+
+    #[tokio::test]
+    async fn test_render_first_run_wizard() {
+        let mut app = App::new(Arc::new(MockDatabaseService::new())).unwrap();
+        app.states.main_menu_state.on_enter(&mut app.context).await.unwrap();
+        let rows = render_app(&app);
+        assert_contains(&rows, "Welcome to Reddit Notifier");
+        assert_contains(&rows, "There are no endpoints or subscriptions yet.");
+        assert_contains(&rows, "[n/Esc] Skip");
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE)).await.unwrap();
+        assert_contains(&render_app(&app), "Reddit Notifier TUI");
+    }
+
+    #[tokio::test]
+    async fn test_render_main_menu_poller_health() {
+        let mut app = create_test_app();
+        app.refresh_poller_health().await;
+        assert_contains(&render_app(&app), "Poller: unknown (no heartbeat)");
+
+        let now = chrono::Utc::now().format(TIMESTAMP_FORMAT).to_string();
+        app.db().set_meta(META_LAST_POLL_AT, &now).await.unwrap();
+        app.refresh_poller_health().await;
+        assert_contains(&render_app(&app), "Poller: healthy (last poll 0s ago)");
+    }
+
+    #[tokio::test]
+    async fn test_main_menu_pause_toggle() {
+        let mut app = create_test_app();
+        assert!(!render_app(&app).iter().any(|row| row.contains("Notifications PAUSED")));
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE)).await.unwrap();
+        assert!(!app.db().notifications_enabled().await.unwrap());
+        assert_contains(&render_app(&app), "Notifications PAUSED");
+
+        // A resume from elsewhere (e.g. the CLI) shows up on the next refresh
+        app.db().set_notifications_enabled(true).await.unwrap();
+        app.refresh_poller_health().await;
+        assert!(app.context.notifications_enabled);
+        assert!(!render_app(&app).iter().any(|row| row.contains("Notifications PAUSED")));
+    }
+
+    #[tokio::test]
+    async fn test_main_menu_requests_sync() {
+        use crate::poller::{meta_time, META_SYNC_REQUESTED_AT};
+
+        let mut app = create_test_app();
+        assert!(meta_time(app.db().as_ref(), META_SYNC_REQUESTED_AT).await.unwrap().is_none());
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE)).await.unwrap();
+        assert!(meta_time(app.db().as_ref(), META_SYNC_REQUESTED_AT).await.unwrap().is_some());
+        assert_contains(&render_app(&app), "Sync requested");
+    }
+
+    #[tokio::test]
+    async fn test_expired_toast_is_not_drawn() {
+        let mut app = create_test_app();
+        app.context.messages.set_success_timed("Copied to clipboard".to_string(), std::time::Duration::from_millis(20));
+        assert_contains(&render_app(&app), "Copied to clipboard");
+
+        std::thread::sleep(std::time::Duration::from_millis(40));
+        assert!(!render_app(&app).iter().any(|row| row.contains("Copied to clipboard")));
+    }
+
+    #[tokio::test]
+    async fn test_main_menu_cycles_log_level() {
+        use crate::logging::reloadable_filter;
+        use tracing_subscriber::filter::LevelFilter;
+
+        // Without a handle the key does nothing and the level isn't shown
+        let mut app = create_test_app();
+        app.handle_key(KeyEvent::new(KeyCode::Char('v'), KeyModifiers::NONE)).await.unwrap();
+        assert!(!render_app(&app).iter().any(|row| row.contains("Log level")));
+
+        // The layer must stay alive for the handle to reload it
+        let (_layer, handle) = reloadable_filter(tracing_subscriber::EnvFilter::new("info"));
+        let mut app = create_test_app().with_log_level(handle.clone());
+        assert_contains(&render_app(&app), "Log level: info");
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('v'), KeyModifiers::NONE)).await.unwrap();
+        assert_eq!(handle.current(), LevelFilter::DEBUG);
+        assert_contains(&render_app(&app), "Log level: debug");
+    }
+
+    #[tokio::test]
+    async fn test_subscription_test_shows_spinner_until_results() {
+        let db = Arc::new(MockDatabaseService::new());
+        let sub_id = db.create_subscription("rust").await.unwrap();
+        // Fails to build a notifier, so the "send" finishes without any network
+        let ep_id = db.create_endpoint("discord", "{}", None).await.unwrap();
+        db.link_subscription_endpoint(sub_id, ep_id).await.unwrap();
+        let mut app = App::new(db).unwrap();
+        app.goto_screen(Screen::Subscriptions);
+        app.states.subscriptions_state.on_enter(&mut app.context).await.unwrap();
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE)).await.unwrap();
+        assert!(app.states.subscriptions_state.pending_test.as_ref().is_some_and(|t| t.is_busy()));
+        let rows = render_app(&app);
+        assert_contains(&rows, "Sending test notifications for r/rust...");
+        assert!(!rows.iter().any(|row| row.contains("[t] Test")));
+
+        for _ in 0..100 {
+            app.tick().await.unwrap();
+            if app.states.subscriptions_state.pending_test.is_none() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert!(app.states.subscriptions_state.pending_test.is_none());
+        assert!(matches!(app.states.subscriptions_state.mode, SubscriptionsMode::TestResults { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_render_subscriptions_list() {
+        let mut app = create_test_app();
+        app.goto_screen(Screen::Subscriptions);
+        app.states
+            .subscriptions_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "Manage Subscriptions");
+        assert_contains(&rows, "Subreddit");
+        assert_contains(&rows, "rust");
+        assert_contains(&rows, "programming");
+        assert_contains(&rows, "Notified");
+        assert_contains(&rows, "never");
+        assert_contains(&rows, "[Enter] Manage Endpoints");
+    }
+
+    #[tokio::test]
+    async fn test_render_subscriptions_empty_state() {
+        let mut app = App::new(Arc::new(MockDatabaseService::new())).unwrap();
+        app.goto_screen(Screen::Subscriptions);
+
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "No subscriptions yet. Press 'n' to create one.");
+    }
+
+    #[tokio::test]
+    async fn test_render_endpoints_list() {
+        let mut app = create_test_app();
+        app.goto_screen(Screen::Endpoints);
+        app.states
+            .endpoints_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "Manage Endpoints");
+        assert_contains(&rows, "discord");
+        assert_contains(&rows, "pushover");
+        assert_contains(&rows, "Test Discord endpoint");
+        assert_contains(&rows, "[Space] Toggle");
+    }
+
+    #[tokio::test]
+    async fn test_render_endpoint_last_error_dialog() {
+        use crate::models::database::DeliveryStatus;
+        use crate::tui::screens::endpoints::EndpointsMode;
+
+        let mut app = create_test_app();
+        app.db().record_if_new("rust", "abc123", "Hello", "https://redd.it/abc123").await.unwrap();
+        let error = (1..=30).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n");
+        app.db()
+            .record_delivery(1, "abc123", DeliveryStatus::Failed, 250, Some(&format!("discord webhook non-success: 500\n{}", error)))
+            .await
+            .unwrap();
+        app.goto_screen(Screen::Endpoints);
+        app.states.endpoints_state.on_enter(&mut app.context).await.unwrap();
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE)).await.unwrap();
+        let rows = render_app(&app);
+        assert_contains(&rows, "Last error (endpoint 1)");
+        assert_contains(&rows, "Failed at: ");
+        assert_contains(&rows, "Post: r/rust - Hello");
+        assert_contains(&rows, "Link: https://redd.it/abc123");
+        assert_contains(&rows, "discord webhook non-success: 500");
+
+        // Long errors scroll
+        assert!(!rows.iter().any(|row| row.contains("line 30")));
+        for _ in 0..20 {
+            app.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)).await.unwrap();
+        }
+        assert_contains(&render_app(&app), "line 30");
+
+        app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).await.unwrap();
+        assert!(matches!(app.states.endpoints_state.mode, EndpointsMode::List));
+
+        // Endpoints without failures say so instead of opening the dialog
+        app.handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)).await.unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE)).await.unwrap();
+        assert_contains(&render_app(&app), "No failed deliveries recorded for endpoint 2");
+    }
+
+    #[tokio::test]
+    async fn test_render_logs() {
+        let mut app = create_test_app();
+        app.db().record_if_new("rust", "abc123", "Hello", "https://redd.it/abc123").await.unwrap();
+        app.goto_screen(Screen::Logs);
+        app.states.logs_state.on_enter(&mut app.context).await.unwrap();
+
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "Notification History");
+        assert_contains(&rows, "Filter: All Subreddits");
+        assert_contains(&rows, "Page 1 of 1");
+        assert_contains(&rows, "abc123");
+        assert_contains(&rows, "[t] Truncate");
+    }
+
+    #[test]
+    fn test_logs_first_seen_formatting_is_stable() {
+        use crate::models::database::NotifiedPostRow;
+        use crate::tui::screens::logs::format_first_seen;
+
+        let post = |ts: &str| NotifiedPostRow {
+            id: 1,
+            subreddit: "rust".to_string(),
+            post_id: "abc123".to_string(),
+            first_seen_at: ts.to_string(),
+            title: None,
+            url: None,
+        };
+        assert_eq!(format_first_seen(&post("2026-02-15T09:30:00Z")), "2026-02-15 09:30:00 UTC");
+        assert_eq!(format_first_seen(&post("2026-02-15 09:30:00")), "2026-02-15 09:30:00 UTC");
+        assert_eq!(format_first_seen(&post("garbage")), "garbage");
+    }
+
+    #[test]
+    fn test_logs_share_text() {
+        use crate::models::database::NotifiedPostRow;
+        use crate::tui::screens::logs::share_text;
+
+        let mut post = NotifiedPostRow {
+            id: 1,
+            subreddit: "rust".to_string(),
+            post_id: "abc123".to_string(),
+            first_seen_at: "2026-02-15T09:30:00Z".to_string(),
+            title: Some("Tom &amp; Jerry in Rust".to_string()),
+            url: Some("https://github.com/example/tom".to_string()),
+        };
+        assert_eq!(share_text(&post), "Tom & Jerry in Rust (r/rust)\nhttps://github.com/example/tom");
+
+        // Posts logged before titles and URLs were stored
+        post.title = None;
+        post.url = None;
+        assert_eq!(share_text(&post), "Post abc123 (r/rust)\nhttps://www.reddit.com/r/rust/comments/abc123/");
+    }
+
+    #[tokio::test]
+    async fn test_render_logs_share_dialog() {
+        let mut app = create_test_app();
+        app.db().record_if_new("rust", "abc123", "Hello", "https://redd.it/abc123").await.unwrap();
+        app.goto_screen(Screen::Logs);
+        app.states.logs_state.on_enter(&mut app.context).await.unwrap();
+        assert_contains(&render_app(&app), "[s] Share");
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE)).await.unwrap();
+        let rows = render_app(&app);
+        assert_contains(&rows, "Share Post");
+        assert_contains(&rows, "Hello (r/rust)");
+        assert_contains(&rows, "https://redd.it/abc123");
+
+        app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)).await.unwrap();
+        assert!(app.states.logs_state.share.is_none());
+        assert_eq!(app.context.current_screen, Screen::Logs);
+    }
+
+    #[tokio::test]
+    async fn test_render_logs_empty_state() {
+        let mut app = create_test_app();
+        app.goto_screen(Screen::Logs);
+
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "No notification history yet.");
+    }
+
+    #[tokio::test]
+    async fn test_render_test_notification() {
+        let mut app = create_test_app();
+        app.goto_screen(Screen::TestNotification);
+        app.states
+            .test_notification_state
+            .on_enter(&mut app.context)
+            .await
+            .unwrap();
+
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "Test Notification");
+        assert_contains(&rows, "1. discord (ID: 1) - Test Discord endpoint");
+        assert_contains(&rows, "Status: Ready to send test notification");
+        assert_contains(&rows, "[Enter] Send Test");
+    }
+
+    #[tokio::test]
+    async fn test_test_notification_marks_inactive_endpoints() {
+        let mut app = create_test_app();
+        app.db().toggle_endpoint_active(1).await.unwrap();
+        app.goto_screen(Screen::TestNotification);
+        app.states.test_notification_state.on_enter(&mut app.context).await.unwrap();
+
+        let rows = render_app(&app);
+        assert_contains(&rows, "1. discord (ID: 1) - Test Discord endpoint (inactive)");
+        assert_contains(&rows, "[i] Test inactive: off");
+
+        // Enter on the inactive endpoint explains instead of sending
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)).await.unwrap();
+        assert!(app.states.test_notification_state.sendable_endpoint().is_none());
+        assert_contains(&render_app(&app), "Endpoint is inactive");
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE)).await.unwrap();
+        assert_eq!(app.states.test_notification_state.sendable_endpoint().map(|e| e.id), Some(1));
+        assert_contains(&render_app(&app), "[i] Test inactive: on");
+    }
+
+    #[tokio::test]
+    async fn test_test_notification_sends_in_background() {
+        let db = Arc::new(MockDatabaseService::new());
+        // Fails to build a notifier, so the "send" finishes without any network
+        db.create_endpoint("discord", "{}", None).await.unwrap();
+        let mut app = App::new(db).unwrap();
+        app.goto_screen(Screen::TestNotification);
+        app.states.test_notification_state.on_enter(&mut app.context).await.unwrap();
+
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)).await.unwrap();
+        assert!(app.states.test_notification_state.pending_test.as_ref().is_some_and(|t| t.is_busy()));
+        assert_contains(&render_app(&app), "Sending test notification...");
+
+        for _ in 0..100 {
+            app.tick().await.unwrap();
+            if app.states.test_notification_state.pending_test.is_none() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert!(matches!(
+            &app.states.test_notification_state.status,
+            TestStatus::Error(msg) if msg.starts_with("Failed to build notifier")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_webhook_check_runs_in_background() {
+        let mut app = create_test_app();
+        app.goto_screen(Screen::Endpoints);
+        app.states.endpoints_state.on_enter(&mut app.context).await.unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE)).await.unwrap();
+        // Pick the first endpoint type
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)).await.unwrap();
+
+        // An empty webhook URL is rejected without any network
+        app.handle_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL)).await.unwrap();
+        assert!(app.states.endpoints_state.pending_validation.is_some());
+        let EndpointsMode::Creating(builder) = &app.states.endpoints_state.mode else {
+            panic!("expected the create form");
+        };
+        assert_eq!(builder.webhook_validation, WebhookValidationState::Validating);
+
+        for _ in 0..100 {
+            app.tick().await.unwrap();
+            if app.states.endpoints_state.pending_validation.is_none() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        let EndpointsMode::Creating(builder) = &app.states.endpoints_state.mode else {
+            panic!("expected the create form");
+        };
+        assert!(matches!(builder.webhook_validation, WebhookValidationState::Invalid(_)));
+    }
+
+    #[tokio::test]
+    async fn test_render_confirm_delete_dialog_overlay() {
+        let mut app = create_test_app();
+        app.goto_screen(Screen::Subscriptions);
+        app.states.subscriptions_state.mode = SubscriptionsMode::ConfirmDelete {
+            subscription_id: 1,
+            subreddit_name: "rust".to_string(),
+        };
+
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "Confirm");
+        assert_contains(&rows, "Delete subscription 'rust'?");
+        assert_contains(&rows, "[Y] Yes    [N] No");
+    }
+
+    #[tokio::test]
+    async fn test_render_error_message_overlay() {
+        let mut app = create_test_app();
+        app.context.messages.set_error("Something broke".to_string());
+
+        let rows = render_app(&app);
+
+        assert_contains(&rows, "Error");
+        assert_contains(&rows, "Something broke");
+    }
+
+    #[tokio::test]
+    async fn test_render_terminal_too_small_warning() {
+        let mut app = create_test_app();
+        app.goto_screen(Screen::Subscriptions);
+
+        let rows = render_app_sized(&app, 60, 10);
+
+        assert_contains(&rows, "Terminal too small");
+        assert!(!rows.iter().any(|row| row.contains("Manage Subscriptions")));
+    }
+
+    #[tokio::test]
+    async fn test_render_at_minimum_size_shows_screen() {
+        let app = create_test_app();
+        let rows = render_app_sized(&app, crate::tui::ui::MIN_WIDTH, crate::tui::ui::MIN_HEIGHT);
+
+        assert_contains(&rows, "Reddit Notifier TUI");
+        assert!(!rows.iter().any(|row| row.contains("Terminal too small")));
+    }
+
+    #[tokio::test]
+    async fn test_compact_mode_shows_more_rows_on_short_terminals() {
+        let db = Arc::new(MockDatabaseService::new());
+        for i in 0..30 {
+            db.create_subscription(&format!("sub_{:02}", i)).await.unwrap();
+        }
+        let visible_subscriptions = |compact: Option<bool>| {
+            let db = Arc::clone(&db);
+            async move {
+                let mut app = App::new(db).unwrap().with_compact_mode(compact);
+                app.goto_screen(Screen::Subscriptions);
+                app.states.subscriptions_state.on_enter(&mut app.context).await.unwrap();
+                let rows = render_app_sized(&app, crate::tui::ui::MIN_WIDTH, 24);
+                assert_contains(&rows, "Manage Subscriptions");
+                assert_contains(&rows, "[Esc] Back");
+                rows.iter().filter(|row| row.contains("no endpoints")).count()
+            }
+        };
+
+        // 24 rows is below the threshold, so compact mode is on by default
+        let auto = visible_subscriptions(None).await;
+        let compact = visible_subscriptions(Some(true)).await;
+        let bordered = visible_subscriptions(Some(false)).await;
+        assert_eq!(auto, compact);
+        assert!(compact > bordered, "compact {} rows vs bordered {}", compact, bordered);
+    }
 }
@@ -1,10 +1,25 @@
-use ratatui::Frame;
+use ratatui::{
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
 
 use crate::services::DatabaseService;
 use super::app::{App, Screen};
 use super::screens;
 
+/// Smallest terminal width the screen layouts render correctly in
+pub const MIN_WIDTH: u16 = 80;
+
+/// Smallest terminal height the screen layouts render correctly in
+pub const MIN_HEIGHT: u16 = 20;
+
 pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
+    if render_size_warning(frame) {
+        return;
+    }
+
     match app.context.current_screen {
         Screen::MainMenu => screens::main_menu::render(frame, app),
         Screen::Subscriptions => screens::subscriptions::render(frame, app),
@@ -13,3 +28,33 @@ pub fn render<D: DatabaseService>(frame: &mut Frame, app: &App<D>) {
         Screen::Logs => screens::logs::render(frame, app),
     }
 }
+
+/// Check whether an area is below the minimum supported terminal size
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+/// Render a "terminal too small" notice if the frame is below the minimum size
+///
+/// Returns `true` if the notice was drawn, in which case the caller should
+/// skip rendering the normal screen.
+pub fn render_size_warning(frame: &mut Frame) -> bool {
+    let area = frame.area();
+    if !is_too_small(area) {
+        return false;
+    }
+
+    let text = format!(
+        "Terminal too small (need ≥ {}x{}, have {}x{})",
+        MIN_WIDTH, MIN_HEIGHT, area.width, area.height
+    );
+    let [middle] = Layout::vertical([Constraint::Length(3)])
+        .flex(Flex::Center)
+        .areas(area);
+    let warning = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Yellow))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(warning, middle);
+    true
+}
@@ -1,16 +1,22 @@
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::json;
+use std::fs::OpenOptions;
+use std::path::Path;
 use std::time::Duration;
 
 use crate::models::database::EndpointKind;
+use crate::models::notifiers::{check_file_path, check_mattermost_webhook_url, check_teams_webhook_url, FileConfig};
+use crate::notifiers::file::is_socket;
+use crate::notifiers::teams::{connector_error_status, text_card};
 
 use super::async_validator::{AsyncValidator, ValidationResult};
 
 /// Validator for webhook endpoints
 ///
 /// Sends a test message to verify the webhook is valid and reachable.
-/// Supports Discord and Pushover endpoints.
+/// Supports Discord, Pushover, Mattermost and Teams endpoints; file endpoints
+/// are only checked for a writable path.
 pub struct WebhookValidator {
     client: Client,
     endpoint_kind: EndpointKind,
@@ -61,6 +67,53 @@ impl WebhookValidator {
         }
     }
 
+    /// Validate a Mattermost incoming webhook URL by sending a test message
+    async fn validate_mattermost(&self, webhook_url: &str) -> ValidationResult {
+        if let Err(e) = check_mattermost_webhook_url(webhook_url) {
+            return Err(format!("Invalid Mattermost webhook URL format: {}", e));
+        }
+
+        let test_payload = json!({
+            "text": "✅ Test message from reddit-notifier (validating webhook)",
+        });
+
+        match self.client.post(webhook_url).json(&test_payload).send().await {
+            Ok(resp) if resp.status().is_success() => Ok(Some("✓ Webhook is valid and reachable".to_string())),
+            Ok(resp) => Err(format!(
+                "Webhook returned status {}: {}",
+                resp.status(),
+                resp.text().await.unwrap_or_default()
+            )),
+            Err(e) => Err(format!("Cannot reach webhook: {}", e)),
+        }
+    }
+
+    /// Validate a Teams workflow or connector URL by sending a test card
+    async fn validate_teams(&self, webhook_url: &str) -> ValidationResult {
+        if let Err(e) = check_teams_webhook_url(webhook_url) {
+            return Err(format!("Invalid Teams webhook URL format: {}", e));
+        }
+
+        let test_payload = text_card("✅ Test message from reddit-notifier (validating webhook)");
+
+        match self.client.post(webhook_url).json(&test_payload).send().await {
+            Ok(resp) if resp.status().is_success() => {
+                // Connectors report a rejected card in a 200 response
+                let body = resp.text().await.unwrap_or_default();
+                match connector_error_status(&body) {
+                    Some(status) => Err(format!("Teams rejected the message ({}): {}", status, body)),
+                    None => Ok(Some("✓ Webhook is valid and reachable".to_string())),
+                }
+            }
+            Ok(resp) => Err(format!(
+                "Webhook returned status {}: {}",
+                resp.status(),
+                resp.text().await.unwrap_or_default()
+            )),
+            Err(e) => Err(format!("Cannot reach webhook: {}", e)),
+        }
+    }
+
     /// Validate Pushover configuration by checking token and user
     async fn validate_pushover(&self, config_json: &str) -> ValidationResult {
         // Parse the config JSON to extract token and user
@@ -106,12 +159,38 @@ impl WebhookValidator {
     }
 }
 
+/// Check that a file endpoint's path can be written to, without writing anything
+///
+/// A socket is only checked for existence; connecting would hand the
+/// listener an empty notification.
+fn validate_file(config_json: &str) -> ValidationResult {
+    let cfg: FileConfig = serde_json::from_str(config_json).map_err(|e| format!("Invalid file config: {}", e))?;
+    check_file_path(&cfg.path)?;
+    let path = Path::new(cfg.path.trim());
+    if is_socket(path) {
+        return Ok(Some("✓ Path is a Unix domain socket".to_string()));
+    }
+    if path.exists() {
+        return match OpenOptions::new().append(true).open(path) {
+            Ok(_) => Ok(Some("✓ File is writable".to_string())),
+            Err(e) => Err(format!("Cannot open {} for appending: {}", path.display(), e)),
+        };
+    }
+    match path.parent() {
+        Some(dir) if dir.is_dir() => Ok(Some("✓ File will be created on the first notification".to_string())),
+        _ => Err(format!("Directory of {} does not exist", path.display())),
+    }
+}
+
 #[async_trait]
 impl AsyncValidator for WebhookValidator {
     async fn validate(&self, value: &str) -> ValidationResult {
         match self.endpoint_kind {
             EndpointKind::Discord => self.validate_discord(value).await,
             EndpointKind::Pushover => self.validate_pushover(value).await,
+            EndpointKind::Mattermost => self.validate_mattermost(value).await,
+            EndpointKind::Teams => self.validate_teams(value).await,
+            EndpointKind::File => validate_file(value),
         }
     }
 }
@@ -141,6 +220,34 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_mattermost_invalid_url_format() {
+        let validator = WebhookValidator::new(EndpointKind::Mattermost);
+        let result = validator.validate("https://chat.example.com/api/v4/posts").await;
+        assert!(result.unwrap_err().contains("Invalid Mattermost webhook URL format"));
+    }
+
+    #[tokio::test]
+    async fn test_file_path_checks() {
+        let validator = WebhookValidator::new(EndpointKind::File);
+        let result = validator.validate(r#"{"path":"posts.jsonl"}"#).await;
+        assert!(result.unwrap_err().contains("must be absolute"));
+        let result = validator.validate(r#"{"path":"/nonexistent-reddit-notifier-dir/posts.jsonl"}"#).await;
+        assert!(result.unwrap_err().contains("does not exist"));
+
+        let path = std::env::temp_dir().join(format!("reddit-notifier-validate-{}.jsonl", std::process::id()));
+        let config = json!({ "path": path }).to_string();
+        assert!(validator.validate(&config).await.unwrap().unwrap().contains("will be created"));
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_teams_invalid_url_format() {
+        let validator = WebhookValidator::new(EndpointKind::Teams);
+        let result = validator.validate("http://x.webhook.office.com/webhookb2/abc").await;
+        assert!(result.unwrap_err().contains("Invalid Teams webhook URL format"));
+    }
+
     #[tokio::test]
     async fn test_pushover_invalid_json() {
         let validator = WebhookValidator::new(EndpointKind::Pushover);
@@ -9,6 +9,7 @@ use ratatui::{
 use std::collections::HashSet;
 
 use crate::tui::state::Navigable;
+use crate::tui::keys;
 
 /// A list widget with checkboxes for multi-selection
 ///
@@ -112,7 +113,7 @@ impl<T: Clone + PartialEq> CheckboxList<T> {
     ///
     /// Returns true if the key was handled
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
-        match key.code {
+        match keys::shortcut_code(&key) {
             KeyCode::Up => {
                 self.previous();
                 true
@@ -11,26 +11,57 @@ use ratatui::{
     Frame,
 };
 
+/// Terminals shorter than this use the compact layout unless `TUI_COMPACT` says otherwise
+pub const COMPACT_HEIGHT: u16 = 30;
+
+/// Whether to render `area` in compact mode
+///
+/// `configured` is the `TUI_COMPACT` setting; `None` turns compact mode on
+/// automatically below `COMPACT_HEIGHT` rows.
+pub fn is_compact(area: Rect, configured: Option<bool>) -> bool {
+    configured.unwrap_or(area.height < COMPACT_HEIGHT)
+}
+
+/// Height of a title bar: one line in compact mode, otherwise a bordered box
+pub fn title_height(compact: bool) -> u16 {
+    if compact { 1 } else { 3 }
+}
+
+/// Height of a help bar with `lines` lines of shortcuts
+pub fn help_height(compact: bool, lines: u16) -> u16 {
+    if compact { lines } else { lines + 2 }
+}
+
+/// Block for title and help bars: bordered normally, bare in compact mode
+pub fn chrome_block(compact: bool) -> Block<'static> {
+    if compact {
+        Block::default()
+    } else {
+        Block::default().borders(Borders::ALL)
+    }
+}
+
 /// Standard 3-section screen layout (title, content, help)
 ///
 /// Returns a 3-element array with fixed-height title and help sections,
-/// and a flexible-height content section.
+/// and a flexible-height content section. In compact mode the title and
+/// help take one line each instead of three.
 ///
 /// # Example
 /// ```no_run
 /// # use ratatui::layout::Rect;
 /// # use reddit_notifier::tui::widgets::common::render_screen_layout;
 /// # let area = Rect::default();
-/// let chunks = render_screen_layout(area);
+/// let chunks = render_screen_layout(area, false);
 /// // chunks[0] = title area (height: 3)
 /// // chunks[1] = content area (flexible)
 /// // chunks[2] = help area (height: 3)
 /// ```
-pub fn render_screen_layout(area: Rect) -> [Rect; 3] {
+pub fn render_screen_layout(area: Rect, compact: bool) -> [Rect; 3] {
     let chunks = Layout::vertical([
-        Constraint::Length(3),    // Title
-        Constraint::Min(0),       // Content
-        Constraint::Length(3),    // Help
+        Constraint::Length(title_height(compact)),   // Title
+        Constraint::Min(0),                          // Content
+        Constraint::Length(help_height(compact, 1)), // Help
     ])
     .split(area);
 
@@ -39,22 +70,20 @@ pub fn render_screen_layout(area: Rect) -> [Rect; 3] {
 
 /// Render a styled title bar
 ///
-/// Creates a centered title with cyan borders.
+/// Creates a centered title with cyan borders, or a bare cyan line when
+/// `area` is too short for borders (compact mode).
 pub fn render_title(frame: &mut Frame, area: Rect, title: &str) {
     let widget = Paragraph::new(title)
         .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Cyan)),
-        );
+        .block(chrome_block(area.height < 3).style(Style::default().fg(Color::Cyan)));
     frame.render_widget(widget, area);
 }
 
 /// Render help text with keyboard shortcuts
 ///
 /// Takes an array of (key, description) tuples and renders them
-/// as a centered help bar at the bottom of the screen.
+/// as a centered help bar at the bottom of the screen, without borders
+/// when `area` is a single line (compact mode).
 ///
 /// # Example
 /// ```no_run
@@ -79,7 +108,7 @@ pub fn render_help(frame: &mut Frame, area: Rect, items: &[(&str, &str)]) {
 
     let widget = Paragraph::new(Line::from(spans))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(chrome_block(area.height < 3));
     frame.render_widget(widget, area);
 }
 
@@ -181,7 +210,7 @@ mod tests {
     #[test]
     fn test_screen_layout() {
         let area = Rect::new(0, 0, 100, 50);
-        let chunks = render_screen_layout(area);
+        let chunks = render_screen_layout(area, false);
 
         assert_eq!(chunks.len(), 3);
 
@@ -197,4 +226,17 @@ mod tests {
         assert_eq!(chunks[2].height, 3);
         assert_eq!(chunks[2].y, 47);
     }
+
+    #[test]
+    fn test_compact_screen_layout() {
+        let area = Rect::new(0, 0, 100, 24);
+        assert!(is_compact(area, None));
+        assert!(!is_compact(area, Some(false)));
+        assert!(is_compact(Rect::new(0, 0, 100, 50), Some(true)));
+
+        // One-line title and help leave 22 of 24 rows for content (vs 18)
+        let chunks = render_screen_layout(area, true);
+        assert_eq!([chunks[0].height, chunks[1].height, chunks[2].height], [1, 22, 1]);
+        assert_eq!(render_screen_layout(area, false)[1].height, 18);
+    }
 }
@@ -1,19 +1,38 @@
 use anyhow::{anyhow, Result};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use chrono::Utc;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame,
 };
 use serde_json::json;
+use std::future::Future;
 
+use crate::active_hours::endpoint_active_hours;
 use crate::models::{
     database::EndpointKind,
-    notifiers::{DiscordConfig, PushoverConfig},
+    notifiers::{
+        check_file_path, check_mattermost_webhook_url, check_teams_webhook_url, parse_mention, upgrade_config_value,
+        upgrade_endpoint_config, validate_endpoint_config, DiscordConfig, FileConfig, FileFormat, MattermostConfig,
+        PushoverConfig, TeamsCard, TeamsConfig, CONFIG_VERSION_KEY, MAX_FOOTER_LEN,
+    },
 };
+use crate::notifiers::{
+    discord::DEFAULT_USERNAME,
+    file,
+    message::{append_footer, SAMPLE_SUBREDDIT, SAMPLE_TITLE, SAMPLE_URL},
+    Message,
+};
+use crate::secrets::{is_secret_ref, resolve_config_secrets, resolve_secret};
 use crate::tui::validation::{AsyncValidator, WebhookValidator, ValidationResult};
+use crate::tui::keys;
+use super::TextArea;
+
+/// Config key for a list of webhook URLs, used instead of the single-URL key when there are several
+const WEBHOOK_URLS_KEY: &str = "webhook_urls";
 
 #[derive(Debug, Clone)]
 pub struct FormField {
@@ -21,6 +40,8 @@ pub struct FormField {
     pub value: String,
     pub required: bool,
     pub placeholder: String,
+    /// Guidance shown under the field, e.g. where to find the value
+    pub help_text: Option<String>,
 }
 
 impl FormField {
@@ -30,8 +51,15 @@ impl FormField {
             value: String::new(),
             required,
             placeholder: placeholder.to_string(),
+            help_text: None,
         }
     }
+
+    /// Set help text
+    pub fn with_help(mut self, text: impl Into<String>) -> Self {
+        self.help_text = Some(text.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -63,13 +91,61 @@ impl WebhookValidationState {
 
     pub fn message(&self) -> Option<&str> {
         match self {
+            Self::Validating => Some("Sending test request..."),
             Self::Valid(msg) => Some(msg),
             Self::Invalid(msg) => Some(msg),
-            _ => None,
+            Self::Idle => None,
         }
     }
 }
 
+/// What `ConfigBuilder::start_webhook_validation` sends its test requests to
+enum WebhookCheck {
+    Urls(Vec<String>),
+    /// The config with secrets resolved, or why it couldn't be built
+    Config(Result<String, String>),
+}
+
+/// What the preview panel of the config form shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewMode {
+    /// The config_json that will be stored
+    Json,
+    /// A sample notification as the recipient would see it
+    Message,
+    /// Field-level changes from the stored config (editing an existing endpoint only)
+    Diff,
+}
+
+/// How one config key differs between the stored config and the form
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    Added(serde_json::Value),
+    Removed(serde_json::Value),
+    Changed { old: serde_json::Value, new: serde_json::Value },
+}
+
+/// Top-level keys that differ between two config objects, in key order
+///
+/// Nested values (e.g. `extra_headers`) are compared whole.
+pub fn config_diff(old: &serde_json::Value, new: &serde_json::Value) -> Vec<(String, FieldChange)> {
+    let empty = serde_json::Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+    let keys: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let change = match (old.get(key), new.get(key)) {
+                (None, Some(new)) => FieldChange::Added(new.clone()),
+                (Some(old), None) => FieldChange::Removed(old.clone()),
+                (Some(old), Some(new)) if old != new => FieldChange::Changed { old: old.clone(), new: new.clone() },
+                _ => return None,
+            };
+            Some((key.clone(), change))
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfigBuilder {
     pub endpoint_type: EndpointKind,
@@ -79,6 +155,15 @@ pub struct ConfigBuilder {
     pub type_selection_mode: bool,
     pub editing_note: bool,
     pub webhook_validation: WebhookValidationState,
+    pub preview_mode: PreviewMode,
+    /// `include_nsfw_marker` from the stored config, kept when the form is saved
+    pub include_nsfw_marker: bool,
+    /// JSON text edited in place of the form (Ctrl+R); `None` while using the form
+    pub raw_json: Option<TextArea>,
+    /// Config keys the form has no field for, kept when the form is saved
+    pub extra_keys: serde_json::Map<String, serde_json::Value>,
+    /// The stored config (in the current version) when editing an endpoint, for the diff preview
+    pub original: Option<serde_json::Value>,
 }
 
 impl Default for ConfigBuilder {
@@ -97,6 +182,11 @@ impl ConfigBuilder {
             type_selection_mode: true,
             editing_note: false,
             webhook_validation: WebhookValidationState::Idle,
+            preview_mode: PreviewMode::Json,
+            include_nsfw_marker: true,
+            raw_json: None,
+            extra_keys: serde_json::Map::new(),
+            original: None,
         };
         builder.set_type(EndpointKind::Discord);
         builder
@@ -111,109 +201,327 @@ impl ConfigBuilder {
             type_selection_mode: false,
             editing_note: false,
             webhook_validation: WebhookValidationState::Idle,
+            preview_mode: PreviewMode::Json,
+            include_nsfw_marker: true,
+            raw_json: None,
+            extra_keys: serde_json::Map::new(),
+            original: None,
         };
 
         builder.set_type(kind);
 
-        // Parse existing JSON and populate fields
+        // Read older config versions in the current shape, check it against
+        // the kind's schema, then populate fields
+        let upgraded = upgrade_endpoint_config(&builder.endpoint_type, config_json).map_err(|e| anyhow!(e))?;
+        let config_json = upgraded.as_str();
         match builder.endpoint_type {
             EndpointKind::Discord => {
-                let config: DiscordConfig = serde_json::from_str(config_json)?;
-                builder.fields[0].value = config.webhook_url;
-                if let Some(username) = config.username {
-                    builder.fields[1].value = username;
-                }
+                serde_json::from_str::<DiscordConfig>(config_json)?;
             }
             EndpointKind::Pushover => {
-                let config: PushoverConfig = serde_json::from_str(config_json)?;
-                builder.fields[0].value = config.token;
-                builder.fields[1].value = config.user;
-                if let Some(device) = config.device {
-                    builder.fields[2].value = device;
-                }
+                serde_json::from_str::<PushoverConfig>(config_json)?;
+            }
+            EndpointKind::Mattermost => {
+                serde_json::from_str::<MattermostConfig>(config_json)?;
+            }
+            EndpointKind::Teams => {
+                serde_json::from_str::<TeamsConfig>(config_json)?;
+            }
+            EndpointKind::File => {
+                serde_json::from_str::<FileConfig>(config_json)?;
             }
         }
+        let value: serde_json::Value = serde_json::from_str(config_json)?;
+        builder.fill_from_value(&value);
+        builder.original = Some(value);
 
         Ok(builder)
     }
 
+    /// Build a form from a config that doesn't match its kind's schema
+    ///
+    /// Any string fields that can be recovered are prefilled; the rest are
+    /// left empty for the user to repair. The problem is shown in the form.
+    pub fn for_repair(kind: EndpointKind, config_json: &str, note: Option<String>, problem: &str) -> Self {
+        let mut builder = Self {
+            endpoint_type: kind.clone(),
+            fields: Vec::new(),
+            note: note.unwrap_or_default(),
+            current_field: 0,
+            type_selection_mode: false,
+            editing_note: false,
+            webhook_validation: WebhookValidationState::Invalid(format!("Needs repair: {}", problem)),
+            preview_mode: PreviewMode::Json,
+            include_nsfw_marker: true,
+            raw_json: None,
+            extra_keys: serde_json::Map::new(),
+            original: None,
+        };
+
+        builder.set_type(kind);
+
+        let value: serde_json::Value = serde_json::from_str(config_json).unwrap_or_default();
+        let value = upgrade_config_value(&builder.endpoint_type, value.clone()).unwrap_or(value);
+        builder.fill_from_value(&value);
+        builder.original = value.is_object().then_some(value);
+        builder
+    }
+
+    /// The config key, label and placeholder of each form field for `kind`
+    fn field_specs(kind: &EndpointKind) -> &'static [(&'static str, &'static str, &'static str)] {
+        match kind {
+            EndpointKind::Discord => &[
+                ("webhook_url", "Webhook URL(s)", "https://discord.com/api/webhooks/... (comma-separate several)"),
+                ("username", "Username", "Reddit Notifier"),
+                ("mention", "Mention for priority subs", "@here or <@user_id>"),
+                ("footer", "Footer", "via reddit-notifier"),
+                ("active_hours", "Active hours", "09:00-17:00 (empty = always)"),
+                ("timezone", "Timezone", "UTC or e.g. America/New_York"),
+            ],
+            EndpointKind::Pushover => &[
+                ("token", "Token", "your-app-token"),
+                ("user", "User Key", "your-user-key"),
+                ("device", "Device", ""),
+                ("footer", "Footer", "via reddit-notifier"),
+                ("active_hours", "Active hours", "09:00-17:00 (empty = always)"),
+                ("timezone", "Timezone", "UTC or e.g. America/New_York"),
+            ],
+            EndpointKind::Mattermost => &[
+                ("webhook_url", "Webhook URL", "https://chat.example.com/hooks/..."),
+                ("channel", "Channel", "town-square (empty = webhook default)"),
+                ("username", "Username", "(empty = webhook default)"),
+                ("footer", "Footer", "via reddit-notifier"),
+                ("active_hours", "Active hours", "09:00-17:00 (empty = always)"),
+                ("timezone", "Timezone", "UTC or e.g. America/New_York"),
+            ],
+            EndpointKind::Teams => &[
+                ("webhook_url", "Webhook URL", "https://...logic.azure.com/workflows/..."),
+                ("card", "Card", "adaptive (default) or message_card"),
+                ("footer", "Footer", "via reddit-notifier"),
+                ("active_hours", "Active hours", "09:00-17:00 (empty = always)"),
+                ("timezone", "Timezone", "UTC or e.g. America/New_York"),
+            ],
+            EndpointKind::File => &[
+                ("path", "Path", "/var/log/reddit-notifier/posts.jsonl or a .sock"),
+                ("format", "Format", "json (default) or text"),
+                ("active_hours", "Active hours", "09:00-17:00 (empty = always)"),
+                ("timezone", "Timezone", "UTC or e.g. America/New_York"),
+            ],
+        }
+    }
+
+    /// Trimmed value of the field for config key `key` ("" if the kind has none)
+    fn field_value(&self, key: &str) -> &str {
+        Self::field_specs(&self.endpoint_type)
+            .iter()
+            .position(|(k, _, _)| *k == key)
+            .map_or("", |i| self.fields[i].value.trim())
+    }
+
+    /// URLs in the webhook field, which takes several separated by commas or spaces
+    fn webhook_urls(&self) -> Vec<&str> {
+        match self.endpoint_type.capabilities().webhook_url_field {
+            Some(key) => self
+                .field_value(key)
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|url| !url.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Set the form from a config object
+    ///
+    /// String values fill their fields (missing ones are cleared), and a
+    /// `webhook_urls` list fills the webhook field comma-separated; keys the
+    /// form doesn't know are kept in `extra_keys`.
+    fn fill_from_value(&mut self, value: &serde_json::Value) {
+        let keys: Vec<&str> = Self::field_specs(&self.endpoint_type).iter().map(|(k, _, _)| *k).collect();
+        let webhook_key = self.endpoint_type.capabilities().webhook_url_field;
+        for (field, key) in self.fields.iter_mut().zip(&keys) {
+            field.value = match value.get(WEBHOOK_URLS_KEY).and_then(|v| v.as_array()) {
+                Some(urls) if webhook_key == Some(*key) => {
+                    urls.iter().filter_map(|u| u.as_str()).collect::<Vec<_>>().join(", ")
+                }
+                _ => value.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            };
+        }
+        self.include_nsfw_marker = value.get("include_nsfw_marker").and_then(|v| v.as_bool()).unwrap_or(true);
+        self.extra_keys = value
+            .as_object()
+            .map(|obj| {
+                obj.iter()
+                    .filter(|(k, _)| !keys.contains(&k.as_str()) && *k != "include_nsfw_marker" && *k != CONFIG_VERSION_KEY)
+                    .filter(|(k, _)| webhook_key.is_none() || *k != WEBHOOK_URLS_KEY)
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    /// Switch between the form and editing the config as raw JSON
+    ///
+    /// Raw mode starts from the form's current JSON. Going back to the form
+    /// requires a JSON object; otherwise the error is shown and raw mode stays.
+    pub fn toggle_raw_mode(&mut self) {
+        match self.raw_json.take() {
+            None => {
+                let value = self.form_value();
+                let mut editor = TextArea::new()
+                    .with_title("Raw JSON")
+                    .with_value(serde_json::to_string_pretty(&value).unwrap_or_default());
+                editor.set_focused(true);
+                self.raw_json = Some(editor);
+            }
+            Some(raw) => match serde_json::from_str::<serde_json::Value>(raw.value()) {
+                Ok(value) if value.is_object() => {
+                    self.fill_from_value(&value);
+                    self.webhook_validation = WebhookValidationState::Idle;
+                }
+                Ok(_) => {
+                    self.webhook_validation = WebhookValidationState::Invalid("Raw config must be a JSON object".to_string());
+                    self.raw_json = Some(raw);
+                }
+                Err(e) => {
+                    self.webhook_validation = WebhookValidationState::Invalid(format!("Invalid JSON: {}", e));
+                    self.raw_json = Some(raw);
+                }
+            },
+        }
+    }
+
+    /// Validate raw JSON the way a stored config is validated, returning it compacted
+    /// in the current config version
+    fn build_raw_json(&self, raw: &str) -> Result<String> {
+        let value: serde_json::Value = serde_json::from_str(raw).map_err(|e| anyhow!("Invalid JSON: {}", e))?;
+        if !value.is_object() {
+            return Err(anyhow!("Raw config must be a JSON object"));
+        }
+        let value = upgrade_config_value(&self.endpoint_type, value).map_err(|e| anyhow!(e))?;
+        let json = serde_json::to_string(&value)?;
+        validate_endpoint_config(&self.endpoint_type, &json).map_err(|e| anyhow!(e))?;
+        Ok(json)
+    }
+
+    fn handle_raw_input(&mut self, key: KeyEvent) -> Result<Option<ConfigAction>> {
+        if keys::is_ctrl(&key, 'r') {
+            self.toggle_raw_mode();
+            return Ok(None);
+        }
+
+        // Enter is a newline here, so saving moves to Ctrl+S
+        if keys::is_ctrl(&key, 's') {
+            // Stay in the editor on errors so the text isn't lost
+            if let Err(e) = self.build_json() {
+                self.webhook_validation = WebhookValidationState::Invalid(e.to_string());
+                return Ok(None);
+            }
+            return Ok(Some(ConfigAction::Save));
+        }
+        if keys::shortcut_code(&key) == KeyCode::Esc {
+            return Ok(Some(ConfigAction::Cancel));
+        }
+
+        if let Some(raw) = self.raw_json.as_mut() {
+            raw.handle_key(key);
+        }
+        Ok(None)
+    }
+
     pub fn set_type(&mut self, kind: EndpointKind) {
         self.endpoint_type = kind;
         self.fields.clear();
         self.current_field = 0;
 
-        match self.endpoint_type {
-            EndpointKind::Discord => {
-                self.fields
-                    .push(FormField::new("Webhook URL", true, "https://discord.com/api/webhooks/..."));
-                self.fields
-                    .push(FormField::new("Username (optional)", false, "Reddit Notifier"));
-            }
-            EndpointKind::Pushover => {
-                self.fields.push(FormField::new("Token", true, "your-app-token"));
-                self.fields.push(FormField::new("User Key", true, "your-user-key"));
-                self.fields
-                    .push(FormField::new("Device (optional)", false, ""));
+        let capabilities = self.endpoint_type.capabilities();
+        for (key, label, placeholder) in Self::field_specs(&self.endpoint_type) {
+            let mut field = if capabilities.required_fields.contains(key) {
+                FormField::new(label, true, placeholder)
+            } else {
+                FormField::new(&format!("{} (optional)", label), false, placeholder)
+            };
+            if let Some(help) = capabilities.field_help(key) {
+                field = field.with_help(help);
             }
+            self.fields.push(field);
         }
     }
 
-    /// Trigger webhook validation asynchronously
+    /// Start checking the webhook (Ctrl+T), or the whole config for services without one
     ///
-    /// This method should be called when the user presses Ctrl+T.
-    /// It returns a ValidationResult that needs to be awaited.
-    pub async fn validate_webhook(&mut self) -> ValidationResult {
-        // Set state to validating
+    /// Marks the form as validating and returns the check, which sends test
+    /// requests; run it off the event loop (e.g. as a `BackgroundTask`) and
+    /// pass its result to `finish_webhook_validation`.
+    pub fn start_webhook_validation(&mut self) -> impl Future<Output = ValidationResult> + Send + 'static {
         self.webhook_validation = WebhookValidationState::Validating;
-
         let validator = WebhookValidator::new(self.endpoint_type.clone());
+        let check = match self.endpoint_type.capabilities().webhook_url_field {
+            // Webhooks are checked by URL, each of them when there are several
+            Some(_) => WebhookCheck::Urls(self.webhook_urls().into_iter().map(str::to_string).collect()),
+            // Other services need the whole config
+            None => WebhookCheck::Config(
+                self.build_json()
+                    .and_then(|json| resolve_config_secrets(&json))
+                    .map_err(|e| format!("Invalid config: {}", e)),
+            ),
+        };
 
-        let value_to_validate = match self.endpoint_type {
-            EndpointKind::Discord => {
-                // Discord uses the webhook URL directly
-                self.fields[0].value.trim().to_string()
-            }
-            EndpointKind::Pushover => {
-                // Pushover needs JSON config
-                match self.build_json() {
-                    Ok(json) => json,
-                    Err(e) => {
-                        self.webhook_validation = WebhookValidationState::Invalid(format!("Invalid config: {}", e));
-                        return Err(format!("Invalid config: {}", e));
-                    }
+        async move {
+            match check {
+                WebhookCheck::Urls(urls) if urls.len() > 1 => Self::validate_each(&validator, &urls).await,
+                WebhookCheck::Urls(urls) => {
+                    let url = resolve_secret(urls.first().map_or("", String::as_str)).map_err(|e| format!("{:#}", e))?;
+                    validator.validate(&url).await
                 }
+                WebhookCheck::Config(config) => validator.validate(&config?).await,
             }
-        };
+        }
+    }
 
-        let result = validator.validate(&value_to_validate).await;
+    /// Show the result of a check started with `start_webhook_validation`
+    pub fn finish_webhook_validation(&mut self, result: &ValidationResult) {
+        self.webhook_validation = match result {
+            Ok(Some(msg)) => WebhookValidationState::Valid(msg.clone()),
+            Ok(None) => WebhookValidationState::Valid("Webhook is valid".to_string()),
+            Err(msg) => WebhookValidationState::Invalid(msg.clone()),
+        };
+    }
 
-        // Update validation state based on result
-        match &result {
-            Ok(Some(msg)) => {
-                self.webhook_validation = WebhookValidationState::Valid(msg.clone());
-            }
-            Ok(None) => {
-                self.webhook_validation = WebhookValidationState::Valid("Webhook is valid".to_string());
-            }
-            Err(msg) => {
-                self.webhook_validation = WebhookValidationState::Invalid(msg.clone());
-            }
+    /// Validate several webhooks in turn, stopping at the first one that fails
+    async fn validate_each(validator: &WebhookValidator, urls: &[String]) -> ValidationResult {
+        for (i, url) in urls.iter().enumerate() {
+            let url = resolve_secret(url).map_err(|e| format!("Webhook {}: {:#}", i + 1, e))?;
+            validator.validate(&url).await.map_err(|e| format!("Webhook {}: {}", i + 1, e))?;
         }
-
-        result
+        Ok(Some(format!("All {} webhooks are valid", urls.len())))
     }
 
     pub fn handle_input(&mut self, key: KeyEvent) -> Result<Option<ConfigAction>> {
         if self.type_selection_mode {
             return self.handle_type_selection(key);
         }
+        if self.raw_json.is_some() {
+            return self.handle_raw_input(key);
+        }
+
+        // Ctrl+R edits the config as raw JSON, for keys the form doesn't expose
+        if keys::is_ctrl(&key, 'r') {
+            self.toggle_raw_mode();
+            return Ok(None);
+        }
 
         // Handle Ctrl+T for webhook testing (note: actual validation is async and handled elsewhere)
-        if key.code == KeyCode::Char('t') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        if keys::is_ctrl(&key, 't') {
             return Ok(Some(ConfigAction::TestWebhook));
         }
 
-        match key.code {
+        // Ctrl+P switches the preview panel between JSON and a sample message
+        if keys::is_ctrl(&key, 'p') {
+            self.toggle_preview();
+            return Ok(None);
+        }
+
+        match keys::shortcut_code(&key) {
             KeyCode::Tab => {
                 if self.editing_note {
                     self.editing_note = false;
@@ -263,14 +571,16 @@ impl ConfigBuilder {
     }
 
     fn handle_type_selection(&mut self, key: KeyEvent) -> Result<Option<ConfigAction>> {
-        match key.code {
-            KeyCode::Up | KeyCode::Down => {
-                // Toggle between Discord and Pushover
-                let new_type = match self.endpoint_type {
-                    EndpointKind::Discord => EndpointKind::Pushover,
-                    EndpointKind::Pushover => EndpointKind::Discord,
+        match keys::shortcut_code(&key) {
+            code @ (KeyCode::Up | KeyCode::Down) => {
+                let kinds = EndpointKind::all();
+                let current = kinds.iter().position(|k| *k == self.endpoint_type).unwrap_or(0);
+                let next = if code == KeyCode::Down {
+                    (current + 1) % kinds.len()
+                } else {
+                    (current + kinds.len() - 1) % kinds.len()
                 };
-                self.set_type(new_type);
+                self.set_type(kinds[next].clone());
                 Ok(None)
             }
             KeyCode::Enter => {
@@ -290,62 +600,80 @@ impl ConfigBuilder {
             }
         }
 
-        // Additional validation for Discord webhook URL
-        if self.endpoint_type == EndpointKind::Discord {
-            let webhook_url = &self.fields[0].value;
-            if !webhook_url.starts_with("https://") {
-                return Err(anyhow!("Webhook URL must start with https://"));
+        let capabilities = self.endpoint_type.capabilities();
+        for webhook_url in self.webhook_urls() {
+            if !webhook_url.starts_with("https://") && !is_secret_ref(webhook_url) {
+                return Err(anyhow!("Webhook URL must start with https:// (or be a file:/env: reference)"));
             }
         }
+        if self.endpoint_type == EndpointKind::Mattermost {
+            match self.webhook_urls().as_slice() {
+                [url] => check_mattermost_webhook_url(url).map_err(|e| anyhow!(e))?,
+                _ => return Err(anyhow!("Mattermost endpoints take a single webhook URL")),
+            }
+        }
+        if self.endpoint_type == EndpointKind::Teams {
+            match self.webhook_urls().as_slice() {
+                [url] => check_teams_webhook_url(url).map_err(|e| anyhow!(e))?,
+                _ => return Err(anyhow!("Teams endpoints take a single webhook URL")),
+            }
+            let card = self.field_value("card");
+            if !card.is_empty() {
+                serde_json::from_value::<TeamsCard>(json!(card))
+                    .map_err(|_| anyhow!("Card must be 'adaptive' or 'message_card'"))?;
+            }
+        }
+        if self.endpoint_type == EndpointKind::File {
+            check_file_path(self.field_value("path")).map_err(|e| anyhow!(e))?;
+            self.file_format()?;
+        }
+        let mention = self.field_value("mention");
+        if capabilities.mentions && !mention.is_empty() {
+            parse_mention(mention).map_err(|e| anyhow!("Mention: {}", e))?;
+        }
+
+        if self.field_value("footer").chars().count() > MAX_FOOTER_LEN {
+            return Err(anyhow!("Footer must be at most {} characters", MAX_FOOTER_LEN));
+        }
+        endpoint_active_hours(&self.form_value().to_string()).map_err(|e| anyhow!("Active hours: {}", e))?;
 
         Ok(())
     }
 
     pub fn build_json(&self) -> Result<String> {
+        if let Some(raw) = &self.raw_json {
+            return self.build_raw_json(raw.value());
+        }
         self.validate_and_build()?;
+        let value = upgrade_config_value(&self.endpoint_type, self.form_value()).map_err(|e| anyhow!(e))?;
+        Ok(serde_json::to_string(&value)?)
+    }
 
-        let json_value = match self.endpoint_type {
-            EndpointKind::Discord => {
-                let username = if self.fields[1].value.trim().is_empty() {
-                    None
-                } else {
-                    Some(self.fields[1].value.trim())
-                };
-
-                if let Some(user) = username {
-                    json!({
-                        "webhook_url": self.fields[0].value.trim(),
-                        "username": user
-                    })
-                } else {
-                    json!({
-                        "webhook_url": self.fields[0].value.trim()
-                    })
-                }
-            }
-            EndpointKind::Pushover => {
-                let device = if self.fields[2].value.trim().is_empty() {
-                    None
-                } else {
-                    Some(self.fields[2].value.trim())
-                };
-
-                if let Some(dev) = device {
-                    json!({
-                        "token": self.fields[0].value.trim(),
-                        "user": self.fields[1].value.trim(),
-                        "device": dev
-                    })
-                } else {
-                    json!({
-                        "token": self.fields[0].value.trim(),
-                        "user": self.fields[1].value.trim()
-                    })
-                }
+    /// The config object described by the form, without validation
+    ///
+    /// Required keys are always written; optional ones only when filled in.
+    /// Several webhook URLs are written as a `webhook_urls` list.
+    fn form_value(&self) -> serde_json::Value {
+        let capabilities = self.endpoint_type.capabilities();
+        let required = capabilities.required_fields;
+        let webhook_urls = self.webhook_urls();
+        let mut json_value = json!({});
+        for ((key, _, _), field) in Self::field_specs(&self.endpoint_type).iter().zip(&self.fields) {
+            let value = field.value.trim();
+            if capabilities.webhook_url_field == Some(*key) && webhook_urls.len() > 1 {
+                json_value[WEBHOOK_URLS_KEY] = json!(webhook_urls);
+            } else if required.contains(key) || !value.is_empty() {
+                json_value[*key] = json!(value);
             }
-        };
+        }
+        if !self.include_nsfw_marker {
+            json_value["include_nsfw_marker"] = json!(false);
+        }
+        for (key, value) in &self.extra_keys {
+            json_value[key.as_str()] = value.clone();
+        }
 
-        Ok(serde_json::to_string(&json_value)?)
+        json_value
     }
 
     pub fn preview_json(&self) -> String {
@@ -362,6 +690,115 @@ impl ConfigBuilder {
         }
     }
 
+    /// Cycle the preview panel through JSON, message and (when editing) diff preview
+    pub fn toggle_preview(&mut self) {
+        self.preview_mode = match self.preview_mode {
+            PreviewMode::Json => PreviewMode::Message,
+            PreviewMode::Message if self.original.is_some() => PreviewMode::Diff,
+            PreviewMode::Message | PreviewMode::Diff => PreviewMode::Json,
+        };
+    }
+
+    /// Changes the form makes to the stored config, or `None` for a new endpoint
+    ///
+    /// Compares what would be saved, before validation, so the diff stays
+    /// visible while a field is half-typed.
+    pub fn diff(&self) -> Option<Vec<(String, FieldChange)>> {
+        let original = self.original.as_ref()?;
+        let value = self.form_value();
+        let value = upgrade_config_value(&self.endpoint_type, value.clone()).unwrap_or(value);
+        Some(config_diff(original, &value))
+    }
+
+    /// The diff as coloured lines: added in green, removed in red, changed in yellow
+    fn diff_lines(&self) -> Vec<Line<'static>> {
+        let changes = self.diff().unwrap_or_default();
+        if changes.is_empty() {
+            return vec![Line::from(Span::styled("No changes", Style::default().fg(Color::DarkGray)))];
+        }
+        changes
+            .into_iter()
+            .map(|(key, change)| match change {
+                FieldChange::Added(new) => {
+                    Line::from(Span::styled(format!("+ {}: {}", key, new), Style::default().fg(Color::Green)))
+                }
+                FieldChange::Removed(old) => {
+                    Line::from(Span::styled(format!("- {}: {}", key, old), Style::default().fg(Color::Red)))
+                }
+                FieldChange::Changed { old, new } => {
+                    Line::from(Span::styled(format!("~ {}: {} → {}", key, old, new), Style::default().fg(Color::Yellow)))
+                }
+            })
+            .collect()
+    }
+
+    /// Render a sample notification as it would appear for this endpoint
+    ///
+    /// Uses the same message rendering as the notifiers, filled in with the
+    /// values currently entered in the form.
+    pub fn preview_message(&self) -> String {
+        let mut message = Message::sample();
+        let footer = self.field_value("footer");
+        if !footer.is_empty() {
+            message.body = append_footer(&message.body, Some(footer), self.endpoint_type.max_message_len(), "preview").into_owned();
+        }
+
+        match self.endpoint_type {
+            EndpointKind::Discord => {
+                let username = self.field_value("username");
+                let username = if username.is_empty() {
+                    DEFAULT_USERNAME
+                } else {
+                    username
+                };
+                format!(
+                    "{}\n  {}\n  {}\n  {}",
+                    username, message.title, message.body, message.url
+                )
+            }
+            EndpointKind::Pushover => {
+                let device = self.field_value("device");
+                let device = if device.is_empty() { "all devices" } else { device };
+                format!(
+                    "{}\n{}\n{}\n(to {})",
+                    message.title, message.body, message.url, device
+                )
+            }
+            EndpointKind::Mattermost => {
+                let username = self.field_value("username");
+                let username = if username.is_empty() { "webhook default" } else { username };
+                let channel = self.field_value("channel");
+                let channel = if channel.is_empty() { "webhook default" } else { channel };
+                format!(
+                    "{} in {}\n  **{}**\n  {}\n  {}",
+                    username, channel, message.title, message.body, message.url
+                )
+            }
+            EndpointKind::Teams => {
+                let card = self.field_value("card");
+                let card = if card.is_empty() { "adaptive" } else { card };
+                format!(
+                    "[{} card]\n  {}\n  {}\n  [Open post] {}",
+                    card, message.title, message.body, message.url
+                )
+            }
+            EndpointKind::File => {
+                let format = self.file_format().unwrap_or_default();
+                let line = file::post_line(format, SAMPLE_SUBREDDIT, SAMPLE_TITLE, SAMPLE_URL, false, Utc::now());
+                format!("Appended to {}:\n{}", self.field_value("path"), line.trim_end())
+            }
+        }
+    }
+
+    /// The format entered for a file endpoint (empty = JSON)
+    fn file_format(&self) -> Result<FileFormat> {
+        let format = self.field_value("format");
+        if format.is_empty() {
+            return Ok(FileFormat::default());
+        }
+        serde_json::from_value(json!(format)).map_err(|_| anyhow!("Format must be 'json' or 'text'"))
+    }
+
     pub fn get_note(&self) -> Option<&str> {
         if self.note.is_empty() {
             None
@@ -396,28 +833,18 @@ impl ConfigBuilder {
                     .style(Style::default().fg(Color::Cyan)),
             );
 
-        let items = vec![
-            ListItem::new(if self.endpoint_type == EndpointKind::Discord {
-                "> Discord"
-            } else {
-                "  Discord"
-            })
-            .style(if self.endpoint_type == EndpointKind::Discord {
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
-            }),
-            ListItem::new(if self.endpoint_type == EndpointKind::Pushover {
-                "> Pushover"
-            } else {
-                "  Pushover"
+        let items: Vec<ListItem> = EndpointKind::all()
+            .iter()
+            .map(|kind| {
+                let name = kind.capabilities().display_name;
+                if *kind == self.endpoint_type {
+                    ListItem::new(format!("> {}", name))
+                        .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+                } else {
+                    ListItem::new(format!("  {}", name))
+                }
             })
-            .style(if self.endpoint_type == EndpointKind::Pushover {
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
-            }),
-        ];
+            .collect();
 
         let list = List::new(items).block(Block::default().borders(Borders::ALL));
 
@@ -455,10 +882,7 @@ impl ConfigBuilder {
         .split(area);
 
         // Title
-        let type_name = match self.endpoint_type {
-            EndpointKind::Discord => "Discord",
-            EndpointKind::Pushover => "Pushover",
-        };
+        let type_name = self.endpoint_type.capabilities().display_name;
         let title = Paragraph::new(format!("Configure {} Endpoint", type_name))
             .alignment(Alignment::Center)
             .block(
@@ -517,7 +941,10 @@ impl ConfigBuilder {
                         Span::raw(cursor),
                         Span::raw("]"),
                     ]),
-                    Line::from(""),
+                    Line::styled(
+                        format!("  {}", field.help_text.as_deref().unwrap_or_default()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
                 ]
             })
             .collect();
@@ -525,9 +952,18 @@ impl ConfigBuilder {
         let form = Paragraph::new(field_lines)
             .block(Block::default().borders(Borders::ALL).title("Endpoint Configuration"));
 
-        // JSON Preview
-        let preview = Paragraph::new(self.preview_json())
-            .block(Block::default().borders(Borders::ALL).title("JSON Preview"))
+        // JSON or message preview; in raw mode, whether the JSON would be accepted
+        let (preview_text, preview_title): (Text, _) = match (&self.raw_json, self.preview_mode) {
+            (Some(_), _) => match self.build_json() {
+                Ok(_) => ("Valid config".into(), "Raw JSON Check"),
+                Err(e) => (format!("Validation error: {}", e).into(), "Raw JSON Check"),
+            },
+            (None, PreviewMode::Json) => (self.preview_json().into(), "JSON Preview"),
+            (None, PreviewMode::Message) => (self.preview_message().into(), "Message Preview"),
+            (None, PreviewMode::Diff) => (self.diff_lines().into(), "Changes"),
+        };
+        let preview = Paragraph::new(preview_text)
+            .block(Block::default().borders(Borders::ALL).title(preview_title))
             .style(Style::default().fg(Color::Green));
 
         // Webhook validation status
@@ -544,13 +980,20 @@ impl ConfigBuilder {
         }
 
         // Help text
-        let help = Paragraph::new(Line::from(vec![
-            "[Tab] Next  ".into(),
-            "[Shift+Tab] Prev  ".into(),
-            "[Ctrl+T] Test  ".into(),
-            "[Enter] Save  ".into(),
-            "[Esc] Cancel".into(),
-        ]))
+        let help_spans = if self.raw_json.is_some() {
+            vec!["[Ctrl+R] Form  ".into(), "[Ctrl+S] Save  ".into(), "[Esc] Cancel".into()]
+        } else {
+            vec![
+                "[Tab] Next  ".into(),
+                "[Shift+Tab] Prev  ".into(),
+                "[Ctrl+T] Test  ".into(),
+                "[Ctrl+P] Preview  ".into(),
+                "[Ctrl+R] Raw JSON  ".into(),
+                "[Enter] Save  ".into(),
+                "[Esc] Cancel".into(),
+            ]
+        };
+        let help = Paragraph::new(Line::from(help_spans))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
 
@@ -563,7 +1006,10 @@ impl ConfigBuilder {
         frame.render_widget(Clear, area);
         frame.render_widget(title, chunks[0]);
         frame.render_widget(note_widget, chunks[1]);
-        frame.render_widget(form, chunks[2]);
+        match &self.raw_json {
+            Some(raw) => raw.render(frame, chunks[2]),
+            None => frame.render_widget(form, chunks[2]),
+        }
         frame.render_widget(preview, chunks[3]);
         frame.render_widget(help, help_chunk);
     }
@@ -590,3 +1036,355 @@ pub enum ConfigAction {
     Cancel,
     TestWebhook,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_preview_message_discord() {
+        let mut builder = ConfigBuilder::new();
+        builder.fields[0].value = "https://discord.com/api/webhooks/1/abc".to_string();
+
+        let preview = builder.preview_message();
+        assert!(preview.starts_with("Reddit Notifier\n"));
+        assert!(preview.contains("New Reddit Post Alert (rust)"));
+        assert!(preview.contains("Announcing Rust 1.90.0 & what's next"));
+
+        builder.fields[1].value = "Alerts".to_string();
+        assert!(builder.preview_message().starts_with("Alerts\n"));
+    }
+
+    #[test]
+    fn test_preview_message_pushover() {
+        let mut builder = ConfigBuilder::new();
+        builder.set_type(EndpointKind::Pushover);
+
+        let preview = builder.preview_message();
+        assert!(preview.starts_with("New Reddit Post Alert (rust)\n"));
+        assert!(preview.ends_with("(to all devices)"));
+
+        builder.fields[2].value = "phone".to_string();
+        assert!(builder.preview_message().ends_with("(to phone)"));
+    }
+
+    #[test]
+    fn test_every_field_has_help_text() {
+        let mut builder = ConfigBuilder::new();
+        for kind in EndpointKind::all() {
+            builder.set_type(kind.clone());
+            for field in &builder.fields {
+                let help = field.help_text.as_deref().unwrap_or_default();
+                assert!(!help.trim().is_empty(), "{} field '{}' has no help text", kind.as_str(), field.label);
+            }
+            // Help keys match the form's own config keys, so none is left unused
+            for (key, _) in kind.capabilities().field_help {
+                assert!(ConfigBuilder::field_specs(kind).iter().any(|(k, _, _)| k == key), "{}", key);
+            }
+        }
+
+        builder.set_type(EndpointKind::Teams);
+        assert!(builder.fields[0].help_text.as_deref().unwrap().contains("Workflows"));
+        builder.set_type(EndpointKind::Discord);
+        assert!(builder.fields[0].help_text.as_deref().unwrap().contains("Integrations → Webhooks"));
+    }
+
+    #[test]
+    fn test_teams_card_field() {
+        let mut builder = ConfigBuilder::new();
+        builder.set_type(EndpointKind::Teams);
+        builder.type_selection_mode = false;
+        builder.fields[0].value = "https://contoso.webhook.office.com/webhookb2/abc".to_string();
+        let json: serde_json::Value = serde_json::from_str(&builder.build_json().unwrap()).unwrap();
+        assert!(json.get("card").is_none());
+        assert!(builder.preview_message().starts_with("[adaptive card]\n"));
+
+        builder.fields[1].value = "hero".to_string();
+        assert!(builder.build_json().unwrap_err().to_string().contains("message_card"));
+        builder.fields[1].value = "message_card".to_string();
+        let json: serde_json::Value = serde_json::from_str(&builder.build_json().unwrap()).unwrap();
+        assert_eq!(json["card"], "message_card");
+        assert!(validate_endpoint_config(&EndpointKind::Teams, &json.to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_file_fields() {
+        let mut builder = ConfigBuilder::new();
+        builder.set_type(EndpointKind::File);
+        builder.type_selection_mode = false;
+        builder.fields[0].value = "posts.jsonl".to_string();
+        assert!(builder.build_json().unwrap_err().to_string().contains("absolute"));
+
+        builder.fields[0].value = "/var/log/posts.jsonl".to_string();
+        let json: serde_json::Value = serde_json::from_str(&builder.build_json().unwrap()).unwrap();
+        assert!(json.get("format").is_none());
+        let preview = builder.preview_message();
+        assert!(preview.starts_with("Appended to /var/log/posts.jsonl:\n{"), "{}", preview);
+
+        builder.fields[1].value = "csv".to_string();
+        assert!(builder.build_json().unwrap_err().to_string().contains("'json' or 'text'"));
+        builder.fields[1].value = "text".to_string();
+        let json: serde_json::Value = serde_json::from_str(&builder.build_json().unwrap()).unwrap();
+        assert_eq!(json["format"], "text");
+        assert!(validate_endpoint_config(&EndpointKind::File, &json.to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_discord_mention_field() {
+        let mut builder = ConfigBuilder::new();
+        builder.type_selection_mode = false;
+        builder.fields[0].value = "https://discord.com/api/webhooks/1/abc".to_string();
+        builder.fields[2].value = "<@123>".to_string();
+        let json: serde_json::Value = serde_json::from_str(&builder.build_json().unwrap()).unwrap();
+        assert_eq!(json["mention"], "<@123>");
+        assert!(json.get("username").is_none());
+
+        builder.fields[2].value = "@everyone".to_string();
+        assert!(builder.build_json().unwrap_err().to_string().contains("@everyone"));
+    }
+
+    #[test]
+    fn test_footer_field_round_trip_and_preview() {
+        let mut builder = ConfigBuilder::new();
+        builder.type_selection_mode = false;
+        builder.set_type(EndpointKind::Pushover);
+        builder.fields[0].value = "t".to_string();
+        builder.fields[1].value = "u".to_string();
+        builder.fields[3].value = "via reddit-notifier".to_string();
+        let json = builder.build_json().unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&json).unwrap()["footer"], "via reddit-notifier");
+        assert!(builder.preview_message().contains("what's next\n\nvia reddit-notifier"));
+
+        let rebuilt = ConfigBuilder::from_existing(EndpointKind::Pushover, &json, None).unwrap();
+        assert_eq!(rebuilt.fields[3].value, "via reddit-notifier");
+
+        builder.fields[3].value = "x".repeat(MAX_FOOTER_LEN + 1);
+        assert!(builder.build_json().unwrap_err().to_string().contains("Footer"));
+    }
+
+    #[test]
+    fn test_active_hours_fields_round_trip() {
+        let mut builder = ConfigBuilder::new();
+        builder.type_selection_mode = false;
+        builder.set_type(EndpointKind::Discord);
+        builder.fields[0].value = "https://discord.com/api/webhooks/1/abc".to_string();
+        builder.fields[4].value = "09:00-17:00".to_string();
+        builder.fields[5].value = "Europe/Berlin".to_string();
+        let json = builder.build_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!((&value["active_hours"], &value["timezone"]), (&json!("09:00-17:00"), &json!("Europe/Berlin")));
+
+        let rebuilt = ConfigBuilder::from_existing(EndpointKind::Discord, &json, None).unwrap();
+        assert_eq!((rebuilt.fields[4].value.as_str(), rebuilt.fields[5].value.as_str()), ("09:00-17:00", "Europe/Berlin"));
+
+        builder.fields[5].value = "Berlin".to_string();
+        assert!(builder.build_json().unwrap_err().to_string().contains("unknown timezone"));
+        builder.fields[5].value = "UTC".to_string();
+        builder.fields[4].value = String::new();
+        assert!(builder.build_json().unwrap_err().to_string().contains("without active_hours"));
+    }
+
+    #[test]
+    fn test_disabled_nsfw_marker_survives_edit() {
+        let json = r#"{"webhook_url":"https://discord.com/api/webhooks/1/abc","include_nsfw_marker":false}"#;
+        let builder = ConfigBuilder::from_existing(EndpointKind::Discord, json, None).unwrap();
+        let rebuilt: serde_json::Value = serde_json::from_str(&builder.build_json().unwrap()).unwrap();
+        assert_eq!(rebuilt["include_nsfw_marker"], false);
+
+        let builder = ConfigBuilder::from_existing(EndpointKind::Pushover, r#"{"token":"t","user":"u"}"#, None).unwrap();
+        assert_eq!(builder.build_json().unwrap(), r#"{"token":"t","user":"u","version":2}"#);
+    }
+
+    #[test]
+    fn test_from_existing_upgrades_old_config_versions() {
+        let v1 = r#"{"webhook_url":"https://discord.com/api/webhooks/1/abc","username":"bot"}"#;
+        let builder = ConfigBuilder::from_existing(EndpointKind::Discord, v1, None).unwrap();
+        assert_eq!(builder.fields[0].value, "https://discord.com/api/webhooks/1/abc");
+        // The version isn't carried along as an unknown key
+        assert!(builder.extra_keys.is_empty());
+        let saved: serde_json::Value = serde_json::from_str(&builder.build_json().unwrap()).unwrap();
+        assert_eq!(
+            saved,
+            json!({"webhook_urls": ["https://discord.com/api/webhooks/1/abc"], "username": "bot", "version": 2})
+        );
+
+        let future = r#"{"webhook_urls":["https://discord.com/api/webhooks/1/abc"],"version":9}"#;
+        let err = ConfigBuilder::from_existing(EndpointKind::Discord, future, None).unwrap_err();
+        assert!(err.to_string().contains("newer than this build"));
+    }
+
+    #[test]
+    fn test_for_repair_prefills_recoverable_fields() {
+        let builder = ConfigBuilder::for_repair(
+            EndpointKind::Pushover,
+            r#"{"token":"abc"}"#,
+            Some("phone".to_string()),
+            "user is empty",
+        );
+        assert!(!builder.type_selection_mode);
+        assert_eq!(builder.fields[0].value, "abc");
+        assert!(builder.fields[1].value.is_empty());
+        assert_eq!(builder.note, "phone");
+        assert_eq!(
+            builder.webhook_validation,
+            WebhookValidationState::Invalid("Needs repair: user is empty".to_string())
+        );
+
+        let from_garbage = ConfigBuilder::for_repair(EndpointKind::Discord, "not json", None, "bad");
+        assert!(from_garbage.fields.iter().all(|f| f.value.is_empty()));
+    }
+
+    #[test]
+    fn test_raw_json_edits_round_trip() {
+        let json = r#"{"webhook_url":"https://discord.com/api/webhooks/1/abc","username":"bot"}"#;
+        let mut builder = ConfigBuilder::from_existing(EndpointKind::Discord, json, None).unwrap();
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+
+        builder.handle_input(ctrl_r).unwrap();
+        let raw = builder.raw_json.as_ref().unwrap().value().to_string();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&raw).unwrap()["username"], "bot");
+
+        // Replace the closing brace with a key the form has no field for
+        builder.raw_json = Some(TextArea::new().with_value(format!("{}, \"avatar_url\": \"https://example.com/a.png\"}}", raw.trim_end().trim_end_matches('}'))));
+        let saved: serde_json::Value = serde_json::from_str(&builder.build_json().unwrap()).unwrap();
+        assert_eq!(saved["avatar_url"], "https://example.com/a.png");
+
+        // Back in the form, edits keep the extra key
+        builder.handle_input(ctrl_r).unwrap();
+        assert!(builder.raw_json.is_none());
+        builder.fields[1].value = "alerts".to_string();
+        let json = builder.build_json().unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!((saved["username"].as_str(), saved["avatar_url"].as_str()), (Some("alerts"), Some("https://example.com/a.png")));
+
+        // ...and so does reopening the saved endpoint
+        let reopened = ConfigBuilder::from_existing(EndpointKind::Discord, &json, None).unwrap();
+        assert_eq!(reopened.build_json().unwrap(), json);
+    }
+
+    #[test]
+    fn test_invalid_raw_json_rejected() {
+        let mut builder = ConfigBuilder::from_existing(EndpointKind::Pushover, r#"{"token":"t","user":"u"}"#, None).unwrap();
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        let save = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        builder.handle_input(ctrl_r).unwrap();
+
+        // Enter is a newline in the editor, not a save
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(builder.handle_input(enter).unwrap().is_none());
+        assert!(builder.raw_json.as_ref().unwrap().value().ends_with("}\n"));
+
+        // Unparseable: saving stays in the editor and the form can't be restored from it
+        builder.raw_json = Some(TextArea::new().with_value(r#"{"token": "t", "user": "#));
+        assert!(builder.handle_input(save).unwrap().is_none());
+        assert!(builder.webhook_validation.message().unwrap().starts_with("Invalid JSON"));
+        builder.handle_input(ctrl_r).unwrap();
+        assert!(builder.raw_json.is_some());
+
+        // Parseable but missing a required key
+        builder.raw_json = Some(TextArea::new().with_value(r#"{"token": "t"}"#));
+        assert!(builder.build_json().unwrap_err().to_string().contains("user"));
+
+        builder.raw_json = Some(TextArea::new().with_value(r#"{"token": "t", "user": "u", "sound": "magic"}"#));
+        assert!(matches!(builder.handle_input(save).unwrap(), Some(ConfigAction::Save)));
+    }
+
+    #[test]
+    fn test_required_fields_follow_kind_capabilities() {
+        let mut builder = ConfigBuilder::new();
+        for kind in EndpointKind::all() {
+            builder.set_type(kind.clone());
+            let required: Vec<bool> = builder.fields.iter().map(|f| f.required).collect();
+            let expected: Vec<bool> = ConfigBuilder::field_specs(kind)
+                .iter()
+                .map(|(key, _, _)| kind.capabilities().required_fields.contains(key))
+                .collect();
+            assert_eq!(required, expected);
+            assert!(builder.fields.iter().all(|f| f.required != f.label.ends_with("(optional)")));
+        }
+    }
+
+    #[test]
+    fn test_multiple_webhook_urls_round_trip() {
+        let mut builder = ConfigBuilder::new();
+        builder.type_selection_mode = false;
+        builder.fields[0].value = "https://discord.com/api/webhooks/1/a, https://discord.com/api/webhooks/2/b".to_string();
+        let json = builder.build_json().unwrap();
+        assert_eq!(
+            json,
+            r#"{"version":2,"webhook_urls":["https://discord.com/api/webhooks/1/a","https://discord.com/api/webhooks/2/b"]}"#
+        );
+        assert!(validate_endpoint_config(&EndpointKind::Discord, &json).is_ok());
+
+        // Reopening shows the list in the one field, and doesn't duplicate it as an extra key
+        let reopened = ConfigBuilder::from_existing(EndpointKind::Discord, &json, None).unwrap();
+        assert_eq!(reopened.fields[0].value, "https://discord.com/api/webhooks/1/a, https://discord.com/api/webhooks/2/b");
+        assert!(reopened.extra_keys.is_empty());
+        assert_eq!(reopened.build_json().unwrap(), json);
+
+        // One URL is stored as a one-item list, the current config shape
+        builder.fields[0].value = "https://discord.com/api/webhooks/1/a".to_string();
+        assert_eq!(builder.build_json().unwrap(), r#"{"version":2,"webhook_urls":["https://discord.com/api/webhooks/1/a"]}"#);
+
+        builder.fields[0].value = "https://discord.com/api/webhooks/1/a http://insecure".to_string();
+        assert!(builder.build_json().unwrap_err().to_string().contains("https://"));
+    }
+
+    #[test]
+    fn test_ctrl_p_toggles_preview_mode() {
+        let mut builder = ConfigBuilder::new();
+        builder.type_selection_mode = false;
+        assert_eq!(builder.preview_mode, PreviewMode::Json);
+
+        let ctrl_p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+        builder.handle_input(ctrl_p).unwrap();
+        assert_eq!(builder.preview_mode, PreviewMode::Message);
+        assert!(builder.fields[0].value.is_empty());
+
+        builder.handle_input(ctrl_p).unwrap();
+        assert_eq!(builder.preview_mode, PreviewMode::Json);
+    }
+
+    #[test]
+    fn test_config_diff_reports_field_changes() {
+        let old = json!({"token": "t", "user": "u", "device": "phone", "version": 2});
+        let new = json!({"token": "t", "user": "u2", "footer": "via reddit-notifier", "version": 2});
+        assert_eq!(
+            config_diff(&old, &new),
+            vec![
+                ("device".to_string(), FieldChange::Removed(json!("phone"))),
+                ("footer".to_string(), FieldChange::Added(json!("via reddit-notifier"))),
+                ("user".to_string(), FieldChange::Changed { old: json!("u"), new: json!("u2") }),
+            ]
+        );
+        assert!(config_diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_editing_diff_against_stored_config() {
+        let stored = r#"{"token":"t","user":"u","device":"phone","extra_headers":{"X-A":"1"}}"#;
+        let mut builder = ConfigBuilder::from_existing(EndpointKind::Pushover, stored, None).unwrap();
+        // Reopening changes nothing, even though the stored config predates versioning
+        assert_eq!(builder.diff(), Some(Vec::new()));
+
+        builder.fields[1].value = "u2".to_string();
+        builder.fields[2].value = String::new();
+        assert_eq!(
+            builder.diff().unwrap(),
+            vec![
+                ("device".to_string(), FieldChange::Removed(json!("phone"))),
+                ("user".to_string(), FieldChange::Changed { old: json!("u"), new: json!("u2") }),
+            ]
+        );
+
+        // Ctrl+P reaches the diff only when editing
+        let ctrl_p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+        builder.handle_input(ctrl_p).unwrap();
+        builder.handle_input(ctrl_p).unwrap();
+        assert_eq!(builder.preview_mode, PreviewMode::Diff);
+        builder.handle_input(ctrl_p).unwrap();
+        assert_eq!(builder.preview_mode, PreviewMode::Json);
+        assert_eq!(ConfigBuilder::new().diff(), None);
+    }
+}
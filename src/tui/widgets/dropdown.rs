@@ -8,6 +8,7 @@ use ratatui::{
 };
 
 use crate::tui::state::Navigable;
+use crate::tui::keys;
 
 /// A dropdown widget with filtering and selection
 ///
@@ -80,7 +81,7 @@ impl Dropdown {
     ///
     /// Returns Some(index) if an option was selected, None otherwise
     pub fn handle_key(&mut self, key: KeyEvent) -> Option<usize> {
-        match key.code {
+        match keys::shortcut_code(&key) {
             KeyCode::Up => {
                 self.previous();
                 None
@@ -133,6 +134,13 @@ impl Dropdown {
         frame.render_widget(filter_widget, chunks[0]);
 
         // Render filtered options
+        self.render_inline(frame, chunks[1]);
+    }
+
+    /// Render just the option list (no filter box) into `area`
+    ///
+    /// For suggestion lists attached to another input.
+    pub fn render_inline(&self, frame: &mut Frame, area: Rect) {
         let filtered = self.filtered_options();
 
         let items: Vec<ListItem> = filtered
@@ -156,7 +164,7 @@ impl Dropdown {
                 .title(format!("{} options", filtered.len())),
         );
 
-        frame.render_widget(list, chunks[1]);
+        frame.render_widget(list, area);
     }
 
     /// Calculate centered rectangle for popup
@@ -6,13 +6,19 @@ pub mod form_field;
 pub mod modal_dialog;
 pub mod paginated_view;
 pub mod selectable_table;
+pub mod spinner;
+pub mod text_area;
 pub mod text_input;
+pub mod typed_confirm;
 
 pub use checkbox_list::CheckboxList;
-pub use config_builder::{ConfigAction, ConfigBuilder};
+pub use config_builder::{ConfigAction, ConfigBuilder, PreviewMode};
 pub use dropdown::Dropdown;
 pub use form_field::{FormField, ValidationState};
 pub use modal_dialog::{DialogType, ModalDialog};
 pub use paginated_view::PaginatedView;
 pub use selectable_table::{ColumnDef, SelectableTable};
+pub use spinner::Spinner;
+pub use text_area::TextArea;
 pub use text_input::TextInput;
+pub use typed_confirm::{TypedConfirm, TypedConfirmAction};
@@ -29,6 +29,9 @@ pub struct ModalDialog {
 
     /// Height as percentage of screen (1-100)
     pub height_percent: u16,
+
+    /// Number of content lines scrolled past (for long content)
+    pub scroll: u16,
 }
 
 /// Types of modal dialogs
@@ -88,6 +91,7 @@ impl ModalDialog {
             dialog_type,
             width_percent: 60,
             height_percent: 20,
+            scroll: 0,
         }
     }
 
@@ -139,6 +143,12 @@ impl ModalDialog {
         self
     }
 
+    /// Scroll the content down by `lines`
+    pub fn with_scroll(mut self, lines: u16) -> Self {
+        self.scroll = lines;
+        self
+    }
+
     /// Add a line to the content
     pub fn add_line(mut self, line: Line<'static>) -> Self {
         self.content.push(line);
@@ -146,7 +156,7 @@ impl ModalDialog {
     }
 
     /// Calculate the centered rectangle for the modal
-    fn centered_rect(&self, area: Rect) -> Rect {
+    pub fn centered_rect(&self, area: Rect) -> Rect {
         let popup_layout = Layout::vertical([
             Constraint::Percentage((100 - self.height_percent) / 2),
             Constraint::Percentage(self.height_percent),
@@ -182,7 +192,8 @@ impl ModalDialog {
 
         let paragraph = Paragraph::new(self.content.clone())
             .block(block)
-            .wrap(Wrap { trim: true });
+            .wrap(Wrap { trim: true })
+            .scroll((self.scroll, 0));
 
         frame.render_widget(paragraph, modal_area);
     }
@@ -199,6 +210,7 @@ mod tests {
         assert_eq!(dialog.dialog_type, DialogType::Info);
         assert_eq!(dialog.width_percent, 60);
         assert_eq!(dialog.height_percent, 20);
+        assert_eq!(dialog.scroll, 0);
     }
 
     #[test]
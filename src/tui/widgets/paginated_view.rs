@@ -1,4 +1,5 @@
 use crossterm::event::{KeyCode, KeyEvent};
+use crate::tui::keys;
 
 /// A pagination wrapper for managing large lists of items
 ///
@@ -97,7 +98,7 @@ impl<T: Clone> PaginatedView<T> {
     ///
     /// Returns true if the key was handled
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
-        match key.code {
+        match keys::shortcut_code(&key) {
             KeyCode::PageDown | KeyCode::Char(']') => {
                 self.next_page();
                 true
@@ -7,6 +7,7 @@ use ratatui::{
 };
 
 use crate::tui::state::Navigable;
+use crate::tui::keys;
 
 /// A selectable table widget with navigation and optional sorting
 ///
@@ -120,7 +121,7 @@ impl<T: Clone> SelectableTable<T> {
     ///
     /// Returns true if the key was handled
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
-        match key.code {
+        match keys::shortcut_code(&key) {
             KeyCode::Up => {
                 self.previous();
                 true
@@ -0,0 +1,66 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// Animation frames, advanced once per event-loop tick
+pub const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// A one-character activity indicator for operations in progress
+///
+/// The spinner has no clock of its own: the owner calls `tick` once per
+/// frame, so it only moves while the UI is actually redrawing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spinner {
+    frame: usize,
+}
+
+impl Spinner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance to the next frame (wraps around)
+    pub fn tick(&mut self) {
+        self.frame = (self.frame + 1) % FRAMES.len();
+    }
+
+    /// The character for the current frame
+    pub fn symbol(&self) -> &'static str {
+        FRAMES[self.frame]
+    }
+
+    /// Render `label` after the spinner, centered in a bordered box
+    ///
+    /// Sized to replace a screen's help bar while it is busy.
+    pub fn render(&self, frame: &mut Frame, area: Rect, label: &str) {
+        let line = Line::from(vec![
+            Span::styled(self.symbol(), Style::default().fg(Color::Yellow)),
+            Span::raw(format!(" {}", label)),
+        ]);
+        let widget = Paragraph::new(line)
+            .alignment(Alignment::Center)
+            .block(super::common::chrome_block(area.height < 3));
+        frame.render_widget(widget, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_advance_and_wrap() {
+        let mut spinner = Spinner::new();
+        assert_eq!(spinner.symbol(), FRAMES[0]);
+        spinner.tick();
+        assert_eq!(spinner.symbol(), FRAMES[1]);
+        for _ in 1..FRAMES.len() {
+            spinner.tick();
+        }
+        assert_eq!(spinner.symbol(), FRAMES[0]);
+    }
+}
@@ -0,0 +1,339 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use crate::tui::keys;
+
+/// A reusable multi-line text input widget
+///
+/// Like `TextInput`, but:
+/// - Enter inserts a newline
+/// - Up/Down move between lines, keeping the column where possible
+/// - Home/End go to the start/end of the current line
+/// - Long lines wrap, and the view scrolls to keep the cursor line visible
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextArea {
+    /// Current input value, lines separated by `\n`
+    pub value: String,
+
+    /// Placeholder text shown when empty
+    pub placeholder: String,
+
+    /// Title shown on the border
+    pub title: String,
+
+    /// Maximum allowed length in characters, newlines included (None = unlimited)
+    pub max_length: Option<usize>,
+
+    /// Cursor position as a byte offset into `value` (always on a char boundary)
+    pub cursor_pos: usize,
+
+    /// Whether this input is currently focused
+    pub is_focused: bool,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextArea {
+    /// Create a new empty TextArea
+    pub fn new() -> Self {
+        Self {
+            value: String::new(),
+            placeholder: String::new(),
+            title: String::new(),
+            max_length: None,
+            cursor_pos: 0,
+            is_focused: false,
+        }
+    }
+
+    /// Set the placeholder text
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Set the border title
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the maximum length
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Set the initial value, with the cursor at the end
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.value = value.into();
+        self.cursor_pos = self.value.len();
+        self
+    }
+
+    /// Set focus state
+    pub fn set_focused(&mut self, focused: bool) {
+        self.is_focused = focused;
+    }
+
+    /// Cursor position as (line, column), both 0-indexed and counted in characters
+    pub fn cursor_line_col(&self) -> (usize, usize) {
+        let before = &self.value[..self.cursor_pos];
+        let line = before.matches('\n').count();
+        let col = before[self.line_start(self.cursor_pos)..].chars().count();
+        (line, col)
+    }
+
+    /// Byte offset of the start of the line containing `pos`
+    fn line_start(&self, pos: usize) -> usize {
+        self.value[..pos].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    /// Byte offset of the end (the `\n` or end of text) of the line containing `pos`
+    fn line_end(&self, pos: usize) -> usize {
+        self.value[pos..].find('\n').map_or(self.value.len(), |i| pos + i)
+    }
+
+    /// Byte offset `col` characters into the line starting at `start`, clamped to its end
+    fn offset_in_line(&self, start: usize, col: usize) -> usize {
+        let end = self.line_end(start);
+        self.value[start..end]
+            .char_indices()
+            .nth(col)
+            .map_or(end, |(i, _)| start + i)
+    }
+
+    fn prev_char_len(&self) -> usize {
+        self.value[..self.cursor_pos].chars().next_back().map_or(0, char::len_utf8)
+    }
+
+    fn next_char_len(&self) -> usize {
+        self.value[self.cursor_pos..].chars().next().map_or(0, char::len_utf8)
+    }
+
+    fn insert(&mut self, c: char) -> bool {
+        if self.max_length.is_some_and(|max| self.value.chars().count() >= max) {
+            return false;
+        }
+        self.value.insert(self.cursor_pos, c);
+        self.cursor_pos += c.len_utf8();
+        true
+    }
+
+    /// Handle keyboard input
+    ///
+    /// Returns true if the value or cursor changed
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match keys::shortcut_code(&key) {
+            KeyCode::Char(c) => self.insert(c),
+            KeyCode::Enter => self.insert('\n'),
+            KeyCode::Backspace if self.cursor_pos > 0 => {
+                self.cursor_pos -= self.prev_char_len();
+                self.value.remove(self.cursor_pos);
+                true
+            }
+            KeyCode::Delete if self.cursor_pos < self.value.len() => {
+                self.value.remove(self.cursor_pos);
+                true
+            }
+            KeyCode::Left if self.cursor_pos > 0 => {
+                self.cursor_pos -= self.prev_char_len();
+                true
+            }
+            KeyCode::Right if self.cursor_pos < self.value.len() => {
+                self.cursor_pos += self.next_char_len();
+                true
+            }
+            KeyCode::Up => {
+                let start = self.line_start(self.cursor_pos);
+                if start == 0 {
+                    return false;
+                }
+                let (_, col) = self.cursor_line_col();
+                self.cursor_pos = self.offset_in_line(self.line_start(start - 1), col);
+                true
+            }
+            KeyCode::Down => {
+                let end = self.line_end(self.cursor_pos);
+                if end == self.value.len() {
+                    return false;
+                }
+                let (_, col) = self.cursor_line_col();
+                self.cursor_pos = self.offset_in_line(end + 1, col);
+                true
+            }
+            KeyCode::Home => {
+                let start = self.line_start(self.cursor_pos);
+                let moved = start != self.cursor_pos;
+                self.cursor_pos = start;
+                moved
+            }
+            KeyCode::End => {
+                let end = self.line_end(self.cursor_pos);
+                let moved = end != self.cursor_pos;
+                self.cursor_pos = end;
+                moved
+            }
+            _ => false,
+        }
+    }
+
+    /// Render the text area widget
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let border_style = if self.is_focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::White)
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title.clone())
+            .style(border_style);
+
+        let lines: Vec<Line> = if self.value.is_empty() && !self.is_focused {
+            vec![Line::from(self.placeholder.clone()).style(Style::default().fg(Color::DarkGray))]
+        } else {
+            let mut display = self.value.clone();
+            if self.is_focused {
+                display.insert(self.cursor_pos, '█');
+            }
+            display.split('\n').map(|line| Line::from(line.to_string())).collect()
+        };
+
+        // Keep the cursor line in view (wrapped lines can push it further down)
+        let visible = area.height.saturating_sub(2) as usize;
+        let (cursor_line, _) = self.cursor_line_col();
+        let scroll = cursor_line.saturating_sub(visible.saturating_sub(1));
+
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((u16::try_from(scroll).unwrap_or(u16::MAX), 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    /// Clear the input value
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor_pos = 0;
+    }
+
+    /// Get the current value
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Check if the input is empty
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_str(area: &mut TextArea, s: &str) {
+        for c in s.chars() {
+            let key = if c == '\n' { KeyCode::Enter } else { KeyCode::Char(c) };
+            area.handle_key(KeyEvent::from(key));
+        }
+    }
+
+    #[test]
+    fn test_enter_inserts_newline() {
+        let mut area = TextArea::new();
+        type_str(&mut area, "ab\ncd");
+        assert_eq!(area.value, "ab\ncd");
+        assert_eq!(area.cursor_line_col(), (1, 2));
+
+        // Splitting a line in the middle
+        area.cursor_pos = 1;
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Enter)));
+        assert_eq!(area.value, "a\nb\ncd");
+        assert_eq!(area.cursor_line_col(), (1, 0));
+    }
+
+    #[test]
+    fn test_delete_joins_lines() {
+        let mut area = TextArea::new().with_value("ab\ncd");
+
+        // Backspace at the start of a line joins it with the one above
+        area.cursor_pos = 3;
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Backspace)));
+        assert_eq!(area.value, "abcd");
+        assert_eq!(area.cursor_pos, 2);
+
+        // Delete at the end of a line pulls up the next one
+        let mut area = TextArea::new().with_value("ab\ncd");
+        area.cursor_pos = 2;
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Delete)));
+        assert_eq!(area.value, "abcd");
+        assert_eq!(area.cursor_pos, 2);
+
+        assert!(!TextArea::new().handle_key(KeyEvent::from(KeyCode::Backspace)));
+    }
+
+    #[test]
+    fn test_left_right_cross_line_boundaries() {
+        let mut area = TextArea::new().with_value("ab\ncd");
+        area.cursor_pos = 3;
+
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Left)));
+        assert_eq!(area.cursor_line_col(), (0, 2));
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Right)));
+        assert_eq!(area.cursor_line_col(), (1, 0));
+    }
+
+    #[test]
+    fn test_up_down_keep_column() {
+        let mut area = TextArea::new().with_value("abcdef\nxy\nlonger line");
+        area.cursor_pos = 5; // "abcde|f"
+
+        // A shorter line clamps the column
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Down)));
+        assert_eq!(area.cursor_line_col(), (1, 2));
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Down)));
+        assert_eq!(area.cursor_line_col(), (2, 2));
+        assert!(!area.handle_key(KeyEvent::from(KeyCode::Down)));
+
+        assert!(area.handle_key(KeyEvent::from(KeyCode::End)));
+        assert_eq!(area.cursor_line_col(), (2, 11));
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(area.cursor_line_col(), (1, 2));
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(area.cursor_line_col(), (0, 2));
+        assert!(!area.handle_key(KeyEvent::from(KeyCode::Up)));
+
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Home)));
+        assert_eq!(area.cursor_pos, 0);
+    }
+
+    #[test]
+    fn test_multibyte_characters() {
+        let mut area = TextArea::new();
+        type_str(&mut area, "é\nñx");
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Up)));
+        assert_eq!(area.cursor_line_col(), (0, 1));
+        assert!(area.handle_key(KeyEvent::from(KeyCode::Backspace)));
+        assert_eq!(area.value, "\nñx");
+    }
+
+    #[test]
+    fn test_max_length_counts_newlines() {
+        let mut area = TextArea::new().with_max_length(3);
+        type_str(&mut area, "a\nbc");
+        assert_eq!(area.value, "a\nb");
+        assert!(!area.handle_key(KeyEvent::from(KeyCode::Enter)));
+    }
+}
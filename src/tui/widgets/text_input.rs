@@ -6,6 +6,7 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use crate::tui::keys;
 
 /// A reusable text input widget with validation and cursor support
 ///
@@ -112,7 +113,7 @@ impl TextInput {
     ///
     /// Returns true if the input was modified
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
-        match key.code {
+        match keys::shortcut_code(&key) {
             KeyCode::Char(c) if self.is_valid_char(c) && self.max_length.is_none_or(|max| self.value.len() < max) => {
                 self.value.insert(self.cursor_pos, c);
                 self.cursor_pos += 1;
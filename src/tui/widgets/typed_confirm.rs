@@ -0,0 +1,174 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    Frame,
+};
+
+use super::{DialogType, ModalDialog, TextInput};
+use crate::tui::keys;
+
+/// Word the user types to confirm unless another one is given
+pub const DEFAULT_CONFIRM_WORD: &str = "CONFIRM";
+
+/// Outcome of a key press in a `TypedConfirm` dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypedConfirmAction {
+    /// The exact confirmation word was entered and submitted
+    Confirmed,
+    /// The dialog was dismissed with Esc
+    Cancelled,
+}
+
+/// A confirmation dialog for destructive actions that requires typing a word
+///
+/// A y/n prompt is easy to answer by accident; here Enter only confirms once
+/// the input matches the confirmation word exactly (case-sensitive).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedConfirm {
+    /// Dialog title
+    pub title: String,
+
+    /// What will happen if the user confirms
+    pub prompt: String,
+
+    /// Text the user must type
+    pub word: String,
+
+    /// What the user has typed so far
+    pub input: TextInput,
+
+    /// Set when Enter was pressed with non-matching text
+    pub mismatch: bool,
+}
+
+impl TypedConfirm {
+    /// Create a dialog that asks the user to type `CONFIRM`
+    pub fn new(title: impl Into<String>, prompt: impl Into<String>) -> Self {
+        let mut input = TextInput::new().with_max_length(32);
+        input.set_focused(true);
+        Self {
+            title: title.into(),
+            prompt: prompt.into(),
+            word: DEFAULT_CONFIRM_WORD.to_string(),
+            input,
+            mismatch: false,
+        }
+    }
+
+    /// Require a different confirmation word
+    pub fn with_word(mut self, word: impl Into<String>) -> Self {
+        self.word = word.into();
+        self
+    }
+
+    /// True when the typed text matches the confirmation word exactly
+    pub fn is_match(&self) -> bool {
+        self.input.value() == self.word
+    }
+
+    /// Handle a key press
+    ///
+    /// Returns `Some` once the dialog is finished; `None` while it stays open.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<TypedConfirmAction> {
+        match keys::shortcut_code(&key) {
+            KeyCode::Esc => Some(TypedConfirmAction::Cancelled),
+            KeyCode::Enter if self.is_match() => Some(TypedConfirmAction::Confirmed),
+            KeyCode::Enter => {
+                self.mismatch = true;
+                None
+            }
+            _ => {
+                if self.input.handle_key(key) {
+                    self.mismatch = false;
+                }
+                None
+            }
+        }
+    }
+
+    /// Render the dialog with the input box at its bottom
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let highlight = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        let mut content = vec![
+            Line::from(""),
+            Line::from(self.prompt.clone()).alignment(Alignment::Center),
+            Line::from(""),
+            Line::from(vec![
+                Span::raw("Type "),
+                Span::styled(self.word.clone(), highlight),
+                Span::raw(" and press Enter to proceed, Esc to cancel"),
+            ])
+            .alignment(Alignment::Center),
+        ];
+        if self.mismatch {
+            content.push(
+                Line::from(format!("Text does not match {}", self.word))
+                    .alignment(Alignment::Center)
+                    .style(Style::default().fg(Color::Red)),
+            );
+        }
+
+        let dialog = ModalDialog::new(DialogType::Error, self.title.clone(), "")
+            .with_content(content)
+            .with_width_percent(60)
+            .with_height_percent(40);
+        dialog.render(frame, area);
+
+        // Input box inside the dialog, above the bottom border
+        let modal = dialog.centered_rect(area);
+        if modal.height >= 5 && modal.width > 4 {
+            let input_area = Rect {
+                x: modal.x + 2,
+                y: modal.y + modal.height - 4,
+                width: modal.width - 4,
+                height: 3,
+            };
+            self.input.render(frame, input_area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_text(confirm: &mut TypedConfirm, text: &str) {
+        for c in text.chars() {
+            assert_eq!(confirm.handle_key(key(KeyCode::Char(c))), None);
+        }
+    }
+
+    #[test]
+    fn test_confirms_only_on_exact_word() {
+        let mut confirm = TypedConfirm::new("Truncate", "Delete old posts?");
+        assert_eq!(confirm.handle_key(key(KeyCode::Enter)), None);
+
+        type_text(&mut confirm, "confirm");
+        assert_eq!(confirm.handle_key(key(KeyCode::Enter)), None);
+        assert!(confirm.mismatch);
+
+        confirm.input.clear();
+        type_text(&mut confirm, "CONFIRMED");
+        assert_eq!(confirm.handle_key(key(KeyCode::Enter)), None);
+
+        confirm.handle_key(key(KeyCode::Backspace));
+        confirm.handle_key(key(KeyCode::Backspace));
+        assert!(!confirm.mismatch);
+        assert_eq!(confirm.handle_key(key(KeyCode::Enter)), Some(TypedConfirmAction::Confirmed));
+    }
+
+    #[test]
+    fn test_custom_word_and_escape() {
+        let mut confirm = TypedConfirm::new("Delete", "Delete everything?").with_word("DELETE");
+        type_text(&mut confirm, "CONFIRM");
+        assert_eq!(confirm.handle_key(key(KeyCode::Enter)), None);
+        assert_eq!(confirm.handle_key(key(KeyCode::Esc)), Some(TypedConfirmAction::Cancelled));
+    }
+}
@@ -0,0 +1,52 @@
+//! Runs the `reddit-notifier` binary the way CI or a user would
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// A scratch directory for one test, without a `.env` for dotenv to pick up
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("reddit-notifier-cli-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_import_check_works_without_database_url() {
+    let dir = scratch_dir("import-check");
+    std::fs::write(
+        dir.join("config.json"),
+        r#"{
+            "endpoints": [{"name": "alerts", "kind": "discord", "config": {"webhook_url": "https://discord.com/api/webhooks/1/abc"}}],
+            "subscriptions": [{"subreddit": "rust", "endpoints": ["alerts"]}]
+        }"#,
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_reddit-notifier"))
+        .args(["import", "config.json", "--check"])
+        .current_dir(&dir)
+        .env_remove("DATABASE_URL")
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("config.json is valid: 1 endpoint(s), 1 subscription(s)"));
+}
+
+#[test]
+fn test_import_without_check_still_needs_database_url() {
+    let dir = scratch_dir("import-apply");
+    std::fs::write(dir.join("config.json"), r#"{"endpoints": [], "subscriptions": []}"#).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_reddit-notifier"))
+        .args(["import", "config.json"])
+        .current_dir(&dir)
+        .env_remove("DATABASE_URL")
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("DATABASE_URL is required"));
+}